@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
-use crate::empty::RafxApiEmpty;
+use crate::empty::{RafxApiDefEmpty, RafxApiEmpty};
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::{RafxApiDefGles3, RafxApiGles3};
 #[cfg(feature = "rafx-metal")]
 use crate::metal::{RafxApiDefMetal, RafxApiMetal};
 #[cfg(feature = "rafx-vulkan")]
@@ -28,9 +34,15 @@ pub enum RafxApi {
     Metal(RafxApiMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxApiEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxApiGles3),
 }
 
 impl RafxApi {
@@ -50,7 +62,12 @@ impl RafxApi {
             return RafxApi::new_vulkan(_window, _api_def, &Default::default());
         }
 
-        return Err("Rafx was compiled with no backend feature flag. Use feature rafx-metal or rafx-vulkan.")?;
+        #[cfg(feature = "rafx-gles3")]
+        {
+            return RafxApi::new_gles3(_window, _api_def, &Default::default());
+        }
+
+        return Err("Rafx was compiled with no backend feature flag. Use feature rafx-metal, rafx-vulkan or rafx-gles3.")?;
     }
 
     /// Initialize a device using vulkan
@@ -60,21 +77,156 @@ impl RafxApi {
         api_def: &RafxApiDef,
         vk_api_def: &RafxApiDefVulkan,
     ) -> RafxResult<Self> {
-        Ok(RafxApi::Vk(RafxApiVulkan::new(
-            window, api_def, vk_api_def,
-        )?))
+        let api = RafxApi::Vk(RafxApiVulkan::new(window, api_def, vk_api_def)?);
+        Ok(api.with_api_def(api_def))
     }
 
-    /// Initialize a device using vulkan
+    /// Initialize a device using metal
     #[cfg(feature = "rafx-metal")]
     pub fn new_metal(
         window: &dyn HasRawWindowHandle,
         api_def: &RafxApiDef,
         vk_api_def: &RafxApiDefMetal,
     ) -> RafxResult<Self> {
-        Ok(RafxApi::Metal(RafxApiMetal::new(
-            window, api_def, vk_api_def,
-        )?))
+        let api = RafxApi::Metal(RafxApiMetal::new(window, api_def, vk_api_def)?);
+        Ok(api.with_api_def(api_def))
+    }
+
+    /// Initialize a headless device that does not use a GPU. Resources are backed by host memory
+    /// and submitted work completes immediately. This is useful for tests and tools that need a
+    /// device but will never present to a window.
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn new_empty(
+        api_def: &RafxApiDef,
+        empty_api_def: &RafxApiDefEmpty,
+    ) -> RafxResult<Self> {
+        let api = RafxApi::Empty(RafxApiEmpty::new(api_def, empty_api_def)?);
+        Ok(api.with_api_def(api_def))
+    }
+
+    /// Initialize a device using OpenGL ES 3 through EGL. The window is used to select the EGL
+    /// display, swapchains must be created for the same window.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn new_gles3(
+        window: &dyn HasRawWindowHandle,
+        api_def: &RafxApiDef,
+        gles3_api_def: &RafxApiDefGles3,
+    ) -> RafxResult<Self> {
+        let api = RafxApi::Gles3(RafxApiGles3::new(window, api_def, gles3_api_def)?);
+        Ok(api.with_api_def(api_def))
+    }
+
+    /// Initialize an OpenGL ES 3 device that is not associated with a window. Swapchains cannot be
+    /// created, but everything else works the same as a windowed device. With mesa's software
+    /// rasterizer this runs without a GPU or display server, which makes it useful for CI.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn new_gles3_headless(
+        api_def: &RafxApiDef,
+        gles3_api_def: &RafxApiDefGles3,
+    ) -> RafxResult<Self> {
+        let api = RafxApi::Gles3(RafxApiGles3::new_headless(api_def, gles3_api_def)?);
+        Ok(api.with_api_def(api_def))
+    }
+
+    // Applies the parts of the api def that are handled the same way for all backends
+    fn with_api_def(
+        self,
+        _api_def: &RafxApiDef,
+    ) -> Self {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context()
+            .resource_state_tracker()
+            .set_validation_mode(_api_def.resource_state_validation_mode);
+        #[cfg(feature = "capture")]
+        self.device_context()
+            .capture_recorder()
+            .set_enabled(_api_def.enable_capture);
+        self
+    }
+
+    /// List the adapters (GPUs and software rasterizers) available to the "default" backend for the
+    /// platform. Pass an adapter's index as `RafxApiDef::adapter_index` when creating the API to use
+    /// that adapter. The order is stable as long as the hardware/drivers on the system do not
+    /// change.
+    ///
+    /// This does not create a device, so it can be called before deciding which adapter to use.
+    #[allow(unreachable_code)]
+    pub fn enumerate_adapters(
+        _window: &dyn HasRawWindowHandle
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        #[cfg(feature = "rafx-metal")]
+        {
+            return RafxApi::enumerate_adapters_metal(_window, &Default::default());
+        }
+
+        #[cfg(feature = "rafx-vulkan")]
+        {
+            return RafxApi::enumerate_adapters_vulkan(_window, &Default::default());
+        }
+
+        #[cfg(feature = "rafx-gles3")]
+        {
+            return RafxApi::enumerate_adapters_gles3(_window, &Default::default());
+        }
+
+        return Err("Rafx was compiled with no backend feature flag. Use feature rafx-metal, rafx-vulkan or rafx-gles3.")?;
+    }
+
+    /// List the adapters available to vulkan. See `enumerate_adapters()`
+    #[cfg(feature = "rafx-vulkan")]
+    pub fn enumerate_adapters_vulkan(
+        window: &dyn HasRawWindowHandle,
+        vk_api_def: &RafxApiDefVulkan,
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        RafxApiVulkan::enumerate_adapters(window, vk_api_def)
+    }
+
+    /// List the adapters available to metal. See `enumerate_adapters()`
+    #[cfg(feature = "rafx-metal")]
+    pub fn enumerate_adapters_metal(
+        window: &dyn HasRawWindowHandle,
+        metal_api_def: &RafxApiDefMetal,
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        RafxApiMetal::enumerate_adapters(window, metal_api_def)
+    }
+
+    /// List the adapters available to the empty backend. There is always exactly one.
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn enumerate_adapters_empty(
+        empty_api_def: &RafxApiDefEmpty
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        RafxApiEmpty::enumerate_adapters(empty_api_def)
+    }
+
+    /// List the adapters available to GL ES for the given window. See `enumerate_adapters()`
+    #[cfg(feature = "rafx-gles3")]
+    pub fn enumerate_adapters_gles3(
+        window: &dyn HasRawWindowHandle,
+        gles3_api_def: &RafxApiDefGles3,
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        RafxApiGles3::enumerate_adapters(window, gles3_api_def)
+    }
+
+    /// List the adapters available to a headless GL ES device. See `enumerate_adapters()`
+    #[cfg(feature = "rafx-gles3")]
+    pub fn enumerate_adapters_gles3_headless(
+        gles3_api_def: &RafxApiDefGles3
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        RafxApiGles3::enumerate_adapters_headless(gles3_api_def)
     }
 
     /// Create a cloneable handle to the device. Most of the interaction with the graphics backend
@@ -94,9 +246,15 @@ impl RafxApi {
             RafxApi::Metal(inner) => RafxDeviceContext::Metal(inner.device_context().clone()),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxApi::Empty(inner) => RafxDeviceContext::Empty(inner.device_context().clone()),
+            #[cfg(feature = "rafx-gles3")]
+            RafxApi::Gles3(inner) => RafxDeviceContext::Gles3(inner.device_context().clone()),
         }
     }
 
@@ -113,9 +271,15 @@ impl RafxApi {
             RafxApi::Metal(inner) => inner.destroy(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxApi::Empty(inner) => inner.destroy(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxApi::Gles3(inner) => inner.destroy(),
         }
     }
 
@@ -130,9 +294,15 @@ impl RafxApi {
             RafxApi::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxApi::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxApi::Gles3(_) => None,
         }
     }
 
@@ -147,9 +317,15 @@ impl RafxApi {
             RafxApi::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxApi::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxApi::Gles3(_) => None,
         }
     }
 
@@ -157,7 +333,11 @@ impl RafxApi {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_api(&self) -> Option<&RafxApiEmpty> {
         match self {
@@ -167,9 +347,38 @@ impl RafxApi {
             RafxApi::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxApi::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxApi::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_api(&self) -> Option<&RafxApiGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxApi::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxApi::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxApi::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxApi::Empty(_) => None,
         }
     }
 }
@@ -0,0 +1,111 @@
+use crate::{RafxAdapterInfo, RafxAdapterType, RafxApiDef, RafxResult};
+use std::sync::Arc;
+
+use crate::empty::{RafxDeviceContextEmpty, RafxDeviceContextEmptyInner};
+
+/// Empty-specific configuration
+#[derive(Default)]
+pub struct RafxApiDefEmpty {}
+
+/// A headless "null device". Resources are backed by host memory, command buffers record into a
+/// list that can be inspected, and all GPU work completes immediately on submit. No window or GPU
+/// is required, so this backend is useful for running rafx in automated tests.
+pub struct RafxApiEmpty {
+    device_context: Option<RafxDeviceContextEmpty>,
+}
+
+impl Drop for RafxApiEmpty {
+    fn drop(&mut self) {
+        self.destroy().unwrap();
+    }
+}
+
+impl RafxApiEmpty {
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        self.device_context.as_ref().unwrap()
+    }
+
+    /// There is a single adapter with no GPU behind it
+    pub fn enumerate_adapters(
+        _empty_api_def: &RafxApiDefEmpty
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        Ok(vec![RafxAdapterInfo {
+            adapter_index: 0,
+            name: "Empty".to_string(),
+            vendor_id: 0,
+            device_id: 0,
+            adapter_type: RafxAdapterType::Cpu,
+            driver_version: String::new(),
+            is_supported: true,
+            is_default: true,
+        }])
+    }
+
+    pub fn new(
+        api_def: &RafxApiDef,
+        _empty_api_def: &RafxApiDefEmpty,
+    ) -> RafxResult<Self> {
+        if let Some(adapter_index) = api_def.adapter_index {
+            if adapter_index != 0 {
+                Err(format!(
+                    "Adapter index {} is out of range, the empty backend has a single adapter",
+                    adapter_index
+                ))?;
+            }
+        }
+
+        let inner = Arc::new(RafxDeviceContextEmptyInner::new()?);
+        let device_context = RafxDeviceContextEmpty::new(inner)?;
+
+        Ok(RafxApiEmpty {
+            device_context: Some(device_context),
+        })
+    }
+
+    pub fn destroy(&mut self) -> RafxResult<()> {
+        if let Some(device_context) = self.device_context.take() {
+            let inner = device_context.inner.clone();
+
+            // This should be the final device context
+            std::mem::drop(device_context);
+
+            let strong_count = Arc::strong_count(&inner);
+            match Arc::try_unwrap(inner) {
+                Ok(inner) => std::mem::drop(inner),
+                Err(_arc) => {
+                    Err(format!(
+                        "Could not destroy device, {} references to it exist",
+                        strong_count
+                    ))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn enumerate_and_select_adapter() {
+        let adapters = RafxApi::enumerate_adapters_empty(&Default::default()).unwrap();
+        assert_eq!(adapters.len(), 1);
+        assert!(adapters[0].is_supported);
+        assert!(adapters[0].is_default);
+
+        let api_def = RafxApiDef {
+            adapter_index: Some(adapters[0].adapter_index),
+            ..Default::default()
+        };
+        RafxApi::new_empty(&api_def, &Default::default()).unwrap();
+
+        let api_def = RafxApiDef {
+            adapter_index: Some(1),
+            ..Default::default()
+        };
+        assert!(RafxApi::new_empty(&api_def, &Default::default()).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::empty::{RafxDeviceContextEmpty, RafxHeapEmpty, RafxHostMemoryEmpty};
+use crate::{RafxBufferDef, RafxMemoryRequirements, RafxMemoryUsage, RafxResourceType, RafxResult};
+#[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxBufferEmptyInner {
+    device_context: RafxDeviceContextEmpty,
+    buffer_def: RafxBufferDef,
+    memory: RafxHostMemoryEmpty,
+    // Used internally to identify the buffer for validation and capture
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    buffer_id: u32,
+}
+
+// for UnsafeCell, the rafx API does not synchronize access to buffer memory
+unsafe impl Send for RafxBufferEmptyInner {}
+unsafe impl Sync for RafxBufferEmptyInner {}
+
+impl Drop for RafxBufferEmptyInner {
+    fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_buffer_destroyed(self.buffer_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_buffer_destroyed(self.buffer_id);
+
+        // Placed buffers don't own their memory
+        if !self.memory.is_placed() {
+            self.device_context
+                .memory_usage_tracker()
+                .on_buffer_freed(self.buffer_def.memory_usage, self.memory.len());
+        }
+    }
+}
+
+/// A buffer backed by host memory. Cloning produces another handle to the same memory so that
+/// recorded commands can refer to the buffer.
+#[derive(Clone, Debug)]
+pub struct RafxBufferEmpty {
+    inner: Arc<RafxBufferEmptyInner>,
+}
+
+impl PartialEq for RafxBufferEmpty {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for RafxBufferEmpty {}
+
+impl RafxBufferEmpty {
+    pub fn buffer_def(&self) -> &RafxBufferDef {
+        &self.inner.buffer_def
+    }
+
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn buffer_id(&self) -> u32 {
+        self.inner.buffer_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.inner.device_context.capture_recorder()
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    /// Size of the host memory allocation, may be larger than the size in the buffer def
+    pub fn allocation_size(&self) -> u64 {
+        self.inner.memory.len()
+    }
+
+    // Pointer to the host memory, regardless of memory usage. Used to execute transfers
+    pub(crate) fn host_memory_ptr(&self) -> *mut u8 {
+        self.inner.memory.ptr()
+    }
+
+    pub fn map_buffer(&self) -> RafxResult<*mut u8> {
+        if self.inner.buffer_def.memory_usage == RafxMemoryUsage::GpuOnly {
+            return Err("Cannot map GPU-only buffer")?;
+        }
+
+        Ok(self.host_memory_ptr())
+    }
+
+    pub fn unmap_buffer(&self) -> RafxResult<()> {
+        // don't do anything, host memory is always mapped
+        Ok(())
+    }
+
+    pub fn mapped_memory(&self) -> Option<*mut u8> {
+        if self.inner.buffer_def.memory_usage == RafxMemoryUsage::GpuOnly {
+            None
+        } else {
+            Some(self.host_memory_ptr())
+        }
+    }
+
+    pub fn copy_to_host_visible_buffer<T: Copy>(
+        &self,
+        data: &[T],
+    ) -> RafxResult<()> {
+        // Cannot check size of data == buffer because buffer size might be rounded up
+        self.copy_to_host_visible_buffer_with_offset(data, 0)
+    }
+
+    pub fn copy_to_host_visible_buffer_with_offset<T: Copy>(
+        &self,
+        data: &[T],
+        buffer_byte_offset: u64,
+    ) -> RafxResult<()> {
+        let data_size_in_bytes = rafx_base::memory::slice_size_in_bytes(data) as u64;
+        assert!(buffer_byte_offset + data_size_in_bytes <= self.inner.buffer_def.size);
+
+        let src = data.as_ptr() as *const u8;
+
+        unsafe {
+            let dst = self.map_buffer()?.add(buffer_byte_offset as usize);
+            std::ptr::copy_nonoverlapping(src, dst, data_size_in_bytes as usize);
+        }
+
+        self.unmap_buffer()?;
+
+        Ok(())
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Nothing to name, there is no GPU object
+    }
+
+    // Uniform buffers are rounded up so that they can be bound at any offset
+    fn required_allocation_size(
+        device_context: &RafxDeviceContextEmpty,
+        buffer_def: &RafxBufferDef,
+    ) -> u64 {
+        if buffer_def
+            .resource_type
+            .intersects(RafxResourceType::UNIFORM_BUFFER)
+        {
+            rafx_base::memory::round_size_up_to_alignment_u64(
+                buffer_def.size,
+                device_context
+                    .device_info()
+                    .min_uniform_buffer_offset_alignment as u64,
+            )
+        } else {
+            buffer_def.size
+        }
+    }
+
+    pub fn memory_requirements(
+        device_context: &RafxDeviceContextEmpty,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxMemoryRequirements {
+        RafxMemoryRequirements {
+            size: Self::required_allocation_size(device_context, buffer_def),
+            alignment: super::heap::HEAP_PLACEMENT_ALIGNMENT,
+        }
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
+        let allocation_size = Self::required_allocation_size(device_context, buffer_def);
+        device_context
+            .memory_usage_tracker()
+            .on_buffer_allocated(buffer_def.memory_usage, allocation_size);
+
+        Ok(Self::from_memory(
+            device_context,
+            buffer_def,
+            RafxHostMemoryEmpty::new_dedicated(allocation_size),
+        ))
+    }
+
+    pub fn new_in_heap(
+        device_context: &RafxDeviceContextEmpty,
+        heap: &RafxHeapEmpty,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
+        let memory_requirements = Self::memory_requirements(device_context, buffer_def);
+        heap.heap_def().verify_buffer_def(buffer_def)?;
+        heap.heap_def()
+            .verify_placement(offset, &memory_requirements)?;
+
+        Ok(Self::from_memory(
+            device_context,
+            buffer_def,
+            RafxHostMemoryEmpty::Placed {
+                heap: heap.clone(),
+                offset,
+                size: memory_requirements.size,
+            },
+        ))
+    }
+
+    fn from_memory(
+        device_context: &RafxDeviceContextEmpty,
+        buffer_def: &RafxBufferDef,
+        memory: RafxHostMemoryEmpty,
+    ) -> Self {
+        let inner = RafxBufferEmptyInner {
+            device_context: device_context.clone(),
+            buffer_def: buffer_def.clone(),
+            memory,
+            #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+            buffer_id: crate::internal_shared::NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed),
+        };
+
+        RafxBufferEmpty {
+            inner: Arc::new(inner),
+        }
+    }
+}
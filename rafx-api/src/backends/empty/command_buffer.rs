@@ -0,0 +1,1713 @@
+use crate::empty::{
+    RafxBufferEmpty, RafxCommandPoolEmpty, RafxDescriptorSetArrayEmpty,
+    RafxDescriptorSetHandleEmpty, RafxPipelineEmpty, RafxQueryPoolEmpty, RafxQueueEmpty,
+    RafxRootSignatureEmpty, RafxTextureEmpty, RafxTextureViewEmpty,
+};
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxCommandBufferResourceStates;
+use crate::{
+    RafxBufferBarrier, RafxCmdBlitParams, RafxCmdClearTextureParams,
+    RafxCmdCopyBufferToTextureParams, RafxCmdCopyTextureToBufferParams,
+    RafxCmdCopyTextureToTextureParams, RafxColorClearValue, RafxColorRenderTargetBinding,
+    RafxCommandBufferDef, RafxCommandBufferInheritanceInfo, RafxDepthStencilClearValue,
+    RafxDepthStencilRenderTargetBinding, RafxDeviceInfo, RafxDispatchIndirectCommand,
+    RafxDrawIndexedIndirectCommand, RafxDrawIndirectCommand, RafxExtents3D, RafxIndexBufferBinding,
+    RafxIndexType, RafxLoadOp, RafxPushConstantIndex, RafxQueryType, RafxResourceState,
+    RafxResourceType, RafxResult, RafxStoreOp, RafxTextureBarrier, RafxTextureBufferLayout,
+    RafxTextureClearValue, RafxTextureView, RafxVertexBufferBinding,
+};
+use rafx_base::trust_cell::TrustCell;
+
+/// A color render target captured by `cmd_begin_render_pass`
+#[derive(Clone, Debug)]
+pub struct RafxColorRenderTargetEmpty {
+    pub texture: RafxTextureEmpty,
+    pub load_op: RafxLoadOp,
+    pub store_op: RafxStoreOp,
+    pub mip_slice: Option<u8>,
+    pub array_slice: Option<u16>,
+    pub texture_view: Option<RafxTextureViewEmpty>,
+    pub clear_value: RafxColorClearValue,
+    pub resolve_target: Option<RafxTextureEmpty>,
+}
+
+/// A depth/stencil render target captured by `cmd_begin_render_pass`
+#[derive(Clone, Debug)]
+pub struct RafxDepthStencilRenderTargetEmpty {
+    pub texture: RafxTextureEmpty,
+    pub depth_load_op: RafxLoadOp,
+    pub stencil_load_op: RafxLoadOp,
+    pub depth_store_op: RafxStoreOp,
+    pub stencil_store_op: RafxStoreOp,
+    pub mip_slice: Option<u8>,
+    pub array_slice: Option<u16>,
+    pub texture_view: Option<RafxTextureViewEmpty>,
+    pub clear_value: RafxDepthStencilClearValue,
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxBufferBarrierEmpty {
+    pub buffer: RafxBufferEmpty,
+    pub src_state: RafxResourceState,
+    pub dst_state: RafxResourceState,
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxTextureBarrierEmpty {
+    pub texture: RafxTextureEmpty,
+    pub src_state: RafxResourceState,
+    pub dst_state: RafxResourceState,
+    pub array_slice: Option<u16>,
+    pub mip_slice: Option<u8>,
+}
+
+/// A command recorded into a `RafxCommandBufferEmpty`. Resources are held by handle so the
+/// commands stay valid after the bindings that were passed in go out of scope.
+#[derive(Clone, Debug)]
+pub enum RafxRecordedCommandEmpty {
+    BeginRenderPass {
+        color_targets: Vec<RafxColorRenderTargetEmpty>,
+        depth_target: Option<RafxDepthStencilRenderTargetEmpty>,
+        // True if the render pass was begun with cmd_begin_render_pass_for_secondary
+        secondary_command_buffers: bool,
+    },
+    EndRenderPass,
+    ExecuteSecondaryCommandBuffers {
+        command_buffers: Vec<Vec<RafxRecordedCommandEmpty>>,
+    },
+    SetViewport {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        depth_min: f32,
+        depth_max: f32,
+    },
+    SetScissor {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    SetStencilReferenceValue(u32),
+    SetDepthBias {
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    },
+    SetBlendConstants([f32; 4]),
+    SetLineWidth(f32),
+    BindPipeline(RafxPipelineEmpty),
+    BindVertexBuffers {
+        first_binding: u32,
+        bindings: Vec<(RafxBufferEmpty, u64)>,
+    },
+    BindIndexBuffer {
+        buffer: RafxBufferEmpty,
+        byte_offset: u64,
+        index_type: RafxIndexType,
+    },
+    BindDescriptorSet {
+        set_index: u32,
+        array_index: u32,
+    },
+    BindPushConstant {
+        push_constant_index: RafxPushConstantIndex,
+        data: Vec<u8>,
+    },
+    Draw {
+        vertex_count: u32,
+        first_vertex: u32,
+    },
+    DrawInstanced {
+        vertex_count: u32,
+        first_vertex: u32,
+        instance_count: u32,
+        first_instance: u32,
+    },
+    DrawIndexed {
+        index_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+    },
+    DrawIndexedInstanced {
+        index_count: u32,
+        first_index: u32,
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: i32,
+    },
+    DrawIndirect {
+        indirect_buffer: RafxBufferEmpty,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    DrawIndexedIndirect {
+        indirect_buffer: RafxBufferEmpty,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    Dispatch {
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    },
+    DispatchIndirect {
+        indirect_buffer: RafxBufferEmpty,
+        indirect_buffer_offset_in_bytes: u64,
+    },
+    ResourceBarrier {
+        buffer_barriers: Vec<RafxBufferBarrierEmpty>,
+        texture_barriers: Vec<RafxTextureBarrierEmpty>,
+    },
+    ClearTexture {
+        texture: RafxTextureEmpty,
+        clear_value: RafxTextureClearValue,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    },
+    FillBuffer {
+        buffer: RafxBufferEmpty,
+        offset: u64,
+        size: u64,
+        value: u8,
+    },
+    CopyBufferToBuffer {
+        src_buffer: RafxBufferEmpty,
+        dst_buffer: RafxBufferEmpty,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
+    },
+    CopyBufferToTexture {
+        src_buffer: RafxBufferEmpty,
+        dst_texture: RafxTextureEmpty,
+        buffer_offset: u64,
+        array_layer: u16,
+        mip_level: u8,
+    },
+    CopyTextureToBuffer {
+        src_texture: RafxTextureEmpty,
+        dst_buffer: RafxBufferEmpty,
+        buffer_offset: u64,
+        array_layer: u16,
+        mip_level: u8,
+    },
+    CopyTextureToTexture {
+        src_texture: RafxTextureEmpty,
+        dst_texture: RafxTextureEmpty,
+        src_offset: RafxExtents3D,
+        dst_offset: RafxExtents3D,
+        extents: RafxExtents3D,
+        src_mip_level: u8,
+        dst_mip_level: u8,
+        array_slices: Option<[u16; 2]>,
+    },
+    Blit {
+        src_texture: RafxTextureEmpty,
+        dst_texture: RafxTextureEmpty,
+        src_extents: [RafxExtents3D; 2],
+        dst_extents: [RafxExtents3D; 2],
+        src_mip_level: u8,
+        dst_mip_level: u8,
+        array_slices: Option<[u16; 2]>,
+    },
+    ResetQueryPool {
+        query_pool: RafxQueryPoolEmpty,
+        first_query: u32,
+        query_count: u32,
+    },
+    BeginQuery {
+        query_pool: RafxQueryPoolEmpty,
+        query_index: u32,
+    },
+    EndQuery {
+        query_pool: RafxQueryPoolEmpty,
+        query_index: u32,
+    },
+    WriteTimestamp {
+        query_pool: RafxQueryPoolEmpty,
+        query_index: u32,
+    },
+    ResolveQuery {
+        query_pool: RafxQueryPoolEmpty,
+        first_query: u32,
+        query_count: u32,
+        dst_buffer: RafxBufferEmpty,
+        dst_offset_in_bytes: u64,
+    },
+    PushGroupDebugMarker {
+        name: String,
+    },
+    PopGroupDebugMarker,
+}
+
+#[derive(Debug, Default)]
+struct RafxCommandBufferEmptyInner {
+    is_recording: bool,
+    in_render_pass: bool,
+    // The render pass was inherited by begin_secondary, so it can't be ended by this command buffer
+    inherited_render_pass: bool,
+    // The render pass was begun with cmd_begin_render_pass_for_secondary
+    secondary_render_pass: bool,
+    // The most recently bound graphics pipeline uses RafxRasterizerState::dynamic_depth_bias
+    dynamic_depth_bias_bound: bool,
+    // Number of debug marker groups that have been pushed but not popped
+    debug_marker_depth: u32,
+    // Queries that have begun but not ended
+    active_queries: Vec<(RafxQueryPoolEmpty, u32)>,
+    commands: Vec<RafxRecordedCommandEmpty>,
+}
+
+/// Records commands into a list rather than encoding them for a GPU. The list can be inspected
+/// with `recorded_commands()`, which is useful for testing code that records command buffers.
+#[derive(Debug)]
+pub struct RafxCommandBufferEmpty {
+    queue: RafxQueueEmpty,
+    is_secondary: bool,
+    inner: TrustCell<RafxCommandBufferEmptyInner>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxCommandBufferResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl RafxCommandBufferEmpty {
+    pub fn queue(&self) -> &RafxQueueEmpty {
+        &self.queue
+    }
+
+    /// Returns a copy of the commands recorded since the last call to `begin()`
+    pub fn recorded_commands(&self) -> Vec<RafxRecordedCommandEmpty> {
+        self.inner.borrow().commands.clone()
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.inner.borrow().is_recording
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxCommandBufferResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.queue.device_context().capture_recorder()
+    }
+
+    // Runs the transfer subset of the recorded commands against host memory
+    pub(crate) fn execute(
+        &self,
+        device_info: &RafxDeviceInfo,
+    ) -> RafxResult<()> {
+        super::execute::execute_commands(device_info, &self.inner.borrow().commands)
+    }
+
+    pub fn new(
+        command_pool: &RafxCommandPoolEmpty,
+        command_buffer_def: &RafxCommandBufferDef,
+    ) -> RafxResult<RafxCommandBufferEmpty> {
+        Ok(RafxCommandBufferEmpty {
+            queue: command_pool.queue().clone(),
+            is_secondary: command_buffer_def.is_secondary,
+            inner: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        })
+    }
+
+    fn record(
+        &self,
+        command: RafxRecordedCommandEmpty,
+    ) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.is_recording {
+            return Err("Recorded a command into a command buffer that has not begun")?;
+        }
+
+        if inner.secondary_render_pass {
+            match &command {
+                RafxRecordedCommandEmpty::EndRenderPass
+                | RafxRecordedCommandEmpty::ExecuteSecondaryCommandBuffers { .. }
+                | RafxRecordedCommandEmpty::PushGroupDebugMarker { .. }
+                | RafxRecordedCommandEmpty::PopGroupDebugMarker => {}
+                _ => {
+                    return Err("Only secondary command buffers can be executed inside a render pass begun with cmd_begin_render_pass_for_secondary")?;
+                }
+            }
+        }
+
+        match &command {
+            RafxRecordedCommandEmpty::BeginRenderPass {
+                secondary_command_buffers,
+                ..
+            } => {
+                if inner.in_render_pass {
+                    return Err("cmd_begin_render_pass called while a render pass is active")?;
+                }
+                inner.in_render_pass = true;
+                inner.secondary_render_pass = *secondary_command_buffers;
+            }
+            RafxRecordedCommandEmpty::EndRenderPass => {
+                if !inner.in_render_pass {
+                    return Err("cmd_end_render_pass called without an active render pass")?;
+                }
+                if inner.inherited_render_pass {
+                    return Err(
+                        "cmd_end_render_pass called on a secondary command buffer that inherited its render pass",
+                    )?;
+                }
+                inner.in_render_pass = false;
+                inner.secondary_render_pass = false;
+            }
+            RafxRecordedCommandEmpty::ExecuteSecondaryCommandBuffers { .. } => {
+                if !inner.secondary_render_pass {
+                    return Err("cmd_execute_secondary_command_buffers must be called in a render pass begun with cmd_begin_render_pass_for_secondary")?;
+                }
+            }
+            RafxRecordedCommandEmpty::ResourceBarrier { .. }
+            | RafxRecordedCommandEmpty::ClearTexture { .. }
+            | RafxRecordedCommandEmpty::FillBuffer { .. }
+            | RafxRecordedCommandEmpty::CopyBufferToBuffer { .. }
+            | RafxRecordedCommandEmpty::CopyBufferToTexture { .. }
+            | RafxRecordedCommandEmpty::CopyTextureToBuffer { .. }
+            | RafxRecordedCommandEmpty::CopyTextureToTexture { .. }
+            | RafxRecordedCommandEmpty::Blit { .. }
+            | RafxRecordedCommandEmpty::ResetQueryPool { .. }
+            | RafxRecordedCommandEmpty::ResolveQuery { .. } => {
+                if inner.in_render_pass {
+                    return Err("Barriers and copies cannot be recorded inside a render pass")?;
+                }
+            }
+            RafxRecordedCommandEmpty::BeginQuery {
+                query_pool,
+                query_index,
+            } => {
+                let query = (query_pool.clone(), *query_index);
+                if inner.active_queries.contains(&query) {
+                    return Err("cmd_begin_query called for a query that is already active")?;
+                }
+                inner.active_queries.push(query);
+            }
+            RafxRecordedCommandEmpty::EndQuery {
+                query_pool,
+                query_index,
+            } => {
+                let query = (query_pool.clone(), *query_index);
+                let position = inner
+                    .active_queries
+                    .iter()
+                    .position(|x| *x == query)
+                    .ok_or("cmd_end_query called for a query that is not active")?;
+                inner.active_queries.swap_remove(position);
+            }
+            RafxRecordedCommandEmpty::BindPipeline(pipeline) => {
+                if let Some(graphics_state) = pipeline.graphics_state() {
+                    inner.dynamic_depth_bias_bound =
+                        graphics_state.rasterizer_state.dynamic_depth_bias;
+                }
+            }
+            RafxRecordedCommandEmpty::SetDepthBias { .. } => {
+                if !inner.dynamic_depth_bias_bound {
+                    return Err("cmd_set_depth_bias called without a bound pipeline that uses RafxRasterizerState::dynamic_depth_bias")?;
+                }
+            }
+            RafxRecordedCommandEmpty::PushGroupDebugMarker { .. } => {
+                inner.debug_marker_depth += 1;
+            }
+            RafxRecordedCommandEmpty::PopGroupDebugMarker => {
+                if inner.debug_marker_depth == 0 {
+                    return Err(
+                        "cmd_pop_group_debug_marker called without an active debug marker group",
+                    )?;
+                }
+                inner.debug_marker_depth -= 1;
+            }
+            _ => {}
+        }
+
+        inner.commands.push(command);
+        Ok(())
+    }
+
+    pub fn begin(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        inner.commands.clear();
+        inner.in_render_pass = false;
+        inner.inherited_render_pass = false;
+        inner.secondary_render_pass = false;
+        inner.dynamic_depth_bias_bound = false;
+        inner.debug_marker_depth = 0;
+        inner.active_queries.clear();
+        inner.is_recording = true;
+        Ok(())
+    }
+
+    pub fn begin_secondary(
+        &self,
+        _inheritance_info: &RafxCommandBufferInheritanceInfo,
+    ) -> RafxResult<()> {
+        if !self.is_secondary {
+            return Err("begin_secondary called on a primary command buffer")?;
+        }
+
+        self.begin()?;
+        let mut inner = self.inner.borrow_mut();
+        inner.in_render_pass = true;
+        inner.inherited_render_pass = true;
+        Ok(())
+    }
+
+    pub fn end(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.in_render_pass && !inner.inherited_render_pass {
+            return Err("Command buffer ended with an active render pass")?;
+        }
+        if !inner.active_queries.is_empty() {
+            return Err("Command buffer ended with an active query")?;
+        }
+        if inner.debug_marker_depth != 0 {
+            return Err("Command buffer ended with a debug marker group that was not popped")?;
+        }
+        inner.is_recording = false;
+        Ok(())
+    }
+
+    pub fn return_to_pool(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        inner.commands.clear();
+        inner.in_render_pass = false;
+        inner.inherited_render_pass = false;
+        inner.secondary_render_pass = false;
+        inner.debug_marker_depth = 0;
+        inner.active_queries.clear();
+        inner.is_recording = false;
+        Ok(())
+    }
+
+    pub fn cmd_begin_render_pass(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, false)
+    }
+
+    pub fn cmd_begin_render_pass_for_secondary(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, true)
+    }
+
+    fn do_begin_render_pass(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+        secondary_command_buffers: bool,
+    ) -> RafxResult<()> {
+        if color_targets.is_empty() && depth_target.is_none() {
+            Err("No color or depth target supplied to cmd_begin_render_pass")?;
+        }
+
+        let mut recorded_color_targets = Vec::with_capacity(color_targets.len());
+        for color_target in color_targets {
+            let texture = color_target.texture.empty_texture().unwrap();
+            let texture_view = Self::render_target_view(
+                texture,
+                color_target.texture_view,
+                color_target.mip_slice,
+                color_target.array_slice,
+            )?;
+
+            recorded_color_targets.push(RafxColorRenderTargetEmpty {
+                texture: texture.clone(),
+                load_op: color_target.load_op,
+                store_op: color_target.store_op,
+                mip_slice: color_target.mip_slice,
+                array_slice: color_target.array_slice,
+                texture_view,
+                clear_value: color_target.clear_value,
+                resolve_target: color_target
+                    .resolve_target
+                    .map(|x| x.empty_texture().unwrap().clone()),
+            });
+        }
+
+        let depth_target = if let Some(depth_target) = depth_target {
+            let texture = depth_target.texture.empty_texture().unwrap();
+            let texture_view = Self::render_target_view(
+                texture,
+                depth_target.texture_view,
+                depth_target.mip_slice,
+                depth_target.array_slice,
+            )?;
+
+            Some(RafxDepthStencilRenderTargetEmpty {
+                texture: texture.clone(),
+                depth_load_op: depth_target.depth_load_op,
+                stencil_load_op: depth_target.stencil_load_op,
+                depth_store_op: depth_target.depth_store_op,
+                stencil_store_op: depth_target.stencil_store_op,
+                mip_slice: depth_target.mip_slice,
+                array_slice: depth_target.array_slice,
+                texture_view,
+                clear_value: depth_target.clear_value,
+            })
+        } else {
+            None
+        };
+
+        self.record(RafxRecordedCommandEmpty::BeginRenderPass {
+            color_targets: recorded_color_targets,
+            depth_target,
+            secondary_command_buffers,
+        })
+    }
+
+    fn render_target_view(
+        texture: &RafxTextureEmpty,
+        texture_view: Option<&RafxTextureView>,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    ) -> RafxResult<Option<RafxTextureViewEmpty>> {
+        if let Some(texture_view) = texture_view {
+            let texture_view = texture_view.empty_texture_view().unwrap();
+            crate::internal_shared::verify_render_target_view(
+                texture.texture_def(),
+                texture_view.texture_view_def(),
+                texture_view.empty_texture() == texture,
+                mip_slice,
+                array_slice,
+            )?;
+            Ok(Some(texture_view.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn cmd_end_render_pass(&self) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::EndRenderPass)
+    }
+
+    pub fn cmd_execute_secondary_command_buffers(
+        &self,
+        secondary_command_buffers: &[&RafxCommandBufferEmpty],
+    ) -> RafxResult<()> {
+        let mut command_buffers = Vec::with_capacity(secondary_command_buffers.len());
+        for secondary_command_buffer in secondary_command_buffers {
+            if !secondary_command_buffer.is_secondary {
+                return Err(
+                    "cmd_execute_secondary_command_buffers was passed a primary command buffer",
+                )?;
+            }
+
+            if secondary_command_buffer.is_recording() {
+                return Err("cmd_execute_secondary_command_buffers was passed a secondary command buffer that has not ended")?;
+            }
+
+            command_buffers.push(secondary_command_buffer.recorded_commands());
+        }
+
+        self.record(RafxRecordedCommandEmpty::ExecuteSecondaryCommandBuffers { command_buffers })
+    }
+
+    pub fn cmd_set_viewport(
+        &self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        depth_min: f32,
+        depth_max: f32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::SetViewport {
+            x,
+            y,
+            width,
+            height,
+            depth_min,
+            depth_max,
+        })
+    }
+
+    pub fn cmd_set_scissor(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::SetScissor {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    pub fn cmd_set_stencil_reference_value(
+        &self,
+        value: u32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::SetStencilReferenceValue(value))
+    }
+
+    pub fn cmd_set_depth_bias(
+        &self,
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::SetDepthBias {
+            constant_factor,
+            slope_factor,
+            clamp,
+        })
+    }
+
+    pub fn cmd_set_blend_constants(
+        &self,
+        blend_constants: [f32; 4],
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::SetBlendConstants(blend_constants))
+    }
+
+    pub fn cmd_set_line_width(
+        &self,
+        line_width: f32,
+    ) -> RafxResult<()> {
+        self.queue
+            .device_context()
+            .device_info()
+            .verify_line_width(line_width)?;
+        self.record(RafxRecordedCommandEmpty::SetLineWidth(line_width))
+    }
+
+    pub fn cmd_bind_pipeline(
+        &self,
+        pipeline: &RafxPipelineEmpty,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::BindPipeline(pipeline.clone()))
+    }
+
+    pub fn cmd_bind_vertex_buffers(
+        &self,
+        first_binding: u32,
+        bindings: &[RafxVertexBufferBinding],
+    ) -> RafxResult<()> {
+        let bindings = bindings
+            .iter()
+            .map(|x| (x.buffer.empty_buffer().unwrap().clone(), x.byte_offset))
+            .collect();
+
+        self.record(RafxRecordedCommandEmpty::BindVertexBuffers {
+            first_binding,
+            bindings,
+        })
+    }
+
+    pub fn cmd_bind_index_buffer(
+        &self,
+        binding: &RafxIndexBufferBinding,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::BindIndexBuffer {
+            buffer: binding.buffer.empty_buffer().unwrap().clone(),
+            byte_offset: binding.byte_offset,
+            index_type: binding.index_type,
+        })
+    }
+
+    pub fn cmd_bind_descriptor_set(
+        &self,
+        descriptor_set_array: &RafxDescriptorSetArrayEmpty,
+        index: u32,
+    ) -> RafxResult<()> {
+        if index >= descriptor_set_array.array_length() {
+            return Err(format!(
+                "Bound descriptor set index {} but the array only has {} sets",
+                index,
+                descriptor_set_array.array_length()
+            ))?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::BindDescriptorSet {
+            set_index: descriptor_set_array.set_index(),
+            array_index: index,
+        })
+    }
+
+    pub fn cmd_bind_descriptor_set_handle(
+        &self,
+        _root_signature: &RafxRootSignatureEmpty,
+        set_index: u32,
+        descriptor_set_handle: &RafxDescriptorSetHandleEmpty,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::BindDescriptorSet {
+            set_index,
+            array_index: descriptor_set_handle.array_index(),
+        })
+    }
+
+    pub fn cmd_bind_push_constant(
+        &self,
+        root_signature: &RafxRootSignatureEmpty,
+        push_constant_index: RafxPushConstantIndex,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        let push_constant = root_signature
+            .push_constant(push_constant_index)
+            .ok_or("Push constant index is not valid for this root signature")?;
+
+        if data.len() as u32 > push_constant.size_in_bytes {
+            Err(format!(
+                "Push constant {:?} is {} bytes but {} bytes were provided",
+                push_constant.name,
+                push_constant.size_in_bytes,
+                data.len()
+            ))?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::BindPushConstant {
+            push_constant_index,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn cmd_draw(
+        &self,
+        vertex_count: u32,
+        first_vertex: u32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::Draw {
+            vertex_count,
+            first_vertex,
+        })
+    }
+
+    pub fn cmd_draw_instanced(
+        &self,
+        vertex_count: u32,
+        first_vertex: u32,
+        instance_count: u32,
+        first_instance: u32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::DrawInstanced {
+            vertex_count,
+            first_vertex,
+            instance_count,
+            first_instance,
+        })
+    }
+
+    pub fn cmd_draw_indexed(
+        &self,
+        index_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::DrawIndexed {
+            index_count,
+            first_index,
+            vertex_offset,
+        })
+    }
+
+    pub fn cmd_draw_indexed_instanced(
+        &self,
+        index_count: u32,
+        first_index: u32,
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: i32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::DrawIndexedInstanced {
+            index_count,
+            first_index,
+            instance_count,
+            first_instance,
+            vertex_offset,
+        })
+    }
+
+    pub fn cmd_dispatch(
+        &self,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::Dispatch {
+            group_count_x,
+            group_count_y,
+            group_count_z,
+        })
+    }
+
+    // Checks that an indirect buffer can hold `count` sets of arguments of `args_size` bytes each,
+    // starting at `offset` and spaced `stride` bytes apart
+    fn validate_indirect_buffer(
+        command_name: &str,
+        indirect_buffer: &RafxBufferEmpty,
+        offset: u64,
+        count: u32,
+        stride: u32,
+        args_size: u64,
+    ) -> RafxResult<()> {
+        let buffer_def = indirect_buffer.buffer_def();
+        if !buffer_def
+            .resource_type
+            .contains(RafxResourceType::INDIRECT_BUFFER)
+        {
+            return Err(format!(
+                "{} requires a buffer created with RafxResourceType::INDIRECT_BUFFER",
+                command_name
+            ))?;
+        }
+
+        if count > 1 && (stride as u64) < args_size {
+            return Err(format!(
+                "{} stride {} is smaller than the argument size {}",
+                command_name, stride, args_size
+            ))?;
+        }
+
+        if count > 0 && offset + (count as u64 - 1) * stride as u64 + args_size > buffer_def.size {
+            return Err(format!(
+                "{} reads past the end of the indirect buffer",
+                command_name
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_draw_indirect(
+        &self,
+        indirect_buffer: &RafxBufferEmpty,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        Self::validate_indirect_buffer(
+            "cmd_draw_indirect",
+            indirect_buffer,
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+            std::mem::size_of::<RafxDrawIndirectCommand>() as u64,
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::DrawIndirect {
+            indirect_buffer: indirect_buffer.clone(),
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+        })
+    }
+
+    pub fn cmd_draw_indexed_indirect(
+        &self,
+        indirect_buffer: &RafxBufferEmpty,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        Self::validate_indirect_buffer(
+            "cmd_draw_indexed_indirect",
+            indirect_buffer,
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+            std::mem::size_of::<RafxDrawIndexedIndirectCommand>() as u64,
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::DrawIndexedIndirect {
+            indirect_buffer: indirect_buffer.clone(),
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+        })
+    }
+
+    pub fn cmd_dispatch_indirect(
+        &self,
+        indirect_buffer: &RafxBufferEmpty,
+        indirect_buffer_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        let args_size = std::mem::size_of::<RafxDispatchIndirectCommand>() as u64;
+        Self::validate_indirect_buffer(
+            "cmd_dispatch_indirect",
+            indirect_buffer,
+            indirect_buffer_offset_in_bytes,
+            1,
+            args_size as u32,
+            args_size,
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::DispatchIndirect {
+            indirect_buffer: indirect_buffer.clone(),
+            indirect_buffer_offset_in_bytes,
+        })
+    }
+
+    // Checks that the queries are within the pool and that the pool is of an allowed type
+    fn validate_queries(
+        command_name: &str,
+        query_pool: &RafxQueryPoolEmpty,
+        first_query: u32,
+        query_count: u32,
+        allowed_query_types: &[RafxQueryType],
+    ) -> RafxResult<()> {
+        let query_pool_def = query_pool.query_pool_def();
+        if !allowed_query_types.contains(&query_pool_def.query_type) {
+            return Err(format!(
+                "{} cannot be used with a query pool of type {:?}",
+                command_name, query_pool_def.query_type
+            ))?;
+        }
+
+        if first_query + query_count > query_pool_def.query_count {
+            return Err(format!(
+                "{} uses queries {}..{} but the query pool has {} queries",
+                command_name,
+                first_query,
+                first_query + query_count,
+                query_pool_def.query_count
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_reset_query_pool(
+        &self,
+        query_pool: &RafxQueryPoolEmpty,
+        first_query: u32,
+        query_count: u32,
+    ) -> RafxResult<()> {
+        Self::validate_queries(
+            "cmd_reset_query_pool",
+            query_pool,
+            first_query,
+            query_count,
+            &[
+                RafxQueryType::Timestamp,
+                RafxQueryType::Occlusion,
+                RafxQueryType::PipelineStatistics,
+            ],
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::ResetQueryPool {
+            query_pool: query_pool.clone(),
+            first_query,
+            query_count,
+        })
+    }
+
+    pub fn cmd_begin_query(
+        &self,
+        query_pool: &RafxQueryPoolEmpty,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        Self::validate_queries(
+            "cmd_begin_query",
+            query_pool,
+            query_index,
+            1,
+            &[RafxQueryType::Occlusion, RafxQueryType::PipelineStatistics],
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::BeginQuery {
+            query_pool: query_pool.clone(),
+            query_index,
+        })
+    }
+
+    pub fn cmd_end_query(
+        &self,
+        query_pool: &RafxQueryPoolEmpty,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        Self::validate_queries(
+            "cmd_end_query",
+            query_pool,
+            query_index,
+            1,
+            &[RafxQueryType::Occlusion, RafxQueryType::PipelineStatistics],
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::EndQuery {
+            query_pool: query_pool.clone(),
+            query_index,
+        })
+    }
+
+    pub fn cmd_write_timestamp(
+        &self,
+        query_pool: &RafxQueryPoolEmpty,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        Self::validate_queries(
+            "cmd_write_timestamp",
+            query_pool,
+            query_index,
+            1,
+            &[RafxQueryType::Timestamp],
+        )?;
+
+        self.record(RafxRecordedCommandEmpty::WriteTimestamp {
+            query_pool: query_pool.clone(),
+            query_index,
+        })
+    }
+
+    pub fn cmd_resolve_query(
+        &self,
+        query_pool: &RafxQueryPoolEmpty,
+        first_query: u32,
+        query_count: u32,
+        dst_buffer: &RafxBufferEmpty,
+        dst_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        Self::validate_queries(
+            "cmd_resolve_query",
+            query_pool,
+            first_query,
+            query_count,
+            &[
+                RafxQueryType::Timestamp,
+                RafxQueryType::Occlusion,
+                RafxQueryType::PipelineStatistics,
+            ],
+        )?;
+
+        let values_per_query = query_pool.query_pool_def().query_type.values_per_query();
+        let size = (query_count * values_per_query) as u64 * std::mem::size_of::<u64>() as u64;
+        if dst_offset_in_bytes + size > dst_buffer.buffer_def().size {
+            return Err("cmd_resolve_query writes past the end of the destination buffer")?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::ResolveQuery {
+            query_pool: query_pool.clone(),
+            first_query,
+            query_count,
+            dst_buffer: dst_buffer.clone(),
+            dst_offset_in_bytes,
+        })
+    }
+
+    pub fn cmd_resource_barrier(
+        &self,
+        buffer_barriers: &[RafxBufferBarrier],
+        texture_barriers: &[RafxTextureBarrier],
+    ) -> RafxResult<()> {
+        let buffer_barriers = buffer_barriers
+            .iter()
+            .map(|x| RafxBufferBarrierEmpty {
+                buffer: x.buffer.empty_buffer().unwrap().clone(),
+                src_state: x.src_state,
+                dst_state: x.dst_state,
+            })
+            .collect();
+
+        let texture_barriers = texture_barriers
+            .iter()
+            .map(|x| RafxTextureBarrierEmpty {
+                texture: x.texture.empty_texture().unwrap().clone(),
+                src_state: x.src_state,
+                dst_state: x.dst_state,
+                array_slice: x.array_slice,
+                mip_slice: x.mip_slice,
+            })
+            .collect();
+
+        self.record(RafxRecordedCommandEmpty::ResourceBarrier {
+            buffer_barriers,
+            texture_barriers,
+        })
+    }
+
+    pub fn cmd_clear_texture(
+        &self,
+        texture: &RafxTextureEmpty,
+        params: &RafxCmdClearTextureParams,
+    ) -> RafxResult<()> {
+        if params.texture_state != RafxResourceState::COPY_DST
+            && params.texture_state != RafxResourceState::UNORDERED_ACCESS
+        {
+            return Err(format!(
+                "cmd_clear_texture requires the texture to be in COPY_DST or UNORDERED_ACCESS state but got {:?}",
+                params.texture_state
+            ))?;
+        }
+
+        let texture_def = texture.texture_def();
+        let is_depth_stencil_value = match params.clear_value {
+            RafxTextureClearValue::Color(_) => false,
+            RafxTextureClearValue::DepthStencil(_) => true,
+        };
+        if is_depth_stencil_value != texture_def.format.has_depth_or_stencil() {
+            return Err(format!(
+                "cmd_clear_texture clear value {:?} does not match format {:?}",
+                params.clear_value, texture_def.format
+            ))?;
+        }
+
+        if params
+            .mip_slice
+            .map_or(false, |x| x as u32 >= texture_def.mip_count)
+            || params
+                .array_slice
+                .map_or(false, |x| x as u32 >= texture_def.array_length)
+        {
+            return Err("cmd_clear_texture mip or array slice is out of range")?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::ClearTexture {
+            texture: texture.clone(),
+            clear_value: params.clear_value,
+            mip_slice: params.mip_slice,
+            array_slice: params.array_slice,
+        })
+    }
+
+    pub fn cmd_fill_buffer(
+        &self,
+        buffer: &RafxBufferEmpty,
+        offset: u64,
+        size: u64,
+        value: u8,
+    ) -> RafxResult<()> {
+        if offset % 4 != 0 || size % 4 != 0 {
+            return Err("cmd_fill_buffer offset and size must be multiples of 4")?;
+        }
+
+        if offset + size > buffer.buffer_def().size {
+            return Err("cmd_fill_buffer writes past the end of the buffer")?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::FillBuffer {
+            buffer: buffer.clone(),
+            offset,
+            size,
+            value,
+        })
+    }
+
+    pub fn cmd_copy_buffer_to_buffer(
+        &self,
+        src_buffer: &RafxBufferEmpty,
+        dst_buffer: &RafxBufferEmpty,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
+    ) -> RafxResult<()> {
+        if src_offset + size > src_buffer.buffer_def().size {
+            return Err("cmd_copy_buffer_to_buffer reads past the end of the source buffer")?;
+        }
+
+        if dst_offset + size > dst_buffer.buffer_def().size {
+            return Err("cmd_copy_buffer_to_buffer writes past the end of the destination buffer")?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::CopyBufferToBuffer {
+            src_buffer: src_buffer.clone(),
+            dst_buffer: dst_buffer.clone(),
+            src_offset,
+            dst_offset,
+            size,
+        })
+    }
+
+    pub fn cmd_copy_buffer_to_texture(
+        &self,
+        src_buffer: &RafxBufferEmpty,
+        dst_texture: &RafxTextureEmpty,
+        params: &RafxCmdCopyBufferToTextureParams,
+    ) -> RafxResult<()> {
+        let texture_def = dst_texture.texture_def();
+        if params.mip_level as u32 >= texture_def.mip_count
+            || params.array_layer as u32 >= texture_def.array_length
+        {
+            return Err(format!(
+                "cmd_copy_buffer_to_texture targets mip {} layer {} but the texture has {} mips and {} layers",
+                params.mip_level,
+                params.array_layer,
+                texture_def.mip_count,
+                texture_def.array_length
+            ))?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::CopyBufferToTexture {
+            src_buffer: src_buffer.clone(),
+            dst_texture: dst_texture.clone(),
+            buffer_offset: params.buffer_offset,
+            array_layer: params.array_layer,
+            mip_level: params.mip_level,
+        })
+    }
+
+    pub fn cmd_copy_texture_to_buffer(
+        &self,
+        src_texture: &RafxTextureEmpty,
+        dst_buffer: &RafxBufferEmpty,
+        params: &RafxCmdCopyTextureToBufferParams,
+    ) -> RafxResult<()> {
+        let texture_def = src_texture.texture_def();
+        if params.mip_level as u32 >= texture_def.mip_count
+            || params.array_layer as u32 >= texture_def.array_length
+        {
+            return Err(format!(
+                "cmd_copy_texture_to_buffer reads mip {} layer {} but the texture has {} mips and {} layers",
+                params.mip_level,
+                params.array_layer,
+                texture_def.mip_count,
+                texture_def.array_length
+            ))?;
+        }
+
+        let layout = RafxTextureBufferLayout::new(
+            self.queue.device_context().device_info(),
+            texture_def.format,
+            texture_def.extents,
+            params.mip_level,
+        );
+        let required_size = params.buffer_offset + layout.size;
+        if required_size > dst_buffer.buffer_def().size {
+            return Err(format!(
+                "cmd_copy_texture_to_buffer writes {} bytes to a buffer of size {}",
+                required_size,
+                dst_buffer.buffer_def().size
+            ))?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::CopyTextureToBuffer {
+            src_texture: src_texture.clone(),
+            dst_buffer: dst_buffer.clone(),
+            buffer_offset: params.buffer_offset,
+            array_layer: params.array_layer,
+            mip_level: params.mip_level,
+        })
+    }
+
+    pub fn cmd_copy_texture_to_texture(
+        &self,
+        src_texture: &RafxTextureEmpty,
+        dst_texture: &RafxTextureEmpty,
+        params: &RafxCmdCopyTextureToTextureParams,
+    ) -> RafxResult<()> {
+        let src_def = src_texture.texture_def();
+        let dst_def = dst_texture.texture_def();
+        if src_def.format != dst_def.format {
+            return Err(format!(
+                "cmd_copy_texture_to_texture requires matching formats but got {:?} and {:?}",
+                src_def.format, dst_def.format
+            ))?;
+        }
+
+        if params.src_mip_level as u32 >= src_def.mip_count
+            || params.dst_mip_level as u32 >= dst_def.mip_count
+        {
+            return Err("cmd_copy_texture_to_texture mip level is out of range")?;
+        }
+
+        if let Some(array_slices) = params.array_slices {
+            if array_slices[0] as u32 >= src_def.array_length
+                || array_slices[1] as u32 >= dst_def.array_length
+            {
+                return Err("cmd_copy_texture_to_texture array slice is out of range")?;
+            }
+        }
+
+        let block_width = src_def.format.block_width_in_pixels();
+        let block_height = src_def.format.block_height_in_pixels();
+        let check_region =
+            |offset: RafxExtents3D, texture_extents: RafxExtents3D, mip_level: u8| {
+                let mip_extents = RafxExtents3D {
+                    width: 1.max(texture_extents.width >> mip_level),
+                    height: 1.max(texture_extents.height >> mip_level),
+                    depth: 1.max(texture_extents.depth >> mip_level),
+                };
+
+                let end_x = offset.width + params.extents.width;
+                let end_y = offset.height + params.extents.height;
+                let in_bounds = end_x <= mip_extents.width
+                    && end_y <= mip_extents.height
+                    && offset.depth + params.extents.depth <= mip_extents.depth;
+
+                // Regions of block-compressed textures must be whole blocks, except at the edge of the
+                // mip level
+                let block_aligned = offset.width % block_width == 0
+                    && offset.height % block_height == 0
+                    && (end_x % block_width == 0 || end_x == mip_extents.width)
+                    && (end_y % block_height == 0 || end_y == mip_extents.height);
+
+                in_bounds && block_aligned
+            };
+
+        if !check_region(params.src_offset, src_def.extents, params.src_mip_level)
+            || !check_region(params.dst_offset, dst_def.extents, params.dst_mip_level)
+        {
+            return Err(
+                "cmd_copy_texture_to_texture region is out of bounds or not block-aligned",
+            )?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::CopyTextureToTexture {
+            src_texture: src_texture.clone(),
+            dst_texture: dst_texture.clone(),
+            src_offset: params.src_offset,
+            dst_offset: params.dst_offset,
+            extents: params.extents,
+            src_mip_level: params.src_mip_level,
+            dst_mip_level: params.dst_mip_level,
+            array_slices: params.array_slices,
+        })
+    }
+
+    pub fn cmd_blit(
+        &self,
+        src_texture: &RafxTextureEmpty,
+        dst_texture: &RafxTextureEmpty,
+        params: &RafxCmdBlitParams,
+    ) -> RafxResult<()> {
+        if params.src_mip_level as u32 >= src_texture.texture_def().mip_count
+            || params.dst_mip_level as u32 >= dst_texture.texture_def().mip_count
+        {
+            return Err("cmd_blit mip level is out of range")?;
+        }
+
+        self.record(RafxRecordedCommandEmpty::Blit {
+            src_texture: src_texture.clone(),
+            dst_texture: dst_texture.clone(),
+            src_extents: params.src_extents,
+            dst_extents: params.dst_extents,
+            src_mip_level: params.src_mip_level,
+            dst_mip_level: params.dst_mip_level,
+            array_slices: params.array_slices,
+        })
+    }
+
+    pub fn cmd_push_group_debug_marker(
+        &self,
+        name: impl AsRef<str>,
+    ) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::PushGroupDebugMarker {
+            name: name.as_ref().to_string(),
+        })
+    }
+
+    pub fn cmd_pop_group_debug_marker(&self) -> RafxResult<()> {
+        self.record(RafxRecordedCommandEmpty::PopGroupDebugMarker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::{empty_shader_module, EmptyTestContext};
+    use crate::empty::RafxRecordedCommandEmpty;
+    use crate::*;
+
+    #[test]
+    fn record_and_submit() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let queue = &test_context.queue;
+        let command_buffer = &test_context.command_buffer;
+
+        let buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &[1u32, 2, 3, 4],
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+        buffer
+            .copy_to_host_visible_buffer(&[1u32, 2, 3, 4])
+            .unwrap();
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 2,
+                    height: 2,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Recording before begin() is an error
+        assert!(command_buffer.cmd_draw(3, 0).is_err());
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_copy_buffer_to_texture(&buffer, &texture, &Default::default())
+            .unwrap();
+        command_buffer.end().unwrap();
+
+        let recorded_commands = test_context.recorded_commands();
+        assert_eq!(recorded_commands.len(), 1);
+        assert!(matches!(
+            recorded_commands[0],
+            RafxRecordedCommandEmpty::CopyBufferToTexture { .. }
+        ));
+
+        let fence = device_context.create_fence().unwrap();
+        assert!(fence.get_fence_status().unwrap() == RafxFenceStatus::Unsubmitted);
+        queue
+            .submit(&[command_buffer], &[], &[], Some(&fence))
+            .unwrap();
+        assert!(fence.get_fence_status().unwrap() == RafxFenceStatus::Complete);
+    }
+
+    #[test]
+    fn indirect_commands_validate_buffer() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let draws = [RafxDrawIndirectCommand::default(); 2];
+        let draw_size = std::mem::size_of::<RafxDrawIndirectCommand>() as u32;
+        let indirect_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &draws,
+                RafxResourceType::INDIRECT_BUFFER,
+            ))
+            .unwrap();
+        let plain_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &draws,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_draw_indirect(&indirect_buffer, 0, 2, draw_size)
+            .unwrap();
+        // Missing INDIRECT_BUFFER, reading past the end, and overlapping arguments are errors
+        assert!(command_buffer
+            .cmd_draw_indirect(&plain_buffer, 0, 1, draw_size)
+            .is_err());
+        assert!(command_buffer
+            .cmd_draw_indirect(&indirect_buffer, draw_size as u64, 2, draw_size)
+            .is_err());
+        assert!(command_buffer
+            .cmd_draw_indirect(&indirect_buffer, 0, 2, draw_size - 4)
+            .is_err());
+        command_buffer.end().unwrap();
+
+        let recorded_commands = test_context.recorded_commands();
+        assert_eq!(recorded_commands.len(), 1);
+        assert!(matches!(
+            recorded_commands[0],
+            RafxRecordedCommandEmpty::DrawIndirect { draw_count: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn bind_push_constant() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let shader_module = empty_shader_module(device_context);
+        let shader = device_context
+            .create_shader(vec![RafxShaderStageDef {
+                shader_module,
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::VERTEX,
+                    resources: vec![RafxShaderResource {
+                        resource_type: RafxResourceType::ROOT_CONSTANT,
+                        size_in_bytes: 8,
+                        used_in_shader_stages: RafxShaderStageFlags::VERTEX,
+                        name: Some("PushConstants".to_string()),
+                        ..Default::default()
+                    }],
+                    compute_threads_per_group: None,
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![],
+                },
+                specialization_constants: vec![],
+            }])
+            .unwrap();
+        let root_signature = device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: &[shader],
+                immutable_samplers: &[],
+                bindless_arrays: &[],
+            })
+            .unwrap();
+
+        let push_constant_index = root_signature
+            .find_push_constant_by_name("PushConstants")
+            .unwrap();
+        assert!(root_signature
+            .find_push_constant_by_name("Missing")
+            .is_none());
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_bind_push_constant(&root_signature, push_constant_index, &[1u32, 2u32])
+            .unwrap();
+        // Larger than the push constant range
+        assert!(command_buffer
+            .cmd_bind_push_constant(&root_signature, push_constant_index, &[1u32, 2u32, 3u32])
+            .is_err());
+        command_buffer.end().unwrap();
+
+        let recorded_commands = test_context.recorded_commands();
+        assert_eq!(recorded_commands.len(), 1);
+        match &recorded_commands[0] {
+            RafxRecordedCommandEmpty::BindPushConstant { data, .. } => {
+                assert_eq!(data, &[1, 0, 0, 0, 2, 0, 0, 0]);
+            }
+            _ => panic!("Expected a BindPushConstant command"),
+        }
+    }
+
+    #[test]
+    fn debug_markers_must_be_balanced() {
+        let test_context = EmptyTestContext::new();
+        let command_buffer = &test_context.command_buffer;
+
+        command_buffer.begin().unwrap();
+        assert!(command_buffer.cmd_pop_group_debug_marker().is_err());
+        command_buffer.cmd_push_group_debug_marker("outer").unwrap();
+        command_buffer.cmd_push_group_debug_marker("inner").unwrap();
+        command_buffer.cmd_pop_group_debug_marker().unwrap();
+        assert!(command_buffer.end().is_err());
+        command_buffer.cmd_pop_group_debug_marker().unwrap();
+        command_buffer.end().unwrap();
+
+        let recorded_commands = test_context.recorded_commands();
+        assert_eq!(recorded_commands.len(), 4);
+        match &recorded_commands[0] {
+            RafxRecordedCommandEmpty::PushGroupDebugMarker { name } => assert_eq!(name, "outer"),
+            _ => panic!("Expected a PushGroupDebugMarker command"),
+        }
+    }
+
+    #[test]
+    fn dynamic_depth_bias_and_line_width() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |rasterizer_state: &RafxRasterizerState| {
+            let mut hasher = DefaultHasher::new();
+            rasterizer_state.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        // Depth bias is not part of the hash if it's dynamic
+        let baked_state = RafxRasterizerState {
+            depth_bias: 2,
+            ..Default::default()
+        };
+        let dynamic_state = RafxRasterizerState {
+            dynamic_depth_bias: true,
+            ..Default::default()
+        };
+        assert_ne!(hash(&baked_state), hash(&Default::default()));
+        assert_eq!(
+            hash(&dynamic_state),
+            hash(&RafxRasterizerState {
+                depth_bias: 2,
+                ..dynamic_state.clone()
+            })
+        );
+
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let shader_module = empty_shader_module(device_context);
+        let shader = device_context
+            .create_shader(vec![RafxShaderStageDef {
+                shader_module,
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::VERTEX,
+                    resources: vec![],
+                    compute_threads_per_group: None,
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![],
+                },
+                specialization_constants: vec![],
+            }])
+            .unwrap();
+        let root_signature = device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: std::slice::from_ref(&shader),
+                immutable_samplers: &[],
+                bindless_arrays: &[],
+            })
+            .unwrap();
+
+        let vertex_layout = RafxVertexLayout {
+            attributes: vec![],
+            buffers: vec![],
+        };
+        let pipeline_def = RafxGraphicsPipelineDef {
+            shader: &shader,
+            root_signature: &root_signature,
+            vertex_layout: &vertex_layout,
+            blend_state: &Default::default(),
+            depth_state: &Default::default(),
+            rasterizer_state: &baked_state,
+            primitive_topology: RafxPrimitiveTopology::TriangleList,
+            color_formats: &[],
+            depth_stencil_format: Some(RafxFormat::D32_SFLOAT),
+            sample_count: RafxSampleCount::SampleCount1,
+        };
+        let baked_pipeline = device_context
+            .create_graphics_pipeline(&pipeline_def)
+            .unwrap();
+        let dynamic_pipeline = device_context
+            .create_graphics_pipeline(&RafxGraphicsPipelineDef {
+                rasterizer_state: &dynamic_state,
+                ..pipeline_def
+            })
+            .unwrap();
+
+        command_buffer.begin().unwrap();
+        command_buffer.cmd_bind_pipeline(&baked_pipeline).unwrap();
+        assert!(command_buffer.cmd_set_depth_bias(1.0, 1.0, 0.0).is_err());
+        command_buffer.cmd_bind_pipeline(&dynamic_pipeline).unwrap();
+        command_buffer.cmd_set_depth_bias(1.0, 1.0, 0.0).unwrap();
+        command_buffer
+            .cmd_set_blend_constants([1.0, 0.5, 0.25, 1.0])
+            .unwrap();
+
+        // Wide lines are not supported by the empty backend
+        command_buffer.cmd_set_line_width(1.0).unwrap();
+        assert!(command_buffer.cmd_set_line_width(2.0).is_err());
+        assert!(command_buffer.cmd_set_line_width(0.0).is_err());
+        command_buffer.end().unwrap();
+
+        let recorded_commands = test_context.recorded_commands();
+        assert_eq!(recorded_commands.len(), 5);
+        assert!(matches!(
+            recorded_commands[2],
+            RafxRecordedCommandEmpty::SetDepthBias {
+                constant_factor,
+                ..
+            } if constant_factor == 1.0
+        ));
+        assert!(matches!(
+            recorded_commands[3],
+            RafxRecordedCommandEmpty::SetBlendConstants([1.0, 0.5, 0.25, 1.0])
+        ));
+    }
+}
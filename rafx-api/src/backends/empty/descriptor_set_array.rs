@@ -0,0 +1,289 @@
+use crate::empty::RafxDeviceContextEmpty;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxDescriptorSetResourceStates;
+use crate::{
+    RafxDescriptorKey, RafxDescriptorSetArrayDef, RafxDescriptorUpdate, RafxResult,
+    RafxRootSignature,
+};
+
+#[derive(Clone, Debug)]
+pub struct RafxDescriptorSetHandleEmpty {
+    set_index: u32,
+    array_index: u32,
+    #[cfg(feature = "capture")]
+    descriptor_set_array_id: u32,
+}
+
+impl RafxDescriptorSetHandleEmpty {
+    pub fn set_index(&self) -> u32 {
+        self.set_index
+    }
+
+    pub fn array_index(&self) -> u32 {
+        self.array_index
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_ref(&self) -> crate::capture::RafxCaptureDescriptorSetRef {
+        crate::capture::RafxCaptureDescriptorSetRef {
+            descriptor_set_array_id: self.descriptor_set_array_id,
+            array_index: self.array_index,
+        }
+    }
+}
+
+/// Descriptor sets are not backed by anything in the empty backend. Updates are validated against
+/// the root signature but otherwise discarded.
+#[derive(Debug)]
+pub struct RafxDescriptorSetArrayEmpty {
+    root_signature: RafxRootSignature,
+    set_index: u32,
+    array_length: u32,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxDescriptorSetResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl RafxDescriptorSetArrayEmpty {
+    pub fn root_signature(&self) -> &RafxRootSignature {
+        &self.root_signature
+    }
+
+    pub fn set_index(&self) -> u32 {
+        self.set_index
+    }
+
+    pub fn array_length(&self) -> u32 {
+        self.array_length
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxDescriptorSetResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states_mut(&mut self) -> &mut RafxDescriptorSetResourceStates {
+        &mut self.resource_states
+    }
+
+    pub fn handle(
+        &self,
+        array_index: u32,
+    ) -> Option<RafxDescriptorSetHandleEmpty> {
+        if array_index < self.array_length {
+            Some(RafxDescriptorSetHandleEmpty {
+                set_index: self.set_index,
+                array_index,
+                #[cfg(feature = "capture")]
+                descriptor_set_array_id: self.capture_id,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn new(
+        _device_context: &RafxDeviceContextEmpty,
+        descriptor_set_array_def: &RafxDescriptorSetArrayDef,
+    ) -> RafxResult<Self> {
+        // Make sure the root signature belongs to this backend
+        descriptor_set_array_def
+            .root_signature
+            .empty_root_signature()
+            .unwrap();
+
+        Ok(RafxDescriptorSetArrayEmpty {
+            root_signature: descriptor_set_array_def.root_signature.clone(),
+            set_index: descriptor_set_array_def.set_index,
+            array_length: descriptor_set_array_def.array_length as u32,
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: RafxDescriptorSetResourceStates::new(
+                descriptor_set_array_def.set_index,
+            ),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        })
+    }
+
+    pub fn update_descriptor_set(
+        &mut self,
+        descriptor_updates: &[RafxDescriptorUpdate],
+    ) -> RafxResult<()> {
+        for update in descriptor_updates {
+            self.queue_descriptor_set_update(update)?;
+        }
+        self.flush_descriptor_set_updates()
+    }
+
+    pub fn flush_descriptor_set_updates(&mut self) -> RafxResult<()> {
+        // Don't need to do anything on flush
+        Ok(())
+    }
+
+    pub fn queue_descriptor_set_update(
+        &mut self,
+        update: &RafxDescriptorUpdate,
+    ) -> RafxResult<()> {
+        if update.array_index >= self.array_length {
+            return Err(format!(
+                "Descriptor set update array_index {} is out of range for array of length {}",
+                update.array_index, self.array_length
+            ))?;
+        }
+
+        let root_signature = self.root_signature.empty_root_signature().unwrap();
+        let descriptor_index = match &update.descriptor_key {
+            RafxDescriptorKey::Name(name) => root_signature.find_descriptor_by_name(name),
+            RafxDescriptorKey::Binding(binding) => {
+                root_signature.find_descriptor_by_binding(self.set_index, *binding)
+            }
+            RafxDescriptorKey::DescriptorIndex(descriptor_index) => Some(*descriptor_index),
+            RafxDescriptorKey::Undefined => {
+                return Err("Passed RafxDescriptorKey::Undefined to update_descriptor_set()")?
+            }
+        }
+        .ok_or_else(|| format!("Could not find descriptor {:?}", update.descriptor_key))?;
+
+        let descriptor = root_signature
+            .descriptor(descriptor_index)
+            .ok_or_else(|| format!("Could not find descriptor {:?}", update.descriptor_key))?;
+
+        if descriptor.set_index != self.set_index {
+            return Err(format!(
+                "Found descriptor {:?} but it's set_index ({:?}) does not match the set ({:?})",
+                &update.descriptor_key, descriptor.set_index, self.set_index
+            ))?;
+        }
+
+        crate::internal_shared::verify_descriptor_update_range(
+            update,
+            &descriptor.name,
+            descriptor.resource_type,
+            descriptor.element_count,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::empty_shader_module;
+    use crate::*;
+
+    #[test]
+    fn bindless_arrays_allow_sparse_updates() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let device_info = device_context.device_info();
+        assert!(device_info.supports_bindless_descriptors);
+
+        // An unbounded texture array at binding 0 and a uniform buffer at binding 1
+        let resource = |resource_type, binding, name: &str| RafxShaderResource {
+            resource_type,
+            set_index: 0,
+            binding,
+            element_count: 0,
+            size_in_bytes: 0,
+            used_in_shader_stages: RafxShaderStageFlags::FRAGMENT,
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        let shader_module = empty_shader_module(&device_context);
+        let shader = device_context
+            .create_shader(vec![RafxShaderStageDef {
+                shader_module,
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::FRAGMENT,
+                    resources: vec![
+                        resource(RafxResourceType::TEXTURE, 0, "textures"),
+                        resource(RafxResourceType::UNIFORM_BUFFER, 1, "config"),
+                    ],
+                    compute_threads_per_group: None,
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![],
+                },
+                specialization_constants: vec![],
+            }])
+            .unwrap();
+
+        let create_root_signature = |bindless_arrays: &[RafxBindlessArray]| {
+            device_context.create_root_signature(&RafxRootSignatureDef {
+                shaders: std::slice::from_ref(&shader),
+                immutable_samplers: &[],
+                bindless_arrays,
+            })
+        };
+
+        assert!(matches!(
+            create_root_signature(&[RafxBindlessArray::from_name("config", 16)]),
+            Err(RafxError::ReflectionMismatch { .. })
+        ));
+        assert!(matches!(
+            create_root_signature(&[RafxBindlessArray::from_name("missing", 16)]),
+            Err(RafxError::ReflectionMismatch { .. })
+        ));
+        assert!(matches!(
+            create_root_signature(&[RafxBindlessArray::from_binding(
+                0,
+                0,
+                device_info.max_bindless_descriptor_count + 1
+            )]),
+            Err(RafxError::InvalidArgument {
+                argument: "max_element_count",
+                ..
+            })
+        ));
+
+        let root_signature =
+            create_root_signature(&[RafxBindlessArray::from_name("textures", 1024)]).unwrap();
+        let mut descriptor_set_array = device_context
+            .create_descriptor_set_array(&RafxDescriptorSetArrayDef {
+                root_signature: &root_signature,
+                set_index: 0,
+                array_length: 1,
+            })
+            .unwrap();
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 1,
+                    height: 1,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Only the last element is written, the rest of the array is left unset
+        let textures = [&texture];
+        let update = |dst_element_offset| RafxDescriptorUpdate {
+            descriptor_key: RafxDescriptorKey::Name("textures"),
+            elements: RafxDescriptorElements {
+                textures: Some(&textures),
+                ..Default::default()
+            },
+            dst_element_offset,
+            ..Default::default()
+        };
+        descriptor_set_array
+            .update_descriptor_set(&[update(1023)])
+            .unwrap();
+        assert!(matches!(
+            descriptor_set_array.update_descriptor_set(&[update(1024)]),
+            Err(RafxError::InvalidArgument {
+                argument: "dst_element_offset",
+                ..
+            })
+        ));
+    }
+}
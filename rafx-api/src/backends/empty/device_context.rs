@@ -0,0 +1,602 @@
+#[cfg(feature = "capture")]
+use crate::capture::RafxCaptureRecorder;
+use crate::internal_shared::RafxMemoryUsageTracker;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxResourceStateTracker;
+use crate::{
+    RafxBufferDef, RafxComputePipelineDef, RafxDescriptorSetArrayDef, RafxDeviceContext,
+    RafxDeviceInfo, RafxFormat, RafxGraphicsPipelineDef, RafxHeapDef, RafxMemoryHeapStatistics,
+    RafxMemoryRequirements, RafxMemoryStatistics, RafxQueryPoolDef, RafxQueueType,
+    RafxResourceType, RafxResult, RafxRootSignatureDef, RafxSampleCount, RafxSamplerDef,
+    RafxShaderModuleDefEmpty, RafxShaderStageDef, RafxSwapchainDef, RafxTexture, RafxTextureDef,
+    RafxTextureViewDef,
+};
+use raw_window_handle::HasRawWindowHandle;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::empty::{
+    RafxBufferEmpty, RafxDescriptorSetArrayEmpty, RafxFenceEmpty, RafxHeapEmpty, RafxPipelineEmpty,
+    RafxQueryPoolEmpty, RafxQueueEmpty, RafxRootSignatureEmpty, RafxSamplerEmpty,
+    RafxSemaphoreEmpty, RafxShaderEmpty, RafxShaderModuleEmpty, RafxSwapchainEmpty,
+    RafxTextureEmpty, RafxTextureViewEmpty, RafxTimelineSemaphoreEmpty,
+};
+
+pub struct RafxDeviceContextEmptyInner {
+    pub(crate) device_info: RafxDeviceInfo,
+    // Timestamp queries are measured relative to this
+    created_at: Instant,
+    memory_usage_tracker: RafxMemoryUsageTracker,
+    #[cfg(feature = "validate-resource-states")]
+    resource_state_tracker: RafxResourceStateTracker,
+    #[cfg(feature = "capture")]
+    capture_recorder: RafxCaptureRecorder,
+}
+
+impl Drop for RafxDeviceContextEmptyInner {
+    fn drop(&mut self) {
+        log::trace!("destroying device");
+    }
+}
+
+impl RafxDeviceContextEmptyInner {
+    pub fn new() -> RafxResult<Self> {
+        // Values are chosen to be at least as strict as the real backends so that code exercised
+        // against the empty backend doesn't rely on unusually loose limits
+        let device_info = RafxDeviceInfo {
+            min_uniform_buffer_offset_alignment: 256,
+            min_storage_buffer_offset_alignment: 64,
+            upload_buffer_texture_alignment: 16,
+            upload_buffer_texture_row_alignment: 1,
+            supports_clamp_to_border_color: true,
+            supports_timestamp_queries: true,
+            supports_occlusion_queries: true,
+            supports_pipeline_statistics_queries: true,
+            // Timestamps are measured in nanoseconds since the device was created
+            timestamp_period_ns: 1.0,
+            supports_timeline_semaphores: true,
+            pipeline_cache_uuid: [0; 16],
+            // Limits are the minimums guaranteed by the vulkan spec
+            max_texture_dimension_2d: 4096,
+            max_texture_dimension_3d: 256,
+            max_texture_dimension_cube: 4096,
+            max_texture_array_layers: 256,
+            max_sampler_anisotropy: 16.0,
+            max_color_render_targets: 4,
+            max_vertex_input_bindings: 16,
+            max_vertex_attributes: 16,
+            max_compute_workgroup_size: [128, 128, 64],
+            max_compute_workgroup_invocations: 128,
+            max_compute_workgroup_count: [65535; 3],
+            supported_sample_counts: vec![
+                RafxSampleCount::SampleCount1,
+                RafxSampleCount::SampleCount4,
+            ],
+            // Texture data is never decoded, so any compressed format can be stored
+            supports_bc_texture_compression: true,
+            supports_astc_texture_compression: true,
+            supports_etc2_texture_compression: true,
+            // Not supported on metal
+            supports_geometry_shaders: false,
+            supports_tessellation_shaders: false,
+            supports_wide_lines: false,
+            max_line_width: 1.0,
+            supports_bindless_descriptors: true,
+            max_bindless_descriptor_count: 500_000,
+        };
+
+        Ok(RafxDeviceContextEmptyInner {
+            device_info,
+            created_at: Instant::now(),
+            memory_usage_tracker: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_state_tracker: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_recorder: Default::default(),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct RafxDeviceContextEmpty {
+    pub(crate) inner: Arc<RafxDeviceContextEmptyInner>,
+}
+
+impl std::fmt::Debug for RafxDeviceContextEmpty {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        f.debug_struct("RafxDeviceContextEmpty").finish()
+    }
+}
+
+impl Into<RafxDeviceContext> for RafxDeviceContextEmpty {
+    fn into(self) -> RafxDeviceContext {
+        RafxDeviceContext::Empty(self)
+    }
+}
+
+impl RafxDeviceContextEmpty {
+    pub fn device_info(&self) -> &RafxDeviceInfo {
+        &self.inner.device_info
+    }
+
+    // Reports a single heap that holds all host memory allocated for buffers and textures. It has
+    // no fixed size, so the budget is reported as unlimited
+    pub fn memory_statistics(&self) -> RafxResult<RafxMemoryStatistics> {
+        let mut memory_statistics = self.inner.memory_usage_tracker.memory_statistics();
+        let usage_bytes = memory_statistics.total_usage_statistics().allocated_bytes();
+        memory_statistics.heaps.push(RafxMemoryHeapStatistics {
+            device_local: true,
+            size_bytes: u64::MAX,
+            budget_bytes: u64::MAX,
+            usage_bytes,
+        });
+        Ok(memory_statistics)
+    }
+
+    // No pipeline cache is kept, so there is never any data to save or load
+    pub fn pipeline_cache_data(&self) -> RafxResult<Vec<u8>> {
+        Ok(Vec::default())
+    }
+
+    pub fn load_pipeline_cache_data(
+        &self,
+        _data: &[u8],
+    ) -> RafxResult<()> {
+        Ok(())
+    }
+
+    pub(crate) fn memory_usage_tracker(&self) -> &RafxMemoryUsageTracker {
+        &self.inner.memory_usage_tracker
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_state_tracker(&self) -> &RafxResourceStateTracker {
+        &self.inner.resource_state_tracker
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &RafxCaptureRecorder {
+        &self.inner.capture_recorder
+    }
+
+    // Current value of the timestamp counter, in nanoseconds since the device was created
+    pub(crate) fn current_timestamp(&self) -> u64 {
+        self.inner.created_at.elapsed().as_nanos() as u64
+    }
+
+    pub fn new(inner: Arc<RafxDeviceContextEmptyInner>) -> RafxResult<Self> {
+        Ok(RafxDeviceContextEmpty { inner })
+    }
+
+    pub fn create_queue(
+        &self,
+        queue_type: RafxQueueType,
+    ) -> RafxResult<RafxQueueEmpty> {
+        RafxQueueEmpty::new(self, queue_type)
+    }
+
+    pub fn create_fence(&self) -> RafxResult<RafxFenceEmpty> {
+        RafxFenceEmpty::new(self)
+    }
+
+    pub fn create_semaphore(&self) -> RafxResult<RafxSemaphoreEmpty> {
+        RafxSemaphoreEmpty::new(self)
+    }
+
+    pub fn create_timeline_semaphore(
+        &self,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreEmpty> {
+        RafxTimelineSemaphoreEmpty::new(self, initial_value)
+    }
+
+    pub fn create_swapchain(
+        &self,
+        _raw_window_handle: &dyn HasRawWindowHandle,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<RafxSwapchainEmpty> {
+        RafxSwapchainEmpty::new(self, swapchain_def)
+    }
+
+    pub fn wait_for_fences(
+        &self,
+        fences: &[&RafxFenceEmpty],
+    ) -> RafxResult<()> {
+        RafxFenceEmpty::wait_for_fences(self, fences)
+    }
+
+    pub fn create_query_pool(
+        &self,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolEmpty> {
+        RafxQueryPoolEmpty::new(self, query_pool_def)
+    }
+
+    pub fn create_sampler(
+        &self,
+        sampler_def: &RafxSamplerDef,
+    ) -> RafxResult<RafxSamplerEmpty> {
+        RafxSamplerEmpty::new(self, sampler_def)
+    }
+
+    pub fn create_texture(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureEmpty> {
+        RafxTextureEmpty::new(self, texture_def)
+    }
+
+    pub fn create_texture_view(
+        &self,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewEmpty> {
+        RafxTextureViewEmpty::new(self, texture, texture_view_def)
+    }
+
+    pub fn create_buffer(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBufferEmpty> {
+        RafxBufferEmpty::new(self, buffer_def)
+    }
+
+    pub fn create_heap(
+        &self,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeapEmpty> {
+        RafxHeapEmpty::new(self, heap_def)
+    }
+
+    pub fn texture_memory_requirements(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        RafxTextureEmpty::memory_requirements(self, texture_def)
+    }
+
+    pub fn buffer_memory_requirements(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        Ok(RafxBufferEmpty::memory_requirements(self, buffer_def))
+    }
+
+    pub fn create_texture_in_heap(
+        &self,
+        heap: &RafxHeapEmpty,
+        offset: u64,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureEmpty> {
+        RafxTextureEmpty::new_in_heap(self, heap, offset, texture_def)
+    }
+
+    pub fn create_buffer_in_heap(
+        &self,
+        heap: &RafxHeapEmpty,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBufferEmpty> {
+        RafxBufferEmpty::new_in_heap(self, heap, offset, buffer_def)
+    }
+
+    pub fn create_shader(
+        &self,
+        stages: Vec<RafxShaderStageDef>,
+    ) -> RafxResult<RafxShaderEmpty> {
+        RafxShaderEmpty::new(self, stages)
+    }
+
+    pub fn create_root_signature(
+        &self,
+        root_signature_def: &RafxRootSignatureDef,
+    ) -> RafxResult<RafxRootSignatureEmpty> {
+        RafxRootSignatureEmpty::new(self, root_signature_def)
+    }
+
+    pub fn create_descriptor_set_array(
+        &self,
+        descriptor_set_array_def: &RafxDescriptorSetArrayDef,
+    ) -> RafxResult<RafxDescriptorSetArrayEmpty> {
+        RafxDescriptorSetArrayEmpty::new(self, descriptor_set_array_def)
+    }
+
+    pub fn create_graphics_pipeline(
+        &self,
+        graphics_pipeline_def: &RafxGraphicsPipelineDef,
+    ) -> RafxResult<RafxPipelineEmpty> {
+        RafxPipelineEmpty::new_graphics_pipeline(self, graphics_pipeline_def)
+    }
+
+    pub fn create_compute_pipeline(
+        &self,
+        compute_pipeline_def: &RafxComputePipelineDef,
+    ) -> RafxResult<RafxPipelineEmpty> {
+        RafxPipelineEmpty::new_compute_pipeline(self, compute_pipeline_def)
+    }
+
+    pub fn create_shader_module(
+        &self,
+        data: RafxShaderModuleDefEmpty,
+    ) -> RafxResult<RafxShaderModuleEmpty> {
+        RafxShaderModuleEmpty::new(self, data)
+    }
+
+    pub fn find_supported_format(
+        &self,
+        candidates: &[RafxFormat],
+        _resource_type: RafxResourceType,
+    ) -> Option<RafxFormat> {
+        // Every format is supported since all resources live in host memory
+        candidates.iter().find(|x| !x.is_undefined()).copied()
+    }
+
+    pub fn find_supported_sample_count(
+        &self,
+        candidates: &[RafxSampleCount],
+    ) -> Option<RafxSampleCount> {
+        candidates.first().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::empty_shader_module;
+    use crate::*;
+
+    #[test]
+    fn create_fails_when_exceeding_device_limits() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let device_info = device_context.device_info();
+
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: device_info.max_texture_dimension_2d,
+                height: 1,
+                depth: 1,
+            },
+            format: RafxFormat::R8G8B8A8_UNORM,
+            ..Default::default()
+        };
+        device_context.create_texture(&texture_def).unwrap();
+        assert!(device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: device_info.max_texture_dimension_2d + 1,
+                    ..texture_def.extents
+                },
+                ..texture_def.clone()
+            })
+            .is_err());
+        assert!(device_context
+            .create_texture(&RafxTextureDef {
+                array_length: device_info.max_texture_array_layers + 1,
+                ..texture_def.clone()
+            })
+            .is_err());
+        assert!(!device_info.supports_sample_count(RafxSampleCount::SampleCount16));
+        assert!(device_context
+            .create_texture(&RafxTextureDef {
+                sample_count: RafxSampleCount::SampleCount16,
+                resource_type: RafxResourceType::RENDER_TARGET_COLOR,
+                ..texture_def
+            })
+            .is_err());
+
+        // Anisotropy above the limit is clamped rather than rejected
+        device_context
+            .create_sampler(&RafxSamplerDef {
+                max_anisotropy: device_info.max_sampler_anisotropy + 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(device_context
+            .create_sampler(&RafxSamplerDef {
+                max_anisotropy: -1.0,
+                ..Default::default()
+            })
+            .is_err());
+
+        let shader_module = empty_shader_module(&device_context);
+        let shader = device_context
+            .create_shader(vec![RafxShaderStageDef {
+                shader_module,
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::VERTEX,
+                    resources: vec![],
+                    compute_threads_per_group: None,
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![],
+                },
+                specialization_constants: vec![],
+            }])
+            .unwrap();
+        let root_signature = device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: std::slice::from_ref(&shader),
+                immutable_samplers: &[],
+                bindless_arrays: &[],
+            })
+            .unwrap();
+
+        let vertex_layout = RafxVertexLayout {
+            attributes: vec![],
+            buffers: vec![],
+        };
+        let color_formats =
+            vec![RafxFormat::R8G8B8A8_UNORM; device_info.max_color_render_targets as usize + 1];
+        let pipeline_def = RafxGraphicsPipelineDef {
+            shader: &shader,
+            root_signature: &root_signature,
+            vertex_layout: &vertex_layout,
+            blend_state: &Default::default(),
+            depth_state: &Default::default(),
+            rasterizer_state: &Default::default(),
+            primitive_topology: RafxPrimitiveTopology::TriangleList,
+            color_formats: &color_formats[1..],
+            depth_stencil_format: None,
+            sample_count: RafxSampleCount::SampleCount1,
+        };
+        device_context
+            .create_graphics_pipeline(&pipeline_def)
+            .unwrap();
+        assert!(device_context
+            .create_graphics_pipeline(&RafxGraphicsPipelineDef {
+                color_formats: &color_formats,
+                ..pipeline_def
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn memory_statistics_track_allocations() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+
+        let buffer = device_context
+            .create_buffer(&RafxBufferDef {
+                size: 64,
+                memory_usage: RafxMemoryUsage::CpuToGpu,
+                resource_type: RafxResourceType::VERTEX_BUFFER,
+                ..Default::default()
+            })
+            .unwrap();
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 4,
+                    height: 4,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let memory_statistics = device_context.memory_statistics().unwrap();
+        assert_eq!(
+            *memory_statistics.usage_statistics(RafxMemoryUsage::CpuToGpu),
+            RafxMemoryUsageStatistics {
+                buffer_count: 1,
+                buffer_bytes: 64,
+                texture_count: 0,
+                texture_bytes: 0,
+            }
+        );
+        assert_eq!(
+            *memory_statistics.usage_statistics(RafxMemoryUsage::GpuOnly),
+            RafxMemoryUsageStatistics {
+                buffer_count: 0,
+                buffer_bytes: 0,
+                texture_count: 1,
+                texture_bytes: 64,
+            }
+        );
+        assert_eq!(memory_statistics.heaps.len(), 1);
+        assert_eq!(memory_statistics.heaps[0].usage_bytes, 128);
+
+        std::mem::drop(buffer);
+        std::mem::drop(texture);
+
+        let memory_statistics = device_context.memory_statistics().unwrap();
+        assert_eq!(
+            memory_statistics.total_usage_statistics(),
+            RafxMemoryUsageStatistics::default()
+        );
+    }
+
+    #[test]
+    fn pipeline_cache_data_round_trip() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+
+        // The empty backend has no pipeline cache, so there is nothing to save and loading is
+        // accepted but has no effect
+        assert_eq!(device_context.device_info().pipeline_cache_uuid, [0; 16]);
+        let data = device_context.pipeline_cache_data().unwrap();
+        assert!(data.is_empty());
+        device_context.load_pipeline_cache_data(&data).unwrap();
+    }
+
+    #[test]
+    fn errors_have_matchable_variants() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let device_info = device_context.device_info();
+
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+            format: RafxFormat::R8G8B8A8_UNORM,
+            ..Default::default()
+        };
+        let result = device_context.create_texture(&RafxTextureDef {
+            extents: RafxExtents3D {
+                width: device_info.max_texture_dimension_2d + 1,
+                ..texture_def.extents
+            },
+            ..texture_def.clone()
+        });
+        assert!(matches!(
+            result,
+            Err(RafxError::InvalidArgument {
+                argument: "extents",
+                ..
+            })
+        ));
+
+        let result = device_context.create_texture(&RafxTextureDef {
+            format: RafxFormat::UNDEFINED,
+            ..texture_def
+        });
+        assert!(matches!(
+            result,
+            Err(RafxError::InvalidArgument {
+                argument: "format",
+                ..
+            })
+        ));
+
+        // The same binding is a texture in one stage and a buffer in the other
+        let stage = |shader_stage, resource_type| RafxShaderStageDef {
+            shader_module: empty_shader_module(&device_context),
+            reflection: RafxShaderStageReflection {
+                shader_stage,
+                resources: vec![RafxShaderResource {
+                    resource_type,
+                    set_index: 0,
+                    binding: 0,
+                    element_count: 1,
+                    size_in_bytes: 0,
+                    used_in_shader_stages: shader_stage,
+                    name: Some("material".to_string()),
+                    ..Default::default()
+                }],
+                compute_threads_per_group: None,
+                entry_point_name: "main".to_string(),
+                specialization_constants: vec![],
+            },
+            specialization_constants: vec![],
+        };
+        let result = device_context.create_shader(vec![
+            stage(RafxShaderStageFlags::VERTEX, RafxResourceType::TEXTURE),
+            stage(
+                RafxShaderStageFlags::FRAGMENT,
+                RafxResourceType::UNIFORM_BUFFER,
+            ),
+        ]);
+        match result {
+            Err(RafxError::ReflectionMismatch { name, .. }) => {
+                assert_eq!(name.as_deref(), Some("material"))
+            }
+            _ => panic!("expected a reflection mismatch"),
+        }
+    }
+}
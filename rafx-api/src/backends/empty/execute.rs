@@ -0,0 +1,1005 @@
+use super::texel;
+use crate::empty::{
+    RafxBufferEmpty, RafxColorRenderTargetEmpty, RafxDepthStencilRenderTargetEmpty,
+    RafxRecordedCommandEmpty, RafxTextureEmpty, RafxTextureViewEmpty,
+};
+use crate::{
+    RafxDeviceInfo, RafxExtents3D, RafxLoadOp, RafxResult, RafxTextureBufferLayout,
+    RafxTextureClearValue,
+};
+
+// Executes the transfer subset of recorded commands against host memory: buffer/texture copies,
+// blits, render target clears, and queries. Draws and dispatches have no effect.
+pub(crate) fn execute_commands(
+    device_info: &RafxDeviceInfo,
+    commands: &[RafxRecordedCommandEmpty],
+) -> RafxResult<()> {
+    for command in commands {
+        match command {
+            RafxRecordedCommandEmpty::BeginRenderPass {
+                color_targets,
+                depth_target,
+                ..
+            } => {
+                for color_target in color_targets {
+                    clear_color_target(color_target)?;
+                }
+
+                if let Some(depth_target) = depth_target {
+                    clear_depth_stencil_target(depth_target)?;
+                }
+            }
+            RafxRecordedCommandEmpty::ExecuteSecondaryCommandBuffers { command_buffers } => {
+                for command_buffer in command_buffers {
+                    execute_commands(device_info, command_buffer)?;
+                }
+            }
+            RafxRecordedCommandEmpty::ClearTexture {
+                texture,
+                clear_value,
+                mip_slice,
+                array_slice,
+            } => clear_texture(texture, *clear_value, *mip_slice, *array_slice)?,
+            RafxRecordedCommandEmpty::FillBuffer {
+                buffer,
+                offset,
+                size,
+                value,
+            } => unsafe {
+                let dst = buffer.host_memory_ptr().add(*offset as usize);
+                std::ptr::write_bytes(dst, *value, *size as usize);
+            },
+            RafxRecordedCommandEmpty::CopyBufferToBuffer {
+                src_buffer,
+                dst_buffer,
+                src_offset,
+                dst_offset,
+                size,
+            } => copy_buffer_to_buffer(src_buffer, dst_buffer, *src_offset, *dst_offset, *size),
+            RafxRecordedCommandEmpty::CopyBufferToTexture {
+                src_buffer,
+                dst_texture,
+                buffer_offset,
+                array_layer,
+                mip_level,
+            } => copy_buffer_to_texture(
+                device_info,
+                src_buffer,
+                dst_texture,
+                *buffer_offset,
+                *array_layer as u32,
+                *mip_level as u32,
+            )?,
+            RafxRecordedCommandEmpty::CopyTextureToBuffer {
+                src_texture,
+                dst_buffer,
+                buffer_offset,
+                array_layer,
+                mip_level,
+            } => copy_texture_to_buffer(
+                device_info,
+                src_texture,
+                dst_buffer,
+                *buffer_offset,
+                *array_layer as u32,
+                *mip_level as u32,
+            ),
+            RafxRecordedCommandEmpty::CopyTextureToTexture {
+                src_texture,
+                dst_texture,
+                src_offset,
+                dst_offset,
+                extents,
+                src_mip_level,
+                dst_mip_level,
+                array_slices,
+            } => {
+                let layers = if let Some(array_slices) = array_slices {
+                    vec![(array_slices[0] as u32, array_slices[1] as u32)]
+                } else {
+                    let layer_count = src_texture
+                        .texture_def()
+                        .array_length
+                        .min(dst_texture.texture_def().array_length);
+                    (0..layer_count).map(|x| (x, x)).collect()
+                };
+
+                for (src_layer, dst_layer) in layers {
+                    copy_texture_to_texture(
+                        src_texture,
+                        dst_texture,
+                        (*src_offset, *src_mip_level as u32, src_layer),
+                        (*dst_offset, *dst_mip_level as u32, dst_layer),
+                        *extents,
+                    );
+                }
+            }
+            RafxRecordedCommandEmpty::Blit {
+                src_texture,
+                dst_texture,
+                src_extents,
+                dst_extents,
+                src_mip_level,
+                dst_mip_level,
+                array_slices,
+            } => {
+                let layers = if let Some(array_slices) = array_slices {
+                    vec![(array_slices[0] as u32, array_slices[1] as u32)]
+                } else {
+                    let layer_count = src_texture
+                        .texture_def()
+                        .array_length
+                        .min(dst_texture.texture_def().array_length);
+                    (0..layer_count).map(|x| (x, x)).collect()
+                };
+
+                for (src_layer, dst_layer) in layers {
+                    blit_image(
+                        src_texture,
+                        dst_texture,
+                        src_extents,
+                        dst_extents,
+                        (*src_mip_level as u32, src_layer),
+                        (*dst_mip_level as u32, dst_layer),
+                    )?;
+                }
+            }
+            RafxRecordedCommandEmpty::ResetQueryPool {
+                query_pool,
+                first_query,
+                query_count,
+            } => query_pool.write_queries(*first_query, *query_count, 0),
+            RafxRecordedCommandEmpty::BeginQuery {
+                query_pool,
+                query_index,
+            } => {
+                // Nothing is rasterized or shaded, so all counters stay at zero
+                query_pool.write_queries(*query_index, 1, 0);
+            }
+            RafxRecordedCommandEmpty::WriteTimestamp {
+                query_pool,
+                query_index,
+            } => {
+                let timestamp = query_pool.device_context().current_timestamp();
+                query_pool.write_queries(*query_index, 1, timestamp);
+            }
+            RafxRecordedCommandEmpty::ResolveQuery {
+                query_pool,
+                first_query,
+                query_count,
+                dst_buffer,
+                dst_offset_in_bytes,
+            } => {
+                let values = query_pool.read_queries(*first_query, *query_count);
+                let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+                unsafe {
+                    let dst = dst_buffer
+                        .host_memory_ptr()
+                        .add(*dst_offset_in_bytes as usize);
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), dst, bytes.len());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// Array slice None means the render target covers every layer
+fn target_layers(
+    texture: &RafxTextureEmpty,
+    array_slice: Option<u16>,
+) -> std::ops::Range<u32> {
+    match array_slice {
+        Some(array_slice) => array_slice as u32..array_slice as u32 + 1,
+        None => 0..texture.texture_def().array_length,
+    }
+}
+
+// Returns the mip level and array layers a render target binding writes to
+fn render_target_subresources(
+    texture: &RafxTextureEmpty,
+    texture_view: Option<&RafxTextureViewEmpty>,
+    mip_slice: Option<u8>,
+    array_slice: Option<u16>,
+) -> (u32, std::ops::Range<u32>) {
+    if let Some(texture_view) = texture_view {
+        let texture_view_def = texture_view.texture_view_def();
+        (
+            texture_view_def.mip_range.as_ref().unwrap().start,
+            texture_view_def.array_range.clone().unwrap(),
+        )
+    } else {
+        (
+            mip_slice.unwrap_or(0) as u32,
+            target_layers(texture, array_slice),
+        )
+    }
+}
+
+// Writes a single encoded texel to every texel of a subresource
+fn fill_subresource(
+    texture: &RafxTextureEmpty,
+    mip_level: u32,
+    array_layer: u32,
+    texel: &[u8],
+) {
+    let layout = texture.subresource_layout(mip_level, array_layer);
+    let texel_size = texel.len();
+    unsafe {
+        let dst = std::slice::from_raw_parts_mut(
+            texture.host_memory_ptr().add(layout.offset as usize),
+            layout.size as usize,
+        );
+        for dst_texel in dst.chunks_exact_mut(texel_size) {
+            dst_texel.copy_from_slice(texel);
+        }
+    }
+}
+
+fn clear_color_target(color_target: &RafxColorRenderTargetEmpty) -> RafxResult<()> {
+    if color_target.load_op != RafxLoadOp::Clear {
+        return Ok(());
+    }
+
+    let texture = &color_target.texture;
+    // A view may reinterpret the format, so the clear value is encoded as the view sees it
+    let format = color_target
+        .texture_view
+        .as_ref()
+        .map(|x| x.texture_view_def().format.unwrap())
+        .unwrap_or(texture.texture_def().format);
+    let mut texel = vec![0; format.block_or_pixel_size_in_bytes() as usize];
+    texel::encode_color(format, color_target.clear_value.0, &mut texel)?;
+
+    let (mip_level, array_layers) = render_target_subresources(
+        texture,
+        color_target.texture_view.as_ref(),
+        color_target.mip_slice,
+        color_target.array_slice,
+    );
+    for array_layer in array_layers {
+        fill_subresource(texture, mip_level, array_layer, &texel);
+    }
+
+    Ok(())
+}
+
+fn clear_depth_stencil_target(depth_target: &RafxDepthStencilRenderTargetEmpty) -> RafxResult<()> {
+    let texture = &depth_target.texture;
+    let format = texture.texture_def().format;
+
+    let depth = if depth_target.depth_load_op == RafxLoadOp::Clear && format.has_depth() {
+        Some(depth_target.clear_value.depth)
+    } else {
+        None
+    };
+
+    let stencil = if depth_target.stencil_load_op == RafxLoadOp::Clear && format.has_stencil() {
+        Some(depth_target.clear_value.stencil as u8)
+    } else {
+        None
+    };
+
+    if depth.is_none() && stencil.is_none() {
+        return Ok(());
+    }
+
+    let (mip_level, array_layers) = render_target_subresources(
+        texture,
+        depth_target.texture_view.as_ref(),
+        depth_target.mip_slice,
+        depth_target.array_slice,
+    );
+    for array_layer in array_layers {
+        fill_depth_stencil_subresource(texture, mip_level, array_layer, depth, stencil)?;
+    }
+
+    Ok(())
+}
+
+// Depth and stencil may be cleared independently, so update texels in place
+fn fill_depth_stencil_subresource(
+    texture: &RafxTextureEmpty,
+    mip_level: u32,
+    array_layer: u32,
+    depth: Option<f32>,
+    stencil: Option<u8>,
+) -> RafxResult<()> {
+    let format = texture.texture_def().format;
+    let texel_size = format.block_or_pixel_size_in_bytes() as usize;
+    let layout = texture.subresource_layout(mip_level, array_layer);
+    let memory = unsafe {
+        std::slice::from_raw_parts_mut(
+            texture.host_memory_ptr().add(layout.offset as usize),
+            layout.size as usize,
+        )
+    };
+
+    for texel in memory.chunks_exact_mut(texel_size) {
+        texel::encode_depth_stencil(format, depth, stencil, texel)?;
+    }
+
+    Ok(())
+}
+
+// Mip/array slice None means every mip level/layer is cleared
+fn clear_texture(
+    texture: &RafxTextureEmpty,
+    clear_value: RafxTextureClearValue,
+    mip_slice: Option<u8>,
+    array_slice: Option<u16>,
+) -> RafxResult<()> {
+    let texture_def = texture.texture_def();
+    let format = texture_def.format;
+    let mip_levels = match mip_slice {
+        Some(mip_slice) => mip_slice as u32..mip_slice as u32 + 1,
+        None => 0..texture_def.mip_count,
+    };
+
+    match clear_value {
+        RafxTextureClearValue::Color(color) => {
+            let mut texel = vec![0; format.block_or_pixel_size_in_bytes() as usize];
+            texel::encode_color(format, color.0, &mut texel)?;
+            for mip_level in mip_levels {
+                for array_layer in target_layers(texture, array_slice) {
+                    fill_subresource(texture, mip_level, array_layer, &texel);
+                }
+            }
+        }
+        RafxTextureClearValue::DepthStencil(depth_stencil) => {
+            let depth = Some(depth_stencil.depth).filter(|_| format.has_depth());
+            let stencil = Some(depth_stencil.stencil as u8).filter(|_| format.has_stencil());
+            for mip_level in mip_levels {
+                for array_layer in target_layers(texture, array_slice) {
+                    fill_depth_stencil_subresource(
+                        texture,
+                        mip_level,
+                        array_layer,
+                        depth,
+                        stencil,
+                    )?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn copy_buffer_to_buffer(
+    src_buffer: &RafxBufferEmpty,
+    dst_buffer: &RafxBufferEmpty,
+    src_offset: u64,
+    dst_offset: u64,
+    size: u64,
+) {
+    // Bounds were checked when the command was recorded. The source and destination may be the
+    // same buffer, so use a copy that allows overlap.
+    unsafe {
+        std::ptr::copy(
+            src_buffer.host_memory_ptr().add(src_offset as usize),
+            dst_buffer.host_memory_ptr().add(dst_offset as usize),
+            size as usize,
+        );
+    }
+}
+
+fn copy_buffer_to_texture(
+    device_info: &RafxDeviceInfo,
+    src_buffer: &RafxBufferEmpty,
+    dst_texture: &RafxTextureEmpty,
+    buffer_offset: u64,
+    array_layer: u32,
+    mip_level: u32,
+) -> RafxResult<()> {
+    let layout = dst_texture.subresource_layout(mip_level, array_layer);
+
+    // Rows in the buffer are padded to the same alignment that the upload helpers use
+    let src_row_pitch = rafx_base::memory::round_size_up_to_alignment_u64(
+        layout.row_pitch,
+        device_info.upload_buffer_texture_row_alignment as u64,
+    );
+    let rows_per_image = layout.depth_pitch / layout.row_pitch;
+    let src_depth_pitch = rafx_base::memory::round_size_up_to_alignment_u64(
+        src_row_pitch * rows_per_image,
+        device_info.upload_buffer_texture_alignment as u64,
+    );
+
+    let required_size = buffer_offset
+        + src_depth_pitch * (layout.extents.depth as u64 - 1)
+        + src_row_pitch * (rows_per_image - 1)
+        + layout.row_pitch;
+    if required_size > src_buffer.buffer_def().size {
+        return Err(format!(
+            "cmd_copy_buffer_to_texture reads {} bytes from a buffer of size {}",
+            required_size,
+            src_buffer.buffer_def().size
+        ))?;
+    }
+
+    unsafe {
+        let src_base = src_buffer.host_memory_ptr().add(buffer_offset as usize);
+        let dst_base = dst_texture.host_memory_ptr().add(layout.offset as usize);
+        for z in 0..layout.extents.depth as u64 {
+            for row in 0..rows_per_image {
+                std::ptr::copy_nonoverlapping(
+                    src_base.add((z * src_depth_pitch + row * src_row_pitch) as usize),
+                    dst_base.add((z * layout.depth_pitch + row * layout.row_pitch) as usize),
+                    layout.row_pitch as usize,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Writes a subresource into a buffer using the padded layout of RafxTextureBufferLayout. The buffer
+// size was validated when the command was recorded.
+fn copy_texture_to_buffer(
+    device_info: &RafxDeviceInfo,
+    src_texture: &RafxTextureEmpty,
+    dst_buffer: &RafxBufferEmpty,
+    buffer_offset: u64,
+    array_layer: u32,
+    mip_level: u32,
+) {
+    let layout = src_texture.subresource_layout(mip_level, array_layer);
+    let texture_def = src_texture.texture_def();
+    let buffer_layout = RafxTextureBufferLayout::new(
+        device_info,
+        texture_def.format,
+        texture_def.extents,
+        mip_level as u8,
+    );
+
+    unsafe {
+        let src_base = src_texture.host_memory_ptr().add(layout.offset as usize);
+        let dst_base = dst_buffer.host_memory_ptr().add(buffer_offset as usize);
+        for z in 0..layout.extents.depth as u64 {
+            for row in 0..buffer_layout.rows_per_image as u64 {
+                std::ptr::copy_nonoverlapping(
+                    src_base.add((z * layout.depth_pitch + row * layout.row_pitch) as usize),
+                    dst_base.add(
+                        (z * buffer_layout.bytes_per_image as u64
+                            + row * buffer_layout.bytes_per_row as u64)
+                            as usize,
+                    ),
+                    buffer_layout.bytes_per_row_unpadded as usize,
+                );
+            }
+        }
+    }
+}
+
+// Copies a region between subresources of the same format. Regions were validated when the
+// command was recorded.
+fn copy_texture_to_texture(
+    src_texture: &RafxTextureEmpty,
+    dst_texture: &RafxTextureEmpty,
+    (src_offset, src_mip_level, src_layer): (RafxExtents3D, u32, u32),
+    (dst_offset, dst_mip_level, dst_layer): (RafxExtents3D, u32, u32),
+    extents: RafxExtents3D,
+) {
+    let format = src_texture.texture_def().format;
+    let block_width = format.block_width_in_pixels();
+    let block_height = format.block_height_in_pixels();
+    let block_size = format.block_or_pixel_size_in_bytes() as u64;
+
+    let src_layout = src_texture.subresource_layout(src_mip_level, src_layer);
+    let dst_layout = dst_texture.subresource_layout(dst_mip_level, dst_layer);
+
+    let blocks_wide = ((extents.width + block_width - 1) / block_width) as u64;
+    let blocks_high = (extents.height + block_height - 1) / block_height;
+    let row_size = (blocks_wide * block_size) as usize;
+
+    let texel_offset = |offset: RafxExtents3D, row_pitch: u64, depth_pitch: u64| {
+        (offset.width / block_width) as u64 * block_size
+            + (offset.height / block_height) as u64 * row_pitch
+            + offset.depth as u64 * depth_pitch
+    };
+
+    unsafe {
+        let src_base = src_texture.host_memory_ptr().add(
+            (src_layout.offset
+                + texel_offset(src_offset, src_layout.row_pitch, src_layout.depth_pitch))
+                as usize,
+        );
+        let dst_base = dst_texture.host_memory_ptr().add(
+            (dst_layout.offset
+                + texel_offset(dst_offset, dst_layout.row_pitch, dst_layout.depth_pitch))
+                as usize,
+        );
+
+        // The source and destination may be the same texture, so use copy rather than
+        // copy_nonoverlapping
+        for z in 0..extents.depth as u64 {
+            for row in 0..blocks_high as u64 {
+                std::ptr::copy(
+                    src_base
+                        .add((z * src_layout.depth_pitch + row * src_layout.row_pitch) as usize),
+                    dst_base
+                        .add((z * dst_layout.depth_pitch + row * dst_layout.row_pitch) as usize),
+                    row_size,
+                );
+            }
+        }
+    }
+}
+
+// Returns the two texels to interpolate between along an axis and the weight of the second one
+fn sample_axis(
+    coord: f32,
+    texel_count: u32,
+) -> (u32, u32, f32) {
+    let floor = coord.floor();
+    let clamp = |x: i64| x.max(0).min(texel_count as i64 - 1) as u32;
+    (clamp(floor as i64), clamp(floor as i64 + 1), coord - floor)
+}
+
+// Blits a region of one subresource to another using linear filtering. Regions are given as
+// [min, max) corners and may be flipped by swapping the corners.
+fn blit_image(
+    src_texture: &RafxTextureEmpty,
+    dst_texture: &RafxTextureEmpty,
+    src_extents: &[RafxExtents3D; 2],
+    dst_extents: &[RafxExtents3D; 2],
+    (src_mip_level, src_layer): (u32, u32),
+    (dst_mip_level, dst_layer): (u32, u32),
+) -> RafxResult<()> {
+    let src_format = src_texture.texture_def().format;
+    let dst_format = dst_texture.texture_def().format;
+    let src_texel_size = src_format.block_or_pixel_size_in_bytes() as u64;
+    let dst_texel_size = dst_format.block_or_pixel_size_in_bytes() as usize;
+    if src_format.block_width_in_pixels() != 1 || dst_format.block_width_in_pixels() != 1 {
+        return Err("cmd_blit does not support block-compressed formats")?;
+    }
+
+    let src_layout = src_texture.subresource_layout(src_mip_level, src_layer);
+    let dst_layout = dst_texture.subresource_layout(dst_mip_level, dst_layer);
+
+    let src_memory = unsafe {
+        std::slice::from_raw_parts(
+            src_texture
+                .host_memory_ptr()
+                .add(src_layout.offset as usize),
+            src_layout.size as usize,
+        )
+    };
+
+    let read_texel = |x: u32, y: u32, z: u32| -> RafxResult<[f32; 4]> {
+        let offset = z as u64 * src_layout.depth_pitch
+            + y as u64 * src_layout.row_pitch
+            + x as u64 * src_texel_size;
+        texel::decode_color(
+            src_format,
+            &src_memory[offset as usize..(offset + src_texel_size) as usize],
+        )
+    };
+
+    // Maps a destination coordinate to the source along one axis
+    let scale = |src: [u32; 2], dst: [u32; 2]| -> (f32, f32) {
+        let scale = (src[1] as f32 - src[0] as f32) / (dst[1] as f32 - dst[0] as f32);
+        (scale, src[0] as f32 - dst[0] as f32 * scale)
+    };
+
+    let (scale_x, offset_x) = scale(
+        [src_extents[0].width, src_extents[1].width],
+        [dst_extents[0].width, dst_extents[1].width],
+    );
+    let (scale_y, offset_y) = scale(
+        [src_extents[0].height, src_extents[1].height],
+        [dst_extents[0].height, dst_extents[1].height],
+    );
+    let (scale_z, offset_z) = scale(
+        [src_extents[0].depth, src_extents[1].depth],
+        [dst_extents[0].depth, dst_extents[1].depth],
+    );
+
+    let src_max = src_layout.extents;
+    let dst_range = |a: u32, b: u32, max: u32| a.min(b).min(max)..a.max(b).min(max);
+
+    let mut dst_texel = vec![0; dst_texel_size];
+    for z in dst_range(
+        dst_extents[0].depth,
+        dst_extents[1].depth,
+        dst_layout.extents.depth,
+    ) {
+        // Depth uses nearest filtering
+        let src_z = ((z as f32 + 0.5) * scale_z + offset_z).floor();
+        let src_z = (src_z.max(0.0) as u32).min(src_max.depth - 1);
+
+        for y in dst_range(
+            dst_extents[0].height,
+            dst_extents[1].height,
+            dst_layout.extents.height,
+        ) {
+            let (y0, y1, fy) =
+                sample_axis((y as f32 + 0.5) * scale_y + offset_y - 0.5, src_max.height);
+
+            for x in dst_range(
+                dst_extents[0].width,
+                dst_extents[1].width,
+                dst_layout.extents.width,
+            ) {
+                let (x0, x1, fx) =
+                    sample_axis((x as f32 + 0.5) * scale_x + offset_x - 0.5, src_max.width);
+
+                let c00 = read_texel(x0, y0, src_z)?;
+                let c10 = read_texel(x1, y0, src_z)?;
+                let c01 = read_texel(x0, y1, src_z)?;
+                let c11 = read_texel(x1, y1, src_z)?;
+
+                let mut color = [0.0; 4];
+                for channel in 0..4 {
+                    let top = c00[channel] + (c10[channel] - c00[channel]) * fx;
+                    let bottom = c01[channel] + (c11[channel] - c01[channel]) * fx;
+                    color[channel] = top + (bottom - top) * fy;
+                }
+
+                texel::encode_color(dst_format, color, &mut dst_texel)?;
+
+                let offset = z as u64 * dst_layout.depth_pitch
+                    + y as u64 * dst_layout.row_pitch
+                    + x as u64 * dst_texel_size as u64;
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        dst_texel.as_ptr(),
+                        dst_texture
+                            .host_memory_ptr()
+                            .add((dst_layout.offset + offset) as usize),
+                        dst_texel_size,
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::EmptyTestContext;
+    use crate::empty::RafxRecordedCommandEmpty;
+    use crate::*;
+
+    #[test]
+    fn render_pass_clears_targets() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let extents = RafxExtents3D {
+            width: 2,
+            height: 1,
+            depth: 1,
+        };
+        let color_texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents,
+                format: RafxFormat::B8G8R8A8_UNORM,
+                resource_type: RafxResourceType::RENDER_TARGET_COLOR,
+                ..Default::default()
+            })
+            .unwrap();
+        let depth_texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents,
+                format: RafxFormat::D32_SFLOAT,
+                resource_type: RafxResourceType::RENDER_TARGET_DEPTH_STENCIL,
+                ..Default::default()
+            })
+            .unwrap();
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_begin_render_pass(
+                &[RafxColorRenderTargetBinding {
+                    texture: &color_texture,
+                    load_op: RafxLoadOp::Clear,
+                    store_op: RafxStoreOp::Store,
+                    clear_value: RafxColorClearValue([1.0, 0.0, 0.0, 1.0]),
+                    mip_slice: None,
+                    array_slice: None,
+                    texture_view: None,
+                    resolve_target: None,
+                    resolve_store_op: RafxStoreOp::DontCare,
+                    resolve_mip_slice: None,
+                    resolve_array_slice: None,
+                }],
+                Some(RafxDepthStencilRenderTargetBinding {
+                    texture: &depth_texture,
+                    depth_load_op: RafxLoadOp::Clear,
+                    stencil_load_op: RafxLoadOp::DontCare,
+                    depth_store_op: RafxStoreOp::Store,
+                    stencil_store_op: RafxStoreOp::DontCare,
+                    clear_value: RafxDepthStencilClearValue {
+                        depth: 0.5,
+                        stencil: 0,
+                    },
+                    mip_slice: None,
+                    array_slice: None,
+                    texture_view: None,
+                }),
+            )
+            .unwrap();
+        command_buffer.cmd_end_render_pass().unwrap();
+        command_buffer.end().unwrap();
+        test_context.submit().unwrap();
+
+        // BGRA byte order
+        assert_eq!(
+            color_texture
+                .empty_texture()
+                .unwrap()
+                .read_subresource(0, 0),
+            vec![0, 0, 255, 255, 0, 0, 255, 255]
+        );
+
+        let depth_bytes = 0.5f32.to_le_bytes();
+        assert_eq!(
+            depth_texture
+                .empty_texture()
+                .unwrap()
+                .read_subresource(0, 0),
+            [depth_bytes, depth_bytes].concat()
+        );
+    }
+
+    #[test]
+    fn copy_texture_region() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: 4,
+                height: 4,
+                depth: 1,
+            },
+            format: RafxFormat::R8_UNORM,
+            ..Default::default()
+        };
+        let src_texture = device_context.create_texture(&texture_def).unwrap();
+        let dst_texture = device_context.create_texture(&texture_def).unwrap();
+        let other_format_texture = device_context
+            .create_texture(&RafxTextureDef {
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..texture_def
+            })
+            .unwrap();
+
+        let src_data: Vec<u8> = (0..16).collect();
+        let staging_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &src_data,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+        staging_buffer
+            .copy_to_host_visible_buffer(&src_data)
+            .unwrap();
+
+        let params = RafxCmdCopyTextureToTextureParams {
+            src_offset: RafxExtents3D {
+                width: 1,
+                height: 1,
+                depth: 0,
+            },
+            dst_offset: RafxExtents3D {
+                width: 2,
+                height: 0,
+                depth: 0,
+            },
+            extents: RafxExtents3D {
+                width: 2,
+                height: 2,
+                depth: 1,
+            },
+            ..Default::default()
+        };
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_copy_buffer_to_texture(&staging_buffer, &src_texture, &Default::default())
+            .unwrap();
+        command_buffer
+            .cmd_copy_texture_to_texture(&src_texture, &dst_texture, &params)
+            .unwrap();
+        assert!(command_buffer
+            .cmd_copy_texture_to_texture(&src_texture, &other_format_texture, &params)
+            .is_err());
+        assert!(command_buffer
+            .cmd_copy_texture_to_texture(
+                &src_texture,
+                &dst_texture,
+                &RafxCmdCopyTextureToTextureParams {
+                    dst_offset: RafxExtents3D {
+                        width: 3,
+                        height: 0,
+                        depth: 0,
+                    },
+                    ..params
+                },
+            )
+            .is_err());
+        command_buffer.end().unwrap();
+
+        test_context.submit().unwrap();
+
+        assert_eq!(
+            dst_texture.empty_texture().unwrap().read_subresource(0, 0),
+            vec![
+                0, 0, 5, 6, //
+                0, 0, 9, 10, //
+                0, 0, 0, 0, //
+                0, 0, 0, 0,
+            ]
+        );
+    }
+
+    #[test]
+    fn clear_texture_and_fill_buffer() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 2,
+                    height: 2,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                mip_count: 2,
+                ..Default::default()
+            })
+            .unwrap();
+        let buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer(
+                16,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_clear_texture(
+                &texture,
+                &RafxCmdClearTextureParams {
+                    texture_state: RafxResourceState::COPY_DST,
+                    clear_value: RafxTextureClearValue::Color(RafxColorClearValue([
+                        1.0, 0.0, 1.0, 1.0,
+                    ])),
+                    mip_slice: Some(1),
+                    array_slice: None,
+                },
+            )
+            .unwrap();
+        // Depth values can't be written to a color format
+        assert!(command_buffer
+            .cmd_clear_texture(
+                &texture,
+                &RafxCmdClearTextureParams {
+                    texture_state: RafxResourceState::COPY_DST,
+                    clear_value: RafxTextureClearValue::DepthStencil(Default::default()),
+                    mip_slice: None,
+                    array_slice: None,
+                },
+            )
+            .is_err());
+        command_buffer.cmd_fill_buffer(&buffer, 4, 8, 0xAB).unwrap();
+        assert!(command_buffer.cmd_fill_buffer(&buffer, 2, 8, 0xAB).is_err());
+        command_buffer.end().unwrap();
+
+        test_context.submit().unwrap();
+
+        let texture = texture.empty_texture().unwrap();
+        assert_eq!(texture.read_subresource(0, 0), vec![0; 16]);
+        assert_eq!(texture.read_subresource(1, 0), vec![255, 0, 255, 255]);
+
+        let mut buffer_data = [0; 16];
+        buffer_data[4..12].copy_from_slice(&[0xAB; 8]);
+        let buffer_ptr = buffer.map_buffer().unwrap();
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(buffer_ptr, 16) },
+            &buffer_data[..]
+        );
+        buffer.unmap_buffer().unwrap();
+    }
+
+    #[test]
+    fn execute_secondary_command_buffers() {
+        let mut test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+
+        let color_texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 2,
+                    height: 1,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                resource_type: RafxResourceType::RENDER_TARGET_COLOR,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let primary = &test_context.command_buffer;
+        let secondary = test_context
+            .command_pool
+            .create_command_buffer(&RafxCommandBufferDef { is_secondary: true })
+            .unwrap();
+
+        primary.begin().unwrap();
+        primary
+            .cmd_begin_render_pass_for_secondary(
+                &[RafxColorRenderTargetBinding {
+                    texture: &color_texture,
+                    load_op: RafxLoadOp::Clear,
+                    store_op: RafxStoreOp::Store,
+                    clear_value: RafxColorClearValue([0.0, 1.0, 0.0, 1.0]),
+                    mip_slice: None,
+                    array_slice: None,
+                    texture_view: None,
+                    resolve_target: None,
+                    resolve_store_op: RafxStoreOp::DontCare,
+                    resolve_mip_slice: None,
+                    resolve_array_slice: None,
+                }],
+                None,
+            )
+            .unwrap();
+        // Draws must be recorded into the secondary command buffers
+        assert!(primary.cmd_draw(3, 0).is_err());
+
+        let inheritance_info = RafxCommandBufferInheritanceInfo {
+            color_formats: &[RafxFormat::R8G8B8A8_UNORM],
+            depth_stencil_format: None,
+            sample_count: RafxSampleCount::SampleCount1,
+            primary_command_buffer: Some(primary),
+        };
+        assert!(primary.begin_secondary(&inheritance_info).is_err());
+        secondary.begin_secondary(&inheritance_info).unwrap();
+        secondary.cmd_set_scissor(0, 0, 2, 1).unwrap();
+        secondary.cmd_draw(3, 0).unwrap();
+        // The render pass belongs to the primary
+        assert!(secondary.cmd_end_render_pass().is_err());
+
+        // The secondary must end before it is executed
+        assert!(primary
+            .cmd_execute_secondary_command_buffers(&[&secondary])
+            .is_err());
+        secondary.end().unwrap();
+        primary
+            .cmd_execute_secondary_command_buffers(&[&secondary])
+            .unwrap();
+        primary.cmd_end_render_pass().unwrap();
+        primary.end().unwrap();
+        test_context.submit().unwrap();
+
+        let recorded_commands = test_context.recorded_commands();
+        assert_eq!(recorded_commands.len(), 3);
+        match &recorded_commands[1] {
+            RafxRecordedCommandEmpty::ExecuteSecondaryCommandBuffers { command_buffers } => {
+                assert_eq!(command_buffers.len(), 1);
+                assert_eq!(command_buffers[0].len(), 2);
+            }
+            _ => panic!("Expected an ExecuteSecondaryCommandBuffers command"),
+        }
+
+        assert_eq!(
+            color_texture
+                .empty_texture()
+                .unwrap()
+                .read_subresource(0, 0),
+            vec![0, 255, 0, 255, 0, 255, 0, 255]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{RafxFenceStatus, RafxResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Work submitted to the empty backend completes immediately, so a submitted fence is always
+/// signaled.
+pub struct RafxFenceEmpty {
+    _device_context: RafxDeviceContextEmpty,
+    // Set to true when an operation is scheduled to signal this fence
+    // Cleared when an operation is scheduled to consume this fence
+    submitted: AtomicBool,
+    // Used internally to identify the fence for validation
+    #[cfg(feature = "validate-resource-states")]
+    fence_id: u32,
+}
+
+impl RafxFenceEmpty {
+    pub fn new(device_context: &RafxDeviceContextEmpty) -> RafxResult<RafxFenceEmpty> {
+        Ok(RafxFenceEmpty {
+            _device_context: device_context.clone(),
+            submitted: AtomicBool::new(false),
+            #[cfg(feature = "validate-resource-states")]
+            fence_id: crate::internal_shared::NEXT_FENCE_ID.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn fence_id(&self) -> u32 {
+        self.fence_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self._device_context
+    }
+
+    pub(crate) fn submitted(&self) -> bool {
+        self.submitted.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_submitted(
+        &self,
+        available: bool,
+    ) {
+        self.submitted.store(available, Ordering::Relaxed);
+    }
+
+    pub fn wait(&self) -> RafxResult<()> {
+        self.set_submitted(false);
+        Ok(())
+    }
+
+    pub fn wait_for_fences(
+        _device_context: &RafxDeviceContextEmpty,
+        fences: &[&RafxFenceEmpty],
+    ) -> RafxResult<()> {
+        for fence in fences {
+            if fence.submitted() {
+                fence.wait()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_fence_status(&self) -> RafxResult<RafxFenceStatus> {
+        if !self.submitted() {
+            Ok(RafxFenceStatus::Unsubmitted)
+        } else {
+            self.set_submitted(false);
+            Ok(RafxFenceStatus::Complete)
+        }
+    }
+}
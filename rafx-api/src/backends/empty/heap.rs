@@ -0,0 +1,223 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{RafxHeapDef, RafxResult};
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+
+// Offsets of resources placed in a heap must be a multiple of this
+pub(crate) const HEAP_PLACEMENT_ALIGNMENT: u64 = 256;
+
+#[derive(Debug)]
+pub struct RafxHeapEmptyInner {
+    device_context: RafxDeviceContextEmpty,
+    heap_def: RafxHeapDef,
+    memory: UnsafeCell<Box<[u8]>>,
+}
+
+// for UnsafeCell, the rafx API does not synchronize access to heap memory
+unsafe impl Send for RafxHeapEmptyInner {}
+unsafe impl Sync for RafxHeapEmptyInner {}
+
+/// A heap backed by host memory. Placed buffers and textures read and write the heap's memory
+/// directly, so resources placed at overlapping offsets alias each other like they would on a GPU.
+#[derive(Clone, Debug)]
+pub struct RafxHeapEmpty {
+    inner: Arc<RafxHeapEmptyInner>,
+}
+
+impl RafxHeapEmpty {
+    pub fn heap_def(&self) -> &RafxHeapDef {
+        &self.inner.heap_def
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    // Pointer to the host memory, placed resources offset into it
+    pub(crate) fn host_memory_ptr(&self) -> *mut u8 {
+        unsafe { (&mut *self.inner.memory.get()).as_mut_ptr() }
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeapEmpty> {
+        if heap_def.size == 0 {
+            return Err("Cannot create a heap with a size of 0")?;
+        }
+
+        let memory = vec![0u8; heap_def.size as usize].into_boxed_slice();
+
+        let inner = RafxHeapEmptyInner {
+            device_context: device_context.clone(),
+            heap_def: heap_def.clone(),
+            memory: UnsafeCell::new(memory),
+        };
+
+        Ok(RafxHeapEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+}
+
+// Host memory that backs a buffer or texture
+#[derive(Debug)]
+pub(crate) enum RafxHostMemoryEmpty {
+    // Owned by the resource
+    Dedicated(UnsafeCell<Box<[u8]>>),
+    // A range of a heap's memory, shared with anything else placed in the same range
+    Placed {
+        heap: RafxHeapEmpty,
+        offset: u64,
+        size: u64,
+    },
+}
+
+impl RafxHostMemoryEmpty {
+    pub(crate) fn new_dedicated(size: u64) -> Self {
+        RafxHostMemoryEmpty::Dedicated(UnsafeCell::new(vec![0u8; size as usize].into_boxed_slice()))
+    }
+
+    pub(crate) fn is_placed(&self) -> bool {
+        match self {
+            RafxHostMemoryEmpty::Dedicated(_) => false,
+            RafxHostMemoryEmpty::Placed { .. } => true,
+        }
+    }
+
+    pub(crate) fn len(&self) -> u64 {
+        match self {
+            RafxHostMemoryEmpty::Dedicated(memory) => unsafe { (&*memory.get()).len() as u64 },
+            RafxHostMemoryEmpty::Placed { size, .. } => *size,
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *mut u8 {
+        match self {
+            RafxHostMemoryEmpty::Dedicated(memory) => unsafe { (&mut *memory.get()).as_mut_ptr() },
+            RafxHostMemoryEmpty::Placed { heap, offset, .. } => unsafe {
+                heap.host_memory_ptr().add(*offset as usize)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::EmptyTestContext;
+    use crate::*;
+
+    #[test]
+    fn placed_resources_alias_heap_memory() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        // Two buffers placed at the same offset share memory
+        let buffer_def = RafxBufferDef {
+            size: 16,
+            memory_usage: RafxMemoryUsage::CpuToGpu,
+            resource_type: RafxResourceType::VERTEX_BUFFER,
+            ..Default::default()
+        };
+        let buffer_requirements = device_context
+            .buffer_memory_requirements(&buffer_def)
+            .unwrap();
+        assert_eq!(buffer_requirements.size, 16);
+
+        let buffer_heap = device_context
+            .create_heap(&RafxHeapDef {
+                size: buffer_requirements.alignment * 2,
+                memory_usage: RafxMemoryUsage::CpuToGpu,
+            })
+            .unwrap();
+        let offset = buffer_requirements.alignment;
+        let buffer_a = device_context
+            .create_buffer_in_heap(&buffer_heap, offset, &buffer_def)
+            .unwrap();
+        let buffer_b = device_context
+            .create_buffer_in_heap(&buffer_heap, offset, &buffer_def)
+            .unwrap();
+        buffer_a.copy_to_host_visible_buffer(&[7u8; 16]).unwrap();
+        let data_b = unsafe { std::slice::from_raw_parts(buffer_b.map_buffer().unwrap(), 16) };
+        assert_eq!(data_b, &[7u8; 16]);
+
+        // Placement is validated against the heap
+        assert!(device_context
+            .create_buffer_in_heap(&buffer_heap, 1, &buffer_def)
+            .is_err());
+        assert!(device_context
+            .create_buffer_in_heap(&buffer_heap, offset * 2, &buffer_def)
+            .is_err());
+        assert!(device_context
+            .create_buffer_in_heap(
+                &buffer_heap,
+                0,
+                &RafxBufferDef {
+                    memory_usage: RafxMemoryUsage::GpuOnly,
+                    ..buffer_def.clone()
+                }
+            )
+            .is_err());
+
+        // A texture written by a copy is visible through another texture placed at the same offset
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: 2,
+                height: 2,
+                depth: 1,
+            },
+            format: RafxFormat::R8G8B8A8_UNORM,
+            ..Default::default()
+        };
+        assert!(device_context
+            .create_texture_in_heap(&buffer_heap, 0, &texture_def)
+            .is_err());
+
+        let texture_requirements = device_context
+            .texture_memory_requirements(&texture_def)
+            .unwrap();
+        assert_eq!(texture_requirements.size, 16);
+        let texture_heap = device_context
+            .create_heap(&RafxHeapDef {
+                size: texture_requirements.size,
+                memory_usage: RafxMemoryUsage::GpuOnly,
+            })
+            .unwrap();
+        let texture_a = device_context
+            .create_texture_in_heap(&texture_heap, 0, &texture_def)
+            .unwrap();
+        let texture_b = device_context
+            .create_texture_in_heap(&texture_heap, 0, &texture_def)
+            .unwrap();
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_copy_buffer_to_texture(
+                &buffer_a,
+                &texture_a,
+                &RafxCmdCopyBufferToTextureParams {
+                    buffer_offset: 0,
+                    array_layer: 0,
+                    mip_level: 0,
+                },
+            )
+            .unwrap();
+        command_buffer.end().unwrap();
+        test_context.submit().unwrap();
+
+        assert_eq!(
+            texture_b.empty_texture().unwrap().read_subresource(0, 0),
+            vec![7u8; 16]
+        );
+
+        // Placed resources don't allocate memory of their own
+        assert_eq!(
+            device_context
+                .memory_statistics()
+                .unwrap()
+                .total_usage_statistics(),
+            RafxMemoryUsageStatistics::default()
+        );
+    }
+}
@@ -0,0 +1,65 @@
+mod api;
+pub use api::*;
+
+mod device_context;
+pub use device_context::*;
+
+mod swapchain;
+pub use swapchain::*;
+
+mod texture;
+pub use texture::*;
+
+mod texture_view;
+pub use texture_view::*;
+
+mod semaphore;
+pub use semaphore::*;
+
+mod timeline_semaphore;
+pub use timeline_semaphore::*;
+
+mod fence;
+pub use fence::*;
+
+mod heap;
+pub use heap::*;
+
+mod query_pool;
+pub use query_pool::*;
+
+mod queue;
+pub use queue::*;
+
+mod command_pool;
+pub use command_pool::*;
+
+mod command_buffer;
+pub use command_buffer::*;
+
+mod buffer;
+pub use buffer::*;
+
+mod shader_module;
+pub use shader_module::*;
+
+mod shader;
+pub use shader::*;
+
+mod root_signature;
+pub use root_signature::*;
+
+mod descriptor_set_array;
+pub use descriptor_set_array::*;
+
+mod sampler;
+pub use sampler::*;
+
+mod pipeline;
+pub use pipeline::*;
+
+mod execute;
+mod texel;
+
+#[cfg(test)]
+pub(crate) mod test_util;
@@ -0,0 +1,123 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{
+    RafxComputePipelineDef, RafxFormat, RafxGraphicsPipelineDef, RafxPipelineType,
+    RafxPrimitiveTopology, RafxRasterizerState, RafxResult, RafxRootSignature, RafxSampleCount,
+    RafxShaderStageFlags,
+};
+use std::sync::Arc;
+
+/// Fixed-function state captured from a `RafxGraphicsPipelineDef`
+#[derive(Debug)]
+pub struct RafxGraphicsPipelineStateEmpty {
+    pub primitive_topology: RafxPrimitiveTopology,
+    pub rasterizer_state: RafxRasterizerState,
+    pub color_formats: Vec<RafxFormat>,
+    pub depth_stencil_format: Option<RafxFormat>,
+    pub sample_count: RafxSampleCount,
+}
+
+#[derive(Debug)]
+struct RafxPipelineEmptyInner {
+    pipeline_type: RafxPipelineType,
+    // It's a RafxRootSignatureEmpty, but stored as RafxRootSignature so we can return refs to it
+    root_signature: RafxRootSignature,
+    graphics_state: Option<RafxGraphicsPipelineStateEmpty>,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+/// Cloning produces another handle to the same pipeline so that recorded commands can refer to it
+#[derive(Clone, Debug)]
+pub struct RafxPipelineEmpty {
+    inner: Arc<RafxPipelineEmptyInner>,
+}
+
+impl RafxPipelineEmpty {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn pipeline_type(&self) -> RafxPipelineType {
+        self.inner.pipeline_type
+    }
+
+    pub fn root_signature(&self) -> &RafxRootSignature {
+        &self.inner.root_signature
+    }
+
+    /// Returns the fixed-function state, only set for graphics pipelines
+    pub fn graphics_state(&self) -> Option<&RafxGraphicsPipelineStateEmpty> {
+        self.inner.graphics_state.as_ref()
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Nothing to name, there is no GPU object
+    }
+
+    pub fn new_graphics_pipeline(
+        device_context: &RafxDeviceContextEmpty,
+        pipeline_def: &RafxGraphicsPipelineDef,
+    ) -> RafxResult<Self> {
+        pipeline_def
+            .blend_state
+            .verify(pipeline_def.color_formats.len());
+        pipeline_def.verify_device_limits(device_context.device_info())?;
+
+        let stage_flags = pipeline_def.shader.empty_shader().unwrap().stage_flags();
+        if !stage_flags.intersects(RafxShaderStageFlags::VERTEX) {
+            return Err("Graphics pipeline shader does not have a vertex stage")?;
+        }
+
+        if pipeline_def.root_signature.pipeline_type() != RafxPipelineType::Graphics {
+            return Err("Graphics pipeline created with a compute root signature")?;
+        }
+
+        let inner = RafxPipelineEmptyInner {
+            pipeline_type: RafxPipelineType::Graphics,
+            root_signature: pipeline_def.root_signature.clone(),
+            graphics_state: Some(RafxGraphicsPipelineStateEmpty {
+                primitive_topology: pipeline_def.primitive_topology,
+                rasterizer_state: pipeline_def.rasterizer_state.clone(),
+                color_formats: pipeline_def.color_formats.to_vec(),
+                depth_stencil_format: pipeline_def.depth_stencil_format,
+                sample_count: pipeline_def.sample_count,
+            }),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxPipelineEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn new_compute_pipeline(
+        _device_context: &RafxDeviceContextEmpty,
+        pipeline_def: &RafxComputePipelineDef,
+    ) -> RafxResult<Self> {
+        let stage_flags = pipeline_def.shader.empty_shader().unwrap().stage_flags();
+        if !stage_flags.intersects(RafxShaderStageFlags::COMPUTE) {
+            return Err("Compute pipeline shader does not have a compute stage")?;
+        }
+
+        if pipeline_def.root_signature.pipeline_type() != RafxPipelineType::Compute {
+            return Err("Compute pipeline created with a graphics root signature")?;
+        }
+
+        let inner = RafxPipelineEmptyInner {
+            pipeline_type: RafxPipelineType::Compute,
+            root_signature: pipeline_def.root_signature.clone(),
+            graphics_state: None,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxPipelineEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{RafxQueryPoolDef, RafxResult};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+pub struct RafxQueryPoolEmptyInner {
+    device_context: RafxDeviceContextEmpty,
+    query_pool_def: RafxQueryPoolDef,
+    // query_count * values_per_query values, written when command buffers are submitted
+    values: Mutex<Vec<u64>>,
+}
+
+/// Query results are produced when command buffers are submitted. Timestamps are real (in
+/// nanoseconds) but nothing is rasterized, so occlusion and pipeline statistics queries always
+/// report zero.
+#[derive(Clone, Debug)]
+pub struct RafxQueryPoolEmpty {
+    inner: Arc<RafxQueryPoolEmptyInner>,
+}
+
+impl PartialEq for RafxQueryPoolEmpty {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for RafxQueryPoolEmpty {}
+
+impl RafxQueryPoolEmpty {
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolEmpty> {
+        query_pool_def.verify();
+
+        let value_count = query_pool_def.query_count * query_pool_def.query_type.values_per_query();
+
+        let inner = RafxQueryPoolEmptyInner {
+            device_context: device_context.clone(),
+            query_pool_def: query_pool_def.clone(),
+            values: Mutex::new(vec![0; value_count as usize]),
+        };
+
+        Ok(RafxQueryPoolEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn query_pool_def(&self) -> &RafxQueryPoolDef {
+        &self.inner.query_pool_def
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    // Overwrites all values of the given queries
+    pub(crate) fn write_queries(
+        &self,
+        first_query: u32,
+        query_count: u32,
+        value: u64,
+    ) {
+        let values_per_query = self.inner.query_pool_def.query_type.values_per_query();
+        let begin = (first_query * values_per_query) as usize;
+        let end = ((first_query + query_count) * values_per_query) as usize;
+        for x in &mut self.inner.values.lock().unwrap()[begin..end] {
+            *x = value;
+        }
+    }
+
+    // Returns all values of the given queries
+    pub(crate) fn read_queries(
+        &self,
+        first_query: u32,
+        query_count: u32,
+    ) -> Vec<u64> {
+        let values_per_query = self.inner.query_pool_def.query_type.values_per_query();
+        let begin = (first_query * values_per_query) as usize;
+        let end = ((first_query + query_count) * values_per_query) as usize;
+        self.inner.values.lock().unwrap()[begin..end].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::EmptyTestContext;
+    use crate::*;
+
+    #[test]
+    fn resolve_timestamp_queries() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+        assert!(device_context.device_info().supports_timestamp_queries);
+
+        let query_pool = device_context
+            .create_query_pool(&RafxQueryPoolDef {
+                query_type: RafxQueryType::Timestamp,
+                query_count: 2,
+            })
+            .unwrap();
+        let readback_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &[0u64; 2],
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_reset_query_pool(&query_pool, 0, 2)
+            .unwrap();
+        command_buffer.cmd_write_timestamp(&query_pool, 0).unwrap();
+        command_buffer.cmd_write_timestamp(&query_pool, 1).unwrap();
+        // Out of range, and timestamp pools can't be used with begin/end
+        assert!(command_buffer.cmd_write_timestamp(&query_pool, 2).is_err());
+        assert!(command_buffer.cmd_begin_query(&query_pool, 0).is_err());
+        command_buffer
+            .cmd_resolve_query(&query_pool, 0, 2, &readback_buffer, 0)
+            .unwrap();
+        command_buffer.end().unwrap();
+
+        test_context.submit().unwrap();
+
+        let mut timestamps = [0u64; 2];
+        let ptr = readback_buffer.map_buffer().unwrap();
+        unsafe {
+            std::ptr::copy_nonoverlapping(ptr as *const u64, timestamps.as_mut_ptr(), 2);
+        }
+        readback_buffer.unmap_buffer().unwrap();
+        assert!(timestamps[0] <= timestamps[1]);
+    }
+}
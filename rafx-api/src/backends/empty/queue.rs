@@ -0,0 +1,336 @@
+use crate::empty::{
+    RafxCommandBufferEmpty, RafxCommandPoolEmpty, RafxDeviceContextEmpty, RafxFenceEmpty,
+    RafxSemaphoreEmpty, RafxSwapchainEmpty, RafxTimelineSemaphoreEmpty,
+};
+use crate::{RafxCommandPoolDef, RafxPresentSuccessResult, RafxQueueType, RafxResult};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+static NEXT_QUEUE_ID: AtomicU32 = AtomicU32::new(0);
+
+/// The timeline semaphore values a submit waited on and signaled. The empty backend runs work
+/// immediately, so these are kept to check how submits on different queues are ordered.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RafxSubmittedTimelineValuesEmpty {
+    pub wait_values: Vec<u64>,
+    pub signal_values: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub struct RafxQueueEmptyInner {
+    device_context: RafxDeviceContextEmpty,
+    queue_type: RafxQueueType,
+    queue_id: u32,
+    last_submitted_timeline_values: Mutex<RafxSubmittedTimelineValuesEmpty>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxQueueEmpty {
+    inner: Arc<RafxQueueEmptyInner>,
+}
+
+impl RafxQueueEmpty {
+    pub fn queue_id(&self) -> u32 {
+        self.inner.queue_id
+    }
+
+    pub fn queue_type(&self) -> RafxQueueType {
+        self.inner.queue_type
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    /// Returns the timeline semaphore values passed to the most recent submit on this queue
+    pub fn last_submitted_timeline_values(&self) -> RafxSubmittedTimelineValuesEmpty {
+        self.inner
+            .last_submitted_timeline_values
+            .lock()
+            .unwrap()
+            .clone()
+    }
+
+    pub fn create_command_pool(
+        &self,
+        command_pool_def: &RafxCommandPoolDef,
+    ) -> RafxResult<RafxCommandPoolEmpty> {
+        RafxCommandPoolEmpty::new(self, command_pool_def)
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        queue_type: RafxQueueType,
+    ) -> RafxResult<RafxQueueEmpty> {
+        let queue_id = NEXT_QUEUE_ID.fetch_add(1, Ordering::Relaxed);
+        let inner = RafxQueueEmptyInner {
+            device_context: device_context.clone(),
+            queue_type,
+            queue_id,
+            last_submitted_timeline_values: Default::default(),
+        };
+
+        Ok(RafxQueueEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn wait_for_queue_idle(&self) -> RafxResult<()> {
+        // All submitted work is already complete
+        Ok(())
+    }
+
+    pub fn submit(
+        &self,
+        command_buffers: &[&RafxCommandBufferEmpty],
+        wait_semaphores: &[&RafxSemaphoreEmpty],
+        signal_semaphores: &[&RafxSemaphoreEmpty],
+        wait_timeline_semaphores: &[(&RafxTimelineSemaphoreEmpty, u64)],
+        signal_timeline_semaphores: &[(&RafxTimelineSemaphoreEmpty, u64)],
+        signal_fence: Option<&RafxFenceEmpty>,
+    ) -> RafxResult<()> {
+        assert!(!command_buffers.is_empty());
+
+        for command_buffer in command_buffers {
+            if command_buffer.is_recording() {
+                return Err("Submitted a command buffer that is still being recorded")?;
+            }
+        }
+
+        // The work runs now, so anything it waits for must already be signaled
+        for (wait_semaphore, value) in wait_timeline_semaphores {
+            wait_semaphore.wait(*value)?;
+        }
+
+        // Transfers are executed on the CPU immediately, in submission order
+        let device_info = self.inner.device_context.device_info();
+        for command_buffer in command_buffers {
+            command_buffer.execute(device_info)?;
+        }
+
+        for wait_semaphore in wait_semaphores {
+            wait_semaphore.set_signal_available(false);
+        }
+
+        // Work "completes" immediately, so signal everything now
+        for signal_semaphore in signal_semaphores {
+            signal_semaphore.set_signal_available(true);
+        }
+
+        for (signal_semaphore, value) in signal_timeline_semaphores {
+            signal_semaphore.signal(*value)?;
+        }
+
+        if let Some(signal_fence) = signal_fence {
+            signal_fence.set_submitted(true);
+        }
+
+        *self.inner.last_submitted_timeline_values.lock().unwrap() =
+            RafxSubmittedTimelineValuesEmpty {
+                wait_values: wait_timeline_semaphores
+                    .iter()
+                    .map(|(_, value)| *value)
+                    .collect(),
+                signal_values: signal_timeline_semaphores
+                    .iter()
+                    .map(|(_, value)| *value)
+                    .collect(),
+            };
+
+        Ok(())
+    }
+
+    pub fn present(
+        &self,
+        _swapchain: &RafxSwapchainEmpty,
+        wait_semaphores: &[&RafxSemaphoreEmpty],
+        _image_index: u32,
+    ) -> RafxResult<RafxPresentSuccessResult> {
+        for wait_semaphore in wait_semaphores {
+            wait_semaphore.set_signal_available(false);
+        }
+
+        Ok(RafxPresentSuccessResult::Success)
+    }
+}
+
+#[cfg(all(test, feature = "validate-resource-states"))]
+mod tests {
+    use crate::empty::test_util::{empty_shader_module, EmptyTestContext};
+    use crate::*;
+
+    #[test]
+    fn resource_state_validation_detects_misuse() {
+        let test_context = EmptyTestContext::with_api_def(&RafxApiDef {
+            resource_state_validation_mode: RafxResourceStateValidationMode::Panic,
+            ..Default::default()
+        });
+        let device_context = &test_context.device_context;
+        let queue = &test_context.queue;
+        let command_buffer = &test_context.command_buffer;
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 1,
+                    height: 1,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // A compute shader that samples the texture
+        let shader_module = empty_shader_module(device_context);
+        let shader = device_context
+            .create_shader(vec![RafxShaderStageDef {
+                shader_module,
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::COMPUTE,
+                    resources: vec![RafxShaderResource {
+                        resource_type: RafxResourceType::TEXTURE,
+                        set_index: 0,
+                        binding: 0,
+                        element_count: 1,
+                        size_in_bytes: 0,
+                        used_in_shader_stages: RafxShaderStageFlags::COMPUTE,
+                        name: Some("tex".to_string()),
+                        ..Default::default()
+                    }],
+                    compute_threads_per_group: Some([1, 1, 1]),
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![],
+                },
+                specialization_constants: vec![],
+            }])
+            .unwrap();
+        let root_signature = device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: &[shader],
+                immutable_samplers: &[],
+                bindless_arrays: &[],
+            })
+            .unwrap();
+        let mut descriptor_set_array = device_context
+            .create_descriptor_set_array(&RafxDescriptorSetArrayDef {
+                root_signature: &root_signature,
+                set_index: 0,
+                array_length: 1,
+            })
+            .unwrap();
+        descriptor_set_array
+            .update_descriptor_set(&[RafxDescriptorUpdate {
+                descriptor_key: RafxDescriptorKey::Name("tex"),
+                elements: RafxDescriptorElements {
+                    textures: Some(&[&texture]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }])
+            .unwrap();
+
+        // Records the commands and returns true if submitting them passes validation
+        let submit = |f: &dyn Fn(&RafxCommandBuffer), fence: Option<&RafxFence>| {
+            command_buffer.begin().unwrap();
+            f(command_buffer);
+            command_buffer.end().unwrap();
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                queue.submit(&[command_buffer], &[], &[], fence).unwrap();
+            }))
+            .is_ok()
+        };
+        let transition = |command_buffer: &RafxCommandBuffer, src_state, dst_state| {
+            command_buffer
+                .cmd_resource_barrier(
+                    &[],
+                    &[RafxTextureBarrier::state_transition(
+                        &texture, src_state, dst_state,
+                    )],
+                )
+                .unwrap();
+        };
+        let sample = |command_buffer: &RafxCommandBuffer| {
+            command_buffer
+                .cmd_bind_descriptor_set(&descriptor_set_array, 0)
+                .unwrap();
+            command_buffer.cmd_dispatch(1, 1, 1).unwrap();
+        };
+
+        // The state is unknown until the first barrier, after that src_state must match
+        assert!(submit(
+            &|command_buffer| transition(
+                command_buffer,
+                RafxResourceState::COPY_DST,
+                RafxResourceState::RENDER_TARGET
+            ),
+            None
+        ));
+        assert!(!submit(
+            &|command_buffer| transition(
+                command_buffer,
+                RafxResourceState::COPY_DST,
+                RafxResourceState::SHADER_RESOURCE
+            ),
+            None
+        ));
+        assert!(submit(
+            &|command_buffer| transition(
+                command_buffer,
+                RafxResourceState::UNDEFINED,
+                RafxResourceState::RENDER_TARGET
+            ),
+            None
+        ));
+
+        // Sampling the texture is only valid once it leaves RENDER_TARGET
+        assert!(!submit(&sample, None));
+        assert!(submit(
+            &|command_buffer| {
+                transition(
+                    command_buffer,
+                    RafxResourceState::RENDER_TARGET,
+                    RafxResourceState::SHADER_RESOURCE,
+                );
+                sample(command_buffer);
+            },
+            None
+        ));
+
+        // Resources can't be destroyed until a submission referencing them is known to complete
+        let fill_buffer = |buffer: &RafxBuffer, fence| {
+            submit(
+                &|command_buffer| command_buffer.cmd_fill_buffer(buffer, 0, 16, 0).unwrap(),
+                Some(fence),
+            )
+        };
+        let create_buffer = || {
+            device_context
+                .create_buffer(&RafxBufferDef::for_staging_buffer(
+                    16,
+                    RafxResourceType::BUFFER,
+                ))
+                .unwrap()
+        };
+        let fence = device_context.create_fence().unwrap();
+
+        // The empty backend's recorded commands keep the buffer alive until the command buffer is
+        // begun again
+        let buffer = create_buffer();
+        assert!(fill_buffer(&buffer, &fence));
+        fence.wait().unwrap();
+        command_buffer.begin().unwrap();
+        drop(buffer);
+
+        let buffer = create_buffer();
+        assert!(fill_buffer(&buffer, &fence));
+        command_buffer.begin().unwrap();
+        let destroy = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || drop(buffer)));
+        assert!(destroy.is_err());
+
+        queue.wait_for_queue_idle().unwrap();
+        drop(descriptor_set_array);
+        drop(texture);
+    }
+}
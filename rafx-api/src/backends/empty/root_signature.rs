@@ -0,0 +1,225 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{
+    RafxDescriptorIndex, RafxError, RafxPipelineType, RafxPushConstantIndex, RafxResourceType,
+    RafxResult, RafxRootSignatureDef, MAX_DESCRIPTOR_SET_LAYOUTS,
+};
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub(crate) struct DescriptorInfo {
+    pub(crate) name: Option<String>,
+    pub(crate) resource_type: RafxResourceType,
+
+    // Also the set layout
+    pub(crate) set_index: u32,
+    // Used for arrays of textures, samplers, etc.
+    pub(crate) element_count: u32,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PushConstantInfo {
+    pub(crate) name: Option<String>,
+    pub(crate) size_in_bytes: u32,
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct DescriptorSetLayoutInfo {
+    // Settable descriptors, immutable samplers are omitted
+    pub(crate) descriptors: Vec<RafxDescriptorIndex>,
+    // Indexes binding index to the descriptors list
+    pub(crate) binding_to_descriptor_index: FnvHashMap<u32, RafxDescriptorIndex>,
+}
+
+#[derive(Debug)]
+pub(crate) struct RafxRootSignatureEmptyInner {
+    pub(crate) device_context: RafxDeviceContextEmpty,
+    pub(crate) pipeline_type: RafxPipelineType,
+    pub(crate) layouts: [DescriptorSetLayoutInfo; MAX_DESCRIPTOR_SET_LAYOUTS],
+    pub(crate) descriptors: Vec<DescriptorInfo>,
+    pub(crate) name_to_descriptor_index: FnvHashMap<String, RafxDescriptorIndex>,
+    pub(crate) push_constants: Vec<PushConstantInfo>,
+    pub(crate) name_to_push_constant_index: FnvHashMap<String, RafxPushConstantIndex>,
+    #[cfg(feature = "capture")]
+    pub(crate) capture_id: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxRootSignatureEmpty {
+    pub(crate) inner: Arc<RafxRootSignatureEmptyInner>,
+}
+
+impl RafxRootSignatureEmpty {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    pub fn pipeline_type(&self) -> RafxPipelineType {
+        self.inner.pipeline_type
+    }
+
+    pub fn find_descriptor_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxDescriptorIndex> {
+        self.inner.name_to_descriptor_index.get(name).copied()
+    }
+
+    pub fn find_descriptor_by_binding(
+        &self,
+        set_index: u32,
+        binding: u32,
+    ) -> Option<RafxDescriptorIndex> {
+        self.inner
+            .layouts
+            .get(set_index as usize)
+            .and_then(|x| x.binding_to_descriptor_index.get(&binding))
+            .copied()
+    }
+
+    pub fn find_push_constant_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxPushConstantIndex> {
+        self.inner.name_to_push_constant_index.get(name).copied()
+    }
+
+    pub(crate) fn push_constant(
+        &self,
+        push_constant_index: RafxPushConstantIndex,
+    ) -> Option<&PushConstantInfo> {
+        self.inner
+            .push_constants
+            .get(push_constant_index.0 as usize)
+    }
+
+    pub(crate) fn descriptor(
+        &self,
+        descriptor_index: RafxDescriptorIndex,
+    ) -> Option<&DescriptorInfo> {
+        self.inner.descriptors.get(descriptor_index.0 as usize)
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Nothing to name, there is no GPU object
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        root_signature_def: &RafxRootSignatureDef,
+    ) -> RafxResult<Self> {
+        log::trace!("Create RafxRootSignatureEmpty");
+
+        // Make sure all shaders are compatible/build lookup of shared data from them
+        let (pipeline_type, mut merged_resources, _merged_resources_name_index_map) =
+            crate::internal_shared::merge_resources(root_signature_def)?;
+
+        // Nothing is allocated per-element, so bindless arrays only change the element count
+        crate::internal_shared::apply_bindless_arrays(
+            root_signature_def,
+            device_context.device_info(),
+            &mut merged_resources,
+        )?;
+
+        merged_resources.sort_by(|lhs, rhs| lhs.binding.cmp(&rhs.binding));
+
+        let mut layouts: [DescriptorSetLayoutInfo; MAX_DESCRIPTOR_SET_LAYOUTS] = Default::default();
+        let mut descriptors = Vec::with_capacity(merged_resources.len());
+        let mut name_to_descriptor_index = FnvHashMap::default();
+        let mut push_constants = vec![];
+        let mut name_to_push_constant_index = FnvHashMap::default();
+
+        for resource in &merged_resources {
+            resource.validate()?;
+
+            // Push constants are not part of a descriptor set
+            if resource.resource_type == RafxResourceType::ROOT_CONSTANT {
+                let push_constant_index = RafxPushConstantIndex(push_constants.len() as u32);
+                push_constants.push(PushConstantInfo {
+                    name: resource.name.clone(),
+                    size_in_bytes: resource.size_in_bytes,
+                });
+
+                if let Some(name) = resource.name.as_ref() {
+                    name_to_push_constant_index.insert(name.clone(), push_constant_index);
+                }
+
+                continue;
+            }
+
+            let immutable_sampler = crate::internal_shared::find_immutable_sampler_index(
+                root_signature_def.immutable_samplers,
+                &resource.name,
+                resource.set_index,
+                resource.binding,
+            );
+
+            // Check that if an immutable sampler is set, the array size matches the resource element count
+            if let Some(immutable_sampler_index) = immutable_sampler {
+                if resource.element_count_normalized() as usize
+                    != root_signature_def.immutable_samplers[immutable_sampler_index]
+                        .samplers
+                        .len()
+                {
+                    Err(RafxError::reflection_mismatch(
+                        resource.name.as_deref(),
+                        format!(
+                            "Descriptor (set={:?} binding={:?}) named {:?} specifies {} elements but the count of provided immutable samplers ({}) did not match",
+                            resource.set_index,
+                            resource.binding,
+                            resource.name,
+                            resource.element_count_normalized(),
+                            root_signature_def.immutable_samplers[immutable_sampler_index].samplers.len()
+                        ),
+                    ))?;
+                }
+
+                // Immutable samplers can't be updated, so they are not added to the descriptor list
+                continue;
+            }
+
+            let layout = &mut layouts[resource.set_index as usize];
+            let descriptor_index = RafxDescriptorIndex(descriptors.len() as u32);
+
+            descriptors.push(DescriptorInfo {
+                name: resource.name.clone(),
+                resource_type: resource.resource_type,
+                set_index: resource.set_index,
+                element_count: resource.element_count_normalized(),
+            });
+
+            if let Some(name) = resource.name.as_ref() {
+                name_to_descriptor_index.insert(name.clone(), descriptor_index);
+            }
+
+            layout.descriptors.push(descriptor_index);
+            layout
+                .binding_to_descriptor_index
+                .insert(resource.binding, descriptor_index);
+        }
+
+        let inner = RafxRootSignatureEmptyInner {
+            device_context: device_context.clone(),
+            pipeline_type,
+            layouts,
+            descriptors,
+            name_to_descriptor_index,
+            push_constants,
+            name_to_push_constant_index,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxRootSignatureEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+}
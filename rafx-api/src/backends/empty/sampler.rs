@@ -0,0 +1,56 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{RafxResult, RafxSamplerDef};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxSamplerEmptyInner {
+    device_context: RafxDeviceContextEmpty,
+    sampler_def: RafxSamplerDef,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RafxSamplerEmpty {
+    inner: Arc<RafxSamplerEmptyInner>,
+}
+
+impl RafxSamplerEmpty {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    pub fn sampler_def(&self) -> &RafxSamplerDef {
+        &self.inner.sampler_def
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Nothing to name, there is no GPU object
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        sampler_def: &RafxSamplerDef,
+    ) -> RafxResult<RafxSamplerEmpty> {
+        sampler_def.verify_device_limits(device_context.device_info())?;
+
+        let inner = RafxSamplerEmptyInner {
+            device_context: device_context.clone(),
+            sampler_def: sampler_def.clone(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxSamplerEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+}
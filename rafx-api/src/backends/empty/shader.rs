@@ -0,0 +1,121 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{RafxPipelineReflection, RafxResult, RafxShaderStageDef, RafxShaderStageFlags};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct RafxShaderEmptyInner {
+    stage_flags: RafxShaderStageFlags,
+    stages: Vec<RafxShaderStageDef>,
+    pipeline_reflection: RafxPipelineReflection,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxShaderEmpty {
+    inner: Arc<RafxShaderEmptyInner>,
+}
+
+impl RafxShaderEmpty {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextEmpty,
+        stages: Vec<RafxShaderStageDef>,
+    ) -> RafxResult<Self> {
+        let pipeline_reflection = RafxPipelineReflection::from_stages(&stages)?;
+        let mut stage_flags = RafxShaderStageFlags::empty();
+        for stage in &stages {
+            stage_flags |= stage.reflection.shader_stage;
+        }
+
+        let inner = RafxShaderEmptyInner {
+            stages,
+            pipeline_reflection,
+            stage_flags,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxShaderEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn stages(&self) -> &[RafxShaderStageDef] {
+        &self.inner.stages
+    }
+
+    pub fn pipeline_reflection(&self) -> &RafxPipelineReflection {
+        &self.inner.pipeline_reflection
+    }
+
+    pub fn stage_flags(&self) -> RafxShaderStageFlags {
+        self.inner.stage_flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::empty_shader_module;
+    use crate::*;
+
+    #[test]
+    fn specialization_constants_are_validated() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+
+        let create_shader = |specialization_constants: Vec<RafxSpecializationConstant>| {
+            device_context.create_shader(vec![RafxShaderStageDef {
+                shader_module: empty_shader_module(&device_context),
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::FRAGMENT,
+                    resources: vec![],
+                    compute_threads_per_group: None,
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![RafxSpecializationConstantReflection {
+                        name: Some("SHADOW_PCF".to_string()),
+                        constant_id: 3,
+                        default_value: RafxSpecializationConstantValue::Bool(false),
+                    }],
+                },
+                specialization_constants,
+            }])
+        };
+
+        let constant = |constant_id, value| RafxSpecializationConstant { constant_id, value };
+
+        // Leaving constants at their defaults and overriding with the declared type are both fine
+        create_shader(vec![]).unwrap();
+        create_shader(vec![constant(
+            3,
+            RafxSpecializationConstantValue::Bool(true),
+        )])
+        .unwrap();
+
+        assert!(matches!(
+            create_shader(vec![constant(3, RafxSpecializationConstantValue::Int(1))]),
+            Err(RafxError::ReflectionMismatch { .. })
+        ));
+        assert!(matches!(
+            create_shader(vec![constant(
+                4,
+                RafxSpecializationConstantValue::Bool(true)
+            )]),
+            Err(RafxError::ReflectionMismatch { .. })
+        ));
+        assert!(matches!(
+            create_shader(vec![
+                constant(3, RafxSpecializationConstantValue::Bool(true)),
+                constant(3, RafxSpecializationConstantValue::Bool(false)),
+            ]),
+            Err(RafxError::InvalidArgument {
+                argument: "specialization_constants",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::{RafxResult, RafxShaderModule, RafxShaderModuleDefEmpty};
+
+/// Shader code is never executed by the empty backend, so shader modules hold no data
+#[derive(Clone, Debug)]
+pub struct RafxShaderModuleEmpty {
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl RafxShaderModuleEmpty {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextEmpty,
+        _data: RafxShaderModuleDefEmpty,
+    ) -> RafxResult<Self> {
+        Ok(RafxShaderModuleEmpty {
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        })
+    }
+}
+
+impl Into<RafxShaderModule> for RafxShaderModuleEmpty {
+    fn into(self) -> RafxShaderModule {
+        RafxShaderModule::Empty(self)
+    }
+}
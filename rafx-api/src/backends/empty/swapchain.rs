@@ -0,0 +1,119 @@
+use crate::empty::{RafxDeviceContextEmpty, RafxFenceEmpty, RafxSemaphoreEmpty, RafxTextureEmpty};
+use crate::{
+    RafxExtents3D, RafxFormat, RafxResourceType, RafxResult, RafxSampleCount, RafxSwapchainDef,
+    RafxSwapchainImage, RafxTexture, RafxTextureDef, RafxTextureDimensions,
+};
+
+const SWAPCHAIN_IMAGE_COUNT: u32 = 3;
+const SWAPCHAIN_FORMAT: RafxFormat = RafxFormat::B8G8R8A8_SRGB;
+
+/// A swapchain backed by plain render target textures. No window is presented to, images are
+/// handed out round-robin.
+pub struct RafxSwapchainEmpty {
+    device_context: RafxDeviceContextEmpty,
+    swapchain_def: RafxSwapchainDef,
+    swapchain_images: Vec<RafxTextureEmpty>,
+    next_swapchain_image_index: u32,
+}
+
+impl RafxSwapchainEmpty {
+    pub fn swapchain_def(&self) -> &RafxSwapchainDef {
+        &self.swapchain_def
+    }
+
+    pub fn image_count(&self) -> usize {
+        self.swapchain_images.len()
+    }
+
+    pub fn format(&self) -> RafxFormat {
+        SWAPCHAIN_FORMAT
+    }
+
+    /// Returns the texture that backs the given swapchain image
+    pub fn swapchain_image(
+        &self,
+        image_index: u32,
+    ) -> &RafxTextureEmpty {
+        &self.swapchain_images[image_index as usize]
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<RafxSwapchainEmpty> {
+        let swapchain_images = Self::create_swapchain_images(device_context, swapchain_def)?;
+
+        Ok(RafxSwapchainEmpty {
+            device_context: device_context.clone(),
+            swapchain_def: swapchain_def.clone(),
+            swapchain_images,
+            next_swapchain_image_index: 0,
+        })
+    }
+
+    fn create_swapchain_images(
+        device_context: &RafxDeviceContextEmpty,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<Vec<RafxTextureEmpty>> {
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: swapchain_def.width,
+                height: swapchain_def.height,
+                depth: 1,
+            },
+            array_length: 1,
+            mip_count: 1,
+            format: SWAPCHAIN_FORMAT,
+            resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+            sample_count: RafxSampleCount::SampleCount1,
+            dimensions: RafxTextureDimensions::Dim2D,
+        };
+
+        (0..SWAPCHAIN_IMAGE_COUNT)
+            .map(|_| RafxTextureEmpty::new(device_context, &texture_def))
+            .collect()
+    }
+
+    pub fn rebuild(
+        &mut self,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<()> {
+        self.swapchain_images = Self::create_swapchain_images(&self.device_context, swapchain_def)?;
+        self.swapchain_def = swapchain_def.clone();
+        self.next_swapchain_image_index = 0;
+        Ok(())
+    }
+
+    fn acquire_next_image(&mut self) -> RafxSwapchainImage {
+        let swapchain_image_index = self.next_swapchain_image_index;
+        self.next_swapchain_image_index += 1;
+        if self.next_swapchain_image_index >= SWAPCHAIN_IMAGE_COUNT {
+            self.next_swapchain_image_index = 0;
+        }
+
+        RafxSwapchainImage {
+            texture: RafxTexture::Empty(
+                self.swapchain_images[swapchain_image_index as usize].clone(),
+            ),
+            swapchain_image_index,
+        }
+    }
+
+    pub fn acquire_next_image_fence(
+        &mut self,
+        fence: &RafxFenceEmpty,
+    ) -> RafxResult<RafxSwapchainImage> {
+        let swapchain_image = self.acquire_next_image();
+        fence.set_submitted(true);
+        Ok(swapchain_image)
+    }
+
+    pub fn acquire_next_image_semaphore(
+        &mut self,
+        semaphore: &RafxSemaphoreEmpty,
+    ) -> RafxResult<RafxSwapchainImage> {
+        let swapchain_image = self.acquire_next_image();
+        semaphore.set_signal_available(true);
+        Ok(swapchain_image)
+    }
+}
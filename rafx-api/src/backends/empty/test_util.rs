@@ -0,0 +1,69 @@
+use crate::empty::RafxRecordedCommandEmpty;
+use crate::*;
+
+/// An api with a graphics queue and a primary command buffer, which most of the empty backend's
+/// tests start from. Fields drop in order, so the api is destroyed after everything created from it.
+pub(crate) struct EmptyTestContext {
+    pub(crate) command_buffer: RafxCommandBuffer,
+    pub(crate) command_pool: RafxCommandPool,
+    pub(crate) queue: RafxQueue,
+    pub(crate) device_context: RafxDeviceContext,
+    _api: RafxApi,
+}
+
+impl EmptyTestContext {
+    pub(crate) fn new() -> Self {
+        Self::with_api_def(&Default::default())
+    }
+
+    pub(crate) fn with_api_def(api_def: &RafxApiDef) -> Self {
+        let api = RafxApi::new_empty(api_def, &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+        let mut command_pool = queue
+            .create_command_pool(&RafxCommandPoolDef { transient: true })
+            .unwrap();
+        let command_buffer = command_pool
+            .create_command_buffer(&RafxCommandBufferDef {
+                is_secondary: false,
+            })
+            .unwrap();
+
+        EmptyTestContext {
+            command_buffer,
+            command_pool,
+            queue,
+            device_context,
+            _api: api,
+        }
+    }
+
+    /// Submits the command buffer to the queue without waiting on or signaling anything
+    pub(crate) fn submit(&self) -> RafxResult<()> {
+        self.queue.submit(&[&self.command_buffer], &[], &[], None)
+    }
+
+    pub(crate) fn recorded_commands(&self) -> Vec<RafxRecordedCommandEmpty> {
+        self.command_buffer
+            .empty_command_buffer()
+            .unwrap()
+            .recorded_commands()
+    }
+}
+
+/// The empty backend doesn't run shaders, so a module only needs to exist for the reflection data
+pub(crate) fn empty_shader_module(device_context: &RafxDeviceContext) -> RafxShaderModule {
+    device_context
+        .create_shader_module(RafxShaderModuleDef {
+            empty: Some(RafxShaderModuleDefEmpty::Empty(Default::default())),
+            #[cfg(feature = "rafx-vulkan")]
+            vk: None,
+            #[cfg(feature = "rafx-metal")]
+            metal: None,
+            #[cfg(feature = "rafx-gles3")]
+            gles3: None,
+        })
+        .unwrap()
+}
@@ -0,0 +1,177 @@
+use crate::{RafxError, RafxFormat, RafxResult};
+
+// Conversion between texels stored in host memory and linear floating point values. Only the
+// uncompressed formats that are commonly used for color/depth targets and uploaded images are
+// supported.
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn unorm8_to_f32(value: u8) -> f32 {
+    value as f32 / 255.0
+}
+
+fn f32_to_unorm8(value: f32) -> u8 {
+    (value.max(0.0).min(1.0) * 255.0 + 0.5) as u8
+}
+
+// Returns (channel count, is srgb, is bgr ordered) for 8-bit normalized formats
+fn unorm8_layout(format: RafxFormat) -> Option<(usize, bool, bool)> {
+    match format {
+        RafxFormat::R8_UNORM => Some((1, false, false)),
+        RafxFormat::R8_SRGB => Some((1, true, false)),
+        RafxFormat::R8G8_UNORM => Some((2, false, false)),
+        RafxFormat::R8G8_SRGB => Some((2, true, false)),
+        RafxFormat::R8G8B8A8_UNORM => Some((4, false, false)),
+        RafxFormat::R8G8B8A8_SRGB => Some((4, true, false)),
+        RafxFormat::B8G8R8A8_UNORM => Some((4, false, true)),
+        RafxFormat::B8G8R8A8_SRGB => Some((4, true, true)),
+        _ => None,
+    }
+}
+
+fn sfloat32_channel_count(format: RafxFormat) -> Option<usize> {
+    match format {
+        RafxFormat::R32_SFLOAT => Some(1),
+        RafxFormat::R32G32_SFLOAT => Some(2),
+        RafxFormat::R32G32B32_SFLOAT => Some(3),
+        RafxFormat::R32G32B32A32_SFLOAT => Some(4),
+        _ => None,
+    }
+}
+
+// Returned for formats that don't have a texel conversion implemented
+fn unsupported_format(format: RafxFormat) -> RafxError {
+    RafxError::UnsupportedFormat(format)
+}
+
+/// Decodes a single texel into linear RGBA. Missing channels are filled with 0, missing alpha is 1
+pub(crate) fn decode_color(
+    format: RafxFormat,
+    texel: &[u8],
+) -> RafxResult<[f32; 4]> {
+    let mut color = [0.0, 0.0, 0.0, 1.0];
+    if let Some((channel_count, is_srgb, is_bgr)) = unorm8_layout(format) {
+        for channel in 0..channel_count {
+            let value = unorm8_to_f32(texel[channel]);
+            // Alpha is never sRGB encoded
+            color[channel] = if is_srgb && channel < 3 {
+                srgb_to_linear(value)
+            } else {
+                value
+            };
+        }
+
+        if is_bgr {
+            color.swap(0, 2);
+        }
+    } else if let Some(channel_count) = sfloat32_channel_count(format) {
+        for channel in 0..channel_count {
+            let offset = channel * 4;
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&texel[offset..offset + 4]);
+            color[channel] = f32::from_le_bytes(bytes);
+        }
+    } else {
+        return Err(unsupported_format(format));
+    }
+
+    Ok(color)
+}
+
+/// Encodes linear RGBA into a single texel
+pub(crate) fn encode_color(
+    format: RafxFormat,
+    mut color: [f32; 4],
+    texel: &mut [u8],
+) -> RafxResult<()> {
+    if let Some((channel_count, is_srgb, is_bgr)) = unorm8_layout(format) {
+        if is_bgr {
+            color.swap(0, 2);
+        }
+
+        for channel in 0..channel_count {
+            let value = if is_srgb && channel < 3 {
+                linear_to_srgb(color[channel])
+            } else {
+                color[channel]
+            };
+            texel[channel] = f32_to_unorm8(value);
+        }
+    } else if let Some(channel_count) = sfloat32_channel_count(format) {
+        for channel in 0..channel_count {
+            let offset = channel * 4;
+            texel[offset..offset + 4].copy_from_slice(&color[channel].to_le_bytes());
+        }
+    } else {
+        return Err(unsupported_format(format));
+    }
+
+    Ok(())
+}
+
+/// Writes depth and/or stencil into a single texel. Depth is stored first, followed by stencil.
+/// D24_UNORM_S8_UINT is packed into a little-endian u32 with stencil in the high byte.
+pub(crate) fn encode_depth_stencil(
+    format: RafxFormat,
+    depth: Option<f32>,
+    stencil: Option<u8>,
+    texel: &mut [u8],
+) -> RafxResult<()> {
+    let depth = depth.map(|x| x.max(0.0).min(1.0));
+    match format {
+        RafxFormat::D16_UNORM | RafxFormat::D16_UNORM_S8_UINT => {
+            if let Some(depth) = depth {
+                let value = (depth * 65535.0 + 0.5) as u16;
+                texel[0..2].copy_from_slice(&value.to_le_bytes());
+            }
+            if let Some(stencil) = stencil {
+                if format == RafxFormat::D16_UNORM_S8_UINT {
+                    texel[2] = stencil;
+                }
+            }
+        }
+        RafxFormat::X8_D24_UNORM_PACK32 | RafxFormat::D24_UNORM_S8_UINT => {
+            if let Some(depth) = depth {
+                let value = (depth * 16777215.0 + 0.5) as u32;
+                texel[0..3].copy_from_slice(&value.to_le_bytes()[0..3]);
+            }
+            if let Some(stencil) = stencil {
+                if format == RafxFormat::D24_UNORM_S8_UINT {
+                    texel[3] = stencil;
+                }
+            }
+        }
+        RafxFormat::D32_SFLOAT | RafxFormat::D32_SFLOAT_S8_UINT => {
+            if let Some(depth) = depth {
+                texel[0..4].copy_from_slice(&depth.to_le_bytes());
+            }
+            if let Some(stencil) = stencil {
+                if format == RafxFormat::D32_SFLOAT_S8_UINT {
+                    texel[4] = stencil;
+                }
+            }
+        }
+        RafxFormat::S8_UINT => {
+            if let Some(stencil) = stencil {
+                texel[0] = stencil;
+            }
+        }
+        _ => return Err(unsupported_format(format)),
+    }
+
+    Ok(())
+}
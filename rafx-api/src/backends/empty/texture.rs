@@ -0,0 +1,273 @@
+use crate::empty::{RafxDeviceContextEmpty, RafxHeapEmpty, RafxHostMemoryEmpty};
+use crate::{
+    RafxError, RafxExtents3D, RafxMemoryRequirements, RafxMemoryUsage, RafxResult, RafxTextureDef,
+};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Describes where a single mip level of a single array layer lives within the host memory of a
+/// texture. Rows are tightly packed, using the block size of the texture's format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RafxTextureSubresourceLayoutEmpty {
+    pub offset: u64,
+    pub row_pitch: u64,
+    pub depth_pitch: u64,
+    pub size: u64,
+    pub extents: RafxExtents3D,
+}
+
+#[derive(Debug)]
+pub struct RafxTextureEmptyInner {
+    device_context: RafxDeviceContextEmpty,
+    texture_def: RafxTextureDef,
+    // Indexed by array_layer * mip_count + mip_level
+    subresource_layouts: Vec<RafxTextureSubresourceLayoutEmpty>,
+    memory: RafxHostMemoryEmpty,
+    texture_id: u32,
+}
+
+// for UnsafeCell, the rafx API does not synchronize access to texture memory
+unsafe impl Send for RafxTextureEmptyInner {}
+unsafe impl Sync for RafxTextureEmptyInner {}
+
+impl Drop for RafxTextureEmptyInner {
+    fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_texture_destroyed(self.texture_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_texture_destroyed(self.texture_id);
+
+        // Placed textures don't own their memory
+        if !self.memory.is_placed() {
+            self.device_context
+                .memory_usage_tracker()
+                .on_texture_freed(RafxMemoryUsage::GpuOnly, self.memory.len());
+        }
+    }
+}
+
+/// A texture backed by host memory. Each subresource is stored contiguously, array layers first,
+/// then mip levels within each layer.
+#[derive(Clone, Debug)]
+pub struct RafxTextureEmpty {
+    inner: Arc<RafxTextureEmptyInner>,
+}
+
+impl PartialEq for RafxTextureEmpty {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.inner.texture_id == other.inner.texture_id
+    }
+}
+
+impl Eq for RafxTextureEmpty {}
+
+impl Hash for RafxTextureEmpty {
+    fn hash<H: Hasher>(
+        &self,
+        state: &mut H,
+    ) {
+        self.inner.texture_id.hash(state);
+    }
+}
+
+impl RafxTextureEmpty {
+    pub fn texture_def(&self) -> &RafxTextureDef {
+        &self.inner.texture_def
+    }
+
+    // Used internally to identify the texture for validation
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn texture_id(&self) -> u32 {
+        self.inner.texture_id
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextEmpty {
+        &self.inner.device_context
+    }
+
+    /// Size of the host memory allocation that holds all subresources
+    pub fn allocation_size(&self) -> u64 {
+        self.inner.memory.len()
+    }
+
+    /// Returns where the given mip level/array layer is stored in host memory
+    pub fn subresource_layout(
+        &self,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> RafxTextureSubresourceLayoutEmpty {
+        assert!(mip_level < self.inner.texture_def.mip_count);
+        assert!(array_layer < self.inner.texture_def.array_length);
+        self.inner.subresource_layouts
+            [(array_layer * self.inner.texture_def.mip_count + mip_level) as usize]
+    }
+
+    /// Copies the contents of the given mip level/array layer out of host memory
+    pub fn read_subresource(
+        &self,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Vec<u8> {
+        let layout = self.subresource_layout(mip_level, array_layer);
+        unsafe {
+            let src = self.host_memory_ptr().add(layout.offset as usize);
+            std::slice::from_raw_parts(src, layout.size as usize).to_vec()
+        }
+    }
+
+    // Pointer to the host memory. Used to execute transfers
+    pub(crate) fn host_memory_ptr(&self) -> *mut u8 {
+        self.inner.memory.ptr()
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Nothing to name, there is no GPU object
+    }
+
+    pub fn memory_requirements(
+        device_context: &RafxDeviceContextEmpty,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        let (_, allocation_size) = Self::subresource_layouts(device_context, texture_def)?;
+        Ok(RafxMemoryRequirements {
+            size: allocation_size,
+            alignment: super::heap::HEAP_PLACEMENT_ALIGNMENT,
+        })
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureEmpty> {
+        let (subresource_layouts, allocation_size) =
+            Self::subresource_layouts(device_context, texture_def)?;
+        device_context
+            .memory_usage_tracker()
+            .on_texture_allocated(RafxMemoryUsage::GpuOnly, allocation_size);
+
+        Ok(Self::from_memory(
+            device_context,
+            texture_def,
+            subresource_layouts,
+            RafxHostMemoryEmpty::new_dedicated(allocation_size),
+        ))
+    }
+
+    pub fn new_in_heap(
+        device_context: &RafxDeviceContextEmpty,
+        heap: &RafxHeapEmpty,
+        offset: u64,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureEmpty> {
+        let (subresource_layouts, allocation_size) =
+            Self::subresource_layouts(device_context, texture_def)?;
+        heap.heap_def().verify_texture_def(texture_def)?;
+        heap.heap_def().verify_placement(
+            offset,
+            &RafxMemoryRequirements {
+                size: allocation_size,
+                alignment: super::heap::HEAP_PLACEMENT_ALIGNMENT,
+            },
+        )?;
+
+        Ok(Self::from_memory(
+            device_context,
+            texture_def,
+            subresource_layouts,
+            RafxHostMemoryEmpty::Placed {
+                heap: heap.clone(),
+                offset,
+                size: allocation_size,
+            },
+        ))
+    }
+
+    // Returns where each subresource is stored and the total size of host memory required
+    fn subresource_layouts(
+        device_context: &RafxDeviceContextEmpty,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<(Vec<RafxTextureSubresourceLayoutEmpty>, u64)> {
+        texture_def.verify();
+        texture_def.verify_device_limits(device_context.device_info())?;
+
+        let format = texture_def.format;
+        if format.is_undefined() {
+            return Err(RafxError::invalid_argument(
+                "format",
+                "Cannot create a texture with an undefined format",
+            ));
+        }
+
+        let block_size = format.block_or_pixel_size_in_bytes() as u64;
+        let block_width = format.block_width_in_pixels();
+        let block_height = format.block_height_in_pixels();
+
+        let mut subresource_layouts =
+            Vec::with_capacity((texture_def.array_length * texture_def.mip_count) as usize);
+        let mut allocation_size = 0;
+        for _array_layer in 0..texture_def.array_length {
+            for mip_level in 0..texture_def.mip_count {
+                let extents = RafxExtents3D {
+                    width: (texture_def.extents.width >> mip_level).max(1),
+                    height: (texture_def.extents.height >> mip_level).max(1),
+                    depth: (texture_def.extents.depth >> mip_level).max(1),
+                };
+
+                let blocks_wide =
+                    rafx_base::memory::round_size_up_to_alignment_u32(extents.width, block_width)
+                        / block_width;
+                let blocks_high =
+                    rafx_base::memory::round_size_up_to_alignment_u32(extents.height, block_height)
+                        / block_height;
+
+                let row_pitch = blocks_wide as u64 * block_size;
+                let depth_pitch = row_pitch * blocks_high as u64;
+                let size = depth_pitch * extents.depth as u64;
+
+                subresource_layouts.push(RafxTextureSubresourceLayoutEmpty {
+                    offset: allocation_size,
+                    row_pitch,
+                    depth_pitch,
+                    size,
+                    extents,
+                });
+
+                allocation_size += size;
+            }
+        }
+
+        Ok((subresource_layouts, allocation_size))
+    }
+
+    fn from_memory(
+        device_context: &RafxDeviceContextEmpty,
+        texture_def: &RafxTextureDef,
+        subresource_layouts: Vec<RafxTextureSubresourceLayoutEmpty>,
+        memory: RafxHostMemoryEmpty,
+    ) -> RafxTextureEmpty {
+        let texture_id = crate::internal_shared::NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed);
+
+        let inner = RafxTextureEmptyInner {
+            device_context: device_context.clone(),
+            texture_def: texture_def.clone(),
+            subresource_layouts,
+            memory,
+            texture_id,
+        };
+
+        RafxTextureEmpty {
+            inner: Arc::new(inner),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::empty::{RafxDeviceContextEmpty, RafxTextureEmpty};
+use crate::{RafxResult, RafxTexture, RafxTextureViewDef};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxTextureViewEmptyInner {
+    // It's a RafxTextureEmpty, but stored as RafxTexture so we can return refs to it
+    texture: RafxTexture,
+    texture_view_def: RafxTextureViewDef,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+/// A view of a texture. The empty backend only uses it to pick which subresources render pass
+/// clears write to, and which format the clear value is encoded in.
+#[derive(Clone, Debug)]
+pub struct RafxTextureViewEmpty {
+    inner: Arc<RafxTextureViewEmptyInner>,
+}
+
+impl RafxTextureViewEmpty {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn texture_view_def(&self) -> &RafxTextureViewDef {
+        &self.inner.texture_view_def
+    }
+
+    pub fn texture(&self) -> &RafxTexture {
+        &self.inner.texture
+    }
+
+    pub fn empty_texture(&self) -> &RafxTextureEmpty {
+        self.inner.texture.empty_texture().unwrap()
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextEmpty,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewEmpty> {
+        let empty_texture = texture
+            .empty_texture()
+            .ok_or("Texture views must be created from a texture of the same backend")?;
+        let texture_view_def = texture_view_def.resolve(empty_texture.texture_def())?;
+
+        let inner = RafxTextureViewEmptyInner {
+            texture: texture.clone(),
+            texture_view_def,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxTextureViewEmpty {
+            inner: Arc::new(inner),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::EmptyTestContext;
+    use crate::*;
+
+    #[test]
+    fn texture_views_reinterpret_formats_and_subresources() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let command_buffer = &test_context.command_buffer;
+
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: 2,
+                height: 2,
+                depth: 1,
+            },
+            array_length: 6,
+            mip_count: 2,
+            format: RafxFormat::R8G8B8A8_SRGB,
+            resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+            ..Default::default()
+        };
+        let unorm_view_def = RafxTextureViewDef {
+            format: Some(RafxFormat::R8G8B8A8_UNORM),
+            ..Default::default()
+        };
+
+        // Reinterpreting the format must be opted into when creating the texture
+        let immutable_texture = device_context.create_texture(&texture_def).unwrap();
+        let result = device_context.create_texture_view(&immutable_texture, &unorm_view_def);
+        assert!(matches!(
+            result,
+            Err(RafxError::InvalidArgument {
+                argument: "format",
+                ..
+            })
+        ));
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                resource_type: texture_def.resource_type | RafxResourceType::TEXTURE_MUTABLE_FORMAT,
+                ..texture_def.clone()
+            })
+            .unwrap();
+
+        // Unset fields cover the whole texture
+        let view = device_context
+            .create_texture_view(&texture, &unorm_view_def)
+            .unwrap();
+        assert_eq!(
+            view.texture_view_def(),
+            &RafxTextureViewDef {
+                format: Some(RafxFormat::R8G8B8A8_UNORM),
+                dimensions: Some(RafxTextureViewDimensions::Dim2DArray),
+                mip_range: Some(0..2),
+                array_range: Some(0..6),
+            }
+        );
+
+        // Cube views require a texture created with TEXTURE_CUBE
+        let result = device_context.create_texture_view(
+            &texture,
+            &RafxTextureViewDef {
+                dimensions: Some(RafxTextureViewDimensions::Cube),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(RafxError::InvalidArgument {
+                argument: "dimensions",
+                ..
+            })
+        ));
+
+        let result = device_context.create_texture_view(
+            &texture,
+            &RafxTextureViewDef {
+                mip_range: Some(1..3),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(RafxError::InvalidArgument {
+                argument: "mip_range",
+                ..
+            })
+        ));
+
+        // Depth textures can be sampled as a color format without TEXTURE_MUTABLE_FORMAT
+        let depth_texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: texture_def.extents,
+                format: RafxFormat::D32_SFLOAT,
+                resource_type: RafxResourceType::TEXTURE
+                    | RafxResourceType::RENDER_TARGET_DEPTH_STENCIL,
+                ..Default::default()
+            })
+            .unwrap();
+        device_context
+            .create_texture_view(
+                &depth_texture,
+                &RafxTextureViewDef {
+                    format: Some(RafxFormat::R32_SFLOAT),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let slice_view = device_context
+            .create_texture_view(
+                &texture,
+                &RafxTextureViewDef {
+                    format: Some(RafxFormat::R8G8B8A8_UNORM),
+                    dimensions: Some(RafxTextureViewDimensions::Dim2D),
+                    mip_range: Some(1..2),
+                    array_range: Some(3..4),
+                },
+            )
+            .unwrap();
+
+        let color_target = |texture_view| RafxColorRenderTargetBinding {
+            texture: &texture,
+            load_op: RafxLoadOp::Clear,
+            store_op: RafxStoreOp::Store,
+            clear_value: RafxColorClearValue([0.5, 0.5, 0.5, 0.5]),
+            mip_slice: None,
+            array_slice: None,
+            texture_view: Some(texture_view),
+            resolve_target: None,
+            resolve_store_op: RafxStoreOp::DontCare,
+            resolve_mip_slice: None,
+            resolve_array_slice: None,
+        };
+
+        command_buffer.begin().unwrap();
+        // Render targets must be a single mip level
+        let result = command_buffer.cmd_begin_render_pass(&[color_target(&view)], None);
+        assert!(matches!(
+            result,
+            Err(RafxError::InvalidArgument {
+                argument: "texture_view",
+                ..
+            })
+        ));
+        command_buffer
+            .cmd_begin_render_pass(&[color_target(&slice_view)], None)
+            .unwrap();
+        command_buffer.cmd_end_render_pass().unwrap();
+        command_buffer.end().unwrap();
+        test_context.submit().unwrap();
+
+        // The clear is encoded as UNORM rather than sRGB and only touches the view's subresource
+        let empty_texture = texture.empty_texture().unwrap();
+        assert_eq!(empty_texture.read_subresource(1, 3), vec![128; 4]);
+        assert_eq!(empty_texture.read_subresource(0, 3), vec![0; 16]);
+        assert_eq!(empty_texture.read_subresource(1, 2), vec![0; 4]);
+    }
+}
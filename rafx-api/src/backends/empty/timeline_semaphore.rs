@@ -0,0 +1,118 @@
+use crate::empty::RafxDeviceContextEmpty;
+use crate::RafxResult;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Work submitted to the empty backend completes immediately, so signal values are applied when
+/// the work is submitted. Waiting for a value that has not been signaled yet would block forever,
+/// so it fails instead.
+pub struct RafxTimelineSemaphoreEmpty {
+    _device_context: RafxDeviceContextEmpty,
+    value: AtomicU64,
+}
+
+impl RafxTimelineSemaphoreEmpty {
+    pub fn new(
+        device_context: &RafxDeviceContextEmpty,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreEmpty> {
+        Ok(RafxTimelineSemaphoreEmpty {
+            _device_context: device_context.clone(),
+            value: AtomicU64::new(initial_value),
+        })
+    }
+
+    pub fn current_value(&self) -> RafxResult<u64> {
+        Ok(self.value.load(Ordering::Acquire))
+    }
+
+    pub fn signal(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        let previous_value = self.value.fetch_max(value, Ordering::AcqRel);
+        if previous_value >= value {
+            return Err(format!(
+                "Signaled timeline semaphore with value {} but it is already {}",
+                value, previous_value
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn wait(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        let current_value = self.current_value()?;
+        if current_value < value {
+            return Err(format!(
+                "Waited for timeline semaphore value {} but it is {} and nothing is pending that could signal it",
+                value, current_value
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::empty::test_util::EmptyTestContext;
+    use crate::*;
+
+    #[test]
+    fn timeline_semaphore_submit_and_cpu_signal() {
+        let test_context = EmptyTestContext::new();
+        let device_context = &test_context.device_context;
+        let queue = &test_context.queue;
+        let command_buffer = &test_context.command_buffer;
+        assert!(device_context.device_info().supports_timeline_semaphores);
+
+        command_buffer.begin().unwrap();
+        command_buffer.end().unwrap();
+
+        let timeline_semaphore = device_context.create_timeline_semaphore(1).unwrap();
+        assert_eq!(timeline_semaphore.current_value().unwrap(), 1);
+
+        queue
+            .submit_with_timeline_semaphores(
+                &[command_buffer],
+                &[],
+                &[],
+                &[RafxTimelineSemaphoreValue {
+                    semaphore: &timeline_semaphore,
+                    value: 1,
+                }],
+                &[RafxTimelineSemaphoreValue {
+                    semaphore: &timeline_semaphore,
+                    value: 2,
+                }],
+                None,
+            )
+            .unwrap();
+        assert_eq!(timeline_semaphore.current_value().unwrap(), 2);
+        timeline_semaphore.wait(2).unwrap();
+
+        // Nothing is pending that could signal 3
+        assert!(timeline_semaphore.wait(3).is_err());
+        assert!(queue
+            .submit_with_timeline_semaphores(
+                &[command_buffer],
+                &[],
+                &[],
+                &[RafxTimelineSemaphoreValue {
+                    semaphore: &timeline_semaphore,
+                    value: 3,
+                }],
+                &[],
+                None,
+            )
+            .is_err());
+
+        // Values can only increase
+        assert!(timeline_semaphore.signal(2).is_err());
+        timeline_semaphore.signal(3).unwrap();
+        timeline_semaphore.wait(3).unwrap();
+    }
+}
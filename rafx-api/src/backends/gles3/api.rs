@@ -0,0 +1,669 @@
+use crate::{RafxAdapterInfo, RafxAdapterType, RafxApiDef, RafxResult};
+use raw_window_handle::HasRawWindowHandle;
+use std::sync::Arc;
+
+use super::internal::gl_context::GlContext;
+use crate::gles3::{RafxDeviceContextGles3, RafxDeviceContextGles3Inner};
+
+/// GL ES-specific configuration
+#[derive(Default)]
+pub struct RafxApiDefGles3 {}
+
+/// OpenGL ES 3.0 through EGL. Intended for devices that don't support vulkan or metal, and for
+/// rendering on CI machines with a software rasterizer like mesa's llvmpipe. GL has no equivalent
+/// of command buffers, so recorded commands are executed when they are submitted.
+pub struct RafxApiGles3 {
+    device_context: Option<RafxDeviceContextGles3>,
+}
+
+impl Drop for RafxApiGles3 {
+    fn drop(&mut self) {
+        self.destroy().unwrap();
+    }
+}
+
+impl RafxApiGles3 {
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        self.device_context.as_ref().unwrap()
+    }
+
+    /// List the adapters available to GL ES. EGL picks the GPU when the display is opened, so this
+    /// is the single adapter a device for this window would use. Only a GL context is created.
+    pub fn enumerate_adapters(
+        window: &dyn HasRawWindowHandle,
+        _gles3_api_def: &RafxApiDefGles3,
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        let gl_context = GlContext::new_for_window(window.raw_window_handle())?;
+        Ok(Self::adapters_for_gl_context(&gl_context))
+    }
+
+    /// Same as `enumerate_adapters`, for a headless device
+    pub fn enumerate_adapters_headless(
+        _gles3_api_def: &RafxApiDefGles3
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        let gl_context = GlContext::new_headless()?;
+        Ok(Self::adapters_for_gl_context(&gl_context))
+    }
+
+    fn adapters_for_gl_context(gl_context: &GlContext) -> Vec<RafxAdapterInfo> {
+        let renderer = gl_context.renderer();
+        let is_software = ["llvmpipe", "softpipe", "SwiftShader"]
+            .iter()
+            .any(|x| renderer.contains(x));
+
+        vec![RafxAdapterInfo {
+            adapter_index: 0,
+            name: renderer.to_string(),
+            vendor_id: 0,
+            device_id: 0,
+            adapter_type: if is_software {
+                RafxAdapterType::Cpu
+            } else {
+                RafxAdapterType::Other
+            },
+            driver_version: gl_context.version().to_string(),
+            is_supported: true,
+            is_default: true,
+        }]
+    }
+
+    pub fn new(
+        window: &dyn HasRawWindowHandle,
+        api_def: &RafxApiDef,
+        _gles3_api_def: &RafxApiDefGles3,
+    ) -> RafxResult<Self> {
+        Self::verify_adapter_index(api_def)?;
+        let gl_context = GlContext::new_for_window(window.raw_window_handle())?;
+        Self::from_gl_context(gl_context, false)
+    }
+
+    pub fn new_headless(
+        api_def: &RafxApiDef,
+        _gles3_api_def: &RafxApiDefGles3,
+    ) -> RafxResult<Self> {
+        Self::verify_adapter_index(api_def)?;
+        let gl_context = GlContext::new_headless()?;
+        Self::from_gl_context(gl_context, true)
+    }
+
+    fn verify_adapter_index(api_def: &RafxApiDef) -> RafxResult<()> {
+        if let Some(adapter_index) = api_def.adapter_index {
+            if adapter_index != 0 {
+                Err(format!(
+                    "Adapter index {} is out of range, the GL ES backend has a single adapter",
+                    adapter_index
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn from_gl_context(
+        gl_context: GlContext,
+        is_headless: bool,
+    ) -> RafxResult<Self> {
+        let inner = Arc::new(RafxDeviceContextGles3Inner::new(gl_context, is_headless)?);
+        let device_context = RafxDeviceContextGles3::new(inner)?;
+
+        Ok(RafxApiGles3 {
+            device_context: Some(device_context),
+        })
+    }
+
+    pub fn destroy(&mut self) -> RafxResult<()> {
+        if let Some(device_context) = self.device_context.take() {
+            let inner = device_context.inner.clone();
+
+            // This should be the final device context
+            std::mem::drop(device_context);
+
+            let strong_count = Arc::strong_count(&inner);
+            match Arc::try_unwrap(inner) {
+                Ok(inner) => std::mem::drop(inner),
+                Err(_arc) => {
+                    Err(format!(
+                        "Could not destroy device, {} references to it exist",
+                        strong_count
+                    ))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    // Tests render with whatever EGL provides without a window, usually mesa's llvmpipe on CI.
+    // Returns None if there is no EGL/GL ES driver, in which case the test should be skipped.
+    fn create_api() -> Option<RafxApi> {
+        match RafxApi::new_gles3_headless(&Default::default(), &Default::default()) {
+            Ok(api) => Some(api),
+            Err(e) => {
+                log::warn!(
+                    "Skipping GL ES test, could not create a headless context: {:?}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    fn create_command_buffer(queue: &RafxQueue) -> (RafxCommandPool, RafxCommandBuffer) {
+        let mut command_pool = queue
+            .create_command_pool(&RafxCommandPoolDef { transient: true })
+            .unwrap();
+        let command_buffer = command_pool
+            .create_command_buffer(&RafxCommandBufferDef {
+                is_secondary: false,
+            })
+            .unwrap();
+        (command_pool, command_buffer)
+    }
+
+    fn submit_and_wait(
+        device_context: &RafxDeviceContext,
+        queue: &RafxQueue,
+        command_buffer: &RafxCommandBuffer,
+    ) {
+        let fence = device_context.create_fence().unwrap();
+        queue
+            .submit(&[command_buffer], &[], &[], Some(&fence))
+            .unwrap();
+        fence.wait().unwrap();
+    }
+
+    fn create_render_target(
+        device_context: &RafxDeviceContext,
+        format: RafxFormat,
+    ) -> RafxTexture {
+        device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 4,
+                    height: 4,
+                    depth: 1,
+                },
+                format,
+                resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    // Copies mip 0 of a 2D texture to a readback buffer and returns the pixels of each row
+    fn read_texture(
+        device_context: &RafxDeviceContext,
+        queue: &RafxQueue,
+        texture: &RafxTexture,
+    ) -> Vec<Vec<u8>> {
+        let texture_def = texture.texture_def();
+        let layout = RafxTextureBufferLayout::new(
+            device_context.device_info(),
+            texture_def.format,
+            texture_def.extents,
+            0,
+        );
+        let buffer = device_context
+            .create_buffer(&RafxBufferDef {
+                size: layout.size,
+                memory_usage: RafxMemoryUsage::GpuToCpu,
+                resource_type: RafxResourceType::BUFFER,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let (_command_pool, command_buffer) = create_command_buffer(queue);
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_copy_texture_to_buffer(texture, &buffer, &Default::default())
+            .unwrap();
+        command_buffer.end().unwrap();
+        submit_and_wait(device_context, queue, &command_buffer);
+
+        let data = buffer.map_buffer().unwrap();
+        let rows = (0..layout.rows_per_image)
+            .map(|row| {
+                let row_data = unsafe {
+                    std::slice::from_raw_parts(
+                        data.add((row * layout.bytes_per_row) as usize),
+                        layout.bytes_per_row_unpadded as usize,
+                    )
+                };
+                row_data.to_vec()
+            })
+            .collect();
+        buffer.unmap_buffer().unwrap();
+        rows
+    }
+
+    fn create_shader_module(
+        device_context: &RafxDeviceContext,
+        src: &str,
+    ) -> RafxShaderModule {
+        device_context
+            .create_shader_module(RafxShaderModuleDef {
+                #[cfg(feature = "rafx-vulkan")]
+                vk: None,
+                #[cfg(feature = "rafx-metal")]
+                metal: None,
+                #[cfg(feature = "rafx-empty")]
+                empty: None,
+                gles3: Some(RafxShaderModuleDefGles3::GlSrc(src)),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn clear_and_read_back() {
+        let api = match create_api() {
+            Some(api) => api,
+            None => return,
+        };
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        // BGRA is stored as RGBA in GL, the channels are swapped back when copying to a buffer
+        for (format, expected_pixel) in &[
+            (RafxFormat::R8G8B8A8_UNORM, [255, 0, 0, 255]),
+            (RafxFormat::B8G8R8A8_UNORM, [0, 0, 255, 255]),
+        ] {
+            let texture = create_render_target(&device_context, *format);
+
+            let (_command_pool, command_buffer) = create_command_buffer(&queue);
+            command_buffer.begin().unwrap();
+            command_buffer
+                .cmd_begin_render_pass(
+                    &[RafxColorRenderTargetBinding {
+                        texture: &texture,
+                        load_op: RafxLoadOp::Clear,
+                        store_op: RafxStoreOp::Store,
+                        mip_slice: None,
+                        array_slice: None,
+                        texture_view: None,
+                        clear_value: RafxColorClearValue([1.0, 0.0, 0.0, 1.0]),
+                        resolve_target: None,
+                        resolve_store_op: RafxStoreOp::DontCare,
+                        resolve_mip_slice: None,
+                        resolve_array_slice: None,
+                    }],
+                    None,
+                )
+                .unwrap();
+            command_buffer.cmd_end_render_pass().unwrap();
+            command_buffer.end().unwrap();
+            submit_and_wait(&device_context, &queue, &command_buffer);
+
+            for row in read_texture(&device_context, &queue, &texture) {
+                assert_eq!(row, expected_pixel.repeat(4));
+            }
+        }
+    }
+
+    #[test]
+    fn draw_and_read_back() {
+        let api = match create_api() {
+            Some(api) => api,
+            None => return,
+        };
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        // The source the shader processor would produce: push constants become a uniform block,
+        // and the texture and sampler become a sampler uniform named after the texture
+        let vertex_shader_module = create_shader_module(
+            &device_context,
+            "#version 300 es
+            layout(std140) uniform PushConstants { vec4 offset; } push_constants;
+            layout(location = 0) in vec2 pos;
+            void main() {
+                gl_Position = vec4(pos + push_constants.offset.xy, 0.0, 1.0);
+            }",
+        );
+        let fragment_shader_module = create_shader_module(
+            &device_context,
+            "#version 300 es
+            precision mediump float;
+            layout(std140) uniform Tint { vec4 color; } tint;
+            uniform highp sampler2D tex;
+            layout(location = 0) out vec4 out_color;
+            void main() {
+                out_color = texture(tex, vec2(0.5)) * tint.color;
+            }",
+        );
+
+        let resource =
+            |resource_type, binding, name: &str, gles_name: Option<&str>| RafxShaderResource {
+                resource_type,
+                set_index: 0,
+                binding,
+                used_in_shader_stages: RafxShaderStageFlags::FRAGMENT,
+                name: Some(name.to_string()),
+                gles_name: gles_name.map(|x| x.to_string()),
+                ..Default::default()
+            };
+        let shader = device_context
+            .create_shader(vec![
+                RafxShaderStageDef {
+                    shader_module: vertex_shader_module,
+                    reflection: RafxShaderStageReflection {
+                        shader_stage: RafxShaderStageFlags::VERTEX,
+                        resources: vec![RafxShaderResource {
+                            resource_type: RafxResourceType::ROOT_CONSTANT,
+                            size_in_bytes: 16,
+                            used_in_shader_stages: RafxShaderStageFlags::VERTEX,
+                            name: Some("push_constants".to_string()),
+                            gles_name: Some("PushConstants".to_string()),
+                            ..Default::default()
+                        }],
+                        compute_threads_per_group: None,
+                        entry_point_name: "main".to_string(),
+                        specialization_constants: vec![],
+                    },
+                    specialization_constants: vec![],
+                },
+                RafxShaderStageDef {
+                    shader_module: fragment_shader_module,
+                    reflection: RafxShaderStageReflection {
+                        shader_stage: RafxShaderStageFlags::FRAGMENT,
+                        resources: vec![
+                            resource(RafxResourceType::UNIFORM_BUFFER, 1, "tint", Some("Tint")),
+                            RafxShaderResource {
+                                gles_sampler_name: Some("smp".to_string()),
+                                ..resource(RafxResourceType::TEXTURE, 2, "tex", None)
+                            },
+                            resource(RafxResourceType::SAMPLER, 3, "smp", None),
+                        ],
+                        compute_threads_per_group: None,
+                        entry_point_name: "main".to_string(),
+                        specialization_constants: vec![],
+                    },
+                    specialization_constants: vec![],
+                },
+            ])
+            .unwrap();
+        let root_signature = device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: std::slice::from_ref(&shader),
+                immutable_samplers: &[],
+                bindless_arrays: &[],
+            })
+            .unwrap();
+
+        let vertex_layout = RafxVertexLayout {
+            attributes: vec![RafxVertexLayoutAttribute {
+                format: RafxFormat::R32G32_SFLOAT,
+                buffer_index: 0,
+                location: 0,
+                byte_offset: 0,
+            }],
+            buffers: vec![RafxVertexLayoutBuffer {
+                stride: 8,
+                rate: RafxVertexAttributeRate::Vertex,
+            }],
+        };
+        let pipeline = device_context
+            .create_graphics_pipeline(&RafxGraphicsPipelineDef {
+                shader: &shader,
+                root_signature: &root_signature,
+                vertex_layout: &vertex_layout,
+                blend_state: &Default::default(),
+                depth_state: &Default::default(),
+                rasterizer_state: &Default::default(),
+                primitive_topology: RafxPrimitiveTopology::TriangleList,
+                color_formats: &[RafxFormat::R8G8B8A8_UNORM],
+                depth_stencil_format: None,
+                sample_count: RafxSampleCount::SampleCount1,
+            })
+            .unwrap();
+
+        // A quad covering the left half of the top half of the render target. The push constant
+        // moves it to the right half.
+        let vertices = [
+            -1.0f32, -1.0, 0.0, -1.0, -1.0, 0.0, 0.0, -1.0, 0.0, 0.0, -1.0, 0.0,
+        ];
+        let vertex_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_vertex_buffer_data(&vertices))
+            .unwrap();
+        vertex_buffer
+            .copy_to_host_visible_buffer(&vertices)
+            .unwrap();
+
+        let tint = [1.0f32, 1.0, 0.0, 1.0];
+        let tint_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_uniform_buffer_data(&tint))
+            .unwrap();
+        tint_buffer.copy_to_host_visible_buffer(&tint).unwrap();
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 1,
+                    height: 1,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..Default::default()
+            })
+            .unwrap();
+        let texel_layout = RafxTextureBufferLayout::new(
+            device_context.device_info(),
+            RafxFormat::R8G8B8A8_UNORM,
+            texture.texture_def().extents,
+            0,
+        );
+        let mut texel_data = vec![0u8; texel_layout.size as usize];
+        texel_data[0..4].copy_from_slice(&[64, 128, 255, 255]);
+        let texel_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &texel_data,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+        texel_buffer
+            .copy_to_host_visible_buffer(&texel_data)
+            .unwrap();
+
+        let sampler = device_context
+            .create_sampler(&RafxSamplerDef::default())
+            .unwrap();
+
+        let mut descriptor_set_array = device_context
+            .create_descriptor_set_array(&RafxDescriptorSetArrayDef {
+                root_signature: &root_signature,
+                set_index: 0,
+                array_length: 1,
+            })
+            .unwrap();
+        descriptor_set_array
+            .update_descriptor_set(&[
+                RafxDescriptorUpdate {
+                    descriptor_key: RafxDescriptorKey::Name("tint"),
+                    elements: RafxDescriptorElements {
+                        buffers: Some(&[&tint_buffer]),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                RafxDescriptorUpdate {
+                    descriptor_key: RafxDescriptorKey::Name("tex"),
+                    elements: RafxDescriptorElements {
+                        textures: Some(&[&texture]),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                RafxDescriptorUpdate {
+                    descriptor_key: RafxDescriptorKey::Name("smp"),
+                    elements: RafxDescriptorElements {
+                        samplers: Some(&[&sampler]),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ])
+            .unwrap();
+
+        let render_target = create_render_target(&device_context, RafxFormat::R8G8B8A8_UNORM);
+        let push_constant_index = root_signature
+            .find_push_constant_by_name("push_constants")
+            .unwrap();
+
+        let (_command_pool, command_buffer) = create_command_buffer(&queue);
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_copy_buffer_to_texture(&texel_buffer, &texture, &Default::default())
+            .unwrap();
+        command_buffer
+            .cmd_begin_render_pass(
+                &[RafxColorRenderTargetBinding {
+                    texture: &render_target,
+                    load_op: RafxLoadOp::Clear,
+                    store_op: RafxStoreOp::Store,
+                    mip_slice: None,
+                    array_slice: None,
+                    texture_view: None,
+                    clear_value: RafxColorClearValue([0.0, 0.0, 0.0, 1.0]),
+                    resolve_target: None,
+                    resolve_store_op: RafxStoreOp::DontCare,
+                    resolve_mip_slice: None,
+                    resolve_array_slice: None,
+                }],
+                None,
+            )
+            .unwrap();
+        command_buffer.cmd_bind_pipeline(&pipeline).unwrap();
+        command_buffer
+            .cmd_bind_vertex_buffers(
+                0,
+                &[RafxVertexBufferBinding {
+                    buffer: &vertex_buffer,
+                    byte_offset: 0,
+                }],
+            )
+            .unwrap();
+        command_buffer
+            .cmd_bind_descriptor_set(&descriptor_set_array, 0)
+            .unwrap();
+        command_buffer
+            .cmd_bind_push_constant(
+                &root_signature,
+                push_constant_index,
+                &[1.0f32, 0.0, 0.0, 0.0],
+            )
+            .unwrap();
+        command_buffer.cmd_draw(6, 0).unwrap();
+        command_buffer.cmd_end_render_pass().unwrap();
+        command_buffer.end().unwrap();
+        submit_and_wait(&device_context, &queue, &command_buffer);
+
+        // Row 0 is the top of the image like the other backends (clip space y = -1)
+        let background = [0, 0, 0, 255];
+        let drawn = [64, 128, 0, 255];
+        let rows = read_texture(&device_context, &queue, &render_target);
+        for (y, row) in rows.iter().enumerate() {
+            let expected = if y < 2 {
+                [background, background, drawn, drawn].concat()
+            } else {
+                background.repeat(4)
+            };
+            assert_eq!(row, &expected, "row {}", y);
+        }
+    }
+
+    #[test]
+    fn buffer_copies_round_trip() {
+        let api = match create_api() {
+            Some(api) => api,
+            None => return,
+        };
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let data: Vec<u8> = (0..64).collect();
+        let staging_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                &data,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+        staging_buffer.copy_to_host_visible_buffer(&data).unwrap();
+        let gpu_buffer = device_context
+            .create_buffer(&RafxBufferDef {
+                size: 64,
+                memory_usage: RafxMemoryUsage::GpuOnly,
+                resource_type: RafxResourceType::BUFFER,
+                ..Default::default()
+            })
+            .unwrap();
+        let readback_buffer = device_context
+            .create_buffer(&RafxBufferDef {
+                size: 64,
+                memory_usage: RafxMemoryUsage::GpuToCpu,
+                resource_type: RafxResourceType::BUFFER,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let (_command_pool, command_buffer) = create_command_buffer(&queue);
+        command_buffer.begin().unwrap();
+        command_buffer
+            .cmd_copy_buffer_to_buffer(&staging_buffer, &gpu_buffer, 0, 0, 64)
+            .unwrap();
+        command_buffer
+            .cmd_fill_buffer(&gpu_buffer, 16, 8, 0xAB)
+            .unwrap();
+        command_buffer
+            .cmd_copy_buffer_to_buffer(&gpu_buffer, &readback_buffer, 0, 0, 64)
+            .unwrap();
+        command_buffer.end().unwrap();
+        submit_and_wait(&device_context, &queue, &command_buffer);
+
+        let mut expected = data.clone();
+        expected[16..24].copy_from_slice(&[0xAB; 8]);
+        let readback_ptr = readback_buffer.map_buffer().unwrap();
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(readback_ptr, 64) },
+            &expected[..]
+        );
+        readback_buffer.unmap_buffer().unwrap();
+    }
+
+    #[test]
+    fn unsupported_features_return_errors() {
+        let api = match create_api() {
+            Some(api) => api,
+            None => return,
+        };
+        let device_context = api.device_context();
+        let device_info = device_context.device_info();
+        assert!(!device_info.supports_timeline_semaphores);
+        assert!(!device_info.supports_timestamp_queries);
+
+        assert!(device_context.create_timeline_semaphore(0).is_err());
+        assert!(device_context
+            .create_query_pool(&RafxQueryPoolDef {
+                query_type: RafxQueryType::Timestamp,
+                query_count: 1,
+            })
+            .is_err());
+
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+        let (_command_pool, command_buffer) = create_command_buffer(&queue);
+        command_buffer.begin().unwrap();
+        assert!(command_buffer.cmd_dispatch(1, 1, 1).is_err());
+        command_buffer.end().unwrap();
+    }
+}
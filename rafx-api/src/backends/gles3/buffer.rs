@@ -0,0 +1,403 @@
+use super::internal::conversions;
+use super::internal::gl_context::check_gl_error;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxBufferDef, RafxMemoryUsage, RafxResourceType, RafxResult};
+use std::cell::UnsafeCell;
+#[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+// Tracks whether the host copy may have been written since it was last uploaded
+#[derive(Default, Debug)]
+struct HostCopyState {
+    map_count: u32,
+    // True if the buffer is in the device's list of buffers to upload at the next submit
+    queued_for_upload: bool,
+}
+
+#[derive(Debug)]
+pub struct RafxBufferGles3Inner {
+    device_context: RafxDeviceContextGles3,
+    buffer_def: RafxBufferDef,
+    gl_buffer: gl::GLuint,
+    allocation_size: u64,
+    // GL ES 3.0 can't keep a buffer mapped while it is used by the GPU, so buffers that the CPU
+    // can access are mapped to this copy instead. It is uploaded before each submit that follows a
+    // write, and kept up to date when the GPU writes to the buffer. None for GPU-only buffers.
+    host_copy: Option<UnsafeCell<Box<[u8]>>>,
+    host_copy_state: Mutex<HostCopyState>,
+    // Used internally to identify the buffer for validation and capture
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    buffer_id: u32,
+}
+
+// for UnsafeCell, the rafx API does not synchronize access to buffer memory
+unsafe impl Send for RafxBufferGles3Inner {}
+unsafe impl Sync for RafxBufferGles3Inner {}
+
+impl Drop for RafxBufferGles3Inner {
+    fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_buffer_destroyed(self.buffer_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_buffer_destroyed(self.buffer_id);
+
+        if let Ok(_guard) = self.device_context.gl_context().lock() {
+            unsafe {
+                gl::glDeleteBuffers(1, &self.gl_buffer);
+            }
+        }
+
+        self.device_context
+            .memory_usage_tracker()
+            .on_buffer_freed(self.buffer_def.memory_usage, self.allocation_size);
+    }
+}
+
+/// A GL buffer object. Buffers that are not GPU-only also have a copy in host memory that
+/// `map_buffer()` returns, see `RafxBufferGles3Inner::host_copy`.
+#[derive(Clone, Debug)]
+pub struct RafxBufferGles3 {
+    inner: Arc<RafxBufferGles3Inner>,
+}
+
+impl PartialEq for RafxBufferGles3 {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for RafxBufferGles3 {}
+
+impl RafxBufferGles3 {
+    pub fn buffer_def(&self) -> &RafxBufferDef {
+        &self.inner.buffer_def
+    }
+
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn buffer_id(&self) -> u32 {
+        self.inner.buffer_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.inner.device_context.capture_recorder()
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        &self.inner.device_context
+    }
+
+    pub fn gl_buffer(&self) -> gl::GLuint {
+        self.inner.gl_buffer
+    }
+
+    fn host_copy_ptr(&self) -> Option<*mut u8> {
+        self.inner
+            .host_copy
+            .as_ref()
+            .map(|x| unsafe { (*x.get()).as_mut_ptr() })
+    }
+
+    pub fn map_buffer(&self) -> RafxResult<*mut u8> {
+        let ptr = self.host_copy_ptr().ok_or("Cannot map GPU-only buffer")?;
+
+        let mut state = self.inner.host_copy_state.lock().unwrap();
+        state.map_count += 1;
+        if !state.queued_for_upload {
+            state.queued_for_upload = true;
+            self.inner
+                .device_context
+                .mark_buffer_dirty(Arc::downgrade(&self.inner));
+        }
+
+        Ok(ptr)
+    }
+
+    pub fn unmap_buffer(&self) -> RafxResult<()> {
+        let mut state = self.inner.host_copy_state.lock().unwrap();
+        if state.map_count == 0 {
+            return Err("Cannot unmap a buffer that is not mapped")?;
+        }
+
+        // The buffer stays queued, writes made while it was mapped are uploaded at the next submit
+        state.map_count -= 1;
+        Ok(())
+    }
+
+    pub fn mapped_memory(&self) -> Option<*mut u8> {
+        if self.inner.host_copy_state.lock().unwrap().map_count > 0 {
+            self.host_copy_ptr()
+        } else {
+            None
+        }
+    }
+
+    pub fn copy_to_host_visible_buffer<T: Copy>(
+        &self,
+        data: &[T],
+    ) -> RafxResult<()> {
+        // Cannot check size of data == buffer because buffer size might be rounded up
+        self.copy_to_host_visible_buffer_with_offset(data, 0)
+    }
+
+    pub fn copy_to_host_visible_buffer_with_offset<T: Copy>(
+        &self,
+        data: &[T],
+        buffer_byte_offset: u64,
+    ) -> RafxResult<()> {
+        let data_size_in_bytes = rafx_base::memory::slice_size_in_bytes(data) as u64;
+        assert!(buffer_byte_offset + data_size_in_bytes <= self.inner.buffer_def.size);
+
+        let src = data.as_ptr() as *const u8;
+
+        unsafe {
+            let dst = self.map_buffer()?.add(buffer_byte_offset as usize);
+            std::ptr::copy_nonoverlapping(src, dst, data_size_in_bytes as usize);
+        }
+
+        self.unmap_buffer()?;
+
+        Ok(())
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // GL ES 3.0 has no object labels without KHR_debug
+    }
+
+    // Uploads the whole host copy to the GL buffer. Returns true if the buffer is still mapped and
+    // must be uploaded again at the next submit. Must be called with the GL context locked.
+    pub(crate) fn upload_host_copy(inner: &Arc<RafxBufferGles3Inner>) -> RafxResult<bool> {
+        let mut state = inner.host_copy_state.lock().unwrap();
+        let still_mapped = state.map_count > 0;
+        state.queued_for_upload = still_mapped;
+
+        if let Some(host_copy) = &inner.host_copy {
+            unsafe {
+                let host_copy = &*host_copy.get();
+                gl::glBindBuffer(gl::COPY_WRITE_BUFFER, inner.gl_buffer);
+                gl::glBufferSubData(
+                    gl::COPY_WRITE_BUFFER,
+                    0,
+                    host_copy.len() as _,
+                    host_copy.as_ptr() as _,
+                );
+                gl::glBindBuffer(gl::COPY_WRITE_BUFFER, 0);
+            }
+            check_gl_error("Uploading mapped buffer")?;
+        }
+
+        Ok(still_mapped)
+    }
+
+    // Reads a range of the buffer. Must be called with the GL context locked.
+    pub(crate) fn read_range(
+        &self,
+        offset: u64,
+        size: u64,
+    ) -> RafxResult<Vec<u8>> {
+        if offset + size > self.inner.allocation_size {
+            return Err(format!(
+                "Reading {} bytes at offset {} is out of bounds of a buffer of size {}",
+                size, offset, self.inner.allocation_size
+            ))?;
+        }
+
+        if let Some(host_copy) = self.host_copy_ptr() {
+            let data = unsafe {
+                std::slice::from_raw_parts(host_copy.add(offset as usize), size as usize)
+            };
+            return Ok(data.to_vec());
+        }
+
+        let mut data = vec![0; size as usize];
+        if size > 0 {
+            unsafe {
+                gl::glBindBuffer(gl::COPY_READ_BUFFER, self.inner.gl_buffer);
+                let ptr = gl::glMapBufferRange(
+                    gl::COPY_READ_BUFFER,
+                    offset as _,
+                    size as _,
+                    gl::MAP_READ_BIT,
+                );
+                if !ptr.is_null() {
+                    std::ptr::copy_nonoverlapping(ptr as *const u8, data.as_mut_ptr(), data.len());
+                    gl::glUnmapBuffer(gl::COPY_READ_BUFFER);
+                }
+                gl::glBindBuffer(gl::COPY_READ_BUFFER, 0);
+            }
+            check_gl_error("Reading buffer")?;
+        }
+
+        Ok(data)
+    }
+
+    // Writes a range of the buffer and its host copy. Must be called with the GL context locked.
+    pub(crate) fn write_range(
+        &self,
+        offset: u64,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        if offset + data.len() as u64 > self.inner.allocation_size {
+            return Err(format!(
+                "Writing {} bytes at offset {} is out of bounds of a buffer of size {}",
+                data.len(),
+                offset,
+                self.inner.allocation_size
+            ))?;
+        }
+
+        unsafe {
+            gl::glBindBuffer(gl::COPY_WRITE_BUFFER, self.inner.gl_buffer);
+            gl::glBufferSubData(
+                gl::COPY_WRITE_BUFFER,
+                offset as _,
+                data.len() as _,
+                data.as_ptr() as _,
+            );
+            gl::glBindBuffer(gl::COPY_WRITE_BUFFER, 0);
+
+            if let Some(host_copy) = self.host_copy_ptr() {
+                std::ptr::copy_nonoverlapping(
+                    data.as_ptr(),
+                    host_copy.add(offset as usize),
+                    data.len(),
+                );
+            }
+        }
+
+        check_gl_error("Writing buffer")
+    }
+
+    // Copies the GL buffer back into the host copy after the GPU wrote to it. Must be called with
+    // the GL context locked.
+    pub(crate) fn refresh_host_copy(
+        &self,
+        offset: u64,
+        size: u64,
+    ) -> RafxResult<()> {
+        let host_copy = match self.host_copy_ptr() {
+            Some(host_copy) => host_copy,
+            None => return Ok(()),
+        };
+
+        if size > 0 {
+            unsafe {
+                gl::glBindBuffer(gl::COPY_READ_BUFFER, self.inner.gl_buffer);
+                let ptr = gl::glMapBufferRange(
+                    gl::COPY_READ_BUFFER,
+                    offset as _,
+                    size as _,
+                    gl::MAP_READ_BIT,
+                );
+                if !ptr.is_null() {
+                    std::ptr::copy_nonoverlapping(
+                        ptr as *const u8,
+                        host_copy.add(offset as usize),
+                        size as usize,
+                    );
+                    gl::glUnmapBuffer(gl::COPY_READ_BUFFER);
+                }
+                gl::glBindBuffer(gl::COPY_READ_BUFFER, 0);
+            }
+        }
+
+        check_gl_error("Reading back buffer")
+    }
+
+    // Uniform buffers are rounded up so that they can be bound at any offset
+    fn required_allocation_size(
+        device_context: &RafxDeviceContextGles3,
+        buffer_def: &RafxBufferDef,
+    ) -> u64 {
+        if buffer_def
+            .resource_type
+            .intersects(RafxResourceType::UNIFORM_BUFFER)
+        {
+            rafx_base::memory::round_size_up_to_alignment_u64(
+                buffer_def.size,
+                device_context
+                    .device_info()
+                    .min_uniform_buffer_offset_alignment as u64,
+            )
+        } else {
+            buffer_def.size
+        }
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextGles3,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
+        let allocation_size = Self::required_allocation_size(device_context, buffer_def);
+
+        let gl_buffer = {
+            let _guard = device_context.gl_context().lock()?;
+            let mut gl_buffer = 0;
+            unsafe {
+                gl::glGenBuffers(1, &mut gl_buffer);
+                gl::glBindBuffer(gl::COPY_WRITE_BUFFER, gl_buffer);
+                gl::glBufferData(
+                    gl::COPY_WRITE_BUFFER,
+                    allocation_size as _,
+                    std::ptr::null(),
+                    conversions::gl_buffer_usage(buffer_def.memory_usage),
+                );
+                gl::glBindBuffer(gl::COPY_WRITE_BUFFER, 0);
+            }
+
+            if let Err(e) = check_gl_error("glBufferData") {
+                unsafe {
+                    gl::glDeleteBuffers(1, &gl_buffer);
+                }
+                return Err(e);
+            }
+
+            gl_buffer
+        };
+
+        device_context
+            .memory_usage_tracker()
+            .on_buffer_allocated(buffer_def.memory_usage, allocation_size);
+
+        let host_copy = if buffer_def.memory_usage == RafxMemoryUsage::GpuOnly {
+            None
+        } else {
+            Some(UnsafeCell::new(
+                vec![0; allocation_size as usize].into_boxed_slice(),
+            ))
+        };
+
+        let inner = RafxBufferGles3Inner {
+            device_context: device_context.clone(),
+            buffer_def: buffer_def.clone(),
+            gl_buffer,
+            allocation_size,
+            host_copy,
+            host_copy_state: Default::default(),
+            #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+            buffer_id: crate::internal_shared::NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed),
+        };
+
+        let buffer = RafxBufferGles3 {
+            inner: Arc::new(inner),
+        };
+
+        if buffer_def.always_mapped {
+            buffer.map_buffer()?;
+        }
+
+        Ok(buffer)
+    }
+}
@@ -0,0 +1,1287 @@
+use super::descriptor_set_array::GlDescriptorSetValues;
+use super::execute::GlCommandExecutor;
+use crate::gles3::{
+    RafxBufferGles3, RafxCommandPoolGles3, RafxDescriptorSetArrayGles3,
+    RafxDescriptorSetHandleGles3, RafxPipelineGles3, RafxQueryPoolGles3, RafxQueueGles3,
+    RafxRootSignatureGles3, RafxTextureGles3,
+};
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxCommandBufferResourceStates;
+use crate::{
+    RafxBufferBarrier, RafxCmdBlitParams, RafxCmdClearTextureParams,
+    RafxCmdCopyBufferToTextureParams, RafxCmdCopyTextureToBufferParams,
+    RafxCmdCopyTextureToTextureParams, RafxColorClearValue, RafxColorRenderTargetBinding,
+    RafxCommandBufferDef, RafxCommandBufferInheritanceInfo, RafxDepthStencilClearValue,
+    RafxDepthStencilRenderTargetBinding, RafxDrawIndexedIndirectCommand, RafxDrawIndirectCommand,
+    RafxExtents3D, RafxIndexBufferBinding, RafxIndexType, RafxLoadOp, RafxPushConstantIndex,
+    RafxResourceState, RafxResourceType, RafxResult, RafxStoreOp, RafxTextureBarrier,
+    RafxTextureBufferLayout, RafxTextureClearValue, RafxTextureDimensions, RafxTextureView,
+    RafxVertexBufferBinding,
+};
+use rafx_base::trust_cell::TrustCell;
+use std::sync::Arc;
+
+/// A single mip level and array layer (or depth slice of a 3D texture) of a texture, attached to a
+/// framebuffer
+#[derive(Clone, Debug)]
+pub(crate) struct GlAttachment {
+    pub(crate) texture: RafxTextureGles3,
+    pub(crate) mip_level: u32,
+    pub(crate) array_layer: u32,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GlColorRenderTarget {
+    pub(crate) attachment: GlAttachment,
+    pub(crate) load_op: RafxLoadOp,
+    pub(crate) store_op: RafxStoreOp,
+    pub(crate) clear_value: RafxColorClearValue,
+    pub(crate) resolve_target: Option<GlAttachment>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GlDepthStencilRenderTarget {
+    pub(crate) attachment: GlAttachment,
+    pub(crate) depth_load_op: RafxLoadOp,
+    pub(crate) stencil_load_op: RafxLoadOp,
+    pub(crate) depth_store_op: RafxStoreOp,
+    pub(crate) stencil_store_op: RafxStoreOp,
+    pub(crate) clear_value: RafxDepthStencilClearValue,
+}
+
+/// A command recorded into a `RafxCommandBufferGles3`. GL calls can only be made with the context
+/// locked, so commands are recorded and then issued to GL when the command buffer is submitted.
+#[derive(Clone, Debug)]
+pub(crate) enum GlRecordedCommand {
+    BeginRenderPass {
+        color_targets: Vec<GlColorRenderTarget>,
+        depth_target: Option<GlDepthStencilRenderTarget>,
+        // True if the render pass was begun with cmd_begin_render_pass_for_secondary
+        secondary_command_buffers: bool,
+    },
+    EndRenderPass,
+    ExecuteSecondaryCommandBuffers {
+        command_buffers: Vec<Vec<GlRecordedCommand>>,
+    },
+    SetViewport {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        depth_min: f32,
+        depth_max: f32,
+    },
+    SetScissor {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    SetStencilReferenceValue(u32),
+    SetDepthBias {
+        constant_factor: f32,
+        slope_factor: f32,
+    },
+    SetBlendConstants([f32; 4]),
+    SetLineWidth(f32),
+    BindPipeline(RafxPipelineGles3),
+    BindVertexBuffers {
+        first_binding: u32,
+        bindings: Vec<(RafxBufferGles3, u64)>,
+    },
+    BindIndexBuffer {
+        buffer: RafxBufferGles3,
+        byte_offset: u64,
+        index_type: RafxIndexType,
+    },
+    BindDescriptorSet {
+        set_index: u32,
+        array_index: u32,
+        values: Arc<GlDescriptorSetValues>,
+    },
+    BindPushConstant {
+        gl_binding: u32,
+        buffer_offset: u32,
+        data: Vec<u8>,
+    },
+    Draw {
+        vertex_count: u32,
+        first_vertex: u32,
+        instance_count: u32,
+        first_instance: u32,
+    },
+    DrawIndexed {
+        index_count: u32,
+        first_index: u32,
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: i32,
+    },
+    DrawIndirect {
+        indirect_buffer: RafxBufferGles3,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    DrawIndexedIndirect {
+        indirect_buffer: RafxBufferGles3,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    // GL orders all access to resources itself, barriers are only recorded so that they are
+    // validated like other transfer commands
+    ResourceBarrier,
+    ClearTexture {
+        texture: RafxTextureGles3,
+        clear_value: RafxTextureClearValue,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    },
+    FillBuffer {
+        buffer: RafxBufferGles3,
+        offset: u64,
+        size: u64,
+        value: u8,
+    },
+    CopyBufferToBuffer {
+        src_buffer: RafxBufferGles3,
+        dst_buffer: RafxBufferGles3,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
+    },
+    CopyBufferToTexture {
+        src_buffer: RafxBufferGles3,
+        dst_texture: RafxTextureGles3,
+        buffer_offset: u64,
+        array_layer: u16,
+        mip_level: u8,
+    },
+    CopyTextureToBuffer {
+        src_texture: RafxTextureGles3,
+        dst_buffer: RafxBufferGles3,
+        buffer_offset: u64,
+        array_layer: u16,
+        mip_level: u8,
+    },
+    CopyTextureToTexture {
+        src_texture: RafxTextureGles3,
+        dst_texture: RafxTextureGles3,
+        src_offset: RafxExtents3D,
+        dst_offset: RafxExtents3D,
+        extents: RafxExtents3D,
+        src_mip_level: u8,
+        dst_mip_level: u8,
+        array_slices: Option<[u16; 2]>,
+    },
+    Blit {
+        src_texture: RafxTextureGles3,
+        dst_texture: RafxTextureGles3,
+        src_extents: [RafxExtents3D; 2],
+        dst_extents: [RafxExtents3D; 2],
+        src_mip_level: u8,
+        dst_mip_level: u8,
+        array_slices: Option<[u16; 2]>,
+    },
+    PushGroupDebugMarker,
+    PopGroupDebugMarker,
+}
+
+#[derive(Debug, Default)]
+struct RafxCommandBufferGles3Inner {
+    is_recording: bool,
+    in_render_pass: bool,
+    // The render pass was inherited by begin_secondary, so it can't be ended by this command buffer
+    inherited_render_pass: bool,
+    // The render pass was begun with cmd_begin_render_pass_for_secondary
+    secondary_render_pass: bool,
+    // The most recently bound graphics pipeline uses RafxRasterizerState::dynamic_depth_bias
+    dynamic_depth_bias_bound: bool,
+    // Number of debug marker groups that have been pushed but not popped
+    debug_marker_depth: u32,
+    commands: Vec<GlRecordedCommand>,
+}
+
+/// Records commands into a list that is issued to GL when the command buffer is submitted. GL ES
+/// 3.0 has no compute shaders or queries, so recording a dispatch or query command fails.
+#[derive(Debug)]
+pub struct RafxCommandBufferGles3 {
+    queue: RafxQueueGles3,
+    is_secondary: bool,
+    inner: TrustCell<RafxCommandBufferGles3Inner>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxCommandBufferResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl RafxCommandBufferGles3 {
+    pub fn queue(&self) -> &RafxQueueGles3 {
+        &self.queue
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.inner.borrow().is_recording
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxCommandBufferResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.queue.device_context().capture_recorder()
+    }
+
+    // Issues the recorded commands to GL. Must be called with the GL context locked.
+    pub(crate) fn execute(
+        &self,
+        executor: &mut GlCommandExecutor,
+    ) -> RafxResult<()> {
+        executor.execute_commands(&self.inner.borrow().commands)
+    }
+
+    pub fn new(
+        command_pool: &RafxCommandPoolGles3,
+        command_buffer_def: &RafxCommandBufferDef,
+    ) -> RafxResult<RafxCommandBufferGles3> {
+        Ok(RafxCommandBufferGles3 {
+            queue: command_pool.queue().clone(),
+            is_secondary: command_buffer_def.is_secondary,
+            inner: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        })
+    }
+
+    fn record(
+        &self,
+        command: GlRecordedCommand,
+    ) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.is_recording {
+            return Err("Recorded a command into a command buffer that has not begun")?;
+        }
+
+        if inner.secondary_render_pass {
+            match &command {
+                GlRecordedCommand::EndRenderPass
+                | GlRecordedCommand::ExecuteSecondaryCommandBuffers { .. }
+                | GlRecordedCommand::PushGroupDebugMarker
+                | GlRecordedCommand::PopGroupDebugMarker => {}
+                _ => {
+                    return Err("Only secondary command buffers can be executed inside a render pass begun with cmd_begin_render_pass_for_secondary")?;
+                }
+            }
+        }
+
+        match &command {
+            GlRecordedCommand::BeginRenderPass {
+                secondary_command_buffers,
+                ..
+            } => {
+                if inner.in_render_pass {
+                    return Err("cmd_begin_render_pass called while a render pass is active")?;
+                }
+                inner.in_render_pass = true;
+                inner.secondary_render_pass = *secondary_command_buffers;
+            }
+            GlRecordedCommand::EndRenderPass => {
+                if !inner.in_render_pass {
+                    return Err("cmd_end_render_pass called without an active render pass")?;
+                }
+                if inner.inherited_render_pass {
+                    return Err(
+                        "cmd_end_render_pass called on a secondary command buffer that inherited its render pass",
+                    )?;
+                }
+                inner.in_render_pass = false;
+                inner.secondary_render_pass = false;
+            }
+            GlRecordedCommand::ExecuteSecondaryCommandBuffers { .. }
+                if !inner.secondary_render_pass =>
+            {
+                return Err("cmd_execute_secondary_command_buffers must be called in a render pass begun with cmd_begin_render_pass_for_secondary")?;
+            }
+            GlRecordedCommand::ResourceBarrier
+            | GlRecordedCommand::ClearTexture { .. }
+            | GlRecordedCommand::FillBuffer { .. }
+            | GlRecordedCommand::CopyBufferToBuffer { .. }
+            | GlRecordedCommand::CopyBufferToTexture { .. }
+            | GlRecordedCommand::CopyTextureToBuffer { .. }
+            | GlRecordedCommand::CopyTextureToTexture { .. }
+            | GlRecordedCommand::Blit { .. }
+                if inner.in_render_pass =>
+            {
+                return Err("Barriers and copies cannot be recorded inside a render pass")?;
+            }
+            GlRecordedCommand::BindPipeline(pipeline) => {
+                inner.dynamic_depth_bias_bound = pipeline
+                    .graphics_state()
+                    .rasterizer_state
+                    .dynamic_depth_bias;
+            }
+            GlRecordedCommand::SetDepthBias { .. } if !inner.dynamic_depth_bias_bound => {
+                return Err("cmd_set_depth_bias called without a bound pipeline that uses RafxRasterizerState::dynamic_depth_bias")?;
+            }
+            GlRecordedCommand::PushGroupDebugMarker => {
+                inner.debug_marker_depth += 1;
+            }
+            GlRecordedCommand::PopGroupDebugMarker => {
+                if inner.debug_marker_depth == 0 {
+                    return Err(
+                        "cmd_pop_group_debug_marker called without an active debug marker group",
+                    )?;
+                }
+                inner.debug_marker_depth -= 1;
+            }
+            _ => {}
+        }
+
+        inner.commands.push(command);
+        Ok(())
+    }
+
+    pub fn begin(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        inner.commands.clear();
+        inner.in_render_pass = false;
+        inner.inherited_render_pass = false;
+        inner.secondary_render_pass = false;
+        inner.dynamic_depth_bias_bound = false;
+        inner.debug_marker_depth = 0;
+        inner.is_recording = true;
+        Ok(())
+    }
+
+    pub fn begin_secondary(
+        &self,
+        _inheritance_info: &RafxCommandBufferInheritanceInfo,
+    ) -> RafxResult<()> {
+        if !self.is_secondary {
+            return Err("begin_secondary called on a primary command buffer")?;
+        }
+
+        self.begin()?;
+        let mut inner = self.inner.borrow_mut();
+        inner.in_render_pass = true;
+        inner.inherited_render_pass = true;
+        Ok(())
+    }
+
+    pub fn end(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.in_render_pass && !inner.inherited_render_pass {
+            return Err("Command buffer ended with an active render pass")?;
+        }
+        if inner.debug_marker_depth != 0 {
+            return Err("Command buffer ended with a debug marker group that was not popped")?;
+        }
+        inner.is_recording = false;
+        Ok(())
+    }
+
+    pub fn return_to_pool(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        inner.commands.clear();
+        inner.in_render_pass = false;
+        inner.inherited_render_pass = false;
+        inner.secondary_render_pass = false;
+        inner.debug_marker_depth = 0;
+        inner.is_recording = false;
+        Ok(())
+    }
+
+    pub fn cmd_begin_render_pass(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, false)
+    }
+
+    pub fn cmd_begin_render_pass_for_secondary(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, true)
+    }
+
+    fn do_begin_render_pass(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+        secondary_command_buffers: bool,
+    ) -> RafxResult<()> {
+        if color_targets.is_empty() && depth_target.is_none() {
+            Err("No color or depth target supplied to cmd_begin_render_pass")?;
+        }
+
+        let max_color_targets = self
+            .queue
+            .device_context()
+            .device_info()
+            .max_color_render_targets;
+        if color_targets.len() > max_color_targets as usize {
+            return Err(format!(
+                "cmd_begin_render_pass was passed {} color targets but the device supports {}",
+                color_targets.len(),
+                max_color_targets
+            ))?;
+        }
+
+        let mut recorded_color_targets = Vec::with_capacity(color_targets.len());
+        for color_target in color_targets {
+            let attachment = Self::render_target_attachment(
+                color_target.texture.gles3_texture().unwrap(),
+                color_target.texture_view,
+                color_target.mip_slice,
+                color_target.array_slice,
+            )?;
+
+            let resolve_target = if let Some(resolve_target) = color_target.resolve_target {
+                Some(Self::render_target_attachment(
+                    resolve_target.gles3_texture().unwrap(),
+                    None,
+                    color_target.resolve_mip_slice,
+                    color_target.resolve_array_slice,
+                )?)
+            } else {
+                None
+            };
+
+            recorded_color_targets.push(GlColorRenderTarget {
+                attachment,
+                load_op: color_target.load_op,
+                store_op: color_target.store_op,
+                clear_value: color_target.clear_value,
+                resolve_target,
+            });
+        }
+
+        let depth_target = if let Some(depth_target) = depth_target {
+            let attachment = Self::render_target_attachment(
+                depth_target.texture.gles3_texture().unwrap(),
+                depth_target.texture_view,
+                depth_target.mip_slice,
+                depth_target.array_slice,
+            )?;
+
+            Some(GlDepthStencilRenderTarget {
+                attachment,
+                depth_load_op: depth_target.depth_load_op,
+                stencil_load_op: depth_target.stencil_load_op,
+                depth_store_op: depth_target.depth_store_op,
+                stencil_store_op: depth_target.stencil_store_op,
+                clear_value: depth_target.clear_value,
+            })
+        } else {
+            None
+        };
+
+        self.record(GlRecordedCommand::BeginRenderPass {
+            color_targets: recorded_color_targets,
+            depth_target,
+            secondary_command_buffers,
+        })
+    }
+
+    // Determines the subresource that a render target binding draws to. GL ES 3.0 can't attach
+    // more than one layer of a texture to a framebuffer, so layered rendering is not supported.
+    fn render_target_attachment(
+        texture: &RafxTextureGles3,
+        texture_view: Option<&RafxTextureView>,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    ) -> RafxResult<GlAttachment> {
+        let texture_def = texture.texture_def();
+        let layer_count = if texture_def
+            .dimensions
+            .determine_dimensions(texture_def.extents)
+            == RafxTextureDimensions::Dim3D
+        {
+            texture_def.extents.depth
+        } else {
+            texture_def.array_length
+        };
+
+        let (mip_level, array_layers) = if let Some(texture_view) = texture_view {
+            let texture_view = texture_view.gles3_texture_view().unwrap();
+            let texture_view_def = texture_view.texture_view_def();
+            crate::internal_shared::verify_render_target_view(
+                texture_def,
+                texture_view_def,
+                texture_view.gles3_texture() == texture,
+                mip_slice,
+                array_slice,
+            )?;
+
+            (
+                texture_view_def.mip_range.as_ref().unwrap().start,
+                texture_view_def.array_range.clone().unwrap(),
+            )
+        } else {
+            let array_layers = match array_slice {
+                Some(array_slice) => array_slice as u32..array_slice as u32 + 1,
+                None => 0..layer_count,
+            };
+            (mip_slice.unwrap_or(0) as u32, array_layers)
+        };
+
+        if array_layers.end - array_layers.start != 1 {
+            return Err(format!(
+                "GL ES 3.0 can only render to a single array layer at a time, the render target covers layers {:?}",
+                array_layers
+            ))?;
+        }
+
+        if mip_level >= texture_def.mip_count || array_layers.start >= layer_count {
+            return Err(format!(
+                "Render target mip {} layer {} is out of range for a texture with {} mips and {} layers",
+                mip_level, array_layers.start, texture_def.mip_count, layer_count
+            ))?;
+        }
+
+        Ok(GlAttachment {
+            texture: texture.clone(),
+            mip_level,
+            array_layer: array_layers.start,
+        })
+    }
+
+    pub fn cmd_end_render_pass(&self) -> RafxResult<()> {
+        self.record(GlRecordedCommand::EndRenderPass)
+    }
+
+    pub fn cmd_execute_secondary_command_buffers(
+        &self,
+        secondary_command_buffers: &[&RafxCommandBufferGles3],
+    ) -> RafxResult<()> {
+        let mut command_buffers = Vec::with_capacity(secondary_command_buffers.len());
+        for secondary_command_buffer in secondary_command_buffers {
+            if !secondary_command_buffer.is_secondary {
+                return Err(
+                    "cmd_execute_secondary_command_buffers was passed a primary command buffer",
+                )?;
+            }
+
+            if secondary_command_buffer.is_recording() {
+                return Err("cmd_execute_secondary_command_buffers was passed a secondary command buffer that has not ended")?;
+            }
+
+            command_buffers.push(secondary_command_buffer.inner.borrow().commands.clone());
+        }
+
+        self.record(GlRecordedCommand::ExecuteSecondaryCommandBuffers { command_buffers })
+    }
+
+    pub fn cmd_set_viewport(
+        &self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        depth_min: f32,
+        depth_max: f32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::SetViewport {
+            x,
+            y,
+            width,
+            height,
+            depth_min,
+            depth_max,
+        })
+    }
+
+    pub fn cmd_set_scissor(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::SetScissor {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    pub fn cmd_set_stencil_reference_value(
+        &self,
+        value: u32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::SetStencilReferenceValue(value))
+    }
+
+    pub fn cmd_set_depth_bias(
+        &self,
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    ) -> RafxResult<()> {
+        if clamp != 0.0 {
+            return Err("GL ES 3.0 does not support depth bias clamping, clamp must be 0.0")?;
+        }
+
+        self.record(GlRecordedCommand::SetDepthBias {
+            constant_factor,
+            slope_factor,
+        })
+    }
+
+    pub fn cmd_set_blend_constants(
+        &self,
+        blend_constants: [f32; 4],
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::SetBlendConstants(blend_constants))
+    }
+
+    pub fn cmd_set_line_width(
+        &self,
+        line_width: f32,
+    ) -> RafxResult<()> {
+        self.queue
+            .device_context()
+            .device_info()
+            .verify_line_width(line_width)?;
+        self.record(GlRecordedCommand::SetLineWidth(line_width))
+    }
+
+    pub fn cmd_bind_pipeline(
+        &self,
+        pipeline: &RafxPipelineGles3,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::BindPipeline(pipeline.clone()))
+    }
+
+    pub fn cmd_bind_vertex_buffers(
+        &self,
+        first_binding: u32,
+        bindings: &[RafxVertexBufferBinding],
+    ) -> RafxResult<()> {
+        let bindings = bindings
+            .iter()
+            .map(|x| (x.buffer.gles3_buffer().unwrap().clone(), x.byte_offset))
+            .collect();
+
+        self.record(GlRecordedCommand::BindVertexBuffers {
+            first_binding,
+            bindings,
+        })
+    }
+
+    pub fn cmd_bind_index_buffer(
+        &self,
+        binding: &RafxIndexBufferBinding,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::BindIndexBuffer {
+            buffer: binding.buffer.gles3_buffer().unwrap().clone(),
+            byte_offset: binding.byte_offset,
+            index_type: binding.index_type,
+        })
+    }
+
+    pub fn cmd_bind_descriptor_set(
+        &self,
+        descriptor_set_array: &RafxDescriptorSetArrayGles3,
+        index: u32,
+    ) -> RafxResult<()> {
+        if index >= descriptor_set_array.array_length() {
+            return Err(format!(
+                "Bound descriptor set index {} but the array only has {} sets",
+                index,
+                descriptor_set_array.array_length()
+            ))?;
+        }
+
+        self.record(GlRecordedCommand::BindDescriptorSet {
+            set_index: descriptor_set_array.set_index(),
+            array_index: index,
+            values: descriptor_set_array.values().clone(),
+        })
+    }
+
+    pub fn cmd_bind_descriptor_set_handle(
+        &self,
+        _root_signature: &RafxRootSignatureGles3,
+        set_index: u32,
+        descriptor_set_handle: &RafxDescriptorSetHandleGles3,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::BindDescriptorSet {
+            set_index,
+            array_index: descriptor_set_handle.array_index(),
+            values: descriptor_set_handle.values().clone(),
+        })
+    }
+
+    pub fn cmd_bind_push_constant(
+        &self,
+        root_signature: &RafxRootSignatureGles3,
+        push_constant_index: RafxPushConstantIndex,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        let push_constant = root_signature
+            .push_constant(push_constant_index)
+            .ok_or("Push constant index is not valid for this root signature")?;
+
+        if data.len() as u32 > push_constant.size_in_bytes {
+            Err(format!(
+                "Push constant {:?} is {} bytes but {} bytes were provided",
+                push_constant.name,
+                push_constant.size_in_bytes,
+                data.len()
+            ))?;
+        }
+
+        self.record(GlRecordedCommand::BindPushConstant {
+            gl_binding: push_constant.gl_binding,
+            buffer_offset: push_constant.buffer_offset,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn cmd_draw(
+        &self,
+        vertex_count: u32,
+        first_vertex: u32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::Draw {
+            vertex_count,
+            first_vertex,
+            instance_count: 1,
+            first_instance: 0,
+        })
+    }
+
+    pub fn cmd_draw_instanced(
+        &self,
+        vertex_count: u32,
+        first_vertex: u32,
+        instance_count: u32,
+        first_instance: u32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::Draw {
+            vertex_count,
+            first_vertex,
+            instance_count,
+            first_instance,
+        })
+    }
+
+    pub fn cmd_draw_indexed(
+        &self,
+        index_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::DrawIndexed {
+            index_count,
+            first_index,
+            instance_count: 1,
+            first_instance: 0,
+            vertex_offset,
+        })
+    }
+
+    pub fn cmd_draw_indexed_instanced(
+        &self,
+        index_count: u32,
+        first_index: u32,
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: i32,
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::DrawIndexed {
+            index_count,
+            first_index,
+            instance_count,
+            first_instance,
+            vertex_offset,
+        })
+    }
+
+    pub fn cmd_dispatch(
+        &self,
+        _group_count_x: u32,
+        _group_count_y: u32,
+        _group_count_z: u32,
+    ) -> RafxResult<()> {
+        Err("GL ES 3.0 does not support compute shaders")?
+    }
+
+    // Checks that an indirect buffer can hold `count` sets of arguments of `args_size` bytes each,
+    // starting at `offset` and spaced `stride` bytes apart
+    fn validate_indirect_buffer(
+        command_name: &str,
+        indirect_buffer: &RafxBufferGles3,
+        offset: u64,
+        count: u32,
+        stride: u32,
+        args_size: u64,
+    ) -> RafxResult<()> {
+        let buffer_def = indirect_buffer.buffer_def();
+        if !buffer_def
+            .resource_type
+            .contains(RafxResourceType::INDIRECT_BUFFER)
+        {
+            return Err(format!(
+                "{} requires a buffer created with RafxResourceType::INDIRECT_BUFFER",
+                command_name
+            ))?;
+        }
+
+        if count > 1 && (stride as u64) < args_size {
+            return Err(format!(
+                "{} stride {} is smaller than the argument size {}",
+                command_name, stride, args_size
+            ))?;
+        }
+
+        if count > 0 && offset + (count as u64 - 1) * stride as u64 + args_size > buffer_def.size {
+            return Err(format!(
+                "{} reads past the end of the indirect buffer",
+                command_name
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_draw_indirect(
+        &self,
+        indirect_buffer: &RafxBufferGles3,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        Self::validate_indirect_buffer(
+            "cmd_draw_indirect",
+            indirect_buffer,
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+            std::mem::size_of::<RafxDrawIndirectCommand>() as u64,
+        )?;
+
+        self.record(GlRecordedCommand::DrawIndirect {
+            indirect_buffer: indirect_buffer.clone(),
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+        })
+    }
+
+    pub fn cmd_draw_indexed_indirect(
+        &self,
+        indirect_buffer: &RafxBufferGles3,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        Self::validate_indirect_buffer(
+            "cmd_draw_indexed_indirect",
+            indirect_buffer,
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+            std::mem::size_of::<RafxDrawIndexedIndirectCommand>() as u64,
+        )?;
+
+        self.record(GlRecordedCommand::DrawIndexedIndirect {
+            indirect_buffer: indirect_buffer.clone(),
+            indirect_buffer_offset_in_bytes,
+            draw_count,
+            stride,
+        })
+    }
+
+    pub fn cmd_dispatch_indirect(
+        &self,
+        _indirect_buffer: &RafxBufferGles3,
+        _indirect_buffer_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        Err("GL ES 3.0 does not support compute shaders")?
+    }
+
+    pub fn cmd_reset_query_pool(
+        &self,
+        _query_pool: &RafxQueryPoolGles3,
+        _first_query: u32,
+        _query_count: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the GL ES backend")?
+    }
+
+    pub fn cmd_begin_query(
+        &self,
+        _query_pool: &RafxQueryPoolGles3,
+        _query_index: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the GL ES backend")?
+    }
+
+    pub fn cmd_end_query(
+        &self,
+        _query_pool: &RafxQueryPoolGles3,
+        _query_index: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the GL ES backend")?
+    }
+
+    pub fn cmd_write_timestamp(
+        &self,
+        _query_pool: &RafxQueryPoolGles3,
+        _query_index: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the GL ES backend")?
+    }
+
+    pub fn cmd_resolve_query(
+        &self,
+        _query_pool: &RafxQueryPoolGles3,
+        _first_query: u32,
+        _query_count: u32,
+        _dst_buffer: &RafxBufferGles3,
+        _dst_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the GL ES backend")?
+    }
+
+    pub fn cmd_resource_barrier(
+        &self,
+        _buffer_barriers: &[RafxBufferBarrier],
+        _texture_barriers: &[RafxTextureBarrier],
+    ) -> RafxResult<()> {
+        self.record(GlRecordedCommand::ResourceBarrier)
+    }
+
+    pub fn cmd_clear_texture(
+        &self,
+        texture: &RafxTextureGles3,
+        params: &RafxCmdClearTextureParams,
+    ) -> RafxResult<()> {
+        if params.texture_state != RafxResourceState::COPY_DST
+            && params.texture_state != RafxResourceState::UNORDERED_ACCESS
+        {
+            return Err(format!(
+                "cmd_clear_texture requires the texture to be in COPY_DST or UNORDERED_ACCESS state but got {:?}",
+                params.texture_state
+            ))?;
+        }
+
+        let texture_def = texture.texture_def();
+        let is_depth_stencil_value = match params.clear_value {
+            RafxTextureClearValue::Color(_) => false,
+            RafxTextureClearValue::DepthStencil(_) => true,
+        };
+        if is_depth_stencil_value != texture_def.format.has_depth_or_stencil() {
+            return Err(format!(
+                "cmd_clear_texture clear value {:?} does not match format {:?}",
+                params.clear_value, texture_def.format
+            ))?;
+        }
+
+        if params
+            .mip_slice
+            .is_some_and(|x| x as u32 >= texture_def.mip_count)
+            || params
+                .array_slice
+                .is_some_and(|x| x as u32 >= texture_def.array_length)
+        {
+            return Err("cmd_clear_texture mip or array slice is out of range")?;
+        }
+
+        // Clears are drawn through a framebuffer, which compressed formats can't be attached to
+        if texture.format_info().is_compressed {
+            return Err(format!(
+                "GL ES 3.0 cannot clear textures with compressed format {:?}",
+                texture_def.format
+            ))?;
+        }
+
+        self.record(GlRecordedCommand::ClearTexture {
+            texture: texture.clone(),
+            clear_value: params.clear_value,
+            mip_slice: params.mip_slice,
+            array_slice: params.array_slice,
+        })
+    }
+
+    pub fn cmd_fill_buffer(
+        &self,
+        buffer: &RafxBufferGles3,
+        offset: u64,
+        size: u64,
+        value: u8,
+    ) -> RafxResult<()> {
+        if offset % 4 != 0 || size % 4 != 0 {
+            return Err("cmd_fill_buffer offset and size must be multiples of 4")?;
+        }
+
+        if offset + size > buffer.buffer_def().size {
+            return Err("cmd_fill_buffer writes past the end of the buffer")?;
+        }
+
+        self.record(GlRecordedCommand::FillBuffer {
+            buffer: buffer.clone(),
+            offset,
+            size,
+            value,
+        })
+    }
+
+    pub fn cmd_copy_buffer_to_buffer(
+        &self,
+        src_buffer: &RafxBufferGles3,
+        dst_buffer: &RafxBufferGles3,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
+    ) -> RafxResult<()> {
+        if src_offset + size > src_buffer.buffer_def().size {
+            return Err("cmd_copy_buffer_to_buffer reads past the end of the source buffer")?;
+        }
+
+        if dst_offset + size > dst_buffer.buffer_def().size {
+            return Err("cmd_copy_buffer_to_buffer writes past the end of the destination buffer")?;
+        }
+
+        self.record(GlRecordedCommand::CopyBufferToBuffer {
+            src_buffer: src_buffer.clone(),
+            dst_buffer: dst_buffer.clone(),
+            src_offset,
+            dst_offset,
+            size,
+        })
+    }
+
+    pub fn cmd_copy_buffer_to_texture(
+        &self,
+        src_buffer: &RafxBufferGles3,
+        dst_texture: &RafxTextureGles3,
+        params: &RafxCmdCopyBufferToTextureParams,
+    ) -> RafxResult<()> {
+        let texture_def = dst_texture.texture_def();
+        if params.mip_level as u32 >= texture_def.mip_count
+            || params.array_layer as u32 >= texture_def.array_length
+        {
+            return Err(format!(
+                "cmd_copy_buffer_to_texture targets mip {} layer {} but the texture has {} mips and {} layers",
+                params.mip_level,
+                params.array_layer,
+                texture_def.mip_count,
+                texture_def.array_length
+            ))?;
+        }
+
+        if !matches!(
+            dst_texture.gl_raw_texture(),
+            super::RafxRawTextureGles3::Texture { .. }
+        ) {
+            return Err("cmd_copy_buffer_to_texture cannot write to a multisampled texture")?;
+        }
+
+        let layout = RafxTextureBufferLayout::new(
+            self.queue.device_context().device_info(),
+            texture_def.format,
+            texture_def.extents,
+            params.mip_level,
+        );
+        let required_size = params.buffer_offset + layout.size;
+        if required_size > src_buffer.buffer_def().size {
+            return Err(format!(
+                "cmd_copy_buffer_to_texture reads {} bytes from a buffer of size {}",
+                required_size,
+                src_buffer.buffer_def().size
+            ))?;
+        }
+
+        self.record(GlRecordedCommand::CopyBufferToTexture {
+            src_buffer: src_buffer.clone(),
+            dst_texture: dst_texture.clone(),
+            buffer_offset: params.buffer_offset,
+            array_layer: params.array_layer,
+            mip_level: params.mip_level,
+        })
+    }
+
+    pub fn cmd_copy_texture_to_buffer(
+        &self,
+        src_texture: &RafxTextureGles3,
+        dst_buffer: &RafxBufferGles3,
+        params: &RafxCmdCopyTextureToBufferParams,
+    ) -> RafxResult<()> {
+        let texture_def = src_texture.texture_def();
+        if params.mip_level as u32 >= texture_def.mip_count
+            || params.array_layer as u32 >= texture_def.array_length
+        {
+            return Err(format!(
+                "cmd_copy_texture_to_buffer reads mip {} layer {} but the texture has {} mips and {} layers",
+                params.mip_level,
+                params.array_layer,
+                texture_def.mip_count,
+                texture_def.array_length
+            ))?;
+        }
+
+        // Textures are read back with glReadPixels, which can't read depth, stencil or compressed
+        // data in GL ES
+        if texture_def.format.has_depth_or_stencil() || src_texture.format_info().is_compressed {
+            return Err(format!(
+                "GL ES 3.0 cannot copy textures with format {:?} to a buffer",
+                texture_def.format
+            ))?;
+        }
+
+        let layout = RafxTextureBufferLayout::new(
+            self.queue.device_context().device_info(),
+            texture_def.format,
+            texture_def.extents,
+            params.mip_level,
+        );
+        let required_size = params.buffer_offset + layout.size;
+        if required_size > dst_buffer.buffer_def().size {
+            return Err(format!(
+                "cmd_copy_texture_to_buffer writes {} bytes to a buffer of size {}",
+                required_size,
+                dst_buffer.buffer_def().size
+            ))?;
+        }
+
+        self.record(GlRecordedCommand::CopyTextureToBuffer {
+            src_texture: src_texture.clone(),
+            dst_buffer: dst_buffer.clone(),
+            buffer_offset: params.buffer_offset,
+            array_layer: params.array_layer,
+            mip_level: params.mip_level,
+        })
+    }
+
+    pub fn cmd_copy_texture_to_texture(
+        &self,
+        src_texture: &RafxTextureGles3,
+        dst_texture: &RafxTextureGles3,
+        params: &RafxCmdCopyTextureToTextureParams,
+    ) -> RafxResult<()> {
+        let src_def = src_texture.texture_def();
+        let dst_def = dst_texture.texture_def();
+        if src_def.format != dst_def.format {
+            return Err(format!(
+                "cmd_copy_texture_to_texture requires matching formats but got {:?} and {:?}",
+                src_def.format, dst_def.format
+            ))?;
+        }
+
+        // Copies are done with glBlitFramebuffer, which requires both textures to be attachable
+        if src_texture.format_info().is_compressed {
+            return Err(format!(
+                "GL ES 3.0 cannot copy between textures with compressed format {:?}",
+                src_def.format
+            ))?;
+        }
+
+        if params.src_mip_level as u32 >= src_def.mip_count
+            || params.dst_mip_level as u32 >= dst_def.mip_count
+        {
+            return Err("cmd_copy_texture_to_texture mip level is out of range")?;
+        }
+
+        if let Some(array_slices) = params.array_slices {
+            if array_slices[0] as u32 >= src_def.array_length
+                || array_slices[1] as u32 >= dst_def.array_length
+            {
+                return Err("cmd_copy_texture_to_texture array slice is out of range")?;
+            }
+        }
+
+        let check_region =
+            |offset: RafxExtents3D, texture_extents: RafxExtents3D, mip_level: u8| {
+                let mip_extents = RafxExtents3D {
+                    width: 1.max(texture_extents.width >> mip_level),
+                    height: 1.max(texture_extents.height >> mip_level),
+                    depth: 1.max(texture_extents.depth >> mip_level),
+                };
+
+                offset.width + params.extents.width <= mip_extents.width
+                    && offset.height + params.extents.height <= mip_extents.height
+                    && offset.depth + params.extents.depth <= mip_extents.depth
+            };
+
+        if !check_region(params.src_offset, src_def.extents, params.src_mip_level)
+            || !check_region(params.dst_offset, dst_def.extents, params.dst_mip_level)
+        {
+            return Err("cmd_copy_texture_to_texture region is out of bounds")?;
+        }
+
+        self.record(GlRecordedCommand::CopyTextureToTexture {
+            src_texture: src_texture.clone(),
+            dst_texture: dst_texture.clone(),
+            src_offset: params.src_offset,
+            dst_offset: params.dst_offset,
+            extents: params.extents,
+            src_mip_level: params.src_mip_level,
+            dst_mip_level: params.dst_mip_level,
+            array_slices: params.array_slices,
+        })
+    }
+
+    pub fn cmd_blit(
+        &self,
+        src_texture: &RafxTextureGles3,
+        dst_texture: &RafxTextureGles3,
+        params: &RafxCmdBlitParams,
+    ) -> RafxResult<()> {
+        if params.src_mip_level as u32 >= src_texture.texture_def().mip_count
+            || params.dst_mip_level as u32 >= dst_texture.texture_def().mip_count
+        {
+            return Err("cmd_blit mip level is out of range")?;
+        }
+
+        if src_texture.format_info().is_compressed || dst_texture.format_info().is_compressed {
+            return Err("GL ES 3.0 cannot blit textures with compressed formats")?;
+        }
+
+        // Each depth slice is blitted separately, so slices can't be scaled
+        let src_depth = params.src_extents[1].depth as i64 - params.src_extents[0].depth as i64;
+        let dst_depth = params.dst_extents[1].depth as i64 - params.dst_extents[0].depth as i64;
+        if src_depth != dst_depth {
+            return Err("GL ES 3.0 cannot scale blits along the depth axis")?;
+        }
+
+        self.record(GlRecordedCommand::Blit {
+            src_texture: src_texture.clone(),
+            dst_texture: dst_texture.clone(),
+            src_extents: params.src_extents,
+            dst_extents: params.dst_extents,
+            src_mip_level: params.src_mip_level,
+            dst_mip_level: params.dst_mip_level,
+            array_slices: params.array_slices,
+        })
+    }
+
+    pub fn cmd_push_group_debug_marker(
+        &self,
+        _name: impl AsRef<str>,
+    ) -> RafxResult<()> {
+        // GL ES 3.0 has no debug groups without KHR_debug, the marker is only tracked so that
+        // unbalanced groups are reported like on other backends
+        self.record(GlRecordedCommand::PushGroupDebugMarker)
+    }
+
+    pub fn cmd_pop_group_debug_marker(&self) -> RafxResult<()> {
+        self.record(GlRecordedCommand::PopGroupDebugMarker)
+    }
+}
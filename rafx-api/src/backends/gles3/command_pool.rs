@@ -0,0 +1,41 @@
+use crate::gles3::{RafxCommandBufferGles3, RafxDeviceContextGles3, RafxQueueGles3};
+use crate::{RafxCommandBufferDef, RafxCommandPoolDef, RafxQueueType, RafxResult};
+
+pub struct RafxCommandPoolGles3 {
+    queue: RafxQueueGles3,
+}
+
+impl RafxCommandPoolGles3 {
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        self.queue.device_context()
+    }
+
+    pub fn queue_type(&self) -> RafxQueueType {
+        self.queue.queue_type()
+    }
+
+    pub fn queue(&self) -> &RafxQueueGles3 {
+        &self.queue
+    }
+
+    pub fn create_command_buffer(
+        &self,
+        command_buffer_def: &RafxCommandBufferDef,
+    ) -> RafxResult<RafxCommandBufferGles3> {
+        RafxCommandBufferGles3::new(self, command_buffer_def)
+    }
+
+    pub fn reset_command_pool(&self) -> RafxResult<()> {
+        // do nothing, command buffers clear their recorded commands when they begin
+        Ok(())
+    }
+
+    pub fn new(
+        queue: &RafxQueueGles3,
+        _command_pool_def: &RafxCommandPoolDef,
+    ) -> RafxResult<RafxCommandPoolGles3> {
+        Ok(RafxCommandPoolGles3 {
+            queue: queue.clone(),
+        })
+    }
+}
@@ -0,0 +1,357 @@
+use super::root_signature::DescriptorInfo;
+use crate::gles3::{
+    RafxBufferGles3, RafxDeviceContextGles3, RafxSamplerGles3, RafxTextureGles3,
+    RafxTextureViewGles3,
+};
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxDescriptorSetResourceStates;
+use crate::{
+    RafxDescriptorKey, RafxDescriptorSetArrayDef, RafxDescriptorUpdate, RafxResourceType,
+    RafxResult, RafxRootSignature, RafxTextureBindType,
+};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+// The resource written to one element of a descriptor
+#[derive(Clone, Debug)]
+pub(crate) enum GlDescriptorValue {
+    Empty,
+    Buffer {
+        buffer: RafxBufferGles3,
+        offset: u64,
+        size: u64,
+    },
+    Texture {
+        texture: RafxTextureGles3,
+        mip_range: Range<u32>,
+    },
+    Sampler(RafxSamplerGles3),
+}
+
+// The values of every descriptor set in the array, indexed by array index and then by
+// DescriptorInfo::value_offset + element index. Shared with recorded commands that bind the sets,
+// the values are read when the commands are executed.
+#[derive(Debug)]
+pub(crate) struct GlDescriptorSetValues {
+    sets: Vec<Mutex<Vec<GlDescriptorValue>>>,
+}
+
+impl GlDescriptorSetValues {
+    pub(crate) fn value(
+        &self,
+        array_index: u32,
+        descriptor: &DescriptorInfo,
+        element_index: u32,
+    ) -> GlDescriptorValue {
+        self.sets[array_index as usize].lock().unwrap()
+            [(descriptor.value_offset + element_index) as usize]
+            .clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxDescriptorSetHandleGles3 {
+    values: Arc<GlDescriptorSetValues>,
+    set_index: u32,
+    array_index: u32,
+    #[cfg(feature = "capture")]
+    descriptor_set_array_id: u32,
+}
+
+impl RafxDescriptorSetHandleGles3 {
+    pub fn set_index(&self) -> u32 {
+        self.set_index
+    }
+
+    pub fn array_index(&self) -> u32 {
+        self.array_index
+    }
+
+    pub(crate) fn values(&self) -> &Arc<GlDescriptorSetValues> {
+        &self.values
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_ref(&self) -> crate::capture::RafxCaptureDescriptorSetRef {
+        crate::capture::RafxCaptureDescriptorSetRef {
+            descriptor_set_array_id: self.descriptor_set_array_id,
+            array_index: self.array_index,
+        }
+    }
+}
+
+/// Descriptor sets are stored on the CPU, binding one binds its resources to the uniform buffer
+/// binding points and texture units assigned by the root signature.
+#[derive(Debug)]
+pub struct RafxDescriptorSetArrayGles3 {
+    root_signature: RafxRootSignature,
+    set_index: u32,
+    array_length: u32,
+    values: Arc<GlDescriptorSetValues>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxDescriptorSetResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl RafxDescriptorSetArrayGles3 {
+    pub fn root_signature(&self) -> &RafxRootSignature {
+        &self.root_signature
+    }
+
+    pub fn set_index(&self) -> u32 {
+        self.set_index
+    }
+
+    pub fn array_length(&self) -> u32 {
+        self.array_length
+    }
+
+    pub(crate) fn values(&self) -> &Arc<GlDescriptorSetValues> {
+        &self.values
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxDescriptorSetResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states_mut(&mut self) -> &mut RafxDescriptorSetResourceStates {
+        &mut self.resource_states
+    }
+
+    pub fn handle(
+        &self,
+        array_index: u32,
+    ) -> Option<RafxDescriptorSetHandleGles3> {
+        if array_index < self.array_length {
+            Some(RafxDescriptorSetHandleGles3 {
+                values: self.values.clone(),
+                set_index: self.set_index,
+                array_index,
+                #[cfg(feature = "capture")]
+                descriptor_set_array_id: self.capture_id,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn new(
+        _device_context: &RafxDeviceContextGles3,
+        descriptor_set_array_def: &RafxDescriptorSetArrayDef,
+    ) -> RafxResult<Self> {
+        let root_signature = descriptor_set_array_def
+            .root_signature
+            .gles3_root_signature()
+            .ok_or("The root signature must be created by the same backend")?;
+
+        let layout = &root_signature.inner.layouts[descriptor_set_array_def.set_index as usize];
+        let sets = (0..descriptor_set_array_def.array_length)
+            .map(|_| Mutex::new(vec![GlDescriptorValue::Empty; layout.value_count as usize]))
+            .collect();
+
+        Ok(RafxDescriptorSetArrayGles3 {
+            root_signature: descriptor_set_array_def.root_signature.clone(),
+            set_index: descriptor_set_array_def.set_index,
+            array_length: descriptor_set_array_def.array_length as u32,
+            values: Arc::new(GlDescriptorSetValues { sets }),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: RafxDescriptorSetResourceStates::new(
+                descriptor_set_array_def.set_index,
+            ),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        })
+    }
+
+    pub fn update_descriptor_set(
+        &mut self,
+        descriptor_updates: &[RafxDescriptorUpdate],
+    ) -> RafxResult<()> {
+        for update in descriptor_updates {
+            self.queue_descriptor_set_update(update)?;
+        }
+        self.flush_descriptor_set_updates()
+    }
+
+    pub fn flush_descriptor_set_updates(&mut self) -> RafxResult<()> {
+        // Updates are written immediately
+        Ok(())
+    }
+
+    pub fn queue_descriptor_set_update(
+        &mut self,
+        update: &RafxDescriptorUpdate,
+    ) -> RafxResult<()> {
+        if update.array_index >= self.array_length {
+            return Err(format!(
+                "Descriptor set update array_index {} is out of range for array of length {}",
+                update.array_index, self.array_length
+            ))?;
+        }
+
+        let root_signature = self.root_signature.gles3_root_signature().unwrap();
+        let descriptor_index = match &update.descriptor_key {
+            RafxDescriptorKey::Name(name) => root_signature.find_descriptor_by_name(name),
+            RafxDescriptorKey::Binding(binding) => {
+                root_signature.find_descriptor_by_binding(self.set_index, *binding)
+            }
+            RafxDescriptorKey::DescriptorIndex(descriptor_index) => Some(*descriptor_index),
+            RafxDescriptorKey::Undefined => {
+                return Err("Passed RafxDescriptorKey::Undefined to update_descriptor_set()")?
+            }
+        }
+        .ok_or_else(|| format!("Could not find descriptor {:?}", update.descriptor_key))?;
+
+        let descriptor = root_signature
+            .descriptor(descriptor_index)
+            .ok_or_else(|| format!("Could not find descriptor {:?}", update.descriptor_key))?;
+
+        if descriptor.set_index != self.set_index {
+            return Err(format!(
+                "Found descriptor {:?} but it's set_index ({:?}) does not match the set ({:?})",
+                &update.descriptor_key, descriptor.set_index, self.set_index
+            ))?;
+        }
+
+        crate::internal_shared::verify_descriptor_update_range(
+            update,
+            &descriptor.name,
+            descriptor.resource_type,
+            descriptor.element_count,
+        )?;
+
+        let new_values = Self::descriptor_values(update, descriptor)?;
+
+        let mut values = self.values.sets[update.array_index as usize]
+            .lock()
+            .unwrap();
+        let first_value = (descriptor.value_offset + update.dst_element_offset) as usize;
+        for (value, new_value) in values[first_value..].iter_mut().zip(new_values) {
+            *value = new_value;
+        }
+
+        Ok(())
+    }
+
+    // Converts the elements of an update to the values that are stored
+    fn descriptor_values(
+        update: &RafxDescriptorUpdate,
+        descriptor: &DescriptorInfo,
+    ) -> RafxResult<Vec<GlDescriptorValue>> {
+        let missing_elements = |element_name: &str| {
+            format!(
+                "Tried to update binding {:?} (set: {:?} binding: {} name: {:?} type: {:?}) but the {} element list was None",
+                update.descriptor_key,
+                descriptor.set_index,
+                descriptor.binding,
+                descriptor.name,
+                descriptor.resource_type,
+                element_name,
+            )
+        };
+
+        match descriptor.resource_type {
+            RafxResourceType::SAMPLER => {
+                let samplers = update
+                    .elements
+                    .samplers
+                    .ok_or_else(|| missing_elements("samplers"))?;
+
+                Ok(samplers
+                    .iter()
+                    .map(|x| GlDescriptorValue::Sampler(x.gles3_sampler().unwrap().clone()))
+                    .collect())
+            }
+            RafxResourceType::TEXTURE if update.elements.texture_views.is_some() => {
+                let texture_views = update.elements.texture_views.unwrap();
+                texture_views
+                    .iter()
+                    .map(|x| Self::texture_view_value(x.gles3_texture_view().unwrap()))
+                    .collect()
+            }
+            RafxResourceType::TEXTURE => {
+                let textures = update
+                    .elements
+                    .textures
+                    .ok_or_else(|| missing_elements("textures"))?;
+
+                let texture_bind_type =
+                    update.texture_bind_type.unwrap_or(RafxTextureBindType::Srv);
+                if texture_bind_type != RafxTextureBindType::Srv {
+                    return Err(format!(
+                        "Tried to update binding {:?} (set: {:?} binding: {} name: {:?}) with texture_bind_type {:?}, the GL ES backend only supports RafxTextureBindType::Srv",
+                        update.descriptor_key,
+                        descriptor.set_index,
+                        descriptor.binding,
+                        descriptor.name,
+                        texture_bind_type
+                    ))?;
+                }
+
+                Ok(textures
+                    .iter()
+                    .map(|x| {
+                        let texture = x.gles3_texture().unwrap();
+                        GlDescriptorValue::Texture {
+                            texture: texture.clone(),
+                            mip_range: 0..texture.texture_def().mip_count,
+                        }
+                    })
+                    .collect())
+            }
+            RafxResourceType::UNIFORM_BUFFER => {
+                let buffers = update
+                    .elements
+                    .buffers
+                    .ok_or_else(|| missing_elements("buffers"))?;
+
+                Ok(buffers
+                    .iter()
+                    .enumerate()
+                    .map(|(buffer_index, buffer)| {
+                        let buffer = buffer.gles3_buffer().unwrap();
+                        let mut offset = 0;
+                        let mut size = buffer.buffer_def().size;
+                        if let Some(offset_size) = update.elements.buffer_offset_sizes {
+                            offset = offset_size[buffer_index].byte_offset;
+                            size = if offset_size[buffer_index].size != 0 {
+                                offset_size[buffer_index].size
+                            } else {
+                                size - offset
+                            };
+                        }
+
+                        GlDescriptorValue::Buffer {
+                            buffer: buffer.clone(),
+                            offset,
+                            size,
+                        }
+                    })
+                    .collect())
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn texture_view_value(texture_view: &RafxTextureViewGles3) -> RafxResult<GlDescriptorValue> {
+        if !texture_view.covers_all_array_layers() {
+            return Err(format!(
+                "GL ES 3.0 can only sample views of all array layers of a texture, the view includes layers {:?}",
+                texture_view.texture_view_def().array_range
+            ))?;
+        }
+
+        Ok(GlDescriptorValue::Texture {
+            texture: texture_view.gles3_texture().clone(),
+            mip_range: texture_view.texture_view_def().mip_range.clone().unwrap(),
+        })
+    }
+}
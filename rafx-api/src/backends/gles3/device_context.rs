@@ -0,0 +1,576 @@
+#[cfg(feature = "capture")]
+use crate::capture::RafxCaptureRecorder;
+use crate::internal_shared::RafxMemoryUsageTracker;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxResourceStateTracker;
+use crate::{
+    RafxBufferDef, RafxComputePipelineDef, RafxDescriptorSetArrayDef, RafxDeviceContext,
+    RafxDeviceInfo, RafxFormat, RafxGraphicsPipelineDef, RafxHeapDef, RafxMemoryRequirements,
+    RafxMemoryStatistics, RafxQueryPoolDef, RafxQueueType, RafxResourceType, RafxResult,
+    RafxRootSignatureDef, RafxSampleCount, RafxSamplerDef, RafxShaderModuleDefGles3,
+    RafxShaderStageDef, RafxSwapchainDef, RafxTexture, RafxTextureDef, RafxTextureViewDef,
+};
+use raw_window_handle::HasRawWindowHandle;
+use std::sync::{Arc, Mutex, Weak};
+
+use super::internal::conversions;
+use super::internal::gl_context::{check_gl_error, GlContext};
+use super::internal::gles3_bindings as gl;
+use crate::gles3::{
+    RafxBufferGles3, RafxBufferGles3Inner, RafxDescriptorSetArrayGles3, RafxFenceGles3,
+    RafxHeapGles3, RafxPipelineGles3, RafxQueryPoolGles3, RafxQueueGles3, RafxRootSignatureGles3,
+    RafxSamplerGles3, RafxSemaphoreGles3, RafxShaderGles3, RafxShaderModuleGles3,
+    RafxSwapchainGles3, RafxTextureGles3, RafxTextureViewGles3, RafxTimelineSemaphoreGles3,
+};
+
+// Push constants are emulated with a uniform buffer owned by the device. Each push constant block
+// of the bound root signature gets its own range of it.
+pub(crate) const PUSH_CONSTANT_BUFFER_SIZE: u32 = 4096;
+
+// GL objects shared by everything recorded on the device. Command buffers are executed one at a
+// time with the context locked, so these can be reused by every submit.
+#[derive(Debug)]
+pub(crate) struct GlDeviceObjects {
+    // Attribute pointers are respecified for each draw, so a single vertex array object is used
+    pub(crate) vertex_array: gl::GLuint,
+    // Render passes attach their targets to this framebuffer
+    pub(crate) render_framebuffer: gl::GLuint,
+    // Used as the source/destination of blits, copies and readbacks
+    pub(crate) read_framebuffer: gl::GLuint,
+    pub(crate) draw_framebuffer: gl::GLuint,
+    pub(crate) push_constant_buffer: gl::GLuint,
+}
+
+pub struct RafxDeviceContextGles3Inner {
+    pub(crate) device_info: RafxDeviceInfo,
+    pub(crate) gl_context: GlContext,
+    pub(crate) gl_objects: GlDeviceObjects,
+    // Swapchains can only be created if the context was created for a window
+    is_headless: bool,
+    // Buffers whose host copy was written through a mapping and must be uploaded before the next
+    // submit
+    dirty_buffers: Mutex<Vec<Weak<RafxBufferGles3Inner>>>,
+    memory_usage_tracker: RafxMemoryUsageTracker,
+    #[cfg(feature = "validate-resource-states")]
+    resource_state_tracker: RafxResourceStateTracker,
+    #[cfg(feature = "capture")]
+    capture_recorder: RafxCaptureRecorder,
+}
+
+impl Drop for RafxDeviceContextGles3Inner {
+    fn drop(&mut self) {
+        log::trace!("destroying device");
+        if let Ok(_guard) = self.gl_context.lock() {
+            let framebuffers = [
+                self.gl_objects.render_framebuffer,
+                self.gl_objects.read_framebuffer,
+                self.gl_objects.draw_framebuffer,
+            ];
+            unsafe {
+                gl::glDeleteVertexArrays(1, &self.gl_objects.vertex_array);
+                gl::glDeleteFramebuffers(framebuffers.len() as _, framebuffers.as_ptr());
+                gl::glDeleteBuffers(1, &self.gl_objects.push_constant_buffer);
+            }
+        }
+    }
+}
+
+impl RafxDeviceContextGles3Inner {
+    pub(crate) fn new(
+        gl_context: GlContext,
+        is_headless: bool,
+    ) -> RafxResult<Self> {
+        let _guard = gl_context.lock()?;
+
+        let get_integer = |pname: gl::GLenum| {
+            let mut value = 0;
+            unsafe {
+                gl::glGetIntegerv(pname, &mut value);
+            }
+            value.max(0) as u32
+        };
+
+        let mut max_anisotropy = 1.0;
+        if gl_context.has_extension("GL_EXT_texture_filter_anisotropic") {
+            unsafe {
+                gl::glGetFloatv(gl::MAX_TEXTURE_MAX_ANISOTROPY_EXT, &mut max_anisotropy);
+            }
+        }
+
+        let mut line_width_range = [1.0f32; 2];
+        unsafe {
+            gl::glGetFloatv(gl::ALIASED_LINE_WIDTH_RANGE, line_width_range.as_mut_ptr());
+        }
+
+        // Render targets and depth buffers are both created as renderbuffers when multisampled, so
+        // only counts supported by both are reported
+        let mut supported_sample_counts = vec![RafxSampleCount::SampleCount1];
+        for sample_count in &[
+            RafxSampleCount::SampleCount2,
+            RafxSampleCount::SampleCount4,
+            RafxSampleCount::SampleCount8,
+            RafxSampleCount::SampleCount16,
+        ] {
+            let supported = [gl::RGBA8, gl::DEPTH24_STENCIL8].iter().all(|format| {
+                supported_sample_counts_for_format(*format).contains(&(*sample_count as i32))
+            });
+            if supported {
+                supported_sample_counts.push(*sample_count);
+            }
+        }
+
+        // GL ES 3.2 made texture border clamping core
+        let is_gles32 = gl_context.version().starts_with("OpenGL ES 3.2");
+
+        let device_info = RafxDeviceInfo {
+            min_uniform_buffer_offset_alignment: get_integer(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT)
+                .max(1),
+            // Storage buffers are not supported
+            min_storage_buffer_offset_alignment: 256,
+            upload_buffer_texture_alignment: 16,
+            upload_buffer_texture_row_alignment: 1,
+            supports_clamp_to_border_color: is_gles32
+                || gl_context.has_extension("GL_EXT_texture_border_clamp")
+                || gl_context.has_extension("GL_OES_texture_border_clamp"),
+            supports_timestamp_queries: false,
+            supports_occlusion_queries: false,
+            supports_pipeline_statistics_queries: false,
+            timestamp_period_ns: 1.0,
+            supports_timeline_semaphores: false,
+            pipeline_cache_uuid: [0; 16],
+            max_texture_dimension_2d: get_integer(gl::MAX_TEXTURE_SIZE),
+            max_texture_dimension_3d: get_integer(gl::MAX_3D_TEXTURE_SIZE),
+            max_texture_dimension_cube: get_integer(gl::MAX_CUBE_MAP_TEXTURE_SIZE),
+            max_texture_array_layers: get_integer(gl::MAX_ARRAY_TEXTURE_LAYERS),
+            max_sampler_anisotropy: max_anisotropy,
+            max_color_render_targets: get_integer(gl::MAX_DRAW_BUFFERS)
+                .min(get_integer(gl::MAX_COLOR_ATTACHMENTS)),
+            // Each attribute reads from its own buffer binding
+            max_vertex_input_bindings: get_integer(gl::MAX_VERTEX_ATTRIBS),
+            max_vertex_attributes: get_integer(gl::MAX_VERTEX_ATTRIBS),
+            // GL ES 3.0 has no compute shaders
+            max_compute_workgroup_size: [0; 3],
+            max_compute_workgroup_invocations: 0,
+            max_compute_workgroup_count: [0; 3],
+            supported_sample_counts,
+            supports_bc_texture_compression: gl_context
+                .has_extension("GL_EXT_texture_compression_s3tc")
+                && gl_context.has_extension("GL_EXT_texture_compression_rgtc")
+                && gl_context.has_extension("GL_EXT_texture_compression_bptc"),
+            supports_astc_texture_compression: gl_context
+                .has_extension("GL_KHR_texture_compression_astc_ldr"),
+            // ETC2/EAC are core in GL ES 3.0
+            supports_etc2_texture_compression: true,
+            supports_geometry_shaders: false,
+            supports_tessellation_shaders: false,
+            supports_wide_lines: line_width_range[1] > 1.0,
+            max_line_width: line_width_range[1].max(1.0),
+            supports_bindless_descriptors: false,
+            max_bindless_descriptor_count: 0,
+        };
+
+        let gl_objects = unsafe {
+            let mut vertex_array = 0;
+            gl::glGenVertexArrays(1, &mut vertex_array);
+
+            let mut framebuffers = [0; 3];
+            gl::glGenFramebuffers(framebuffers.len() as _, framebuffers.as_mut_ptr());
+
+            let mut push_constant_buffer = 0;
+            gl::glGenBuffers(1, &mut push_constant_buffer);
+            gl::glBindBuffer(gl::UNIFORM_BUFFER, push_constant_buffer);
+            gl::glBufferData(
+                gl::UNIFORM_BUFFER,
+                PUSH_CONSTANT_BUFFER_SIZE as _,
+                std::ptr::null(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::glBindBuffer(gl::UNIFORM_BUFFER, 0);
+
+            GlDeviceObjects {
+                vertex_array,
+                render_framebuffer: framebuffers[0],
+                read_framebuffer: framebuffers[1],
+                draw_framebuffer: framebuffers[2],
+                push_constant_buffer,
+            }
+        };
+        check_gl_error("Creating device objects")?;
+
+        std::mem::drop(_guard);
+
+        Ok(RafxDeviceContextGles3Inner {
+            device_info,
+            gl_context,
+            gl_objects,
+            is_headless,
+            dirty_buffers: Default::default(),
+            memory_usage_tracker: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_state_tracker: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_recorder: Default::default(),
+        })
+    }
+}
+
+// Sample counts the implementation supports for a renderbuffer format
+fn supported_sample_counts_for_format(internal_format: gl::GLenum) -> Vec<i32> {
+    unsafe {
+        let mut count = 0;
+        gl::glGetInternalformativ(
+            gl::RENDERBUFFER,
+            internal_format,
+            gl::NUM_SAMPLE_COUNTS,
+            1,
+            &mut count,
+        );
+        let mut sample_counts = vec![0; count.max(0) as usize];
+        if count > 0 {
+            gl::glGetInternalformativ(
+                gl::RENDERBUFFER,
+                internal_format,
+                gl::SAMPLES,
+                count,
+                sample_counts.as_mut_ptr(),
+            );
+        }
+        sample_counts
+    }
+}
+
+#[derive(Clone)]
+pub struct RafxDeviceContextGles3 {
+    pub(crate) inner: Arc<RafxDeviceContextGles3Inner>,
+}
+
+impl std::fmt::Debug for RafxDeviceContextGles3 {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        f.debug_struct("RafxDeviceContextGles3")
+            .field("gl_context", &self.inner.gl_context)
+            .finish()
+    }
+}
+
+impl Into<RafxDeviceContext> for RafxDeviceContextGles3 {
+    fn into(self) -> RafxDeviceContext {
+        RafxDeviceContext::Gles3(self)
+    }
+}
+
+impl RafxDeviceContextGles3 {
+    pub fn device_info(&self) -> &RafxDeviceInfo {
+        &self.inner.device_info
+    }
+
+    pub(crate) fn gl_context(&self) -> &GlContext {
+        &self.inner.gl_context
+    }
+
+    pub(crate) fn gl_objects(&self) -> &GlDeviceObjects {
+        &self.inner.gl_objects
+    }
+
+    pub(crate) fn is_headless(&self) -> bool {
+        self.inner.is_headless
+    }
+
+    // GL does not report memory budgets, so only the tracked allocations are reported
+    pub fn memory_statistics(&self) -> RafxResult<RafxMemoryStatistics> {
+        Ok(self.inner.memory_usage_tracker.memory_statistics())
+    }
+
+    // Programs are linked when pipelines are created, there is no cache to save or load
+    pub fn pipeline_cache_data(&self) -> RafxResult<Vec<u8>> {
+        Ok(Vec::default())
+    }
+
+    pub fn load_pipeline_cache_data(
+        &self,
+        _data: &[u8],
+    ) -> RafxResult<()> {
+        Ok(())
+    }
+
+    pub(crate) fn memory_usage_tracker(&self) -> &RafxMemoryUsageTracker {
+        &self.inner.memory_usage_tracker
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_state_tracker(&self) -> &RafxResourceStateTracker {
+        &self.inner.resource_state_tracker
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &RafxCaptureRecorder {
+        &self.inner.capture_recorder
+    }
+
+    // Queues the buffer's host copy to be uploaded before the next submit
+    pub(crate) fn mark_buffer_dirty(
+        &self,
+        buffer: Weak<RafxBufferGles3Inner>,
+    ) {
+        self.inner.dirty_buffers.lock().unwrap().push(buffer);
+    }
+
+    // Uploads the host copy of every buffer written since the last submit. Buffers that are still
+    // mapped may be written again, so they stay queued.
+    pub(crate) fn upload_dirty_buffers(&self) -> RafxResult<()> {
+        let dirty_buffers = std::mem::take(&mut *self.inner.dirty_buffers.lock().unwrap());
+        let mut still_mapped = Vec::default();
+        for buffer in dirty_buffers {
+            if let Some(buffer) = buffer.upgrade() {
+                if RafxBufferGles3::upload_host_copy(&buffer)? {
+                    still_mapped.push(Arc::downgrade(&buffer));
+                }
+            }
+        }
+
+        self.inner
+            .dirty_buffers
+            .lock()
+            .unwrap()
+            .append(&mut still_mapped);
+        Ok(())
+    }
+
+    pub fn new(inner: Arc<RafxDeviceContextGles3Inner>) -> RafxResult<Self> {
+        Ok(RafxDeviceContextGles3 { inner })
+    }
+
+    pub fn create_queue(
+        &self,
+        queue_type: RafxQueueType,
+    ) -> RafxResult<RafxQueueGles3> {
+        RafxQueueGles3::new(self, queue_type)
+    }
+
+    pub fn create_fence(&self) -> RafxResult<RafxFenceGles3> {
+        RafxFenceGles3::new(self)
+    }
+
+    pub fn create_semaphore(&self) -> RafxResult<RafxSemaphoreGles3> {
+        RafxSemaphoreGles3::new(self)
+    }
+
+    pub fn create_timeline_semaphore(
+        &self,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreGles3> {
+        RafxTimelineSemaphoreGles3::new(self, initial_value)
+    }
+
+    pub fn create_swapchain(
+        &self,
+        raw_window_handle: &dyn HasRawWindowHandle,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<RafxSwapchainGles3> {
+        RafxSwapchainGles3::new(self, raw_window_handle, swapchain_def)
+    }
+
+    pub fn wait_for_fences(
+        &self,
+        fences: &[&RafxFenceGles3],
+    ) -> RafxResult<()> {
+        RafxFenceGles3::wait_for_fences(self, fences)
+    }
+
+    pub fn create_query_pool(
+        &self,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolGles3> {
+        RafxQueryPoolGles3::new(self, query_pool_def)
+    }
+
+    pub fn create_sampler(
+        &self,
+        sampler_def: &RafxSamplerDef,
+    ) -> RafxResult<RafxSamplerGles3> {
+        RafxSamplerGles3::new(self, sampler_def)
+    }
+
+    pub fn create_texture(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureGles3> {
+        RafxTextureGles3::new(self, texture_def)
+    }
+
+    pub fn create_texture_view(
+        &self,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewGles3> {
+        RafxTextureViewGles3::new(self, texture, texture_view_def)
+    }
+
+    pub fn create_buffer(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBufferGles3> {
+        RafxBufferGles3::new(self, buffer_def)
+    }
+
+    pub fn create_heap(
+        &self,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeapGles3> {
+        RafxHeapGles3::new(self, heap_def)
+    }
+
+    pub fn texture_memory_requirements(
+        &self,
+        _texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        Err("GL ES does not expose memory requirements, placed resources are not supported")?
+    }
+
+    pub fn buffer_memory_requirements(
+        &self,
+        _buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        Err("GL ES does not expose memory requirements, placed resources are not supported")?
+    }
+
+    pub fn create_texture_in_heap(
+        &self,
+        _heap: &RafxHeapGles3,
+        _offset: u64,
+        _texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureGles3> {
+        Err("Placed resources are not supported by the GL ES backend")?
+    }
+
+    pub fn create_buffer_in_heap(
+        &self,
+        _heap: &RafxHeapGles3,
+        _offset: u64,
+        _buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBufferGles3> {
+        Err("Placed resources are not supported by the GL ES backend")?
+    }
+
+    pub fn create_shader(
+        &self,
+        stages: Vec<RafxShaderStageDef>,
+    ) -> RafxResult<RafxShaderGles3> {
+        RafxShaderGles3::new(self, stages)
+    }
+
+    pub fn create_root_signature(
+        &self,
+        root_signature_def: &RafxRootSignatureDef,
+    ) -> RafxResult<RafxRootSignatureGles3> {
+        RafxRootSignatureGles3::new(self, root_signature_def)
+    }
+
+    pub fn create_descriptor_set_array(
+        &self,
+        descriptor_set_array_def: &RafxDescriptorSetArrayDef,
+    ) -> RafxResult<RafxDescriptorSetArrayGles3> {
+        RafxDescriptorSetArrayGles3::new(self, descriptor_set_array_def)
+    }
+
+    pub fn create_graphics_pipeline(
+        &self,
+        graphics_pipeline_def: &RafxGraphicsPipelineDef,
+    ) -> RafxResult<RafxPipelineGles3> {
+        RafxPipelineGles3::new_graphics_pipeline(self, graphics_pipeline_def)
+    }
+
+    pub fn create_compute_pipeline(
+        &self,
+        compute_pipeline_def: &RafxComputePipelineDef,
+    ) -> RafxResult<RafxPipelineGles3> {
+        RafxPipelineGles3::new_compute_pipeline(self, compute_pipeline_def)
+    }
+
+    pub fn create_shader_module(
+        &self,
+        data: RafxShaderModuleDefGles3,
+    ) -> RafxResult<RafxShaderModuleGles3> {
+        RafxShaderModuleGles3::new(self, data)
+    }
+
+    pub fn find_supported_format(
+        &self,
+        candidates: &[RafxFormat],
+        resource_type: RafxResourceType,
+    ) -> Option<RafxFormat> {
+        candidates
+            .iter()
+            .find(|format| self.is_format_supported(**format, resource_type))
+            .copied()
+    }
+
+    fn is_format_supported(
+        &self,
+        format: RafxFormat,
+        resource_type: RafxResourceType,
+    ) -> bool {
+        let format_info = match conversions::gl_format_info(format) {
+            Some(format_info) => format_info,
+            None => return false,
+        };
+
+        if format_info.is_compressed {
+            let device_info = self.device_info();
+            let supported = if format.is_bc_compressed() {
+                device_info.supports_bc_texture_compression
+            } else if format.is_astc_compressed() {
+                device_info.supports_astc_texture_compression
+            } else {
+                device_info.supports_etc2_texture_compression
+            };
+
+            // Compressed formats can't be rendered to
+            return supported
+                && !resource_type.intersects(
+                    RafxResourceType::RENDER_TARGET_COLOR
+                        | RafxResourceType::RENDER_TARGET_DEPTH_STENCIL,
+                );
+        }
+
+        if resource_type.intersects(RafxResourceType::TEXTURE_READ_WRITE) {
+            return false;
+        }
+
+        if resource_type.intersects(
+            RafxResourceType::RENDER_TARGET_COLOR | RafxResourceType::RENDER_TARGET_DEPTH_STENCIL,
+        ) {
+            // Querying sample counts of a format that is not renderable is an error. Integer formats
+            // are renderable but may report no multisampled sample counts.
+            let _guard = match self.gl_context().lock() {
+                Ok(guard) => guard,
+                Err(_) => return false,
+            };
+            let mut count = 0;
+            unsafe {
+                gl::glGetInternalformativ(
+                    gl::RENDERBUFFER,
+                    format_info.internal_format,
+                    gl::NUM_SAMPLE_COUNTS,
+                    1,
+                    &mut count,
+                );
+            }
+            return check_gl_error("Querying format support").is_ok();
+        }
+
+        true
+    }
+
+    pub fn find_supported_sample_count(
+        &self,
+        candidates: &[RafxSampleCount],
+    ) -> Option<RafxSampleCount> {
+        candidates
+            .iter()
+            .find(|x| self.device_info().supports_sample_count(**x))
+            .copied()
+    }
+}
@@ -0,0 +1,1499 @@
+use super::command_buffer::{GlColorRenderTarget, GlDepthStencilRenderTarget, GlRecordedCommand};
+use super::descriptor_set_array::{GlDescriptorSetValues, GlDescriptorValue};
+use super::internal::conversions;
+use super::internal::gl_context::{check_gl_error, null_offset};
+use super::internal::gles3_bindings as gl;
+use super::root_signature::{DescriptorInfo, GlSamplerSource};
+use crate::gles3::{
+    RafxBufferGles3, RafxDeviceContextGles3, RafxPipelineGles3, RafxRawTextureGles3,
+    RafxRootSignatureGles3, RafxTextureGles3,
+};
+use crate::{
+    RafxDrawIndexedIndirectCommand, RafxDrawIndirectCommand, RafxExtents3D, RafxIndexType,
+    RafxLoadOp, RafxResourceType, RafxResult, RafxStoreOp, RafxTextureBufferLayout,
+    RafxTextureClearValue, RafxTextureDimensions, RafxVertexAttributeRate,
+    MAX_DESCRIPTOR_SET_LAYOUTS,
+};
+use std::sync::Arc;
+
+/// Attaches a mip level and array layer of a texture to the framebuffer bound to `target`. The
+/// layer of a 3D texture is a depth slice and the layer of a cube map is a face.
+pub(crate) fn attach_texture(
+    target: gl::GLenum,
+    attachment_point: gl::GLenum,
+    texture: &RafxTextureGles3,
+    mip_level: u32,
+    array_layer: u32,
+) {
+    unsafe {
+        match texture.gl_raw_texture() {
+            RafxRawTextureGles3::Renderbuffer(renderbuffer) => gl::glFramebufferRenderbuffer(
+                target,
+                attachment_point,
+                gl::RENDERBUFFER,
+                renderbuffer,
+            ),
+            RafxRawTextureGles3::Texture {
+                target: gl::TEXTURE_2D,
+                texture,
+            } => gl::glFramebufferTexture2D(
+                target,
+                attachment_point,
+                gl::TEXTURE_2D,
+                texture,
+                mip_level as _,
+            ),
+            RafxRawTextureGles3::Texture {
+                target: gl::TEXTURE_CUBE_MAP,
+                texture,
+            } => gl::glFramebufferTexture2D(
+                target,
+                attachment_point,
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + array_layer,
+                texture,
+                mip_level as _,
+            ),
+            RafxRawTextureGles3::Texture { texture, .. } => gl::glFramebufferTextureLayer(
+                target,
+                attachment_point,
+                texture,
+                mip_level as _,
+                array_layer as _,
+            ),
+        }
+    }
+}
+
+// Removes whatever is attached at the attachment point, whether it is a texture or renderbuffer
+fn detach(
+    target: gl::GLenum,
+    attachment_point: gl::GLenum,
+) {
+    unsafe {
+        gl::glFramebufferTexture2D(target, attachment_point, gl::TEXTURE_2D, 0, 0);
+    }
+}
+
+fn check_framebuffer_status(target: gl::GLenum) -> RafxResult<()> {
+    let status = unsafe { gl::glCheckFramebufferStatus(target) };
+    if status != gl::FRAMEBUFFER_COMPLETE {
+        Err(format!(
+            "Framebuffer is incomplete (status 0x{:x}), the attached formats may not be renderable",
+            status
+        ))?;
+    }
+
+    Ok(())
+}
+
+// The attachment point and the buffers that glBlitFramebuffer/glClearBuffer* use for a format
+fn attachment_point_and_mask(texture: &RafxTextureGles3) -> (gl::GLenum, gl::GLbitfield) {
+    let format = texture.texture_def().format;
+    if format.has_depth_or_stencil() {
+        let mut mask = 0;
+        if format.has_depth() {
+            mask |= gl::DEPTH_BUFFER_BIT;
+        }
+        if format.has_stencil() {
+            mask |= gl::STENCIL_BUFFER_BIT;
+        }
+        (conversions::depth_stencil_attachment(format), mask)
+    } else {
+        (gl::COLOR_ATTACHMENT0, gl::COLOR_BUFFER_BIT)
+    }
+}
+
+// Binds `framebuffer` to `target` with only the given subresource attached. Returns the buffer
+// mask to blit with.
+fn bind_subresource_framebuffer(
+    target: gl::GLenum,
+    framebuffer: gl::GLuint,
+    texture: &RafxTextureGles3,
+    mip_level: u32,
+    array_layer: u32,
+) -> RafxResult<gl::GLbitfield> {
+    unsafe {
+        gl::glBindFramebuffer(target, framebuffer);
+    }
+    detach(target, gl::COLOR_ATTACHMENT0);
+    detach(target, gl::DEPTH_STENCIL_ATTACHMENT);
+
+    let (attachment_point, mask) = attachment_point_and_mask(texture);
+    attach_texture(target, attachment_point, texture, mip_level, array_layer);
+    check_framebuffer_status(target)?;
+    Ok(mask)
+}
+
+// Number of layers that can be attached at a mip level, depth slices for 3D textures
+fn layer_count(
+    texture: &RafxTextureGles3,
+    mip_level: u32,
+) -> u32 {
+    let texture_def = texture.texture_def();
+    if is_3d(texture) {
+        1.max(texture_def.extents.depth >> mip_level)
+    } else {
+        texture_def.array_length
+    }
+}
+
+fn is_3d(texture: &RafxTextureGles3) -> bool {
+    let texture_def = texture.texture_def();
+    texture_def
+        .dimensions
+        .determine_dimensions(texture_def.extents)
+        == RafxTextureDimensions::Dim3D
+}
+
+// GL stores BGRA formats as RGBA, so the bytes of each pixel are reordered when copying to or from
+// a buffer
+fn swap_red_blue(
+    data: &mut [u8],
+    bytes_per_pixel: usize,
+) {
+    for pixel in data.chunks_exact_mut(bytes_per_pixel) {
+        pixel.swap(0, 2);
+    }
+}
+
+fn clear_color_attachment(
+    texture: &RafxTextureGles3,
+    draw_buffer: i32,
+    color: [f32; 4],
+) {
+    let format_info = texture.format_info();
+    unsafe {
+        if format_info.is_signed_integer() {
+            let value = [
+                color[0] as i32,
+                color[1] as i32,
+                color[2] as i32,
+                color[3] as i32,
+            ];
+            gl::glClearBufferiv(gl::COLOR, draw_buffer, value.as_ptr());
+        } else if format_info.is_integer() {
+            let value = [
+                color[0] as u32,
+                color[1] as u32,
+                color[2] as u32,
+                color[3] as u32,
+            ];
+            gl::glClearBufferuiv(gl::COLOR, draw_buffer, value.as_ptr());
+        } else {
+            gl::glClearBufferfv(gl::COLOR, draw_buffer, color.as_ptr());
+        }
+    }
+}
+
+fn clear_depth_stencil_attachment(
+    clear_depth: bool,
+    clear_stencil: bool,
+    depth: f32,
+    stencil: u32,
+) {
+    unsafe {
+        if clear_depth && clear_stencil {
+            gl::glClearBufferfi(gl::DEPTH_STENCIL, 0, depth, stencil as _);
+        } else if clear_depth {
+            gl::glClearBufferfv(gl::DEPTH, 0, &depth);
+        } else if clear_stencil {
+            let stencil = stencil as gl::GLint;
+            gl::glClearBufferiv(gl::STENCIL, 0, &stencil);
+        }
+    }
+}
+
+// Clears ignore the pipeline's masks in vulkan but not in GL
+fn reset_write_masks() {
+    unsafe {
+        gl::glDisable(gl::SCISSOR_TEST);
+        gl::glColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+        gl::glDepthMask(gl::TRUE);
+        gl::glStencilMask(!0);
+    }
+}
+
+// The render pass that is active, kept so that it can be resolved and invalidated when it ends
+struct GlActiveRenderPass {
+    color_targets: Vec<GlColorRenderTarget>,
+    depth_target: Option<GlDepthStencilRenderTarget>,
+    extents: (u32, u32),
+}
+
+/// Issues recorded commands to GL. Tracks which state has been applied so that a draw only
+/// re-applies what changed since the last one. All GL state that commands depend on is set by the
+/// executor, so nothing is assumed about the state left by a previous submit.
+pub(crate) struct GlCommandExecutor<'a> {
+    device_context: &'a RafxDeviceContextGles3,
+    pipeline: Option<RafxPipelineGles3>,
+    // Set when the pipeline changes or a clear/copy changed GL's fixed-function state
+    pipeline_state_dirty: bool,
+    // Set when the pipeline or a descriptor set changes
+    descriptors_dirty: bool,
+    descriptor_sets: [Option<(Arc<GlDescriptorSetValues>, u32)>; MAX_DESCRIPTOR_SET_LAYOUTS],
+    vertex_buffers: Vec<Option<(RafxBufferGles3, u64)>>,
+    index_buffer: Option<(RafxBufferGles3, u64, RafxIndexType)>,
+    enabled_vertex_attributes: Vec<u32>,
+    stencil_reference: u32,
+    // Constant and slope-scaled bias set by cmd_set_depth_bias
+    dynamic_depth_bias: (f32, f32),
+    render_pass: Option<GlActiveRenderPass>,
+}
+
+impl<'a> GlCommandExecutor<'a> {
+    // Must be called with the GL context locked
+    pub(crate) fn new(device_context: &'a RafxDeviceContextGles3) -> Self {
+        unsafe {
+            gl::glBindVertexArray(device_context.gl_objects().vertex_array);
+        }
+
+        GlCommandExecutor {
+            device_context,
+            pipeline: None,
+            pipeline_state_dirty: true,
+            descriptors_dirty: true,
+            descriptor_sets: Default::default(),
+            vertex_buffers: Default::default(),
+            index_buffer: None,
+            enabled_vertex_attributes: Default::default(),
+            stencil_reference: 0,
+            dynamic_depth_bias: (0.0, 0.0),
+            render_pass: None,
+        }
+    }
+
+    // Returns GL to the state that the rest of the backend expects: vertex attributes disabled,
+    // no scissor test and the default framebuffer bound
+    pub(crate) fn reset_gl_state(&mut self) {
+        unsafe {
+            for location in self.enabled_vertex_attributes.drain(..) {
+                gl::glDisableVertexAttribArray(location);
+            }
+            gl::glDisable(gl::SCISSOR_TEST);
+            gl::glBindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::glBindVertexArray(0);
+            gl::glUseProgram(0);
+        }
+    }
+
+    pub(crate) fn execute_commands(
+        &mut self,
+        commands: &[GlRecordedCommand],
+    ) -> RafxResult<()> {
+        for command in commands {
+            self.execute_command(command)?;
+        }
+
+        Ok(())
+    }
+
+    fn execute_command(
+        &mut self,
+        command: &GlRecordedCommand,
+    ) -> RafxResult<()> {
+        match command {
+            GlRecordedCommand::BeginRenderPass {
+                color_targets,
+                depth_target,
+                ..
+            } => self.begin_render_pass(color_targets, depth_target)?,
+            GlRecordedCommand::EndRenderPass => self.end_render_pass()?,
+            GlRecordedCommand::ExecuteSecondaryCommandBuffers { command_buffers } => {
+                for command_buffer in command_buffers {
+                    self.execute_commands(command_buffer)?;
+                }
+            }
+            GlRecordedCommand::SetViewport {
+                x,
+                y,
+                width,
+                height,
+                depth_min,
+                depth_max,
+            } => unsafe {
+                // Rows are stored top-down like vulkan (see the swapchain), so the viewport
+                // doesn't need to be flipped
+                gl::glViewport(*x as _, *y as _, *width as _, *height as _);
+                gl::glDepthRangef(*depth_min, *depth_max);
+            },
+            GlRecordedCommand::SetScissor {
+                x,
+                y,
+                width,
+                height,
+            } => unsafe {
+                gl::glScissor(*x as _, *y as _, *width as _, *height as _);
+            },
+            GlRecordedCommand::SetStencilReferenceValue(value) => {
+                self.stencil_reference = *value;
+                self.pipeline_state_dirty = true;
+            }
+            GlRecordedCommand::SetDepthBias {
+                constant_factor,
+                slope_factor,
+            } => {
+                self.dynamic_depth_bias = (*constant_factor, *slope_factor);
+                self.pipeline_state_dirty = true;
+            }
+            GlRecordedCommand::SetBlendConstants(blend_constants) => unsafe {
+                gl::glBlendColor(
+                    blend_constants[0],
+                    blend_constants[1],
+                    blend_constants[2],
+                    blend_constants[3],
+                );
+            },
+            GlRecordedCommand::SetLineWidth(line_width) => unsafe {
+                gl::glLineWidth(*line_width);
+            },
+            GlRecordedCommand::BindPipeline(pipeline) => {
+                self.pipeline = Some(pipeline.clone());
+                self.pipeline_state_dirty = true;
+                self.descriptors_dirty = true;
+            }
+            GlRecordedCommand::BindVertexBuffers {
+                first_binding,
+                bindings,
+            } => {
+                for (i, binding) in bindings.iter().enumerate() {
+                    let index = *first_binding as usize + i;
+                    if self.vertex_buffers.len() <= index {
+                        self.vertex_buffers.resize(index + 1, None);
+                    }
+                    self.vertex_buffers[index] = Some(binding.clone());
+                }
+            }
+            GlRecordedCommand::BindIndexBuffer {
+                buffer,
+                byte_offset,
+                index_type,
+            } => {
+                self.index_buffer = Some((buffer.clone(), *byte_offset, *index_type));
+            }
+            GlRecordedCommand::BindDescriptorSet {
+                set_index,
+                array_index,
+                values,
+            } => {
+                self.descriptor_sets[*set_index as usize] = Some((values.clone(), *array_index));
+                self.descriptors_dirty = true;
+            }
+            GlRecordedCommand::BindPushConstant {
+                gl_binding,
+                buffer_offset,
+                data,
+            } => unsafe {
+                let push_constant_buffer = self.device_context.gl_objects().push_constant_buffer;
+                gl::glBindBuffer(gl::COPY_WRITE_BUFFER, push_constant_buffer);
+                gl::glBufferSubData(
+                    gl::COPY_WRITE_BUFFER,
+                    *buffer_offset as _,
+                    data.len() as _,
+                    data.as_ptr() as _,
+                );
+                gl::glBindBuffer(gl::COPY_WRITE_BUFFER, 0);
+                gl::glBindBufferRange(
+                    gl::UNIFORM_BUFFER,
+                    *gl_binding,
+                    push_constant_buffer,
+                    *buffer_offset as _,
+                    data.len() as _,
+                );
+            },
+            GlRecordedCommand::Draw {
+                vertex_count,
+                first_vertex,
+                instance_count,
+                first_instance,
+            } => self.draw(
+                *vertex_count,
+                *first_vertex,
+                *instance_count,
+                *first_instance,
+            )?,
+            GlRecordedCommand::DrawIndexed {
+                index_count,
+                first_index,
+                instance_count,
+                first_instance,
+                vertex_offset,
+            } => self.draw_indexed(
+                *index_count,
+                *first_index,
+                *instance_count,
+                *first_instance,
+                *vertex_offset,
+            )?,
+            GlRecordedCommand::DrawIndirect {
+                indirect_buffer,
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            } => {
+                // GL ES 3.0 has no indirect draws, the arguments are read back and drawn directly
+                for i in 0..*draw_count {
+                    let args = Self::read_indirect_args::<RafxDrawIndirectCommand>(
+                        indirect_buffer,
+                        indirect_buffer_offset_in_bytes + (i * stride) as u64,
+                    )?;
+                    self.draw(
+                        args.vertex_count,
+                        args.first_vertex,
+                        args.instance_count,
+                        args.first_instance,
+                    )?;
+                }
+            }
+            GlRecordedCommand::DrawIndexedIndirect {
+                indirect_buffer,
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            } => {
+                for i in 0..*draw_count {
+                    let args = Self::read_indirect_args::<RafxDrawIndexedIndirectCommand>(
+                        indirect_buffer,
+                        indirect_buffer_offset_in_bytes + (i * stride) as u64,
+                    )?;
+                    self.draw_indexed(
+                        args.index_count,
+                        args.first_index,
+                        args.instance_count,
+                        args.first_instance,
+                        args.vertex_offset,
+                    )?;
+                }
+            }
+            GlRecordedCommand::ResourceBarrier => {}
+            GlRecordedCommand::ClearTexture {
+                texture,
+                clear_value,
+                mip_slice,
+                array_slice,
+            } => self.clear_texture(texture, *clear_value, *mip_slice, *array_slice)?,
+            GlRecordedCommand::FillBuffer {
+                buffer,
+                offset,
+                size,
+                value,
+            } => buffer.write_range(*offset, &vec![*value; *size as usize])?,
+            GlRecordedCommand::CopyBufferToBuffer {
+                src_buffer,
+                dst_buffer,
+                src_offset,
+                dst_offset,
+                size,
+            } => {
+                unsafe {
+                    gl::glBindBuffer(gl::COPY_READ_BUFFER, src_buffer.gl_buffer());
+                    gl::glBindBuffer(gl::COPY_WRITE_BUFFER, dst_buffer.gl_buffer());
+                    gl::glCopyBufferSubData(
+                        gl::COPY_READ_BUFFER,
+                        gl::COPY_WRITE_BUFFER,
+                        *src_offset as _,
+                        *dst_offset as _,
+                        *size as _,
+                    );
+                    gl::glBindBuffer(gl::COPY_READ_BUFFER, 0);
+                    gl::glBindBuffer(gl::COPY_WRITE_BUFFER, 0);
+                }
+                dst_buffer.refresh_host_copy(*dst_offset, *size)?;
+            }
+            GlRecordedCommand::CopyBufferToTexture {
+                src_buffer,
+                dst_texture,
+                buffer_offset,
+                array_layer,
+                mip_level,
+            } => self.copy_buffer_to_texture(
+                src_buffer,
+                dst_texture,
+                *buffer_offset,
+                *array_layer as u32,
+                *mip_level,
+            )?,
+            GlRecordedCommand::CopyTextureToBuffer {
+                src_texture,
+                dst_buffer,
+                buffer_offset,
+                array_layer,
+                mip_level,
+            } => self.copy_texture_to_buffer(
+                src_texture,
+                dst_buffer,
+                *buffer_offset,
+                *array_layer as u32,
+                *mip_level,
+            )?,
+            GlRecordedCommand::CopyTextureToTexture {
+                src_texture,
+                dst_texture,
+                src_offset,
+                dst_offset,
+                extents,
+                src_mip_level,
+                dst_mip_level,
+                array_slices,
+            } => {
+                let layers = if let Some(array_slices) = array_slices {
+                    vec![(array_slices[0] as u32, array_slices[1] as u32)]
+                } else if is_3d(src_texture) {
+                    (0..extents.depth)
+                        .map(|z| (src_offset.depth + z, dst_offset.depth + z))
+                        .collect()
+                } else {
+                    (0..src_texture.texture_def().array_length)
+                        .map(|layer| (layer, layer))
+                        .collect()
+                };
+
+                let src_rect = [
+                    *src_offset,
+                    RafxExtents3D {
+                        width: src_offset.width + extents.width,
+                        height: src_offset.height + extents.height,
+                        depth: 1,
+                    },
+                ];
+                let dst_rect = [
+                    *dst_offset,
+                    RafxExtents3D {
+                        width: dst_offset.width + extents.width,
+                        height: dst_offset.height + extents.height,
+                        depth: 1,
+                    },
+                ];
+                for (src_layer, dst_layer) in layers {
+                    self.blit_layer(
+                        src_texture,
+                        dst_texture,
+                        (*src_mip_level as u32, src_layer, src_rect),
+                        (*dst_mip_level as u32, dst_layer, dst_rect),
+                        gl::NEAREST,
+                    )?;
+                }
+            }
+            GlRecordedCommand::Blit {
+                src_texture,
+                dst_texture,
+                src_extents,
+                dst_extents,
+                src_mip_level,
+                dst_mip_level,
+                array_slices,
+            } => {
+                let layers = if let Some(array_slices) = array_slices {
+                    vec![(array_slices[0] as u32, array_slices[1] as u32)]
+                } else if is_3d(src_texture) {
+                    (src_extents[0].depth..src_extents[1].depth)
+                        .map(|z| (z, dst_extents[0].depth + z - src_extents[0].depth))
+                        .collect()
+                } else {
+                    (0..src_texture.texture_def().array_length)
+                        .map(|layer| (layer, layer))
+                        .collect()
+                };
+
+                // Integer and depth/stencil formats can only be blitted with nearest filtering
+                let format_info = src_texture.format_info();
+                let filter = if format_info.is_integer()
+                    || src_texture.texture_def().format.has_depth_or_stencil()
+                {
+                    gl::NEAREST
+                } else {
+                    gl::LINEAR
+                };
+
+                for (src_layer, dst_layer) in layers {
+                    self.blit_layer(
+                        src_texture,
+                        dst_texture,
+                        (*src_mip_level as u32, src_layer, *src_extents),
+                        (*dst_mip_level as u32, dst_layer, *dst_extents),
+                        filter,
+                    )?;
+                }
+            }
+            GlRecordedCommand::PushGroupDebugMarker | GlRecordedCommand::PopGroupDebugMarker => {}
+        }
+
+        Ok(())
+    }
+
+    fn begin_render_pass(
+        &mut self,
+        color_targets: &[GlColorRenderTarget],
+        depth_target: &Option<GlDepthStencilRenderTarget>,
+    ) -> RafxResult<()> {
+        let gl_objects = self.device_context.gl_objects();
+        let max_color_targets = self.device_context.device_info().max_color_render_targets;
+
+        unsafe {
+            gl::glBindFramebuffer(gl::FRAMEBUFFER, gl_objects.render_framebuffer);
+        }
+
+        // Remove everything attached by the previous render pass. A depth-only target attached
+        // after a depth/stencil one would otherwise leave the old stencil attached.
+        for i in 0..max_color_targets {
+            detach(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0 + i);
+        }
+        detach(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT);
+
+        let mut draw_buffers = Vec::with_capacity(color_targets.len());
+        for (i, color_target) in color_targets.iter().enumerate() {
+            let attachment = &color_target.attachment;
+            let attachment_point = gl::COLOR_ATTACHMENT0 + i as u32;
+            attach_texture(
+                gl::FRAMEBUFFER,
+                attachment_point,
+                &attachment.texture,
+                attachment.mip_level,
+                attachment.array_layer,
+            );
+            draw_buffers.push(attachment_point);
+        }
+
+        if let Some(depth_target) = depth_target {
+            let attachment = &depth_target.attachment;
+            let (attachment_point, _) = attachment_point_and_mask(&attachment.texture);
+            attach_texture(
+                gl::FRAMEBUFFER,
+                attachment_point,
+                &attachment.texture,
+                attachment.mip_level,
+                attachment.array_layer,
+            );
+        }
+
+        unsafe {
+            if draw_buffers.is_empty() {
+                gl::glDrawBuffers(1, &gl::NONE);
+            } else {
+                gl::glDrawBuffers(draw_buffers.len() as _, draw_buffers.as_ptr());
+            }
+        }
+        check_framebuffer_status(gl::FRAMEBUFFER)?;
+
+        reset_write_masks();
+        self.pipeline_state_dirty = true;
+
+        for (i, color_target) in color_targets.iter().enumerate() {
+            if color_target.load_op == RafxLoadOp::Clear {
+                clear_color_attachment(
+                    &color_target.attachment.texture,
+                    i as i32,
+                    color_target.clear_value.0,
+                );
+            }
+        }
+
+        if let Some(depth_target) = depth_target {
+            let format = depth_target.attachment.texture.texture_def().format;
+            clear_depth_stencil_attachment(
+                format.has_depth() && depth_target.depth_load_op == RafxLoadOp::Clear,
+                format.has_stencil() && depth_target.stencil_load_op == RafxLoadOp::Clear,
+                depth_target.clear_value.depth,
+                depth_target.clear_value.stencil,
+            );
+        }
+
+        let first_attachment = color_targets
+            .first()
+            .map(|x| &x.attachment)
+            .or_else(|| depth_target.as_ref().map(|x| &x.attachment))
+            .unwrap();
+        let texture_extents = first_attachment.texture.texture_def().extents;
+        let extents = (
+            1.max(texture_extents.width >> first_attachment.mip_level),
+            1.max(texture_extents.height >> first_attachment.mip_level),
+        );
+
+        // Same as the other backends, the viewport and scissor cover the render targets
+        unsafe {
+            gl::glViewport(0, 0, extents.0 as _, extents.1 as _);
+            gl::glDepthRangef(0.0, 1.0);
+            gl::glScissor(0, 0, extents.0 as _, extents.1 as _);
+        }
+
+        self.render_pass = Some(GlActiveRenderPass {
+            color_targets: color_targets.to_vec(),
+            depth_target: depth_target.clone(),
+            extents,
+        });
+
+        check_gl_error("Beginning render pass")
+    }
+
+    fn end_render_pass(&mut self) -> RafxResult<()> {
+        let render_pass = self
+            .render_pass
+            .take()
+            .ok_or("cmd_end_render_pass executed without an active render pass")?;
+        let gl_objects = self.device_context.gl_objects();
+        let (width, height) = (render_pass.extents.0 as i32, render_pass.extents.1 as i32);
+
+        // Multisampled targets are resolved by blitting them to the resolve target
+        for color_target in &render_pass.color_targets {
+            if let Some(resolve_target) = &color_target.resolve_target {
+                let attachment = &color_target.attachment;
+                bind_subresource_framebuffer(
+                    gl::READ_FRAMEBUFFER,
+                    gl_objects.read_framebuffer,
+                    &attachment.texture,
+                    attachment.mip_level,
+                    attachment.array_layer,
+                )?;
+                bind_subresource_framebuffer(
+                    gl::DRAW_FRAMEBUFFER,
+                    gl_objects.draw_framebuffer,
+                    &resolve_target.texture,
+                    resolve_target.mip_level,
+                    resolve_target.array_layer,
+                )?;
+                unsafe {
+                    gl::glDisable(gl::SCISSOR_TEST);
+                    gl::glBlitFramebuffer(
+                        0,
+                        0,
+                        width,
+                        height,
+                        0,
+                        0,
+                        width,
+                        height,
+                        gl::COLOR_BUFFER_BIT,
+                        gl::NEAREST,
+                    );
+                }
+                self.pipeline_state_dirty = true;
+            }
+        }
+
+        // Let tiled GPUs skip writing out attachments whose contents aren't needed
+        let mut invalidated_attachments = Vec::default();
+        for (i, color_target) in render_pass.color_targets.iter().enumerate() {
+            if color_target.store_op == RafxStoreOp::DontCare {
+                invalidated_attachments.push(gl::COLOR_ATTACHMENT0 + i as u32);
+            }
+        }
+
+        if let Some(depth_target) = &render_pass.depth_target {
+            let format = depth_target.attachment.texture.texture_def().format;
+            if format.has_depth() && depth_target.depth_store_op == RafxStoreOp::DontCare {
+                invalidated_attachments.push(gl::DEPTH_ATTACHMENT);
+            }
+            if format.has_stencil() && depth_target.stencil_store_op == RafxStoreOp::DontCare {
+                invalidated_attachments.push(gl::STENCIL_ATTACHMENT);
+            }
+        }
+
+        if !invalidated_attachments.is_empty() {
+            unsafe {
+                gl::glBindFramebuffer(gl::FRAMEBUFFER, gl_objects.render_framebuffer);
+                gl::glInvalidateFramebuffer(
+                    gl::FRAMEBUFFER,
+                    invalidated_attachments.len() as _,
+                    invalidated_attachments.as_ptr(),
+                );
+            }
+        }
+
+        check_gl_error("Ending render pass")
+    }
+
+    fn apply_pipeline_state(
+        &mut self,
+        pipeline: &RafxPipelineGles3,
+    ) {
+        let state = pipeline.graphics_state();
+        let rasterizer_state = &state.rasterizer_state;
+        let depth_stencil_state = &state.depth_stencil_state;
+        let blend_state = &state.blend_state;
+
+        let set_enabled = |cap: gl::GLenum, enabled: bool| unsafe {
+            if enabled {
+                gl::glEnable(cap);
+            } else {
+                gl::glDisable(cap);
+            }
+        };
+
+        unsafe {
+            gl::glUseProgram(pipeline.gl_program());
+
+            set_enabled(gl::CULL_FACE, rasterizer_state.cull_face.is_some());
+            if let Some(cull_face) = rasterizer_state.cull_face {
+                gl::glCullFace(cull_face);
+            }
+            gl::glFrontFace(rasterizer_state.front_face);
+
+            let (constant_bias, slope_bias) = if rasterizer_state.dynamic_depth_bias {
+                self.dynamic_depth_bias
+            } else {
+                rasterizer_state.depth_bias
+            };
+            set_enabled(
+                gl::POLYGON_OFFSET_FILL,
+                constant_bias != 0.0 || slope_bias != 0.0,
+            );
+            gl::glPolygonOffset(slope_bias, constant_bias);
+
+            set_enabled(gl::SCISSOR_TEST, rasterizer_state.scissor_enable);
+
+            // GL only writes depth when the depth test is enabled, so writing without testing is
+            // done with a test that always passes
+            let depth_enable =
+                depth_stencil_state.depth_test_enable || depth_stencil_state.depth_write_enable;
+            set_enabled(gl::DEPTH_TEST, depth_enable);
+            gl::glDepthFunc(if depth_stencil_state.depth_test_enable {
+                depth_stencil_state.depth_func
+            } else {
+                gl::ALWAYS
+            });
+            gl::glDepthMask(depth_stencil_state.depth_write_enable as _);
+
+            set_enabled(gl::STENCIL_TEST, depth_stencil_state.stencil_test_enable);
+            for (face, face_state) in &[
+                (gl::FRONT, depth_stencil_state.front),
+                (gl::BACK, depth_stencil_state.back),
+            ] {
+                gl::glStencilFuncSeparate(
+                    *face,
+                    face_state.func,
+                    self.stencil_reference as _,
+                    depth_stencil_state.stencil_read_mask,
+                );
+                gl::glStencilOpSeparate(
+                    *face,
+                    face_state.fail_op,
+                    face_state.depth_fail_op,
+                    face_state.pass_op,
+                );
+            }
+            gl::glStencilMask(depth_stencil_state.stencil_write_mask);
+
+            set_enabled(gl::BLEND, blend_state.blend_enable);
+            gl::glBlendFuncSeparate(
+                blend_state.src_rgb,
+                blend_state.dst_rgb,
+                blend_state.src_alpha,
+                blend_state.dst_alpha,
+            );
+            gl::glBlendEquationSeparate(blend_state.op_rgb, blend_state.op_alpha);
+            gl::glColorMask(
+                blend_state.color_mask[0] as _,
+                blend_state.color_mask[1] as _,
+                blend_state.color_mask[2] as _,
+                blend_state.color_mask[3] as _,
+            );
+        }
+    }
+
+    // Returns the GL sampler a texture descriptor element is sampled with, or 0 to use the
+    // texture's own parameters
+    fn sampler_for_texture(
+        &self,
+        root_signature: &RafxRootSignatureGles3,
+        descriptor: &DescriptorInfo,
+        element_index: u32,
+    ) -> gl::GLuint {
+        match &descriptor.sampler_source {
+            GlSamplerSource::None => 0,
+            GlSamplerSource::Immutable(samplers) => samplers
+                .get(element_index as usize)
+                .or_else(|| samplers.first())
+                .map_or(0, |x| x.gl_sampler()),
+            GlSamplerSource::Descriptor(sampler_descriptor_index) => {
+                let sampler_descriptor = root_signature
+                    .descriptor(*sampler_descriptor_index)
+                    .unwrap();
+                match &self.descriptor_sets[sampler_descriptor.set_index as usize] {
+                    Some((values, array_index)) => {
+                        // A texture array may share a single sampler
+                        let sampler_element =
+                            element_index.min(sampler_descriptor.element_count - 1);
+                        match values.value(*array_index, sampler_descriptor, sampler_element) {
+                            GlDescriptorValue::Sampler(sampler) => sampler.gl_sampler(),
+                            _ => 0,
+                        }
+                    }
+                    None => 0,
+                }
+            }
+        }
+    }
+
+    // Binds the resources of the bound descriptor sets to the binding points and texture units
+    // the pipeline's root signature assigned them
+    fn bind_descriptors(
+        &self,
+        pipeline: &RafxPipelineGles3,
+    ) -> RafxResult<()> {
+        let root_signature = pipeline.root_signature().gles3_root_signature().unwrap();
+        for (set_index, layout) in root_signature.inner.layouts.iter().enumerate() {
+            let (values, array_index) = match &self.descriptor_sets[set_index] {
+                Some((values, array_index)) => (values, *array_index),
+                None => continue,
+            };
+
+            for descriptor_index in &layout.descriptors {
+                let descriptor = root_signature.descriptor(*descriptor_index).unwrap();
+                for element_index in 0..descriptor.element_count {
+                    let gl_binding = descriptor.gl_binding + element_index;
+                    if descriptor.resource_type == RafxResourceType::UNIFORM_BUFFER {
+                        if let GlDescriptorValue::Buffer {
+                            buffer,
+                            offset,
+                            size,
+                        } = values.value(array_index, descriptor, element_index)
+                        {
+                            unsafe {
+                                gl::glBindBufferRange(
+                                    gl::UNIFORM_BUFFER,
+                                    gl_binding,
+                                    buffer.gl_buffer(),
+                                    offset as _,
+                                    size as _,
+                                );
+                            }
+                        }
+                    } else if descriptor.resource_type == RafxResourceType::TEXTURE {
+                        if let GlDescriptorValue::Texture { texture, mip_range } =
+                            values.value(array_index, descriptor, element_index)
+                        {
+                            let (target, gl_texture) = match texture.gl_raw_texture() {
+                                RafxRawTextureGles3::Texture { target, texture } => {
+                                    (target, texture)
+                                }
+                                RafxRawTextureGles3::Renderbuffer(_) => {
+                                    return Err(format!(
+                                        "Descriptor {:?} is a multisampled texture, GL ES 3.0 can't sample multisampled textures",
+                                        descriptor.name
+                                    ))?;
+                                }
+                            };
+
+                            let sampler =
+                                self.sampler_for_texture(root_signature, descriptor, element_index);
+                            unsafe {
+                                gl::glActiveTexture(gl::TEXTURE0 + gl_binding);
+                                gl::glBindTexture(target, gl_texture);
+                                // Views of a subset of mips are emulated by limiting the mips of
+                                // the texture object
+                                gl::glTexParameteri(
+                                    target,
+                                    gl::TEXTURE_BASE_LEVEL,
+                                    mip_range.start as _,
+                                );
+                                gl::glTexParameteri(
+                                    target,
+                                    gl::TEXTURE_MAX_LEVEL,
+                                    mip_range.end as gl::GLint - 1,
+                                );
+                                gl::glBindSampler(gl_binding, sampler);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Push constants share binding points with uniform buffers of other root signatures, so
+        // they are rebound whenever the bindings are
+        let push_constant_buffer = self.device_context.gl_objects().push_constant_buffer;
+        for push_constant in &root_signature.inner.push_constants {
+            unsafe {
+                gl::glBindBufferRange(
+                    gl::UNIFORM_BUFFER,
+                    push_constant.gl_binding,
+                    push_constant_buffer,
+                    push_constant.buffer_offset as _,
+                    push_constant.size_in_bytes as _,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    // Applies state that changed since the last draw and points the vertex attributes at the
+    // bound vertex buffers. GL ES 3.0 has no base instance or base vertex, so the attributes are
+    // offset instead. gl_InstanceIndex gets the first instance from a uniform, but gl_VertexIndex
+    // does not include the vertex offset of indexed draws.
+    fn prepare_draw(
+        &mut self,
+        first_instance: u32,
+        vertex_offset: i64,
+    ) -> RafxResult<gl::GLenum> {
+        let pipeline = self
+            .pipeline
+            .clone()
+            .ok_or("A draw was recorded without a bound pipeline")?;
+
+        if self.pipeline_state_dirty {
+            self.apply_pipeline_state(&pipeline);
+            self.pipeline_state_dirty = false;
+        }
+
+        if self.descriptors_dirty {
+            self.bind_descriptors(&pipeline)?;
+            self.descriptors_dirty = false;
+        }
+
+        let state = pipeline.graphics_state();
+        if let Some(base_instance_location) = state.base_instance_location {
+            unsafe {
+                gl::glUniform1i(base_instance_location, first_instance as _);
+            }
+        }
+
+        let mut enabled_vertex_attributes = Vec::with_capacity(state.vertex_attributes.len());
+        for attribute in &state.vertex_attributes {
+            let (buffer, buffer_offset) = self
+                .vertex_buffers
+                .get(attribute.buffer_index as usize)
+                .cloned()
+                .flatten()
+                .ok_or_else(|| {
+                    format!(
+                        "The bound pipeline reads vertex buffer {} but no buffer is bound to it",
+                        attribute.buffer_index
+                    )
+                })?;
+
+            let vertex_buffer = &state.vertex_buffers[attribute.buffer_index as usize];
+            let (first_element, divisor) = match vertex_buffer.rate {
+                RafxVertexAttributeRate::Vertex => (vertex_offset, 0),
+                RafxVertexAttributeRate::Instance => (first_instance as i64, 1),
+            };
+            let offset = buffer_offset as i64
+                + attribute.byte_offset as i64
+                + first_element * vertex_buffer.stride as i64;
+            if offset < 0 {
+                return Err(format!(
+                    "Vertex offset {} points before the start of vertex buffer {}",
+                    vertex_offset, attribute.buffer_index
+                ))?;
+            }
+
+            let format = &attribute.format;
+            unsafe {
+                gl::glBindBuffer(gl::ARRAY_BUFFER, buffer.gl_buffer());
+                gl::glEnableVertexAttribArray(attribute.location);
+                if format.is_integer {
+                    gl::glVertexAttribIPointer(
+                        attribute.location,
+                        format.component_count,
+                        format.type_,
+                        vertex_buffer.stride as _,
+                        null_offset(offset as u64),
+                    );
+                } else {
+                    gl::glVertexAttribPointer(
+                        attribute.location,
+                        format.component_count,
+                        format.type_,
+                        format.normalized as _,
+                        vertex_buffer.stride as _,
+                        null_offset(offset as u64),
+                    );
+                }
+                gl::glVertexAttribDivisor(attribute.location, divisor);
+            }
+            enabled_vertex_attributes.push(attribute.location);
+        }
+
+        for location in &self.enabled_vertex_attributes {
+            if !enabled_vertex_attributes.contains(location) {
+                unsafe {
+                    gl::glDisableVertexAttribArray(*location);
+                }
+            }
+        }
+        self.enabled_vertex_attributes = enabled_vertex_attributes;
+
+        unsafe {
+            gl::glBindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+
+        Ok(state.primitive_mode)
+    }
+
+    fn draw(
+        &mut self,
+        vertex_count: u32,
+        first_vertex: u32,
+        instance_count: u32,
+        first_instance: u32,
+    ) -> RafxResult<()> {
+        let primitive_mode = self.prepare_draw(first_instance, 0)?;
+        unsafe {
+            gl::glDrawArraysInstanced(
+                primitive_mode,
+                first_vertex as _,
+                vertex_count as _,
+                instance_count as _,
+            );
+        }
+        Ok(())
+    }
+
+    fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        first_index: u32,
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: i32,
+    ) -> RafxResult<()> {
+        let (index_buffer, byte_offset, index_type) = self
+            .index_buffer
+            .clone()
+            .ok_or("An indexed draw was recorded without a bound index buffer")?;
+        let primitive_mode = self.prepare_draw(first_instance, vertex_offset as i64)?;
+        let (gl_index_type, index_size) = conversions::gl_index_type(index_type);
+
+        unsafe {
+            gl::glBindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_buffer.gl_buffer());
+            gl::glDrawElementsInstanced(
+                primitive_mode,
+                index_count as _,
+                gl_index_type,
+                null_offset(byte_offset + first_index as u64 * index_size),
+                instance_count as _,
+            );
+        }
+        Ok(())
+    }
+
+    fn read_indirect_args<T: Copy>(
+        indirect_buffer: &RafxBufferGles3,
+        offset: u64,
+    ) -> RafxResult<T> {
+        let data = indirect_buffer.read_range(offset, std::mem::size_of::<T>() as u64)?;
+        // The argument structs are repr(C) plain data
+        Ok(unsafe { std::ptr::read_unaligned(data.as_ptr() as *const T) })
+    }
+
+    fn clear_texture(
+        &mut self,
+        texture: &RafxTextureGles3,
+        clear_value: RafxTextureClearValue,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    ) -> RafxResult<()> {
+        let texture_def = texture.texture_def();
+        let mip_levels = match mip_slice {
+            Some(mip_slice) => mip_slice as u32..mip_slice as u32 + 1,
+            None => 0..texture_def.mip_count,
+        };
+
+        let draw_framebuffer = self.device_context.gl_objects().draw_framebuffer;
+        reset_write_masks();
+        self.pipeline_state_dirty = true;
+
+        for mip_level in mip_levels {
+            let layers = match array_slice {
+                Some(array_slice) => array_slice as u32..array_slice as u32 + 1,
+                None => 0..layer_count(texture, mip_level),
+            };
+
+            for layer in layers {
+                bind_subresource_framebuffer(
+                    gl::DRAW_FRAMEBUFFER,
+                    draw_framebuffer,
+                    texture,
+                    mip_level,
+                    layer,
+                )?;
+
+                match clear_value {
+                    RafxTextureClearValue::Color(color) => {
+                        clear_color_attachment(texture, 0, color.0)
+                    }
+                    RafxTextureClearValue::DepthStencil(depth_stencil) => {
+                        clear_depth_stencil_attachment(
+                            texture_def.format.has_depth(),
+                            texture_def.format.has_stencil(),
+                            depth_stencil.depth,
+                            depth_stencil.stencil,
+                        )
+                    }
+                }
+            }
+        }
+
+        check_gl_error("Clearing texture")
+    }
+
+    // Copies a whole mip level from a buffer laid out like RafxTextureBufferLayout
+    fn copy_buffer_to_texture(
+        &self,
+        src_buffer: &RafxBufferGles3,
+        dst_texture: &RafxTextureGles3,
+        buffer_offset: u64,
+        array_layer: u32,
+        mip_level: u8,
+    ) -> RafxResult<()> {
+        let texture_def = dst_texture.texture_def();
+        let format_info = *dst_texture.format_info();
+        let layout = RafxTextureBufferLayout::new(
+            self.device_context.device_info(),
+            texture_def.format,
+            texture_def.extents,
+            mip_level,
+        );
+        let target = match dst_texture.gl_raw_texture() {
+            RafxRawTextureGles3::Texture { target, texture } => {
+                unsafe {
+                    gl::glBindTexture(target, texture);
+                }
+                target
+            }
+            RafxRawTextureGles3::Renderbuffer(_) => {
+                return Err("Cannot copy a buffer to a multisampled texture")?
+            }
+        };
+
+        let data = src_buffer.read_range(buffer_offset, layout.size)?;
+        let bytes_per_pixel = texture_def.format.block_or_pixel_size_in_bytes() as usize;
+        let width = layout.extents.width as gl::GLsizei;
+        let height = layout.extents.height as gl::GLsizei;
+
+        unsafe {
+            gl::glPixelStorei(gl::UNPACK_ALIGNMENT, 1);
+        }
+
+        // Each depth slice is copied separately because images are padded in the buffer
+        let (first_layer, slice_count) = if target == gl::TEXTURE_3D {
+            (0, layout.extents.depth)
+        } else {
+            (array_layer, 1)
+        };
+
+        for slice in 0..slice_count {
+            // Remove row padding so the rows are tightly packed
+            let mut texels = Vec::with_capacity(
+                (layout.bytes_per_row_unpadded * layout.rows_per_image) as usize,
+            );
+            let slice_start = (slice * layout.bytes_per_image) as usize;
+            for row in 0..layout.rows_per_image as usize {
+                let row_start = slice_start + row * layout.bytes_per_row as usize;
+                texels.extend_from_slice(
+                    &data[row_start..row_start + layout.bytes_per_row_unpadded as usize],
+                );
+            }
+
+            if format_info.swap_red_blue {
+                swap_red_blue(&mut texels, bytes_per_pixel);
+            }
+
+            let layer = (first_layer + slice) as gl::GLint;
+            unsafe {
+                match (target, format_info.is_compressed) {
+                    (gl::TEXTURE_2D, false) | (gl::TEXTURE_CUBE_MAP, false) => gl::glTexSubImage2D(
+                        Self::image_target(target, layer),
+                        mip_level as _,
+                        0,
+                        0,
+                        width,
+                        height,
+                        format_info.format,
+                        format_info.type_,
+                        texels.as_ptr() as _,
+                    ),
+                    (gl::TEXTURE_2D, true) | (gl::TEXTURE_CUBE_MAP, true) => {
+                        gl::glCompressedTexSubImage2D(
+                            Self::image_target(target, layer),
+                            mip_level as _,
+                            0,
+                            0,
+                            width,
+                            height,
+                            format_info.internal_format,
+                            texels.len() as _,
+                            texels.as_ptr() as _,
+                        )
+                    }
+                    (_, false) => gl::glTexSubImage3D(
+                        target,
+                        mip_level as _,
+                        0,
+                        0,
+                        layer,
+                        width,
+                        height,
+                        1,
+                        format_info.format,
+                        format_info.type_,
+                        texels.as_ptr() as _,
+                    ),
+                    (_, true) => gl::glCompressedTexSubImage3D(
+                        target,
+                        mip_level as _,
+                        0,
+                        0,
+                        layer,
+                        width,
+                        height,
+                        1,
+                        format_info.internal_format,
+                        texels.len() as _,
+                        texels.as_ptr() as _,
+                    ),
+                }
+            }
+        }
+
+        unsafe {
+            gl::glPixelStorei(gl::UNPACK_ALIGNMENT, 4);
+            gl::glBindTexture(target, 0);
+        }
+
+        check_gl_error("Copying buffer to texture")
+    }
+
+    // The 2D image that glTexSubImage2D writes, each face of a cube map is a separate target
+    fn image_target(
+        target: gl::GLenum,
+        layer: gl::GLint,
+    ) -> gl::GLenum {
+        if target == gl::TEXTURE_CUBE_MAP {
+            gl::TEXTURE_CUBE_MAP_POSITIVE_X + layer as u32
+        } else {
+            target
+        }
+    }
+
+    // Reads a whole mip level back with glReadPixels and writes it to a buffer laid out like
+    // RafxTextureBufferLayout
+    fn copy_texture_to_buffer(
+        &mut self,
+        src_texture: &RafxTextureGles3,
+        dst_buffer: &RafxBufferGles3,
+        buffer_offset: u64,
+        array_layer: u32,
+        mip_level: u8,
+    ) -> RafxResult<()> {
+        let texture_def = src_texture.texture_def();
+        let format_info = *src_texture.format_info();
+        let layout = RafxTextureBufferLayout::new(
+            self.device_context.device_info(),
+            texture_def.format,
+            texture_def.extents,
+            mip_level,
+        );
+        let bytes_per_pixel = texture_def.format.block_or_pixel_size_in_bytes() as usize;
+        let read_framebuffer = self.device_context.gl_objects().read_framebuffer;
+
+        let (first_layer, slice_count) = if is_3d(src_texture) {
+            (0, layout.extents.depth)
+        } else {
+            (array_layer, 1)
+        };
+
+        // Padding between rows and images is left as it was
+        let mut data = dst_buffer.read_range(buffer_offset, layout.size)?;
+        let mut texels =
+            vec![0u8; (layout.bytes_per_row_unpadded * layout.rows_per_image) as usize];
+
+        unsafe {
+            gl::glPixelStorei(gl::PACK_ALIGNMENT, 1);
+        }
+
+        for slice in 0..slice_count {
+            bind_subresource_framebuffer(
+                gl::READ_FRAMEBUFFER,
+                read_framebuffer,
+                src_texture,
+                mip_level as u32,
+                first_layer + slice,
+            )?;
+
+            unsafe {
+                gl::glReadBuffer(gl::COLOR_ATTACHMENT0);
+                gl::glReadPixels(
+                    0,
+                    0,
+                    layout.extents.width as _,
+                    layout.extents.height as _,
+                    format_info.format,
+                    format_info.type_,
+                    texels.as_mut_ptr() as _,
+                );
+            }
+            check_gl_error("glReadPixels")?;
+
+            if format_info.swap_red_blue {
+                swap_red_blue(&mut texels, bytes_per_pixel);
+            }
+
+            let slice_start = (slice * layout.bytes_per_image) as usize;
+            for (row, row_texels) in texels
+                .chunks_exact(layout.bytes_per_row_unpadded as usize)
+                .enumerate()
+            {
+                let row_start = slice_start + row * layout.bytes_per_row as usize;
+                data[row_start..row_start + row_texels.len()].copy_from_slice(row_texels);
+            }
+        }
+
+        unsafe {
+            gl::glPixelStorei(gl::PACK_ALIGNMENT, 4);
+            gl::glBindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+
+        dst_buffer.write_range(buffer_offset, &data)
+    }
+
+    // Blits a region of one layer to a region of another. The regions are [min, max] corners, a
+    // region may be flipped by swapping its corners.
+    fn blit_layer(
+        &mut self,
+        src_texture: &RafxTextureGles3,
+        dst_texture: &RafxTextureGles3,
+        src: (u32, u32, [RafxExtents3D; 2]),
+        dst: (u32, u32, [RafxExtents3D; 2]),
+        filter: gl::GLenum,
+    ) -> RafxResult<()> {
+        let gl_objects = self.device_context.gl_objects();
+        let (src_mip_level, src_layer, src_rect) = src;
+        let (dst_mip_level, dst_layer, dst_rect) = dst;
+
+        let mask = bind_subresource_framebuffer(
+            gl::READ_FRAMEBUFFER,
+            gl_objects.read_framebuffer,
+            src_texture,
+            src_mip_level,
+            src_layer,
+        )?;
+        bind_subresource_framebuffer(
+            gl::DRAW_FRAMEBUFFER,
+            gl_objects.draw_framebuffer,
+            dst_texture,
+            dst_mip_level,
+            dst_layer,
+        )?;
+
+        unsafe {
+            gl::glDisable(gl::SCISSOR_TEST);
+            gl::glBlitFramebuffer(
+                src_rect[0].width as _,
+                src_rect[0].height as _,
+                src_rect[1].width as _,
+                src_rect[1].height as _,
+                dst_rect[0].width as _,
+                dst_rect[0].height as _,
+                dst_rect[1].width as _,
+                dst_rect[1].height as _,
+                mask,
+                filter,
+            );
+            gl::glBindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::glBindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+        }
+        self.pipeline_state_dirty = true;
+
+        check_gl_error("Blitting texture")
+    }
+}
@@ -0,0 +1,164 @@
+use super::internal::gl_context::check_gl_error;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxFenceStatus, RafxResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// How long to block in a single glClientWaitSync call, in nanoseconds
+const WAIT_TIMEOUT_NS: u64 = 1_000_000_000;
+
+// GL sync objects are plain handles owned by the context, they may be passed between threads as
+// long as they are only used with the context locked
+struct GlSync(gl::GLsync);
+unsafe impl Send for GlSync {}
+
+/// Signaled by a GL sync object that is inserted after the submitted commands. A fence signaled by
+/// acquiring a swapchain image has no sync object and is complete immediately.
+pub struct RafxFenceGles3 {
+    device_context: RafxDeviceContextGles3,
+    // Set to true when an operation is scheduled to signal this fence
+    // Cleared when an operation is scheduled to consume this fence
+    submitted: AtomicBool,
+    sync: Mutex<Option<GlSync>>,
+    // Used internally to identify the fence for validation
+    #[cfg(feature = "validate-resource-states")]
+    fence_id: u32,
+}
+
+impl Drop for RafxFenceGles3 {
+    fn drop(&mut self) {
+        if let Some(sync) = self.sync.get_mut().unwrap().take() {
+            if let Ok(_guard) = self.device_context.gl_context().lock() {
+                unsafe {
+                    gl::glDeleteSync(sync.0);
+                }
+            }
+        }
+    }
+}
+
+impl RafxFenceGles3 {
+    pub fn new(device_context: &RafxDeviceContextGles3) -> RafxResult<RafxFenceGles3> {
+        Ok(RafxFenceGles3 {
+            device_context: device_context.clone(),
+            submitted: AtomicBool::new(false),
+            sync: Mutex::new(None),
+            #[cfg(feature = "validate-resource-states")]
+            fence_id: crate::internal_shared::NEXT_FENCE_ID.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn fence_id(&self) -> u32 {
+        self.fence_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn device_context(&self) -> &RafxDeviceContextGles3 {
+        &self.device_context
+    }
+
+    pub(crate) fn submitted(&self) -> bool {
+        self.submitted.load(Ordering::Relaxed)
+    }
+
+    // Must be called with the GL context locked. Inserts a sync object after all commands that have
+    // been issued so far.
+    pub(crate) fn signal_after_commands(&self) -> RafxResult<()> {
+        let sync = unsafe { gl::glFenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) };
+        check_gl_error("glFenceSync")?;
+        self.replace_sync(Some(GlSync(sync)));
+        self.submitted.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    // Marks the fence as signaled without any GPU work to wait for
+    pub(crate) fn signal_immediately(&self) {
+        self.replace_sync(None);
+        self.submitted.store(true, Ordering::Relaxed);
+    }
+
+    fn replace_sync(
+        &self,
+        sync: Option<GlSync>,
+    ) {
+        let previous_sync = std::mem::replace(&mut *self.sync.lock().unwrap(), sync);
+        if let Some(previous_sync) = previous_sync {
+            if let Ok(_guard) = self.device_context.gl_context().lock() {
+                unsafe {
+                    gl::glDeleteSync(previous_sync.0);
+                }
+            }
+        }
+    }
+
+    pub fn wait(&self) -> RafxResult<()> {
+        if let Some(sync) = self.sync.lock().unwrap().take() {
+            let _guard = self.device_context.gl_context().lock()?;
+            let result = loop {
+                let result = unsafe {
+                    gl::glClientWaitSync(sync.0, gl::SYNC_FLUSH_COMMANDS_BIT, WAIT_TIMEOUT_NS)
+                };
+                if result != gl::TIMEOUT_EXPIRED {
+                    break result;
+                }
+            };
+
+            unsafe {
+                gl::glDeleteSync(sync.0);
+            }
+
+            if result == gl::WAIT_FAILED {
+                check_gl_error("glClientWaitSync")?;
+                return Err("glClientWaitSync failed")?;
+            }
+        }
+
+        self.submitted.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn wait_for_fences(
+        _device_context: &RafxDeviceContextGles3,
+        fences: &[&RafxFenceGles3],
+    ) -> RafxResult<()> {
+        for fence in fences {
+            if fence.submitted() {
+                fence.wait()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_fence_status(&self) -> RafxResult<RafxFenceStatus> {
+        if !self.submitted() {
+            return Ok(RafxFenceStatus::Unsubmitted);
+        }
+
+        let is_complete = if let Some(sync) = &*self.sync.lock().unwrap() {
+            let _guard = self.device_context.gl_context().lock()?;
+            let mut status = 0;
+            unsafe {
+                gl::glGetSynciv(
+                    sync.0,
+                    gl::SYNC_STATUS,
+                    1,
+                    std::ptr::null_mut(),
+                    &mut status,
+                );
+            }
+            status as gl::GLenum == gl::SIGNALED
+        } else {
+            true
+        };
+
+        if is_complete {
+            self.wait()?;
+            Ok(RafxFenceStatus::Complete)
+        } else {
+            Ok(RafxFenceStatus::Incomplete)
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxHeapDef, RafxResult};
+
+/// GL allocates memory for each buffer and texture itself, so heaps and placed resources are not
+/// supported. Creating a heap fails.
+#[derive(Clone, Debug)]
+pub struct RafxHeapGles3 {
+    heap_def: RafxHeapDef,
+}
+
+impl RafxHeapGles3 {
+    pub fn heap_def(&self) -> &RafxHeapDef {
+        &self.heap_def
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextGles3,
+        _heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeapGles3> {
+        Err("Heaps are not supported by the GL ES backend")?
+    }
+}
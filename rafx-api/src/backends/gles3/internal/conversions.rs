@@ -0,0 +1,470 @@
+use super::gles3_bindings as gl;
+use super::gles3_bindings::{GLenum, GLint};
+use crate::{
+    RafxAddressMode, RafxBlendFactor, RafxBlendOp, RafxCompareOp, RafxFilterType, RafxFormat,
+    RafxFrontFace, RafxIndexType, RafxMemoryUsage, RafxMipMapMode, RafxPrimitiveTopology,
+    RafxStencilOp,
+};
+
+/// How a rafx format is stored in GL
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct GlFormatInfo {
+    // Sized internal format passed to glTexStorage*/glRenderbufferStorage*
+    pub(crate) internal_format: GLenum,
+    // Format/type of client data for glTexSubImage*/glReadPixels, unused for compressed formats
+    pub(crate) format: GLenum,
+    pub(crate) type_: GLenum,
+    pub(crate) is_compressed: bool,
+    // GL has no BGRA storage, so BGRA formats are stored as RGBA. Shaders and render targets see
+    // the same logical channels either way, but bytes copied to/from buffers have to be swizzled
+    pub(crate) swap_red_blue: bool,
+}
+
+impl GlFormatInfo {
+    const fn uncompressed(
+        internal_format: GLenum,
+        format: GLenum,
+        type_: GLenum,
+    ) -> Self {
+        GlFormatInfo {
+            internal_format,
+            format,
+            type_,
+            is_compressed: false,
+            swap_red_blue: false,
+        }
+    }
+
+    const fn bgra(
+        internal_format: GLenum,
+        format: GLenum,
+        type_: GLenum,
+    ) -> Self {
+        GlFormatInfo {
+            internal_format,
+            format,
+            type_,
+            is_compressed: false,
+            swap_red_blue: true,
+        }
+    }
+
+    const fn compressed(internal_format: GLenum) -> Self {
+        GlFormatInfo {
+            internal_format,
+            format: gl::NONE,
+            type_: gl::NONE,
+            is_compressed: true,
+            swap_red_blue: false,
+        }
+    }
+
+    pub(crate) fn is_integer(&self) -> bool {
+        matches!(
+            self.format,
+            gl::RED_INTEGER | gl::RG_INTEGER | gl::RGB_INTEGER | gl::RGBA_INTEGER
+        )
+    }
+
+    pub(crate) fn is_signed_integer(&self) -> bool {
+        self.is_integer() && matches!(self.type_, gl::BYTE | gl::SHORT | gl::INT)
+    }
+}
+
+/// Returns how the format is stored in GL ES 3.0, or None if it can't be. Scaled, 64-bit,
+/// 16-bit normalized and most packed formats have no GL ES equivalent.
+pub(crate) fn gl_format_info(format: RafxFormat) -> Option<GlFormatInfo> {
+    use GlFormatInfo as F;
+    Some(match format {
+        RafxFormat::R4G4B4A4_UNORM_PACK16 => {
+            F::uncompressed(gl::RGBA4, gl::RGBA, gl::UNSIGNED_SHORT_4_4_4_4)
+        }
+        RafxFormat::R5G6B5_UNORM_PACK16 => {
+            F::uncompressed(gl::RGB565, gl::RGB, gl::UNSIGNED_SHORT_5_6_5)
+        }
+        RafxFormat::R5G5B5A1_UNORM_PACK16 => {
+            F::uncompressed(gl::RGB5_A1, gl::RGBA, gl::UNSIGNED_SHORT_5_5_5_1)
+        }
+
+        RafxFormat::R8_UNORM => F::uncompressed(gl::R8, gl::RED, gl::UNSIGNED_BYTE),
+        RafxFormat::R8_SNORM => F::uncompressed(gl::R8_SNORM, gl::RED, gl::BYTE),
+        RafxFormat::R8_UINT => F::uncompressed(gl::R8UI, gl::RED_INTEGER, gl::UNSIGNED_BYTE),
+        RafxFormat::R8_SINT => F::uncompressed(gl::R8I, gl::RED_INTEGER, gl::BYTE),
+        RafxFormat::R8G8_UNORM => F::uncompressed(gl::RG8, gl::RG, gl::UNSIGNED_BYTE),
+        RafxFormat::R8G8_SNORM => F::uncompressed(gl::RG8_SNORM, gl::RG, gl::BYTE),
+        RafxFormat::R8G8_UINT => F::uncompressed(gl::RG8UI, gl::RG_INTEGER, gl::UNSIGNED_BYTE),
+        RafxFormat::R8G8_SINT => F::uncompressed(gl::RG8I, gl::RG_INTEGER, gl::BYTE),
+        RafxFormat::R8G8B8_UNORM => F::uncompressed(gl::RGB8, gl::RGB, gl::UNSIGNED_BYTE),
+        RafxFormat::R8G8B8_SNORM => F::uncompressed(gl::RGB8_SNORM, gl::RGB, gl::BYTE),
+        RafxFormat::R8G8B8_UINT => F::uncompressed(gl::RGB8UI, gl::RGB_INTEGER, gl::UNSIGNED_BYTE),
+        RafxFormat::R8G8B8_SINT => F::uncompressed(gl::RGB8I, gl::RGB_INTEGER, gl::BYTE),
+        RafxFormat::R8G8B8_SRGB => F::uncompressed(gl::SRGB8, gl::RGB, gl::UNSIGNED_BYTE),
+        RafxFormat::B8G8R8_UNORM => F::bgra(gl::RGB8, gl::RGB, gl::UNSIGNED_BYTE),
+        RafxFormat::B8G8R8_SRGB => F::bgra(gl::SRGB8, gl::RGB, gl::UNSIGNED_BYTE),
+        RafxFormat::R8G8B8A8_UNORM => F::uncompressed(gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE),
+        RafxFormat::R8G8B8A8_SNORM => F::uncompressed(gl::RGBA8_SNORM, gl::RGBA, gl::BYTE),
+        RafxFormat::R8G8B8A8_UINT => {
+            F::uncompressed(gl::RGBA8UI, gl::RGBA_INTEGER, gl::UNSIGNED_BYTE)
+        }
+        RafxFormat::R8G8B8A8_SINT => F::uncompressed(gl::RGBA8I, gl::RGBA_INTEGER, gl::BYTE),
+        RafxFormat::R8G8B8A8_SRGB => F::uncompressed(gl::SRGB8_ALPHA8, gl::RGBA, gl::UNSIGNED_BYTE),
+        RafxFormat::B8G8R8A8_UNORM => F::bgra(gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE),
+        RafxFormat::B8G8R8A8_SRGB => F::bgra(gl::SRGB8_ALPHA8, gl::RGBA, gl::UNSIGNED_BYTE),
+        // Packed little-endian, so the bytes are in R, G, B, A order
+        RafxFormat::A8B8G8R8_UNORM_PACK32 => {
+            F::uncompressed(gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE)
+        }
+        RafxFormat::A8B8G8R8_SRGB_PACK32 => {
+            F::uncompressed(gl::SRGB8_ALPHA8, gl::RGBA, gl::UNSIGNED_BYTE)
+        }
+        RafxFormat::A2B10G10R10_UNORM_PACK32 => {
+            F::uncompressed(gl::RGB10_A2, gl::RGBA, gl::UNSIGNED_INT_2_10_10_10_REV)
+        }
+        RafxFormat::A2B10G10R10_UINT_PACK32 => F::uncompressed(
+            gl::RGB10_A2UI,
+            gl::RGBA_INTEGER,
+            gl::UNSIGNED_INT_2_10_10_10_REV,
+        ),
+
+        RafxFormat::R16_UINT => F::uncompressed(gl::R16UI, gl::RED_INTEGER, gl::UNSIGNED_SHORT),
+        RafxFormat::R16_SINT => F::uncompressed(gl::R16I, gl::RED_INTEGER, gl::SHORT),
+        RafxFormat::R16_SFLOAT => F::uncompressed(gl::R16F, gl::RED, gl::HALF_FLOAT),
+        RafxFormat::R16G16_UINT => F::uncompressed(gl::RG16UI, gl::RG_INTEGER, gl::UNSIGNED_SHORT),
+        RafxFormat::R16G16_SINT => F::uncompressed(gl::RG16I, gl::RG_INTEGER, gl::SHORT),
+        RafxFormat::R16G16_SFLOAT => F::uncompressed(gl::RG16F, gl::RG, gl::HALF_FLOAT),
+        RafxFormat::R16G16B16_UINT => {
+            F::uncompressed(gl::RGB16UI, gl::RGB_INTEGER, gl::UNSIGNED_SHORT)
+        }
+        RafxFormat::R16G16B16_SINT => F::uncompressed(gl::RGB16I, gl::RGB_INTEGER, gl::SHORT),
+        RafxFormat::R16G16B16_SFLOAT => F::uncompressed(gl::RGB16F, gl::RGB, gl::HALF_FLOAT),
+        RafxFormat::R16G16B16A16_UINT => {
+            F::uncompressed(gl::RGBA16UI, gl::RGBA_INTEGER, gl::UNSIGNED_SHORT)
+        }
+        RafxFormat::R16G16B16A16_SINT => F::uncompressed(gl::RGBA16I, gl::RGBA_INTEGER, gl::SHORT),
+        RafxFormat::R16G16B16A16_SFLOAT => F::uncompressed(gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT),
+
+        RafxFormat::R32_UINT => F::uncompressed(gl::R32UI, gl::RED_INTEGER, gl::UNSIGNED_INT),
+        RafxFormat::R32_SINT => F::uncompressed(gl::R32I, gl::RED_INTEGER, gl::INT),
+        RafxFormat::R32_SFLOAT => F::uncompressed(gl::R32F, gl::RED, gl::FLOAT),
+        RafxFormat::R32G32_UINT => F::uncompressed(gl::RG32UI, gl::RG_INTEGER, gl::UNSIGNED_INT),
+        RafxFormat::R32G32_SINT => F::uncompressed(gl::RG32I, gl::RG_INTEGER, gl::INT),
+        RafxFormat::R32G32_SFLOAT => F::uncompressed(gl::RG32F, gl::RG, gl::FLOAT),
+        RafxFormat::R32G32B32_UINT => {
+            F::uncompressed(gl::RGB32UI, gl::RGB_INTEGER, gl::UNSIGNED_INT)
+        }
+        RafxFormat::R32G32B32_SINT => F::uncompressed(gl::RGB32I, gl::RGB_INTEGER, gl::INT),
+        RafxFormat::R32G32B32_SFLOAT => F::uncompressed(gl::RGB32F, gl::RGB, gl::FLOAT),
+        RafxFormat::R32G32B32A32_UINT => {
+            F::uncompressed(gl::RGBA32UI, gl::RGBA_INTEGER, gl::UNSIGNED_INT)
+        }
+        RafxFormat::R32G32B32A32_SINT => F::uncompressed(gl::RGBA32I, gl::RGBA_INTEGER, gl::INT),
+        RafxFormat::R32G32B32A32_SFLOAT => F::uncompressed(gl::RGBA32F, gl::RGBA, gl::FLOAT),
+
+        RafxFormat::B10G11R11_UFLOAT_PACK32 => F::uncompressed(
+            gl::R11F_G11F_B10F,
+            gl::RGB,
+            gl::UNSIGNED_INT_10F_11F_11F_REV,
+        ),
+        RafxFormat::E5B9G9R9_UFLOAT_PACK32 => {
+            F::uncompressed(gl::RGB9_E5, gl::RGB, gl::UNSIGNED_INT_5_9_9_9_REV)
+        }
+
+        RafxFormat::D16_UNORM => F::uncompressed(
+            gl::DEPTH_COMPONENT16,
+            gl::DEPTH_COMPONENT,
+            gl::UNSIGNED_SHORT,
+        ),
+        RafxFormat::X8_D24_UNORM_PACK32 => {
+            F::uncompressed(gl::DEPTH_COMPONENT24, gl::DEPTH_COMPONENT, gl::UNSIGNED_INT)
+        }
+        RafxFormat::D32_SFLOAT => {
+            F::uncompressed(gl::DEPTH_COMPONENT32F, gl::DEPTH_COMPONENT, gl::FLOAT)
+        }
+        RafxFormat::S8_UINT => F::uncompressed(gl::STENCIL_INDEX8, gl::NONE, gl::NONE),
+        RafxFormat::D24_UNORM_S8_UINT => F::uncompressed(
+            gl::DEPTH24_STENCIL8,
+            gl::DEPTH_STENCIL,
+            gl::UNSIGNED_INT_24_8,
+        ),
+        RafxFormat::D32_SFLOAT_S8_UINT => F::uncompressed(
+            gl::DEPTH32F_STENCIL8,
+            gl::DEPTH_STENCIL,
+            gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+        ),
+
+        RafxFormat::BC1_RGB_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGB_S3TC_DXT1_EXT),
+        RafxFormat::BC1_RGB_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB_S3TC_DXT1_EXT),
+        RafxFormat::BC1_RGBA_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGBA_S3TC_DXT1_EXT),
+        RafxFormat::BC1_RGBA_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT),
+        RafxFormat::BC2_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGBA_S3TC_DXT3_EXT),
+        RafxFormat::BC2_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT),
+        RafxFormat::BC3_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGBA_S3TC_DXT5_EXT),
+        RafxFormat::BC3_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT),
+        RafxFormat::BC4_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RED_RGTC1_EXT),
+        RafxFormat::BC4_SNORM_BLOCK => F::compressed(gl::COMPRESSED_SIGNED_RED_RGTC1_EXT),
+        RafxFormat::BC5_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RED_GREEN_RGTC2_EXT),
+        RafxFormat::BC5_SNORM_BLOCK => F::compressed(gl::COMPRESSED_SIGNED_RED_GREEN_RGTC2_EXT),
+        RafxFormat::BC6H_UFLOAT_BLOCK => F::compressed(gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT_EXT),
+        RafxFormat::BC6H_SFLOAT_BLOCK => F::compressed(gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT_EXT),
+        RafxFormat::BC7_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGBA_BPTC_UNORM_EXT),
+        RafxFormat::BC7_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM_EXT),
+
+        RafxFormat::ETC2_R8G8B8_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGB8_ETC2),
+        RafxFormat::ETC2_R8G8B8_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB8_ETC2),
+        RafxFormat::ETC2_R8G8B8A1_UNORM_BLOCK => {
+            F::compressed(gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2)
+        }
+        RafxFormat::ETC2_R8G8B8A1_SRGB_BLOCK => {
+            F::compressed(gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2)
+        }
+        RafxFormat::ETC2_R8G8B8A8_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RGBA8_ETC2_EAC),
+        RafxFormat::ETC2_R8G8B8A8_SRGB_BLOCK => F::compressed(gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC),
+        RafxFormat::EAC_R11_UNORM_BLOCK => F::compressed(gl::COMPRESSED_R11_EAC),
+        RafxFormat::EAC_R11_SNORM_BLOCK => F::compressed(gl::COMPRESSED_SIGNED_R11_EAC),
+        RafxFormat::EAC_R11G11_UNORM_BLOCK => F::compressed(gl::COMPRESSED_RG11_EAC),
+        RafxFormat::EAC_R11G11_SNORM_BLOCK => F::compressed(gl::COMPRESSED_SIGNED_RG11_EAC),
+
+        _ if format.is_astc_compressed() => F::compressed(astc_internal_format(format)),
+
+        _ => return None,
+    })
+}
+
+// The ASTC formats are numbered consecutively by block size, the same order RafxFormat uses, with
+// the sRGB variants in a separate range
+fn astc_internal_format(format: RafxFormat) -> GLenum {
+    const BLOCK_SIZES: [(u32, u32); 14] = [
+        (4, 4),
+        (5, 4),
+        (5, 5),
+        (6, 5),
+        (6, 6),
+        (8, 5),
+        (8, 6),
+        (8, 8),
+        (10, 5),
+        (10, 6),
+        (10, 8),
+        (10, 10),
+        (12, 10),
+        (12, 12),
+    ];
+
+    let block_size = (
+        format.block_width_in_pixels(),
+        format.block_height_in_pixels(),
+    );
+    let index = BLOCK_SIZES.iter().position(|x| *x == block_size).unwrap() as GLenum;
+    let is_srgb = format!("{:?}", format).ends_with("_SRGB_BLOCK");
+    if is_srgb {
+        gl::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4_KHR + index
+    } else {
+        gl::COMPRESSED_RGBA_ASTC_4x4_KHR + index
+    }
+}
+
+/// The attachment point a depth/stencil format is bound to
+pub(crate) fn depth_stencil_attachment(format: RafxFormat) -> GLenum {
+    if format.has_depth() && format.has_stencil() {
+        gl::DEPTH_STENCIL_ATTACHMENT
+    } else if format.has_stencil() {
+        gl::STENCIL_ATTACHMENT
+    } else {
+        gl::DEPTH_ATTACHMENT
+    }
+}
+
+/// The component count, GL type, and whether the attribute is normalized or an integer for a
+/// vertex attribute format
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct GlVertexFormat {
+    pub(crate) component_count: GLint,
+    pub(crate) type_: GLenum,
+    pub(crate) normalized: bool,
+    // Integer attributes must be set with glVertexAttribIPointer
+    pub(crate) is_integer: bool,
+}
+
+pub(crate) fn gl_vertex_format(format: RafxFormat) -> Option<GlVertexFormat> {
+    let (component_count, type_, normalized, is_integer) = match format {
+        RafxFormat::R8_UNORM => (1, gl::UNSIGNED_BYTE, true, false),
+        RafxFormat::R8_SNORM => (1, gl::BYTE, true, false),
+        RafxFormat::R8_UINT => (1, gl::UNSIGNED_BYTE, false, true),
+        RafxFormat::R8_SINT => (1, gl::BYTE, false, true),
+        RafxFormat::R8G8_UNORM => (2, gl::UNSIGNED_BYTE, true, false),
+        RafxFormat::R8G8_SNORM => (2, gl::BYTE, true, false),
+        RafxFormat::R8G8_UINT => (2, gl::UNSIGNED_BYTE, false, true),
+        RafxFormat::R8G8_SINT => (2, gl::BYTE, false, true),
+        RafxFormat::R8G8B8_UNORM => (3, gl::UNSIGNED_BYTE, true, false),
+        RafxFormat::R8G8B8_SNORM => (3, gl::BYTE, true, false),
+        RafxFormat::R8G8B8_UINT => (3, gl::UNSIGNED_BYTE, false, true),
+        RafxFormat::R8G8B8_SINT => (3, gl::BYTE, false, true),
+        RafxFormat::R8G8B8A8_UNORM => (4, gl::UNSIGNED_BYTE, true, false),
+        RafxFormat::R8G8B8A8_SNORM => (4, gl::BYTE, true, false),
+        RafxFormat::R8G8B8A8_UINT => (4, gl::UNSIGNED_BYTE, false, true),
+        RafxFormat::R8G8B8A8_SINT => (4, gl::BYTE, false, true),
+        RafxFormat::R16_UNORM => (1, gl::UNSIGNED_SHORT, true, false),
+        RafxFormat::R16_SNORM => (1, gl::SHORT, true, false),
+        RafxFormat::R16_UINT => (1, gl::UNSIGNED_SHORT, false, true),
+        RafxFormat::R16_SINT => (1, gl::SHORT, false, true),
+        RafxFormat::R16_SFLOAT => (1, gl::HALF_FLOAT, false, false),
+        RafxFormat::R16G16_UNORM => (2, gl::UNSIGNED_SHORT, true, false),
+        RafxFormat::R16G16_SNORM => (2, gl::SHORT, true, false),
+        RafxFormat::R16G16_UINT => (2, gl::UNSIGNED_SHORT, false, true),
+        RafxFormat::R16G16_SINT => (2, gl::SHORT, false, true),
+        RafxFormat::R16G16_SFLOAT => (2, gl::HALF_FLOAT, false, false),
+        RafxFormat::R16G16B16_UNORM => (3, gl::UNSIGNED_SHORT, true, false),
+        RafxFormat::R16G16B16_SNORM => (3, gl::SHORT, true, false),
+        RafxFormat::R16G16B16_UINT => (3, gl::UNSIGNED_SHORT, false, true),
+        RafxFormat::R16G16B16_SINT => (3, gl::SHORT, false, true),
+        RafxFormat::R16G16B16_SFLOAT => (3, gl::HALF_FLOAT, false, false),
+        RafxFormat::R16G16B16A16_UNORM => (4, gl::UNSIGNED_SHORT, true, false),
+        RafxFormat::R16G16B16A16_SNORM => (4, gl::SHORT, true, false),
+        RafxFormat::R16G16B16A16_UINT => (4, gl::UNSIGNED_SHORT, false, true),
+        RafxFormat::R16G16B16A16_SINT => (4, gl::SHORT, false, true),
+        RafxFormat::R16G16B16A16_SFLOAT => (4, gl::HALF_FLOAT, false, false),
+        RafxFormat::R32_UINT => (1, gl::UNSIGNED_INT, false, true),
+        RafxFormat::R32_SINT => (1, gl::INT, false, true),
+        RafxFormat::R32_SFLOAT => (1, gl::FLOAT, false, false),
+        RafxFormat::R32G32_UINT => (2, gl::UNSIGNED_INT, false, true),
+        RafxFormat::R32G32_SINT => (2, gl::INT, false, true),
+        RafxFormat::R32G32_SFLOAT => (2, gl::FLOAT, false, false),
+        RafxFormat::R32G32B32_UINT => (3, gl::UNSIGNED_INT, false, true),
+        RafxFormat::R32G32B32_SINT => (3, gl::INT, false, true),
+        RafxFormat::R32G32B32_SFLOAT => (3, gl::FLOAT, false, false),
+        RafxFormat::R32G32B32A32_UINT => (4, gl::UNSIGNED_INT, false, true),
+        RafxFormat::R32G32B32A32_SINT => (4, gl::INT, false, true),
+        RafxFormat::R32G32B32A32_SFLOAT => (4, gl::FLOAT, false, false),
+        RafxFormat::A2B10G10R10_UNORM_PACK32 => (4, gl::UNSIGNED_INT_2_10_10_10_REV, true, false),
+        RafxFormat::A2B10G10R10_SNORM_PACK32 => (4, gl::INT_2_10_10_10_REV, true, false),
+        _ => return None,
+    };
+
+    Some(GlVertexFormat {
+        component_count,
+        type_,
+        normalized,
+        is_integer,
+    })
+}
+
+// Returns the GL buffer usage hint for a memory usage
+pub(crate) fn gl_buffer_usage(memory_usage: RafxMemoryUsage) -> GLenum {
+    match memory_usage {
+        RafxMemoryUsage::Unknown | RafxMemoryUsage::GpuOnly => gl::STATIC_DRAW,
+        RafxMemoryUsage::CpuOnly => gl::STREAM_DRAW,
+        RafxMemoryUsage::CpuToGpu => gl::DYNAMIC_DRAW,
+        RafxMemoryUsage::GpuToCpu => gl::DYNAMIC_READ,
+    }
+}
+
+pub(crate) fn gl_min_filter(
+    min_filter: RafxFilterType,
+    mip_map_mode: RafxMipMapMode,
+) -> GLenum {
+    match (min_filter, mip_map_mode) {
+        (RafxFilterType::Nearest, RafxMipMapMode::Nearest) => gl::NEAREST_MIPMAP_NEAREST,
+        (RafxFilterType::Nearest, RafxMipMapMode::Linear) => gl::NEAREST_MIPMAP_LINEAR,
+        (RafxFilterType::Linear, RafxMipMapMode::Nearest) => gl::LINEAR_MIPMAP_NEAREST,
+        (RafxFilterType::Linear, RafxMipMapMode::Linear) => gl::LINEAR_MIPMAP_LINEAR,
+    }
+}
+
+pub(crate) fn gl_mag_filter(mag_filter: RafxFilterType) -> GLenum {
+    match mag_filter {
+        RafxFilterType::Nearest => gl::NEAREST,
+        RafxFilterType::Linear => gl::LINEAR,
+    }
+}
+
+pub(crate) fn gl_address_mode(address_mode: RafxAddressMode) -> GLenum {
+    match address_mode {
+        RafxAddressMode::Mirror => gl::MIRRORED_REPEAT,
+        RafxAddressMode::Repeat => gl::REPEAT,
+        RafxAddressMode::ClampToEdge => gl::CLAMP_TO_EDGE,
+        RafxAddressMode::ClampToBorder => gl::CLAMP_TO_BORDER,
+    }
+}
+
+pub(crate) fn gl_compare_func(compare_op: RafxCompareOp) -> GLenum {
+    match compare_op {
+        RafxCompareOp::Never => gl::NEVER,
+        RafxCompareOp::Less => gl::LESS,
+        RafxCompareOp::Equal => gl::EQUAL,
+        RafxCompareOp::LessOrEqual => gl::LEQUAL,
+        RafxCompareOp::Greater => gl::GREATER,
+        RafxCompareOp::NotEqual => gl::NOTEQUAL,
+        RafxCompareOp::GreaterOrEqual => gl::GEQUAL,
+        RafxCompareOp::Always => gl::ALWAYS,
+    }
+}
+
+pub(crate) fn gl_stencil_op(stencil_op: RafxStencilOp) -> GLenum {
+    match stencil_op {
+        RafxStencilOp::Keep => gl::KEEP,
+        RafxStencilOp::Zero => gl::ZERO,
+        RafxStencilOp::Replace => gl::REPLACE,
+        RafxStencilOp::IncrementAndClamp => gl::INCR,
+        RafxStencilOp::DecrementAndClamp => gl::DECR,
+        RafxStencilOp::Invert => gl::INVERT,
+        RafxStencilOp::IncrementAndWrap => gl::INCR_WRAP,
+        RafxStencilOp::DecrementAndWrap => gl::DECR_WRAP,
+    }
+}
+
+pub(crate) fn gl_blend_factor(blend_factor: RafxBlendFactor) -> GLenum {
+    match blend_factor {
+        RafxBlendFactor::Zero => gl::ZERO,
+        RafxBlendFactor::One => gl::ONE,
+        RafxBlendFactor::SrcColor => gl::SRC_COLOR,
+        RafxBlendFactor::OneMinusSrcColor => gl::ONE_MINUS_SRC_COLOR,
+        RafxBlendFactor::DstColor => gl::DST_COLOR,
+        RafxBlendFactor::OneMinusDstColor => gl::ONE_MINUS_DST_COLOR,
+        RafxBlendFactor::SrcAlpha => gl::SRC_ALPHA,
+        RafxBlendFactor::OneMinusSrcAlpha => gl::ONE_MINUS_SRC_ALPHA,
+        RafxBlendFactor::DstAlpha => gl::DST_ALPHA,
+        RafxBlendFactor::OneMinusDstAlpha => gl::ONE_MINUS_DST_ALPHA,
+        RafxBlendFactor::SrcAlphaSaturate => gl::SRC_ALPHA_SATURATE,
+        RafxBlendFactor::ConstantColor => gl::CONSTANT_COLOR,
+        RafxBlendFactor::OneMinusConstantColor => gl::ONE_MINUS_CONSTANT_COLOR,
+    }
+}
+
+pub(crate) fn gl_blend_op(blend_op: RafxBlendOp) -> GLenum {
+    match blend_op {
+        RafxBlendOp::Add => gl::FUNC_ADD,
+        RafxBlendOp::Subtract => gl::FUNC_SUBTRACT,
+        RafxBlendOp::ReverseSubtract => gl::FUNC_REVERSE_SUBTRACT,
+        RafxBlendOp::Min => gl::MIN,
+        RafxBlendOp::Max => gl::MAX,
+    }
+}
+
+// Winding is computed with the opposite sign in vulkan (whose framebuffer y axis points down) and
+// GL, so with the same vertex data vulkan's counter-clockwise is GL's clockwise
+pub(crate) fn gl_front_face(front_face: RafxFrontFace) -> GLenum {
+    match front_face {
+        RafxFrontFace::CounterClockwise => gl::CW,
+        RafxFrontFace::Clockwise => gl::CCW,
+    }
+}
+
+// Returns None for patch lists, GL ES 3.0 has no tessellation
+pub(crate) fn gl_primitive_mode(primitive_topology: RafxPrimitiveTopology) -> Option<GLenum> {
+    match primitive_topology {
+        RafxPrimitiveTopology::PointList => Some(gl::POINTS),
+        RafxPrimitiveTopology::LineList => Some(gl::LINES),
+        RafxPrimitiveTopology::LineStrip => Some(gl::LINE_STRIP),
+        RafxPrimitiveTopology::TriangleList => Some(gl::TRIANGLES),
+        RafxPrimitiveTopology::TriangleStrip => Some(gl::TRIANGLE_STRIP),
+        RafxPrimitiveTopology::PatchList => None,
+    }
+}
+
+// Returns the GL index type and the size of an index in bytes
+pub(crate) fn gl_index_type(index_type: RafxIndexType) -> (GLenum, u64) {
+    match index_type {
+        RafxIndexType::Uint32 => (gl::UNSIGNED_INT, 4),
+        RafxIndexType::Uint16 => (gl::UNSIGNED_SHORT, 2),
+    }
+}
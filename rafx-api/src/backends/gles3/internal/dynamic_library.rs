@@ -0,0 +1,82 @@
+// Declares the entry points of a library that is loaded at runtime, using the same syntax as an
+// extern block. This generates:
+//  * A struct with a function pointer for each entry point, and a `load()` that opens the first of
+//    the given library names that exists and looks up every entry point. It must succeed before
+//    any of the functions are called, and only loads the library the first time it's called.
+//  * A free function for each entry point that calls through the loaded function pointer, so call
+//    sites look the same as they would if the library were linked.
+//
+// Loading at runtime means enabling the backend doesn't require the libraries to be installed
+// unless a GL ES device is actually created.
+macro_rules! dynamic_library_functions {
+    (
+        $struct_name:ident, $static_name:ident, $library_names:expr;
+        $(
+            pub fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;
+        )*
+    ) => {
+        pub struct $struct_name {
+            // Keeps the library loaded while the function pointers are in use
+            _library: libloading::Library,
+            $(
+                $name: unsafe extern "system" fn($($ty),*) $(-> $ret)?,
+            )*
+        }
+
+        static $static_name: std::sync::OnceLock<Result<$struct_name, String>> =
+            std::sync::OnceLock::new();
+
+        impl $struct_name {
+            unsafe fn load_library() -> Result<Self, String> {
+                let library_names: &[&str] = $library_names;
+                let mut library = None;
+                for library_name in library_names {
+                    match libloading::Library::new(library_name) {
+                        Ok(loaded) => {
+                            library = Some(loaded);
+                            break;
+                        }
+                        Err(e) => log::debug!("Could not load {}: {}", library_name, e),
+                    }
+                }
+
+                let library = library
+                    .ok_or_else(|| format!("Could not load any of {:?}", library_names))?;
+
+                $(
+                    let $name = *library
+                        .get::<unsafe extern "system" fn($($ty),*) $(-> $ret)?>(
+                            concat!(stringify!($name), "\0").as_bytes(),
+                        )
+                        .map_err(|e| format!("Could not load {}: {}", stringify!($name), e))?;
+                )*
+
+                Ok($struct_name {
+                    _library: library,
+                    $($name,)*
+                })
+            }
+
+            pub fn load() -> crate::RafxResult<()> {
+                match $static_name.get_or_init(|| unsafe { Self::load_library() }) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(e.clone())?,
+                }
+            }
+        }
+
+        $(
+            #[inline]
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                match $static_name.get() {
+                    Some(Ok(functions)) => (functions.$name)($($arg),*),
+                    _ => panic!(concat!(
+                        stringify!($name),
+                        " was called before its library was loaded"
+                    )),
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,153 @@
+// Hand-written bindings for the subset of EGL 1.4 used by the backend. Extension entry points
+// (like eglGetPlatformDisplayEXT) are loaded with eglGetProcAddress because they are not exported
+// by every libEGL.
+#![allow(
+    non_camel_case_types,
+    non_snake_case,
+    clippy::upper_case_acronyms,
+    dead_code
+)]
+
+use std::os::raw::{c_char, c_void};
+
+pub type EGLBoolean = u32;
+pub type EGLenum = u32;
+pub type EGLint = i32;
+pub type EGLDisplay = *mut c_void;
+pub type EGLConfig = *mut c_void;
+pub type EGLContext = *mut c_void;
+pub type EGLSurface = *mut c_void;
+pub type EGLNativeDisplayType = *mut c_void;
+pub type EGLNativeWindowType = *mut c_void;
+
+pub const FALSE: EGLBoolean = 0;
+pub const TRUE: EGLBoolean = 1;
+
+pub const DEFAULT_DISPLAY: EGLNativeDisplayType = std::ptr::null_mut();
+pub const NO_DISPLAY: EGLDisplay = std::ptr::null_mut();
+pub const NO_CONTEXT: EGLContext = std::ptr::null_mut();
+pub const NO_SURFACE: EGLSurface = std::ptr::null_mut();
+pub const NO_CONFIG: EGLConfig = std::ptr::null_mut();
+
+pub const SUCCESS: EGLint = 0x3000;
+pub const BAD_SURFACE: EGLint = 0x300D;
+pub const CONTEXT_LOST: EGLint = 0x300E;
+
+pub const ALPHA_SIZE: EGLint = 0x3021;
+pub const BLUE_SIZE: EGLint = 0x3022;
+pub const GREEN_SIZE: EGLint = 0x3023;
+pub const RED_SIZE: EGLint = 0x3024;
+pub const DEPTH_SIZE: EGLint = 0x3025;
+pub const STENCIL_SIZE: EGLint = 0x3026;
+pub const SURFACE_TYPE: EGLint = 0x3033;
+pub const NONE: EGLint = 0x3038;
+pub const RENDERABLE_TYPE: EGLint = 0x3040;
+pub const HEIGHT: EGLint = 0x3056;
+pub const WIDTH: EGLint = 0x3057;
+pub const VENDOR: EGLint = 0x3053;
+pub const VERSION: EGLint = 0x3054;
+pub const EXTENSIONS: EGLint = 0x3055;
+pub const CONTEXT_CLIENT_VERSION: EGLint = 0x3098;
+pub const OPENGL_ES_API: EGLenum = 0x30A0;
+
+pub const PBUFFER_BIT: EGLint = 0x0001;
+pub const WINDOW_BIT: EGLint = 0x0004;
+pub const OPENGL_ES3_BIT: EGLint = 0x0040;
+
+// EGL_KHR_gl_colorspace
+pub const GL_COLORSPACE_KHR: EGLint = 0x309D;
+pub const GL_COLORSPACE_SRGB_KHR: EGLint = 0x3089;
+
+// EGL_MESA_platform_surfaceless
+pub const PLATFORM_SURFACELESS_MESA: EGLenum = 0x31DD;
+
+pub type PFNEGLGETPLATFORMDISPLAYEXTPROC = unsafe extern "system" fn(
+    platform: EGLenum,
+    native_display: *mut c_void,
+    attrib_list: *const EGLint,
+) -> EGLDisplay;
+
+#[cfg(target_os = "windows")]
+const EGL_LIBRARY_NAMES: &[&str] = &["libEGL.dll"];
+#[cfg(target_os = "macos")]
+const EGL_LIBRARY_NAMES: &[&str] = &["libEGL.dylib"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const EGL_LIBRARY_NAMES: &[&str] = &["libEGL.so.1", "libEGL.so"];
+
+dynamic_library_functions! {
+    EglFunctions, EGL_FUNCTIONS, EGL_LIBRARY_NAMES;
+
+    pub fn eglGetError() -> EGLint;
+    pub fn eglGetProcAddress(procname: *const c_char) -> *const c_void;
+    pub fn eglGetDisplay(display_id: EGLNativeDisplayType) -> EGLDisplay;
+    pub fn eglInitialize(
+        dpy: EGLDisplay,
+        major: *mut EGLint,
+        minor: *mut EGLint,
+    ) -> EGLBoolean;
+    pub fn eglTerminate(dpy: EGLDisplay) -> EGLBoolean;
+    pub fn eglQueryString(
+        dpy: EGLDisplay,
+        name: EGLint,
+    ) -> *const c_char;
+    pub fn eglBindAPI(api: EGLenum) -> EGLBoolean;
+    pub fn eglChooseConfig(
+        dpy: EGLDisplay,
+        attrib_list: *const EGLint,
+        configs: *mut EGLConfig,
+        config_size: EGLint,
+        num_config: *mut EGLint,
+    ) -> EGLBoolean;
+    pub fn eglGetConfigAttrib(
+        dpy: EGLDisplay,
+        config: EGLConfig,
+        attribute: EGLint,
+        value: *mut EGLint,
+    ) -> EGLBoolean;
+    pub fn eglCreateContext(
+        dpy: EGLDisplay,
+        config: EGLConfig,
+        share_context: EGLContext,
+        attrib_list: *const EGLint,
+    ) -> EGLContext;
+    pub fn eglDestroyContext(
+        dpy: EGLDisplay,
+        ctx: EGLContext,
+    ) -> EGLBoolean;
+    pub fn eglCreatePbufferSurface(
+        dpy: EGLDisplay,
+        config: EGLConfig,
+        attrib_list: *const EGLint,
+    ) -> EGLSurface;
+    pub fn eglCreateWindowSurface(
+        dpy: EGLDisplay,
+        config: EGLConfig,
+        win: EGLNativeWindowType,
+        attrib_list: *const EGLint,
+    ) -> EGLSurface;
+    pub fn eglDestroySurface(
+        dpy: EGLDisplay,
+        surface: EGLSurface,
+    ) -> EGLBoolean;
+    pub fn eglMakeCurrent(
+        dpy: EGLDisplay,
+        draw: EGLSurface,
+        read: EGLSurface,
+        ctx: EGLContext,
+    ) -> EGLBoolean;
+    pub fn eglSwapBuffers(
+        dpy: EGLDisplay,
+        surface: EGLSurface,
+    ) -> EGLBoolean;
+    pub fn eglSwapInterval(
+        dpy: EGLDisplay,
+        interval: EGLint,
+    ) -> EGLBoolean;
+    pub fn eglQuerySurface(
+        dpy: EGLDisplay,
+        surface: EGLSurface,
+        attribute: EGLint,
+        value: *mut EGLint,
+    ) -> EGLBoolean;
+    pub fn eglReleaseThread() -> EGLBoolean;
+}
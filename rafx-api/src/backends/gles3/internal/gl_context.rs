@@ -0,0 +1,575 @@
+use super::egl_bindings as egl;
+use super::gles3_bindings as gl;
+use crate::RafxResult;
+use raw_window_handle::RawWindowHandle;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_void};
+use std::sync::{Condvar, Mutex};
+use std::thread::ThreadId;
+
+// Which thread the context is current on, and how many guards that thread holds
+#[derive(Default)]
+struct GlContextLockState {
+    owner: Option<ThreadId>,
+    depth: u32,
+}
+
+/// Owns the EGL display/context that all GL calls are made through. A GL context can only be
+/// current on one thread at a time, so every GL call must be made while holding a
+/// `GlContextGuard`. The lock is reentrant, and the context is released from the thread when the
+/// last guard on that thread is dropped so that any thread can use the device.
+pub(crate) struct GlContext {
+    display: egl::EGLDisplay,
+    config: egl::EGLConfig,
+    context: egl::EGLContext,
+    // A 1x1 pbuffer, or NO_SURFACE if the config can't create one (requires
+    // EGL_KHR_surfaceless_context). All rendering goes to textures, so this is never drawn to
+    default_surface: egl::EGLSurface,
+    supports_srgb_surfaces: bool,
+    renderer: String,
+    version: String,
+    extensions: Vec<String>,
+    lock_state: Mutex<GlContextLockState>,
+    lock_released: Condvar,
+}
+
+// EGL handles may be used from any thread, access to the context itself is serialized by lock()
+unsafe impl Send for GlContext {}
+unsafe impl Sync for GlContext {}
+
+impl Drop for GlContext {
+    fn drop(&mut self) {
+        log::trace!("destroying EGL context");
+        unsafe {
+            egl::eglMakeCurrent(
+                self.display,
+                egl::NO_SURFACE,
+                egl::NO_SURFACE,
+                egl::NO_CONTEXT,
+            );
+            if self.default_surface != egl::NO_SURFACE {
+                egl::eglDestroySurface(self.display, self.default_surface);
+            }
+            egl::eglDestroyContext(self.display, self.context);
+
+            // The display is not terminated. EGL returns the same display handle for every call
+            // with the same native display, so terminating it would break any other context in
+            // the process that uses it
+        }
+    }
+}
+
+impl std::fmt::Debug for GlContext {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        f.debug_struct("GlContext")
+            .field("renderer", &self.renderer)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+/// Proof that the GL context is current on this thread. Not Send, the context is only current on
+/// the thread that locked it.
+pub(crate) struct GlContextGuard<'a> {
+    context: &'a GlContext,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<'a> Drop for GlContextGuard<'a> {
+    fn drop(&mut self) {
+        self.context.unlock();
+    }
+}
+
+impl<'a> GlContextGuard<'a> {
+    /// Makes the window surface current, runs `f` with the surface's default framebuffer available
+    /// as framebuffer 0, then swaps the surface's buffers
+    pub(crate) fn present_to_surface(
+        &self,
+        surface: egl::EGLSurface,
+        f: impl FnOnce() -> RafxResult<()>,
+    ) -> RafxResult<()> {
+        let context = self.context;
+        unsafe {
+            if egl::eglMakeCurrent(context.display, surface, surface, context.context) == egl::FALSE
+            {
+                return Err(format!(
+                    "eglMakeCurrent failed for window surface: {}",
+                    egl_error_string(egl::eglGetError())
+                ))?;
+            }
+
+            let result = f().and_then(|_| {
+                if egl::eglSwapBuffers(context.display, surface) == egl::FALSE {
+                    Err(format!(
+                        "eglSwapBuffers failed: {}",
+                        egl_error_string(egl::eglGetError())
+                    ))?;
+                }
+
+                Ok(())
+            });
+
+            egl::eglMakeCurrent(
+                context.display,
+                context.default_surface,
+                context.default_surface,
+                context.context,
+            );
+
+            result
+        }
+    }
+}
+
+impl GlContext {
+    /// Create a context that is not associated with any window. Uses mesa's surfaceless platform
+    /// when it is available so that no display server is required.
+    pub(crate) fn new_headless() -> RafxResult<Self> {
+        load_libraries()?;
+
+        let client_extensions = unsafe { query_string(egl::NO_DISPLAY, egl::EXTENSIONS) };
+        let has_client_extension = |name: &str| client_extensions.split(' ').any(|x| x == name);
+
+        let display = if has_client_extension("EGL_EXT_platform_base")
+            && has_client_extension("EGL_MESA_platform_surfaceless")
+        {
+            log::debug!("Using EGL surfaceless platform");
+            unsafe {
+                let get_platform_display =
+                    egl::eglGetProcAddress(b"eglGetPlatformDisplayEXT\0".as_ptr() as *const c_char);
+                if get_platform_display.is_null() {
+                    return Err("eglGetPlatformDisplayEXT could not be loaded")?;
+                }
+
+                let get_platform_display: egl::PFNEGLGETPLATFORMDISPLAYEXTPROC =
+                    std::mem::transmute(get_platform_display);
+                get_platform_display(
+                    egl::PLATFORM_SURFACELESS_MESA,
+                    egl::DEFAULT_DISPLAY,
+                    std::ptr::null(),
+                )
+            }
+        } else {
+            unsafe { egl::eglGetDisplay(egl::DEFAULT_DISPLAY) }
+        };
+
+        Self::new(display, egl::PBUFFER_BIT, None)
+    }
+
+    /// Create a context that is able to present to the given window
+    pub(crate) fn new_for_window(raw_window_handle: RawWindowHandle) -> RafxResult<Self> {
+        load_libraries()?;
+
+        let native_display = native_display(raw_window_handle)?;
+        let display = unsafe { egl::eglGetDisplay(native_display) };
+        Self::new(display, egl::WINDOW_BIT, Some(egl::PBUFFER_BIT))
+    }
+
+    fn new(
+        display: egl::EGLDisplay,
+        required_surface_type: egl::EGLint,
+        preferred_surface_type: Option<egl::EGLint>,
+    ) -> RafxResult<Self> {
+        if display == egl::NO_DISPLAY {
+            return Err("Could not open an EGL display")?;
+        }
+
+        unsafe {
+            let mut major = 0;
+            let mut minor = 0;
+            if egl::eglInitialize(display, &mut major, &mut minor) == egl::FALSE {
+                return Err(format!(
+                    "eglInitialize failed: {}",
+                    egl_error_string(egl::eglGetError())
+                ))?;
+            }
+
+            log::debug!(
+                "EGL {}.{} ({})",
+                major,
+                minor,
+                query_string(display, egl::VENDOR)
+            );
+
+            if egl::eglBindAPI(egl::OPENGL_ES_API) == egl::FALSE {
+                return Err("eglBindAPI(EGL_OPENGL_ES_API) failed")?;
+            }
+
+            let display_extensions = query_string(display, egl::EXTENSIONS);
+            let has_display_extension =
+                |name: &str| display_extensions.split(' ').any(|x| x == name);
+
+            let config = preferred_surface_type
+                .and_then(|x| choose_config(display, required_surface_type | x))
+                .or_else(|| choose_config(display, required_surface_type))
+                .ok_or("No EGL config supports OpenGL ES 3 with an RGBA8 surface")?;
+
+            let context_attributes = [egl::CONTEXT_CLIENT_VERSION, 3, egl::NONE];
+            let context = egl::eglCreateContext(
+                display,
+                config,
+                egl::NO_CONTEXT,
+                context_attributes.as_ptr(),
+            );
+            if context == egl::NO_CONTEXT {
+                return Err(format!(
+                    "eglCreateContext failed: {}",
+                    egl_error_string(egl::eglGetError())
+                ))?;
+            }
+
+            let mut surface_type = 0;
+            egl::eglGetConfigAttrib(display, config, egl::SURFACE_TYPE, &mut surface_type);
+            let default_surface = if surface_type & egl::PBUFFER_BIT != 0 {
+                let pbuffer_attributes = [egl::WIDTH, 1, egl::HEIGHT, 1, egl::NONE];
+                egl::eglCreatePbufferSurface(display, config, pbuffer_attributes.as_ptr())
+            } else {
+                egl::NO_SURFACE
+            };
+
+            if default_surface == egl::NO_SURFACE
+                && !has_display_extension("EGL_KHR_surfaceless_context")
+            {
+                egl::eglDestroyContext(display, context);
+                return Err("The EGL config has no pbuffer support and EGL_KHR_surfaceless_context is not available")?;
+            }
+
+            let mut gl_context = GlContext {
+                display,
+                config,
+                context,
+                default_surface,
+                supports_srgb_surfaces: has_display_extension("EGL_KHR_gl_colorspace"),
+                renderer: String::default(),
+                version: String::default(),
+                extensions: Vec::default(),
+                lock_state: Default::default(),
+                lock_released: Condvar::new(),
+            };
+
+            let (renderer, vendor, version, extensions) = {
+                let _guard = gl_context.lock()?;
+                let mut extension_count = 0;
+                gl::glGetIntegerv(gl::NUM_EXTENSIONS, &mut extension_count);
+                let extensions = (0..extension_count as u32)
+                    .map(|i| gl_string(gl::glGetStringi(gl::EXTENSIONS, i)))
+                    .collect();
+
+                (
+                    gl_string(gl::glGetString(gl::RENDERER)),
+                    gl_string(gl::glGetString(gl::VENDOR)),
+                    gl_string(gl::glGetString(gl::VERSION)),
+                    extensions,
+                )
+            };
+
+            log::info!(
+                "Created GL ES context: {} {} ({})",
+                vendor,
+                renderer,
+                version
+            );
+            gl_context.renderer = renderer;
+            gl_context.version = version;
+            gl_context.extensions = extensions;
+            Ok(gl_context)
+        }
+    }
+
+    pub(crate) fn renderer(&self) -> &str {
+        &self.renderer
+    }
+
+    pub(crate) fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub(crate) fn supports_srgb_surfaces(&self) -> bool {
+        self.supports_srgb_surfaces
+    }
+
+    pub(crate) fn has_extension(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.extensions.iter().any(|x| x == name)
+    }
+
+    /// Makes the context current on this thread, blocking if it is current on another thread
+    pub(crate) fn lock(&self) -> RafxResult<GlContextGuard<'_>> {
+        let thread_id = std::thread::current().id();
+        let mut lock_state = self.lock_state.lock().unwrap();
+        loop {
+            match lock_state.owner {
+                None => {
+                    let result = unsafe {
+                        egl::eglMakeCurrent(
+                            self.display,
+                            self.default_surface,
+                            self.default_surface,
+                            self.context,
+                        )
+                    };
+                    if result == egl::FALSE {
+                        return Err(format!(
+                            "eglMakeCurrent failed: {}",
+                            egl_error_string(unsafe { egl::eglGetError() })
+                        ))?;
+                    }
+
+                    lock_state.owner = Some(thread_id);
+                    lock_state.depth = 1;
+                    break;
+                }
+                Some(owner) if owner == thread_id => {
+                    lock_state.depth += 1;
+                    break;
+                }
+                Some(_) => {
+                    lock_state = self.lock_released.wait(lock_state).unwrap();
+                }
+            }
+        }
+
+        Ok(GlContextGuard {
+            context: self,
+            _not_send: PhantomData,
+        })
+    }
+
+    fn unlock(&self) {
+        let mut lock_state = self.lock_state.lock().unwrap();
+        lock_state.depth -= 1;
+        if lock_state.depth == 0 {
+            unsafe {
+                egl::eglMakeCurrent(
+                    self.display,
+                    egl::NO_SURFACE,
+                    egl::NO_SURFACE,
+                    egl::NO_CONTEXT,
+                );
+            }
+
+            lock_state.owner = None;
+            self.lock_released.notify_one();
+        }
+    }
+
+    pub(crate) fn create_window_surface(
+        &self,
+        raw_window_handle: RawWindowHandle,
+    ) -> RafxResult<egl::EGLSurface> {
+        let native_window = native_window(raw_window_handle)?;
+
+        // Swapchain images are sRGB, so the surface needs to be too or the blit at present would
+        // store linear values
+        let mut attributes = vec![];
+        if self.supports_srgb_surfaces {
+            attributes.push(egl::GL_COLORSPACE_KHR);
+            attributes.push(egl::GL_COLORSPACE_SRGB_KHR);
+        } else {
+            log::warn!("EGL_KHR_gl_colorspace is not supported, presented images will not be gamma-correct");
+        }
+        attributes.push(egl::NONE);
+
+        let surface = unsafe {
+            egl::eglCreateWindowSurface(
+                self.display,
+                self.config,
+                native_window,
+                attributes.as_ptr(),
+            )
+        };
+
+        if surface == egl::NO_SURFACE {
+            return Err(format!(
+                "eglCreateWindowSurface failed: {}",
+                egl_error_string(unsafe { egl::eglGetError() })
+            ))?;
+        }
+
+        Ok(surface)
+    }
+
+    pub(crate) fn destroy_window_surface(
+        &self,
+        surface: egl::EGLSurface,
+    ) {
+        unsafe {
+            egl::eglDestroySurface(self.display, surface);
+        }
+    }
+
+    pub(crate) fn set_swap_interval(
+        &self,
+        _guard: &GlContextGuard,
+        interval: i32,
+    ) {
+        unsafe {
+            egl::eglSwapInterval(self.display, interval);
+        }
+    }
+}
+
+// Loads libEGL and libGLESv2. This only does work the first time it's called
+fn load_libraries() -> RafxResult<()> {
+    egl::EglFunctions::load()?;
+    gl::Gles3Functions::load()?;
+    Ok(())
+}
+
+fn choose_config(
+    display: egl::EGLDisplay,
+    surface_type: egl::EGLint,
+) -> Option<egl::EGLConfig> {
+    let attributes = [
+        egl::RENDERABLE_TYPE,
+        egl::OPENGL_ES3_BIT,
+        egl::SURFACE_TYPE,
+        surface_type,
+        egl::RED_SIZE,
+        8,
+        egl::GREEN_SIZE,
+        8,
+        egl::BLUE_SIZE,
+        8,
+        egl::ALPHA_SIZE,
+        8,
+        egl::NONE,
+    ];
+
+    let mut config = egl::NO_CONFIG;
+    let mut config_count = 0;
+    let result = unsafe {
+        egl::eglChooseConfig(
+            display,
+            attributes.as_ptr(),
+            &mut config,
+            1,
+            &mut config_count,
+        )
+    };
+
+    if result == egl::FALSE || config_count == 0 {
+        None
+    } else {
+        Some(config)
+    }
+}
+
+// The EGL native display for the windowing system the window belongs to
+fn native_display(raw_window_handle: RawWindowHandle) -> RafxResult<egl::EGLNativeDisplayType> {
+    match raw_window_handle {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        RawWindowHandle::Xlib(handle) => Ok(handle.display),
+        #[cfg(target_os = "android")]
+        RawWindowHandle::Android(_) => Ok(egl::DEFAULT_DISPLAY),
+        #[cfg(target_os = "windows")]
+        RawWindowHandle::Windows(_) => Ok(egl::DEFAULT_DISPLAY),
+        _ => Err(format!(
+            "The GL ES backend does not support presenting to {:?}",
+            raw_window_handle
+        ))?,
+    }
+}
+
+fn native_window(raw_window_handle: RawWindowHandle) -> RafxResult<egl::EGLNativeWindowType> {
+    match raw_window_handle {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        RawWindowHandle::Xlib(handle) => Ok(handle.window as egl::EGLNativeWindowType),
+        #[cfg(target_os = "android")]
+        RawWindowHandle::Android(handle) => Ok(handle.a_native_window),
+        #[cfg(target_os = "windows")]
+        RawWindowHandle::Windows(handle) => Ok(handle.hwnd),
+        _ => Err(format!(
+            "The GL ES backend does not support presenting to {:?}",
+            raw_window_handle
+        ))?,
+    }
+}
+
+unsafe fn query_string(
+    display: egl::EGLDisplay,
+    name: egl::EGLint,
+) -> String {
+    let s = egl::eglQueryString(display, name);
+    if s.is_null() {
+        // Clear the error, querying client extensions fails if EGL_EXT_client_extensions is not
+        // supported
+        egl::eglGetError();
+        String::default()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+unsafe fn gl_string(s: *const u8) -> String {
+    if s.is_null() {
+        String::default()
+    } else {
+        CStr::from_ptr(s as *const c_char)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+pub(crate) fn egl_error_string(error: egl::EGLint) -> String {
+    match error {
+        egl::SUCCESS => "EGL_SUCCESS".to_string(),
+        egl::BAD_SURFACE => "EGL_BAD_SURFACE".to_string(),
+        egl::CONTEXT_LOST => "EGL_CONTEXT_LOST".to_string(),
+        _ => format!("EGL error 0x{:X}", error),
+    }
+}
+
+/// Returns an error if a GL call made since the last check failed. `operation` describes what was
+/// being done for the error message.
+pub(crate) fn check_gl_error(operation: &str) -> RafxResult<()> {
+    let error = unsafe { gl::glGetError() };
+    if error == gl::NO_ERROR {
+        return Ok(());
+    }
+
+    let error_name = match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        _ => "unknown GL error",
+    };
+
+    // Only the first error is reported, drain the rest so they aren't reported by a later check
+    while unsafe { gl::glGetError() } != gl::NO_ERROR {}
+
+    Err(format!(
+        "{} failed with {} (0x{:X})",
+        operation, error_name, error
+    ))?
+}
+
+// Source strings passed to GL must be nul-terminated
+pub(crate) fn to_cstring(s: &str) -> RafxResult<CString> {
+    CString::new(s).map_err(|_| format!("String {:?} contains a nul byte", s).into())
+}
+
+pub(crate) fn null_offset(offset: u64) -> *const c_void {
+    offset as usize as *const c_void
+}
@@ -0,0 +1,827 @@
+// Hand-written bindings for the subset of OpenGL ES 3.0 used by the backend. The functions are
+// looked up in libGLESv2 when the device is created. It exports the ES 3.0 entry points on mesa,
+// ANGLE and android (API 18+), so eglGetProcAddress is not required.
+#![allow(
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    dead_code
+)]
+
+use std::os::raw::{c_char, c_void};
+
+pub type GLenum = u32;
+pub type GLboolean = u8;
+pub type GLbitfield = u32;
+pub type GLint = i32;
+pub type GLuint = u32;
+pub type GLsizei = i32;
+pub type GLfloat = f32;
+pub type GLintptr = isize;
+pub type GLsizeiptr = isize;
+pub type GLuint64 = u64;
+pub type GLint64 = i64;
+pub type GLchar = c_char;
+pub type GLsync = *const c_void;
+
+pub const NONE: GLuint = 0;
+pub const FALSE: GLboolean = 0;
+pub const TRUE: GLboolean = 1;
+
+// Errors
+pub const NO_ERROR: GLenum = 0;
+pub const INVALID_ENUM: GLenum = 0x0500;
+pub const INVALID_VALUE: GLenum = 0x0501;
+pub const INVALID_OPERATION: GLenum = 0x0502;
+pub const OUT_OF_MEMORY: GLenum = 0x0505;
+pub const INVALID_FRAMEBUFFER_OPERATION: GLenum = 0x0506;
+
+// Strings
+pub const VENDOR: GLenum = 0x1F00;
+pub const RENDERER: GLenum = 0x1F01;
+pub const VERSION: GLenum = 0x1F02;
+pub const EXTENSIONS: GLenum = 0x1F03;
+pub const SHADING_LANGUAGE_VERSION: GLenum = 0x8B8C;
+pub const NUM_EXTENSIONS: GLenum = 0x821D;
+
+// Limits
+pub const MAX_TEXTURE_SIZE: GLenum = 0x0D33;
+pub const MAX_3D_TEXTURE_SIZE: GLenum = 0x8073;
+pub const MAX_CUBE_MAP_TEXTURE_SIZE: GLenum = 0x851C;
+pub const MAX_ARRAY_TEXTURE_LAYERS: GLenum = 0x88FF;
+pub const MAX_VERTEX_ATTRIBS: GLenum = 0x8869;
+pub const MAX_DRAW_BUFFERS: GLenum = 0x8824;
+pub const MAX_COLOR_ATTACHMENTS: GLenum = 0x8CDF;
+pub const MAX_SAMPLES: GLenum = 0x8D57;
+pub const MAX_COMBINED_TEXTURE_IMAGE_UNITS: GLenum = 0x8B4D;
+pub const MAX_UNIFORM_BUFFER_BINDINGS: GLenum = 0x8A2F;
+pub const UNIFORM_BUFFER_OFFSET_ALIGNMENT: GLenum = 0x8A34;
+pub const ALIASED_LINE_WIDTH_RANGE: GLenum = 0x846E;
+pub const MAX_TEXTURE_MAX_ANISOTROPY_EXT: GLenum = 0x84FF;
+pub const TEXTURE_MAX_ANISOTROPY_EXT: GLenum = 0x84FE;
+pub const NUM_SAMPLE_COUNTS: GLenum = 0x9380;
+pub const SAMPLES: GLenum = 0x80A9;
+
+// Buffers
+pub const ARRAY_BUFFER: GLenum = 0x8892;
+pub const ELEMENT_ARRAY_BUFFER: GLenum = 0x8893;
+pub const COPY_READ_BUFFER: GLenum = 0x8F36;
+pub const COPY_WRITE_BUFFER: GLenum = 0x8F37;
+pub const PIXEL_PACK_BUFFER: GLenum = 0x88EB;
+pub const PIXEL_UNPACK_BUFFER: GLenum = 0x88EC;
+pub const UNIFORM_BUFFER: GLenum = 0x8A11;
+pub const STREAM_DRAW: GLenum = 0x88E0;
+pub const STREAM_READ: GLenum = 0x88E1;
+pub const STATIC_DRAW: GLenum = 0x88E4;
+pub const DYNAMIC_DRAW: GLenum = 0x88E8;
+pub const DYNAMIC_READ: GLenum = 0x88E9;
+pub const MAP_READ_BIT: GLbitfield = 0x0001;
+pub const MAP_WRITE_BIT: GLbitfield = 0x0002;
+pub const MAP_INVALIDATE_RANGE_BIT: GLbitfield = 0x0004;
+
+// Textures
+pub const TEXTURE_2D: GLenum = 0x0DE1;
+pub const TEXTURE_3D: GLenum = 0x806F;
+pub const TEXTURE_2D_ARRAY: GLenum = 0x8C1A;
+pub const TEXTURE_CUBE_MAP: GLenum = 0x8513;
+pub const TEXTURE_CUBE_MAP_POSITIVE_X: GLenum = 0x8515;
+pub const TEXTURE0: GLenum = 0x84C0;
+pub const TEXTURE_MIN_FILTER: GLenum = 0x2801;
+pub const TEXTURE_MAG_FILTER: GLenum = 0x2800;
+pub const TEXTURE_WRAP_S: GLenum = 0x2802;
+pub const TEXTURE_WRAP_T: GLenum = 0x2803;
+pub const TEXTURE_WRAP_R: GLenum = 0x8072;
+pub const TEXTURE_MIN_LOD: GLenum = 0x813A;
+pub const TEXTURE_MAX_LOD: GLenum = 0x813B;
+pub const TEXTURE_BASE_LEVEL: GLenum = 0x813C;
+pub const TEXTURE_MAX_LEVEL: GLenum = 0x813D;
+pub const TEXTURE_COMPARE_MODE: GLenum = 0x884C;
+pub const TEXTURE_COMPARE_FUNC: GLenum = 0x884D;
+pub const COMPARE_REF_TO_TEXTURE: GLenum = 0x884E;
+pub const TEXTURE_SWIZZLE_R: GLenum = 0x8E42;
+pub const TEXTURE_SWIZZLE_B: GLenum = 0x8E44;
+pub const NEAREST: GLenum = 0x2600;
+pub const LINEAR: GLenum = 0x2601;
+pub const NEAREST_MIPMAP_NEAREST: GLenum = 0x2700;
+pub const LINEAR_MIPMAP_NEAREST: GLenum = 0x2701;
+pub const NEAREST_MIPMAP_LINEAR: GLenum = 0x2702;
+pub const LINEAR_MIPMAP_LINEAR: GLenum = 0x2703;
+pub const REPEAT: GLenum = 0x2901;
+pub const CLAMP_TO_EDGE: GLenum = 0x812F;
+pub const MIRRORED_REPEAT: GLenum = 0x8370;
+// GL ES 3.2 or EXT_texture_border_clamp
+pub const CLAMP_TO_BORDER: GLenum = 0x812D;
+pub const UNPACK_ALIGNMENT: GLenum = 0x0CF5;
+pub const PACK_ALIGNMENT: GLenum = 0x0D05;
+pub const UNPACK_ROW_LENGTH: GLenum = 0x0CF2;
+pub const UNPACK_IMAGE_HEIGHT: GLenum = 0x806E;
+pub const PACK_ROW_LENGTH: GLenum = 0x0D02;
+
+// Pixel formats
+pub const RED: GLenum = 0x1903;
+pub const RG: GLenum = 0x8227;
+pub const RGB: GLenum = 0x1907;
+pub const RGBA: GLenum = 0x1908;
+pub const RED_INTEGER: GLenum = 0x8D94;
+pub const RG_INTEGER: GLenum = 0x8228;
+pub const RGB_INTEGER: GLenum = 0x8D98;
+pub const RGBA_INTEGER: GLenum = 0x8D99;
+pub const DEPTH_COMPONENT: GLenum = 0x1902;
+pub const DEPTH_STENCIL: GLenum = 0x84F9;
+pub const STENCIL_INDEX8: GLenum = 0x8D48;
+
+// Pixel types
+pub const BYTE: GLenum = 0x1400;
+pub const UNSIGNED_BYTE: GLenum = 0x1401;
+pub const SHORT: GLenum = 0x1402;
+pub const UNSIGNED_SHORT: GLenum = 0x1403;
+pub const INT: GLenum = 0x1404;
+pub const UNSIGNED_INT: GLenum = 0x1405;
+pub const FLOAT: GLenum = 0x1406;
+pub const HALF_FLOAT: GLenum = 0x140B;
+pub const UNSIGNED_SHORT_4_4_4_4: GLenum = 0x8033;
+pub const UNSIGNED_SHORT_5_5_5_1: GLenum = 0x8034;
+pub const UNSIGNED_SHORT_5_6_5: GLenum = 0x8363;
+pub const UNSIGNED_INT_2_10_10_10_REV: GLenum = 0x8368;
+pub const UNSIGNED_INT_10F_11F_11F_REV: GLenum = 0x8C3B;
+pub const UNSIGNED_INT_5_9_9_9_REV: GLenum = 0x8C3E;
+pub const UNSIGNED_INT_24_8: GLenum = 0x84FA;
+pub const FLOAT_32_UNSIGNED_INT_24_8_REV: GLenum = 0x8DAD;
+pub const INT_2_10_10_10_REV: GLenum = 0x8D9F;
+
+// Sized internal formats
+pub const R8: GLenum = 0x8229;
+pub const R8_SNORM: GLenum = 0x8F94;
+pub const R8UI: GLenum = 0x8232;
+pub const R8I: GLenum = 0x8231;
+pub const RG8: GLenum = 0x822B;
+pub const RG8_SNORM: GLenum = 0x8F95;
+pub const RG8UI: GLenum = 0x8238;
+pub const RG8I: GLenum = 0x8237;
+pub const RGB8: GLenum = 0x8051;
+pub const RGB8_SNORM: GLenum = 0x8F96;
+pub const RGB8UI: GLenum = 0x8D7D;
+pub const RGB8I: GLenum = 0x8D8F;
+pub const SRGB8: GLenum = 0x8C41;
+pub const RGBA8: GLenum = 0x8058;
+pub const RGBA8_SNORM: GLenum = 0x8F97;
+pub const RGBA8UI: GLenum = 0x8D7C;
+pub const RGBA8I: GLenum = 0x8D8E;
+pub const SRGB8_ALPHA8: GLenum = 0x8C43;
+pub const RGBA4: GLenum = 0x8056;
+pub const RGB5_A1: GLenum = 0x8057;
+pub const RGB565: GLenum = 0x8D62;
+pub const RGB10_A2: GLenum = 0x8059;
+pub const RGB10_A2UI: GLenum = 0x906F;
+pub const R16UI: GLenum = 0x8234;
+pub const R16I: GLenum = 0x8233;
+pub const R16F: GLenum = 0x822D;
+pub const RG16UI: GLenum = 0x823A;
+pub const RG16I: GLenum = 0x8239;
+pub const RG16F: GLenum = 0x822F;
+pub const RGB16UI: GLenum = 0x8D77;
+pub const RGB16I: GLenum = 0x8D89;
+pub const RGB16F: GLenum = 0x881B;
+pub const RGBA16UI: GLenum = 0x8D76;
+pub const RGBA16I: GLenum = 0x8D88;
+pub const RGBA16F: GLenum = 0x881A;
+pub const R32UI: GLenum = 0x8236;
+pub const R32I: GLenum = 0x8235;
+pub const R32F: GLenum = 0x822E;
+pub const RG32UI: GLenum = 0x823C;
+pub const RG32I: GLenum = 0x823B;
+pub const RG32F: GLenum = 0x8230;
+pub const RGB32UI: GLenum = 0x8D71;
+pub const RGB32I: GLenum = 0x8D83;
+pub const RGB32F: GLenum = 0x8815;
+pub const RGBA32UI: GLenum = 0x8D70;
+pub const RGBA32I: GLenum = 0x8D82;
+pub const RGBA32F: GLenum = 0x8814;
+pub const R11F_G11F_B10F: GLenum = 0x8C3A;
+pub const RGB9_E5: GLenum = 0x8C3D;
+pub const DEPTH_COMPONENT16: GLenum = 0x81A5;
+pub const DEPTH_COMPONENT24: GLenum = 0x81A6;
+pub const DEPTH_COMPONENT32F: GLenum = 0x8CAC;
+pub const DEPTH24_STENCIL8: GLenum = 0x88F0;
+pub const DEPTH32F_STENCIL8: GLenum = 0x8CAD;
+
+// Compressed internal formats. ETC2/EAC are core, ASTC and BC (S3TC/RGTC/BPTC) are extensions
+pub const COMPRESSED_R11_EAC: GLenum = 0x9270;
+pub const COMPRESSED_SIGNED_R11_EAC: GLenum = 0x9271;
+pub const COMPRESSED_RG11_EAC: GLenum = 0x9272;
+pub const COMPRESSED_SIGNED_RG11_EAC: GLenum = 0x9273;
+pub const COMPRESSED_RGB8_ETC2: GLenum = 0x9274;
+pub const COMPRESSED_SRGB8_ETC2: GLenum = 0x9275;
+pub const COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2: GLenum = 0x9276;
+pub const COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2: GLenum = 0x9277;
+pub const COMPRESSED_RGBA8_ETC2_EAC: GLenum = 0x9278;
+pub const COMPRESSED_SRGB8_ALPHA8_ETC2_EAC: GLenum = 0x9279;
+pub const COMPRESSED_RGBA_ASTC_4x4_KHR: GLenum = 0x93B0;
+pub const COMPRESSED_SRGB8_ALPHA8_ASTC_4x4_KHR: GLenum = 0x93D0;
+pub const COMPRESSED_RGB_S3TC_DXT1_EXT: GLenum = 0x83F0;
+pub const COMPRESSED_RGBA_S3TC_DXT1_EXT: GLenum = 0x83F1;
+pub const COMPRESSED_RGBA_S3TC_DXT3_EXT: GLenum = 0x83F2;
+pub const COMPRESSED_RGBA_S3TC_DXT5_EXT: GLenum = 0x83F3;
+pub const COMPRESSED_SRGB_S3TC_DXT1_EXT: GLenum = 0x8C4C;
+pub const COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT: GLenum = 0x8C4D;
+pub const COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT: GLenum = 0x8C4E;
+pub const COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT: GLenum = 0x8C4F;
+pub const COMPRESSED_RED_RGTC1_EXT: GLenum = 0x8DBB;
+pub const COMPRESSED_SIGNED_RED_RGTC1_EXT: GLenum = 0x8DBC;
+pub const COMPRESSED_RED_GREEN_RGTC2_EXT: GLenum = 0x8DBD;
+pub const COMPRESSED_SIGNED_RED_GREEN_RGTC2_EXT: GLenum = 0x8DBE;
+pub const COMPRESSED_RGBA_BPTC_UNORM_EXT: GLenum = 0x8E8C;
+pub const COMPRESSED_SRGB_ALPHA_BPTC_UNORM_EXT: GLenum = 0x8E8D;
+pub const COMPRESSED_RGB_BPTC_SIGNED_FLOAT_EXT: GLenum = 0x8E8E;
+pub const COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT_EXT: GLenum = 0x8E8F;
+
+// Framebuffers
+pub const FRAMEBUFFER: GLenum = 0x8D40;
+pub const READ_FRAMEBUFFER: GLenum = 0x8CA8;
+pub const DRAW_FRAMEBUFFER: GLenum = 0x8CA9;
+pub const RENDERBUFFER: GLenum = 0x8D41;
+pub const COLOR_ATTACHMENT0: GLenum = 0x8CE0;
+pub const DEPTH_ATTACHMENT: GLenum = 0x8D00;
+pub const STENCIL_ATTACHMENT: GLenum = 0x8D20;
+pub const DEPTH_STENCIL_ATTACHMENT: GLenum = 0x821A;
+pub const FRAMEBUFFER_COMPLETE: GLenum = 0x8CD5;
+pub const COLOR: GLenum = 0x1800;
+pub const DEPTH: GLenum = 0x1801;
+pub const STENCIL: GLenum = 0x1802;
+pub const BACK: GLenum = 0x0405;
+pub const COLOR_BUFFER_BIT: GLbitfield = 0x4000;
+pub const DEPTH_BUFFER_BIT: GLbitfield = 0x0100;
+pub const STENCIL_BUFFER_BIT: GLbitfield = 0x0400;
+
+// Shaders
+pub const VERTEX_SHADER: GLenum = 0x8B31;
+pub const FRAGMENT_SHADER: GLenum = 0x8B30;
+pub const COMPILE_STATUS: GLenum = 0x8B81;
+pub const LINK_STATUS: GLenum = 0x8B82;
+pub const INFO_LOG_LENGTH: GLenum = 0x8B84;
+pub const INVALID_INDEX: GLuint = 0xFFFF_FFFF;
+
+// Fixed function state
+pub const BLEND: GLenum = 0x0BE2;
+pub const CULL_FACE: GLenum = 0x0B44;
+pub const DEPTH_TEST: GLenum = 0x0B71;
+pub const STENCIL_TEST: GLenum = 0x0B90;
+pub const SCISSOR_TEST: GLenum = 0x0C11;
+pub const POLYGON_OFFSET_FILL: GLenum = 0x8037;
+pub const FRAMEBUFFER_SRGB_EXT: GLenum = 0x8DB9;
+pub const FRONT: GLenum = 0x0404;
+pub const FRONT_AND_BACK: GLenum = 0x0408;
+pub const CW: GLenum = 0x0900;
+pub const CCW: GLenum = 0x0901;
+
+pub const NEVER: GLenum = 0x0200;
+pub const LESS: GLenum = 0x0201;
+pub const EQUAL: GLenum = 0x0202;
+pub const LEQUAL: GLenum = 0x0203;
+pub const GREATER: GLenum = 0x0204;
+pub const NOTEQUAL: GLenum = 0x0205;
+pub const GEQUAL: GLenum = 0x0206;
+pub const ALWAYS: GLenum = 0x0207;
+
+pub const ZERO: GLenum = 0;
+pub const ONE: GLenum = 1;
+pub const KEEP: GLenum = 0x1E00;
+pub const REPLACE: GLenum = 0x1E01;
+pub const INCR: GLenum = 0x1E02;
+pub const DECR: GLenum = 0x1E03;
+pub const INVERT: GLenum = 0x150A;
+pub const INCR_WRAP: GLenum = 0x8507;
+pub const DECR_WRAP: GLenum = 0x8508;
+
+pub const SRC_COLOR: GLenum = 0x0300;
+pub const ONE_MINUS_SRC_COLOR: GLenum = 0x0301;
+pub const SRC_ALPHA: GLenum = 0x0302;
+pub const ONE_MINUS_SRC_ALPHA: GLenum = 0x0303;
+pub const DST_ALPHA: GLenum = 0x0304;
+pub const ONE_MINUS_DST_ALPHA: GLenum = 0x0305;
+pub const DST_COLOR: GLenum = 0x0306;
+pub const ONE_MINUS_DST_COLOR: GLenum = 0x0307;
+pub const SRC_ALPHA_SATURATE: GLenum = 0x0308;
+pub const CONSTANT_COLOR: GLenum = 0x8001;
+pub const ONE_MINUS_CONSTANT_COLOR: GLenum = 0x8002;
+pub const FUNC_ADD: GLenum = 0x8006;
+pub const MIN: GLenum = 0x8007;
+pub const MAX: GLenum = 0x8008;
+pub const FUNC_SUBTRACT: GLenum = 0x800A;
+pub const FUNC_REVERSE_SUBTRACT: GLenum = 0x800B;
+
+// Primitives
+pub const POINTS: GLenum = 0x0000;
+pub const LINES: GLenum = 0x0001;
+pub const LINE_STRIP: GLenum = 0x0003;
+pub const TRIANGLES: GLenum = 0x0004;
+pub const TRIANGLE_STRIP: GLenum = 0x0005;
+
+// Sync
+pub const SYNC_GPU_COMMANDS_COMPLETE: GLenum = 0x9117;
+pub const SYNC_FLUSH_COMMANDS_BIT: GLbitfield = 0x0000_0001;
+pub const ALREADY_SIGNALED: GLenum = 0x911A;
+pub const TIMEOUT_EXPIRED: GLenum = 0x911B;
+pub const CONDITION_SATISFIED: GLenum = 0x911C;
+pub const WAIT_FAILED: GLenum = 0x911D;
+pub const SYNC_STATUS: GLenum = 0x9114;
+pub const SIGNALED: GLenum = 0x9119;
+pub const TIMEOUT_IGNORED: GLuint64 = 0xFFFF_FFFF_FFFF_FFFF;
+
+#[cfg(target_os = "windows")]
+const GLES3_LIBRARY_NAMES: &[&str] = &["libGLESv2.dll"];
+#[cfg(target_os = "macos")]
+const GLES3_LIBRARY_NAMES: &[&str] = &["libGLESv2.dylib"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const GLES3_LIBRARY_NAMES: &[&str] = &["libGLESv2.so.2", "libGLESv2.so"];
+
+dynamic_library_functions! {
+    Gles3Functions, GLES3_FUNCTIONS, GLES3_LIBRARY_NAMES;
+
+    pub fn glGetError() -> GLenum;
+    pub fn glGetString(name: GLenum) -> *const u8;
+    pub fn glGetStringi(
+        name: GLenum,
+        index: GLuint,
+    ) -> *const u8;
+    pub fn glGetIntegerv(
+        pname: GLenum,
+        data: *mut GLint,
+    );
+    pub fn glGetFloatv(
+        pname: GLenum,
+        data: *mut GLfloat,
+    );
+    pub fn glGetInternalformativ(
+        target: GLenum,
+        internalformat: GLenum,
+        pname: GLenum,
+        buf_size: GLsizei,
+        params: *mut GLint,
+    );
+    pub fn glFlush();
+    pub fn glFinish();
+
+    // Buffers
+    pub fn glGenBuffers(
+        n: GLsizei,
+        buffers: *mut GLuint,
+    );
+    pub fn glDeleteBuffers(
+        n: GLsizei,
+        buffers: *const GLuint,
+    );
+    pub fn glBindBuffer(
+        target: GLenum,
+        buffer: GLuint,
+    );
+    pub fn glBindBufferRange(
+        target: GLenum,
+        index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        size: GLsizeiptr,
+    );
+    pub fn glBufferData(
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const c_void,
+        usage: GLenum,
+    );
+    pub fn glBufferSubData(
+        target: GLenum,
+        offset: GLintptr,
+        size: GLsizeiptr,
+        data: *const c_void,
+    );
+    pub fn glCopyBufferSubData(
+        read_target: GLenum,
+        write_target: GLenum,
+        read_offset: GLintptr,
+        write_offset: GLintptr,
+        size: GLsizeiptr,
+    );
+    pub fn glMapBufferRange(
+        target: GLenum,
+        offset: GLintptr,
+        length: GLsizeiptr,
+        access: GLbitfield,
+    ) -> *mut c_void;
+    pub fn glUnmapBuffer(target: GLenum) -> GLboolean;
+
+    // Textures
+    pub fn glGenTextures(
+        n: GLsizei,
+        textures: *mut GLuint,
+    );
+    pub fn glDeleteTextures(
+        n: GLsizei,
+        textures: *const GLuint,
+    );
+    pub fn glBindTexture(
+        target: GLenum,
+        texture: GLuint,
+    );
+    pub fn glActiveTexture(texture: GLenum);
+    pub fn glTexParameteri(
+        target: GLenum,
+        pname: GLenum,
+        param: GLint,
+    );
+    pub fn glTexStorage2D(
+        target: GLenum,
+        levels: GLsizei,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    );
+    pub fn glTexStorage3D(
+        target: GLenum,
+        levels: GLsizei,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+    );
+    pub fn glTexSubImage2D(
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const c_void,
+    );
+    pub fn glTexSubImage3D(
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const c_void,
+    );
+    pub fn glCompressedTexSubImage2D(
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        image_size: GLsizei,
+        data: *const c_void,
+    );
+    pub fn glCompressedTexSubImage3D(
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        image_size: GLsizei,
+        data: *const c_void,
+    );
+    pub fn glPixelStorei(
+        pname: GLenum,
+        param: GLint,
+    );
+    pub fn glReadPixels(
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *mut c_void,
+    );
+
+    // Samplers
+    pub fn glGenSamplers(
+        count: GLsizei,
+        samplers: *mut GLuint,
+    );
+    pub fn glDeleteSamplers(
+        count: GLsizei,
+        samplers: *const GLuint,
+    );
+    pub fn glBindSampler(
+        unit: GLuint,
+        sampler: GLuint,
+    );
+    pub fn glSamplerParameteri(
+        sampler: GLuint,
+        pname: GLenum,
+        param: GLint,
+    );
+    pub fn glSamplerParameterf(
+        sampler: GLuint,
+        pname: GLenum,
+        param: GLfloat,
+    );
+
+    // Renderbuffers and framebuffers
+    pub fn glGenRenderbuffers(
+        n: GLsizei,
+        renderbuffers: *mut GLuint,
+    );
+    pub fn glDeleteRenderbuffers(
+        n: GLsizei,
+        renderbuffers: *const GLuint,
+    );
+    pub fn glBindRenderbuffer(
+        target: GLenum,
+        renderbuffer: GLuint,
+    );
+    pub fn glRenderbufferStorageMultisample(
+        target: GLenum,
+        samples: GLsizei,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    );
+    pub fn glGenFramebuffers(
+        n: GLsizei,
+        framebuffers: *mut GLuint,
+    );
+    pub fn glDeleteFramebuffers(
+        n: GLsizei,
+        framebuffers: *const GLuint,
+    );
+    pub fn glBindFramebuffer(
+        target: GLenum,
+        framebuffer: GLuint,
+    );
+    pub fn glFramebufferTexture2D(
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    );
+    pub fn glFramebufferTextureLayer(
+        target: GLenum,
+        attachment: GLenum,
+        texture: GLuint,
+        level: GLint,
+        layer: GLint,
+    );
+    pub fn glFramebufferRenderbuffer(
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    );
+    pub fn glCheckFramebufferStatus(target: GLenum) -> GLenum;
+    pub fn glDrawBuffers(
+        n: GLsizei,
+        bufs: *const GLenum,
+    );
+    pub fn glReadBuffer(src: GLenum);
+    pub fn glBlitFramebuffer(
+        src_x0: GLint,
+        src_y0: GLint,
+        src_x1: GLint,
+        src_y1: GLint,
+        dst_x0: GLint,
+        dst_y0: GLint,
+        dst_x1: GLint,
+        dst_y1: GLint,
+        mask: GLbitfield,
+        filter: GLenum,
+    );
+    pub fn glInvalidateFramebuffer(
+        target: GLenum,
+        num_attachments: GLsizei,
+        attachments: *const GLenum,
+    );
+    pub fn glClearBufferfv(
+        buffer: GLenum,
+        drawbuffer: GLint,
+        value: *const GLfloat,
+    );
+    pub fn glClearBufferiv(
+        buffer: GLenum,
+        drawbuffer: GLint,
+        value: *const GLint,
+    );
+    pub fn glClearBufferuiv(
+        buffer: GLenum,
+        drawbuffer: GLint,
+        value: *const GLuint,
+    );
+    pub fn glClearBufferfi(
+        buffer: GLenum,
+        drawbuffer: GLint,
+        depth: GLfloat,
+        stencil: GLint,
+    );
+
+    // Shaders and programs
+    pub fn glCreateShader(type_: GLenum) -> GLuint;
+    pub fn glDeleteShader(shader: GLuint);
+    pub fn glShaderSource(
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint,
+    );
+    pub fn glCompileShader(shader: GLuint);
+    pub fn glGetShaderiv(
+        shader: GLuint,
+        pname: GLenum,
+        params: *mut GLint,
+    );
+    pub fn glGetShaderInfoLog(
+        shader: GLuint,
+        buf_size: GLsizei,
+        length: *mut GLsizei,
+        info_log: *mut GLchar,
+    );
+    pub fn glCreateProgram() -> GLuint;
+    pub fn glDeleteProgram(program: GLuint);
+    pub fn glAttachShader(
+        program: GLuint,
+        shader: GLuint,
+    );
+    pub fn glLinkProgram(program: GLuint);
+    pub fn glGetProgramiv(
+        program: GLuint,
+        pname: GLenum,
+        params: *mut GLint,
+    );
+    pub fn glGetProgramInfoLog(
+        program: GLuint,
+        buf_size: GLsizei,
+        length: *mut GLsizei,
+        info_log: *mut GLchar,
+    );
+    pub fn glUseProgram(program: GLuint);
+    pub fn glGetUniformBlockIndex(
+        program: GLuint,
+        uniform_block_name: *const GLchar,
+    ) -> GLuint;
+    pub fn glUniformBlockBinding(
+        program: GLuint,
+        uniform_block_index: GLuint,
+        uniform_block_binding: GLuint,
+    );
+    pub fn glGetUniformLocation(
+        program: GLuint,
+        name: *const GLchar,
+    ) -> GLint;
+    pub fn glUniform1i(
+        location: GLint,
+        v0: GLint,
+    );
+
+    // Vertex arrays
+    pub fn glGenVertexArrays(
+        n: GLsizei,
+        arrays: *mut GLuint,
+    );
+    pub fn glDeleteVertexArrays(
+        n: GLsizei,
+        arrays: *const GLuint,
+    );
+    pub fn glBindVertexArray(array: GLuint);
+    pub fn glEnableVertexAttribArray(index: GLuint);
+    pub fn glDisableVertexAttribArray(index: GLuint);
+    pub fn glVertexAttribPointer(
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: GLboolean,
+        stride: GLsizei,
+        pointer: *const c_void,
+    );
+    pub fn glVertexAttribIPointer(
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        stride: GLsizei,
+        pointer: *const c_void,
+    );
+    pub fn glVertexAttribDivisor(
+        index: GLuint,
+        divisor: GLuint,
+    );
+
+    // Fixed function state
+    pub fn glEnable(cap: GLenum);
+    pub fn glDisable(cap: GLenum);
+    pub fn glViewport(
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    );
+    pub fn glDepthRangef(
+        n: GLfloat,
+        f: GLfloat,
+    );
+    pub fn glScissor(
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    );
+    pub fn glCullFace(mode: GLenum);
+    pub fn glFrontFace(mode: GLenum);
+    pub fn glPolygonOffset(
+        factor: GLfloat,
+        units: GLfloat,
+    );
+    pub fn glLineWidth(width: GLfloat);
+    pub fn glDepthFunc(func: GLenum);
+    pub fn glDepthMask(flag: GLboolean);
+    pub fn glStencilFuncSeparate(
+        face: GLenum,
+        func: GLenum,
+        ref_: GLint,
+        mask: GLuint,
+    );
+    pub fn glStencilOpSeparate(
+        face: GLenum,
+        sfail: GLenum,
+        dpfail: GLenum,
+        dppass: GLenum,
+    );
+    pub fn glStencilMask(mask: GLuint);
+    pub fn glBlendFuncSeparate(
+        sfactor_rgb: GLenum,
+        dfactor_rgb: GLenum,
+        sfactor_alpha: GLenum,
+        dfactor_alpha: GLenum,
+    );
+    pub fn glBlendEquationSeparate(
+        mode_rgb: GLenum,
+        mode_alpha: GLenum,
+    );
+    pub fn glBlendColor(
+        red: GLfloat,
+        green: GLfloat,
+        blue: GLfloat,
+        alpha: GLfloat,
+    );
+    pub fn glColorMask(
+        red: GLboolean,
+        green: GLboolean,
+        blue: GLboolean,
+        alpha: GLboolean,
+    );
+
+    // Draws
+    pub fn glDrawArrays(
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+    );
+    pub fn glDrawArraysInstanced(
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        instancecount: GLsizei,
+    );
+    pub fn glDrawElements(
+        mode: GLenum,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const c_void,
+    );
+    pub fn glDrawElementsInstanced(
+        mode: GLenum,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const c_void,
+        instancecount: GLsizei,
+    );
+
+    // Sync objects
+    pub fn glFenceSync(
+        condition: GLenum,
+        flags: GLbitfield,
+    ) -> GLsync;
+    pub fn glDeleteSync(sync: GLsync);
+    pub fn glClientWaitSync(
+        sync: GLsync,
+        flags: GLbitfield,
+        timeout: GLuint64,
+    ) -> GLenum;
+    pub fn glGetSynciv(
+        sync: GLsync,
+        pname: GLenum,
+        buf_size: GLsizei,
+        length: *mut GLsizei,
+        values: *mut GLint,
+    );
+}
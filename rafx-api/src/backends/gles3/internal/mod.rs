@@ -0,0 +1,7 @@
+#[macro_use]
+mod dynamic_library;
+
+pub mod conversions;
+pub mod egl_bindings;
+pub mod gl_context;
+pub mod gles3_bindings;
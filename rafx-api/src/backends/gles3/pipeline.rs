@@ -0,0 +1,515 @@
+use super::internal::conversions::{self, GlVertexFormat};
+use super::internal::gl_context::{check_gl_error, to_cstring};
+use super::internal::gles3_bindings as gl;
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{
+    RafxBlendStateRenderTarget, RafxColorFlags, RafxComputePipelineDef, RafxCullMode, RafxFillMode,
+    RafxGraphicsPipelineDef, RafxPipelineType, RafxResult, RafxRootSignature, RafxShaderStageDef,
+    RafxShaderStageFlags, RafxSpecializationConstant, RafxSpecializationConstantValue,
+    RafxVertexAttributeRate,
+};
+use std::sync::Arc;
+
+// Name of the uniform spirv_cross adds for gl_InstanceIndex, GL ES has no base instance so the
+// first instance of each draw is passed in through it
+const BASE_INSTANCE_UNIFORM_NAME: &str = "SPIRV_Cross_BaseInstance";
+
+// GL ES requires a fragment shader to link a program, this is used for depth-only pipelines
+const EMPTY_FRAGMENT_SHADER: &str = "#version 300 es\nvoid main() {}\n";
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct GlVertexAttribute {
+    pub(crate) location: u32,
+    pub(crate) buffer_index: u32,
+    pub(crate) byte_offset: u32,
+    pub(crate) format: GlVertexFormat,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct GlVertexBuffer {
+    pub(crate) stride: u32,
+    pub(crate) rate: RafxVertexAttributeRate,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct GlStencilFaceState {
+    pub(crate) func: gl::GLenum,
+    pub(crate) fail_op: gl::GLenum,
+    pub(crate) depth_fail_op: gl::GLenum,
+    pub(crate) pass_op: gl::GLenum,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GlDepthStencilState {
+    pub(crate) depth_test_enable: bool,
+    pub(crate) depth_write_enable: bool,
+    pub(crate) depth_func: gl::GLenum,
+    pub(crate) stencil_test_enable: bool,
+    pub(crate) stencil_read_mask: u32,
+    pub(crate) stencil_write_mask: u32,
+    pub(crate) front: GlStencilFaceState,
+    pub(crate) back: GlStencilFaceState,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GlRasterizerState {
+    // None if culling is disabled
+    pub(crate) cull_face: Option<gl::GLenum>,
+    pub(crate) front_face: gl::GLenum,
+    // Constant and slope-scaled bias. Set with cmd_set_depth_bias if dynamic_depth_bias is true
+    pub(crate) depth_bias: (f32, f32),
+    pub(crate) dynamic_depth_bias: bool,
+    pub(crate) scissor_enable: bool,
+}
+
+// GL ES 3.0 has a single blend state and color mask shared by all color attachments
+#[derive(Clone, Debug)]
+pub(crate) struct GlBlendState {
+    pub(crate) blend_enable: bool,
+    pub(crate) src_rgb: gl::GLenum,
+    pub(crate) dst_rgb: gl::GLenum,
+    pub(crate) src_alpha: gl::GLenum,
+    pub(crate) dst_alpha: gl::GLenum,
+    pub(crate) op_rgb: gl::GLenum,
+    pub(crate) op_alpha: gl::GLenum,
+    pub(crate) color_mask: [bool; 4],
+}
+
+#[derive(Debug)]
+pub(crate) struct GlGraphicsPipelineState {
+    pub(crate) primitive_mode: gl::GLenum,
+    pub(crate) vertex_attributes: Vec<GlVertexAttribute>,
+    pub(crate) vertex_buffers: Vec<GlVertexBuffer>,
+    pub(crate) rasterizer_state: GlRasterizerState,
+    pub(crate) depth_stencil_state: GlDepthStencilState,
+    pub(crate) blend_state: GlBlendState,
+    // Location of SPIRV_Cross_BaseInstance, if the vertex shader reads gl_InstanceIndex
+    pub(crate) base_instance_location: Option<gl::GLint>,
+}
+
+#[derive(Debug)]
+struct RafxPipelineGles3Inner {
+    device_context: RafxDeviceContextGles3,
+    pipeline_type: RafxPipelineType,
+    // It's a RafxRootSignatureGles3, but stored as RafxRootSignature so we can return refs to it
+    root_signature: RafxRootSignature,
+    program: gl::GLuint,
+    graphics_state: GlGraphicsPipelineState,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl Drop for RafxPipelineGles3Inner {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.device_context.gl_context().lock() {
+            unsafe {
+                gl::glDeleteProgram(self.program);
+            }
+        }
+    }
+}
+
+/// A linked GL program and the fixed-function state that is applied when it is bound. GL ES 3.0
+/// has no compute shaders, so only graphics pipelines can be created.
+#[derive(Clone, Debug)]
+pub struct RafxPipelineGles3 {
+    inner: Arc<RafxPipelineGles3Inner>,
+}
+
+impl RafxPipelineGles3 {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn pipeline_type(&self) -> RafxPipelineType {
+        self.inner.pipeline_type
+    }
+
+    pub fn root_signature(&self) -> &RafxRootSignature {
+        &self.inner.root_signature
+    }
+
+    pub fn gl_program(&self) -> gl::GLuint {
+        self.inner.program
+    }
+
+    pub(crate) fn graphics_state(&self) -> &GlGraphicsPipelineState {
+        &self.inner.graphics_state
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // GL ES 3.0 has no object labels without KHR_debug
+    }
+
+    pub fn new_graphics_pipeline(
+        device_context: &RafxDeviceContextGles3,
+        pipeline_def: &RafxGraphicsPipelineDef,
+    ) -> RafxResult<Self> {
+        pipeline_def
+            .blend_state
+            .verify(pipeline_def.color_formats.len());
+        pipeline_def.verify_device_limits(device_context.device_info())?;
+
+        if pipeline_def.root_signature.pipeline_type() != RafxPipelineType::Graphics {
+            return Err("Graphics pipeline created with a compute root signature")?;
+        }
+
+        let root_signature = pipeline_def
+            .root_signature
+            .gles3_root_signature()
+            .ok_or("The root signature must be created by the same backend")?;
+
+        let primitive_mode = conversions::gl_primitive_mode(pipeline_def.primitive_topology)
+            .ok_or("GL ES 3.0 does not support tessellation, patch lists can't be drawn")?;
+
+        let rasterizer_state = pipeline_def.rasterizer_state;
+        if rasterizer_state.fill_mode != RafxFillMode::Solid {
+            return Err("GL ES 3.0 does not support wireframe fill mode")?;
+        }
+
+        if rasterizer_state.depth_clamp_enable {
+            return Err("GL ES 3.0 does not support depth clamping")?;
+        }
+
+        let mut vertex_src = None;
+        let mut fragment_src = None;
+        for stage in pipeline_def.shader.gles3_shader().unwrap().stages() {
+            let shader_stage = stage.reflection.shader_stage;
+            if shader_stage.intersects(RafxShaderStageFlags::VERTEX) {
+                assert!(vertex_src.is_none());
+                vertex_src = Some(stage_src(stage)?);
+            } else if shader_stage.intersects(RafxShaderStageFlags::FRAGMENT) {
+                assert!(fragment_src.is_none());
+                fragment_src = Some(stage_src(stage)?);
+            } else {
+                return Err(format!(
+                    "Shader stage {:?} is not supported by the GL ES backend",
+                    shader_stage
+                ))?;
+            }
+        }
+
+        let vertex_src =
+            vertex_src.ok_or("Graphics pipeline shader does not have a vertex stage")?;
+        let fragment_src = fragment_src.unwrap_or_else(|| EMPTY_FRAGMENT_SHADER.to_string());
+
+        let mut vertex_attributes = Vec::with_capacity(pipeline_def.vertex_layout.attributes.len());
+        for attribute in &pipeline_def.vertex_layout.attributes {
+            let format = conversions::gl_vertex_format(attribute.format).ok_or_else(|| {
+                format!(
+                    "Vertex attribute format {:?} is not supported by the GL ES backend",
+                    attribute.format
+                )
+            })?;
+
+            vertex_attributes.push(GlVertexAttribute {
+                location: attribute.location,
+                buffer_index: attribute.buffer_index,
+                byte_offset: attribute.byte_offset,
+                format,
+            });
+        }
+
+        let vertex_buffers = pipeline_def
+            .vertex_layout
+            .buffers
+            .iter()
+            .map(|x| GlVertexBuffer {
+                stride: x.stride,
+                rate: x.rate,
+            })
+            .collect();
+
+        let blend_state = gl_blend_state(pipeline_def)?;
+
+        let depth_state = pipeline_def.depth_state;
+        let stencil_face_state = |func, fail_op, depth_fail_op, pass_op| GlStencilFaceState {
+            func: conversions::gl_compare_func(func),
+            fail_op: conversions::gl_stencil_op(fail_op),
+            depth_fail_op: conversions::gl_stencil_op(depth_fail_op),
+            pass_op: conversions::gl_stencil_op(pass_op),
+        };
+        let depth_stencil_state = GlDepthStencilState {
+            depth_test_enable: depth_state.depth_test_enable,
+            depth_write_enable: depth_state.depth_write_enable,
+            depth_func: conversions::gl_compare_func(depth_state.depth_compare_op),
+            stencil_test_enable: depth_state.stencil_test_enable,
+            stencil_read_mask: depth_state.stencil_read_mask as u32,
+            stencil_write_mask: depth_state.stencil_write_mask as u32,
+            front: stencil_face_state(
+                depth_state.front_stencil_compare_op,
+                depth_state.front_stencil_fail_op,
+                depth_state.front_depth_fail_op,
+                depth_state.front_stencil_pass_op,
+            ),
+            back: stencil_face_state(
+                depth_state.back_stencil_compare_op,
+                depth_state.back_stencil_fail_op,
+                depth_state.back_depth_fail_op,
+                depth_state.back_stencil_pass_op,
+            ),
+        };
+
+        let gl_rasterizer_state = GlRasterizerState {
+            cull_face: match rasterizer_state.cull_mode {
+                RafxCullMode::None => None,
+                RafxCullMode::Back => Some(gl::BACK),
+                RafxCullMode::Front => Some(gl::FRONT),
+            },
+            front_face: conversions::gl_front_face(rasterizer_state.front_face),
+            depth_bias: (
+                rasterizer_state.depth_bias as f32,
+                rasterizer_state.depth_bias_slope_scaled,
+            ),
+            dynamic_depth_bias: rasterizer_state.dynamic_depth_bias,
+            scissor_enable: rasterizer_state.scissor,
+        };
+
+        let _guard = device_context.gl_context().lock()?;
+        let program = link_program(&vertex_src, &fragment_src)?;
+
+        // GLSL ES 3.00 can't declare bindings, so uniform blocks and samplers are assigned the
+        // binding points and texture units chosen by the root signature. Resources that a program
+        // doesn't use are optimized out and can't be found.
+        let base_instance_location = unsafe {
+            for uniform_block in &root_signature.inner.uniform_block_bindings {
+                let name = to_cstring(&uniform_block.name)?;
+                let block_index = gl::glGetUniformBlockIndex(program, name.as_ptr());
+                if block_index != gl::INVALID_INDEX {
+                    gl::glUniformBlockBinding(program, block_index, uniform_block.gl_binding);
+                }
+            }
+
+            gl::glUseProgram(program);
+            for sampler_uniform in &root_signature.inner.sampler_uniform_bindings {
+                let name = to_cstring(&sampler_uniform.name)?;
+                let location = gl::glGetUniformLocation(program, name.as_ptr());
+                if location != -1 {
+                    gl::glUniform1i(location, sampler_uniform.gl_binding as gl::GLint);
+                }
+            }
+            gl::glUseProgram(0);
+
+            let name = to_cstring(BASE_INSTANCE_UNIFORM_NAME)?;
+            let location = gl::glGetUniformLocation(program, name.as_ptr());
+            if location != -1 {
+                Some(location)
+            } else {
+                None
+            }
+        };
+
+        if let Err(e) = check_gl_error("Creating pipeline") {
+            unsafe {
+                gl::glDeleteProgram(program);
+            }
+            return Err(e);
+        }
+
+        let inner = RafxPipelineGles3Inner {
+            device_context: device_context.clone(),
+            pipeline_type: RafxPipelineType::Graphics,
+            root_signature: pipeline_def.root_signature.clone(),
+            program,
+            graphics_state: GlGraphicsPipelineState {
+                primitive_mode,
+                vertex_attributes,
+                vertex_buffers,
+                rasterizer_state: gl_rasterizer_state,
+                depth_stencil_state,
+                blend_state,
+                base_instance_location,
+            },
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxPipelineGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn new_compute_pipeline(
+        _device_context: &RafxDeviceContextGles3,
+        _pipeline_def: &RafxComputePipelineDef,
+    ) -> RafxResult<Self> {
+        Err("GL ES 3.0 does not support compute shaders")?
+    }
+}
+
+// The GLSL ES source of a stage, with its specialization constants applied. spirv_cross declares
+// each specialization constant with a default that can be overridden by defining
+// SPIRV_CROSS_CONSTANT_ID_N before it.
+fn stage_src(stage: &RafxShaderStageDef) -> RafxResult<String> {
+    let src = stage
+        .shader_module
+        .gles3_shader_module()
+        .ok_or("Shader modules must be created by the same backend")?
+        .src();
+
+    if stage.specialization_constants.is_empty() {
+        return Ok(src.to_string());
+    }
+
+    // The defines must come after the #version directive, which has to be the first line
+    let version_end = if src.trim_start().starts_with("#version") {
+        src.find('\n').map(|x| x + 1).unwrap_or_else(|| src.len())
+    } else {
+        0
+    };
+
+    let mut specialized_src = String::with_capacity(src.len());
+    specialized_src.push_str(&src[..version_end]);
+    for constant in &stage.specialization_constants {
+        specialized_src.push_str(&specialization_constant_define(constant));
+    }
+    specialized_src.push_str(&src[version_end..]);
+    Ok(specialized_src)
+}
+
+fn specialization_constant_define(constant: &RafxSpecializationConstant) -> String {
+    let value = match constant.value {
+        RafxSpecializationConstantValue::Bool(value) => value.to_string(),
+        RafxSpecializationConstantValue::Int(value) => value.to_string(),
+        RafxSpecializationConstantValue::UInt(value) => format!("{}u", value),
+        // Debug formatting always includes a decimal point or exponent
+        RafxSpecializationConstantValue::Float(value) => format!("{:?}", value),
+    };
+
+    format!(
+        "#define SPIRV_CROSS_CONSTANT_ID_{} {}\n",
+        constant.constant_id, value
+    )
+}
+
+fn gl_blend_state(pipeline_def: &RafxGraphicsPipelineDef) -> RafxResult<GlBlendState> {
+    let blend_state = pipeline_def.blend_state;
+    let render_target_state = &blend_state.render_target_blend_states[0];
+    if blend_state.independent_blend
+        && blend_state.render_target_blend_states[1..]
+            .iter()
+            .any(|x| x != render_target_state)
+    {
+        Err("GL ES 3.0 does not support independent blend states for each render target")?;
+    }
+
+    Ok(gl_render_target_blend_state(render_target_state))
+}
+
+fn gl_render_target_blend_state(render_target_state: &RafxBlendStateRenderTarget) -> GlBlendState {
+    let masks = render_target_state.masks;
+    GlBlendState {
+        blend_enable: render_target_state.blend_enabled(),
+        src_rgb: conversions::gl_blend_factor(render_target_state.src_factor),
+        dst_rgb: conversions::gl_blend_factor(render_target_state.dst_factor),
+        src_alpha: conversions::gl_blend_factor(render_target_state.src_factor_alpha),
+        dst_alpha: conversions::gl_blend_factor(render_target_state.dst_factor_alpha),
+        op_rgb: conversions::gl_blend_op(render_target_state.blend_op),
+        op_alpha: conversions::gl_blend_op(render_target_state.blend_op_alpha),
+        color_mask: [
+            masks.contains(RafxColorFlags::RED),
+            masks.contains(RafxColorFlags::GREEN),
+            masks.contains(RafxColorFlags::BLUE),
+            masks.contains(RafxColorFlags::ALPHA),
+        ],
+    }
+}
+
+// Must be called with the GL context locked
+fn link_program(
+    vertex_src: &str,
+    fragment_src: &str,
+) -> RafxResult<gl::GLuint> {
+    let vertex_shader = compile_shader(gl::VERTEX_SHADER, vertex_src)?;
+    let fragment_shader = match compile_shader(gl::FRAGMENT_SHADER, fragment_src) {
+        Ok(fragment_shader) => fragment_shader,
+        Err(e) => {
+            unsafe {
+                gl::glDeleteShader(vertex_shader);
+            }
+            return Err(e);
+        }
+    };
+
+    unsafe {
+        let program = gl::glCreateProgram();
+        gl::glAttachShader(program, vertex_shader);
+        gl::glAttachShader(program, fragment_shader);
+        gl::glLinkProgram(program);
+
+        // The program keeps the compiled code, the shaders are freed when it is
+        gl::glDeleteShader(vertex_shader);
+        gl::glDeleteShader(fragment_shader);
+
+        let mut link_status = 0;
+        gl::glGetProgramiv(program, gl::LINK_STATUS, &mut link_status);
+        if link_status == gl::FALSE as gl::GLint {
+            let mut log_length = 0;
+            gl::glGetProgramiv(program, gl::INFO_LOG_LENGTH, &mut log_length);
+            let mut log = vec![0u8; log_length.max(1) as usize];
+            gl::glGetProgramInfoLog(
+                program,
+                log.len() as _,
+                std::ptr::null_mut(),
+                log.as_mut_ptr() as *mut gl::GLchar,
+            );
+            gl::glDeleteProgram(program);
+            Err(format!(
+                "Failed to link GLSL ES program:\n{}",
+                info_log_to_string(&log)
+            ))?;
+        }
+
+        Ok(program)
+    }
+}
+
+fn compile_shader(
+    shader_type: gl::GLenum,
+    src: &str,
+) -> RafxResult<gl::GLuint> {
+    let src = to_cstring(src)?;
+    unsafe {
+        let shader = gl::glCreateShader(shader_type);
+        let src_ptr = src.as_ptr();
+        gl::glShaderSource(shader, 1, &src_ptr, std::ptr::null());
+        gl::glCompileShader(shader);
+
+        let mut compile_status = 0;
+        gl::glGetShaderiv(shader, gl::COMPILE_STATUS, &mut compile_status);
+        if compile_status == gl::FALSE as gl::GLint {
+            let mut log_length = 0;
+            gl::glGetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut log_length);
+            let mut log = vec![0u8; log_length.max(1) as usize];
+            gl::glGetShaderInfoLog(
+                shader,
+                log.len() as _,
+                std::ptr::null_mut(),
+                log.as_mut_ptr() as *mut gl::GLchar,
+            );
+            gl::glDeleteShader(shader);
+
+            let stage_name = if shader_type == gl::VERTEX_SHADER {
+                "vertex"
+            } else {
+                "fragment"
+            };
+            Err(format!(
+                "Failed to compile GLSL ES {} shader:\n{}",
+                stage_name,
+                info_log_to_string(&log)
+            ))?;
+        }
+
+        Ok(shader)
+    }
+}
+
+fn info_log_to_string(log: &[u8]) -> String {
+    let len = log.iter().position(|x| *x == 0).unwrap_or(log.len());
+    String::from_utf8_lossy(&log[..len]).into_owned()
+}
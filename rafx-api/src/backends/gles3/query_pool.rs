@@ -0,0 +1,35 @@
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxQueryPoolDef, RafxResult};
+use std::sync::Arc;
+
+/// GL ES 3.0 has no timestamp or pipeline statistics queries, and the device reports no query
+/// support, so creating a query pool fails.
+#[derive(Clone, Debug)]
+pub struct RafxQueryPoolGles3 {
+    inner: Arc<RafxQueryPoolDef>,
+}
+
+impl PartialEq for RafxQueryPoolGles3 {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for RafxQueryPoolGles3 {}
+
+impl RafxQueryPoolGles3 {
+    pub fn new(
+        _device_context: &RafxDeviceContextGles3,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolGles3> {
+        query_pool_def.verify();
+        Err("Query pools are not supported by the GL ES backend")?
+    }
+
+    pub fn query_pool_def(&self) -> &RafxQueryPoolDef {
+        &self.inner
+    }
+}
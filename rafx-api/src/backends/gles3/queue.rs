@@ -0,0 +1,142 @@
+use super::execute::GlCommandExecutor;
+use super::internal::gl_context::check_gl_error;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::{
+    RafxCommandBufferGles3, RafxCommandPoolGles3, RafxDeviceContextGles3, RafxFenceGles3,
+    RafxSemaphoreGles3, RafxSwapchainGles3, RafxTimelineSemaphoreGles3,
+};
+use crate::{RafxCommandPoolDef, RafxPresentSuccessResult, RafxQueueType, RafxResult};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+static NEXT_QUEUE_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug)]
+pub struct RafxQueueGles3Inner {
+    device_context: RafxDeviceContextGles3,
+    queue_type: RafxQueueType,
+    queue_id: u32,
+}
+
+/// All queues issue commands to the device's single GL context, so work submitted to different
+/// queues executes in submission order.
+#[derive(Clone, Debug)]
+pub struct RafxQueueGles3 {
+    inner: Arc<RafxQueueGles3Inner>,
+}
+
+impl RafxQueueGles3 {
+    pub fn queue_id(&self) -> u32 {
+        self.inner.queue_id
+    }
+
+    pub fn queue_type(&self) -> RafxQueueType {
+        self.inner.queue_type
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        &self.inner.device_context
+    }
+
+    pub fn create_command_pool(
+        &self,
+        command_pool_def: &RafxCommandPoolDef,
+    ) -> RafxResult<RafxCommandPoolGles3> {
+        RafxCommandPoolGles3::new(self, command_pool_def)
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextGles3,
+        queue_type: RafxQueueType,
+    ) -> RafxResult<RafxQueueGles3> {
+        let queue_id = NEXT_QUEUE_ID.fetch_add(1, Ordering::Relaxed);
+        let inner = RafxQueueGles3Inner {
+            device_context: device_context.clone(),
+            queue_type,
+            queue_id,
+        };
+
+        Ok(RafxQueueGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn wait_for_queue_idle(&self) -> RafxResult<()> {
+        let _guard = self.inner.device_context.gl_context().lock()?;
+        unsafe {
+            gl::glFinish();
+        }
+        Ok(())
+    }
+
+    pub fn submit(
+        &self,
+        command_buffers: &[&RafxCommandBufferGles3],
+        wait_semaphores: &[&RafxSemaphoreGles3],
+        signal_semaphores: &[&RafxSemaphoreGles3],
+        wait_timeline_semaphores: &[(&RafxTimelineSemaphoreGles3, u64)],
+        signal_timeline_semaphores: &[(&RafxTimelineSemaphoreGles3, u64)],
+        signal_fence: Option<&RafxFenceGles3>,
+    ) -> RafxResult<()> {
+        assert!(!command_buffers.is_empty());
+
+        if !wait_timeline_semaphores.is_empty() || !signal_timeline_semaphores.is_empty() {
+            return Err("Timeline semaphores are not supported by the GL ES backend")?;
+        }
+
+        for command_buffer in command_buffers {
+            if command_buffer.is_recording() {
+                return Err("Submitted a command buffer that is still being recorded")?;
+            }
+        }
+
+        let device_context = &self.inner.device_context;
+        let _guard = device_context.gl_context().lock()?;
+
+        // Writes to mapped buffers are made to a host copy, they must reach GL before any
+        // command that reads the buffer
+        device_context.upload_dirty_buffers()?;
+
+        let mut executor = GlCommandExecutor::new(device_context);
+        let result = command_buffers
+            .iter()
+            .try_for_each(|command_buffer| command_buffer.execute(&mut executor));
+        executor.reset_gl_state();
+        result?;
+        check_gl_error("Executing command buffers")?;
+
+        // GL executes commands in order on the one context, so semaphores only need to track
+        // whether they have been signaled
+        for wait_semaphore in wait_semaphores {
+            wait_semaphore.set_signal_available(false);
+        }
+
+        for signal_semaphore in signal_semaphores {
+            signal_semaphore.set_signal_available(true);
+        }
+
+        if let Some(signal_fence) = signal_fence {
+            signal_fence.signal_after_commands()?;
+        }
+
+        unsafe {
+            gl::glFlush();
+        }
+
+        Ok(())
+    }
+
+    pub fn present(
+        &self,
+        swapchain: &RafxSwapchainGles3,
+        wait_semaphores: &[&RafxSemaphoreGles3],
+        image_index: u32,
+    ) -> RafxResult<RafxPresentSuccessResult> {
+        for wait_semaphore in wait_semaphores {
+            wait_semaphore.set_signal_available(false);
+        }
+
+        swapchain.present(image_index)?;
+        Ok(RafxPresentSuccessResult::Success)
+    }
+}
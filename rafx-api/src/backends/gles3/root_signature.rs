@@ -0,0 +1,477 @@
+use super::device_context::PUSH_CONSTANT_BUFFER_SIZE;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::{RafxDeviceContextGles3, RafxSamplerGles3};
+use crate::{
+    RafxDescriptorIndex, RafxError, RafxPipelineType, RafxPushConstantIndex, RafxResourceType,
+    RafxResult, RafxRootSignatureDef, RafxShaderResource, MAX_DESCRIPTOR_SET_LAYOUTS,
+};
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+// Where a texture descriptor gets the sampler it is sampled with. GLSL ES has no separate
+// samplers, so the shader processor combines each texture with the sampler it is used with.
+#[derive(Clone, Debug)]
+pub(crate) enum GlSamplerSource {
+    // The texture is only read with texelFetch() or was not processed for GL ES. It is sampled
+    // with the texture's own parameters.
+    None,
+    // One sampler per element of the sampler resource
+    Immutable(Vec<RafxSamplerGles3>),
+    // A sampler descriptor, possibly in another set
+    Descriptor(RafxDescriptorIndex),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DescriptorInfo {
+    pub(crate) name: Option<String>,
+    pub(crate) resource_type: RafxResourceType,
+
+    // Also the set layout
+    pub(crate) set_index: u32,
+    pub(crate) binding: u32,
+    // Used for arrays of textures, samplers, etc.
+    pub(crate) element_count: u32,
+    // Index of the first element within the values stored for each descriptor set
+    pub(crate) value_offset: u32,
+    // The first uniform buffer binding point for uniform buffers, or the first texture unit for
+    // textures. Each element of an array uses the next one. Unused by samplers.
+    pub(crate) gl_binding: u32,
+    // Only used by textures
+    pub(crate) sampler_source: GlSamplerSource,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PushConstantInfo {
+    pub(crate) name: Option<String>,
+    pub(crate) size_in_bytes: u32,
+    // Push constants are uniform blocks backed by a range of the device's push constant buffer
+    pub(crate) gl_binding: u32,
+    pub(crate) buffer_offset: u32,
+}
+
+// A uniform block or sampler uniform in the program, and the binding point or texture unit it is
+// assigned when a pipeline using this root signature is created
+#[derive(Clone, Debug)]
+pub(crate) struct GlNamedBinding {
+    pub(crate) name: String,
+    pub(crate) gl_binding: u32,
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct DescriptorSetLayoutInfo {
+    // Settable descriptors, immutable samplers are omitted
+    pub(crate) descriptors: Vec<RafxDescriptorIndex>,
+    // Indexes binding index to the descriptors list
+    pub(crate) binding_to_descriptor_index: FnvHashMap<u32, RafxDescriptorIndex>,
+    // Number of values stored for each descriptor set, one per element of each descriptor
+    pub(crate) value_count: u32,
+}
+
+#[derive(Debug)]
+pub(crate) struct RafxRootSignatureGles3Inner {
+    pub(crate) device_context: RafxDeviceContextGles3,
+    pub(crate) pipeline_type: RafxPipelineType,
+    pub(crate) layouts: [DescriptorSetLayoutInfo; MAX_DESCRIPTOR_SET_LAYOUTS],
+    pub(crate) descriptors: Vec<DescriptorInfo>,
+    pub(crate) name_to_descriptor_index: FnvHashMap<String, RafxDescriptorIndex>,
+    pub(crate) push_constants: Vec<PushConstantInfo>,
+    pub(crate) name_to_push_constant_index: FnvHashMap<String, RafxPushConstantIndex>,
+    pub(crate) uniform_block_bindings: Vec<GlNamedBinding>,
+    pub(crate) sampler_uniform_bindings: Vec<GlNamedBinding>,
+    #[cfg(feature = "capture")]
+    pub(crate) capture_id: u32,
+}
+
+/// Descriptor sets are emulated. Every uniform buffer element gets its own uniform buffer binding
+/// point and every texture element its own texture unit, and binding a descriptor set binds its
+/// resources to them before each draw.
+#[derive(Clone, Debug)]
+pub struct RafxRootSignatureGles3 {
+    pub(crate) inner: Arc<RafxRootSignatureGles3Inner>,
+}
+
+impl RafxRootSignatureGles3 {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        &self.inner.device_context
+    }
+
+    pub fn pipeline_type(&self) -> RafxPipelineType {
+        self.inner.pipeline_type
+    }
+
+    pub fn find_descriptor_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxDescriptorIndex> {
+        self.inner.name_to_descriptor_index.get(name).copied()
+    }
+
+    pub fn find_descriptor_by_binding(
+        &self,
+        set_index: u32,
+        binding: u32,
+    ) -> Option<RafxDescriptorIndex> {
+        self.inner
+            .layouts
+            .get(set_index as usize)
+            .and_then(|x| x.binding_to_descriptor_index.get(&binding))
+            .copied()
+    }
+
+    pub fn find_push_constant_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxPushConstantIndex> {
+        self.inner.name_to_push_constant_index.get(name).copied()
+    }
+
+    pub(crate) fn push_constant(
+        &self,
+        push_constant_index: RafxPushConstantIndex,
+    ) -> Option<&PushConstantInfo> {
+        self.inner
+            .push_constants
+            .get(push_constant_index.0 as usize)
+    }
+
+    pub(crate) fn descriptor(
+        &self,
+        descriptor_index: RafxDescriptorIndex,
+    ) -> Option<&DescriptorInfo> {
+        self.inner.descriptors.get(descriptor_index.0 as usize)
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Nothing to name, there is no GPU object
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextGles3,
+        root_signature_def: &RafxRootSignatureDef,
+    ) -> RafxResult<Self> {
+        log::trace!("Create RafxRootSignatureGles3");
+
+        // Make sure all shaders are compatible/build lookup of shared data from them
+        let (pipeline_type, mut merged_resources, _merged_resources_name_index_map) =
+            crate::internal_shared::merge_resources(root_signature_def)?;
+
+        // Fails if any bindless arrays are requested, GL ES 3.0 can't support them
+        crate::internal_shared::apply_bindless_arrays(
+            root_signature_def,
+            device_context.device_info(),
+            &mut merged_resources,
+        )?;
+
+        merged_resources.sort_by(|lhs, rhs| lhs.binding.cmp(&rhs.binding));
+
+        let (max_uniform_buffer_bindings, max_texture_units) = {
+            let _guard = device_context.gl_context().lock()?;
+            let mut max_uniform_buffer_bindings = 0;
+            let mut max_texture_units = 0;
+            unsafe {
+                gl::glGetIntegerv(
+                    gl::MAX_UNIFORM_BUFFER_BINDINGS,
+                    &mut max_uniform_buffer_bindings,
+                );
+                gl::glGetIntegerv(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS, &mut max_texture_units);
+            }
+            (max_uniform_buffer_bindings as u32, max_texture_units as u32)
+        };
+
+        let mut layouts: [DescriptorSetLayoutInfo; MAX_DESCRIPTOR_SET_LAYOUTS] = Default::default();
+        let mut descriptors = Vec::with_capacity(merged_resources.len());
+        let mut name_to_descriptor_index = FnvHashMap::default();
+        let mut push_constants = vec![];
+        let mut name_to_push_constant_index = FnvHashMap::default();
+        let mut uniform_block_bindings = vec![];
+        let mut sampler_uniform_bindings = vec![];
+
+        // Push constants take the first uniform buffer binding points so that descriptor
+        // bindings don't depend on them
+        let push_constant_alignment = device_context
+            .device_info()
+            .min_uniform_buffer_offset_alignment;
+        let mut next_push_constant_offset = 0;
+        for resource in &merged_resources {
+            if resource.resource_type != RafxResourceType::ROOT_CONSTANT {
+                continue;
+            }
+
+            resource.validate()?;
+
+            let push_constant_index = RafxPushConstantIndex(push_constants.len() as u32);
+            let gl_binding = push_constants.len() as u32;
+            let buffer_offset = rafx_base::memory::round_size_up_to_alignment_u32(
+                next_push_constant_offset,
+                push_constant_alignment,
+            );
+            next_push_constant_offset = buffer_offset + resource.size_in_bytes;
+            if next_push_constant_offset > PUSH_CONSTANT_BUFFER_SIZE {
+                Err(RafxError::reflection_mismatch(
+                    resource.name.as_deref(),
+                    format!(
+                        "Push constants use more than the {} bytes the GL ES backend supports",
+                        PUSH_CONSTANT_BUFFER_SIZE
+                    ),
+                ))?;
+            }
+
+            uniform_block_bindings.push(GlNamedBinding {
+                name: Self::gl_name(resource)?.to_string(),
+                gl_binding,
+            });
+
+            push_constants.push(PushConstantInfo {
+                name: resource.name.clone(),
+                size_in_bytes: resource.size_in_bytes,
+                gl_binding,
+                buffer_offset,
+            });
+
+            if let Some(name) = resource.name.as_ref() {
+                name_to_push_constant_index.insert(name.clone(), push_constant_index);
+            }
+        }
+
+        let mut next_uniform_buffer_binding = push_constants.len() as u32;
+        let mut next_texture_unit = 0;
+        for resource in &merged_resources {
+            if resource.resource_type == RafxResourceType::ROOT_CONSTANT {
+                continue;
+            }
+
+            resource.validate()?;
+
+            let immutable_sampler = crate::internal_shared::find_immutable_sampler_index(
+                root_signature_def.immutable_samplers,
+                &resource.name,
+                resource.set_index,
+                resource.binding,
+            );
+
+            // Check that if an immutable sampler is set, the array size matches the resource element count
+            if let Some(immutable_sampler_index) = immutable_sampler {
+                if resource.element_count_normalized() as usize
+                    != root_signature_def.immutable_samplers[immutable_sampler_index]
+                        .samplers
+                        .len()
+                {
+                    Err(RafxError::reflection_mismatch(
+                        resource.name.as_deref(),
+                        format!(
+                            "Descriptor (set={:?} binding={:?}) named {:?} specifies {} elements but the count of provided immutable samplers ({}) did not match",
+                            resource.set_index,
+                            resource.binding,
+                            resource.name,
+                            resource.element_count_normalized(),
+                            root_signature_def.immutable_samplers[immutable_sampler_index].samplers.len()
+                        ),
+                    ))?;
+                }
+
+                // Immutable samplers can't be updated, so they are not added to the descriptor list
+                continue;
+            }
+
+            let element_count = resource.element_count_normalized();
+            let gl_binding = match resource.resource_type {
+                RafxResourceType::SAMPLER => 0,
+                RafxResourceType::UNIFORM_BUFFER => {
+                    let gl_binding = next_uniform_buffer_binding;
+                    next_uniform_buffer_binding += element_count;
+                    if next_uniform_buffer_binding > max_uniform_buffer_bindings {
+                        Err(RafxError::reflection_mismatch(
+                            resource.name.as_deref(),
+                            format!(
+                                "The root signature uses more than the {} uniform buffer bindings supported by the device",
+                                max_uniform_buffer_bindings
+                            ),
+                        ))?;
+                    }
+
+                    Self::push_named_bindings(
+                        &mut uniform_block_bindings,
+                        Self::gl_name(resource)?,
+                        element_count,
+                        gl_binding,
+                    );
+                    gl_binding
+                }
+                RafxResourceType::TEXTURE => {
+                    let gl_binding = next_texture_unit;
+                    next_texture_unit += element_count;
+                    if next_texture_unit > max_texture_units {
+                        Err(RafxError::reflection_mismatch(
+                            resource.name.as_deref(),
+                            format!(
+                                "The root signature uses more than the {} texture units supported by the device",
+                                max_texture_units
+                            ),
+                        ))?;
+                    }
+
+                    Self::push_named_bindings(
+                        &mut sampler_uniform_bindings,
+                        Self::gl_name(resource)?,
+                        element_count,
+                        gl_binding,
+                    );
+                    gl_binding
+                }
+                _ => Err(RafxError::reflection_mismatch(
+                    resource.name.as_deref(),
+                    format!(
+                        "Descriptor (set={:?} binding={:?}) has resource type {:?}, the GL ES backend only supports samplers, textures and uniform buffers",
+                        resource.set_index, resource.binding, resource.resource_type
+                    ),
+                ))?,
+            };
+
+            let layout = &mut layouts[resource.set_index as usize];
+            let descriptor_index = RafxDescriptorIndex(descriptors.len() as u32);
+
+            descriptors.push(DescriptorInfo {
+                name: resource.name.clone(),
+                resource_type: resource.resource_type,
+                set_index: resource.set_index,
+                binding: resource.binding,
+                element_count,
+                value_offset: layout.value_count,
+                gl_binding,
+                sampler_source: GlSamplerSource::None,
+            });
+
+            if let Some(name) = resource.name.as_ref() {
+                name_to_descriptor_index.insert(name.clone(), descriptor_index);
+            }
+
+            layout.descriptors.push(descriptor_index);
+            layout
+                .binding_to_descriptor_index
+                .insert(resource.binding, descriptor_index);
+            layout.value_count += element_count;
+        }
+
+        // Samplers may be in a later set or binding than the textures that use them, so sampler
+        // sources are found once every descriptor has been assigned an index
+        for resource in &merged_resources {
+            if resource.resource_type != RafxResourceType::TEXTURE {
+                continue;
+            }
+
+            let sampler_name = match &resource.gles_sampler_name {
+                Some(sampler_name) => sampler_name,
+                None => continue,
+            };
+
+            let sampler_resource = merged_resources
+                .iter()
+                .find(|x| {
+                    x.resource_type == RafxResourceType::SAMPLER
+                        && x.name.as_ref() == Some(sampler_name)
+                })
+                .ok_or_else(|| {
+                    RafxError::reflection_mismatch(
+                        resource.name.as_deref(),
+                        format!(
+                            "Texture (set={:?} binding={:?}) is sampled with sampler {:?} but no sampler with that name exists",
+                            resource.set_index, resource.binding, sampler_name
+                        ),
+                    )
+                })?;
+
+            let immutable_sampler = crate::internal_shared::find_immutable_sampler_index(
+                root_signature_def.immutable_samplers,
+                &sampler_resource.name,
+                sampler_resource.set_index,
+                sampler_resource.binding,
+            );
+
+            let sampler_source = if let Some(immutable_sampler_index) = immutable_sampler {
+                let samplers = root_signature_def.immutable_samplers[immutable_sampler_index]
+                    .samplers
+                    .iter()
+                    .map(|x| {
+                        x.gles3_sampler()
+                            .cloned()
+                            .ok_or("Immutable samplers must be created by the same backend")
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                GlSamplerSource::Immutable(samplers)
+            } else {
+                let sampler_descriptor_index = layouts[sampler_resource.set_index as usize]
+                    .binding_to_descriptor_index[&sampler_resource.binding];
+                GlSamplerSource::Descriptor(sampler_descriptor_index)
+            };
+
+            let descriptor_index =
+                layouts[resource.set_index as usize].binding_to_descriptor_index[&resource.binding];
+            descriptors[descriptor_index.0 as usize].sampler_source = sampler_source;
+        }
+
+        let inner = RafxRootSignatureGles3Inner {
+            device_context: device_context.clone(),
+            pipeline_type,
+            layouts,
+            descriptors,
+            name_to_descriptor_index,
+            push_constants,
+            name_to_push_constant_index,
+            uniform_block_bindings,
+            sampler_uniform_bindings,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxRootSignatureGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+
+    // The name of the uniform block or sampler uniform in the GLSL ES source
+    fn gl_name(resource: &RafxShaderResource) -> RafxResult<&str> {
+        resource
+            .gles_name
+            .as_deref()
+            .or(resource.name.as_deref())
+            .ok_or_else(|| {
+                RafxError::reflection_mismatch(
+                    None,
+                    format!(
+                        "Resource (set={:?} binding={:?}) has no name, the GL ES backend binds resources by name",
+                        resource.set_index, resource.binding
+                    ),
+                )
+            })
+    }
+
+    // Arrays of uniform blocks and samplers are bound by the name of each element
+    fn push_named_bindings(
+        bindings: &mut Vec<GlNamedBinding>,
+        name: &str,
+        element_count: u32,
+        first_gl_binding: u32,
+    ) {
+        if element_count == 1 {
+            bindings.push(GlNamedBinding {
+                name: name.to_string(),
+                gl_binding: first_gl_binding,
+            });
+        } else {
+            for element_index in 0..element_count {
+                bindings.push(GlNamedBinding {
+                    name: format!("{}[{}]", name, element_index),
+                    gl_binding: first_gl_binding + element_index,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::internal::conversions;
+use super::internal::gl_context::check_gl_error;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxAddressMode, RafxCompareOp, RafxMipMapMode, RafxResult, RafxSamplerDef};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxSamplerGles3Inner {
+    device_context: RafxDeviceContextGles3,
+    sampler_def: RafxSamplerDef,
+    gl_sampler: gl::GLuint,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl Drop for RafxSamplerGles3Inner {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.device_context.gl_context().lock() {
+            unsafe {
+                gl::glDeleteSamplers(1, &self.gl_sampler);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RafxSamplerGles3 {
+    inner: Arc<RafxSamplerGles3Inner>,
+}
+
+impl RafxSamplerGles3 {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        &self.inner.device_context
+    }
+
+    pub fn sampler_def(&self) -> &RafxSamplerDef {
+        &self.inner.sampler_def
+    }
+
+    pub fn gl_sampler(&self) -> gl::GLuint {
+        self.inner.gl_sampler
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // GL ES 3.0 has no object labels without KHR_debug
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextGles3,
+        sampler_def: &RafxSamplerDef,
+    ) -> RafxResult<RafxSamplerGles3> {
+        let device_info = device_context.device_info();
+        sampler_def.verify_device_limits(device_info)?;
+        let max_anisotropy = sampler_def
+            .max_anisotropy
+            .min(device_info.max_sampler_anisotropy);
+
+        // Same as vulkan, nearest mip mode only samples the base level
+        let max_lod = if sampler_def.mip_map_mode == RafxMipMapMode::Linear {
+            f32::MAX
+        } else {
+            0.0
+        };
+
+        // Without border clamping, clamp to edge is the closest match
+        let address_mode = |address_mode: RafxAddressMode| {
+            if address_mode == RafxAddressMode::ClampToBorder
+                && !device_info.supports_clamp_to_border_color
+            {
+                gl::CLAMP_TO_EDGE
+            } else {
+                conversions::gl_address_mode(address_mode)
+            }
+        };
+
+        let _guard = device_context.gl_context().lock()?;
+        let mut gl_sampler = 0;
+        unsafe {
+            gl::glGenSamplers(1, &mut gl_sampler);
+            gl::glSamplerParameteri(
+                gl_sampler,
+                gl::TEXTURE_MIN_FILTER,
+                conversions::gl_min_filter(sampler_def.min_filter, sampler_def.mip_map_mode) as _,
+            );
+            gl::glSamplerParameteri(
+                gl_sampler,
+                gl::TEXTURE_MAG_FILTER,
+                conversions::gl_mag_filter(sampler_def.mag_filter) as _,
+            );
+            gl::glSamplerParameteri(
+                gl_sampler,
+                gl::TEXTURE_WRAP_S,
+                address_mode(sampler_def.address_mode_u) as _,
+            );
+            gl::glSamplerParameteri(
+                gl_sampler,
+                gl::TEXTURE_WRAP_T,
+                address_mode(sampler_def.address_mode_v) as _,
+            );
+            gl::glSamplerParameteri(
+                gl_sampler,
+                gl::TEXTURE_WRAP_R,
+                address_mode(sampler_def.address_mode_w) as _,
+            );
+            // GL ES has no LOD bias, vulkan also uses the bias as the min LOD
+            gl::glSamplerParameterf(gl_sampler, gl::TEXTURE_MIN_LOD, sampler_def.mip_lod_bias);
+            gl::glSamplerParameterf(gl_sampler, gl::TEXTURE_MAX_LOD, max_lod);
+
+            if sampler_def.compare_op != RafxCompareOp::Never {
+                gl::glSamplerParameteri(
+                    gl_sampler,
+                    gl::TEXTURE_COMPARE_MODE,
+                    gl::COMPARE_REF_TO_TEXTURE as _,
+                );
+                gl::glSamplerParameteri(
+                    gl_sampler,
+                    gl::TEXTURE_COMPARE_FUNC,
+                    conversions::gl_compare_func(sampler_def.compare_op) as _,
+                );
+            }
+
+            if max_anisotropy > 0.0 && device_info.max_sampler_anisotropy > 1.0 {
+                gl::glSamplerParameterf(gl_sampler, gl::TEXTURE_MAX_ANISOTROPY_EXT, max_anisotropy);
+            }
+        }
+
+        if let Err(e) = check_gl_error("Creating sampler") {
+            unsafe {
+                gl::glDeleteSamplers(1, &gl_sampler);
+            }
+            return Err(e);
+        }
+
+        let inner = RafxSamplerGles3Inner {
+            device_context: device_context.clone(),
+            sampler_def: sampler_def.clone(),
+            gl_sampler,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxSamplerGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+}
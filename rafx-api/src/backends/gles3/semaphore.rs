@@ -0,0 +1,34 @@
+use crate::gles3::RafxDeviceContextGles3;
+use crate::RafxResult;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// GL executes commands in submission order on a single context, so semaphores don't need to do
+/// any synchronization. Their state is tracked so that misuse can be detected like on other
+/// backends.
+pub struct RafxSemaphoreGles3 {
+    _device_context: RafxDeviceContextGles3,
+
+    // Set to true when an operation is scheduled to signal this semaphore
+    // Cleared when an operation is scheduled to consume this semaphore
+    signal_available: AtomicBool,
+}
+
+impl RafxSemaphoreGles3 {
+    pub fn new(device_context: &RafxDeviceContextGles3) -> RafxResult<RafxSemaphoreGles3> {
+        Ok(RafxSemaphoreGles3 {
+            _device_context: device_context.clone(),
+            signal_available: AtomicBool::new(false),
+        })
+    }
+
+    pub fn signal_available(&self) -> bool {
+        self.signal_available.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_signal_available(
+        &self,
+        available: bool,
+    ) {
+        self.signal_available.store(available, Ordering::Relaxed);
+    }
+}
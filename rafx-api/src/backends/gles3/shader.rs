@@ -0,0 +1,59 @@
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxPipelineReflection, RafxResult, RafxShaderStageDef, RafxShaderStageFlags};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct RafxShaderGles3Inner {
+    stage_flags: RafxShaderStageFlags,
+    stages: Vec<RafxShaderStageDef>,
+    pipeline_reflection: RafxPipelineReflection,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxShaderGles3 {
+    inner: Arc<RafxShaderGles3Inner>,
+}
+
+impl RafxShaderGles3 {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextGles3,
+        stages: Vec<RafxShaderStageDef>,
+    ) -> RafxResult<Self> {
+        let pipeline_reflection = RafxPipelineReflection::from_stages(&stages)?;
+        let mut stage_flags = RafxShaderStageFlags::empty();
+        for stage in &stages {
+            stage_flags |= stage.reflection.shader_stage;
+        }
+
+        let inner = RafxShaderGles3Inner {
+            stages,
+            pipeline_reflection,
+            stage_flags,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxShaderGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn stages(&self) -> &[RafxShaderStageDef] {
+        &self.inner.stages
+    }
+
+    pub fn pipeline_reflection(&self) -> &RafxPipelineReflection {
+        &self.inner.pipeline_reflection
+    }
+
+    pub fn stage_flags(&self) -> RafxShaderStageFlags {
+        self.inner.stage_flags
+    }
+}
@@ -0,0 +1,42 @@
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{RafxResult, RafxShaderModule, RafxShaderModuleDefGles3};
+use std::sync::Arc;
+
+/// Holds GLSL ES source. GL links all stages of a program together, so the source is compiled when
+/// a pipeline is created rather than when the module is.
+#[derive(Clone, Debug)]
+pub struct RafxShaderModuleGles3 {
+    src: Arc<String>,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl RafxShaderModuleGles3 {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextGles3,
+        data: RafxShaderModuleDefGles3,
+    ) -> RafxResult<Self> {
+        let RafxShaderModuleDefGles3::GlSrc(src) = data;
+
+        Ok(RafxShaderModuleGles3 {
+            src: Arc::new(src.to_string()),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        })
+    }
+}
+
+impl Into<RafxShaderModule> for RafxShaderModuleGles3 {
+    fn into(self) -> RafxShaderModule {
+        RafxShaderModule::Gles3(self)
+    }
+}
@@ -0,0 +1,219 @@
+use super::execute::attach_texture;
+use super::internal::egl_bindings as egl;
+use super::internal::gl_context::check_gl_error;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::{RafxDeviceContextGles3, RafxFenceGles3, RafxSemaphoreGles3, RafxTextureGles3};
+use crate::{
+    RafxExtents3D, RafxFormat, RafxResourceType, RafxResult, RafxSampleCount, RafxSwapchainDef,
+    RafxSwapchainImage, RafxTexture, RafxTextureDef, RafxTextureDimensions,
+};
+use raw_window_handle::HasRawWindowHandle;
+
+const SWAPCHAIN_IMAGE_COUNT: u32 = 3;
+
+// The EGL surface handle may be used from any thread with the GL context locked
+struct GlWindowSurface(egl::EGLSurface);
+unsafe impl Send for GlWindowSurface {}
+unsafe impl Sync for GlWindowSurface {}
+
+/// EGL owns the window surface's buffers and they can't be used as textures, so the swapchain
+/// images are render target textures that are blitted to the surface when presented.
+pub struct RafxSwapchainGles3 {
+    device_context: RafxDeviceContextGles3,
+    swapchain_def: RafxSwapchainDef,
+    surface: GlWindowSurface,
+    format: RafxFormat,
+    swapchain_images: Vec<RafxTextureGles3>,
+    next_swapchain_image_index: u32,
+}
+
+impl Drop for RafxSwapchainGles3 {
+    fn drop(&mut self) {
+        self.device_context
+            .gl_context()
+            .destroy_window_surface(self.surface.0);
+    }
+}
+
+impl RafxSwapchainGles3 {
+    pub fn swapchain_def(&self) -> &RafxSwapchainDef {
+        &self.swapchain_def
+    }
+
+    pub fn image_count(&self) -> usize {
+        self.swapchain_images.len()
+    }
+
+    pub fn format(&self) -> RafxFormat {
+        self.format
+    }
+
+    /// Returns the texture that backs the given swapchain image
+    pub fn swapchain_image(
+        &self,
+        image_index: u32,
+    ) -> &RafxTextureGles3 {
+        &self.swapchain_images[image_index as usize]
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextGles3,
+        raw_window_handle: &dyn HasRawWindowHandle,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<RafxSwapchainGles3> {
+        if device_context.is_headless() {
+            return Err("Cannot create a swapchain with a headless GL ES device, create the device with RafxApi::new_gles3 instead")?;
+        }
+
+        let gl_context = device_context.gl_context();
+
+        // Without an sRGB surface, shaders would have to output gamma-corrected values for the
+        // image to look right, so fall back to a linear format that is presented as-is
+        let format = if gl_context.supports_srgb_surfaces() {
+            RafxFormat::R8G8B8A8_SRGB
+        } else {
+            RafxFormat::R8G8B8A8_UNORM
+        };
+
+        let swapchain_images =
+            Self::create_swapchain_images(device_context, swapchain_def, format)?;
+        let surface = GlWindowSurface(
+            gl_context.create_window_surface(raw_window_handle.raw_window_handle())?,
+        );
+
+        let swapchain = RafxSwapchainGles3 {
+            device_context: device_context.clone(),
+            swapchain_def: swapchain_def.clone(),
+            surface,
+            format,
+            swapchain_images,
+            next_swapchain_image_index: 0,
+        };
+
+        swapchain.apply_swap_interval()?;
+        Ok(swapchain)
+    }
+
+    fn create_swapchain_images(
+        device_context: &RafxDeviceContextGles3,
+        swapchain_def: &RafxSwapchainDef,
+        format: RafxFormat,
+    ) -> RafxResult<Vec<RafxTextureGles3>> {
+        let texture_def = RafxTextureDef {
+            extents: RafxExtents3D {
+                width: swapchain_def.width,
+                height: swapchain_def.height,
+                depth: 1,
+            },
+            array_length: 1,
+            mip_count: 1,
+            format,
+            resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+            sample_count: RafxSampleCount::SampleCount1,
+            dimensions: RafxTextureDimensions::Dim2D,
+        };
+
+        (0..SWAPCHAIN_IMAGE_COUNT)
+            .map(|_| RafxTextureGles3::new(device_context, &texture_def))
+            .collect()
+    }
+
+    // The swap interval applies to the surface that is current, so it is set while the surface
+    // is current for presenting
+    fn apply_swap_interval(&self) -> RafxResult<()> {
+        let guard = self.device_context.gl_context().lock()?;
+        let interval = if self.swapchain_def.enable_vsync {
+            1
+        } else {
+            0
+        };
+        guard.present_to_surface(self.surface.0, || {
+            self.device_context
+                .gl_context()
+                .set_swap_interval(&guard, interval);
+            Ok(())
+        })
+    }
+
+    pub fn rebuild(
+        &mut self,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<()> {
+        self.swapchain_images =
+            Self::create_swapchain_images(&self.device_context, swapchain_def, self.format)?;
+        self.swapchain_def = swapchain_def.clone();
+        self.next_swapchain_image_index = 0;
+        self.apply_swap_interval()
+    }
+
+    fn acquire_next_image(&mut self) -> RafxSwapchainImage {
+        let swapchain_image_index = self.next_swapchain_image_index;
+        self.next_swapchain_image_index += 1;
+        if self.next_swapchain_image_index >= SWAPCHAIN_IMAGE_COUNT {
+            self.next_swapchain_image_index = 0;
+        }
+
+        RafxSwapchainImage {
+            texture: RafxTexture::Gles3(
+                self.swapchain_images[swapchain_image_index as usize].clone(),
+            ),
+            swapchain_image_index,
+        }
+    }
+
+    // Images are handed out round-robin. Work on them is executed in order on the one context,
+    // so they are always available.
+    pub fn acquire_next_image_fence(
+        &mut self,
+        fence: &RafxFenceGles3,
+    ) -> RafxResult<RafxSwapchainImage> {
+        let swapchain_image = self.acquire_next_image();
+        fence.signal_immediately();
+        Ok(swapchain_image)
+    }
+
+    pub fn acquire_next_image_semaphore(
+        &mut self,
+        semaphore: &RafxSemaphoreGles3,
+    ) -> RafxResult<RafxSwapchainImage> {
+        let swapchain_image = self.acquire_next_image();
+        semaphore.set_signal_available(true);
+        Ok(swapchain_image)
+    }
+
+    // Blits the image to the window surface and swaps. Rendering uses vulkan's convention of the
+    // first row being the top of the image, so it is flipped to GL's bottom-up window coordinates.
+    pub(crate) fn present(
+        &self,
+        image_index: u32,
+    ) -> RafxResult<()> {
+        let image = &self.swapchain_images[image_index as usize];
+        let width = self.swapchain_def.width as gl::GLint;
+        let height = self.swapchain_def.height as gl::GLint;
+        let read_framebuffer = self.device_context.gl_objects().read_framebuffer;
+
+        let guard = self.device_context.gl_context().lock()?;
+        guard.present_to_surface(self.surface.0, || {
+            unsafe {
+                gl::glBindFramebuffer(gl::READ_FRAMEBUFFER, read_framebuffer);
+                attach_texture(gl::READ_FRAMEBUFFER, gl::COLOR_ATTACHMENT0, image, 0, 0);
+                gl::glBindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+                gl::glBlitFramebuffer(
+                    0,
+                    0,
+                    width,
+                    height,
+                    0,
+                    height,
+                    width,
+                    0,
+                    gl::COLOR_BUFFER_BIT,
+                    gl::NEAREST,
+                );
+                gl::glBindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            }
+
+            check_gl_error("Presenting swapchain image")
+        })
+    }
+}
@@ -0,0 +1,322 @@
+use super::internal::conversions::{self, GlFormatInfo};
+use super::internal::gl_context::check_gl_error;
+use super::internal::gles3_bindings as gl;
+use crate::gles3::RafxDeviceContextGles3;
+use crate::{
+    RafxError, RafxMemoryUsage, RafxResourceType, RafxResult, RafxSampleCount, RafxTextureDef,
+    RafxTextureDimensions,
+};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// The GL object that stores a texture's texels
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RafxRawTextureGles3 {
+    /// A texture object and the target it is bound to (TEXTURE_2D, TEXTURE_2D_ARRAY, TEXTURE_3D
+    /// or TEXTURE_CUBE_MAP)
+    Texture {
+        target: gl::GLenum,
+        texture: gl::GLuint,
+    },
+    /// GL ES 3.0 can't create multisampled textures, so multisampled render targets are
+    /// renderbuffers. They can be rendered to, resolved and blitted but not sampled.
+    Renderbuffer(gl::GLuint),
+}
+
+#[derive(Debug)]
+pub struct RafxTextureGles3Inner {
+    device_context: RafxDeviceContextGles3,
+    texture_def: RafxTextureDef,
+    raw_texture: RafxRawTextureGles3,
+    format_info: GlFormatInfo,
+    allocation_size: u64,
+    texture_id: u32,
+}
+
+impl Drop for RafxTextureGles3Inner {
+    fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_texture_destroyed(self.texture_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_texture_destroyed(self.texture_id);
+
+        if let Ok(_guard) = self.device_context.gl_context().lock() {
+            unsafe {
+                match self.raw_texture {
+                    RafxRawTextureGles3::Texture { texture, .. } => {
+                        gl::glDeleteTextures(1, &texture)
+                    }
+                    RafxRawTextureGles3::Renderbuffer(renderbuffer) => {
+                        gl::glDeleteRenderbuffers(1, &renderbuffer)
+                    }
+                }
+            }
+        }
+
+        self.device_context
+            .memory_usage_tracker()
+            .on_texture_freed(RafxMemoryUsage::GpuOnly, self.allocation_size);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RafxTextureGles3 {
+    inner: Arc<RafxTextureGles3Inner>,
+}
+
+impl PartialEq for RafxTextureGles3 {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.inner.texture_id == other.inner.texture_id
+    }
+}
+
+impl Eq for RafxTextureGles3 {}
+
+impl Hash for RafxTextureGles3 {
+    fn hash<H: Hasher>(
+        &self,
+        state: &mut H,
+    ) {
+        self.inner.texture_id.hash(state);
+    }
+}
+
+impl RafxTextureGles3 {
+    pub fn texture_def(&self) -> &RafxTextureDef {
+        &self.inner.texture_def
+    }
+
+    // Used internally to identify the texture for validation
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn texture_id(&self) -> u32 {
+        self.inner.texture_id
+    }
+
+    pub fn device_context(&self) -> &RafxDeviceContextGles3 {
+        &self.inner.device_context
+    }
+
+    pub fn gl_raw_texture(&self) -> RafxRawTextureGles3 {
+        self.inner.raw_texture
+    }
+
+    pub(crate) fn format_info(&self) -> &GlFormatInfo {
+        &self.inner.format_info
+    }
+
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // GL ES 3.0 has no object labels without KHR_debug
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextGles3,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureGles3> {
+        texture_def.verify();
+        texture_def.verify_device_limits(device_context.device_info())?;
+
+        let format = texture_def.format;
+        if format.is_undefined() {
+            return Err(RafxError::invalid_argument(
+                "format",
+                "Cannot create a texture with an undefined format",
+            ));
+        }
+
+        let format_info =
+            conversions::gl_format_info(format).ok_or(RafxError::UnsupportedFormat(format))?;
+
+        if format_info.is_compressed {
+            let device_info = device_context.device_info();
+            let supported = if format.is_bc_compressed() {
+                device_info.supports_bc_texture_compression
+            } else if format.is_astc_compressed() {
+                device_info.supports_astc_texture_compression
+            } else {
+                device_info.supports_etc2_texture_compression
+            };
+
+            if !supported {
+                return Err(RafxError::UnsupportedFormat(format));
+            }
+        }
+
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::TEXTURE_READ_WRITE)
+        {
+            return Err("GL ES 3.0 does not support storage images")?;
+        }
+
+        let dimensions = texture_def
+            .dimensions
+            .determine_dimensions(texture_def.extents);
+        let is_cube = texture_def
+            .resource_type
+            .contains(RafxResourceType::TEXTURE_CUBE);
+
+        let raw_texture = {
+            let _guard = device_context.gl_context().lock()?;
+            let raw_texture = if texture_def.sample_count != RafxSampleCount::SampleCount1 {
+                if texture_def.mip_count != 1 || texture_def.array_length != 1 {
+                    return Err("Multisampled textures with more than one mip level or array layer are not supported by the GL ES backend")?;
+                }
+
+                Self::create_renderbuffer(texture_def, &format_info)
+            } else {
+                let target = if dimensions == RafxTextureDimensions::Dim3D {
+                    gl::TEXTURE_3D
+                } else if is_cube {
+                    if texture_def.array_length != 6 {
+                        return Err("GL ES 3.0 does not support cube map arrays")?;
+                    }
+                    gl::TEXTURE_CUBE_MAP
+                } else if texture_def.array_length > 1 {
+                    gl::TEXTURE_2D_ARRAY
+                } else {
+                    gl::TEXTURE_2D
+                };
+
+                Self::create_texture(target, texture_def, &format_info)
+            };
+
+            if let Err(e) = check_gl_error("Creating texture") {
+                unsafe {
+                    match raw_texture {
+                        RafxRawTextureGles3::Texture { texture, .. } => {
+                            gl::glDeleteTextures(1, &texture)
+                        }
+                        RafxRawTextureGles3::Renderbuffer(renderbuffer) => {
+                            gl::glDeleteRenderbuffers(1, &renderbuffer)
+                        }
+                    }
+                }
+                return Err(e);
+            }
+
+            raw_texture
+        };
+
+        let allocation_size = Self::estimated_allocation_size(texture_def);
+        device_context
+            .memory_usage_tracker()
+            .on_texture_allocated(RafxMemoryUsage::GpuOnly, allocation_size);
+
+        let inner = RafxTextureGles3Inner {
+            device_context: device_context.clone(),
+            texture_def: texture_def.clone(),
+            raw_texture,
+            format_info,
+            allocation_size,
+            texture_id: crate::internal_shared::NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed),
+        };
+
+        Ok(RafxTextureGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+
+    fn create_renderbuffer(
+        texture_def: &RafxTextureDef,
+        format_info: &GlFormatInfo,
+    ) -> RafxRawTextureGles3 {
+        let mut renderbuffer = 0;
+        unsafe {
+            gl::glGenRenderbuffers(1, &mut renderbuffer);
+            gl::glBindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+            gl::glRenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                texture_def.sample_count as _,
+                format_info.internal_format,
+                texture_def.extents.width as _,
+                texture_def.extents.height as _,
+            );
+            gl::glBindRenderbuffer(gl::RENDERBUFFER, 0);
+        }
+
+        RafxRawTextureGles3::Renderbuffer(renderbuffer)
+    }
+
+    fn create_texture(
+        target: gl::GLenum,
+        texture_def: &RafxTextureDef,
+        format_info: &GlFormatInfo,
+    ) -> RafxRawTextureGles3 {
+        let extents = texture_def.extents;
+        let mut texture = 0;
+        unsafe {
+            gl::glGenTextures(1, &mut texture);
+            gl::glBindTexture(target, texture);
+            match target {
+                gl::TEXTURE_3D => gl::glTexStorage3D(
+                    target,
+                    texture_def.mip_count as _,
+                    format_info.internal_format,
+                    extents.width as _,
+                    extents.height as _,
+                    extents.depth as _,
+                ),
+                gl::TEXTURE_2D_ARRAY => gl::glTexStorage3D(
+                    target,
+                    texture_def.mip_count as _,
+                    format_info.internal_format,
+                    extents.width as _,
+                    extents.height as _,
+                    texture_def.array_length as _,
+                ),
+                _ => gl::glTexStorage2D(
+                    target,
+                    texture_def.mip_count as _,
+                    format_info.internal_format,
+                    extents.width as _,
+                    extents.height as _,
+                ),
+            }
+
+            // Used when the texture is sampled without a sampler object. Integer and depth
+            // textures are incomplete with linear filtering, so nearest is the only safe default.
+            gl::glTexParameteri(target, gl::TEXTURE_MIN_FILTER, gl::NEAREST as _);
+            gl::glTexParameteri(target, gl::TEXTURE_MAG_FILTER, gl::NEAREST as _);
+            gl::glTexParameteri(target, gl::TEXTURE_BASE_LEVEL, 0);
+            gl::glTexParameteri(
+                target,
+                gl::TEXTURE_MAX_LEVEL,
+                texture_def.mip_count as gl::GLint - 1,
+            );
+            gl::glBindTexture(target, 0);
+        }
+
+        RafxRawTextureGles3::Texture { target, texture }
+    }
+
+    // GL doesn't report how much memory a texture uses, so this is the size of the texel data
+    fn estimated_allocation_size(texture_def: &RafxTextureDef) -> u64 {
+        let format = texture_def.format;
+        let block_size = format.block_or_pixel_size_in_bytes() as u64;
+        let block_width = format.block_width_in_pixels();
+        let block_height = format.block_height_in_pixels();
+
+        let mut size = 0;
+        for mip_level in 0..texture_def.mip_count {
+            let width = (texture_def.extents.width >> mip_level).max(1);
+            let height = (texture_def.extents.height >> mip_level).max(1);
+            let depth = (texture_def.extents.depth >> mip_level).max(1);
+            let blocks_wide = width.div_ceil(block_width);
+            let blocks_high = height.div_ceil(block_height);
+            size += blocks_wide as u64 * blocks_high as u64 * depth as u64 * block_size;
+        }
+
+        size * texture_def.array_length as u64 * texture_def.sample_count as u64
+    }
+}
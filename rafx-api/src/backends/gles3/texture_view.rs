@@ -0,0 +1,80 @@
+use crate::gles3::{RafxDeviceContextGles3, RafxTextureGles3};
+use crate::{RafxError, RafxResult, RafxTexture, RafxTextureViewDef};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxTextureViewGles3Inner {
+    // It's a RafxTextureGles3, but stored as RafxTexture so we can return refs to it
+    texture: RafxTexture,
+    texture_view_def: RafxTextureViewDef,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+/// GL ES 3.0 has no texture views. A view is applied when it is used: sampling restricts the
+/// texture's base/max mip level, and render targets attach the view's first mip level and array
+/// layer. The view can't change the format, except to sample the depth of a depth/stencil texture.
+#[derive(Clone, Debug)]
+pub struct RafxTextureViewGles3 {
+    inner: Arc<RafxTextureViewGles3Inner>,
+}
+
+impl RafxTextureViewGles3 {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn texture_view_def(&self) -> &RafxTextureViewDef {
+        &self.inner.texture_view_def
+    }
+
+    pub fn texture(&self) -> &RafxTexture {
+        &self.inner.texture
+    }
+
+    pub fn gles3_texture(&self) -> &RafxTextureGles3 {
+        self.inner.texture.gles3_texture().unwrap()
+    }
+
+    // Sampling binds the whole texture object, so only views of every array layer can be sampled
+    pub(crate) fn covers_all_array_layers(&self) -> bool {
+        let array_range = self.inner.texture_view_def.array_range.as_ref().unwrap();
+        array_range.start == 0 && array_range.end == self.gles3_texture().texture_def().array_length
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextGles3,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewGles3> {
+        let gles3_texture = texture
+            .gles3_texture()
+            .ok_or("Texture views must be created from a texture of the same backend")?;
+        let texture_def = gles3_texture.texture_def();
+        let texture_view_def = texture_view_def.resolve(texture_def)?;
+
+        let format = texture_view_def.format.unwrap();
+        if format != texture_def.format && texture_def.format.depth_sample_format() != Some(format)
+        {
+            return Err(RafxError::invalid_argument(
+                "format",
+                format!(
+                    "GL ES 3.0 cannot reinterpret texture formats, a {:?} texture cannot be viewed as {:?}",
+                    texture_def.format, format
+                ),
+            ));
+        }
+
+        let inner = RafxTextureViewGles3Inner {
+            texture: texture.clone(),
+            texture_view_def,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxTextureViewGles3 {
+            inner: Arc::new(inner),
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use crate::gles3::RafxDeviceContextGles3;
+use crate::RafxResult;
+
+/// GL ES has no timeline semaphores. `RafxDeviceInfo::supports_timeline_semaphores` is false, so
+/// creating one fails.
+pub struct RafxTimelineSemaphoreGles3 {
+    _device_context: RafxDeviceContextGles3,
+}
+
+impl RafxTimelineSemaphoreGles3 {
+    pub fn new(
+        _device_context: &RafxDeviceContextGles3,
+        _initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreGles3> {
+        Err("Timeline semaphores are not supported by the GL ES backend")?
+    }
+
+    pub fn current_value(&self) -> RafxResult<u64> {
+        Err("Timeline semaphores are not supported by the GL ES backend")?
+    }
+
+    pub fn signal(
+        &self,
+        _value: u64,
+    ) -> RafxResult<()> {
+        Err("Timeline semaphores are not supported by the GL ES backend")?
+    }
+
+    pub fn wait(
+        &self,
+        _value: u64,
+    ) -> RafxResult<()> {
+        Err("Timeline semaphores are not supported by the GL ES backend")?
+    }
+}
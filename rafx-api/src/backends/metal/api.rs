@@ -1,4 +1,5 @@
-use crate::{RafxApiDef, RafxResult};
+use crate::metal::features::MetalFeatures;
+use crate::{RafxAdapterInfo, RafxAdapterType, RafxApiDef, RafxResult};
 use raw_window_handle::HasRawWindowHandle;
 use std::sync::Arc;
 
@@ -23,12 +24,50 @@ impl RafxApiMetal {
         self.device_context.as_ref().unwrap()
     }
 
+    /// List the adapters available to metal. Metal does not report PCI IDs or a driver version.
+    pub fn enumerate_adapters(
+        _window: &dyn HasRawWindowHandle,
+        _metal_api_def: &RafxApiDefMetal,
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        let default_registry_id =
+            metal_rs::Device::system_default().map(|device| device.registry_id());
+
+        let adapters = metal_rs::Device::all()
+            .iter()
+            .enumerate()
+            .map(|(adapter_index, device)| {
+                let metal_features = MetalFeatures::from_device(device.as_ref());
+                let adapter_type =
+                    if metal_features.feature_set_macos.is_some() && !metal_features.is_low_power {
+                        RafxAdapterType::DiscreteGpu
+                    } else {
+                        RafxAdapterType::IntegratedGpu
+                    };
+
+                let is_supported = metal_features.feature_set().is_some();
+
+                RafxAdapterInfo {
+                    adapter_index: adapter_index as u32,
+                    name: metal_features.device_name,
+                    vendor_id: 0,
+                    device_id: 0,
+                    adapter_type,
+                    driver_version: String::new(),
+                    is_supported,
+                    is_default: Some(device.registry_id()) == default_registry_id,
+                }
+            })
+            .collect();
+
+        Ok(adapters)
+    }
+
     pub fn new(
         _window: &dyn HasRawWindowHandle,
-        _api_def: &RafxApiDef,
+        api_def: &RafxApiDef,
         _metal_api_def: &RafxApiDefMetal,
     ) -> RafxResult<Self> {
-        let inner = Arc::new(RafxDeviceContextMetalInner::new()?);
+        let inner = Arc::new(RafxDeviceContextMetalInner::new(api_def.adapter_index)?);
         let device_context = RafxDeviceContextMetal::new(inner)?;
 
         Ok(RafxApiMetal {
@@ -1,22 +1,60 @@
-use crate::metal::RafxDeviceContextMetal;
-use crate::{RafxBufferDef, RafxMemoryUsage, RafxResourceType, RafxResult};
+use crate::metal::{RafxDeviceContextMetal, RafxHeapMetal};
+use crate::{RafxBufferDef, RafxMemoryRequirements, RafxMemoryUsage, RafxResourceType, RafxResult};
+#[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+use std::sync::atomic::Ordering;
 
 #[derive(Debug)]
 pub struct RafxBufferMetal {
     device_context: RafxDeviceContextMetal,
     buffer_def: RafxBufferDef,
     buffer: metal_rs::Buffer,
+    // Set if the buffer was created in a heap, the heap owns the memory
+    heap: Option<RafxHeapMetal>,
+    // Used internally to identify the buffer for validation and capture
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    buffer_id: u32,
 }
 
 // for metal_rs::Buffer
 unsafe impl Send for RafxBufferMetal {}
 unsafe impl Sync for RafxBufferMetal {}
 
+impl Drop for RafxBufferMetal {
+    fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_buffer_destroyed(self.buffer_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_buffer_destroyed(self.buffer_id);
+
+        if self.heap.is_some() {
+            return;
+        }
+
+        self.device_context
+            .memory_usage_tracker()
+            .on_buffer_freed(self.buffer_def.memory_usage, self.buffer.allocated_size());
+    }
+}
+
 impl RafxBufferMetal {
     pub fn buffer_def(&self) -> &RafxBufferDef {
         &self.buffer_def
     }
 
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn buffer_id(&self) -> u32 {
+        self.buffer_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.device_context.capture_recorder()
+    }
+
     pub fn metal_buffer(&self) -> &metal_rs::BufferRef {
         self.buffer.as_ref()
     }
@@ -71,10 +109,17 @@ impl RafxBufferMetal {
         Ok(())
     }
 
-    pub fn new(
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.metal_buffer().set_label(name.as_ref());
+    }
+
+    fn allocation_size(
         device_context: &RafxDeviceContextMetal,
         buffer_def: &RafxBufferDef,
-    ) -> RafxResult<Self> {
+    ) -> u64 {
         let mut allocation_size = buffer_def.size;
         if buffer_def
             .resource_type
@@ -88,15 +133,73 @@ impl RafxBufferMetal {
             )
         }
 
+        allocation_size
+    }
+
+    pub fn memory_requirements(
+        device_context: &RafxDeviceContextMetal,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxMemoryRequirements {
+        let size_and_align = device_context.device().heap_buffer_size_and_align(
+            Self::allocation_size(device_context, buffer_def),
+            buffer_def.memory_usage.mtl_resource_options(),
+        );
+
+        RafxMemoryRequirements {
+            size: size_and_align.size,
+            alignment: size_and_align.align,
+        }
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextMetal,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
         let buffer = device_context.device().new_buffer(
-            allocation_size,
+            Self::allocation_size(device_context, buffer_def),
             buffer_def.memory_usage.mtl_resource_options(),
         );
+        device_context
+            .memory_usage_tracker()
+            .on_buffer_allocated(buffer_def.memory_usage, buffer.allocated_size());
+
+        Ok(RafxBufferMetal {
+            device_context: device_context.clone(),
+            buffer_def: buffer_def.clone(),
+            buffer,
+            heap: None,
+            #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+            buffer_id: crate::internal_shared::NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    pub fn new_in_heap(
+        device_context: &RafxDeviceContextMetal,
+        heap: &RafxHeapMetal,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
+        heap.heap_def().verify_buffer_def(buffer_def)?;
+
+        let allocation_size = Self::allocation_size(device_context, buffer_def);
+        let resource_options = buffer_def.memory_usage.mtl_resource_options();
+        let size_and_align = device_context
+            .device()
+            .heap_buffer_size_and_align(allocation_size, resource_options);
+        heap.verify_placement(offset, &size_and_align)?;
+
+        let buffer = heap
+            .metal_heap()
+            .new_buffer_with_offset(allocation_size, resource_options, offset)
+            .ok_or("Failed to create buffer in heap")?;
 
         Ok(RafxBufferMetal {
             device_context: device_context.clone(),
             buffer_def: buffer_def.clone(),
             buffer,
+            heap: Some(heap.clone()),
+            #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+            buffer_id: crate::internal_shared::NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 }
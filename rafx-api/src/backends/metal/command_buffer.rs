@@ -1,18 +1,23 @@
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxCommandBufferResourceStates;
 use crate::metal::{
     ArgumentBufferData, BarrierFlagsMetal, RafxBufferMetal, RafxCommandPoolMetal,
-    RafxDescriptorSetArrayMetal, RafxDescriptorSetHandleMetal, RafxPipelineMetal, RafxQueueMetal,
-    RafxRootSignatureMetal, RafxTextureMetal,
+    RafxDescriptorSetArrayMetal, RafxDescriptorSetHandleMetal, RafxPipelineMetal,
+    RafxQueryPoolMetal, RafxQueueMetal, RafxRootSignatureMetal, RafxTextureMetal,
 };
 use crate::{
-    RafxBufferBarrier, RafxCmdCopyBufferToTextureParams, RafxColorRenderTargetBinding,
-    RafxCommandBufferDef, RafxDepthStencilRenderTargetBinding, RafxExtents3D,
-    RafxIndexBufferBinding, RafxIndexType, RafxLoadOp, RafxPipelineType, RafxResourceState,
-    RafxResult, RafxTextureBarrier, RafxVertexBufferBinding,
+    RafxBufferBarrier, RafxCmdBlitParams, RafxCmdClearTextureParams,
+    RafxCmdCopyBufferToTextureParams, RafxCmdCopyTextureToTextureParams,
+    RafxColorRenderTargetBinding, RafxCommandBufferDef, RafxCommandBufferInheritanceInfo,
+    RafxDepthStencilRenderTargetBinding, RafxExtents3D, RafxIndexBufferBinding, RafxIndexType,
+    RafxLoadOp, RafxPipelineType, RafxPushConstantIndex, RafxResourceState, RafxResult,
+    RafxShaderStageFlags, RafxTexture, RafxTextureBarrier, RafxTextureView,
+    RafxVertexBufferBinding,
 };
 use fnv::FnvHashSet;
 use metal_rs::{
-    MTLBlitOption, MTLIndexType, MTLOrigin, MTLPrimitiveType, MTLRenderStages, MTLResourceUsage,
-    MTLScissorRect, MTLSize, MTLViewport,
+    MTLBlitOption, MTLIndexType, MTLLoadAction, MTLOrigin, MTLPrimitiveType, MTLRenderStages,
+    MTLResourceUsage, MTLScissorRect, MTLSize, MTLStoreAction, MTLTextureType, MTLViewport,
 };
 use rafx_base::trust_cell::TrustCell;
 
@@ -24,6 +29,8 @@ pub struct RafxCommandBufferMetalInner {
     render_encoder: Option<metal_rs::RenderCommandEncoder>,
     compute_encoder: Option<metal_rs::ComputeCommandEncoder>,
     blit_encoder: Option<metal_rs::BlitCommandEncoder>,
+    // Only used by primaries in a renderpass begun with cmd_begin_render_pass_for_secondary
+    parallel_render_encoder: Option<metal_rs::ParallelRenderCommandEncoder>,
     current_index_buffer: Option<metal_rs::Buffer>,
     current_index_buffer_offset: u64,
     current_index_buffer_type: MTLIndexType,
@@ -35,6 +42,42 @@ pub struct RafxCommandBufferMetalInner {
     compute_threads_per_group_x: u32,
     compute_threads_per_group_y: u32,
     compute_threads_per_group_z: u32,
+    // Debug groups that have been pushed but not popped
+    debug_groups: Vec<String>,
+}
+
+impl RafxCommandBufferMetalInner {
+    fn current_encoder(&self) -> Option<&metal_rs::CommandEncoderRef> {
+        if let Some(render_encoder) = &self.render_encoder {
+            Some(render_encoder)
+        } else if let Some(compute_encoder) = &self.compute_encoder {
+            Some(compute_encoder)
+        } else if let Some(blit_encoder) = &self.blit_encoder {
+            Some(blit_encoder)
+        } else if let Some(parallel_render_encoder) = &self.parallel_render_encoder {
+            Some(parallel_render_encoder)
+        } else {
+            None
+        }
+    }
+
+    // Metal debug groups can't span encoders, so the open groups are pushed onto each encoder when
+    // it's created and popped before it ends
+    fn push_debug_groups_to_current_encoder(&self) {
+        if let Some(encoder) = self.current_encoder() {
+            for debug_group in &self.debug_groups {
+                encoder.push_debug_group(debug_group);
+            }
+        }
+    }
+
+    fn pop_debug_groups_from_current_encoder(&self) {
+        if let Some(encoder) = self.current_encoder() {
+            for _ in &self.debug_groups {
+                encoder.pop_debug_group();
+            }
+        }
+    }
 }
 
 unsafe impl Send for RafxCommandBufferMetalInner {}
@@ -43,7 +86,12 @@ unsafe impl Sync for RafxCommandBufferMetalInner {}
 #[derive(Debug)]
 pub struct RafxCommandBufferMetal {
     queue: RafxQueueMetal,
+    is_secondary: bool,
     inner: TrustCell<RafxCommandBufferMetalInner>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxCommandBufferResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl RafxCommandBufferMetal {
@@ -99,9 +147,24 @@ impl RafxCommandBufferMetal {
         self.inner.borrow_mut().command_buffer = None;
     }
 
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxCommandBufferResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.queue.device_context().capture_recorder()
+    }
+
     pub fn new(
         command_pool: &RafxCommandPoolMetal,
-        _command_buffer_def: &RafxCommandBufferDef,
+        command_buffer_def: &RafxCommandBufferDef,
     ) -> RafxResult<RafxCommandBufferMetal> {
         let inner = RafxCommandBufferMetalInner {
             render_targets_to_make_readable: Default::default(),
@@ -109,6 +172,7 @@ impl RafxCommandBufferMetal {
             render_encoder: None,
             compute_encoder: None,
             blit_encoder: None,
+            parallel_render_encoder: None,
             last_pipeline_type: None,
             primitive_type: MTLPrimitiveType::Triangle,
             current_render_targets_width: 0,
@@ -116,6 +180,7 @@ impl RafxCommandBufferMetal {
             compute_threads_per_group_x: 0,
             compute_threads_per_group_y: 0,
             compute_threads_per_group_z: 0,
+            debug_groups: Default::default(),
             current_index_buffer: None,
             current_index_buffer_offset: 0,
             current_index_buffer_type: MTLIndexType::UInt16,
@@ -124,7 +189,12 @@ impl RafxCommandBufferMetal {
 
         Ok(RafxCommandBufferMetal {
             queue: command_pool.queue().clone(),
+            is_secondary: command_buffer_def.is_secondary,
             inner: TrustCell::new(inner),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 
@@ -134,10 +204,47 @@ impl RafxCommandBufferMetal {
             let mut inner = self.inner.borrow_mut();
             inner.command_buffer = Some(command_buffer.to_owned());
             inner.last_pipeline_type = None;
+            inner.debug_groups.clear();
             Ok(())
         })
     }
 
+    pub fn begin_secondary(
+        &self,
+        inheritance_info: &RafxCommandBufferInheritanceInfo,
+    ) -> RafxResult<()> {
+        if !self.is_secondary {
+            Err("begin_secondary called on a primary command buffer")?;
+        }
+
+        let primary = inheritance_info
+            .primary_command_buffer
+            .ok_or("begin_secondary requires primary_command_buffer to be set on metal")?
+            .metal_command_buffer()
+            .unwrap();
+
+        // The viewport and scissor are left to the caller, like on vulkan where the render target
+        // size isn't known when beginning a secondary command buffer
+        objc::rc::autoreleasepool(|| -> RafxResult<()> {
+            let primary_inner = primary.inner.borrow();
+            let parallel_render_encoder = primary_inner.parallel_render_encoder.as_ref().ok_or(
+                "The primary command buffer must be in a renderpass begun with cmd_begin_render_pass_for_secondary",
+            )?;
+
+            // Each child encoder is executed in the order it was created
+            let render_encoder = parallel_render_encoder.render_command_encoder();
+
+            let mut inner = self.inner.borrow_mut();
+            inner.command_buffer = None;
+            inner.last_pipeline_type = None;
+            inner.debug_groups.clear();
+            inner.current_render_targets_width = primary_inner.current_render_targets_width;
+            inner.current_render_targets_height = primary_inner.current_render_targets_height;
+            inner.render_encoder = Some(render_encoder.to_owned());
+            self.wait_for_barriers(&*inner)
+        })
+    }
+
     pub fn end(&self) -> RafxResult<()> {
         objc::rc::autoreleasepool(|| self.end_current_encoders(true))
     }
@@ -153,6 +260,23 @@ impl RafxCommandBufferMetal {
         &self,
         color_targets: &[RafxColorRenderTargetBinding],
         depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, false)
+    }
+
+    pub fn cmd_begin_render_pass_for_secondary(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, true)
+    }
+
+    fn do_begin_render_pass(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+        for_secondary: bool,
     ) -> RafxResult<()> {
         // if self.has_active_renderpass.load(Ordering::Relaxed) {
         //     self.cmd_end_render_pass()?;
@@ -162,6 +286,24 @@ impl RafxCommandBufferMetal {
             Err("No color or depth target supplied to cmd_begin_render_pass")?;
         }
 
+        for color_target in color_targets {
+            Self::verify_render_target_view(
+                color_target.texture,
+                color_target.texture_view,
+                color_target.mip_slice,
+                color_target.array_slice,
+            )?;
+        }
+
+        if let Some(depth_target) = &depth_target {
+            Self::verify_render_target_view(
+                depth_target.texture,
+                depth_target.texture_view,
+                depth_target.mip_slice,
+                depth_target.array_slice,
+            )?;
+        }
+
         let mut extents = RafxExtents3D::default();
 
         let result: RafxResult<()> = objc::rc::autoreleasepool(|| {
@@ -173,12 +315,19 @@ impl RafxCommandBufferMetal {
                 let texture = color_target.texture.metal_texture().unwrap();
 
                 // Ensure current_render_targets_width/current_render_targets_depth are set
-                extents = texture.texture_def().extents;
+                extents = Self::render_target_extents(texture, color_target.texture_view);
                 inner.current_render_targets_width = extents.width;
                 inner.current_render_targets_height = extents.height;
 
-                color_descriptor.set_texture(Some(texture.metal_texture()));
-                color_descriptor.set_level(color_target.mip_slice.unwrap_or(0) as _);
+                if let Some(texture_view) = color_target.texture_view {
+                    // The view starts at the mip level and layer being rendered to
+                    let metal_texture = texture_view.metal_texture_view().unwrap().metal_texture();
+                    color_descriptor.set_texture(Some(metal_texture));
+                    color_descriptor.set_level(0);
+                } else {
+                    color_descriptor.set_texture(Some(texture.metal_texture()));
+                    color_descriptor.set_level(color_target.mip_slice.unwrap_or(0) as _);
+                }
                 if color_target.array_slice.is_some() {
                     if texture.texture_def().extents.depth > 1 {
                         color_descriptor.set_depth_plane(color_target.array_slice.unwrap() as _);
@@ -211,13 +360,21 @@ impl RafxCommandBufferMetal {
                 let texture = depth_target.texture.metal_texture().unwrap();
 
                 // Ensure current_render_targets_width/current_render_targets_depth are set
-                extents = depth_target.texture.texture_def().extents;
+                extents = Self::render_target_extents(texture, depth_target.texture_view);
                 inner.current_render_targets_width = extents.width;
                 inner.current_render_targets_height = extents.height;
 
-                depth_descriptor.set_texture(Some(texture.metal_texture()));
-                depth_descriptor.set_level(depth_target.mip_slice.unwrap_or(0) as _);
-                depth_descriptor.set_slice(depth_target.array_slice.unwrap_or(0) as _);
+                // The view starts at the mip level and layer being rendered to
+                let metal_texture = depth_target
+                    .texture_view
+                    .map(|x| x.metal_texture_view().unwrap().metal_texture())
+                    .unwrap_or_else(|| texture.metal_texture());
+                let mip_slice = depth_target.mip_slice.unwrap_or(0);
+                let array_slice = depth_target.array_slice.unwrap_or(0);
+
+                depth_descriptor.set_texture(Some(metal_texture));
+                depth_descriptor.set_level(mip_slice as _);
+                depth_descriptor.set_slice(array_slice as _);
                 depth_descriptor.set_load_action(depth_target.depth_load_op.into());
                 depth_descriptor.set_store_action(depth_target.depth_store_op.into());
 
@@ -228,9 +385,9 @@ impl RafxCommandBufferMetal {
                 let has_stencil = texture.texture_def().format.has_stencil();
                 if has_stencil {
                     let stencil_descriptor = descriptor.stencil_attachment().unwrap();
-                    stencil_descriptor.set_texture(Some(texture.metal_texture()));
-                    stencil_descriptor.set_level(depth_target.mip_slice.unwrap_or(0) as _);
-                    stencil_descriptor.set_slice(depth_target.array_slice.unwrap_or(0) as _);
+                    stencil_descriptor.set_texture(Some(metal_texture));
+                    stencil_descriptor.set_level(mip_slice as _);
+                    stencil_descriptor.set_slice(array_slice as _);
                     stencil_descriptor.set_load_action(depth_target.stencil_load_op.into());
                     stencil_descriptor.set_store_action(depth_target.stencil_store_op.into());
                 } else {
@@ -250,8 +407,18 @@ impl RafxCommandBufferMetal {
             // end encoders
             Self::do_end_current_encoders(&self.queue, &mut *inner, false)?;
             let cmd_buffer = inner.command_buffer.as_ref().unwrap();
+            if for_secondary {
+                // Secondaries create their own child encoders and wait on barriers themselves
+                let parallel_render_encoder =
+                    cmd_buffer.new_parallel_render_command_encoder(descriptor);
+                inner.parallel_render_encoder = Some(parallel_render_encoder.to_owned());
+                inner.push_debug_groups_to_current_encoder();
+                return Ok(());
+            }
+
             let render_encoder = cmd_buffer.new_render_command_encoder(descriptor);
             inner.render_encoder = Some(render_encoder.to_owned());
+            inner.push_debug_groups_to_current_encoder();
             self.wait_for_barriers(&*inner)?;
             // set heaps?
 
@@ -259,6 +426,11 @@ impl RafxCommandBufferMetal {
         });
         result?;
 
+        if for_secondary {
+            // The viewport and scissor are set by each secondary
+            return Ok(());
+        }
+
         self.cmd_set_viewport(
             0.0,
             0.0,
@@ -271,6 +443,48 @@ impl RafxCommandBufferMetal {
         self.cmd_set_scissor(0, 0, extents.width, extents.height)
     }
 
+    fn verify_render_target_view(
+        texture: &RafxTexture,
+        texture_view: Option<&RafxTextureView>,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    ) -> RafxResult<()> {
+        if let Some(texture_view) = texture_view {
+            let texture_view = texture_view.metal_texture_view().unwrap();
+            crate::internal_shared::verify_render_target_view(
+                texture.texture_def(),
+                texture_view.texture_view_def(),
+                texture_view.metal_parent_texture() == texture.metal_texture().unwrap(),
+                mip_slice,
+                array_slice,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn render_target_extents(
+        texture: &RafxTextureMetal,
+        texture_view: Option<&RafxTextureView>,
+    ) -> RafxExtents3D {
+        let extents = texture.texture_def().extents;
+        if let Some(texture_view) = texture_view {
+            let mip_level = texture_view
+                .texture_view_def()
+                .mip_range
+                .as_ref()
+                .unwrap()
+                .start;
+            RafxExtents3D {
+                width: (extents.width >> mip_level).max(1),
+                height: (extents.height >> mip_level).max(1),
+                depth: 1,
+            }
+        } else {
+            extents
+        }
+    }
+
     pub fn end_current_encoders(
         &self,
         force_barrier: bool,
@@ -285,6 +499,8 @@ impl RafxCommandBufferMetal {
     ) -> RafxResult<()> {
         let barrier_flags = queue.barrier_flags();
 
+        inner.pop_debug_groups_from_current_encoder();
+
         if let Some(render_encoder) = inner.render_encoder.take() {
             if !barrier_flags.is_empty() || force_barrier {
                 render_encoder.update_fence(queue.metal_fence(), MTLRenderStages::Fragment);
@@ -306,6 +522,9 @@ impl RafxCommandBufferMetal {
             }
 
             blit_encoder.end_encoding();
+        } else if let Some(parallel_render_encoder) = inner.parallel_render_encoder.take() {
+            // The child encoders ended by the secondaries have already updated the fence
+            parallel_render_encoder.end_encoding();
         }
 
         Ok(())
@@ -340,6 +559,34 @@ impl RafxCommandBufferMetal {
         Ok(())
     }
 
+    pub fn cmd_execute_secondary_command_buffers(
+        &self,
+        secondary_command_buffers: &[&RafxCommandBufferMetal],
+    ) -> RafxResult<()> {
+        if self.inner.borrow().parallel_render_encoder.is_none() {
+            Err("cmd_execute_secondary_command_buffers must be called in a renderpass begun with cmd_begin_render_pass_for_secondary")?;
+        }
+
+        for secondary_command_buffer in secondary_command_buffers {
+            if !secondary_command_buffer.is_secondary {
+                Err("cmd_execute_secondary_command_buffers was passed a primary command buffer")?;
+            }
+
+            // The work was encoded directly into the primary's parallel render encoder, so it
+            // only needs to be finished
+            if secondary_command_buffer
+                .inner
+                .borrow()
+                .render_encoder
+                .is_some()
+            {
+                Err("cmd_execute_secondary_command_buffers was passed a secondary command buffer that has not ended")?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn cmd_set_viewport(
         &self,
         x: f32,
@@ -409,6 +656,50 @@ impl RafxCommandBufferMetal {
         Ok(())
     }
 
+    pub fn cmd_set_depth_bias(
+        &self,
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    ) -> RafxResult<()> {
+        self.inner
+            .borrow()
+            .render_encoder
+            .as_ref()
+            .unwrap()
+            .set_depth_bias(constant_factor, slope_factor, clamp);
+        Ok(())
+    }
+
+    pub fn cmd_set_blend_constants(
+        &self,
+        blend_constants: [f32; 4],
+    ) -> RafxResult<()> {
+        self.inner
+            .borrow()
+            .render_encoder
+            .as_ref()
+            .unwrap()
+            .set_blend_color(
+                blend_constants[0],
+                blend_constants[1],
+                blend_constants[2],
+                blend_constants[3],
+            );
+        Ok(())
+    }
+
+    pub fn cmd_set_line_width(
+        &self,
+        line_width: f32,
+    ) -> RafxResult<()> {
+        // Metal has no line width, this fails for anything other than 1.0
+        self.queue
+            .device_context()
+            .device_info()
+            .verify_line_width(line_width)
+    }
+
     pub fn cmd_bind_pipeline(
         &self,
         pipeline: &RafxPipelineMetal,
@@ -431,11 +722,13 @@ impl RafxCommandBufferMetal {
                         .set_front_facing_winding(render_encoder_info.mtl_front_facing_winding);
                     render_encoder
                         .set_triangle_fill_mode(render_encoder_info.mtl_triangle_fill_mode);
-                    render_encoder.set_depth_bias(
-                        render_encoder_info.mtl_depth_bias,
-                        render_encoder_info.mtl_depth_bias_slope_scaled,
-                        0.0,
-                    );
+                    if !render_encoder_info.dynamic_depth_bias {
+                        render_encoder.set_depth_bias(
+                            render_encoder_info.mtl_depth_bias,
+                            render_encoder_info.mtl_depth_bias_slope_scaled,
+                            0.0,
+                        );
+                    }
                     render_encoder.set_depth_clip_mode(render_encoder_info.mtl_depth_clip_mode);
                     if let Some(mtl_depth_stencil_state) =
                         &render_encoder_info.mtl_depth_stencil_state
@@ -456,6 +749,7 @@ impl RafxCommandBufferMetal {
                             .unwrap()
                             .new_compute_command_encoder();
                         inner.compute_encoder = Some(compute_encoder.to_owned());
+                        inner.push_debug_groups_to_current_encoder();
                     }
 
                     let compute_encoder_info = pipeline.compute_encoder_info.as_ref().unwrap();
@@ -629,6 +923,61 @@ impl RafxCommandBufferMetal {
         Ok(())
     }
 
+    pub fn cmd_bind_push_constant(
+        &self,
+        root_signature: &RafxRootSignatureMetal,
+        push_constant_index: RafxPushConstantIndex,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        let push_constant = root_signature
+            .push_constant(push_constant_index)
+            .ok_or("Push constant index is not valid for this root signature")?;
+
+        if data.len() as u32 > push_constant.size_in_bytes {
+            Err(format!(
+                "Push constant {:?} is {} bytes but {} bytes were provided",
+                push_constant.name,
+                push_constant.size_in_bytes,
+                data.len()
+            ))?;
+        }
+
+        let buffer_index = crate::METAL_PUSH_CONSTANT_BUFFER_INDEX as _;
+        let inner = self.inner.borrow();
+        match root_signature.pipeline_type() {
+            RafxPipelineType::Graphics => {
+                let render_encoder = inner
+                    .render_encoder
+                    .as_ref()
+                    .ok_or("Must begin render pass before binding graphics push constants")?;
+                let stages = push_constant.used_in_shader_stages;
+                if stages.intersects(RafxShaderStageFlags::VERTEX) {
+                    render_encoder.set_vertex_bytes(
+                        buffer_index,
+                        data.len() as _,
+                        data.as_ptr() as _,
+                    );
+                }
+                if stages.intersects(RafxShaderStageFlags::FRAGMENT) {
+                    render_encoder.set_fragment_bytes(
+                        buffer_index,
+                        data.len() as _,
+                        data.as_ptr() as _,
+                    );
+                }
+            }
+            RafxPipelineType::Compute => {
+                let compute_encoder = inner
+                    .compute_encoder
+                    .as_ref()
+                    .ok_or("Must bind compute pipeline before binding compute push constants")?;
+                compute_encoder.set_bytes(buffer_index, data.len() as _, data.as_ptr() as _);
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn cmd_draw(
         &self,
         vertex_count: u32,
@@ -806,6 +1155,122 @@ impl RafxCommandBufferMetal {
         Ok(())
     }
 
+    pub fn cmd_draw_indirect(
+        &self,
+        indirect_buffer: &RafxBufferMetal,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        let inner = self.inner.borrow();
+        let render_encoder = inner.render_encoder.as_ref().unwrap();
+
+        // Metal reads one draw per call, so issue a draw for each set of arguments
+        for i in 0..draw_count as u64 {
+            render_encoder.draw_primitives_indirect(
+                inner.primitive_type,
+                indirect_buffer.metal_buffer(),
+                (indirect_buffer_offset_in_bytes + i * stride as u64) as _,
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_draw_indexed_indirect(
+        &self,
+        indirect_buffer: &RafxBufferMetal,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        let inner = self.inner.borrow();
+        let render_encoder = inner.render_encoder.as_ref().unwrap();
+
+        // Metal reads one draw per call, so issue a draw for each set of arguments
+        for i in 0..draw_count as u64 {
+            render_encoder.draw_indexed_primitives_indirect(
+                inner.primitive_type,
+                inner.current_index_buffer_type,
+                inner.current_index_buffer.as_ref().unwrap(),
+                inner.current_index_buffer_offset as _,
+                indirect_buffer.metal_buffer(),
+                (indirect_buffer_offset_in_bytes + i * stride as u64) as _,
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_dispatch_indirect(
+        &self,
+        indirect_buffer: &RafxBufferMetal,
+        indirect_buffer_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        let inner = self.inner.borrow();
+        self.wait_for_barriers(&*inner)?;
+        let thread_per_group = MTLSize {
+            width: inner.compute_threads_per_group_x as _,
+            height: inner.compute_threads_per_group_y as _,
+            depth: inner.compute_threads_per_group_z as _,
+        };
+
+        inner
+            .compute_encoder
+            .as_ref()
+            .unwrap()
+            .dispatch_thread_groups_indirect(
+                indirect_buffer.metal_buffer(),
+                indirect_buffer_offset_in_bytes as _,
+                thread_per_group,
+            );
+        Ok(())
+    }
+
+    pub fn cmd_reset_query_pool(
+        &self,
+        _query_pool: &RafxQueryPoolMetal,
+        _first_query: u32,
+        _query_count: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the metal backend")?
+    }
+
+    pub fn cmd_begin_query(
+        &self,
+        _query_pool: &RafxQueryPoolMetal,
+        _query_index: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the metal backend")?
+    }
+
+    pub fn cmd_end_query(
+        &self,
+        _query_pool: &RafxQueryPoolMetal,
+        _query_index: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the metal backend")?
+    }
+
+    pub fn cmd_write_timestamp(
+        &self,
+        _query_pool: &RafxQueryPoolMetal,
+        _query_index: u32,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the metal backend")?
+    }
+
+    pub fn cmd_resolve_query(
+        &self,
+        _query_pool: &RafxQueryPoolMetal,
+        _first_query: u32,
+        _query_count: u32,
+        _dst_buffer: &RafxBufferMetal,
+        _dst_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        Err("Query pools are not supported by the metal backend")?
+    }
+
     pub fn cmd_resource_barrier(
         &self,
         buffer_barriers: &[RafxBufferBarrier],
@@ -837,6 +1302,144 @@ impl RafxCommandBufferMetal {
         Ok(())
     }
 
+    pub fn cmd_clear_texture(
+        &self,
+        texture: &RafxTextureMetal,
+        params: &RafxCmdClearTextureParams,
+    ) -> RafxResult<()> {
+        let texture_def = texture.texture_def();
+        let format = texture_def.format;
+        match params.clear_value {
+            RafxTextureClearValue::Color(_) => {
+                if format.has_depth_or_stencil() {
+                    Err("cmd_clear_texture requires a depth/stencil clear value for depth/stencil formats")?;
+                }
+            }
+            RafxTextureClearValue::DepthStencil(_) => {
+                if !format.has_depth_or_stencil() {
+                    Err("cmd_clear_texture requires a color clear value for color formats")?;
+                }
+            }
+        }
+
+        let mip_levels = match params.mip_slice {
+            Some(mip_slice) => mip_slice as u32..mip_slice as u32 + 1,
+            None => 0..texture_def.mip_count,
+        };
+
+        // Metal has no clear command outside of a render pass, so begin a render pass that clears
+        // each mip level/slice (or depth plane for 3D textures)
+        for mip_level in mip_levels {
+            let is_3d = texture_def.extents.depth > 1;
+            let slices = if is_3d {
+                0..1.max(texture_def.extents.depth >> mip_level)
+            } else {
+                match params.array_slice {
+                    Some(array_slice) => array_slice as u32..array_slice as u32 + 1,
+                    None => 0..texture_def.array_length,
+                }
+            };
+
+            for slice in slices {
+                let result: RafxResult<()> = objc::rc::autoreleasepool(|| {
+                    let descriptor = metal_rs::RenderPassDescriptor::new();
+                    match params.clear_value {
+                        RafxTextureClearValue::Color(color) => {
+                            let color_descriptor =
+                                descriptor.color_attachments().object_at(0).unwrap();
+                            color_descriptor.set_texture(Some(texture.metal_texture()));
+                            color_descriptor.set_level(mip_level as _);
+                            if is_3d {
+                                color_descriptor.set_depth_plane(slice as _);
+                            } else {
+                                color_descriptor.set_slice(slice as _);
+                            }
+                            color_descriptor.set_load_action(MTLLoadAction::Clear);
+                            color_descriptor.set_store_action(MTLStoreAction::Store);
+                            color_descriptor.set_clear_color(color.into());
+                        }
+                        RafxTextureClearValue::DepthStencil(depth_stencil) => {
+                            if format.has_depth() {
+                                let depth_descriptor = descriptor.depth_attachment().unwrap();
+                                depth_descriptor.set_texture(Some(texture.metal_texture()));
+                                depth_descriptor.set_level(mip_level as _);
+                                depth_descriptor.set_slice(slice as _);
+                                depth_descriptor.set_load_action(MTLLoadAction::Clear);
+                                depth_descriptor.set_store_action(MTLStoreAction::Store);
+                                depth_descriptor.set_clear_depth(depth_stencil.depth as f64);
+                            }
+
+                            if format.has_stencil() {
+                                let stencil_descriptor = descriptor.stencil_attachment().unwrap();
+                                stencil_descriptor.set_texture(Some(texture.metal_texture()));
+                                stencil_descriptor.set_level(mip_level as _);
+                                stencil_descriptor.set_slice(slice as _);
+                                stencil_descriptor.set_load_action(MTLLoadAction::Clear);
+                                stencil_descriptor.set_store_action(MTLStoreAction::Store);
+                                stencil_descriptor.set_clear_stencil(depth_stencil.stencil);
+                            }
+                        }
+                    }
+
+                    let mut inner = self.inner.borrow_mut();
+                    Self::do_end_current_encoders(&self.queue, &mut *inner, false)?;
+                    let render_encoder = inner
+                        .command_buffer
+                        .as_ref()
+                        .unwrap()
+                        .new_render_command_encoder(descriptor);
+                    inner.render_encoder = Some(render_encoder.to_owned());
+                    inner.push_debug_groups_to_current_encoder();
+                    self.wait_for_barriers(&*inner)?;
+                    Self::do_end_current_encoders(&self.queue, &mut *inner, false)
+                });
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_fill_buffer(
+        &self,
+        buffer: &RafxBufferMetal,
+        offset: u64,
+        size: u64,
+        value: u8,
+    ) -> RafxResult<()> {
+        if offset % 4 != 0 || size % 4 != 0 {
+            Err("cmd_fill_buffer offset and size must be multiples of 4")?;
+        }
+
+        let mut inner = self.inner.borrow_mut();
+        let blit_encoder = inner.blit_encoder.as_ref();
+        let blit_encoder = match blit_encoder {
+            Some(x) => x,
+            None => {
+                let result: RafxResult<&metal_rs::BlitCommandEncoderRef> =
+                    objc::rc::autoreleasepool(|| {
+                        Self::do_end_current_encoders(&self.queue, &mut *inner, false)?;
+                        let encoder = inner
+                            .command_buffer
+                            .as_ref()
+                            .unwrap()
+                            .new_blit_command_encoder();
+                        inner.blit_encoder = Some(encoder.to_owned());
+                        inner.push_debug_groups_to_current_encoder();
+                        Ok(inner.blit_encoder.as_ref().unwrap().as_ref())
+                    });
+                result?
+            }
+        };
+
+        blit_encoder.fill_buffer(
+            buffer.metal_buffer(),
+            metal_rs::NSRange::new(offset as _, size as _),
+            value,
+        );
+        Ok(())
+    }
+
     pub fn cmd_copy_buffer_to_buffer(
         &self,
         src_buffer: &RafxBufferMetal,
@@ -859,6 +1462,7 @@ impl RafxCommandBufferMetal {
                             .unwrap()
                             .new_blit_command_encoder();
                         inner.blit_encoder = Some(encoder.to_owned());
+                        inner.push_debug_groups_to_current_encoder();
                         Ok(inner.blit_encoder.as_ref().unwrap().as_ref())
                     });
                 result?
@@ -895,6 +1499,7 @@ impl RafxCommandBufferMetal {
                             .unwrap()
                             .new_blit_command_encoder();
                         inner.blit_encoder = Some(encoder.to_owned());
+                        inner.push_debug_groups_to_current_encoder();
                         Ok(inner.blit_encoder.as_ref().unwrap().as_ref())
                     });
                 result?
@@ -947,4 +1552,265 @@ impl RafxCommandBufferMetal {
         );
         Ok(())
     }
+
+    pub fn cmd_copy_texture_to_buffer(
+        &self,
+        src_texture: &RafxTextureMetal,
+        dst_buffer: &RafxBufferMetal,
+        params: &RafxCmdCopyTextureToBufferParams,
+    ) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        let blit_encoder = inner.blit_encoder.as_ref();
+        let blit_encoder = match blit_encoder {
+            Some(x) => x,
+            None => {
+                let result: RafxResult<&metal_rs::BlitCommandEncoderRef> =
+                    objc::rc::autoreleasepool(|| {
+                        Self::do_end_current_encoders(&self.queue, &mut *inner, false)?;
+                        let encoder = inner
+                            .command_buffer
+                            .as_ref()
+                            .unwrap()
+                            .new_blit_command_encoder();
+                        inner.blit_encoder = Some(encoder.to_owned());
+                        inner.push_debug_groups_to_current_encoder();
+                        Ok(inner.blit_encoder.as_ref().unwrap().as_ref())
+                    });
+                result?
+            }
+        };
+
+        let texture_def = src_texture.texture_def();
+        let layout = RafxTextureBufferLayout::new(
+            self.queue.device_context().device_info(),
+            texture_def.format,
+            texture_def.extents,
+            params.mip_level,
+        );
+
+        let source_size = MTLSize {
+            width: layout.extents.width as _,
+            height: layout.extents.height as _,
+            depth: layout.extents.depth as _,
+        };
+
+        blit_encoder.copy_from_texture_to_buffer(
+            src_texture.metal_texture(),
+            params.array_layer as _,
+            params.mip_level as _,
+            MTLOrigin { x: 0, y: 0, z: 0 },
+            source_size,
+            dst_buffer.metal_buffer(),
+            params.buffer_offset as _,
+            layout.bytes_per_row as _,
+            layout.bytes_per_image as _,
+            MTLBlitOption::empty(),
+        );
+        Ok(())
+    }
+    pub fn cmd_copy_texture_to_texture(
+        &self,
+        src_texture: &RafxTextureMetal,
+        dst_texture: &RafxTextureMetal,
+        params: &RafxCmdCopyTextureToTextureParams,
+    ) -> RafxResult<()> {
+        if src_texture.texture_def().format != dst_texture.texture_def().format {
+            Err("cmd_copy_texture_to_texture requires both textures to have the same format")?;
+        }
+
+        let mut inner = self.inner.borrow_mut();
+        let blit_encoder = inner.blit_encoder.as_ref();
+        let blit_encoder = match blit_encoder {
+            Some(x) => x,
+            None => {
+                let result: RafxResult<&metal_rs::BlitCommandEncoderRef> =
+                    objc::rc::autoreleasepool(|| {
+                        Self::do_end_current_encoders(&self.queue, &mut *inner, false)?;
+                        let encoder = inner
+                            .command_buffer
+                            .as_ref()
+                            .unwrap()
+                            .new_blit_command_encoder();
+                        inner.blit_encoder = Some(encoder.to_owned());
+                        inner.push_debug_groups_to_current_encoder();
+                        Ok(inner.blit_encoder.as_ref().unwrap().as_ref())
+                    });
+                result?
+            }
+        };
+
+        let slices = if let Some(array_slices) = params.array_slices {
+            vec![(array_slices[0], array_slices[1])]
+        } else {
+            let layer_count = src_texture
+                .texture_def()
+                .array_length
+                .min(dst_texture.texture_def().array_length);
+            (0..layer_count as u16).map(|x| (x, x)).collect()
+        };
+
+        let source_size = MTLSize {
+            width: params.extents.width as _,
+            height: params.extents.height as _,
+            depth: params.extents.depth as _,
+        };
+
+        for (src_slice, dst_slice) in slices {
+            blit_encoder.copy_from_texture(
+                src_texture.metal_texture(),
+                src_slice as _,
+                params.src_mip_level as _,
+                MTLOrigin {
+                    x: params.src_offset.width as _,
+                    y: params.src_offset.height as _,
+                    z: params.src_offset.depth as _,
+                },
+                source_size,
+                dst_texture.metal_texture(),
+                dst_slice as _,
+                params.dst_mip_level as _,
+                MTLOrigin {
+                    x: params.dst_offset.width as _,
+                    y: params.dst_offset.height as _,
+                    z: params.dst_offset.depth as _,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_blit(
+        &self,
+        src_texture: &RafxTextureMetal,
+        dst_texture: &RafxTextureMetal,
+        params: &RafxCmdBlitParams,
+    ) -> RafxResult<()> {
+        let src_def = src_texture.texture_def();
+        let dst_def = dst_texture.texture_def();
+        if src_def.extents.depth > 1 || dst_def.extents.depth > 1 {
+            Err("cmd_blit does not support 3D textures on metal")?;
+        }
+
+        if dst_def.format.has_depth_or_stencil() {
+            Err("cmd_blit does not support depth/stencil destinations on metal")?;
+        }
+
+        let device_context = self.queue.device_context();
+        let device = device_context.device();
+        let pipeline = device_context
+            .blit_pipelines()
+            .pipeline(device, dst_def.format.into())?;
+        let sampler = device_context.blit_pipelines().sampler(device)?;
+
+        let slices = if let Some(array_slices) = params.array_slices {
+            vec![(array_slices[0], array_slices[1])]
+        } else {
+            let layer_count = src_def.array_length.min(dst_def.array_length);
+            (0..layer_count as u16).map(|x| (x, x)).collect()
+        };
+
+        // The viewport covers the destination region. If the destination corners are flipped,
+        // flip the source coordinates instead.
+        let src_width = 1.max(src_def.extents.width >> params.src_mip_level) as f32;
+        let src_height = 1.max(src_def.extents.height >> params.src_mip_level) as f32;
+        let src = &params.src_extents;
+        let dst = &params.dst_extents;
+        let (u_min, u_max) = if dst[0].width <= dst[1].width {
+            (src[0].width, src[1].width)
+        } else {
+            (src[1].width, src[0].width)
+        };
+        let (v_min, v_max) = if dst[0].height <= dst[1].height {
+            (src[0].height, src[1].height)
+        } else {
+            (src[1].height, src[0].height)
+        };
+        let args = super::blit::BlitArgsMetal {
+            uv_min: [u_min as f32 / src_width, v_min as f32 / src_height],
+            uv_max: [u_max as f32 / src_width, v_max as f32 / src_height],
+        };
+
+        let viewport = MTLViewport {
+            originX: dst[0].width.min(dst[1].width) as _,
+            originY: dst[0].height.min(dst[1].height) as _,
+            width: (dst[0].width as f64 - dst[1].width as f64).abs(),
+            height: (dst[0].height as f64 - dst[1].height as f64).abs(),
+            znear: 0.0,
+            zfar: 1.0,
+        };
+
+        for (src_slice, dst_slice) in slices {
+            let result: RafxResult<()> = objc::rc::autoreleasepool(|| {
+                // Sample from a view of just the source mip/slice
+                let src_view = src_texture.metal_texture().new_texture_view_from_slice(
+                    src_def.format.into(),
+                    MTLTextureType::D2,
+                    metal_rs::NSRange::new(params.src_mip_level as _, 1),
+                    metal_rs::NSRange::new(src_slice as _, 1),
+                );
+
+                let descriptor = metal_rs::RenderPassDescriptor::new();
+                let color_descriptor = descriptor.color_attachments().object_at(0).unwrap();
+                color_descriptor.set_texture(Some(dst_texture.metal_texture()));
+                color_descriptor.set_level(params.dst_mip_level as _);
+                color_descriptor.set_slice(dst_slice as _);
+                color_descriptor.set_load_action(MTLLoadAction::Load);
+                color_descriptor.set_store_action(MTLStoreAction::Store);
+
+                let mut inner = self.inner.borrow_mut();
+                Self::do_end_current_encoders(&self.queue, &mut *inner, false)?;
+                let render_encoder = inner
+                    .command_buffer
+                    .as_ref()
+                    .unwrap()
+                    .new_render_command_encoder(descriptor)
+                    .to_owned();
+                inner.render_encoder = Some(render_encoder.clone());
+                inner.push_debug_groups_to_current_encoder();
+                self.wait_for_barriers(&*inner)?;
+
+                render_encoder.set_render_pipeline_state(pipeline.as_ref());
+                render_encoder.set_viewport(viewport);
+                render_encoder.set_vertex_bytes(
+                    0,
+                    std::mem::size_of::<super::blit::BlitArgsMetal>() as _,
+                    &args as *const super::blit::BlitArgsMetal as _,
+                );
+                render_encoder.set_fragment_texture(0, Some(src_view.as_ref()));
+                render_encoder.set_fragment_sampler_state(0, Some(sampler.as_ref()));
+                render_encoder.draw_primitives(MTLPrimitiveType::Triangle, 0, 3);
+
+                // The blit's pipeline state should not leak into later commands
+                inner.last_pipeline_type = None;
+                Self::do_end_current_encoders(&self.queue, &mut *inner, false)
+            });
+            result?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_push_group_debug_marker(
+        &self,
+        name: impl AsRef<str>,
+    ) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(encoder) = inner.current_encoder() {
+            encoder.push_debug_group(name.as_ref());
+        }
+        inner.debug_groups.push(name.as_ref().to_string());
+        Ok(())
+    }
+
+    pub fn cmd_pop_group_debug_marker(&self) -> RafxResult<()> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.debug_groups.pop().is_none() {
+            Err("cmd_pop_group_debug_marker called without an active debug marker group")?;
+        }
+        if let Some(encoder) = inner.current_encoder() {
+            encoder.pop_debug_group();
+        }
+        Ok(())
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxDescriptorSetResourceStates;
 use crate::metal::{DescriptorSetLayoutInfo, RafxBufferMetal, RafxDeviceContextMetal};
 use crate::{
     RafxBufferDef, RafxDescriptorKey, RafxDescriptorSetArrayDef, RafxDescriptorUpdate,
@@ -13,6 +15,8 @@ use std::sync::Arc;
 pub struct RafxDescriptorSetHandleMetal {
     argument_buffer_data: Arc<ArgumentBufferData>,
     array_index: u32,
+    #[cfg(feature = "capture")]
+    descriptor_set_array_id: u32,
 }
 
 impl std::fmt::Debug for RafxDescriptorSetHandleMetal {
@@ -47,6 +51,14 @@ impl RafxDescriptorSetHandleMetal {
     pub fn array_index(&self) -> u32 {
         self.array_index
     }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_ref(&self) -> crate::capture::RafxCaptureDescriptorSetRef {
+        crate::capture::RafxCaptureDescriptorSetRef {
+            descriptor_set_array_id: self.descriptor_set_array_id,
+            array_index: self.array_index,
+        }
+    }
 }
 
 pub struct ArgumentBufferData {
@@ -107,6 +119,10 @@ pub struct RafxDescriptorSetArrayMetal {
     root_signature: RafxRootSignature,
     set_index: u32,
     argument_buffer_data: Option<Arc<ArgumentBufferData>>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxDescriptorSetResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl RafxDescriptorSetArrayMetal {
@@ -118,6 +134,21 @@ impl RafxDescriptorSetArrayMetal {
         self.set_index
     }
 
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxDescriptorSetResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states_mut(&mut self) -> &mut RafxDescriptorSetResourceStates {
+        &mut self.resource_states
+    }
+
     pub fn metal_argument_buffer_and_offset(
         &self,
         index: u32,
@@ -145,6 +176,8 @@ impl RafxDescriptorSetArrayMetal {
             .map(|x| RafxDescriptorSetHandleMetal {
                 argument_buffer_data: x.clone(),
                 array_index,
+                #[cfg(feature = "capture")]
+                descriptor_set_array_id: self.capture_id,
             })
     }
 
@@ -220,6 +253,12 @@ impl RafxDescriptorSetArrayMetal {
             root_signature: RafxRootSignature::Metal(root_signature),
             set_index: descriptor_set_array_def.set_index,
             argument_buffer_data: argument_buffer_data.map(|x| Arc::new(x)),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: RafxDescriptorSetResourceStates::new(
+                descriptor_set_array_def.set_index,
+            ),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 
@@ -283,6 +322,13 @@ impl RafxDescriptorSetArrayMetal {
 
         let descriptor = root_signature.descriptor(descriptor_index).unwrap();
 
+        crate::internal_shared::verify_descriptor_update_range(
+            update,
+            &descriptor.name,
+            descriptor.resource_type,
+            descriptor.element_count,
+        )?;
+
         let argument_buffer = self.argument_buffer_data.as_ref().unwrap();
         argument_buffer.encoder.set_argument_buffer(
             argument_buffer.buffer.metal_buffer(),
@@ -319,9 +365,6 @@ impl RafxDescriptorSetArrayMetal {
 
                 let begin_index =
                     descriptor.argument_buffer_id as usize + update.dst_element_offset as usize;
-                assert!(
-                    update.dst_element_offset + samplers.len() as u32 <= descriptor.element_count
-                );
 
                 let mut next_index = begin_index;
                 for sampler in samplers {
@@ -332,6 +375,25 @@ impl RafxDescriptorSetArrayMetal {
                     next_index += 1;
                 }
             }
+            RafxResourceType::TEXTURE | RafxResourceType::TEXTURE_READ_WRITE
+                if update.elements.texture_views.is_some() =>
+            {
+                let texture_views = update.elements.texture_views.unwrap();
+
+                let begin_index =
+                    descriptor.argument_buffer_id as usize + update.dst_element_offset as usize;
+
+                let mut next_index = begin_index;
+                for texture_view in texture_views {
+                    let metal_texture = texture_view.metal_texture_view().unwrap().metal_texture();
+                    argument_buffer
+                        .encoder
+                        .set_texture(next_index as _, metal_texture);
+                    descriptor_resource_pointers[next_index] =
+                        (metal_texture as &metal_rs::ResourceRef).as_ptr();
+                    next_index += 1;
+                }
+            }
             RafxResourceType::TEXTURE | RafxResourceType::TEXTURE_READ_WRITE => {
                 let textures = update.elements.textures.ok_or_else(||
                     format!(
@@ -356,9 +418,6 @@ impl RafxDescriptorSetArrayMetal {
 
                 let begin_index =
                     descriptor.argument_buffer_id as usize + update.dst_element_offset as usize;
-                assert!(
-                    update.dst_element_offset + textures.len() as u32 <= descriptor.element_count
-                );
 
                 let mut next_index = begin_index;
                 if let RafxTextureBindType::UavMipSlice(slice) = texture_bind_type {
@@ -448,9 +507,6 @@ impl RafxDescriptorSetArrayMetal {
 
                 let begin_index =
                     descriptor.argument_buffer_id as usize + update.dst_element_offset as usize;
-                assert!(
-                    update.dst_element_offset + buffers.len() as u32 <= descriptor.element_count
-                );
 
                 // Modify the update data
                 let mut next_index = begin_index;
@@ -1,17 +1,25 @@
+#[cfg(feature = "capture")]
+use crate::capture::RafxCaptureRecorder;
+use crate::internal_shared::RafxMemoryUsageTracker;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxResourceStateTracker;
 use crate::{
     RafxBufferDef, RafxComputePipelineDef, RafxDescriptorSetArrayDef, RafxDeviceContext,
-    RafxDeviceInfo, RafxFormat, RafxGraphicsPipelineDef, RafxQueueType, RafxResourceType,
-    RafxResult, RafxRootSignatureDef, RafxSampleCount, RafxSamplerDef, RafxShaderModuleDefMetal,
-    RafxShaderStageDef, RafxSwapchainDef, RafxTextureDef,
+    RafxDeviceInfo, RafxFormat, RafxGraphicsPipelineDef, RafxMemoryHeapStatistics,
+    RafxMemoryStatistics, RafxQueryPoolDef, RafxQueueType, RafxResourceType, RafxResult,
+    RafxRootSignatureDef, RafxSampleCount, RafxSamplerDef, RafxShaderModuleDefMetal,
+    RafxShaderStageDef, RafxSwapchainDef, RafxTexture, RafxTextureDef, RafxTextureViewDef,
 };
 use raw_window_handle::HasRawWindowHandle;
 use std::sync::Arc;
 
+use crate::metal::blit::BlitPipelinesMetal;
 use crate::metal::features::MetalFeatures;
 use crate::metal::{
-    RafxBufferMetal, RafxDescriptorSetArrayMetal, RafxFenceMetal, RafxPipelineMetal,
-    RafxQueueMetal, RafxRootSignatureMetal, RafxSamplerMetal, RafxSemaphoreMetal, RafxShaderMetal,
-    RafxShaderModuleMetal, RafxSwapchainMetal, RafxTextureMetal,
+    RafxBufferMetal, RafxDescriptorSetArrayMetal, RafxFenceMetal, RafxHeapMetal, RafxPipelineMetal,
+    RafxQueryPoolMetal, RafxQueueMetal, RafxRootSignatureMetal, RafxSamplerMetal,
+    RafxSemaphoreMetal, RafxShaderMetal, RafxShaderModuleMetal, RafxSwapchainMetal,
+    RafxTextureMetal, RafxTextureViewMetal, RafxTimelineSemaphoreMetal,
 };
 
 #[cfg(debug_assertions)]
@@ -29,6 +37,12 @@ pub struct RafxDeviceContextMetalInner {
     #[cfg(feature = "track-device-contexts")]
     next_create_index: AtomicU64,
     metal_features: MetalFeatures,
+    memory_usage_tracker: RafxMemoryUsageTracker,
+    #[cfg(feature = "validate-resource-states")]
+    resource_state_tracker: RafxResourceStateTracker,
+    #[cfg(feature = "capture")]
+    capture_recorder: RafxCaptureRecorder,
+    pub(crate) blit_pipelines: BlitPipelinesMetal,
 
     #[cfg(debug_assertions)]
     #[cfg(feature = "track-device-contexts")]
@@ -47,17 +61,7 @@ impl Drop for RafxDeviceContextMetalInner {
 }
 
 impl RafxDeviceContextMetalInner {
-    pub fn new() -> RafxResult<Self> {
-        let device_info = RafxDeviceInfo {
-            // pretty sure this is consistent across macOS device (maybe not M1, not sure)
-            min_uniform_buffer_offset_alignment: 256,
-            // based on one of the loosest vulkan limits (intel iGPU), can't find official value
-            min_storage_buffer_offset_alignment: 64,
-            upload_buffer_texture_alignment: 16,
-            upload_buffer_texture_row_alignment: 1,
-            supports_clamp_to_border_color: true, //TODO: Check for iOS support
-        };
-
+    pub fn new(adapter_index: Option<u32>) -> RafxResult<Self> {
         #[cfg(debug_assertions)]
         #[cfg(feature = "track-device-contexts")]
         let all_contexts = {
@@ -67,14 +71,113 @@ impl RafxDeviceContextMetalInner {
             all_contexts
         };
 
-        let device = metal_rs::Device::system_default().expect("no device found");
+        let device = if let Some(adapter_index) = adapter_index {
+            let mut all_devices = metal_rs::Device::all();
+            let device_count = all_devices.len();
+            if adapter_index as usize >= device_count {
+                Err(format!(
+                    "Adapter index {} is out of range, {} adapters are available",
+                    adapter_index, device_count
+                ))?;
+            }
+            all_devices.swap_remove(adapter_index as usize)
+        } else {
+            metal_rs::Device::system_default().expect("no device found")
+        };
 
         let metal_features = MetalFeatures::from_device(device.as_ref());
+        let feature_set = metal_features
+            .feature_set()
+            .ok_or("Could not determine the metal feature set supported by the device")?;
+        let max_threads_per_threadgroup = device.max_threads_per_threadgroup();
+
+        let supported_sample_counts = [
+            RafxSampleCount::SampleCount1,
+            RafxSampleCount::SampleCount2,
+            RafxSampleCount::SampleCount4,
+            RafxSampleCount::SampleCount8,
+            RafxSampleCount::SampleCount16,
+        ]
+        .iter()
+        .copied()
+        .filter(|&sample_count| device.supports_texture_sample_count(sample_count.into()))
+        .collect();
+
+        // Tier 2 argument buffers allow large, sparsely populated texture/buffer arrays
+        let supports_bindless_descriptors =
+            metal_features.argument_buffers_tier == metal_rs::MTLArgumentBuffersTier::Tier2;
+
+        let device_info = RafxDeviceInfo {
+            // pretty sure this is consistent across macOS device (maybe not M1, not sure)
+            min_uniform_buffer_offset_alignment: 256,
+            // based on one of the loosest vulkan limits (intel iGPU), can't find official value
+            min_storage_buffer_offset_alignment: 64,
+            upload_buffer_texture_alignment: 16,
+            upload_buffer_texture_row_alignment: 1,
+            supports_clamp_to_border_color: true, //TODO: Check for iOS support
+            // Not yet implemented, requires counter sample buffers and visibility result buffers
+            supports_timestamp_queries: false,
+            supports_occlusion_queries: false,
+            supports_pipeline_statistics_queries: false,
+            timestamp_period_ns: 0.0,
+            // MTLSharedEvent has the same OS requirements as the MTLEvent used by RafxSemaphore
+            supports_timeline_semaphores: true,
+            // Pipelines are not cached by rafx on metal
+            pipeline_cache_uuid: [0; 16],
+            max_texture_dimension_2d: feature_set.max_2d_texture_size(),
+            max_texture_dimension_3d: feature_set.max_3d_texture_size(),
+            max_texture_dimension_cube: feature_set.max_cube_map_texture_size(),
+            max_texture_array_layers: feature_set.max_array_layers(),
+            max_sampler_anisotropy: if feature_set.supports_sampler_max_anisotropy() {
+                16.0
+            } else {
+                1.0
+            },
+            max_color_render_targets: feature_set.max_color_render_targets(),
+            // Vertex buffers share the buffer argument table with the argument buffers and push
+            // constants (see vertex_buffer_adjusted_buffer_index())
+            max_vertex_input_bindings: feature_set.max_buffer_argument_entries()
+                - crate::METAL_PUSH_CONSTANT_BUFFER_INDEX
+                - 1,
+            max_vertex_attributes: feature_set.max_vertex_attributes(),
+            max_compute_workgroup_size: [
+                max_threads_per_threadgroup.width as u32,
+                max_threads_per_threadgroup.height as u32,
+                max_threads_per_threadgroup.depth as u32,
+            ],
+            max_compute_workgroup_invocations: feature_set.max_threads_per_threadgroup(),
+            // Metal does not document a limit on the number of threadgroups per dispatch
+            max_compute_workgroup_count: [u32::MAX; 3],
+            supported_sample_counts,
+            supports_bc_texture_compression: feature_set.supports_bc_pixel_formats(),
+            supports_astc_texture_compression: feature_set.supports_astc_pixel_formats(),
+            supports_etc2_texture_compression: feature_set.supports_eac_etc_pixel_formats(),
+            // Metal has no geometry shaders, and its tessellation model (compute kernel + post
+            // tessellation vertex function) does not map to vulkan-style tessellation shaders
+            supports_geometry_shaders: false,
+            supports_tessellation_shaders: false,
+            // Metal always rasterizes lines one pixel wide
+            supports_wide_lines: false,
+            max_line_width: 1.0,
+            supports_bindless_descriptors,
+            // Apple documents 500,000 textures or buffers per argument buffer for tier 2
+            max_bindless_descriptor_count: if supports_bindless_descriptors {
+                500_000
+            } else {
+                0
+            },
+        };
 
         Ok(RafxDeviceContextMetalInner {
             device_info,
             device,
             metal_features,
+            memory_usage_tracker: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_state_tracker: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_recorder: Default::default(),
+            blit_pipelines: Default::default(),
             destroyed: AtomicBool::new(false),
 
             #[cfg(debug_assertions)]
@@ -165,6 +268,47 @@ impl RafxDeviceContextMetal {
         &self.inner.device_info
     }
 
+    // Metal does not expose individual heaps, so a single heap is reported. The recommended working
+    // set size is used as the budget
+    pub fn memory_statistics(&self) -> RafxResult<RafxMemoryStatistics> {
+        let mut memory_statistics = self.inner.memory_usage_tracker.memory_statistics();
+        let device = self.device();
+        let budget_bytes = device.recommended_max_working_set_size();
+        memory_statistics.heaps.push(RafxMemoryHeapStatistics {
+            device_local: true,
+            size_bytes: budget_bytes,
+            budget_bytes,
+            usage_bytes: device.current_allocated_size() as u64,
+        });
+        Ok(memory_statistics)
+    }
+
+    // No pipeline cache is kept, so there is never any data to save or load
+    pub fn pipeline_cache_data(&self) -> RafxResult<Vec<u8>> {
+        Ok(Vec::default())
+    }
+
+    pub fn load_pipeline_cache_data(
+        &self,
+        _data: &[u8],
+    ) -> RafxResult<()> {
+        Ok(())
+    }
+
+    pub(crate) fn memory_usage_tracker(&self) -> &RafxMemoryUsageTracker {
+        &self.inner.memory_usage_tracker
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_state_tracker(&self) -> &RafxResourceStateTracker {
+        &self.inner.resource_state_tracker
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &RafxCaptureRecorder {
+        &self.inner.capture_recorder
+    }
+
     pub fn device(&self) -> &metal_rs::Device {
         &self.inner.device
     }
@@ -173,6 +317,10 @@ impl RafxDeviceContextMetal {
         &self.inner.metal_features
     }
 
+    pub(crate) fn blit_pipelines(&self) -> &BlitPipelinesMetal {
+        &self.inner.blit_pipelines
+    }
+
     pub fn new(inner: Arc<RafxDeviceContextMetalInner>) -> RafxResult<Self> {
         Ok(RafxDeviceContextMetal {
             inner,
@@ -197,6 +345,13 @@ impl RafxDeviceContextMetal {
         RafxSemaphoreMetal::new(self)
     }
 
+    pub fn create_timeline_semaphore(
+        &self,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreMetal> {
+        RafxTimelineSemaphoreMetal::new(self, initial_value)
+    }
+
     pub fn create_swapchain(
         &self,
         raw_window_handle: &dyn HasRawWindowHandle,
@@ -212,6 +367,13 @@ impl RafxDeviceContextMetal {
         RafxFenceMetal::wait_for_fences(self, fences)
     }
 
+    pub fn create_query_pool(
+        &self,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolMetal> {
+        RafxQueryPoolMetal::new(self, query_pool_def)
+    }
+
     pub fn create_sampler(
         &self,
         sampler_def: &RafxSamplerDef,
@@ -226,6 +388,14 @@ impl RafxDeviceContextMetal {
         RafxTextureMetal::new(self, texture_def)
     }
 
+    pub fn create_texture_view(
+        &self,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewMetal> {
+        RafxTextureViewMetal::new(self, texture, texture_view_def)
+    }
+
     pub fn create_buffer(
         &self,
         buffer_def: &RafxBufferDef,
@@ -233,6 +403,45 @@ impl RafxDeviceContextMetal {
         RafxBufferMetal::new(self, buffer_def)
     }
 
+    pub fn create_heap(
+        &self,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeapMetal> {
+        RafxHeapMetal::new(self, heap_def)
+    }
+
+    pub fn texture_memory_requirements(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        RafxTextureMetal::memory_requirements(self, texture_def)
+    }
+
+    pub fn buffer_memory_requirements(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        Ok(RafxBufferMetal::memory_requirements(self, buffer_def))
+    }
+
+    pub fn create_texture_in_heap(
+        &self,
+        heap: &RafxHeapMetal,
+        offset: u64,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureMetal> {
+        RafxTextureMetal::new_in_heap(self, heap, offset, texture_def)
+    }
+
+    pub fn create_buffer_in_heap(
+        &self,
+        heap: &RafxHeapMetal,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBufferMetal> {
+        RafxBufferMetal::new_in_heap(self, heap, offset, buffer_def)
+    }
+
     pub fn create_shader(
         &self,
         stages: Vec<RafxShaderStageDef>,
@@ -1,7 +1,7 @@
 use crate::metal::RafxDeviceContextMetal;
-use crate::{RafxFenceStatus, RafxResult};
+use crate::{RafxError, RafxFenceStatus, RafxResult};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub struct RafxFenceMetal {
     _device_context: RafxDeviceContextMetal,
@@ -9,6 +9,11 @@ pub struct RafxFenceMetal {
     // Set to true when an operation is scheduled to signal this fence
     // Cleared when an operation is scheduled to consume this fence
     submitted: AtomicBool,
+    // Set by the completion handler if a command buffer signaling this fence failed
+    completion_error: Arc<Mutex<Option<RafxError>>>,
+    // Used internally to identify the fence for validation
+    #[cfg(feature = "validate-resource-states")]
+    fence_id: u32,
 }
 
 impl RafxFenceMetal {
@@ -19,13 +24,38 @@ impl RafxFenceMetal {
             _device_context: device_context.clone(),
             mtl_semaphore: Arc::new(mtl_semaphore),
             submitted: AtomicBool::new(false),
+            completion_error: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            fence_id: crate::internal_shared::NEXT_FENCE_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn fence_id(&self) -> u32 {
+        self.fence_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn device_context(&self) -> &RafxDeviceContextMetal {
+        &self._device_context
+    }
+
     pub(crate) fn metal_dispatch_semaphore(&self) -> &Arc<dispatch::Semaphore> {
         &self.mtl_semaphore
     }
 
+    pub(crate) fn completion_error(&self) -> &Arc<Mutex<Option<RafxError>>> {
+        &self.completion_error
+    }
+
+    // Report (once) the error of any failed command buffer that signaled this fence
+    fn take_completion_error(&self) -> RafxResult<()> {
+        match self.completion_error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
     pub(crate) fn submitted(&self) -> bool {
         self.submitted.load(Ordering::Relaxed)
     }
@@ -36,7 +66,7 @@ impl RafxFenceMetal {
         }
 
         self.set_submitted(false);
-        Ok(())
+        self.take_completion_error()
     }
 
     pub fn wait_for_fences(
@@ -72,6 +102,7 @@ impl RafxFenceMetal {
             }
 
             if is_ready {
+                self.take_completion_error()?;
                 Ok(RafxFenceStatus::Complete)
             } else {
                 Ok(RafxFenceStatus::Incomplete)
@@ -0,0 +1,76 @@
+use crate::metal::RafxDeviceContextMetal;
+use crate::{RafxHeapDef, RafxMemoryRequirements, RafxResult};
+use metal_rs::MTLHeapType;
+use objc::{msg_send, sel, sel_impl};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxHeapMetalInner {
+    _device_context: RafxDeviceContextMetal,
+    heap_def: RafxHeapDef,
+    heap: metal_rs::Heap,
+}
+
+/// A placement MTLHeap, buffers and textures are created in it at explicit offsets
+#[derive(Clone, Debug)]
+pub struct RafxHeapMetal {
+    inner: Arc<RafxHeapMetalInner>,
+}
+
+// for metal_rs::Heap
+unsafe impl Send for RafxHeapMetal {}
+unsafe impl Sync for RafxHeapMetal {}
+
+impl RafxHeapMetal {
+    pub fn heap_def(&self) -> &RafxHeapDef {
+        &self.inner.heap_def
+    }
+
+    pub fn metal_heap(&self) -> &metal_rs::HeapRef {
+        self.inner.heap.as_ref()
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextMetal,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<Self> {
+        if heap_def.size == 0 {
+            return Err("Cannot create a heap with a size of 0")?;
+        }
+
+        let descriptor = metal_rs::HeapDescriptor::new();
+        descriptor.set_size(heap_def.size);
+        descriptor.set_storage_mode(heap_def.memory_usage.mtl_storage_mode());
+        descriptor.set_cpu_cache_mode(heap_def.memory_usage.mtl_cpu_cache_mode());
+        // metal_rs does not expose setType
+        unsafe {
+            let () = msg_send![descriptor.as_ref(), setType: MTLHeapType::Placement];
+        }
+
+        let heap = device_context.device().new_heap(descriptor.as_ref());
+
+        let inner = RafxHeapMetalInner {
+            _device_context: device_context.clone(),
+            heap_def: heap_def.clone(),
+            heap,
+        };
+
+        Ok(RafxHeapMetal {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub(crate) fn verify_placement(
+        &self,
+        offset: u64,
+        size_and_align: &metal_rs::MTLSizeAndAlign,
+    ) -> RafxResult<()> {
+        self.inner.heap_def.verify_placement(
+            offset,
+            &RafxMemoryRequirements {
+                size: size_and_align.size,
+                alignment: size_and_align.align,
+            },
+        )
+    }
+}
@@ -0,0 +1,126 @@
+use crate::RafxResult;
+use fnv::FnvHashMap;
+use metal_rs::{MTLLanguageVersion, MTLPixelFormat, MTLSamplerAddressMode, MTLSamplerMinMagFilter};
+use std::sync::Mutex;
+
+// Metal blit encoders can only copy texels 1:1, so scaled/filtered blits draw a triangle that
+// samples the source texture into the destination instead.
+const BLIT_SHADER_SOURCE: &str = r#"
+#include <metal_stdlib>
+using namespace metal;
+
+struct BlitArgs {
+    float2 uv_min;
+    float2 uv_max;
+};
+
+struct BlitVertexOut {
+    float4 position [[position]];
+    float2 uv;
+};
+
+vertex BlitVertexOut blit_vertex(
+    uint vertex_id [[vertex_id]],
+    constant BlitArgs& args [[buffer(0)]]
+) {
+    // A single triangle that covers the viewport
+    float2 t = float2((vertex_id << 1) & 2, vertex_id & 2);
+    BlitVertexOut out;
+    out.position = float4(t.x * 2.0 - 1.0, 1.0 - t.y * 2.0, 0.0, 1.0);
+    out.uv = args.uv_min + t * (args.uv_max - args.uv_min);
+    return out;
+}
+
+fragment float4 blit_fragment(
+    BlitVertexOut in [[stage_in]],
+    texture2d<float> src [[texture(0)]],
+    sampler src_sampler [[sampler(0)]]
+) {
+    return src.sample(src_sampler, in.uv);
+}
+"#;
+
+/// Must match BlitArgs in the shader
+#[repr(C)]
+pub(crate) struct BlitArgsMetal {
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+struct BlitShadersMetal {
+    vertex_function: metal_rs::Function,
+    fragment_function: metal_rs::Function,
+    sampler: metal_rs::SamplerState,
+}
+
+/// Lazily-created shaders and per-format pipelines used to implement cmd_blit
+#[derive(Default)]
+pub(crate) struct BlitPipelinesMetal {
+    shaders: Mutex<Option<BlitShadersMetal>>,
+    pipelines: Mutex<FnvHashMap<MTLPixelFormat, metal_rs::RenderPipelineState>>,
+}
+
+impl BlitPipelinesMetal {
+    pub(crate) fn sampler(
+        &self,
+        device: &metal_rs::DeviceRef,
+    ) -> RafxResult<metal_rs::SamplerState> {
+        let mut shaders = self.shaders.lock().unwrap();
+        Ok(Self::get_or_create_shaders(&mut *shaders, device)?
+            .sampler
+            .clone())
+    }
+
+    pub(crate) fn pipeline(
+        &self,
+        device: &metal_rs::DeviceRef,
+        format: MTLPixelFormat,
+    ) -> RafxResult<metal_rs::RenderPipelineState> {
+        let mut pipelines = self.pipelines.lock().unwrap();
+        if let Some(pipeline) = pipelines.get(&format) {
+            return Ok(pipeline.clone());
+        }
+
+        let mut shaders = self.shaders.lock().unwrap();
+        let shaders = Self::get_or_create_shaders(&mut *shaders, device)?;
+
+        let descriptor = metal_rs::RenderPipelineDescriptor::new();
+        descriptor.set_label("rafx blit");
+        descriptor.set_vertex_function(Some(shaders.vertex_function.as_ref()));
+        descriptor.set_fragment_function(Some(shaders.fragment_function.as_ref()));
+        descriptor
+            .color_attachments()
+            .object_at(0)
+            .unwrap()
+            .set_pixel_format(format);
+
+        let pipeline = device.new_render_pipeline_state(descriptor.as_ref())?;
+        pipelines.insert(format, pipeline.clone());
+        Ok(pipeline)
+    }
+
+    fn get_or_create_shaders<'a>(
+        shaders: &'a mut Option<BlitShadersMetal>,
+        device: &metal_rs::DeviceRef,
+    ) -> RafxResult<&'a BlitShadersMetal> {
+        if shaders.is_none() {
+            let compile_options = metal_rs::CompileOptions::new();
+            compile_options.set_language_version(MTLLanguageVersion::V2_1);
+            let library = device.new_library_with_source(BLIT_SHADER_SOURCE, &compile_options)?;
+
+            let sampler_descriptor = metal_rs::SamplerDescriptor::new();
+            sampler_descriptor.set_min_filter(MTLSamplerMinMagFilter::Linear);
+            sampler_descriptor.set_mag_filter(MTLSamplerMinMagFilter::Linear);
+            sampler_descriptor.set_address_mode_s(MTLSamplerAddressMode::ClampToEdge);
+            sampler_descriptor.set_address_mode_t(MTLSamplerAddressMode::ClampToEdge);
+
+            *shaders = Some(BlitShadersMetal {
+                vertex_function: library.get_function("blit_vertex", None)?,
+                fragment_function: library.get_function("blit_fragment", None)?,
+                sampler: device.new_sampler(&sampler_descriptor),
+            });
+        }
+
+        Ok(shaders.as_ref().unwrap())
+    }
+}
@@ -1,16 +1,33 @@
 use crate::{
     RafxBlendFactor, RafxBlendOp, RafxColorClearValue, RafxCompareOp, RafxCullMode, RafxFillMode,
     RafxFilterType, RafxFrontFace, RafxIndexType, RafxLoadOp, RafxMemoryUsage, RafxMipMapMode,
-    RafxPrimitiveTopology, RafxSampleCount, RafxStencilOp, RafxStoreOp, RafxVertexAttributeRate,
+    RafxPrimitiveTopology, RafxSampleCount, RafxStencilOp, RafxStoreOp, RafxTextureViewDimensions,
+    RafxVertexAttributeRate,
 };
 use cocoa_foundation::foundation::NSUInteger;
 use metal_rs::{
     MTLBlendFactor, MTLBlendOperation, MTLCPUCacheMode, MTLClearColor, MTLCompareFunction,
     MTLCullMode, MTLIndexType, MTLLoadAction, MTLPrimitiveTopologyClass, MTLPrimitiveType,
     MTLResourceOptions, MTLSamplerMinMagFilter, MTLSamplerMipFilter, MTLStencilOperation,
-    MTLStorageMode, MTLStoreAction, MTLTriangleFillMode, MTLVertexStepFunction, MTLWinding,
+    MTLStorageMode, MTLStoreAction, MTLTextureType, MTLTriangleFillMode, MTLVertexStepFunction,
+    MTLWinding,
 };
 
+// Multisampled 2D textures must be viewed as D2Multisample, which depends on the texture
+impl Into<MTLTextureType> for RafxTextureViewDimensions {
+    fn into(self) -> MTLTextureType {
+        match self {
+            RafxTextureViewDimensions::Dim1D => MTLTextureType::D1,
+            RafxTextureViewDimensions::Dim1DArray => MTLTextureType::D1Array,
+            RafxTextureViewDimensions::Dim2D => MTLTextureType::D2,
+            RafxTextureViewDimensions::Dim2DArray => MTLTextureType::D2Array,
+            RafxTextureViewDimensions::Dim3D => MTLTextureType::D3,
+            RafxTextureViewDimensions::Cube => MTLTextureType::Cube,
+            RafxTextureViewDimensions::CubeArray => MTLTextureType::CubeArray,
+        }
+    }
+}
+
 impl Into<MTLSamplerMinMagFilter> for RafxFilterType {
     fn into(self) -> MTLSamplerMinMagFilter {
         match self {
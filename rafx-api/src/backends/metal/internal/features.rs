@@ -323,6 +323,13 @@ impl MetalFeatures {
         }
     }
 
+    // The most capable feature set supported by the device, regardless of OS
+    pub fn feature_set(&self) -> Option<MTLFeatureSet> {
+        self.feature_set_macos
+            .or(self.feature_set_ios)
+            .or(self.feature_set_tvos)
+    }
+
     pub fn pixel_format_capabilities(
         &self,
         pixel_format: MTLPixelFormat,
@@ -1,3 +1,4 @@
+pub mod blit;
 pub mod conversions;
 pub mod features;
 pub mod util;
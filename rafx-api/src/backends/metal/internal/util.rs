@@ -1,15 +1,19 @@
 use crate::{
     RafxAddressMode, RafxBlendState, RafxBlendStateTargets, RafxColorRenderTargetBinding,
-    RafxDepthState, RafxDeviceInfo, RafxResourceType, RafxStoreOp,
+    RafxDepthState, RafxDeviceInfo, RafxError, RafxResourceType, RafxStoreOp,
 };
-use cocoa_foundation::foundation::NSUInteger;
+use cocoa_foundation::foundation::{NSInteger, NSUInteger};
 use metal_rs::{
-    MTLArgumentAccess, MTLCompareFunction, MTLDataType, MTLResourceUsage, MTLSamplerAddressMode,
-    MTLStoreAction, RenderPipelineColorAttachmentDescriptorArrayRef,
+    CommandBufferRef, MTLArgumentAccess, MTLCommandBufferError, MTLCommandBufferStatus,
+    MTLCompareFunction, MTLDataType, MTLResourceUsage, MTLSamplerAddressMode, MTLStoreAction,
+    RenderPipelineColorAttachmentDescriptorArrayRef,
 };
+use objc::runtime::Object;
+use objc::{msg_send, sel, sel_impl};
 
 pub fn vertex_buffer_adjusted_buffer_index(binding: u32) -> NSUInteger {
     // Argument buffers will be 0-4
+    // push constants will be 4 (METAL_PUSH_CONSTANT_BUFFER_INDEX)
     // vertex buffers will be 30 - n
     (30 - binding) as _
 }
@@ -172,6 +176,37 @@ pub(crate) fn depth_state_to_descriptor(
     descriptor
 }
 
+// Returns the error for a completed command buffer that failed, mapped onto RafxError
+pub(crate) fn command_buffer_error(command_buffer: &CommandBufferRef) -> Option<RafxError> {
+    if command_buffer.status() != MTLCommandBufferStatus::Error {
+        return None;
+    }
+
+    let code: NSInteger = unsafe {
+        let error: *mut Object = msg_send![command_buffer, error];
+        if error.is_null() {
+            0
+        } else {
+            msg_send![error, code]
+        }
+    };
+
+    let error = if code == MTLCommandBufferError::OutOfMemory as NSInteger {
+        RafxError::OutOfDeviceMemory
+    } else if code == MTLCommandBufferError::DeviceRemoved as NSInteger
+        || code == MTLCommandBufferError::Blacklisted as NSInteger
+    {
+        RafxError::DeviceLost
+    } else {
+        RafxError::StringError(format!(
+            "Command buffer failed with MTLCommandBufferError {}",
+            code
+        ))
+    };
+
+    Some(error)
+}
+
 pub fn color_render_target_binding_mtl_store_op(
     color_binding: &RafxColorRenderTargetBinding
 ) -> MTLStoreAction {
@@ -10,12 +10,24 @@ pub use swapchain::*;
 mod texture;
 pub use texture::*;
 
+mod texture_view;
+pub use texture_view::*;
+
 mod semaphore;
 pub use semaphore::*;
 
+mod timeline_semaphore;
+pub use timeline_semaphore::*;
+
 mod fence;
 pub use fence::*;
 
+mod heap;
+pub use heap::*;
+
+mod query_pool;
+pub use query_pool::*;
+
 mod queue;
 pub use queue::*;
 
@@ -1,8 +1,12 @@
 use crate::metal::RafxDeviceContextMetal;
 use crate::{
     RafxComputePipelineDef, RafxGraphicsPipelineDef, RafxPipelineType, RafxResult,
-    RafxRootSignature, RafxShaderStageFlags,
+    RafxRootSignature, RafxShaderStageFlags, RafxSpecializationConstant,
+    RafxSpecializationConstantValue,
 };
+use cocoa_foundation::foundation::NSUInteger;
+use metal_rs::{FunctionConstantValues, MTLDataType};
+use std::ffi::c_void;
 
 fn metal_entry_point_name(name: &str) -> &str {
     // "main" is not an allowed entry point name. spirv_cross adds a 0 to the end of any
@@ -14,6 +18,48 @@ fn metal_entry_point_name(name: &str) -> &str {
     }
 }
 
+fn function_constant_values(
+    specialization_constants: &[RafxSpecializationConstant]
+) -> Option<FunctionConstantValues> {
+    if specialization_constants.is_empty() {
+        return None;
+    }
+
+    // Metal copies the value when it is set, so pointing at these locals is fine
+    let constant_values = FunctionConstantValues::new();
+    for constant in specialization_constants {
+        let index = constant.constant_id as NSUInteger;
+        match constant.value {
+            RafxSpecializationConstantValue::Bool(value) => constant_values
+                .set_constant_value_at_index(
+                    &value as *const bool as *const c_void,
+                    MTLDataType::Bool,
+                    index,
+                ),
+            RafxSpecializationConstantValue::Int(value) => constant_values
+                .set_constant_value_at_index(
+                    &value as *const i32 as *const c_void,
+                    MTLDataType::Int,
+                    index,
+                ),
+            RafxSpecializationConstantValue::UInt(value) => constant_values
+                .set_constant_value_at_index(
+                    &value as *const u32 as *const c_void,
+                    MTLDataType::UInt,
+                    index,
+                ),
+            RafxSpecializationConstantValue::Float(value) => constant_values
+                .set_constant_value_at_index(
+                    &value as *const f32 as *const c_void,
+                    MTLDataType::Float,
+                    index,
+                ),
+        }
+    }
+
+    Some(constant_values)
+}
+
 #[derive(Debug)]
 enum MetalPipelineState {
     Graphics(metal_rs::RenderPipelineState),
@@ -37,6 +83,8 @@ pub(crate) struct PipelineRenderEncoderInfo {
     pub(crate) mtl_front_facing_winding: metal_rs::MTLWinding,
     pub(crate) mtl_depth_bias: f32,
     pub(crate) mtl_depth_bias_slope_scaled: f32,
+    // If true, the depth bias is set with cmd_set_depth_bias instead of when binding the pipeline
+    pub(crate) dynamic_depth_bias: bool,
     pub(crate) mtl_depth_clip_mode: metal_rs::MTLDepthClipMode,
     pub(crate) mtl_depth_stencil_state: Option<metal_rs::DepthStencilState>,
     pub(crate) mtl_primitive_type: metal_rs::MTLPrimitiveType,
@@ -55,9 +103,16 @@ pub struct RafxPipelineMetal {
 
     pub(crate) render_encoder_info: Option<PipelineRenderEncoderInfo>,
     pub(crate) compute_encoder_info: Option<PipelineComputeEncoderInfo>,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl RafxPipelineMetal {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
     pub fn pipeline_type(&self) -> RafxPipelineType {
         self.pipeline_type
     }
@@ -80,10 +135,19 @@ impl RafxPipelineMetal {
         }
     }
 
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Metal pipeline states are immutable, their label can only be set on the descriptor
+    }
+
     pub fn new_graphics_pipeline(
         device_context: &RafxDeviceContextMetal,
         pipeline_def: &RafxGraphicsPipelineDef,
     ) -> RafxResult<Self> {
+        pipeline_def.verify_device_limits(device_context.device_info())?;
+
         let pipeline = metal_rs::RenderPipelineDescriptor::new();
 
         let mut vertex_function = None;
@@ -103,7 +167,10 @@ impl RafxPipelineMetal {
                         .metal_shader_module()
                         .unwrap()
                         .library()
-                        .get_function(entry_point, None)?,
+                        .get_function(
+                            entry_point,
+                            function_constant_values(&stage.specialization_constants),
+                        )?,
                 );
             }
 
@@ -120,7 +187,10 @@ impl RafxPipelineMetal {
                         .metal_shader_module()
                         .unwrap()
                         .library()
-                        .get_function(entry_point, None)?,
+                        .get_function(
+                            entry_point,
+                            function_constant_values(&stage.specialization_constants),
+                        )?,
                 );
             }
         }
@@ -215,6 +285,7 @@ impl RafxPipelineMetal {
             mtl_front_facing_winding,
             mtl_depth_bias,
             mtl_depth_bias_slope_scaled,
+            dynamic_depth_bias: pipeline_def.rasterizer_state.dynamic_depth_bias,
             mtl_depth_clip_mode,
             mtl_depth_stencil_state,
             mtl_primitive_type,
@@ -226,6 +297,8 @@ impl RafxPipelineMetal {
             pipeline: MetalPipelineState::Graphics(pipeline),
             render_encoder_info: Some(render_encoder_info),
             compute_encoder_info: None,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 
@@ -251,7 +324,10 @@ impl RafxPipelineMetal {
                         .metal_shader_module()
                         .unwrap()
                         .library()
-                        .get_function(entry_point, None)?,
+                        .get_function(
+                            entry_point,
+                            function_constant_values(&stage.specialization_constants),
+                        )?,
                 );
 
                 compute_threads_per_group = stage.reflection.compute_threads_per_group;
@@ -274,6 +350,8 @@ impl RafxPipelineMetal {
             pipeline: MetalPipelineState::Compute(pipeline),
             render_encoder_info: None,
             compute_encoder_info: Some(compute_encoder_info),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 }
@@ -0,0 +1,27 @@
+use crate::metal::RafxDeviceContextMetal;
+use crate::{RafxQueryPoolDef, RafxResult};
+
+/// Query pools are not yet supported by the metal backend. Timestamps and pipeline statistics
+/// require counter sample buffers, and occlusion queries require binding a visibility result buffer
+/// when the render pass begins. `RafxDeviceInfo` reports all query types as unsupported.
+#[derive(Debug)]
+pub struct RafxQueryPoolMetal {
+    query_pool_def: RafxQueryPoolDef,
+}
+
+impl RafxQueryPoolMetal {
+    pub fn new(
+        _device_context: &RafxDeviceContextMetal,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolMetal> {
+        query_pool_def.verify();
+        Err(format!(
+            "Query type {:?} is not supported by the metal backend",
+            query_pool_def.query_type
+        ))?
+    }
+
+    pub fn query_pool_def(&self) -> &RafxQueryPoolDef {
+        &self.query_pool_def
+    }
+}
@@ -1,8 +1,9 @@
 use crate::metal::{
     BarrierFlagsMetal, RafxCommandBufferMetal, RafxCommandPoolMetal, RafxDeviceContextMetal,
-    RafxFenceMetal, RafxSemaphoreMetal, RafxSwapchainMetal,
+    RafxFenceMetal, RafxSemaphoreMetal, RafxSwapchainMetal, RafxTimelineSemaphoreMetal,
 };
 use crate::{RafxCommandPoolDef, RafxPresentSuccessResult, RafxQueueType, RafxResult};
+use metal_rs::CommandBufferRef;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
 use std::sync::Arc;
@@ -115,8 +116,10 @@ impl RafxQueueMetal {
     fn submit_semaphore_wait(
         &self,
         wait_semaphores: &[&RafxSemaphoreMetal],
+        wait_timeline_semaphores: &[(&RafxTimelineSemaphoreMetal, u64)],
     ) {
-        let wait_command_buffer_required = wait_semaphores.iter().any(|x| x.signal_available());
+        let wait_command_buffer_required = wait_semaphores.iter().any(|x| x.signal_available())
+            || !wait_timeline_semaphores.is_empty();
 
         if wait_command_buffer_required {
             let wait_command_buffer = self
@@ -130,6 +133,11 @@ impl RafxQueueMetal {
                 }
             }
 
+            for (wait_semaphore, value) in wait_timeline_semaphores {
+                wait_command_buffer
+                    .encode_wait_for_event(wait_semaphore.metal_shared_event(), *value);
+            }
+
             wait_command_buffer.commit();
         }
     }
@@ -139,6 +147,8 @@ impl RafxQueueMetal {
         command_buffers: &[&RafxCommandBufferMetal],
         wait_semaphores: &[&RafxSemaphoreMetal],
         signal_semaphores: &[&RafxSemaphoreMetal],
+        wait_timeline_semaphores: &[(&RafxTimelineSemaphoreMetal, u64)],
+        signal_timeline_semaphores: &[(&RafxTimelineSemaphoreMetal, u64)],
         signal_fence: Option<&RafxFenceMetal>,
     ) -> RafxResult<()> {
         objc::rc::autoreleasepool(|| {
@@ -154,15 +164,22 @@ impl RafxQueueMetal {
                 let command_count = command_buffers.len();
                 let complete_count = Arc::new(AtomicUsize::new(0));
                 let dispatch_semaphore = signal_fence.metal_dispatch_semaphore().clone();
-                let block = block::ConcreteBlock::new(move |_command_buffer_ref| {
-                    // Add 1 because fetch_add returns the value from before the add
-                    let complete =
-                        complete_count.fetch_add(1, Ordering::Relaxed) + 1 == command_count;
-                    if complete {
-                        dispatch_semaphore.signal();
-                    }
-                })
-                .copy();
+                let completion_error = signal_fence.completion_error().clone();
+                let block =
+                    block::ConcreteBlock::new(move |command_buffer_ref: &CommandBufferRef| {
+                        if let Some(error) = super::util::command_buffer_error(command_buffer_ref) {
+                            log::error!("Command buffer failed: {}", error);
+                            *completion_error.lock().unwrap() = Some(error);
+                        }
+
+                        // Add 1 because fetch_add returns the value from before the add
+                        let complete =
+                            complete_count.fetch_add(1, Ordering::Relaxed) + 1 == command_count;
+                        if complete {
+                            dispatch_semaphore.signal();
+                        }
+                    })
+                    .copy();
 
                 for command_buffer in command_buffers {
                     command_buffer
@@ -182,7 +199,16 @@ impl RafxQueueMetal {
                 signal_semaphore.set_signal_available(true);
             }
 
-            self.submit_semaphore_wait(wait_semaphores);
+            for (signal_semaphore, value) in signal_timeline_semaphores {
+                command_buffers
+                    .last()
+                    .unwrap()
+                    .metal_command_buffer()
+                    .unwrap()
+                    .encode_signal_event(signal_semaphore.metal_shared_event(), *value);
+            }
+
+            self.submit_semaphore_wait(wait_semaphores, wait_timeline_semaphores);
 
             for command_buffer in command_buffers {
                 command_buffer.end_current_encoders(false)?;
@@ -201,7 +227,7 @@ impl RafxQueueMetal {
         _image_index: u32,
     ) -> RafxResult<RafxPresentSuccessResult> {
         objc::rc::autoreleasepool(|| {
-            self.submit_semaphore_wait(wait_semaphores);
+            self.submit_semaphore_wait(wait_semaphores, &[]);
 
             let command_buffer = self.inner.queue.new_command_buffer();
             let drawable = swapchain.take_drawable().unwrap();
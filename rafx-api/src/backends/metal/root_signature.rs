@@ -1,7 +1,7 @@
 use crate::metal::{RafxDeviceContextMetal, RafxSamplerMetal};
 use crate::{
-    RafxDescriptorIndex, RafxPipelineType, RafxResourceType, RafxResult, RafxRootSignatureDef,
-    MAX_DESCRIPTOR_SET_LAYOUTS,
+    RafxDescriptorIndex, RafxError, RafxPipelineType, RafxPushConstantIndex, RafxResourceType,
+    RafxResult, RafxRootSignatureDef, RafxShaderStageFlags, MAX_DESCRIPTOR_SET_LAYOUTS,
 };
 use cocoa_foundation::foundation::NSUInteger;
 use fnv::FnvHashMap;
@@ -40,6 +40,13 @@ pub(crate) struct DescriptorInfo {
     pub(crate) argument_buffer_id: NSUInteger,
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct PushConstantInfo {
+    pub(crate) name: Option<String>,
+    pub(crate) size_in_bytes: u32,
+    pub(crate) used_in_shader_stages: RafxShaderStageFlags,
+}
+
 #[derive(Default, Debug)]
 pub(crate) struct DescriptorSetLayoutInfo {
     // Settable descriptors, immutable samplers are omitted
@@ -64,6 +71,10 @@ pub(crate) struct RafxRootSignatureMetalInner {
     pub(crate) layouts: [DescriptorSetLayoutInfo; MAX_DESCRIPTOR_SET_LAYOUTS],
     pub(crate) descriptors: Vec<DescriptorInfo>,
     pub(crate) name_to_descriptor_index: FnvHashMap<String, RafxDescriptorIndex>,
+    pub(crate) push_constants: Vec<PushConstantInfo>,
+    pub(crate) name_to_push_constant_index: FnvHashMap<String, RafxPushConstantIndex>,
+    #[cfg(feature = "capture")]
+    pub(crate) capture_id: u32,
 
     // --- metal-specific ---
     // Keeps them in scope so they don't drop
@@ -85,6 +96,11 @@ pub struct RafxRootSignatureMetal {
 }
 
 impl RafxRootSignatureMetal {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn device_context(&self) -> &RafxDeviceContextMetal {
         &self.inner.device_context
     }
@@ -112,6 +128,22 @@ impl RafxRootSignatureMetal {
             .copied()
     }
 
+    pub fn find_push_constant_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxPushConstantIndex> {
+        self.inner.name_to_push_constant_index.get(name).copied()
+    }
+
+    pub(crate) fn push_constant(
+        &self,
+        push_constant_index: RafxPushConstantIndex,
+    ) -> Option<&PushConstantInfo> {
+        self.inner
+            .push_constants
+            .get(push_constant_index.0 as usize)
+    }
+
     pub(crate) fn descriptor(
         &self,
         descriptor_index: RafxDescriptorIndex,
@@ -119,6 +151,13 @@ impl RafxRootSignatureMetal {
         self.inner.descriptors.get(descriptor_index.0 as usize)
     }
 
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // There is no metal object that corresponds to a root signature
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextMetal,
         root_signature_def: &RafxRootSignatureDef,
@@ -139,6 +178,14 @@ impl RafxRootSignatureMetal {
         let (pipeline_type, mut merged_resources, _merged_resources_name_index_map) =
             crate::internal_shared::merge_resources(root_signature_def)?;
 
+        // Bindless arrays are argument buffer arrays sized to the declared maximum. Unset elements
+        // are left null and skipped when making resources resident
+        crate::internal_shared::apply_bindless_arrays(
+            root_signature_def,
+            device_context.device_info(),
+            &mut merged_resources,
+        )?;
+
         merged_resources.sort_by(|lhs, rhs| lhs.binding.cmp(&rhs.binding));
 
         let mut layouts = [
@@ -154,12 +201,28 @@ impl RafxRootSignatureMetal {
 
         let mut descriptors = Vec::with_capacity(merged_resources.len());
         let mut name_to_descriptor_index = FnvHashMap::default();
+        let mut push_constants = vec![];
+        let mut name_to_push_constant_index = FnvHashMap::default();
 
         for resource in &merged_resources {
             resource.validate()?;
 
-            // Not currently supported
-            assert_ne!(resource.resource_type, RafxResourceType::ROOT_CONSTANT);
+            // Push constants are set with set*Bytes at METAL_PUSH_CONSTANT_BUFFER_INDEX rather than
+            // being part of an argument buffer
+            if resource.resource_type == RafxResourceType::ROOT_CONSTANT {
+                let push_constant_index = RafxPushConstantIndex(push_constants.len() as u32);
+                push_constants.push(PushConstantInfo {
+                    name: resource.name.clone(),
+                    size_in_bytes: resource.size_in_bytes,
+                    used_in_shader_stages: resource.used_in_shader_stages,
+                });
+
+                if let Some(name) = resource.name.as_ref() {
+                    name_to_push_constant_index.insert(name.clone(), push_constant_index);
+                }
+
+                continue;
+            }
 
             // Verify set index is valid
 
@@ -177,13 +240,16 @@ impl RafxRootSignatureMetal {
                         .samplers
                         .len()
                 {
-                    Err(format!(
-                        "Descriptor (set={:?} binding={:?}) named {:?} specifies {} elements but the count of provided immutable samplers ({}) did not match",
-                        resource.set_index,
-                        resource.binding,
-                        resource.name,
-                        resource.element_count_normalized(),
-                        root_signature_def.immutable_samplers[immutable_sampler_index].samplers.len()
+                    Err(RafxError::reflection_mismatch(
+                        resource.name.as_deref(),
+                        format!(
+                            "Descriptor (set={:?} binding={:?}) named {:?} specifies {} elements but the count of provided immutable samplers ({}) did not match",
+                            resource.set_index,
+                            resource.binding,
+                            resource.name,
+                            resource.element_count_normalized(),
+                            root_signature_def.immutable_samplers[immutable_sampler_index].samplers.len()
+                        ),
                     ))?;
                 }
             }
@@ -288,8 +354,12 @@ impl RafxRootSignatureMetal {
             layouts,
             descriptors,
             name_to_descriptor_index,
+            push_constants,
+            name_to_push_constant_index,
             argument_buffer_resource_usages,
             argument_descriptors,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         };
 
         Ok(RafxRootSignatureMetal {
@@ -6,6 +6,8 @@ use std::sync::Arc;
 pub struct RafxSamplerMetalInner {
     device_context: RafxDeviceContextMetal,
     sampler: metal_rs::SamplerState,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 // for metal_rs::SamplerState
@@ -18,24 +20,41 @@ pub struct RafxSamplerMetal {
 }
 
 impl RafxSamplerMetal {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn metal_sampler(&self) -> &metal_rs::SamplerStateRef {
         self.inner.sampler.as_ref()
     }
 
+    pub fn set_debug_name(
+        &self,
+        _name: impl AsRef<str>,
+    ) {
+        // Metal sampler states are immutable, their label can only be set on the descriptor
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextMetal,
         sampler_def: &RafxSamplerDef,
     ) -> RafxResult<RafxSamplerMetal> {
+        let device_info = device_context.device_info();
+        sampler_def.verify_device_limits(device_info)?;
+        let max_anisotropy = sampler_def
+            .max_anisotropy
+            .min(device_info.max_sampler_anisotropy);
+
         let descriptor = metal_rs::SamplerDescriptor::new();
         descriptor.set_min_filter(sampler_def.min_filter.into());
         descriptor.set_mag_filter(sampler_def.mag_filter.into());
         descriptor.set_mip_filter(sampler_def.mip_map_mode.into());
-        if sampler_def.max_anisotropy == 0.0 {
+        if max_anisotropy == 0.0 {
             descriptor.set_max_anisotropy(1);
         } else {
-            descriptor.set_max_anisotropy(sampler_def.max_anisotropy as _);
+            descriptor.set_max_anisotropy(max_anisotropy as _);
         }
-        let device_info = device_context.device_info();
         descriptor.set_address_mode_s(super::util::address_mode_mtl_sampler_address_mode(
             sampler_def.address_mode_u,
             device_info,
@@ -55,6 +74,8 @@ impl RafxSamplerMetal {
         let inner = RafxSamplerMetalInner {
             device_context: device_context.clone(),
             sampler,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         };
 
         Ok(RafxSamplerMetal {
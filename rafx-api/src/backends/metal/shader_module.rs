@@ -6,6 +6,8 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct RafxShaderModuleMetalInner {
     library: metal_rs::Library,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 // for metal_rs::Library
@@ -18,6 +20,11 @@ pub struct RafxShaderModuleMetal {
 }
 
 impl RafxShaderModuleMetal {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn library(&self) -> &metal_rs::LibraryRef {
         self.inner.library.as_ref()
     }
@@ -42,7 +49,11 @@ impl RafxShaderModuleMetal {
     ) -> RafxResult<Self> {
         let library = device_context.device().new_library_with_data(data)?;
 
-        let inner = RafxShaderModuleMetalInner { library };
+        let inner = RafxShaderModuleMetalInner {
+            library,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
 
         Ok(RafxShaderModuleMetal {
             inner: Arc::new(inner),
@@ -59,7 +70,11 @@ impl RafxShaderModuleMetal {
             .device()
             .new_library_with_source(src, &compile_options)?;
 
-        let inner = RafxShaderModuleMetalInner { library };
+        let inner = RafxShaderModuleMetalInner {
+            library,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
 
         Ok(RafxShaderModuleMetal {
             inner: Arc::new(inner),
@@ -1,9 +1,9 @@
-use crate::metal::RafxDeviceContextMetal;
+use crate::metal::{RafxDeviceContextMetal, RafxHeapMetal};
 use crate::{
-    RafxMemoryUsage, RafxResourceType, RafxResult, RafxSampleCount, RafxTextureDef,
-    RafxTextureDimensions,
+    RafxError, RafxMemoryRequirements, RafxMemoryUsage, RafxResourceType, RafxResult,
+    RafxSampleCount, RafxTextureDef, RafxTextureDimensions,
 };
-use metal_rs::{MTLTextureType, MTLTextureUsage};
+use metal_rs::{MTLPixelFormat, MTLTextureType, MTLTextureUsage, PixelFormatCapabilities};
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -34,10 +34,35 @@ pub struct RafxTextureMetalInner {
     device_context: RafxDeviceContextMetal,
     texture_def: RafxTextureDef,
     image: RafxRawImageMetal,
+    // Set if the texture was created in a heap, the heap owns the memory
+    heap: Option<RafxHeapMetal>,
     mip_level_uav_views: Vec<metal_rs::Texture>,
     texture_id: u32,
 }
 
+impl Drop for RafxTextureMetalInner {
+    fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_texture_destroyed(self.texture_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_texture_destroyed(self.texture_id);
+
+        if let RafxRawImageMetal::Owned(texture) = &self.image {
+            if self.heap.is_some() {
+                return;
+            }
+
+            self.device_context
+                .memory_usage_tracker()
+                .on_texture_freed(RafxMemoryUsage::GpuOnly, texture.allocated_size());
+        }
+    }
+}
+
 /// Holds the vk::Image and allocation as well as a few vk::ImageViews depending on the
 /// provided RafxResourceType in the texture_def.
 #[derive(Clone, Debug)]
@@ -74,6 +99,12 @@ impl RafxTextureMetal {
         &self.inner.texture_def
     }
 
+    // Used internally to identify the texture for validation
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn texture_id(&self) -> u32 {
+        self.inner.texture_id
+    }
+
     pub fn metal_texture(&self) -> &metal_rs::TextureRef {
         self.inner.image.metal_texture()
     }
@@ -82,6 +113,13 @@ impl RafxTextureMetal {
         &self.inner.mip_level_uav_views
     }
 
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.metal_texture().set_label(name.as_ref());
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextMetal,
         texture_def: &RafxTextureDef,
@@ -89,19 +127,83 @@ impl RafxTextureMetal {
         Self::from_existing(device_context, None, texture_def)
     }
 
-    // This path is mostly so we can wrap a provided swapchain image
-    pub fn from_existing(
+    pub fn new_in_heap(
         device_context: &RafxDeviceContextMetal,
-        existing_image: Option<RafxRawImageMetal>,
+        heap: &RafxHeapMetal,
+        offset: u64,
         texture_def: &RafxTextureDef,
     ) -> RafxResult<RafxTextureMetal> {
+        heap.heap_def().verify_texture_def(texture_def)?;
+        Self::create(device_context, None, Some((heap, offset)), texture_def)
+    }
+
+    pub fn memory_requirements(
+        device_context: &RafxDeviceContextMetal,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
         texture_def.verify();
+        texture_def.verify_device_limits(device_context.device_info())?;
+        Self::verify_format_supported(device_context, texture_def)?;
 
+        let (mtl_texture_type, mtl_array_length) =
+            Self::texture_type_and_array_length(device_context, texture_def)?;
+        let descriptor = Self::texture_descriptor(texture_def, mtl_texture_type, mtl_array_length);
+        let size_and_align = device_context
+            .device()
+            .heap_texture_size_and_align(descriptor.as_ref());
+
+        Ok(RafxMemoryRequirements {
+            size: size_and_align.size,
+            alignment: size_and_align.align,
+        })
+    }
+
+    // Metal validation aborts on a pixel format the device can't use rather than returning an
+    // error, so check the feature set tables first
+    fn verify_format_supported(
+        device_context: &RafxDeviceContextMetal,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<()> {
+        let mtl_pixel_format: MTLPixelFormat = texture_def.format.into();
+        if mtl_pixel_format == MTLPixelFormat::Invalid {
+            Err(RafxError::UnsupportedFormat(texture_def.format))?;
+        }
+
+        // Same requirements as RafxDeviceContextMetal::find_supported_format
+        let mut required_capabilities = PixelFormatCapabilities::empty();
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::RENDER_TARGET_COLOR)
+        {
+            required_capabilities |= PixelFormatCapabilities::Color;
+        }
+
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::TEXTURE_READ_WRITE)
+        {
+            required_capabilities |= PixelFormatCapabilities::Write;
+        }
+
+        let capabilities = device_context
+            .metal_features()
+            .pixel_format_capabilities(mtl_pixel_format);
+        if !capabilities.contains(required_capabilities) {
+            Err(RafxError::UnsupportedFormat(texture_def.format))?;
+        }
+
+        Ok(())
+    }
+
+    fn texture_type_and_array_length(
+        device_context: &RafxDeviceContextMetal,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<(MTLTextureType, u32)> {
         let dimensions = texture_def
             .dimensions
             .determine_dimensions(texture_def.extents);
 
-        let (mtl_texture_type, mtl_array_length) = match dimensions {
+        let type_and_array_length = match dimensions {
             RafxTextureDimensions::Dim1D => {
                 if texture_def.array_length > 1 {
                     if !device_context.metal_features().supports_array_of_textures {
@@ -146,52 +248,132 @@ impl RafxTextureMetal {
             _ => unreachable!(),
         };
 
-        let image = if let Some(existing_image) = existing_image {
-            existing_image
-        } else {
-            let descriptor = metal_rs::TextureDescriptor::new();
-            descriptor.set_pixel_format(texture_def.format.into());
-            descriptor.set_width(texture_def.extents.width as _);
-            descriptor.set_height(texture_def.extents.height as _);
-            descriptor.set_depth(texture_def.extents.depth as _);
-            descriptor.set_mipmap_level_count(texture_def.mip_count as _);
-            descriptor.set_storage_mode(RafxMemoryUsage::GpuOnly.mtl_storage_mode());
-            descriptor.set_cpu_cache_mode(RafxMemoryUsage::GpuOnly.mtl_cpu_cache_mode());
-            descriptor.set_resource_options(RafxMemoryUsage::GpuOnly.mtl_resource_options());
-            descriptor.set_texture_type(mtl_texture_type);
-            descriptor.set_array_length(mtl_array_length as _);
-            descriptor.set_sample_count(texture_def.sample_count.into());
-
-            let mut mtl_usage = MTLTextureUsage::empty();
-
-            if texture_def
-                .resource_type
-                .intersects(RafxResourceType::TEXTURE)
-            {
-                mtl_usage |= MTLTextureUsage::ShaderRead;
-            }
+        Ok(type_and_array_length)
+    }
 
-            if texture_def.resource_type.intersects(
-                RafxResourceType::RENDER_TARGET_DEPTH_STENCIL
-                    | RafxResourceType::RENDER_TARGET_COLOR,
-            ) {
-                mtl_usage |= MTLTextureUsage::RenderTarget;
-            }
+    fn texture_descriptor(
+        texture_def: &RafxTextureDef,
+        mtl_texture_type: MTLTextureType,
+        mtl_array_length: u32,
+    ) -> metal_rs::TextureDescriptor {
+        let descriptor = metal_rs::TextureDescriptor::new();
+        descriptor.set_pixel_format(texture_def.format.into());
+        descriptor.set_width(texture_def.extents.width as _);
+        descriptor.set_height(texture_def.extents.height as _);
+        descriptor.set_depth(texture_def.extents.depth as _);
+        descriptor.set_mipmap_level_count(texture_def.mip_count as _);
+        descriptor.set_storage_mode(RafxMemoryUsage::GpuOnly.mtl_storage_mode());
+        descriptor.set_cpu_cache_mode(RafxMemoryUsage::GpuOnly.mtl_cpu_cache_mode());
+        descriptor.set_resource_options(RafxMemoryUsage::GpuOnly.mtl_resource_options());
+        descriptor.set_texture_type(mtl_texture_type);
+        descriptor.set_array_length(mtl_array_length as _);
+        descriptor.set_sample_count(texture_def.sample_count.into());
 
-            if texture_def
+        let mut mtl_usage = MTLTextureUsage::empty();
+
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::TEXTURE)
+        {
+            mtl_usage |= MTLTextureUsage::ShaderRead;
+        }
+
+        if texture_def.resource_type.intersects(
+            RafxResourceType::RENDER_TARGET_DEPTH_STENCIL | RafxResourceType::RENDER_TARGET_COLOR,
+        ) {
+            mtl_usage |= MTLTextureUsage::RenderTarget;
+        }
+
+        // cmd_blit and cmd_clear_texture render into the texture, so allow generating mipmaps
+        // and clearing storage images with them
+        let is_blit_or_clear_target = texture_def.mip_count > 1
+            || texture_def
                 .resource_type
-                .intersects(RafxResourceType::TEXTURE_READ_WRITE)
-            {
-                mtl_usage |= MTLTextureUsage::PixelFormatView;
-                mtl_usage |= MTLTextureUsage::ShaderWrite;
-            }
+                .intersects(RafxResourceType::TEXTURE_READ_WRITE);
+        if is_blit_or_clear_target
+            && !texture_def.format.has_depth_or_stencil()
+            && texture_def.format.block_width_in_pixels() == 1
+        {
+            mtl_usage |= MTLTextureUsage::RenderTarget;
+        }
 
-            descriptor.set_usage(mtl_usage);
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::TEXTURE_READ_WRITE)
+        {
+            mtl_usage |= MTLTextureUsage::PixelFormatView;
+            mtl_usage |= MTLTextureUsage::ShaderWrite;
+        }
+
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::TEXTURE_MUTABLE_FORMAT)
+        {
+            mtl_usage |= MTLTextureUsage::PixelFormatView;
+        }
+
+        descriptor.set_usage(mtl_usage);
+
+        descriptor
+    }
 
+    // This path is mostly so we can wrap a provided swapchain image
+    pub fn from_existing(
+        device_context: &RafxDeviceContextMetal,
+        existing_image: Option<RafxRawImageMetal>,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureMetal> {
+        Self::create(device_context, existing_image, None, texture_def)
+    }
+
+    fn create(
+        device_context: &RafxDeviceContextMetal,
+        existing_image: Option<RafxRawImageMetal>,
+        heap_placement: Option<(&RafxHeapMetal, u64)>,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureMetal> {
+        texture_def.verify();
+        texture_def.verify_device_limits(device_context.device_info())?;
+
+        let (mtl_texture_type, mtl_array_length) =
+            Self::texture_type_and_array_length(device_context, texture_def)?;
+
+        let image = if let Some(existing_image) = existing_image {
+            existing_image
+        } else if let Some((heap, offset)) = heap_placement {
+            Self::verify_format_supported(device_context, texture_def)?;
+
+            let descriptor =
+                Self::texture_descriptor(texture_def, mtl_texture_type, mtl_array_length);
+            let size_and_align = device_context
+                .device()
+                .heap_texture_size_and_align(descriptor.as_ref());
+            heap.verify_placement(offset, &size_and_align)?;
+
+            let texture = heap
+                .metal_heap()
+                .new_texture_with_offset(descriptor.as_ref(), offset)
+                .ok_or("Failed to create texture in heap")?;
+            RafxRawImageMetal::Owned(texture)
+        } else {
+            Self::verify_format_supported(device_context, texture_def)?;
+
+            let descriptor =
+                Self::texture_descriptor(texture_def, mtl_texture_type, mtl_array_length);
             let texture = device_context.device().new_texture(descriptor.as_ref());
             RafxRawImageMetal::Owned(texture)
         };
 
+        // Owned textures are released when this texture is dropped, so count them. Textures in a
+        // heap don't own their memory
+        if let RafxRawImageMetal::Owned(texture) = &image {
+            if heap_placement.is_none() {
+                device_context
+                    .memory_usage_tracker()
+                    .on_texture_allocated(RafxMemoryUsage::GpuOnly, texture.allocated_size());
+            }
+        }
+
         let mut mip_level_uav_views = vec![];
         if texture_def
             .resource_type
@@ -221,6 +403,7 @@ impl RafxTextureMetal {
             texture_def: texture_def.clone(),
             device_context: device_context.clone(),
             image,
+            heap: heap_placement.map(|(heap, _)| heap.clone()),
             mip_level_uav_views,
             texture_id,
         };
@@ -0,0 +1,107 @@
+use crate::metal::{RafxDeviceContextMetal, RafxTextureMetal};
+use crate::*;
+use metal_rs::MTLTextureType;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxTextureViewMetalInner {
+    // It's a RafxTextureMetal, but stored as RafxTexture so we can return refs to it
+    texture: RafxTexture,
+    texture_view_def: RafxTextureViewDef,
+    metal_texture: metal_rs::Texture,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+/// Holds a metal texture view of a subset of a texture's subresources
+#[derive(Clone, Debug)]
+pub struct RafxTextureViewMetal {
+    inner: Arc<RafxTextureViewMetalInner>,
+}
+
+// for metal_rs::Texture
+unsafe impl Send for RafxTextureViewMetal {}
+unsafe impl Sync for RafxTextureViewMetal {}
+
+impl RafxTextureViewMetal {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn texture_view_def(&self) -> &RafxTextureViewDef {
+        &self.inner.texture_view_def
+    }
+
+    pub fn texture(&self) -> &RafxTexture {
+        &self.inner.texture
+    }
+
+    pub fn metal_parent_texture(&self) -> &RafxTextureMetal {
+        self.inner.texture.metal_texture().unwrap()
+    }
+
+    // The view, usable anywhere a texture is accepted. Mip level 0 and slice 0 of the view are
+    // the first mip level and layer in the view's ranges
+    pub fn metal_texture(&self) -> &metal_rs::TextureRef {
+        self.inner.metal_texture.as_ref()
+    }
+
+    pub fn new(
+        _device_context: &RafxDeviceContextMetal,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewMetal> {
+        let metal_texture = texture
+            .metal_texture()
+            .ok_or("Texture views must be created from a texture of the same backend")?;
+        let texture_def = metal_texture.texture_def();
+        let texture_view_def = texture_view_def.resolve(texture_def)?;
+
+        let format = texture_view_def.format.unwrap();
+        let mip_range = texture_view_def.mip_range.clone().unwrap();
+        let array_range = texture_view_def.array_range.clone().unwrap();
+
+        // Metal samples depth textures as floats directly, so depth sample views keep the
+        // texture's format
+        let view_format = if format != texture_def.format && texture_def.format.has_depth() {
+            texture_def.format
+        } else {
+            format
+        };
+
+        let mut texture_type: MTLTextureType = texture_view_def.dimensions.unwrap().into();
+        if texture_type == MTLTextureType::D2
+            && texture_def.sample_count != RafxSampleCount::SampleCount1
+        {
+            texture_type = MTLTextureType::D2Multisample;
+        }
+
+        // Slices of cube textures are faces, which are the same as our array layers
+        let levels =
+            metal_rs::NSRange::new(mip_range.start as _, (mip_range.end - mip_range.start) as _);
+        let slices = metal_rs::NSRange::new(
+            array_range.start as _,
+            (array_range.end - array_range.start) as _,
+        );
+
+        let view = metal_texture.metal_texture().new_texture_view_from_slice(
+            view_format.into(),
+            texture_type,
+            levels,
+            slices,
+        );
+
+        let inner = RafxTextureViewMetalInner {
+            texture: texture.clone(),
+            texture_view_def,
+            metal_texture: view,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxTextureViewMetal {
+            inner: Arc::new(inner),
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use crate::metal::RafxDeviceContextMetal;
+use crate::RafxResult;
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use std::sync::Arc;
+
+pub struct RafxTimelineSemaphoreMetal {
+    _device_context: RafxDeviceContextMetal,
+    metal_shared_event: metal_rs::SharedEvent,
+    // Runs the notifications that wait() blocks on. metal_rs only exposes creating a
+    // MTLSharedEventListener with its dispatch feature, so this is created through objc
+    metal_shared_event_listener: *mut Object,
+}
+
+// for metal_rs::SharedEvent and the MTLSharedEventListener
+unsafe impl Send for RafxTimelineSemaphoreMetal {}
+unsafe impl Sync for RafxTimelineSemaphoreMetal {}
+
+impl Drop for RafxTimelineSemaphoreMetal {
+    fn drop(&mut self) {
+        unsafe {
+            let () = msg_send![self.metal_shared_event_listener, release];
+        }
+    }
+}
+
+impl RafxTimelineSemaphoreMetal {
+    pub fn new(
+        device_context: &RafxDeviceContextMetal,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreMetal> {
+        let metal_shared_event = device_context.device().new_shared_event();
+        metal_shared_event.set_signaled_value(initial_value);
+        let metal_shared_event_listener: *mut Object =
+            unsafe { msg_send![class!(MTLSharedEventListener), new] };
+
+        Ok(RafxTimelineSemaphoreMetal {
+            _device_context: device_context.clone(),
+            metal_shared_event,
+            metal_shared_event_listener,
+        })
+    }
+
+    pub fn metal_shared_event(&self) -> &metal_rs::SharedEventRef {
+        self.metal_shared_event.as_ref()
+    }
+
+    pub fn current_value(&self) -> RafxResult<u64> {
+        Ok(self.metal_shared_event.signaled_value())
+    }
+
+    pub fn signal(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        self.metal_shared_event.set_signaled_value(value);
+        Ok(())
+    }
+
+    pub fn wait(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        if self.metal_shared_event.signaled_value() >= value {
+            return Ok(());
+        }
+
+        // The listener runs the block once the event reaches the value (immediately if it already
+        // has), which unblocks this thread
+        let dispatch_semaphore = Arc::new(dispatch::Semaphore::new(0));
+        let block_dispatch_semaphore = dispatch_semaphore.clone();
+        let block = block::ConcreteBlock::new(move |_event: *mut Object, _value: u64| {
+            block_dispatch_semaphore.signal();
+        })
+        .copy();
+
+        unsafe {
+            let () = msg_send![
+                self.metal_shared_event.as_ref(),
+                notifyListener: self.metal_shared_event_listener
+                atValue: value
+                block: &*block
+            ];
+        }
+
+        dispatch_semaphore.wait();
+        Ok(())
+    }
+}
@@ -6,7 +6,12 @@ pub mod vulkan;
 
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
-#[doc(hidden)]
 pub mod empty;
+#[cfg(feature = "rafx-gles3")]
+pub mod gles3;
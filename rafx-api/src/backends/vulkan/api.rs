@@ -79,11 +79,38 @@ impl RafxApiVulkan {
         &self.instance.instance
     }
 
+    /// List the adapters available to vulkan. This only creates a vulkan instance, not a device.
+    pub fn enumerate_adapters(
+        window: &dyn HasRawWindowHandle,
+        vk_api_def: &RafxApiDefVulkan,
+    ) -> RafxResult<Vec<RafxAdapterInfo>> {
+        let instance = Self::create_instance(window, vk_api_def)?;
+        super::device_context::enumerate_adapters(&instance.instance)
+    }
+
     pub fn new(
         window: &dyn HasRawWindowHandle,
-        _api_def: &RafxApiDef,
+        api_def: &RafxApiDef,
         vk_api_def: &RafxApiDefVulkan,
     ) -> RafxResult<Self> {
+        let instance = Self::create_instance(window, vk_api_def)?;
+
+        let inner = Arc::new(RafxDeviceContextVulkanInner::new(
+            &instance,
+            api_def.adapter_index,
+        )?);
+        let device_context = RafxDeviceContextVulkan::new(inner)?;
+
+        Ok(RafxApiVulkan {
+            instance,
+            device_context: Some(device_context),
+        })
+    }
+
+    fn create_instance(
+        window: &dyn HasRawWindowHandle,
+        vk_api_def: &RafxApiDefVulkan,
+    ) -> RafxResult<VkInstance> {
         let link_method = vk_api_def.link_method;
         let app_name = vk_api_def.app_name.clone();
 
@@ -113,13 +140,7 @@ impl RafxApiVulkan {
             validation_layer_debug_report_flags,
         )?;
 
-        let inner = Arc::new(RafxDeviceContextVulkanInner::new(&instance)?);
-        let device_context = RafxDeviceContextVulkan::new(inner)?;
-
-        Ok(RafxApiVulkan {
-            instance,
-            device_context: Some(device_context),
-        })
+        Ok(instance)
     }
 
     pub(crate) fn destroy(&mut self) -> RafxResult<()> {
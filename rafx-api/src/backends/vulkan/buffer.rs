@@ -1,8 +1,10 @@
-use crate::vulkan::RafxDeviceContextVulkan;
+use crate::vulkan::{RafxDeviceContextVulkan, RafxHeapVulkan};
 use crate::*;
 use ash::version::DeviceV1_0;
 use ash::vk;
 use rafx_base::trust_cell::TrustCell;
+#[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+use std::sync::atomic::Ordering;
 
 #[derive(Copy, Clone, Debug)]
 pub struct RafxBufferRaw {
@@ -15,10 +17,18 @@ pub struct RafxBufferVulkan {
     device_context: RafxDeviceContextVulkan,
     allocation_info: TrustCell<vk_mem::AllocationInfo>,
     buffer_raw: Option<RafxBufferRaw>,
+    // Size of the allocation backing the buffer, may be larger than the requested size
+    allocation_size: u64,
+    // Set if the buffer is bound to memory owned by a heap (buffer_raw.allocation is the heap's)
+    heap: Option<RafxHeapVulkan>,
+    heap_offset: u64,
 
     buffer_def: RafxBufferDef,
     uniform_texel_view: Option<vk::BufferView>,
     storage_texel_view: Option<vk::BufferView>,
+    // Used internally to identify the buffer for validation and capture
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    buffer_id: u32,
 }
 
 impl RafxBufferVulkan {
@@ -34,9 +44,20 @@ impl RafxBufferVulkan {
         self.storage_texel_view
     }
 
+    /// Returns None for buffers placed in a heap, they do not own their allocation
     pub fn take_raw(mut self) -> Option<RafxBufferRaw> {
+        if self.heap.is_some() {
+            return None;
+        }
+
         let mut raw = None;
         std::mem::swap(&mut raw, &mut self.buffer_raw);
+        if raw.is_some() {
+            // The caller is now responsible for the allocation, so stop reporting it
+            self.device_context
+                .memory_usage_tracker()
+                .on_buffer_freed(self.buffer_def.memory_usage, self.allocation_size);
+        }
         raw
     }
 
@@ -44,6 +65,16 @@ impl RafxBufferVulkan {
         &self.buffer_def
     }
 
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn buffer_id(&self) -> u32 {
+        self.buffer_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.device_context.capture_recorder()
+    }
+
     pub fn map_buffer(&self) -> RafxResult<*mut u8> {
         let ptr = self
             .device_context
@@ -53,7 +84,8 @@ impl RafxBufferVulkan {
             .device_context
             .allocator()
             .get_allocation_info(&self.buffer_raw.unwrap().allocation)?;
-        Ok(ptr)
+        // Placed buffers share the heap's allocation
+        unsafe { Ok(ptr.add(self.heap_offset as usize)) }
     }
 
     pub fn unmap_buffer(&self) -> RafxResult<()> {
@@ -72,7 +104,7 @@ impl RafxBufferVulkan {
         if ptr.is_null() {
             None
         } else {
-            Some(ptr)
+            unsafe { Some(ptr.add(self.heap_offset as usize)) }
         }
     }
 
@@ -107,10 +139,57 @@ impl RafxBufferVulkan {
         Ok(())
     }
 
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.device_context
+            .set_debug_name(self.vk_buffer(), name.as_ref());
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextVulkan,
         buffer_def: &RafxBufferDef,
     ) -> RafxResult<Self> {
+        Self::create(device_context, None, buffer_def)
+    }
+
+    pub fn new_in_heap(
+        device_context: &RafxDeviceContextVulkan,
+        heap: &RafxHeapVulkan,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
+        heap.heap_def().verify_buffer_def(buffer_def)?;
+        Self::create(device_context, Some((heap, offset)), buffer_def)
+    }
+
+    pub fn memory_requirements(
+        device_context: &RafxDeviceContextVulkan,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        let (buffer_info, _) = Self::buffer_create_info(device_context, buffer_def);
+        let device = device_context.device();
+        let memory_requirements = unsafe {
+            let buffer = device.create_buffer(&buffer_info, None)?;
+            let memory_requirements = device.get_buffer_memory_requirements(buffer);
+            device.destroy_buffer(buffer, None);
+            memory_requirements
+        };
+
+        Ok(RafxMemoryRequirements {
+            size: memory_requirements.size,
+            // Keep placed buffers and textures on separate pages
+            alignment: memory_requirements
+                .alignment
+                .max(device_context.limits().buffer_image_granularity),
+        })
+    }
+
+    fn buffer_create_info(
+        device_context: &RafxDeviceContextVulkan,
+        buffer_def: &RafxBufferDef,
+    ) -> (vk::BufferCreateInfo, vk::BufferUsageFlags) {
         let mut allocation_size = buffer_def.size;
         if buffer_def
             .resource_type
@@ -133,6 +212,22 @@ impl RafxBufferVulkan {
             usage_flags |= vk::BufferUsageFlags::TRANSFER_DST;
         }
 
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(allocation_size)
+            .usage(usage_flags)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .build();
+
+        (buffer_info, usage_flags)
+    }
+
+    fn create(
+        device_context: &RafxDeviceContextVulkan,
+        heap_placement: Option<(&RafxHeapVulkan, u64)>,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<Self> {
+        let (buffer_info, usage_flags) = Self::buffer_create_info(device_context, buffer_def);
+
         let mut flags = vk_mem::AllocationCreateFlags::NONE;
         if buffer_def.always_mapped {
             flags |= vk_mem::AllocationCreateFlags::MAPPED;
@@ -148,21 +243,56 @@ impl RafxBufferVulkan {
             user_data: None,
         };
 
-        let buffer_info = vk::BufferCreateInfo::builder()
-            .size(allocation_size)
-            .usage(usage_flags)
-            .sharing_mode(vk::SharingMode::EXCLUSIVE);
-
-        //TODO: Better way of handling allocator errors
-        let (buffer, allocation, allocation_info) = device_context
-            .allocator()
-            .create_buffer(&buffer_info, &allocation_create_info)
-            .map_err(|e| {
-                log::error!("Error creating buffer {:?}", e);
-                vk::Result::ERROR_UNKNOWN
-            })?;
-
-        let buffer_raw = RafxBufferRaw { buffer, allocation };
+        let (buffer_raw, allocation_info, allocation_size) =
+            if let Some((heap, offset)) = heap_placement {
+                let device = device_context.device();
+                let buffer = unsafe { device.create_buffer(&buffer_info, None)? };
+                let memory_requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
+                let bind_result = heap
+                    .verify_placement(offset, &memory_requirements)
+                    .and_then(|_| unsafe {
+                        Ok(device.bind_buffer_memory(
+                            buffer,
+                            heap.vk_device_memory(),
+                            heap.vk_device_memory_offset() + offset,
+                        )?)
+                    });
+                if let Err(e) = bind_result {
+                    unsafe {
+                        device.destroy_buffer(buffer, None);
+                    }
+                    return Err(e);
+                }
+
+                let buffer_raw = RafxBufferRaw {
+                    buffer,
+                    allocation: heap.vk_allocation(),
+                };
+                (
+                    buffer_raw,
+                    heap.vk_allocation_info().clone(),
+                    memory_requirements.size,
+                )
+            } else {
+                //TODO: Better way of handling allocator errors
+                let (buffer, allocation, allocation_info) = device_context
+                    .allocator()
+                    .create_buffer(&buffer_info, &allocation_create_info)
+                    .map_err(|e| {
+                        log::error!("Error creating buffer {:?}", e);
+                        vk::Result::ERROR_UNKNOWN
+                    })?;
+
+                let allocation_size = allocation_info.get_size() as u64;
+                device_context
+                    .memory_usage_tracker()
+                    .on_buffer_allocated(buffer_def.memory_usage, allocation_size);
+                (
+                    RafxBufferRaw { buffer, allocation },
+                    allocation_info,
+                    allocation_size,
+                )
+            };
 
         log::trace!(
             "Buffer {:?} crated with size {} (always mapped: {:?})",
@@ -230,15 +360,29 @@ impl RafxBufferVulkan {
             device_context: device_context.clone(),
             allocation_info: TrustCell::new(allocation_info),
             buffer_raw: Some(buffer_raw),
+            allocation_size,
+            heap: heap_placement.map(|(heap, _)| heap.clone()),
+            heap_offset: heap_placement.map(|(_, offset)| offset).unwrap_or(0),
             buffer_def: buffer_def.clone(),
             uniform_texel_view,
             storage_texel_view,
+            #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+            buffer_id: crate::internal_shared::NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 }
 
 impl Drop for RafxBufferVulkan {
     fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_buffer_destroyed(self.buffer_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_buffer_destroyed(self.buffer_id);
+
         log::trace!("destroying RafxBufferVulkanInner");
         let device = self.device_context.device();
         if let Some(uniform_texel_view) = self.uniform_texel_view {
@@ -260,10 +404,20 @@ impl Drop for RafxBufferVulkan {
                 self.buffer_def.always_mapped
             );
 
-            self.device_context
-                .allocator()
-                .destroy_buffer(buffer_raw.buffer, &buffer_raw.allocation)
-                .unwrap();
+            if self.heap.is_some() {
+                // The memory belongs to the heap
+                unsafe {
+                    device.destroy_buffer(buffer_raw.buffer, None);
+                }
+            } else {
+                self.device_context
+                    .allocator()
+                    .destroy_buffer(buffer_raw.buffer, &buffer_raw.allocation)
+                    .unwrap();
+                self.device_context
+                    .memory_usage_tracker()
+                    .on_buffer_freed(self.buffer_def.memory_usage, self.allocation_size);
+            }
         }
 
         log::trace!("destroyed RafxBufferVulkanInner");
@@ -1,8 +1,11 @@
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxCommandBufferResourceStates;
 use crate::vulkan::*;
 use crate::*;
 use ash::version::DeviceV1_0;
 use ash::vk;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 #[derive(Debug)]
 pub struct RafxCommandBufferVulkan {
@@ -11,7 +14,14 @@ pub struct RafxCommandBufferVulkan {
     vk_command_buffer: vk::CommandBuffer,
     queue_type: RafxQueueType,
     queue_family_index: u32,
+    is_secondary: bool,
     has_active_renderpass: AtomicBool,
+    // Kept alive while a secondary command buffer that inherits a renderpass is recorded
+    inheritance_renderpass: Mutex<Option<RafxRenderpassVulkan>>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxCommandBufferResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl Into<RafxCommandBuffer> for RafxCommandBufferVulkan {
@@ -53,7 +63,13 @@ impl RafxCommandBufferVulkan {
             vk_command_buffer,
             queue_type: command_pool.queue_type(),
             queue_family_index: command_pool.queue_family_index(),
+            is_secondary: command_buffer_def.is_secondary,
             has_active_renderpass: AtomicBool::new(false),
+            inheritance_renderpass: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 
@@ -61,11 +77,35 @@ impl RafxCommandBufferVulkan {
         self.vk_command_buffer
     }
 
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxCommandBufferResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        self.device_context.capture_recorder()
+    }
+
     pub fn begin(&self) -> RafxResult<()> {
         //TODO: Use one-time-submit?
         let command_buffer_usage_flags = vk::CommandBufferUsageFlags::empty();
 
-        let begin_info = vk::CommandBufferBeginInfo::builder().flags(command_buffer_usage_flags);
+        // Secondary command buffers must always provide inheritance info, even if they will not be
+        // executed within a renderpass
+        let command_buffer_inheritance_info = vk::CommandBufferInheritanceInfo::default();
+        let mut begin_info =
+            vk::CommandBufferBeginInfo::builder().flags(command_buffer_usage_flags);
+        if self.is_secondary {
+            begin_info = begin_info.inheritance_info(&command_buffer_inheritance_info);
+        }
+
+        *self.inheritance_renderpass.lock().unwrap() = None;
 
         unsafe {
             self.device_context
@@ -76,6 +116,68 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
+    pub fn begin_secondary(
+        &self,
+        inheritance_info: &RafxCommandBufferInheritanceInfo,
+    ) -> RafxResult<()> {
+        if !self.is_secondary {
+            Err("begin_secondary called on a primary command buffer")?;
+        }
+
+        // image layouts and load/store ops don't affect compatibility
+        // https://www.khronos.org/registry/vulkan/specs/1.1-extensions/html/chap9.html#renderpass-compatibility
+        let color_attachments: Vec<_> = inheritance_info
+            .color_formats
+            .iter()
+            .map(|&format| RafxRenderpassVulkanColorAttachment {
+                format,
+                load_op: Default::default(),
+                store_op: Default::default(),
+            })
+            .collect();
+
+        let depth_attachment = inheritance_info.depth_stencil_format.map(|format| {
+            RafxRenderpassVulkanDepthAttachment {
+                format,
+                depth_load_op: Default::default(),
+                stencil_load_op: Default::default(),
+                depth_store_op: Default::default(),
+                stencil_store_op: Default::default(),
+            }
+        });
+
+        let renderpass = self
+            .device_context
+            .create_renderpass(&RafxRenderpassVulkanDef {
+                color_attachments,
+                // does not affect compatibility for single-pass renderpasses
+                resolve_attachments: vec![],
+                depth_attachment,
+                sample_count: inheritance_info.sample_count,
+            })?;
+
+        let command_buffer_inheritance_info = vk::CommandBufferInheritanceInfo::builder()
+            .render_pass(renderpass.vk_renderpass())
+            .subpass(0);
+
+        let begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE)
+            .inheritance_info(&command_buffer_inheritance_info);
+
+        unsafe {
+            self.device_context
+                .device()
+                .begin_command_buffer(self.vk_command_buffer, &*begin_info)?;
+        }
+
+        *self.inheritance_renderpass.lock().unwrap() = Some(renderpass);
+
+        // Always dynamic, and the values set by the primary are not inherited. The viewport and
+        // scissor are left to the caller because the render target size isn't known here.
+        self.cmd_set_blend_constants([0.0; 4])?;
+        self.cmd_set_line_width(1.0)
+    }
+
     pub fn end(&self) -> RafxResult<()> {
         if self.has_active_renderpass.load(Ordering::Relaxed) {
             unsafe {
@@ -110,6 +212,27 @@ impl RafxCommandBufferVulkan {
         &self,
         color_targets: &[RafxColorRenderTargetBinding],
         depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(color_targets, depth_target, vk::SubpassContents::INLINE)
+    }
+
+    pub fn cmd_begin_render_pass_for_secondary(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        self.do_begin_render_pass(
+            color_targets,
+            depth_target,
+            vk::SubpassContents::SECONDARY_COMMAND_BUFFERS,
+        )
+    }
+
+    fn do_begin_render_pass(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+        subpass_contents: vk::SubpassContents,
     ) -> RafxResult<()> {
         if self.has_active_renderpass.load(Ordering::Relaxed) {
             self.cmd_end_render_pass()?;
@@ -119,6 +242,24 @@ impl RafxCommandBufferVulkan {
             Err("No color or depth target supplied to cmd_begin_render_pass")?;
         }
 
+        for color_target in color_targets {
+            Self::verify_render_target_view(
+                color_target.texture,
+                color_target.texture_view,
+                color_target.mip_slice,
+                color_target.array_slice,
+            )?;
+        }
+
+        if let Some(depth_target) = &depth_target {
+            Self::verify_render_target_view(
+                depth_target.texture,
+                depth_target.texture_view,
+                depth_target.mip_slice,
+                depth_target.array_slice,
+            )?;
+        }
+
         let (renderpass, framebuffer) = {
             let resource_cache = self.device_context.resource_cache();
             let mut resource_cache = resource_cache.inner.lock().unwrap();
@@ -229,12 +370,19 @@ impl RafxCommandBufferVulkan {
             self.device_context.device().cmd_begin_render_pass(
                 self.vk_command_buffer,
                 &*begin_renderpass_create_info,
-                vk::SubpassContents::INLINE,
+                subpass_contents,
             );
         }
 
         self.has_active_renderpass.store(true, Ordering::Relaxed);
 
+        // Only vkCmdExecuteCommands may be recorded in a subpass that uses secondary command
+        // buffers. They must set their own viewport and scissor, and begin_secondary resets blend
+        // constants and line width.
+        if subpass_contents == vk::SubpassContents::SECONDARY_COMMAND_BUFFERS {
+            return Ok(());
+        }
+
         self.cmd_set_viewport(
             0.0,
             0.0,
@@ -246,6 +394,29 @@ impl RafxCommandBufferVulkan {
         .unwrap();
         self.cmd_set_scissor(0, 0, framebuffer.width(), framebuffer.height())
             .unwrap();
+        // Always dynamic, reset them so render passes behave the same as metal render encoders
+        self.cmd_set_blend_constants([0.0; 4]).unwrap();
+        self.cmd_set_line_width(1.0).unwrap();
+
+        Ok(())
+    }
+
+    fn verify_render_target_view(
+        texture: &RafxTexture,
+        texture_view: Option<&RafxTextureView>,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    ) -> RafxResult<()> {
+        if let Some(texture_view) = texture_view {
+            let texture_view = texture_view.vk_texture_view().unwrap();
+            crate::internal_shared::verify_render_target_view(
+                texture.texture_def(),
+                texture_view.texture_view_def(),
+                texture_view.vk_texture() == texture.vk_texture().unwrap(),
+                mip_slice,
+                array_slice,
+            )?;
+        }
 
         Ok(())
     }
@@ -261,6 +432,28 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
+    pub fn cmd_execute_secondary_command_buffers(
+        &self,
+        secondary_command_buffers: &[&RafxCommandBufferVulkan],
+    ) -> RafxResult<()> {
+        let mut vk_command_buffers = Vec::with_capacity(secondary_command_buffers.len());
+        for secondary_command_buffer in secondary_command_buffers {
+            if !secondary_command_buffer.is_secondary {
+                Err("cmd_execute_secondary_command_buffers called with a primary command buffer")?;
+            }
+
+            vk_command_buffers.push(secondary_command_buffer.vk_command_buffer);
+        }
+
+        unsafe {
+            self.device_context
+                .device()
+                .cmd_execute_commands(self.vk_command_buffer, &vk_command_buffers);
+        }
+
+        Ok(())
+    }
+
     pub fn cmd_set_viewport(
         &self,
         x: f32,
@@ -326,6 +519,51 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
+    pub fn cmd_set_depth_bias(
+        &self,
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    ) -> RafxResult<()> {
+        unsafe {
+            self.device_context.device().cmd_set_depth_bias(
+                self.vk_command_buffer,
+                constant_factor,
+                clamp,
+                slope_factor,
+            );
+        }
+        Ok(())
+    }
+
+    pub fn cmd_set_blend_constants(
+        &self,
+        blend_constants: [f32; 4],
+    ) -> RafxResult<()> {
+        unsafe {
+            self.device_context
+                .device()
+                .cmd_set_blend_constants(self.vk_command_buffer, &blend_constants);
+        }
+        Ok(())
+    }
+
+    pub fn cmd_set_line_width(
+        &self,
+        line_width: f32,
+    ) -> RafxResult<()> {
+        self.device_context
+            .device_info()
+            .verify_line_width(line_width)?;
+
+        unsafe {
+            self.device_context
+                .device()
+                .cmd_set_line_width(self.vk_command_buffer, line_width);
+        }
+        Ok(())
+    }
+
     pub fn cmd_bind_pipeline(
         &self,
         pipeline: &RafxPipelineVulkan,
@@ -421,6 +659,39 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
+    pub fn cmd_bind_push_constant(
+        &self,
+        root_signature: &RafxRootSignatureVulkan,
+        push_constant_index: RafxPushConstantIndex,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        let push_constant = root_signature
+            .push_constant(push_constant_index)
+            .ok_or("Push constant index is not valid for this root signature")?;
+
+        let range = push_constant.vk_push_constant_range;
+        if data.len() as u32 > range.size {
+            Err(format!(
+                "Push constant {:?} is {} bytes but {} bytes were provided",
+                push_constant.name,
+                range.size,
+                data.len()
+            ))?;
+        }
+
+        unsafe {
+            self.device_context.device().cmd_push_constants(
+                self.vk_command_buffer,
+                root_signature.vk_pipeline_layout(),
+                range.stage_flags,
+                range.offset,
+                data,
+            )
+        }
+
+        Ok(())
+    }
+
     pub fn cmd_draw(
         &self,
         vertex_count: u32,
@@ -519,6 +790,191 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
+    pub fn cmd_draw_indirect(
+        &self,
+        indirect_buffer: &RafxBufferVulkan,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        unsafe {
+            if self.supports_multi_draw_indirect() {
+                self.device_context.device().cmd_draw_indirect(
+                    self.vk_command_buffer,
+                    indirect_buffer.vk_buffer(),
+                    indirect_buffer_offset_in_bytes,
+                    draw_count,
+                    stride,
+                );
+            } else {
+                for i in 0..draw_count as u64 {
+                    self.device_context.device().cmd_draw_indirect(
+                        self.vk_command_buffer,
+                        indirect_buffer.vk_buffer(),
+                        indirect_buffer_offset_in_bytes + i * stride as u64,
+                        1,
+                        stride,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_draw_indexed_indirect(
+        &self,
+        indirect_buffer: &RafxBufferVulkan,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> RafxResult<()> {
+        unsafe {
+            if self.supports_multi_draw_indirect() {
+                self.device_context.device().cmd_draw_indexed_indirect(
+                    self.vk_command_buffer,
+                    indirect_buffer.vk_buffer(),
+                    indirect_buffer_offset_in_bytes,
+                    draw_count,
+                    stride,
+                );
+            } else {
+                for i in 0..draw_count as u64 {
+                    self.device_context.device().cmd_draw_indexed_indirect(
+                        self.vk_command_buffer,
+                        indirect_buffer.vk_buffer(),
+                        indirect_buffer_offset_in_bytes + i * stride as u64,
+                        1,
+                        stride,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Without multiDrawIndirect, draw_count must be 0 or 1, so we issue one draw at a time instead
+    fn supports_multi_draw_indirect(&self) -> bool {
+        self.device_context
+            .physical_device_info()
+            .features
+            .multi_draw_indirect
+            == vk::TRUE
+    }
+
+    pub fn cmd_dispatch_indirect(
+        &self,
+        indirect_buffer: &RafxBufferVulkan,
+        indirect_buffer_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        unsafe {
+            self.device_context.device().cmd_dispatch_indirect(
+                self.vk_command_buffer,
+                indirect_buffer.vk_buffer(),
+                indirect_buffer_offset_in_bytes,
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_reset_query_pool(
+        &self,
+        query_pool: &RafxQueryPoolVulkan,
+        first_query: u32,
+        query_count: u32,
+    ) -> RafxResult<()> {
+        unsafe {
+            self.device_context.device().cmd_reset_query_pool(
+                self.vk_command_buffer,
+                query_pool.vk_query_pool(),
+                first_query,
+                query_count,
+            )
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_begin_query(
+        &self,
+        query_pool: &RafxQueryPoolVulkan,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        unsafe {
+            self.device_context.device().cmd_begin_query(
+                self.vk_command_buffer,
+                query_pool.vk_query_pool(),
+                query_index,
+                vk::QueryControlFlags::empty(),
+            )
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_end_query(
+        &self,
+        query_pool: &RafxQueryPoolVulkan,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        unsafe {
+            self.device_context.device().cmd_end_query(
+                self.vk_command_buffer,
+                query_pool.vk_query_pool(),
+                query_index,
+            )
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_write_timestamp(
+        &self,
+        query_pool: &RafxQueryPoolVulkan,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        // Written once all previously submitted work has completed
+        unsafe {
+            self.device_context.device().cmd_write_timestamp(
+                self.vk_command_buffer,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                query_pool.vk_query_pool(),
+                query_index,
+            )
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_resolve_query(
+        &self,
+        query_pool: &RafxQueryPoolVulkan,
+        first_query: u32,
+        query_count: u32,
+        dst_buffer: &RafxBufferVulkan,
+        dst_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        let values_per_query = query_pool.query_pool_def().query_type.values_per_query();
+        let stride = values_per_query as u64 * std::mem::size_of::<u64>() as u64;
+
+        unsafe {
+            self.device_context.device().cmd_copy_query_pool_results(
+                self.vk_command_buffer,
+                query_pool.vk_query_pool(),
+                first_query,
+                query_count,
+                dst_buffer.vk_buffer(),
+                dst_offset_in_bytes,
+                stride,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+
+        Ok(())
+    }
+
     pub fn cmd_resource_barrier(
         &self,
         buffer_barriers: &[RafxBufferBarrier],
@@ -712,6 +1168,99 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
+    pub fn cmd_clear_texture(
+        &self,
+        texture: &RafxTextureVulkan,
+        params: &RafxCmdClearTextureParams,
+    ) -> RafxResult<()> {
+        let image_layout = super::util::resource_state_to_image_layout(params.texture_state);
+        if image_layout != Some(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            && image_layout != Some(vk::ImageLayout::GENERAL)
+        {
+            Err("cmd_clear_texture requires the texture to be in COPY_DST or UNORDERED_ACCESS state")?;
+        }
+
+        let (base_mip_level, level_count) = match params.mip_slice {
+            Some(mip_slice) => (mip_slice as u32, 1),
+            None => (0, vk::REMAINING_MIP_LEVELS),
+        };
+        let (base_array_layer, layer_count) = match params.array_slice {
+            Some(array_slice) => (array_slice as u32, 1),
+            None => (0, vk::REMAINING_ARRAY_LAYERS),
+        };
+
+        let subresource_range = vk::ImageSubresourceRange {
+            aspect_mask: texture.vk_aspect_mask(),
+            base_mip_level,
+            level_count,
+            base_array_layer,
+            layer_count,
+        };
+
+        let format = texture.texture_def().format;
+        match params.clear_value {
+            RafxTextureClearValue::Color(color) => {
+                if format.has_depth_or_stencil() {
+                    Err("cmd_clear_texture requires a depth/stencil clear value for depth/stencil formats")?;
+                }
+
+                unsafe {
+                    self.device_context.device().cmd_clear_color_image(
+                        self.vk_command_buffer,
+                        texture.vk_image(),
+                        image_layout.unwrap(),
+                        &vk::ClearColorValue { float32: color.0 },
+                        &[subresource_range],
+                    );
+                }
+            }
+            RafxTextureClearValue::DepthStencil(depth_stencil) => {
+                if !format.has_depth_or_stencil() {
+                    Err("cmd_clear_texture requires a color clear value for color formats")?;
+                }
+
+                unsafe {
+                    self.device_context.device().cmd_clear_depth_stencil_image(
+                        self.vk_command_buffer,
+                        texture.vk_image(),
+                        image_layout.unwrap(),
+                        &vk::ClearDepthStencilValue {
+                            depth: depth_stencil.depth,
+                            stencil: depth_stencil.stencil,
+                        },
+                        &[subresource_range],
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_fill_buffer(
+        &self,
+        buffer: &RafxBufferVulkan,
+        offset: u64,
+        size: u64,
+        value: u8,
+    ) -> RafxResult<()> {
+        if offset % 4 != 0 || size % 4 != 0 {
+            Err("cmd_fill_buffer offset and size must be multiples of 4")?;
+        }
+
+        unsafe {
+            self.device_context.device().cmd_fill_buffer(
+                self.vk_command_buffer,
+                buffer.vk_buffer(),
+                offset,
+                size,
+                u32::from_ne_bytes([value; 4]),
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn cmd_copy_buffer_to_buffer(
         &self,
         src_buffer: &RafxBufferVulkan,
@@ -777,7 +1326,135 @@ impl RafxCommandBufferVulkan {
         Ok(())
     }
 
-    pub fn cmd_blit_image(
+    pub fn cmd_copy_texture_to_buffer(
+        &self,
+        src_texture: &RafxTextureVulkan,
+        dst_buffer: &RafxBufferVulkan,
+        params: &RafxCmdCopyTextureToBufferParams,
+    ) -> RafxResult<()> {
+        let texture_def = src_texture.texture_def();
+        let format = texture_def.format;
+        let layout = RafxTextureBufferLayout::new(
+            self.device_context.device_info(),
+            format,
+            texture_def.extents,
+            params.mip_level,
+        );
+
+        // Vulkan specifies the buffer layout in texels rather than bytes
+        let block_size = format.block_or_pixel_size_in_bytes();
+        if layout.bytes_per_row % block_size != 0
+            || layout.bytes_per_image % layout.bytes_per_row != 0
+        {
+            Err(format!(
+                "The buffer layout for format {:?} cannot be expressed in texels",
+                format
+            ))?;
+        }
+
+        let buffer_row_length = layout.bytes_per_row / block_size * format.block_width_in_pixels();
+        let buffer_image_height =
+            layout.bytes_per_image / layout.bytes_per_row * format.block_height_in_pixels();
+
+        unsafe {
+            self.device_context.device().cmd_copy_image_to_buffer(
+                self.vk_command_buffer,
+                src_texture.vk_image(),
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                dst_buffer.vk_buffer(),
+                &[vk::BufferImageCopy {
+                    image_extent: vk::Extent3D {
+                        width: layout.extents.width,
+                        height: layout.extents.height,
+                        depth: layout.extents.depth,
+                    },
+                    image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+                    image_subresource: vk::ImageSubresourceLayers {
+                        aspect_mask: src_texture.vk_aspect_mask(),
+                        mip_level: params.mip_level as u32,
+                        base_array_layer: params.array_layer as u32,
+                        layer_count: 1,
+                    },
+                    buffer_offset: params.buffer_offset,
+                    buffer_image_height,
+                    buffer_row_length,
+                }],
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_copy_texture_to_texture(
+        &self,
+        src_texture: &RafxTextureVulkan,
+        dst_texture: &RafxTextureVulkan,
+        params: &RafxCmdCopyTextureToTextureParams,
+    ) -> RafxResult<()> {
+        if src_texture.texture_def().format != dst_texture.texture_def().format {
+            Err("cmd_copy_texture_to_texture requires both textures to have the same format")?;
+        }
+
+        let mut src_subresource = vk::ImageSubresourceLayers::builder()
+            .aspect_mask(src_texture.vk_aspect_mask())
+            .mip_level(params.src_mip_level as u32)
+            .build();
+        let mut dst_subresource = vk::ImageSubresourceLayers::builder()
+            .aspect_mask(dst_texture.vk_aspect_mask())
+            .mip_level(params.dst_mip_level as u32)
+            .build();
+
+        if let Some(array_slices) = params.array_slices {
+            src_subresource.base_array_layer = array_slices[0] as u32;
+            dst_subresource.base_array_layer = array_slices[1] as u32;
+            src_subresource.layer_count = 1;
+            dst_subresource.layer_count = 1;
+        } else {
+            // Layer counts must match, so copy the layers both textures have
+            let layer_count = src_texture
+                .texture_def()
+                .array_length
+                .min(dst_texture.texture_def().array_length);
+            src_subresource.base_array_layer = 0;
+            dst_subresource.base_array_layer = 0;
+            src_subresource.layer_count = layer_count;
+            dst_subresource.layer_count = layer_count;
+        }
+
+        let image_copy = vk::ImageCopy::builder()
+            .src_subresource(src_subresource)
+            .src_offset(vk::Offset3D {
+                x: params.src_offset.width as i32,
+                y: params.src_offset.height as i32,
+                z: params.src_offset.depth as i32,
+            })
+            .dst_subresource(dst_subresource)
+            .dst_offset(vk::Offset3D {
+                x: params.dst_offset.width as i32,
+                y: params.dst_offset.height as i32,
+                z: params.dst_offset.depth as i32,
+            })
+            .extent(vk::Extent3D {
+                width: params.extents.width,
+                height: params.extents.height,
+                depth: params.extents.depth,
+            });
+
+        unsafe {
+            self.device_context.device().cmd_copy_image(
+                self.vk_command_buffer,
+                src_texture.vk_image(),
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                dst_texture.vk_image(),
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[*image_copy],
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_blit(
         &self,
         src_texture: &RafxTextureVulkan,
         dst_texture: &RafxTextureVulkan,
@@ -855,4 +1532,31 @@ impl RafxCommandBufferVulkan {
 
         Ok(())
     }
+
+    pub fn cmd_push_group_debug_marker(
+        &self,
+        name: impl AsRef<str>,
+    ) -> RafxResult<()> {
+        if let Some(debug_utils) = self.device_context.debug_utils() {
+            let name = std::ffi::CString::new(name.as_ref())
+                .map_err(|_| "Debug marker names cannot contain a nul character")?;
+            let label = vk::DebugUtilsLabelEXT::builder().label_name(&name);
+
+            unsafe {
+                debug_utils.cmd_begin_debug_utils_label(self.vk_command_buffer, &*label);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cmd_pop_group_debug_marker(&self) -> RafxResult<()> {
+        if let Some(debug_utils) = self.device_context.debug_utils() {
+            unsafe {
+                debug_utils.cmd_end_debug_utils_label(self.vk_command_buffer);
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxDescriptorSetResourceStates;
 use crate::vulkan::{DescriptorSetLayoutInfo, RafxDescriptorHeapVulkan, RafxDeviceContextVulkan};
 use crate::*;
 use ash::version::DeviceV1_0;
@@ -23,13 +25,25 @@ impl DescriptorUpdateData {
 }
 
 #[derive(Copy, Clone, Debug)]
-pub struct RafxDescriptorSetHandleVulkan(pub vk::DescriptorSet);
+pub struct RafxDescriptorSetHandleVulkan(
+    pub vk::DescriptorSet,
+    #[cfg(feature = "capture")] pub(crate) crate::capture::RafxCaptureDescriptorSetRef,
+);
+
+#[cfg(feature = "capture")]
+impl RafxDescriptorSetHandleVulkan {
+    pub(crate) fn capture_ref(&self) -> crate::capture::RafxCaptureDescriptorSetRef {
+        self.1
+    }
+}
 
 pub struct RafxDescriptorSetArrayVulkan {
     root_signature: RafxRootSignature,
     set_index: u32,
     // one per set
     descriptor_sets: Vec<vk::DescriptorSet>,
+    // Sets with bindless arrays are allocated from their own pool, which is freed with the array
+    update_after_bind_pool: Option<vk::DescriptorPool>,
     //update_data: Vec<UpdateData>,
     //dynamic_size_offset: Option<SizeOffset>,
     update_data: DescriptorUpdateData,
@@ -37,6 +51,10 @@ pub struct RafxDescriptorSetArrayVulkan {
     // vectors are not added/removed from so their addresses will remain stable, even if this
     // struct is moved
     pending_writes: Vec<vk::WriteDescriptorSet>,
+    #[cfg(feature = "validate-resource-states")]
+    resource_states: RafxDescriptorSetResourceStates,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl std::fmt::Debug for RafxDescriptorSetArrayVulkan {
@@ -53,6 +71,22 @@ impl std::fmt::Debug for RafxDescriptorSetArrayVulkan {
     }
 }
 
+impl Drop for RafxDescriptorSetArrayVulkan {
+    fn drop(&mut self) {
+        if let Some(update_after_bind_pool) = self.update_after_bind_pool {
+            let device = self
+                .root_signature
+                .vk_root_signature()
+                .unwrap()
+                .device_context()
+                .device();
+            unsafe {
+                device.destroy_descriptor_pool(update_after_bind_pool, None);
+            }
+        }
+    }
+}
+
 // For *const c_void in vk::WriteDescriptorSet, which always point at contents of vectors in
 // update_data that never get resized
 unsafe impl Send for RafxDescriptorSetArrayVulkan {}
@@ -67,6 +101,21 @@ impl RafxDescriptorSetArrayVulkan {
         self.set_index
     }
 
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxDescriptorSetResourceStates {
+        &self.resource_states
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states_mut(&mut self) -> &mut RafxDescriptorSetResourceStates {
+        &mut self.resource_states
+    }
+
     pub fn vk_descriptor_set(
         &self,
         index: u32,
@@ -78,9 +127,16 @@ impl RafxDescriptorSetArrayVulkan {
         &self,
         index: u32,
     ) -> Option<RafxDescriptorSetHandleVulkan> {
-        self.descriptor_sets
-            .get(index as usize)
-            .map(|x| RafxDescriptorSetHandleVulkan(*x))
+        self.descriptor_sets.get(index as usize).map(|x| {
+            RafxDescriptorSetHandleVulkan(
+                *x,
+                #[cfg(feature = "capture")]
+                crate::capture::RafxCaptureDescriptorSetRef {
+                    descriptor_set_array_id: self.capture_id,
+                    array_index: index,
+                },
+            )
+        })
     }
 
     pub(crate) fn new(
@@ -122,8 +178,20 @@ impl RafxDescriptorSetArrayVulkan {
             // }
         }
 
-        let descriptor_sets =
-            heap.allocate_descriptor_sets(device_context.device(), &descriptors_set_layouts)?;
+        let layout = &root_signature.inner.layouts[layout_index];
+        let (descriptor_sets, update_after_bind_pool) =
+            if let Some(pool_sizes) = &layout.update_after_bind_pool_sizes {
+                let (descriptor_sets, pool) = Self::allocate_update_after_bind_descriptor_sets(
+                    device_context.device(),
+                    pool_sizes,
+                    &descriptors_set_layouts,
+                )?;
+                (descriptor_sets, Some(pool))
+            } else {
+                let descriptor_sets = heap
+                    .allocate_descriptor_sets(device_context.device(), &descriptors_set_layouts)?;
+                (descriptor_sets, None)
+            };
 
         // let dynamic_size_offset = if dynamic_offset_count > 0 {
         //     assert_eq!(1, dynamic_offset_count);
@@ -136,11 +204,57 @@ impl RafxDescriptorSetArrayVulkan {
             root_signature: RafxRootSignature::Vk(root_signature),
             set_index: descriptor_set_array_def.set_index,
             descriptor_sets,
+            update_after_bind_pool,
             update_data,
             pending_writes: Vec::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_states: RafxDescriptorSetResourceStates::new(
+                descriptor_set_array_def.set_index,
+            ),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 
+    fn allocate_update_after_bind_descriptor_sets(
+        device: &ash::Device,
+        pool_sizes: &[vk::DescriptorPoolSize],
+        set_layouts: &[vk::DescriptorSetLayout],
+    ) -> RafxResult<(Vec<vk::DescriptorSet>, vk::DescriptorPool)> {
+        let set_count = set_layouts.len() as u32;
+        let pool_sizes: Vec<_> = pool_sizes
+            .iter()
+            .map(|x| vk::DescriptorPoolSize {
+                ty: x.ty,
+                descriptor_count: x.descriptor_count * set_count,
+            })
+            .collect();
+
+        let pool = unsafe {
+            device.create_descriptor_pool(
+                &*vk::DescriptorPoolCreateInfo::builder()
+                    .flags(vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND)
+                    .max_sets(set_count)
+                    .pool_sizes(&pool_sizes),
+                None,
+            )?
+        };
+
+        let allocate_info = vk::DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(pool)
+            .set_layouts(set_layouts);
+
+        match unsafe { device.allocate_descriptor_sets(&*allocate_info) } {
+            Ok(descriptor_sets) => Ok((descriptor_sets, pool)),
+            Err(e) => {
+                unsafe {
+                    device.destroy_descriptor_pool(pool, None);
+                }
+                Err(e.into())
+            }
+        }
+    }
+
     pub fn update_descriptor_set(
         &mut self,
         descriptor_updates: &[RafxDescriptorUpdate],
@@ -215,6 +329,13 @@ impl RafxDescriptorSetArrayVulkan {
         //let descriptor_index = descriptor_index.ok_or_else(|| format!("Could not find descriptor {:?}", &update.descriptor_key))?;
         let descriptor = root_signature.descriptor(descriptor_index).unwrap();
 
+        crate::internal_shared::verify_descriptor_update_range(
+            update,
+            &descriptor.name,
+            descriptor.resource_type,
+            descriptor.element_count,
+        )?;
+
         let descriptor_first_update_data = descriptor.update_data_offset_in_set.unwrap()
             + (layout.update_data_count_per_set * update.array_index);
 
@@ -239,6 +360,50 @@ impl RafxDescriptorSetArrayVulkan {
             vk_set
         );
 
+        // Views are bound as-is, so texture_bind_type does not apply
+        if let Some(texture_views) = update.elements.texture_views {
+            let image_layout = match descriptor.resource_type {
+                RafxResourceType::TEXTURE => vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                RafxResourceType::COMBINED_IMAGE_SAMPLER if descriptor.has_immutable_sampler => {
+                    vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+                }
+                RafxResourceType::TEXTURE_READ_WRITE => vk::ImageLayout::GENERAL,
+                _ => Err(format!(
+                    "Tried to update binding {:?} (set: {:?} binding: {} name: {:?} type: {:?}) with texture views but it does not accept them",
+                    update.descriptor_key,
+                    descriptor.set_index,
+                    descriptor.binding,
+                    descriptor.name,
+                    descriptor.resource_type,
+                ))?,
+            };
+
+            let begin_index = (descriptor_first_update_data + update.dst_element_offset) as usize;
+            assert!(begin_index + texture_views.len() <= self.update_data.update_data_count);
+
+            // Modify the update data
+            let mut next_index = begin_index;
+            for texture_view in texture_views {
+                let image_info = &mut self.update_data.image_infos[next_index];
+                next_index += 1;
+
+                image_info.image_view = texture_view
+                    .vk_texture_view()
+                    .unwrap()
+                    .vk_descriptor_image_view();
+                image_info.image_layout = image_layout;
+            }
+
+            // Queue a descriptor write
+            self.pending_writes.push(
+                write_descriptor_builder
+                    .image_info(&self.update_data.image_infos[begin_index..next_index])
+                    .build(),
+            );
+
+            return Ok(());
+        }
+
         match descriptor.resource_type {
             RafxResourceType::SAMPLER => {
                 if descriptor.has_immutable_sampler {
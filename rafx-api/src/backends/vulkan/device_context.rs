@@ -1,18 +1,25 @@
 use super::internal::*;
 use crate::*;
-use ash::version::{DeviceV1_0, InstanceV1_0};
+use ash::version::{DeviceV1_0, InstanceV1_0, InstanceV1_1};
 use ash::vk;
 use raw_window_handle::HasRawWindowHandle;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
 
+#[cfg(feature = "capture")]
+use crate::capture::RafxCaptureRecorder;
+use crate::internal_shared::RafxMemoryUsageTracker;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxResourceStateTracker;
 use crate::vulkan::{
-    RafxBufferVulkan, RafxDescriptorSetArrayVulkan, RafxFenceVulkan, RafxPipelineVulkan,
-    RafxQueueVulkan, RafxRootSignatureVulkan, RafxSamplerVulkan, RafxSemaphoreVulkan,
-    RafxShaderModuleVulkan, RafxShaderVulkan, RafxSwapchainVulkan, RafxTextureVulkan,
+    RafxBufferVulkan, RafxDescriptorSetArrayVulkan, RafxFenceVulkan, RafxHeapVulkan,
+    RafxPipelineVulkan, RafxQueryPoolVulkan, RafxQueueVulkan, RafxRootSignatureVulkan,
+    RafxSamplerVulkan, RafxSemaphoreVulkan, RafxShaderModuleVulkan, RafxShaderVulkan,
+    RafxSwapchainVulkan, RafxTextureViewVulkan, RafxTextureVulkan, RafxTimelineSemaphoreVulkan,
 };
+use ash::extensions::ext::DebugUtils;
 use ash::extensions::khr;
 use fnv::FnvHashMap;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 #[cfg(debug_assertions)]
 #[cfg(feature = "track-device-contexts")]
 use std::sync::atomic::AtomicU64;
@@ -53,6 +60,12 @@ impl PhysicalDeviceType {
     }
 }
 
+// Used to score adapters when the application doesn't choose one explicitly
+const PHYSICAL_DEVICE_TYPE_PRIORITY: &[PhysicalDeviceType] = &[
+    PhysicalDeviceType::DiscreteGpu,
+    PhysicalDeviceType::IntegratedGpu,
+];
+
 #[derive(Clone)]
 pub struct PhysicalDeviceInfo {
     pub score: i32,
@@ -80,11 +93,23 @@ pub struct RafxDeviceContextVulkanInner {
     // lock ensures that the present operations for those swapchains do not occur concurrently
     pub(crate) dedicated_present_queue_lock: Mutex<()>,
 
+    // Used when creating all pipelines. Pipeline creation takes a read lock, merging data into the
+    // cache requires external synchronization so it takes a write lock
+    pipeline_cache: RwLock<vk::PipelineCache>,
+
     device: ash::Device,
     allocator: vk_mem::Allocator,
     destroyed: AtomicBool,
     entry: Arc<VkEntry>,
     instance: ash::Instance,
+    debug_utils: Option<DebugUtils>,
+    timeline_semaphore: Option<khr::TimelineSemaphore>,
+    supports_memory_budget: bool,
+    memory_usage_tracker: RafxMemoryUsageTracker,
+    #[cfg(feature = "validate-resource-states")]
+    resource_state_tracker: RafxResourceStateTracker,
+    #[cfg(feature = "capture")]
+    capture_recorder: RafxCaptureRecorder,
     physical_device: vk::PhysicalDevice,
     physical_device_info: PhysicalDeviceInfo,
 
@@ -102,6 +127,8 @@ impl Drop for RafxDeviceContextVulkanInner {
         if !self.destroyed.swap(true, Ordering::AcqRel) {
             unsafe {
                 log::trace!("destroying device");
+                self.device
+                    .destroy_pipeline_cache(*self.pipeline_cache.get_mut().unwrap(), None);
                 self.allocator.destroy();
                 self.device.destroy_device(None);
                 //self.surface_loader.destroy_surface(self.surface, None);
@@ -112,15 +139,16 @@ impl Drop for RafxDeviceContextVulkanInner {
 }
 
 impl RafxDeviceContextVulkanInner {
-    pub fn new(instance: &VkInstance) -> RafxResult<Self> {
-        let physical_device_type_priority = vec![
-            PhysicalDeviceType::DiscreteGpu,
-            PhysicalDeviceType::IntegratedGpu,
-        ];
-
+    pub fn new(
+        instance: &VkInstance,
+        adapter_index: Option<u32>,
+    ) -> RafxResult<Self> {
         // Pick a physical device
-        let (physical_device, physical_device_info) =
-            choose_physical_device(&instance.instance, &physical_device_type_priority)?;
+        let (physical_device, physical_device_info) = choose_physical_device(
+            &instance.instance,
+            PHYSICAL_DEVICE_TYPE_PRIORITY,
+            adapter_index,
+        )?;
 
         //TODO: Don't hardcode queue counts
         let queue_requirements = VkQueueRequirements::determine_required_queue_counts(
@@ -131,14 +159,64 @@ impl RafxDeviceContextVulkanInner {
             VkQueueAllocationStrategy::ShareFirstQueueInFamily,
         );
 
+        // Timeline semaphores are core in vulkan 1.2, but we only require 1.1 so use the extension
+        let supports_timeline_semaphores =
+            physical_device_info
+                .extension_properties
+                .iter()
+                .any(|extension| unsafe {
+                    CStr::from_ptr(extension.extension_name.as_ptr())
+                        == khr::TimelineSemaphore::name()
+                });
+
+        // Used to report per-heap budget/usage in memory_statistics()
+        let supports_memory_budget =
+            physical_device_info
+                .extension_properties
+                .iter()
+                .any(|extension| unsafe {
+                    CStr::from_ptr(extension.extension_name.as_ptr())
+                        == vk::ExtMemoryBudgetFn::name()
+                });
+
+        // Descriptor indexing is core in vulkan 1.2, but we only require 1.1 so use the extension.
+        // Bindless arrays also need a few of its optional features, so check those too
+        let supports_descriptor_indexing =
+            physical_device_info
+                .extension_properties
+                .iter()
+                .any(|extension| unsafe {
+                    CStr::from_ptr(extension.extension_name.as_ptr())
+                        == vk::ExtDescriptorIndexingFn::name()
+                });
+
+        let (supports_bindless_descriptors, max_bindless_descriptor_count) =
+            if supports_descriptor_indexing {
+                query_bindless_descriptor_support(&instance.instance, physical_device)
+            } else {
+                (false, 0)
+            };
+
         // Create a logical device
         let logical_device = create_logical_device(
             &instance.instance,
             physical_device,
             &physical_device_info,
             &queue_requirements,
+            supports_timeline_semaphores,
+            supports_memory_budget,
+            supports_bindless_descriptors,
         )?;
 
+        let timeline_semaphore = if supports_timeline_semaphores {
+            Some(khr::TimelineSemaphore::new(
+                &*instance.entry,
+                &instance.instance,
+            ))
+        } else {
+            None
+        };
+
         let queue_allocator = VkQueueAllocatorSet::new(
             &logical_device,
             &physical_device_info.all_queue_families,
@@ -158,6 +236,22 @@ impl RafxDeviceContextVulkanInner {
         let allocator = vk_mem::Allocator::new(&allocator_create_info)?;
 
         let limits = &physical_device_info.properties.limits;
+        let features = &physical_device_info.features;
+
+        // A sample count is only usable by rafx if both color and depth attachments support it
+        let framebuffer_sample_counts =
+            limits.framebuffer_color_sample_counts & limits.framebuffer_depth_sample_counts;
+        let supported_sample_counts = [
+            RafxSampleCount::SampleCount1,
+            RafxSampleCount::SampleCount2,
+            RafxSampleCount::SampleCount4,
+            RafxSampleCount::SampleCount8,
+            RafxSampleCount::SampleCount16,
+        ]
+        .iter()
+        .copied()
+        .filter(|&sample_count| framebuffer_sample_counts.contains(sample_count.into()))
+        .collect();
 
         let device_info = RafxDeviceInfo {
             min_uniform_buffer_offset_alignment: limits.min_uniform_buffer_offset_alignment as u32,
@@ -166,11 +260,49 @@ impl RafxDeviceContextVulkanInner {
             upload_buffer_texture_row_alignment: limits.optimal_buffer_copy_row_pitch_alignment
                 as u32,
             supports_clamp_to_border_color: true,
+            // Timestamps must be supported on all graphics and compute queues to be usable
+            supports_timestamp_queries: limits.timestamp_compute_and_graphics == vk::TRUE
+                && limits.timestamp_period > 0.0,
+            supports_occlusion_queries: true,
+            supports_pipeline_statistics_queries: features.pipeline_statistics_query == vk::TRUE,
+            timestamp_period_ns: limits.timestamp_period,
+            supports_timeline_semaphores,
+            pipeline_cache_uuid: physical_device_info.properties.pipeline_cache_uuid,
+            max_texture_dimension_2d: limits.max_image_dimension2_d,
+            max_texture_dimension_3d: limits.max_image_dimension3_d,
+            max_texture_dimension_cube: limits.max_image_dimension_cube,
+            max_texture_array_layers: limits.max_image_array_layers,
+            max_sampler_anisotropy: limits.max_sampler_anisotropy,
+            max_color_render_targets: limits.max_color_attachments,
+            max_vertex_input_bindings: limits.max_vertex_input_bindings,
+            max_vertex_attributes: limits.max_vertex_input_attributes,
+            max_compute_workgroup_size: limits.max_compute_work_group_size,
+            max_compute_workgroup_invocations: limits.max_compute_work_group_invocations,
+            max_compute_workgroup_count: limits.max_compute_work_group_count,
+            supported_sample_counts,
+            supports_bc_texture_compression: features.texture_compression_bc == vk::TRUE,
+            supports_astc_texture_compression: features.texture_compression_astc_ldr == vk::TRUE,
+            supports_etc2_texture_compression: features.texture_compression_etc2 == vk::TRUE,
+            supports_geometry_shaders: features.geometry_shader == vk::TRUE,
+            supports_tessellation_shaders: features.tessellation_shader == vk::TRUE,
+            supports_wide_lines: features.wide_lines == vk::TRUE,
+            max_line_width: if features.wide_lines == vk::TRUE {
+                limits.line_width_range[1]
+            } else {
+                1.0
+            },
+            supports_bindless_descriptors,
+            max_bindless_descriptor_count,
         };
 
         let resource_cache = RafxDeviceVulkanResourceCache::default();
         let descriptor_heap = RafxDescriptorHeapVulkan::new(&logical_device)?;
 
+        // Starts empty, use RafxDeviceContext::load_pipeline_cache_data() to seed it
+        let pipeline_cache = unsafe {
+            logical_device.create_pipeline_cache(&vk::PipelineCacheCreateInfo::default(), None)?
+        };
+
         #[cfg(debug_assertions)]
         #[cfg(feature = "track-device-contexts")]
         let all_contexts = {
@@ -186,8 +318,17 @@ impl RafxDeviceContextVulkanInner {
             device_info,
             queue_allocator,
             dedicated_present_queue_lock: Mutex::default(),
+            pipeline_cache: RwLock::new(pipeline_cache),
             entry: instance.entry.clone(),
             instance: instance.instance.clone(),
+            debug_utils: instance.debug_utils.clone(),
+            timeline_semaphore,
+            supports_memory_budget,
+            memory_usage_tracker: Default::default(),
+            #[cfg(feature = "validate-resource-states")]
+            resource_state_tracker: Default::default(),
+            #[cfg(feature = "capture")]
+            capture_recorder: Default::default(),
             physical_device,
             physical_device_info,
             device: logical_device,
@@ -329,6 +470,171 @@ impl RafxDeviceContextVulkan {
         &self.inner.dedicated_present_queue_lock
     }
 
+    pub fn memory_statistics(&self) -> RafxResult<RafxMemoryStatistics> {
+        let mut memory_statistics = self.inner.memory_usage_tracker.memory_statistics();
+
+        let mut memory_budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut memory_properties = vk::PhysicalDeviceMemoryProperties2::builder();
+        if self.inner.supports_memory_budget {
+            memory_properties = memory_properties.push_next(&mut memory_budget_properties);
+        }
+
+        unsafe {
+            self.instance().get_physical_device_memory_properties2(
+                self.inner.physical_device,
+                &mut *memory_properties,
+            );
+        }
+
+        // Copy out of the builder so that it no longer borrows memory_budget_properties
+        let memory_properties = memory_properties.memory_properties;
+        for heap_index in 0..memory_properties.memory_heap_count as usize {
+            let heap = &memory_properties.memory_heaps[heap_index];
+            let (budget_bytes, usage_bytes) = if self.inner.supports_memory_budget {
+                (
+                    memory_budget_properties.heap_budget[heap_index],
+                    memory_budget_properties.heap_usage[heap_index],
+                )
+            } else {
+                (heap.size, 0)
+            };
+
+            memory_statistics.heaps.push(RafxMemoryHeapStatistics {
+                device_local: heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL),
+                size_bytes: heap.size,
+                budget_bytes,
+                usage_bytes,
+            });
+        }
+
+        Ok(memory_statistics)
+    }
+
+    pub(crate) fn memory_usage_tracker(&self) -> &RafxMemoryUsageTracker {
+        &self.inner.memory_usage_tracker
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_state_tracker(&self) -> &RafxResourceStateTracker {
+        &self.inner.resource_state_tracker
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &RafxCaptureRecorder {
+        &self.inner.capture_recorder
+    }
+
+    // Hold the guard while creating pipelines with the cache
+    pub(crate) fn pipeline_cache(&self) -> RwLockReadGuard<vk::PipelineCache> {
+        self.inner.pipeline_cache.read().unwrap()
+    }
+
+    pub fn pipeline_cache_data(&self) -> RafxResult<Vec<u8>> {
+        let pipeline_cache = self.pipeline_cache();
+        unsafe { Ok(self.device().get_pipeline_cache_data(*pipeline_cache)?) }
+    }
+
+    pub fn load_pipeline_cache_data(
+        &self,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        self.verify_pipeline_cache_header(data)?;
+
+        let create_info = vk::PipelineCacheCreateInfo::builder().initial_data(data);
+        let device = self.device();
+        unsafe {
+            let src_pipeline_cache = device.create_pipeline_cache(&*create_info, None)?;
+            let result = {
+                let dst_pipeline_cache = self.inner.pipeline_cache.write().unwrap();
+                device.merge_pipeline_caches(*dst_pipeline_cache, &[src_pipeline_cache])
+            };
+            device.destroy_pipeline_cache(src_pipeline_cache, None);
+            result?;
+        }
+
+        Ok(())
+    }
+
+    // Drivers are required to ignore incompatible data, but not all of them do. Check the
+    // VK_PIPELINE_CACHE_HEADER_VERSION_ONE header before passing the data to the driver.
+    fn verify_pipeline_cache_header(
+        &self,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        const HEADER_SIZE: usize = 32;
+        if data.len() < HEADER_SIZE {
+            Err("Pipeline cache data is too small to contain a header")?;
+        }
+
+        // The header is written least significant byte first
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ])
+        };
+
+        let header_version = read_u32(4);
+        if header_version != vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32 {
+            Err(format!(
+                "Pipeline cache data has unsupported header version {}",
+                header_version
+            ))?;
+        }
+
+        let properties = &self.physical_device_info().properties;
+        if read_u32(8) != properties.vendor_id
+            || read_u32(12) != properties.device_id
+            || data[16..32] != properties.pipeline_cache_uuid
+        {
+            Err("Pipeline cache data was created by a different device or driver")?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the VK_EXT_debug_utils loader if the extension is available
+    pub fn debug_utils(&self) -> Option<&DebugUtils> {
+        self.inner.debug_utils.as_ref()
+    }
+
+    /// Returns the VK_KHR_timeline_semaphore loader if the extension is enabled
+    pub fn timeline_semaphore(&self) -> Option<&khr::TimelineSemaphore> {
+        self.inner.timeline_semaphore.as_ref()
+    }
+
+    // Names the object for debugging tools. Does nothing if VK_EXT_debug_utils is not available
+    pub(crate) fn set_debug_name<T: vk::Handle>(
+        &self,
+        handle: T,
+        name: &str,
+    ) {
+        if let Some(debug_utils) = &self.inner.debug_utils {
+            let name = match CString::new(name) {
+                Ok(name) => name,
+                Err(_) => {
+                    log::warn!("Debug name {:?} contains a nul character", name);
+                    return;
+                }
+            };
+
+            let name_info = vk::DebugUtilsObjectNameInfoEXT::builder()
+                .object_type(T::TYPE)
+                .object_handle(handle.as_raw())
+                .object_name(&name);
+
+            let result = unsafe {
+                debug_utils.debug_utils_set_object_name(self.device().handle(), &*name_info)
+            };
+
+            if let Err(e) = result {
+                log::warn!("Failed to set debug name {:?}: {:?}", name, e);
+            }
+        }
+    }
+
     pub fn new(
         // instance: &VkInstance,
         // window: &dyn HasRawWindowHandle,
@@ -359,6 +665,13 @@ impl RafxDeviceContextVulkan {
         RafxSemaphoreVulkan::new(self)
     }
 
+    pub fn create_timeline_semaphore(
+        &self,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreVulkan> {
+        RafxTimelineSemaphoreVulkan::new(self, initial_value)
+    }
+
     pub fn create_swapchain(
         &self,
         raw_window_handle: &dyn HasRawWindowHandle,
@@ -374,6 +687,13 @@ impl RafxDeviceContextVulkan {
         RafxFenceVulkan::wait_for_fences(self, fences)
     }
 
+    pub fn create_query_pool(
+        &self,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolVulkan> {
+        RafxQueryPoolVulkan::new(self, query_pool_def)
+    }
+
     pub fn create_sampler(
         &self,
         sampler_def: &RafxSamplerDef,
@@ -388,6 +708,14 @@ impl RafxDeviceContextVulkan {
         RafxTextureVulkan::new(self, texture_def)
     }
 
+    pub fn create_texture_view(
+        &self,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewVulkan> {
+        RafxTextureViewVulkan::new(self, texture, texture_view_def)
+    }
+
     pub fn create_buffer(
         &self,
         buffer_def: &RafxBufferDef,
@@ -395,6 +723,45 @@ impl RafxDeviceContextVulkan {
         RafxBufferVulkan::new(self, buffer_def)
     }
 
+    pub fn create_heap(
+        &self,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeapVulkan> {
+        RafxHeapVulkan::new(self, heap_def)
+    }
+
+    pub fn texture_memory_requirements(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        RafxTextureVulkan::memory_requirements(self, texture_def)
+    }
+
+    pub fn buffer_memory_requirements(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        RafxBufferVulkan::memory_requirements(self, buffer_def)
+    }
+
+    pub fn create_texture_in_heap(
+        &self,
+        heap: &RafxHeapVulkan,
+        offset: u64,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureVulkan> {
+        RafxTextureVulkan::new_in_heap(self, heap, offset, texture_def)
+    }
+
+    pub fn create_buffer_in_heap(
+        &self,
+        heap: &RafxHeapVulkan,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBufferVulkan> {
+        RafxBufferVulkan::new_in_heap(self, heap, offset, buffer_def)
+    }
+
     pub fn create_shader(
         &self,
         stages: Vec<RafxShaderStageDef>,
@@ -538,9 +905,61 @@ fn do_find_supported_sample_count(
     None
 }
 
+pub(crate) fn enumerate_adapters(instance: &ash::Instance) -> RafxResult<Vec<RafxAdapterInfo>> {
+    let physical_devices = unsafe { instance.enumerate_physical_devices()? };
+
+    let mut adapters = Vec::with_capacity(physical_devices.len());
+    let mut best_adapter_index = None;
+    let mut best_score = -1;
+    for (adapter_index, &physical_device) in physical_devices.iter().enumerate() {
+        let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+        let name = unsafe {
+            CStr::from_ptr(properties.device_name.as_ptr())
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let adapter_type = match properties.device_type {
+            vk::PhysicalDeviceType::DISCRETE_GPU => RafxAdapterType::DiscreteGpu,
+            vk::PhysicalDeviceType::INTEGRATED_GPU => RafxAdapterType::IntegratedGpu,
+            vk::PhysicalDeviceType::VIRTUAL_GPU => RafxAdapterType::VirtualGpu,
+            vk::PhysicalDeviceType::CPU => RafxAdapterType::Cpu,
+            _ => RafxAdapterType::Other,
+        };
+
+        // Same scoring as choose_physical_device, so is_default matches the adapter it would pick
+        let physical_device_info =
+            query_physical_device_info(instance, physical_device, PHYSICAL_DEVICE_TYPE_PRIORITY)?;
+        if let Some(physical_device_info) = &physical_device_info {
+            if physical_device_info.score > best_score {
+                best_adapter_index = Some(adapter_index);
+                best_score = physical_device_info.score;
+            }
+        }
+
+        adapters.push(RafxAdapterInfo {
+            adapter_index: adapter_index as u32,
+            name,
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            adapter_type,
+            driver_version: vk_version_to_string(properties.driver_version),
+            is_supported: physical_device_info.is_some(),
+            is_default: false,
+        });
+    }
+
+    if let Some(best_adapter_index) = best_adapter_index {
+        adapters[best_adapter_index].is_default = true;
+    }
+
+    Ok(adapters)
+}
+
 fn choose_physical_device(
     instance: &ash::Instance,
     physical_device_type_priority: &[PhysicalDeviceType],
+    adapter_index: Option<u32>,
 ) -> RafxResult<(ash::vk::PhysicalDevice, PhysicalDeviceInfo)> {
     let physical_devices = unsafe { instance.enumerate_physical_devices()? };
 
@@ -548,6 +967,25 @@ fn choose_physical_device(
         panic!("Could not find a physical device");
     }
 
+    // Skip scoring if the application chose an adapter explicitly
+    if let Some(adapter_index) = adapter_index {
+        let physical_device = *physical_devices
+            .get(adapter_index as usize)
+            .ok_or_else(|| {
+                format!(
+                    "Adapter index {} is out of range, {} adapters are available",
+                    adapter_index,
+                    physical_devices.len()
+                )
+            })?;
+
+        let physical_device_info =
+            query_physical_device_info(instance, physical_device, physical_device_type_priority)?
+                .ok_or_else(|| format!("Adapter index {} is not supported", adapter_index))?;
+
+        return Ok((physical_device, physical_device_info));
+    }
+
     let mut best_physical_device = None;
     let mut best_physical_device_info = None;
     let mut best_physical_device_score = -1;
@@ -788,11 +1226,51 @@ fn find_queue_families(
     }
 }
 
+// Returns if the descriptor indexing features needed for RafxBindlessArray are supported, and the
+// largest array that may be declared. Only call this if VK_EXT_descriptor_indexing is available.
+fn query_bindless_descriptor_support(
+    instance: &ash::Instance,
+    physical_device: ash::vk::PhysicalDevice,
+) -> (bool, u32) {
+    let mut indexing_features = vk::PhysicalDeviceDescriptorIndexingFeatures::default();
+    let mut indexing_properties = vk::PhysicalDeviceDescriptorIndexingProperties::default();
+    unsafe {
+        let mut features = vk::PhysicalDeviceFeatures2::builder().push_next(&mut indexing_features);
+        instance.get_physical_device_features2(physical_device, &mut *features);
+
+        let mut properties =
+            vk::PhysicalDeviceProperties2::builder().push_next(&mut indexing_properties);
+        instance.get_physical_device_properties2(physical_device, &mut *properties);
+    }
+
+    let supported = indexing_features.runtime_descriptor_array == vk::TRUE
+        && indexing_features.descriptor_binding_partially_bound == vk::TRUE
+        && indexing_features.descriptor_binding_sampled_image_update_after_bind == vk::TRUE
+        && indexing_features.descriptor_binding_storage_image_update_after_bind == vk::TRUE
+        && indexing_features.descriptor_binding_storage_buffer_update_after_bind == vk::TRUE
+        && indexing_features.shader_sampled_image_array_non_uniform_indexing == vk::TRUE;
+
+    if !supported {
+        return (false, 0);
+    }
+
+    // A bindless array may hold any of these descriptor types, so use the most restrictive limit
+    let max_count = indexing_properties
+        .max_per_stage_descriptor_update_after_bind_sampled_images
+        .min(indexing_properties.max_per_stage_descriptor_update_after_bind_storage_images)
+        .min(indexing_properties.max_per_stage_descriptor_update_after_bind_storage_buffers);
+
+    (true, max_count)
+}
+
 fn create_logical_device(
     instance: &ash::Instance,
     physical_device: ash::vk::PhysicalDevice,
     physical_device_info: &PhysicalDeviceInfo,
     queue_requirements: &VkQueueRequirements,
+    enable_timeline_semaphores: bool,
+    enable_memory_budget: bool,
+    enable_bindless_descriptors: bool,
 ) -> RafxResult<ash::Device> {
     //TODO: Ideally we would set up validation layers for the logical device too.
 
@@ -813,13 +1291,44 @@ fn create_logical_device(
         }
     }
 
+    if enable_timeline_semaphores {
+        device_extension_names.push(khr::TimelineSemaphore::name().as_ptr());
+    }
+
+    if enable_memory_budget {
+        device_extension_names.push(vk::ExtMemoryBudgetFn::name().as_ptr());
+    }
+
+    if enable_bindless_descriptors {
+        device_extension_names.push(vk::ExtDescriptorIndexingFn::name().as_ptr());
+    }
+
     // Features enabled here by default are supported very widely (only unsupported devices on
     // vulkan.gpuinfo.org are SwiftShader, a software renderer.
     let features = vk::PhysicalDeviceFeatures::builder()
         .sampler_anisotropy(true)
         .sample_rate_shading(true)
         // Used for debug drawing lines/points
-        .fill_mode_non_solid(true);
+        .fill_mode_non_solid(true)
+        // Optional, indirect draws fall back to one draw per call if unsupported
+        .multi_draw_indirect(physical_device_info.features.multi_draw_indirect == vk::TRUE)
+        // Optional, reported by RafxDeviceInfo::supports_pipeline_statistics_queries
+        .pipeline_statistics_query(
+            physical_device_info.features.pipeline_statistics_query == vk::TRUE,
+        )
+        // Optional, reported by RafxDeviceInfo::supports_*_texture_compression
+        .texture_compression_bc(physical_device_info.features.texture_compression_bc == vk::TRUE)
+        .texture_compression_astc_ldr(
+            physical_device_info.features.texture_compression_astc_ldr == vk::TRUE,
+        )
+        .texture_compression_etc2(
+            physical_device_info.features.texture_compression_etc2 == vk::TRUE,
+        )
+        // Optional, reported by RafxDeviceInfo::supports_*_shaders
+        .geometry_shader(physical_device_info.features.geometry_shader == vk::TRUE)
+        .tessellation_shader(physical_device_info.features.tessellation_shader == vk::TRUE)
+        // Optional, reported by RafxDeviceInfo::supports_wide_lines
+        .wide_lines(physical_device_info.features.wide_lines == vk::TRUE);
 
     let mut queue_families_to_create = FnvHashMap::default();
     for (&queue_family_index, &count) in &queue_requirements.queue_counts {
@@ -836,11 +1345,32 @@ fn create_logical_device(
         })
         .collect();
 
-    let device_create_info = vk::DeviceCreateInfo::builder()
+    // The feature is required to be supported if the extension is
+    let mut timeline_semaphore_features =
+        vk::PhysicalDeviceTimelineSemaphoreFeatures::builder().timeline_semaphore(true);
+
+    // Only the features checked by query_bindless_descriptor_support()
+    let mut descriptor_indexing_features = vk::PhysicalDeviceDescriptorIndexingFeatures::builder()
+        .runtime_descriptor_array(true)
+        .descriptor_binding_partially_bound(true)
+        .descriptor_binding_sampled_image_update_after_bind(true)
+        .descriptor_binding_storage_image_update_after_bind(true)
+        .descriptor_binding_storage_buffer_update_after_bind(true)
+        .shader_sampled_image_array_non_uniform_indexing(true);
+
+    let mut device_create_info = vk::DeviceCreateInfo::builder()
         .queue_create_infos(&queue_infos)
         .enabled_extension_names(&device_extension_names)
         .enabled_features(&features);
 
+    if enable_timeline_semaphores {
+        device_create_info = device_create_info.push_next(&mut timeline_semaphore_features);
+    }
+
+    if enable_bindless_descriptors {
+        device_create_info = device_create_info.push_next(&mut descriptor_indexing_features);
+    }
+
     let device: ash::Device =
         unsafe { instance.create_device(physical_device, &device_create_info, None)? };
 
@@ -10,6 +10,9 @@ pub struct RafxFenceVulkan {
     // Set to true when an operation is scheduled to signal this fence
     // Cleared when an operation is scheduled to consume this fence
     submitted: AtomicBool,
+    // Used internally to identify the fence for validation
+    #[cfg(feature = "validate-resource-states")]
+    fence_id: u32,
 }
 
 impl Drop for RafxFenceVulkan {
@@ -32,9 +35,21 @@ impl RafxFenceVulkan {
             device_context: device_context.clone(),
             vk_fence,
             submitted: AtomicBool::new(false),
+            #[cfg(feature = "validate-resource-states")]
+            fence_id: crate::internal_shared::NEXT_FENCE_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn fence_id(&self) -> u32 {
+        self.fence_id
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn device_context(&self) -> &RafxDeviceContextVulkan {
+        &self.device_context
+    }
+
     pub fn vk_fence(&self) -> vk::Fence {
         self.vk_fence
     }
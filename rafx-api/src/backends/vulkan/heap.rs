@@ -0,0 +1,129 @@
+use crate::vulkan::RafxDeviceContextVulkan;
+use crate::{RafxHeapDef, RafxMemoryRequirements, RafxResult};
+use ash::vk;
+use std::sync::Arc;
+
+// The heap's memory is a dedicated allocation, so it starts at offset 0 of its vk::DeviceMemory.
+// This is only a minimum, placed resources provide their own alignment.
+const HEAP_ALIGNMENT: u64 = 256;
+
+#[derive(Debug)]
+pub struct RafxHeapVulkanInner {
+    device_context: RafxDeviceContextVulkan,
+    heap_def: RafxHeapDef,
+    allocation: vk_mem::Allocation,
+    allocation_info: vk_mem::AllocationInfo,
+}
+
+impl Drop for RafxHeapVulkanInner {
+    fn drop(&mut self) {
+        log::trace!("destroying RafxHeapVulkanInner");
+        self.device_context
+            .allocator()
+            .free_memory(&self.allocation)
+            .unwrap();
+    }
+}
+
+/// A single vk_mem allocation that buffers and textures are bound to at explicit offsets
+#[derive(Clone, Debug)]
+pub struct RafxHeapVulkan {
+    inner: Arc<RafxHeapVulkanInner>,
+}
+
+impl RafxHeapVulkan {
+    pub fn heap_def(&self) -> &RafxHeapDef {
+        &self.inner.heap_def
+    }
+
+    pub fn vk_allocation(&self) -> vk_mem::Allocation {
+        self.inner.allocation
+    }
+
+    pub fn vk_allocation_info(&self) -> &vk_mem::AllocationInfo {
+        &self.inner.allocation_info
+    }
+
+    pub fn vk_device_memory(&self) -> vk::DeviceMemory {
+        self.inner.allocation_info.get_device_memory()
+    }
+
+    // Offset of the heap's memory within the vk::DeviceMemory
+    pub fn vk_device_memory_offset(&self) -> u64 {
+        self.inner.allocation_info.get_offset() as u64
+    }
+
+    pub fn vk_memory_type_index(&self) -> u32 {
+        self.inner.allocation_info.get_memory_type()
+    }
+
+    // Checks that a resource with the given requirements can be bound at the offset
+    pub(crate) fn verify_placement(
+        &self,
+        offset: u64,
+        memory_requirements: &vk::MemoryRequirements,
+    ) -> RafxResult<()> {
+        if memory_requirements.memory_type_bits & (1 << self.vk_memory_type_index()) == 0 {
+            Err(format!(
+                "The resource cannot be placed in this heap, it requires one of memory types {:#b} but the heap uses memory type {}",
+                memory_requirements.memory_type_bits,
+                self.vk_memory_type_index()
+            ))?;
+        }
+
+        self.inner.heap_def.verify_placement(
+            offset,
+            &RafxMemoryRequirements {
+                size: memory_requirements.size,
+                alignment: memory_requirements.alignment,
+            },
+        )
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextVulkan,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<Self> {
+        if heap_def.size == 0 {
+            return Err("Cannot create a heap with a size of 0")?;
+        }
+
+        let memory_requirements = vk::MemoryRequirements {
+            size: heap_def.size,
+            alignment: HEAP_ALIGNMENT,
+            memory_type_bits: !0,
+        };
+
+        let allocation_create_info = vk_mem::AllocationCreateInfo {
+            usage: heap_def.memory_usage.into(),
+            flags: vk_mem::AllocationCreateFlags::DEDICATED_MEMORY,
+            required_flags: vk::MemoryPropertyFlags::empty(),
+            preferred_flags: vk::MemoryPropertyFlags::empty(),
+            memory_type_bits: 0, // Do not exclude any memory types
+            pool: None,
+            user_data: None,
+        };
+
+        let (allocation, allocation_info) = device_context
+            .allocator()
+            .allocate_memory(&memory_requirements, &allocation_create_info)?;
+
+        log::trace!(
+            "Heap {:?} created with size {} in memory type {}",
+            allocation_info.get_device_memory(),
+            heap_def.size,
+            allocation_info.get_memory_type()
+        );
+
+        let inner = RafxHeapVulkanInner {
+            device_context: device_context.clone(),
+            heap_def: heap_def.clone(),
+            allocation,
+            allocation_info,
+        };
+
+        Ok(RafxHeapVulkan {
+            inner: Arc::new(inner),
+        })
+    }
+}
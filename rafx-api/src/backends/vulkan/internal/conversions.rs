@@ -3,7 +3,7 @@ use crate::{
     RafxCompareOp, RafxCullMode, RafxDepthStencilClearValue, RafxFillMode, RafxFilterType,
     RafxFrontFace, RafxIndexType, RafxLoadOp, RafxMemoryUsage, RafxMipMapMode,
     RafxPrimitiveTopology, RafxSampleCount, RafxShaderStageFlags, RafxStencilOp, RafxStoreOp,
-    RafxVertexAttributeRate,
+    RafxTextureViewDimensions, RafxVertexAttributeRate,
 };
 use ash::vk;
 
@@ -274,3 +274,17 @@ impl Into<vk::ClearValue> for RafxDepthStencilClearValue {
         }
     }
 }
+
+impl Into<vk::ImageViewType> for RafxTextureViewDimensions {
+    fn into(self) -> vk::ImageViewType {
+        match self {
+            RafxTextureViewDimensions::Dim1D => vk::ImageViewType::TYPE_1D,
+            RafxTextureViewDimensions::Dim1DArray => vk::ImageViewType::TYPE_1D_ARRAY,
+            RafxTextureViewDimensions::Dim2D => vk::ImageViewType::TYPE_2D,
+            RafxTextureViewDimensions::Dim2DArray => vk::ImageViewType::TYPE_2D_ARRAY,
+            RafxTextureViewDimensions::Dim3D => vk::ImageViewType::TYPE_3D,
+            RafxTextureViewDimensions::Cube => vk::ImageViewType::CUBE,
+            RafxTextureViewDimensions::CubeArray => vk::ImageViewType::CUBE_ARRAY,
+        }
+    }
+}
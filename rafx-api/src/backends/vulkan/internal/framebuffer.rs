@@ -1,4 +1,4 @@
-use crate::backends::vulkan::RafxTextureVulkan;
+use crate::backends::vulkan::{RafxTextureViewVulkan, RafxTextureVulkan};
 use crate::vulkan::{RafxDeviceContextVulkan, RafxRenderpassVulkan};
 use crate::*;
 use ash::version::DeviceV1_0;
@@ -9,6 +9,52 @@ pub(crate) struct RafxFramebufferVulkanAttachment {
     pub(crate) texture: RafxTextureVulkan,
     pub(crate) array_slice: Option<u16>,
     pub(crate) mip_slice: Option<u8>,
+    // If set, the view is used instead of the texture's render target views
+    pub(crate) texture_view: Option<RafxTextureViewVulkan>,
+}
+
+impl RafxFramebufferVulkanAttachment {
+    fn vk_image_view(&self) -> vk::ImageView {
+        if let Some(texture_view) = &self.texture_view {
+            texture_view.vk_image_view()
+        } else if self.array_slice.is_none() && self.mip_slice.is_none() {
+            self.texture.render_target_vk_view().unwrap()
+        } else {
+            self.texture.render_target_slice_vk_view(
+                0,
+                self.array_slice.unwrap_or(0),
+                self.mip_slice.unwrap_or(0),
+            )
+        }
+    }
+
+    // Returns the extents and layer count of the attachment
+    fn extents_and_array_length(&self) -> (RafxExtents3D, u32) {
+        let texture_def = self.texture.texture_def();
+        if let Some(texture_view) = &self.texture_view {
+            let texture_view_def = texture_view.texture_view_def();
+            let mip_level = texture_view_def.mip_range.as_ref().unwrap().start;
+            let array_range = texture_view_def.array_range.as_ref().unwrap();
+            let extents = RafxExtents3D {
+                width: (texture_def.extents.width >> mip_level).max(1),
+                height: (texture_def.extents.height >> mip_level).max(1),
+                depth: 1,
+            };
+
+            (extents, array_range.end - array_range.start)
+        } else {
+            let extents = texture_def.extents.clone();
+            let array_length = if extents.depth > 1 {
+                extents.depth
+            } else if self.array_slice.is_some() {
+                1u32
+            } else {
+                texture_def.array_length
+            };
+
+            (extents, array_length)
+        }
+    }
 }
 
 pub(crate) struct RafxFramebufferVulkanDef {
@@ -59,29 +105,9 @@ impl RafxFramebufferVulkan {
     ) -> RafxResult<Self> {
         let (extents, array_length) =
             if let Some(first_color_rt) = framebuffer_def.color_attachments.first() {
-                let texture_def = first_color_rt.texture.texture_def();
-                let extents = texture_def.extents.clone();
-
-                let array_length = if extents.depth > 1 {
-                    extents.depth
-                } else if first_color_rt.array_slice.is_some() {
-                    1u32
-                } else {
-                    texture_def.array_length
-                };
-
-                (extents, array_length)
+                first_color_rt.extents_and_array_length()
             } else if let Some(depth_rt) = &framebuffer_def.depth_stencil_attachment {
-                let texture_def = depth_rt.texture.texture_def();
-                let extents = texture_def.extents.clone();
-
-                let array_length = if depth_rt.array_slice.is_some() {
-                    1u32
-                } else {
-                    texture_def.array_length
-                };
-
-                (extents, array_length)
+                depth_rt.extents_and_array_length()
             } else {
                 return Err(RafxError::StringError(
                     "No render target in framebuffer def".to_string(),
@@ -91,42 +117,15 @@ impl RafxFramebufferVulkan {
         let mut image_views = Vec::with_capacity(framebuffer_def.color_attachments.len() + 1);
 
         for color_rt in &framebuffer_def.color_attachments {
-            let image_view = if color_rt.array_slice.is_none() && color_rt.mip_slice.is_none() {
-                color_rt.texture.render_target_vk_view().unwrap()
-            } else {
-                color_rt.texture.render_target_slice_vk_view(
-                    0,
-                    color_rt.array_slice.unwrap_or(0),
-                    color_rt.mip_slice.unwrap_or(0),
-                )
-            };
-            image_views.push(image_view);
+            image_views.push(color_rt.vk_image_view());
         }
 
         for resolve_rt in &framebuffer_def.resolve_attachments {
-            let image_view = if resolve_rt.array_slice.is_none() && resolve_rt.mip_slice.is_none() {
-                resolve_rt.texture.render_target_vk_view().unwrap()
-            } else {
-                resolve_rt.texture.render_target_slice_vk_view(
-                    0,
-                    resolve_rt.array_slice.unwrap_or(0),
-                    resolve_rt.mip_slice.unwrap_or(0),
-                )
-            };
-            image_views.push(image_view);
+            image_views.push(resolve_rt.vk_image_view());
         }
 
         if let Some(depth_rt) = &framebuffer_def.depth_stencil_attachment {
-            let image_view = if depth_rt.mip_slice.is_none() && depth_rt.array_slice.is_none() {
-                depth_rt.texture.render_target_vk_view().unwrap()
-            } else {
-                depth_rt.texture.render_target_slice_vk_view(
-                    0,
-                    depth_rt.array_slice.unwrap_or(0),
-                    depth_rt.mip_slice.unwrap_or(0),
-                )
-            };
-            image_views.push(image_view);
+            image_views.push(depth_rt.vk_image_view());
         };
 
         let framebuffer_create_info = vk::FramebufferCreateInfo::builder()
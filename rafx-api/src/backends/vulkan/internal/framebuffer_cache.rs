@@ -33,6 +33,10 @@ impl RafxFramebufferVulkanCache {
                 .hash(&mut hasher);
             color_target.mip_slice.hash(&mut hasher);
             color_target.array_slice.hash(&mut hasher);
+            color_target
+                .texture_view
+                .map(|x| x.vk_texture_view().unwrap().texture_view_id())
+                .hash(&mut hasher);
 
             if let Some(resolve_target) = color_target.resolve_target {
                 resolve_target
@@ -54,6 +58,10 @@ impl RafxFramebufferVulkanCache {
                 .hash(&mut hasher);
             depth_target.mip_slice.hash(&mut hasher);
             depth_target.array_slice.hash(&mut hasher);
+            depth_target
+                .texture_view
+                .map(|x| x.vk_texture_view().unwrap().texture_view_id())
+                .hash(&mut hasher);
         }
         hasher.finish()
     }
@@ -72,6 +80,9 @@ impl RafxFramebufferVulkanCache {
                 texture: color_target.texture.vk_texture().unwrap().clone(),
                 array_slice: color_target.array_slice,
                 mip_slice: color_target.mip_slice,
+                texture_view: color_target
+                    .texture_view
+                    .map(|x| x.vk_texture_view().unwrap().clone()),
             });
 
             if let Some(resolve_target) = color_target.resolve_target {
@@ -79,6 +90,7 @@ impl RafxFramebufferVulkanCache {
                     texture: resolve_target.vk_texture().unwrap().clone(),
                     array_slice: color_target.resolve_array_slice,
                     mip_slice: color_target.resolve_mip_slice,
+                    texture_view: None,
                 })
             }
         }
@@ -94,6 +106,7 @@ impl RafxFramebufferVulkanCache {
                         texture: x.texture.vk_texture().unwrap().clone(),
                         array_slice: x.array_slice,
                         mip_slice: x.mip_slice,
+                        texture_view: x.texture_view.map(|x| x.vk_texture_view().unwrap().clone()),
                     }
                 }),
             },
@@ -16,6 +16,9 @@ pub struct VkInstance {
     pub entry: Arc<VkEntry>,
     pub instance: ash::Instance,
     pub debug_reporter: Option<VkDebugReporter>,
+    // Used to name objects and label commands for debugging tools. None if VK_EXT_debug_utils is
+    // not available
+    pub debug_utils: Option<DebugUtils>,
 }
 
 #[derive(Debug)]
@@ -108,8 +111,20 @@ impl VkInstance {
             .engine_version(0)
             .api_version(vulkan_version);
 
+        let debug_extension = DebugUtils::name();
+        let has_debug_extension = extensions.iter().any(|extension| unsafe {
+            debug_extension == CStr::from_ptr(extension.extension_name.as_ptr())
+        });
+
         let mut layer_names = vec![];
         let mut extension_names = ash_window::enumerate_required_extensions(window)?;
+
+        // The debug extension is also used to pass object names and command labels to tools like
+        // RenderDoc, so enable it whenever it's available
+        if has_debug_extension {
+            extension_names.push(DebugUtils::name());
+        }
+
         if !validation_layer_debug_report_flags.is_empty() {
             // Find the best validation layer that's available
             let best_validation_layer = VkInstance::find_best_validation_layer(&layers);
@@ -122,11 +137,6 @@ impl VkInstance {
                 }
             }
 
-            let debug_extension = DebugUtils::name();
-            let has_debug_extension = extensions.iter().any(|extension| unsafe {
-                debug_extension == CStr::from_ptr(extension.extension_name.as_ptr())
-            });
-
             if !has_debug_extension {
                 if require_validation_layers_present {
                     log::error!("Could not find the debug extension. Check that the vulkan SDK has been installed or disable validation.");
@@ -139,7 +149,6 @@ impl VkInstance {
             if let Some(best_validation_layer) = best_validation_layer {
                 if has_debug_extension {
                     layer_names.push(best_validation_layer);
-                    extension_names.push(DebugUtils::name());
                 }
             }
         }
@@ -172,10 +181,17 @@ impl VkInstance {
             None
         };
 
+        let debug_utils = if has_debug_extension {
+            Some(DebugUtils::new(&entry, &instance))
+        } else {
+            None
+        };
+
         Ok(VkInstance {
             entry: Arc::new(entry),
             instance,
             debug_reporter,
+            debug_utils,
         })
     }
 
@@ -53,6 +53,17 @@ pub(crate) struct RafxRenderpassVulkan {
     inner: Arc<RafxRenderpassVulkanInner>,
 }
 
+impl std::fmt::Debug for RafxRenderpassVulkan {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        f.debug_struct("RafxRenderpassVulkan")
+            .field("renderpass", &self.inner.renderpass)
+            .finish()
+    }
+}
+
 impl RafxRenderpassVulkan {
     pub fn vk_renderpass(&self) -> vk::RenderPass {
         self.inner.renderpass
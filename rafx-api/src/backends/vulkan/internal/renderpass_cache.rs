@@ -27,7 +27,7 @@ impl RafxRenderpassVulkanCache {
         let mut hasher = FnvHasher::default();
         for color_target in color_targets {
             let texture_def = color_target.texture.texture_def();
-            texture_def.format.hash(&mut hasher);
+            Self::color_target_format(color_target).hash(&mut hasher);
             texture_def.sample_count.hash(&mut hasher);
             color_target.clear_value.hash(&mut hasher);
             color_target.load_op.hash(&mut hasher);
@@ -44,6 +44,14 @@ impl RafxRenderpassVulkanCache {
         hasher.finish()
     }
 
+    // A view bound as a render target may reinterpret the texture's format
+    fn color_target_format(color_target: &RafxColorRenderTargetBinding) -> RafxFormat {
+        color_target
+            .texture_view
+            .map(|x| x.texture_view_def().format.unwrap())
+            .unwrap_or_else(|| color_target.texture.texture_def().format)
+    }
+
     pub(crate) fn create_renderpass(
         device_context: &RafxDeviceContextVulkan,
         color_targets: &[RafxColorRenderTargetBinding],
@@ -63,7 +71,7 @@ impl RafxRenderpassVulkanCache {
         let color_attachments: Vec<_> = color_targets
             .iter()
             .map(|x| RafxRenderpassVulkanColorAttachment {
-                format: x.texture.texture_def().format,
+                format: Self::color_target_format(x),
                 load_op: x.load_op,
                 store_op: x.store_op,
             })
@@ -25,12 +25,24 @@ pub use command_buffer::*;
 mod fence;
 pub use fence::*;
 
+mod heap;
+pub use heap::*;
+
+mod query_pool;
+pub use query_pool::*;
+
 mod semaphore;
 pub use semaphore::*;
 
+mod timeline_semaphore;
+pub use timeline_semaphore::*;
+
 mod texture;
 pub use texture::*;
 
+mod texture_view;
+pub use texture_view::*;
+
 mod buffer;
 pub use buffer::*;
 
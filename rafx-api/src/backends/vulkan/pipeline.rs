@@ -7,12 +7,51 @@ use ash::version::DeviceV1_0;
 use ash::vk;
 use std::ffi::CString;
 
+// Owns the map entries and data referenced by a vk::SpecializationInfo. Every constant is written
+// as 4 bytes, which matches how SPIR-V stores 32-bit bool/int/float spec constants
+struct SpecializationDataVulkan {
+    map_entries: Vec<vk::SpecializationMapEntry>,
+    data: Vec<u8>,
+}
+
+impl SpecializationDataVulkan {
+    fn new(specialization_constants: &[RafxSpecializationConstant]) -> Self {
+        let mut map_entries = Vec::with_capacity(specialization_constants.len());
+        let mut data = Vec::with_capacity(specialization_constants.len() * 4);
+        for constant in specialization_constants {
+            map_entries.push(vk::SpecializationMapEntry {
+                constant_id: constant.constant_id,
+                offset: data.len() as u32,
+                size: 4,
+            });
+            data.extend_from_slice(&constant.value.to_bits().to_ne_bytes());
+        }
+
+        SpecializationDataVulkan { map_entries, data }
+    }
+
+    fn specialization_info(&self) -> Option<vk::SpecializationInfo> {
+        if self.map_entries.is_empty() {
+            return None;
+        }
+
+        Some(
+            vk::SpecializationInfo::builder()
+                .map_entries(&self.map_entries)
+                .data(&self.data)
+                .build(),
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct RafxPipelineVulkan {
     pipeline_type: RafxPipelineType,
     pipeline: vk::Pipeline,
     // It's a RafxRootSignatureVulkan, but stored as RafxRootSignature so we can return refs to it
     root_signature: RafxRootSignature,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl Drop for RafxPipelineVulkan {
@@ -30,6 +69,11 @@ impl Drop for RafxPipelineVulkan {
 }
 
 impl RafxPipelineVulkan {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.capture_id
+    }
+
     pub fn pipeline_type(&self) -> RafxPipelineType {
         self.pipeline_type
     }
@@ -42,11 +86,23 @@ impl RafxPipelineVulkan {
         self.pipeline
     }
 
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.root_signature
+            .vk_root_signature()
+            .unwrap()
+            .device_context()
+            .set_debug_name(self.pipeline, name.as_ref());
+    }
+
     pub fn new_graphics_pipeline(
         device_context: &RafxDeviceContextVulkan,
         pipeline_def: &RafxGraphicsPipelineDef,
     ) -> RafxResult<Self> {
         //log::trace!("Create pipeline\n{:#?}", pipeline_def);
+        pipeline_def.verify_device_limits(device_context.device_info())?;
 
         //TODO: Cache
         let vk_root_signature = pipeline_def.root_signature.vk_root_signature().unwrap();
@@ -93,28 +149,46 @@ impl RafxPipelineVulkan {
                 .push(CString::new(stage.reflection.entry_point_name.clone()).unwrap());
         }
 
+        // These must outlive pipeline creation since the stage create infos point into them
+        let specialization_data: Vec<_> = pipeline_def
+            .shader
+            .vk_shader()
+            .unwrap()
+            .stages()
+            .iter()
+            .map(|stage| SpecializationDataVulkan::new(&stage.specialization_constants))
+            .collect();
+        let specialization_infos: Vec<_> = specialization_data
+            .iter()
+            .map(|x| x.specialization_info())
+            .collect();
+
         let mut stages = vec![];
-        for (stage, entry_point_cstr) in pipeline_def
+        for ((stage, entry_point_cstr), specialization_info) in pipeline_def
             .shader
             .vk_shader()
             .unwrap()
             .stages()
             .iter()
             .zip(&entry_point_names)
+            .zip(&specialization_infos)
         {
-            stages.push(
-                vk::PipelineShaderStageCreateInfo::builder()
-                    .name(entry_point_cstr)
-                    .module(
-                        stage
-                            .shader_module
-                            .vk_shader_module()
-                            .unwrap()
-                            .vk_shader_module(),
-                    )
-                    .stage(stage.reflection.shader_stage.into())
-                    .build(),
-            );
+            let mut stage_create_info = vk::PipelineShaderStageCreateInfo::builder()
+                .name(entry_point_cstr)
+                .module(
+                    stage
+                        .shader_module
+                        .vk_shader_module()
+                        .unwrap()
+                        .vk_shader_module(),
+                )
+                .stage(stage.reflection.shader_stage.into());
+
+            if let Some(specialization_info) = specialization_info {
+                stage_create_info = stage_create_info.specialization_info(specialization_info);
+            }
+
+            stages.push(stage_create_info.build());
         }
 
         let mut bindings = Vec::with_capacity(pipeline_def.vertex_layout.buffers.len());
@@ -173,14 +247,18 @@ impl RafxPipelineVulkan {
             pipeline_def.color_formats.len(),
         );
 
-        let dynamic_states = [
+        let mut dynamic_states = vec![
             vk::DynamicState::VIEWPORT,
             vk::DynamicState::SCISSOR,
-            vk::DynamicState::DEPTH_BIAS,
             vk::DynamicState::BLEND_CONSTANTS,
             vk::DynamicState::DEPTH_BOUNDS,
             vk::DynamicState::STENCIL_REFERENCE,
+            vk::DynamicState::LINE_WIDTH,
         ];
+        // Otherwise the bias in the rasterizer state is baked into the pipeline
+        if pipeline_def.rasterizer_state.dynamic_depth_bias {
+            dynamic_states.push(vk::DynamicState::DEPTH_BIAS);
+        }
         let dynamic_states_create_info =
             vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dynamic_states);
 
@@ -210,7 +288,7 @@ impl RafxPipelineVulkan {
 
         let pipeline = unsafe {
             match device_context.device().create_graphics_pipelines(
-                vk::PipelineCache::null(),
+                *device_context.pipeline_cache(),
                 &[pipeline_create_info],
                 None,
             ) {
@@ -223,6 +301,8 @@ impl RafxPipelineVulkan {
             pipeline_type: RafxPipelineType::Graphics,
             pipeline,
             root_signature: pipeline_def.root_signature.clone(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 
@@ -231,6 +311,7 @@ impl RafxPipelineVulkan {
         pipeline_def: &RafxComputePipelineDef,
     ) -> RafxResult<Self> {
         //log::trace!("Create pipeline\n{:#?}", pipeline_def);
+        pipeline_def.verify_device_limits(device_context.device_info())?;
 
         //TODO: Cache
         let vk_root_signature = pipeline_def.root_signature.vk_root_signature().unwrap();
@@ -248,7 +329,10 @@ impl RafxPipelineVulkan {
         let compute_stage = &vk_shader.stages()[0];
         let entry_point_name =
             CString::new(compute_stage.reflection.entry_point_name.clone()).unwrap();
-        let stage = vk::PipelineShaderStageCreateInfo::builder()
+        let specialization_data =
+            SpecializationDataVulkan::new(&compute_stage.specialization_constants);
+        let specialization_info = specialization_data.specialization_info();
+        let mut stage = vk::PipelineShaderStageCreateInfo::builder()
             .name(&entry_point_name)
             .module(
                 compute_stage
@@ -259,6 +343,10 @@ impl RafxPipelineVulkan {
             )
             .stage(vk::ShaderStageFlags::COMPUTE);
 
+        if let Some(specialization_info) = &specialization_info {
+            stage = stage.specialization_info(specialization_info);
+        }
+
         let pipeline_create_info = vk::ComputePipelineCreateInfo::builder()
             .stage(*stage)
             .layout(vk_root_signature.vk_pipeline_layout())
@@ -268,7 +356,7 @@ impl RafxPipelineVulkan {
 
         let pipeline = unsafe {
             match device_context.device().create_compute_pipelines(
-                vk::PipelineCache::null(),
+                *device_context.pipeline_cache(),
                 &[pipeline_create_info],
                 None,
             ) {
@@ -281,6 +369,8 @@ impl RafxPipelineVulkan {
             pipeline_type: RafxPipelineType::Compute,
             pipeline,
             root_signature: pipeline_def.root_signature.clone(),
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         })
     }
 }
@@ -0,0 +1,93 @@
+use crate::vulkan::RafxDeviceContextVulkan;
+use crate::{RafxQueryPoolDef, RafxQueryType, RafxResult};
+use ash::version::DeviceV1_0;
+use ash::vk;
+
+// The counters collected by pipeline statistics queries. Vulkan writes results in bit order, which
+// must match the field order of RafxPipelineStatistics
+pub(crate) const PIPELINE_STATISTICS_FLAGS: vk::QueryPipelineStatisticFlags =
+    vk::QueryPipelineStatisticFlags::from_raw(
+        vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES.as_raw()
+            | vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES.as_raw()
+            | vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS.as_raw()
+            | vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS.as_raw()
+            | vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES.as_raw()
+            | vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS.as_raw()
+            | vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS.as_raw(),
+    );
+
+pub struct RafxQueryPoolVulkan {
+    device_context: RafxDeviceContextVulkan,
+    vk_query_pool: vk::QueryPool,
+    query_pool_def: RafxQueryPoolDef,
+}
+
+impl Drop for RafxQueryPoolVulkan {
+    fn drop(&mut self) {
+        unsafe {
+            self.device_context
+                .device()
+                .destroy_query_pool(self.vk_query_pool, None)
+        }
+    }
+}
+
+impl RafxQueryPoolVulkan {
+    pub fn new(
+        device_context: &RafxDeviceContextVulkan,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPoolVulkan> {
+        query_pool_def.verify();
+
+        let device_info = device_context.device_info();
+        let (vk_query_type, is_supported) = match query_pool_def.query_type {
+            RafxQueryType::Timestamp => (
+                vk::QueryType::TIMESTAMP,
+                device_info.supports_timestamp_queries,
+            ),
+            RafxQueryType::Occlusion => (
+                vk::QueryType::OCCLUSION,
+                device_info.supports_occlusion_queries,
+            ),
+            RafxQueryType::PipelineStatistics => (
+                vk::QueryType::PIPELINE_STATISTICS,
+                device_info.supports_pipeline_statistics_queries,
+            ),
+        };
+
+        if !is_supported {
+            Err(format!(
+                "Query type {:?} is not supported by this device",
+                query_pool_def.query_type
+            ))?;
+        }
+
+        let mut create_info = vk::QueryPoolCreateInfo::builder()
+            .query_type(vk_query_type)
+            .query_count(query_pool_def.query_count);
+
+        if query_pool_def.query_type == RafxQueryType::PipelineStatistics {
+            create_info = create_info.pipeline_statistics(PIPELINE_STATISTICS_FLAGS);
+        }
+
+        let vk_query_pool = unsafe {
+            device_context
+                .device()
+                .create_query_pool(&*create_info, None)?
+        };
+
+        Ok(RafxQueryPoolVulkan {
+            device_context: device_context.clone(),
+            vk_query_pool,
+            query_pool_def: query_pool_def.clone(),
+        })
+    }
+
+    pub fn query_pool_def(&self) -> &RafxQueryPoolDef {
+        &self.query_pool_def
+    }
+
+    pub fn vk_query_pool(&self) -> vk::QueryPool {
+        self.vk_query_pool
+    }
+}
@@ -1,7 +1,7 @@
 use super::internal::VkQueue;
 use crate::vulkan::{
     RafxCommandBufferVulkan, RafxCommandPoolVulkan, RafxDeviceContextVulkan, RafxFenceVulkan,
-    RafxSemaphoreVulkan, RafxSwapchainVulkan,
+    RafxSemaphoreVulkan, RafxSwapchainVulkan, RafxTimelineSemaphoreVulkan,
 };
 use crate::*;
 use ash::version::DeviceV1_0;
@@ -79,6 +79,8 @@ impl RafxQueueVulkan {
         command_buffers: &[&RafxCommandBufferVulkan],
         wait_semaphores: &[&RafxSemaphoreVulkan],
         signal_semaphores: &[&RafxSemaphoreVulkan],
+        wait_timeline_semaphores: &[(&RafxTimelineSemaphoreVulkan, u64)],
+        signal_timeline_semaphores: &[(&RafxTimelineSemaphoreVulkan, u64)],
         signal_fence: Option<&RafxFenceVulkan>,
     ) -> RafxResult<()> {
         let mut command_buffer_list = Vec::with_capacity(command_buffers.len());
@@ -99,6 +101,15 @@ impl RafxQueueVulkan {
             }
         }
 
+        // Binary semaphores ignore their entry in the value lists, but the lists must be the same
+        // length as the semaphore lists
+        let mut wait_value_list = vec![0; wait_semaphore_list.len()];
+        for (wait_semaphore, value) in wait_timeline_semaphores {
+            wait_semaphore_list.push(wait_semaphore.vk_semaphore());
+            wait_dst_stage_mask.push(vk::PipelineStageFlags::ALL_COMMANDS);
+            wait_value_list.push(*value);
+        }
+
         let mut signal_semaphore_list = Vec::with_capacity(signal_semaphores.len());
         for signal_semaphore in signal_semaphores {
             // Don't signal a semaphore if something is already going to signal it
@@ -109,12 +120,26 @@ impl RafxQueueVulkan {
             }
         }
 
-        let submit_info = vk::SubmitInfo::builder()
+        let mut signal_value_list = vec![0; signal_semaphore_list.len()];
+        for (signal_semaphore, value) in signal_timeline_semaphores {
+            signal_semaphore_list.push(signal_semaphore.vk_semaphore());
+            signal_value_list.push(*value);
+        }
+
+        let mut timeline_semaphore_submit_info = vk::TimelineSemaphoreSubmitInfo::builder()
+            .wait_semaphore_values(&wait_value_list)
+            .signal_semaphore_values(&signal_value_list);
+
+        let mut submit_info = vk::SubmitInfo::builder()
             .wait_semaphores(&wait_semaphore_list)
             .wait_dst_stage_mask(&wait_dst_stage_mask)
             .signal_semaphores(&signal_semaphore_list)
             .command_buffers(&command_buffer_list);
 
+        if !wait_timeline_semaphores.is_empty() || !signal_timeline_semaphores.is_empty() {
+            submit_info = submit_info.push_next(&mut timeline_semaphore_submit_info);
+        }
+
         let fence = signal_fence
             .map(|x| x.vk_fence())
             .unwrap_or(vk::Fence::null());
@@ -8,13 +8,11 @@ use std::sync::Arc;
 // Not currently exposed
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub(crate) struct DynamicDescriptorIndex(pub(crate) u32);
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-pub(crate) struct PushConstantIndex(pub(crate) u32);
 
 #[derive(Clone, Debug)]
 pub(crate) struct PushConstantInfo {
     pub(crate) name: Option<String>,
-    pub(crate) push_constant_index: PushConstantIndex,
+    pub(crate) push_constant_index: RafxPushConstantIndex,
     pub(crate) vk_push_constant_range: vk::PushConstantRange,
 }
 
@@ -59,6 +57,9 @@ pub(crate) struct DescriptorSetLayoutInfo {
     pub(crate) update_data_count_per_set: u32,
     // This indexes into the descriptors list
     pub(crate) dynamic_descriptor_indexes: Vec<RafxDescriptorIndex>,
+    // Layouts with bindless arrays must be allocated from a pool created with UPDATE_AFTER_BIND.
+    // These are the descriptor counts needed for a single set with this layout
+    pub(crate) update_after_bind_pool_sizes: Option<Vec<vk::DescriptorPoolSize>>,
 }
 
 #[derive(Debug)]
@@ -72,7 +73,9 @@ pub(crate) struct RafxRootSignatureVulkanInner {
     immutable_samplers: Vec<RafxSampler>, //empty_descriptor_sets: [vk::DescriptorSet; MAX_DESCRIPTOR_SETS],
 
     // --- vulkan-specific ---
-    pub(crate) name_to_push_constant_index: FnvHashMap<String, PushConstantIndex>,
+    pub(crate) name_to_push_constant_index: FnvHashMap<String, RafxPushConstantIndex>,
+    #[cfg(feature = "capture")]
+    pub(crate) capture_id: u32,
     pub(crate) push_constants: Vec<PushConstantInfo>,
     pub(crate) pipeline_layout: vk::PipelineLayout,
     pub(crate) descriptor_set_layouts: [vk::DescriptorSetLayout; MAX_DESCRIPTOR_SET_LAYOUTS],
@@ -98,6 +101,11 @@ pub struct RafxRootSignatureVulkan {
 }
 
 impl RafxRootSignatureVulkan {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn device_context(&self) -> &RafxDeviceContextVulkan {
         &self.inner.device_context
     }
@@ -125,6 +133,22 @@ impl RafxRootSignatureVulkan {
             .copied()
     }
 
+    pub fn find_push_constant_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxPushConstantIndex> {
+        self.inner.name_to_push_constant_index.get(name).copied()
+    }
+
+    pub(crate) fn push_constant(
+        &self,
+        push_constant_index: RafxPushConstantIndex,
+    ) -> Option<&PushConstantInfo> {
+        self.inner
+            .push_constants
+            .get(push_constant_index.0 as usize)
+    }
+
     pub(crate) fn descriptor(
         &self,
         descriptor_index: RafxDescriptorIndex,
@@ -148,6 +172,14 @@ impl RafxRootSignatureVulkan {
         }
     }
 
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.device_context()
+            .set_debug_name(self.vk_pipeline_layout(), name.as_ref());
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextVulkan,
         root_signature_def: &RafxRootSignatureDef,
@@ -180,9 +212,15 @@ impl RafxRootSignatureVulkan {
         }
 
         // Make sure all shaders are compatible/build lookup of shared data from them
-        let (pipeline_type, merged_resources, _merged_resources_name_index_map) =
+        let (pipeline_type, mut merged_resources, _merged_resources_name_index_map) =
             crate::internal_shared::merge_resources(root_signature_def)?;
 
+        let bindless_flags = crate::internal_shared::apply_bindless_arrays(
+            root_signature_def,
+            device_context.device_info(),
+            &mut merged_resources,
+        )?;
+
         let mut layouts = [
             DescriptorSetLayoutInfo::default(),
             DescriptorSetLayoutInfo::default(),
@@ -191,6 +229,8 @@ impl RafxRootSignatureVulkan {
         ];
 
         let mut vk_set_bindings = [vec![], vec![], vec![], vec![]];
+        // One per binding in vk_set_bindings, only used if the set contains a bindless array
+        let mut vk_set_binding_flags = [vec![], vec![], vec![], vec![]];
 
         let mut name_to_descriptor_index = FnvHashMap::default();
         let mut name_to_push_constant_index = FnvHashMap::default();
@@ -199,7 +239,7 @@ impl RafxRootSignatureVulkan {
         // Create bindings (vulkan representation) and descriptors (what we use)
         // We don't create descriptors for immutable samplers
         //
-        for resource in &merged_resources {
+        for (resource, &is_bindless) in merged_resources.iter().zip(&bindless_flags) {
             let vk_stage_flags = resource.used_in_shader_stages.into();
             let vk_descriptor_type =
                 super::util::resource_type_to_descriptor_type(resource.resource_type).unwrap();
@@ -237,13 +277,16 @@ impl RafxRootSignatureVulkan {
                     if resource.element_count_normalized() as usize
                         != vk_immutable_samplers[immutable_sampler_index].len()
                     {
-                        Err(format!(
-                            "Descriptor (set={:?} binding={:?}) named {:?} specifies {} elements but the count of provided immutable samplers ({}) did not match",
-                            resource.set_index,
-                            resource.binding,
-                            resource.name,
-                            resource.element_count_normalized(),
-                            vk_immutable_samplers[immutable_sampler_index].len()
+                        Err(RafxError::reflection_mismatch(
+                            resource.name.as_deref(),
+                            format!(
+                                "Descriptor (set={:?} binding={:?}) named {:?} specifies {} elements but the count of provided immutable samplers ({}) did not match",
+                                resource.set_index,
+                                resource.binding,
+                                resource.name,
+                                resource.element_count_normalized(),
+                                vk_immutable_samplers[immutable_sampler_index].len()
+                            ),
                         ))?;
                     }
 
@@ -269,11 +312,14 @@ impl RafxRootSignatureVulkan {
                 } else if immutable_sampler.is_none()
                     && vk_descriptor_type == vk::DescriptorType::COMBINED_IMAGE_SAMPLER
                 {
-                    Err(format!(
-                        "Descriptor (set={:?} binding={:?}) named {:?} is a combined image sampler but the sampler is NOT immutable. This is not supported. Use separate sampler/image bindings",
-                        resource.set_index,
-                        resource.binding,
-                        resource.name
+                    Err(RafxError::reflection_mismatch(
+                        resource.name.as_deref(),
+                        format!(
+                            "Descriptor (set={:?} binding={:?}) named {:?} is a combined image sampler but the sampler is NOT immutable. This is not supported. Use separate sampler/image bindings",
+                            resource.set_index,
+                            resource.binding,
+                            resource.name
+                        ),
                     ))?;
                 } else {
                     // dynamic storage buffers not supported
@@ -329,10 +375,19 @@ impl RafxRootSignatureVulkan {
                     layout.update_data_count_per_set += resource.element_count_normalized();
                 }
 
+                // Bindless arrays may have unset elements and may be updated while bound
+                let vk_binding_flags = if is_bindless {
+                    vk::DescriptorBindingFlags::PARTIALLY_BOUND
+                        | vk::DescriptorBindingFlags::UPDATE_AFTER_BIND
+                } else {
+                    vk::DescriptorBindingFlags::empty()
+                };
+                vk_set_binding_flags[resource.set_index as usize].push(vk_binding_flags);
+
                 // Add the binding to the list
                 vk_bindings.push(binding.build());
             } else {
-                let push_constant_index = PushConstantIndex(push_constants.len() as u32);
+                let push_constant_index = RafxPushConstantIndex(push_constants.len() as u32);
                 let vk_push_constant_range = vk::PushConstantRange::builder()
                     .offset(0)
                     .size(resource.size_in_bytes)
@@ -392,11 +447,44 @@ impl RafxRootSignatureVulkan {
             // Create this layout
             //
             {
+                let vk_binding_flags = &vk_set_binding_flags[layout_index];
+                let mut vk_binding_flags_create_info =
+                    vk::DescriptorSetLayoutBindingFlagsCreateInfo::builder()
+                        .binding_flags(vk_binding_flags);
+
+                let mut layout_create_info =
+                    vk::DescriptorSetLayoutCreateInfo::builder().bindings(&vk_bindings);
+
+                let has_bindless_arrays = vk_binding_flags
+                    .iter()
+                    .any(|x| x.contains(vk::DescriptorBindingFlags::UPDATE_AFTER_BIND));
+                if has_bindless_arrays {
+                    layout_create_info = layout_create_info
+                        .flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+                        .push_next(&mut vk_binding_flags_create_info);
+
+                    let mut pool_sizes: Vec<vk::DescriptorPoolSize> = vec![];
+                    for vk_binding in vk_bindings.iter() {
+                        if let Some(pool_size) = pool_sizes
+                            .iter_mut()
+                            .find(|x| x.ty == vk_binding.descriptor_type)
+                        {
+                            pool_size.descriptor_count += vk_binding.descriptor_count;
+                        } else {
+                            pool_sizes.push(vk::DescriptorPoolSize {
+                                ty: vk_binding.descriptor_type,
+                                descriptor_count: vk_binding.descriptor_count,
+                            });
+                        }
+                    }
+
+                    layouts[layout_index].update_after_bind_pool_sizes = Some(pool_sizes);
+                }
+
                 let descriptor_set_layout = unsafe {
-                    device_context.device().create_descriptor_set_layout(
-                        &*vk::DescriptorSetLayoutCreateInfo::builder().bindings(&vk_bindings),
-                        None,
-                    )?
+                    device_context
+                        .device()
+                        .create_descriptor_set_layout(&*layout_create_info, None)?
                 };
 
                 descriptor_set_layouts[descriptor_set_layout_count] = descriptor_set_layout;
@@ -430,6 +518,8 @@ impl RafxRootSignatureVulkan {
             pipeline_layout,
             descriptor_set_layouts,
             name_to_push_constant_index,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         };
 
         Ok(RafxRootSignatureVulkan {
@@ -7,6 +7,8 @@ use std::sync::Arc;
 pub struct RafxSamplerVulkanInner {
     device_context: RafxDeviceContextVulkan,
     sampler: vk::Sampler,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl Drop for RafxSamplerVulkanInner {
@@ -36,14 +38,34 @@ impl std::fmt::Debug for RafxSamplerVulkan {
 }
 
 impl RafxSamplerVulkan {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn vk_sampler(&self) -> vk::Sampler {
         self.inner.sampler
     }
 
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.inner
+            .device_context
+            .set_debug_name(self.inner.sampler, name.as_ref());
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextVulkan,
         sampler_def: &RafxSamplerDef,
     ) -> RafxResult<RafxSamplerVulkan> {
+        let device_info = device_context.device_info();
+        sampler_def.verify_device_limits(device_info)?;
+        let max_anisotropy = sampler_def
+            .max_anisotropy
+            .min(device_info.max_sampler_anisotropy);
+
         let max_lod = if sampler_def.mip_map_mode == RafxMipMapMode::Linear {
             f32::MAX
         } else {
@@ -58,8 +80,8 @@ impl RafxSamplerVulkan {
             .address_mode_v(sampler_def.address_mode_v.into())
             .address_mode_w(sampler_def.address_mode_w.into())
             .mip_lod_bias(sampler_def.mip_lod_bias)
-            .anisotropy_enable(sampler_def.max_anisotropy > 0.0)
-            .max_anisotropy(sampler_def.max_anisotropy)
+            .anisotropy_enable(max_anisotropy > 0.0)
+            .max_anisotropy(max_anisotropy)
             .compare_enable(sampler_def.compare_op != RafxCompareOp::Never)
             .compare_op(sampler_def.compare_op.into())
             .min_lod(sampler_def.mip_lod_bias)
@@ -76,6 +98,8 @@ impl RafxSamplerVulkan {
         let inner = RafxSamplerVulkanInner {
             device_context: device_context.clone(),
             sampler,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         };
 
         Ok(RafxSamplerVulkan {
@@ -7,6 +7,8 @@ struct RafxShaderVulkanInner {
     stage_flags: RafxShaderStageFlags,
     stages: Vec<RafxShaderStageDef>,
     pipeline_reflection: RafxPipelineReflection,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -15,6 +17,11 @@ pub struct RafxShaderVulkan {
 }
 
 impl RafxShaderVulkan {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn new(
         _device_context: &RafxDeviceContextVulkan,
         stages: Vec<RafxShaderStageDef>,
@@ -29,6 +36,8 @@ impl RafxShaderVulkan {
             stages,
             pipeline_reflection,
             stage_flags,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         };
 
         Ok(RafxShaderVulkan {
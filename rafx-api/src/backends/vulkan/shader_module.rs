@@ -8,6 +8,8 @@ use std::sync::Arc;
 pub struct RafxShaderModuleVulkanInner {
     device_context: RafxDeviceContextVulkan,
     shader_module: vk::ShaderModule,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
 }
 
 impl Drop for RafxShaderModuleVulkanInner {
@@ -26,6 +28,11 @@ pub struct RafxShaderModuleVulkan {
 }
 
 impl RafxShaderModuleVulkan {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextVulkan,
         data: RafxShaderModuleDefVulkan,
@@ -62,6 +69,8 @@ impl RafxShaderModuleVulkan {
         let inner = RafxShaderModuleVulkanInner {
             device_context: device_context.clone(),
             shader_module,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
         };
 
         Ok(RafxShaderModuleVulkan {
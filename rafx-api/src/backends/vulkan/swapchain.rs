@@ -231,7 +231,7 @@ impl RafxSwapchainVulkan {
                         .reset_fences(&[fence.vk_fence()])?;
                 }
                 fence.set_submitted(false);
-                Err(RafxError::VkError(e))
+                Err(e.into())
             }
         }
     }
@@ -259,7 +259,7 @@ impl RafxSwapchainVulkan {
             Err(e) => {
                 self.last_image_suboptimal = false;
                 semaphore.set_signal_available(false);
-                Err(RafxError::VkError(e))
+                Err(e.into())
             }
         }
     }
@@ -303,7 +303,7 @@ impl RafxSwapchainVulkan {
         command_buffer.cmd_resource_barrier(&[], &image_barriers)?;
 
         command_buffer.end()?;
-        queue.submit(&[&command_buffer], &[], &[], None)?;
+        queue.submit(&[&command_buffer], &[], &[], &[], &[], None)?;
         queue.wait_for_queue_idle()?;
         Ok(swapchain_images)
     }
@@ -1,7 +1,7 @@
 use crate::types::RafxTextureDimensions;
-use crate::vulkan::RafxDeviceContextVulkan;
+use crate::vulkan::{RafxDeviceContextVulkan, RafxHeapVulkan};
 use crate::*;
-use ash::version::DeviceV1_0;
+use ash::version::{DeviceV1_0, InstanceV1_0};
 use ash::vk;
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -50,6 +50,10 @@ pub struct RafxTextureVulkanInner {
     device_context: RafxDeviceContextVulkan,
     texture_def: RafxTextureDef,
     image: RafxRawImageVulkan,
+    // Size of the allocation if rafx owns the image, otherwise 0
+    allocation_size: u64,
+    // Set if the image is bound to memory owned by a heap, image.allocation will be None
+    heap: Option<RafxHeapVulkan>,
     aspect_mask: vk::ImageAspectFlags,
 
     // For reading
@@ -68,6 +72,15 @@ pub struct RafxTextureVulkanInner {
 
 impl Drop for RafxTextureVulkanInner {
     fn drop(&mut self) {
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context
+            .resource_state_tracker()
+            .on_texture_destroyed(self.texture_id);
+        #[cfg(feature = "capture")]
+        self.device_context
+            .capture_recorder()
+            .on_texture_destroyed(self.texture_id);
+
         let device = self.device_context.device();
 
         unsafe {
@@ -92,6 +105,19 @@ impl Drop for RafxTextureVulkanInner {
             }
         }
 
+        if self.image.allocation.is_some() {
+            self.device_context
+                .memory_usage_tracker()
+                .on_texture_freed(RafxMemoryUsage::GpuOnly, self.allocation_size);
+        }
+
+        if self.heap.is_some() {
+            // The memory belongs to the heap, only the image is destroyed
+            unsafe {
+                device.destroy_image(self.image.image, None);
+            }
+        }
+
         self.image.destroy_image(&self.device_context);
     }
 }
@@ -213,6 +239,14 @@ impl RafxTextureVulkan {
             .swap(false, Ordering::Relaxed)
     }
 
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        self.device_context()
+            .set_debug_name(self.vk_image(), name.as_ref());
+    }
+
     pub fn new(
         device_context: &RafxDeviceContextVulkan,
         texture_def: &RafxTextureDef,
@@ -220,13 +254,179 @@ impl RafxTextureVulkan {
         Self::from_existing(device_context, None, texture_def)
     }
 
+    pub fn new_in_heap(
+        device_context: &RafxDeviceContextVulkan,
+        heap: &RafxHeapVulkan,
+        offset: u64,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureVulkan> {
+        heap.heap_def().verify_texture_def(texture_def)?;
+        Self::create(device_context, None, Some((heap, offset)), texture_def)
+    }
+
+    pub fn memory_requirements(
+        device_context: &RafxDeviceContextVulkan,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        texture_def.verify();
+        texture_def.verify_device_limits(device_context.device_info())?;
+
+        let image_create_info = Self::image_create_info(texture_def);
+        Self::verify_image_format_supported(device_context, texture_def, &image_create_info)?;
+
+        let device = device_context.device();
+        let memory_requirements = unsafe {
+            let image = device
+                .create_image(&image_create_info, None)
+                .map_err(|e| Self::map_format_error(texture_def.format, e.into()))?;
+            let memory_requirements = device.get_image_memory_requirements(image);
+            device.destroy_image(image, None);
+            memory_requirements
+        };
+
+        Ok(RafxMemoryRequirements {
+            size: memory_requirements.size,
+            // Keep placed buffers and textures on separate pages
+            alignment: memory_requirements
+                .alignment
+                .max(device_context.limits().buffer_image_granularity),
+        })
+    }
+
+    fn image_create_info(texture_def: &RafxTextureDef) -> vk::ImageCreateInfo {
+        let image_type = match texture_def
+            .dimensions
+            .determine_dimensions(texture_def.extents)
+        {
+            RafxTextureDimensions::Dim1D => vk::ImageType::TYPE_1D,
+            RafxTextureDimensions::Dim2D => vk::ImageType::TYPE_2D,
+            RafxTextureDimensions::Dim3D => vk::ImageType::TYPE_3D,
+            RafxTextureDimensions::Auto => panic!("dimensions() should not return auto"),
+        };
+
+        //
+        // Determine image usage flags
+        //
+        let mut usage_flags =
+            super::util::resource_type_image_usage_flags(texture_def.resource_type);
+        if texture_def
+            .resource_type
+            .intersects(RafxResourceType::RENDER_TARGET_COLOR)
+        {
+            usage_flags |= vk::ImageUsageFlags::COLOR_ATTACHMENT;
+        } else if texture_def
+            .resource_type
+            .intersects(RafxResourceType::RENDER_TARGET_DEPTH_STENCIL)
+        {
+            usage_flags |= vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT;
+        }
+
+        if usage_flags.intersects(vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::STORAGE) {
+            usage_flags |= vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST;
+        }
+
+        //
+        // Determine image create flags
+        //
+        let mut create_flags = vk::ImageCreateFlags::empty();
+        if texture_def
+            .resource_type
+            .contains(RafxResourceType::TEXTURE_CUBE)
+        {
+            create_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
+        }
+        if image_type == vk::ImageType::TYPE_3D {
+            create_flags |= vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE_KHR
+        }
+        if texture_def
+            .resource_type
+            .contains(RafxResourceType::TEXTURE_MUTABLE_FORMAT)
+        {
+            create_flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
+        }
+
+        let extent = vk::Extent3D {
+            width: texture_def.extents.width,
+            height: texture_def.extents.height,
+            depth: texture_def.extents.depth,
+        };
+
+        vk::ImageCreateInfo::builder()
+            .image_type(image_type)
+            .extent(extent)
+            .mip_levels(texture_def.mip_count)
+            .array_layers(texture_def.array_length)
+            .format(texture_def.format.into())
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .usage(usage_flags)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .samples(texture_def.sample_count.into())
+            .flags(create_flags)
+            .build()
+    }
+
+    // vkCreateImage is not required to reject a format the device can't use the way the image is
+    // created, so ask the physical device up front
+    fn verify_image_format_supported(
+        device_context: &RafxDeviceContextVulkan,
+        texture_def: &RafxTextureDef,
+        image_create_info: &vk::ImageCreateInfo,
+    ) -> RafxResult<()> {
+        unsafe {
+            device_context
+                .instance()
+                .get_physical_device_image_format_properties(
+                    device_context.physical_device(),
+                    image_create_info.format,
+                    image_create_info.image_type,
+                    image_create_info.tiling,
+                    image_create_info.usage,
+                    image_create_info.flags,
+                )
+                .map_err(|e| Self::map_format_error(texture_def.format, e.into()))?;
+        }
+
+        Ok(())
+    }
+
+    fn map_format_error(
+        format: RafxFormat,
+        error: RafxError,
+    ) -> RafxError {
+        match error {
+            RafxError::VkError(vk::Result::ERROR_FORMAT_NOT_SUPPORTED) => {
+                RafxError::UnsupportedFormat(format)
+            }
+            RafxError::VkMemError(ref e)
+                if matches!(
+                    e.kind(),
+                    vk_mem::ErrorKind::Vulkan(vk::Result::ERROR_FORMAT_NOT_SUPPORTED)
+                ) =>
+            {
+                RafxError::UnsupportedFormat(format)
+            }
+            error => error,
+        }
+    }
+
     // This path is mostly so we can wrap a provided swapchain image
     pub fn from_existing(
         device_context: &RafxDeviceContextVulkan,
         existing_image: Option<RafxRawImageVulkan>,
         texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureVulkan> {
+        Self::create(device_context, existing_image, None, texture_def)
+    }
+
+    fn create(
+        device_context: &RafxDeviceContextVulkan,
+        existing_image: Option<RafxRawImageVulkan>,
+        heap_placement: Option<(&RafxHeapVulkan, u64)>,
+        texture_def: &RafxTextureDef,
     ) -> RafxResult<RafxTextureVulkan> {
         texture_def.verify();
+        texture_def.verify_device_limits(device_context.device_info())?;
 
         // if RW texture, create image viewsper mip, otherwise none?
 
@@ -251,42 +451,42 @@ impl RafxTextureVulkan {
         // create the image
         let image = if let Some(existing_image) = existing_image {
             existing_image
-        } else {
-            //
-            // Determine image usage flags
-            //
-            let mut usage_flags =
-                super::util::resource_type_image_usage_flags(texture_def.resource_type);
-            if texture_def
-                .resource_type
-                .intersects(RafxResourceType::RENDER_TARGET_COLOR)
-            {
-                usage_flags |= vk::ImageUsageFlags::COLOR_ATTACHMENT;
-            } else if texture_def
-                .resource_type
-                .intersects(RafxResourceType::RENDER_TARGET_DEPTH_STENCIL)
-            {
-                usage_flags |= vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT;
-            }
-
-            if usage_flags.intersects(vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::STORAGE) {
-                usage_flags |=
-                    vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST;
+        } else if let Some((heap, offset)) = heap_placement {
+            let image_create_info = Self::image_create_info(texture_def);
+            Self::verify_image_format_supported(device_context, texture_def, &image_create_info)?;
+
+            let device = device_context.device();
+            let image = unsafe {
+                device
+                    .create_image(&image_create_info, None)
+                    .map_err(|e| Self::map_format_error(texture_def.format, e.into()))?
+            };
+            let memory_requirements = unsafe { device.get_image_memory_requirements(image) };
+            let bind_result = heap
+                .verify_placement(offset, &memory_requirements)
+                .and_then(|_| unsafe {
+                    Ok(device.bind_image_memory(
+                        image,
+                        heap.vk_device_memory(),
+                        heap.vk_device_memory_offset() + offset,
+                    )?)
+                });
+            if let Err(e) = bind_result {
+                unsafe {
+                    device.destroy_image(image, None);
+                }
+                return Err(e);
             }
 
-            //
-            // Determine image create flags
-            //
-            let mut create_flags = vk::ImageCreateFlags::empty();
-            if is_cubemap {
-                create_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
-            }
-            if image_type == vk::ImageType::TYPE_3D {
-                create_flags |= vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE_KHR
+            // No allocation, the image is destroyed by RafxTextureVulkanInner and the memory is
+            // owned by the heap
+            RafxRawImageVulkan {
+                image,
+                allocation: None,
             }
-
-            //TODO: Could check vkGetPhysicalDeviceFormatProperties for if we support the format for
-            // the various ways we might use it
+        } else {
+            let image_create_info = Self::image_create_info(texture_def);
+            Self::verify_image_format_supported(device_context, texture_def, &image_create_info)?;
 
             let allocation_create_info = vk_mem::AllocationCreateInfo {
                 usage: vk_mem::MemoryUsage::GpuOnly,
@@ -298,32 +498,13 @@ impl RafxTextureVulkan {
                 user_data: None,
             };
 
-            let extent = vk::Extent3D {
-                width: texture_def.extents.width,
-                height: texture_def.extents.height,
-                depth: texture_def.extents.depth,
-            };
-
-            let image_create_info = vk::ImageCreateInfo::builder()
-                .image_type(image_type)
-                .extent(extent)
-                .mip_levels(texture_def.mip_count)
-                .array_layers(texture_def.array_length)
-                .format(format_vk)
-                .tiling(vk::ImageTiling::OPTIMAL)
-                .initial_layout(vk::ImageLayout::UNDEFINED)
-                .usage(usage_flags)
-                .sharing_mode(vk::SharingMode::EXCLUSIVE)
-                .samples(texture_def.sample_count.into())
-                .flags(create_flags);
-
             //let allocator = device.allocator().clone();
             let (image, allocation, _allocation_info) = device_context
                 .allocator()
                 .create_image(&image_create_info, &allocation_create_info)
-                .map_err(|_| {
-                    log::error!("Error creating image");
-                    vk::Result::ERROR_UNKNOWN
+                .map_err(|e| {
+                    log::error!("Error creating image: {:?}", e);
+                    Self::map_format_error(texture_def.format, e.into())
                 })?;
 
             RafxRawImageVulkan {
@@ -513,6 +694,21 @@ impl RafxTextureVulkan {
             }
         }
 
+        // Images that were passed in without an allocation (like swapchain images) are not
+        // destroyed by rafx, so don't count them
+        let allocation_size = if let Some(allocation) = &image.allocation {
+            let allocation_size = device_context
+                .allocator()
+                .get_allocation_info(allocation)?
+                .get_size() as u64;
+            device_context
+                .memory_usage_tracker()
+                .on_texture_allocated(RafxMemoryUsage::GpuOnly, allocation_size);
+            allocation_size
+        } else {
+            0
+        };
+
         // Used for hashing framebuffers
         let texture_id = crate::internal_shared::NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed);
 
@@ -520,6 +716,8 @@ impl RafxTextureVulkan {
             texture_def: texture_def.clone(),
             device_context: device_context.clone(),
             image,
+            allocation_size,
+            heap: heap_placement.map(|(heap, _)| heap.clone()),
             aspect_mask,
             srv_view,
             srv_view_stencil,
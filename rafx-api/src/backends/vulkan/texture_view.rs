@@ -0,0 +1,154 @@
+use crate::vulkan::{RafxDeviceContextVulkan, RafxTextureVulkan};
+use crate::*;
+use ash::version::DeviceV1_0;
+use ash::vk;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RafxTextureViewVulkanInner {
+    device_context: RafxDeviceContextVulkan,
+    // It's a RafxTextureVulkan, but stored as RafxTexture so we can return refs to it
+    texture: RafxTexture,
+    texture_view_def: RafxTextureViewDef,
+    // Covers every aspect of the format, used for render targets
+    image_view: vk::ImageView,
+    // Descriptors may only reference a single aspect, so depth/stencil formats get a depth-only
+    // view for binding to descriptors
+    image_view_depth: Option<vk::ImageView>,
+    // Used as part of the hash for creating/reusing framebuffers
+    texture_view_id: u32,
+    #[cfg(feature = "capture")]
+    capture_id: u32,
+}
+
+impl Drop for RafxTextureViewVulkanInner {
+    fn drop(&mut self) {
+        let device = self.device_context.device();
+        unsafe {
+            device.destroy_image_view(self.image_view, None);
+            if let Some(image_view_depth) = self.image_view_depth {
+                device.destroy_image_view(image_view_depth, None);
+            }
+        }
+    }
+}
+
+/// Holds a vk::ImageView of a subset of a texture's subresources
+#[derive(Clone, Debug)]
+pub struct RafxTextureViewVulkan {
+    inner: Arc<RafxTextureViewVulkanInner>,
+}
+
+impl RafxTextureViewVulkan {
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        self.inner.capture_id
+    }
+
+    pub fn texture_view_def(&self) -> &RafxTextureViewDef {
+        &self.inner.texture_view_def
+    }
+
+    pub fn texture(&self) -> &RafxTexture {
+        &self.inner.texture
+    }
+
+    pub fn vk_texture(&self) -> &RafxTextureVulkan {
+        self.inner.texture.vk_texture().unwrap()
+    }
+
+    // Color/Depth/Stencil, used for render targets
+    pub fn vk_image_view(&self) -> vk::ImageView {
+        self.inner.image_view
+    }
+
+    // Color/Depth, used for descriptors
+    pub fn vk_descriptor_image_view(&self) -> vk::ImageView {
+        self.inner.image_view_depth.unwrap_or(self.inner.image_view)
+    }
+
+    // Used internally as part of the hash for creating/reusing framebuffers
+    pub(crate) fn texture_view_id(&self) -> u32 {
+        self.inner.texture_view_id
+    }
+
+    pub fn new(
+        device_context: &RafxDeviceContextVulkan,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureViewVulkan> {
+        let vk_texture = texture
+            .vk_texture()
+            .ok_or("Texture views must be created from a texture of the same backend")?;
+        let texture_def = vk_texture.texture_def();
+        let texture_view_def = texture_view_def.resolve(texture_def)?;
+
+        let format = texture_view_def.format.unwrap();
+        let mip_range = texture_view_def.mip_range.clone().unwrap();
+        let array_range = texture_view_def.array_range.clone().unwrap();
+
+        // Depth formats can't be viewed as color formats in vulkan, but sampling the depth aspect
+        // returns the same values
+        let is_depth_sample_view = format != texture_def.format && texture_def.format.has_depth();
+        let (format_vk, aspect_mask) = if is_depth_sample_view {
+            (texture_def.format.into(), vk::ImageAspectFlags::DEPTH)
+        } else {
+            (
+                format.into(),
+                super::util::image_format_to_aspect_mask(format),
+            )
+        };
+
+        let subresource_range = vk::ImageSubresourceRange::builder()
+            .aspect_mask(aspect_mask)
+            .base_mip_level(mip_range.start)
+            .level_count(mip_range.end - mip_range.start)
+            .base_array_layer(array_range.start)
+            .layer_count(array_range.end - array_range.start);
+
+        let mut image_view_create_info = vk::ImageViewCreateInfo::builder()
+            .image(vk_texture.vk_image())
+            .view_type(texture_view_def.dimensions.unwrap().into())
+            .format(format_vk)
+            .components(vk::ComponentMapping::default())
+            .subresource_range(*subresource_range);
+
+        let device = device_context.device();
+        let image_view = unsafe { device.create_image_view(&*image_view_create_info, None)? };
+
+        let image_view_depth =
+            if aspect_mask.contains(vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL) {
+                image_view_create_info.subresource_range.aspect_mask = vk::ImageAspectFlags::DEPTH;
+                match unsafe { device.create_image_view(&*image_view_create_info, None) } {
+                    Ok(image_view_depth) => Some(image_view_depth),
+                    Err(e) => {
+                        unsafe {
+                            device.destroy_image_view(image_view, None);
+                        }
+                        return Err(e.into());
+                    }
+                }
+            } else {
+                None
+            };
+
+        let texture_view_id =
+            crate::internal_shared::NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed);
+
+        let inner = RafxTextureViewVulkanInner {
+            device_context: device_context.clone(),
+            texture: texture.clone(),
+            texture_view_def,
+            image_view,
+            image_view_depth,
+            texture_view_id,
+            #[cfg(feature = "capture")]
+            capture_id: crate::capture::next_capture_id(),
+        };
+
+        Ok(RafxTextureViewVulkan {
+            inner: Arc::new(inner),
+        })
+    }
+}
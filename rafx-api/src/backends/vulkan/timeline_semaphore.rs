@@ -0,0 +1,109 @@
+use crate::vulkan::RafxDeviceContextVulkan;
+use crate::RafxResult;
+use ash::version::DeviceV1_0;
+use ash::vk;
+
+pub struct RafxTimelineSemaphoreVulkan {
+    device_context: RafxDeviceContextVulkan,
+    vk_semaphore: vk::Semaphore,
+}
+
+impl Drop for RafxTimelineSemaphoreVulkan {
+    fn drop(&mut self) {
+        unsafe {
+            self.device_context
+                .device()
+                .destroy_semaphore(self.vk_semaphore, None)
+        }
+    }
+}
+
+impl RafxTimelineSemaphoreVulkan {
+    pub fn new(
+        device_context: &RafxDeviceContextVulkan,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphoreVulkan> {
+        if device_context.timeline_semaphore().is_none() {
+            Err("Timeline semaphores are not supported by this device (VK_KHR_timeline_semaphore is not available)")?;
+        }
+
+        let mut semaphore_type_create_info = vk::SemaphoreTypeCreateInfo::builder()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(initial_value);
+
+        let create_info = vk::SemaphoreCreateInfo::builder()
+            .flags(vk::SemaphoreCreateFlags::empty())
+            .push_next(&mut semaphore_type_create_info);
+
+        let vk_semaphore = unsafe {
+            device_context
+                .device()
+                .create_semaphore(&*create_info, None)?
+        };
+
+        Ok(RafxTimelineSemaphoreVulkan {
+            device_context: device_context.clone(),
+            vk_semaphore,
+        })
+    }
+
+    pub fn vk_semaphore(&self) -> vk::Semaphore {
+        self.vk_semaphore
+    }
+
+    pub fn current_value(&self) -> RafxResult<u64> {
+        let value = unsafe {
+            self.device_context
+                .timeline_semaphore()
+                .unwrap()
+                .get_semaphore_counter_value(
+                    self.device_context.device().handle(),
+                    self.vk_semaphore,
+                )?
+        };
+
+        Ok(value)
+    }
+
+    pub fn signal(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        let signal_info = vk::SemaphoreSignalInfo::builder()
+            .semaphore(self.vk_semaphore)
+            .value(value);
+
+        unsafe {
+            self.device_context
+                .timeline_semaphore()
+                .unwrap()
+                .signal_semaphore(self.device_context.device().handle(), &*signal_info)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn wait(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        let semaphores = [self.vk_semaphore];
+        let values = [value];
+        let wait_info = vk::SemaphoreWaitInfo::builder()
+            .semaphores(&semaphores)
+            .values(&values);
+
+        unsafe {
+            self.device_context
+                .timeline_semaphore()
+                .unwrap()
+                .wait_semaphores(
+                    self.device_context.device().handle(),
+                    &*wait_info,
+                    std::u64::MAX,
+                )?;
+        }
+
+        Ok(())
+    }
+}
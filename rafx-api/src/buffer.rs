@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxBufferEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxBufferGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxBufferMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -20,9 +26,15 @@ pub enum RafxBuffer {
     Metal(RafxBufferMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxBufferEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxBufferGles3),
 }
 
 impl RafxBuffer {
@@ -40,10 +52,22 @@ impl RafxBuffer {
             RafxBuffer::Metal(inner) => inner.copy_to_host_visible_buffer(data),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => inner.copy_to_host_visible_buffer(data),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.copy_to_host_visible_buffer(data),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .on_buffer_written(self, 0, Self::slice_as_bytes(data));
+
+        Ok(())
     }
 
     /// Copy all the data in the given slice into the buffer with a given offset. The offset is in
@@ -64,11 +88,38 @@ impl RafxBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => {
                 inner.copy_to_host_visible_buffer_with_offset(data, buffer_byte_offset)
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => {
+                inner.copy_to_host_visible_buffer_with_offset(data, buffer_byte_offset)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_buffer_written(
+            self,
+            buffer_byte_offset,
+            Self::slice_as_bytes(data),
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "capture")]
+    fn slice_as_bytes<T: Copy>(data: &[T]) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                data.as_ptr() as *const u8,
+                rafx_base::memory::slice_size_in_bytes(data),
+            )
         }
     }
 
@@ -81,9 +132,37 @@ impl RafxBuffer {
             RafxBuffer::Metal(inner) => inner.buffer_def(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => inner.buffer_def(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.buffer_def(),
+        }
+    }
+
+    // Used internally to identify the buffer for validation and capture
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn buffer_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxBuffer::Vk(inner) => inner.buffer_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxBuffer::Metal(inner) => inner.buffer_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxBuffer::Empty(inner) => inner.buffer_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.buffer_id(),
         }
     }
 
@@ -95,17 +174,28 @@ impl RafxBuffer {
     ///
     /// Generally speaking, keeping a buffer mapped for its entire lifetime is acceptable.
     pub fn map_buffer(&self) -> RafxResult<*mut u8> {
-        match self {
+        let mapped_ptr = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxBuffer::Vk(inner) => inner.map_buffer(),
             #[cfg(feature = "rafx-metal")]
             RafxBuffer::Metal(inner) => inner.map_buffer(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => inner.map_buffer(),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.map_buffer(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_buffer_mapped(self, mapped_ptr);
+
+        Ok(mapped_ptr)
     }
 
     /// Unmap the contents of the buffer from CPU memory. This function will fail if the buffer is
@@ -115,6 +205,9 @@ impl RafxBuffer {
     /// The mappings are "ref-counted". Repeated calls to map the same buffer are permitted and the
     /// buffer will remain mapped until an equal number of calls to unmap_buffer are made.
     pub fn unmap_buffer(&self) -> RafxResult<()> {
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_buffer_unmapping(self);
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxBuffer::Vk(inner) => inner.unmap_buffer(),
@@ -122,10 +215,21 @@ impl RafxBuffer {
             RafxBuffer::Metal(inner) => inner.unmap_buffer(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => inner.unmap_buffer(),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.unmap_buffer(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_buffer_unmapped(self);
+
+        Ok(())
     }
 
     /// Obtain a pointer to the mapped memory. If the buffer is not mapped, None is returned.
@@ -137,9 +241,61 @@ impl RafxBuffer {
             RafxBuffer::Metal(inner) => inner.mapped_memory(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => inner.mapped_memory(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.mapped_memory(),
+        }
+    }
+
+    /// Sets the name of the buffer shown by GPU debugging tools like RenderDoc and Xcode. On
+    /// vulkan this requires VK_EXT_debug_utils, which is enabled when available.
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxBuffer::Vk(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxBuffer::Metal(inner) => inner.set_debug_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxBuffer::Empty(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.set_debug_name(name),
+        }
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxBuffer::Vk(inner) => inner.capture_recorder(),
+            #[cfg(feature = "rafx-metal")]
+            RafxBuffer::Metal(inner) => inner.capture_recorder(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxBuffer::Empty(inner) => inner.capture_recorder(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => inner.capture_recorder(),
         }
     }
 
@@ -154,9 +310,15 @@ impl RafxBuffer {
             RafxBuffer::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(_inner) => None,
         }
     }
 
@@ -171,9 +333,15 @@ impl RafxBuffer {
             RafxBuffer::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(_inner) => None,
         }
     }
 
@@ -181,7 +349,11 @@ impl RafxBuffer {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_buffer(&self) -> Option<&RafxBufferEmpty> {
         match self {
@@ -191,9 +363,38 @@ impl RafxBuffer {
             RafxBuffer::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxBuffer::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_buffer(&self) -> Option<&RafxBufferGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxBuffer::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxBuffer::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxBuffer::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxBuffer::Empty(_) => None,
         }
     }
 }
@@ -0,0 +1,948 @@
+//! Records the resources and commands submitted through a `RafxDeviceContext` so that they can be
+//! saved to a file and replayed later, possibly on a different machine or backend. This is
+//! intended for reproducing rendering bugs that only happen on someone else's hardware.
+//!
+//! Capturing requires the `capture` feature and is enabled per device with
+//! `RafxApiDef::enable_capture`. Frames are recorded between `RafxDeviceContext::begin_capture()`
+//! and `RafxDeviceContext::end_capture()`, which returns the capture. Save it with
+//! `RafxCapture::write_to_file()`. The `rafx-replay` tool (or `RafxCaptureReplay`) replays a saved
+//! capture.
+//!
+//! Outside of a capture, commands are not recorded and only what's needed to recreate the objects
+//! that are still alive is kept, so capturing can be left enabled for the lifetime of the device.
+//!
+//! A capture contains:
+//!  * The defs used to create buffers, textures, texture views, samplers, shader modules (including
+//!    their bytecode), shaders, root signatures, pipelines and descriptor set arrays
+//!  * Descriptor set updates
+//!  * Data written to host-visible buffers. Writes through mapped pointers are detected by
+//!    comparing the buffer's contents to a shadow copy when it is unmapped and when work is
+//!    submitted.
+//!  * Every command recorded into command buffers, and the order command buffers were submitted in
+//!
+//! Some things are not captured:
+//!  * Work submitted before the capture began. Anything the GPU wrote to textures or
+//!    non-host-visible buffers before then (like uploads through a staging buffer) is missing.
+//!  * Fences and semaphores. Replay waits for the queue to become idle after every submit.
+//!  * Queries. Query pool commands are skipped.
+//!  * Heaps. Resources placed in a heap are replayed as regular (non-aliased) resources.
+//!  * Swapchains. Swapchain images are replayed as offscreen textures and presents only advance
+//!    the frame count.
+//!  * Shader code for other backends. Shader modules contain what was passed to
+//!    `create_shader_module`, so a capture can only be replayed on a backend the shaders were
+//!    provided for. The empty backend can replay any capture.
+
+mod recorder;
+pub(crate) use recorder::*;
+
+mod replay;
+pub use replay::*;
+
+use crate::{
+    RafxBarrierQueueTransition, RafxBlendState, RafxBufferDef, RafxCmdBlitParams,
+    RafxCmdClearTextureParams, RafxCmdCopyBufferToTextureParams, RafxCmdCopyTextureToBufferParams,
+    RafxCmdCopyTextureToTextureParams, RafxColorClearValue, RafxDepthState,
+    RafxDepthStencilClearValue, RafxDescriptorIndex, RafxError, RafxFormat, RafxIndexType,
+    RafxLoadOp, RafxOffsetSize, RafxPrimitiveTopology, RafxPushConstantIndex, RafxQueueType,
+    RafxRasterizerState, RafxResourceState, RafxResult, RafxSampleCount, RafxSamplerDef,
+    RafxShaderPackage, RafxShaderStageReflection, RafxSpecializationConstant, RafxStoreOp,
+    RafxTextureBindType, RafxTextureDef, RafxTextureViewDef, RafxVertexLayout,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Bumped whenever the serialized format of `RafxCapture` changes. Captures with a different
+/// version can't be read.
+pub const RAFX_CAPTURE_VERSION: u32 = 2;
+
+// Written at the start of every capture file, followed by the version as a little-endian u32
+const RAFX_CAPTURE_MAGIC: &[u8; 8] = b"RAFXCAPT";
+
+/// Identifies a descriptor set within a captured descriptor set array
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RafxCaptureDescriptorSetRef {
+    pub descriptor_set_array_id: u32,
+    pub array_index: u32,
+}
+
+/// Owned copy of `RafxImmutableSamplerKey`/`RafxBindlessArrayKey`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RafxCaptureBindingKey {
+    Name(String),
+    Binding(u32, u32),
+}
+
+/// Owned copy of `RafxDescriptorKey`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RafxCaptureDescriptorKey {
+    Undefined,
+    Name(String),
+    Binding(u32),
+    DescriptorIndex(RafxDescriptorIndex),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureShaderStage {
+    pub shader_module_id: u32,
+    pub reflection: RafxShaderStageReflection,
+    pub specialization_constants: Vec<RafxSpecializationConstant>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureImmutableSamplers {
+    pub key: RafxCaptureBindingKey,
+    pub sampler_ids: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureBindlessArray {
+    pub key: RafxCaptureBindingKey,
+    pub max_element_count: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureGraphicsPipelineDef {
+    pub shader_id: u32,
+    pub root_signature_id: u32,
+    pub vertex_layout: RafxVertexLayout,
+    pub blend_state: RafxBlendState,
+    pub depth_state: RafxDepthState,
+    pub rasterizer_state: RafxRasterizerState,
+    pub primitive_topology: RafxPrimitiveTopology,
+    pub color_formats: Vec<RafxFormat>,
+    pub depth_stencil_format: Option<RafxFormat>,
+    pub sample_count: RafxSampleCount,
+}
+
+/// Owned copy of `RafxDescriptorUpdate`, with resources referred to by id
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureDescriptorUpdate {
+    pub array_index: u32,
+    pub descriptor_key: RafxCaptureDescriptorKey,
+    pub texture_ids: Option<Vec<u32>>,
+    pub texture_view_ids: Option<Vec<u32>>,
+    pub sampler_ids: Option<Vec<u32>>,
+    pub buffer_ids: Option<Vec<u32>>,
+    pub buffer_offset_sizes: Option<Vec<RafxOffsetSize>>,
+    pub dst_element_offset: u32,
+    pub texture_bind_type: Option<RafxTextureBindType>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureColorRenderTarget {
+    pub texture_id: u32,
+    pub load_op: RafxLoadOp,
+    pub store_op: RafxStoreOp,
+    pub mip_slice: Option<u8>,
+    pub array_slice: Option<u16>,
+    pub texture_view_id: Option<u32>,
+    pub clear_value: RafxColorClearValue,
+    pub resolve_texture_id: Option<u32>,
+    pub resolve_store_op: RafxStoreOp,
+    pub resolve_mip_slice: Option<u8>,
+    pub resolve_array_slice: Option<u16>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureDepthStencilRenderTarget {
+    pub texture_id: u32,
+    pub depth_load_op: RafxLoadOp,
+    pub stencil_load_op: RafxLoadOp,
+    pub depth_store_op: RafxStoreOp,
+    pub stencil_store_op: RafxStoreOp,
+    pub mip_slice: Option<u8>,
+    pub array_slice: Option<u16>,
+    pub texture_view_id: Option<u32>,
+    pub clear_value: RafxDepthStencilClearValue,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureBufferBarrier {
+    pub buffer_id: u32,
+    pub src_state: RafxResourceState,
+    pub dst_state: RafxResourceState,
+    pub queue_transition: RafxBarrierQueueTransition,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureTextureBarrier {
+    pub texture_id: u32,
+    pub src_state: RafxResourceState,
+    pub dst_state: RafxResourceState,
+    pub queue_transition: RafxBarrierQueueTransition,
+    pub array_slice: Option<u16>,
+    pub mip_slice: Option<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureBufferBinding {
+    pub buffer_id: u32,
+    pub byte_offset: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RafxCaptureInheritanceInfo {
+    pub color_formats: Vec<RafxFormat>,
+    pub depth_stencil_format: Option<RafxFormat>,
+    pub sample_count: RafxSampleCount,
+    pub primary_command_buffer_id: Option<u32>,
+}
+
+/// A command recorded into a command buffer
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RafxCaptureCommand {
+    Begin,
+    BeginSecondary(RafxCaptureInheritanceInfo),
+    End,
+    BeginRenderPass {
+        color_targets: Vec<RafxCaptureColorRenderTarget>,
+        depth_target: Option<RafxCaptureDepthStencilRenderTarget>,
+    },
+    BeginRenderPassForSecondary {
+        color_targets: Vec<RafxCaptureColorRenderTarget>,
+        depth_target: Option<RafxCaptureDepthStencilRenderTarget>,
+    },
+    ExecuteSecondaryCommandBuffers {
+        command_buffer_ids: Vec<u32>,
+    },
+    EndRenderPass,
+    SetViewport {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        depth_min: f32,
+        depth_max: f32,
+    },
+    SetScissor {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    SetStencilReferenceValue {
+        value: u32,
+    },
+    SetDepthBias {
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    },
+    SetBlendConstants {
+        blend_constants: [f32; 4],
+    },
+    SetLineWidth {
+        line_width: f32,
+    },
+    BindPipeline {
+        pipeline_id: u32,
+    },
+    BindVertexBuffers {
+        first_binding: u32,
+        bindings: Vec<RafxCaptureBufferBinding>,
+    },
+    BindIndexBuffer {
+        binding: RafxCaptureBufferBinding,
+        index_type: RafxIndexType,
+    },
+    BindDescriptorSet {
+        descriptor_set: RafxCaptureDescriptorSetRef,
+    },
+    BindDescriptorSetHandle {
+        root_signature_id: u32,
+        set_index: u32,
+        descriptor_set: RafxCaptureDescriptorSetRef,
+    },
+    BindPushConstant {
+        root_signature_id: u32,
+        push_constant_index: RafxPushConstantIndex,
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+    Draw {
+        vertex_count: u32,
+        first_vertex: u32,
+    },
+    DrawInstanced {
+        vertex_count: u32,
+        first_vertex: u32,
+        instance_count: u32,
+        first_instance: u32,
+    },
+    DrawIndexed {
+        index_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+    },
+    DrawIndexedInstanced {
+        index_count: u32,
+        first_index: u32,
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: i32,
+    },
+    Dispatch {
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    },
+    DrawIndirect {
+        buffer_id: u32,
+        offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    DrawIndexedIndirect {
+        buffer_id: u32,
+        offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    DispatchIndirect {
+        buffer_id: u32,
+        offset_in_bytes: u64,
+    },
+    ResourceBarrier {
+        buffer_barriers: Vec<RafxCaptureBufferBarrier>,
+        texture_barriers: Vec<RafxCaptureTextureBarrier>,
+    },
+    ClearTexture {
+        texture_id: u32,
+        params: RafxCmdClearTextureParams,
+    },
+    FillBuffer {
+        buffer_id: u32,
+        offset: u64,
+        size: u64,
+        value: u8,
+    },
+    CopyBufferToBuffer {
+        src_buffer_id: u32,
+        dst_buffer_id: u32,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
+    },
+    CopyBufferToTexture {
+        src_buffer_id: u32,
+        dst_texture_id: u32,
+        params: RafxCmdCopyBufferToTextureParams,
+    },
+    CopyTextureToBuffer {
+        src_texture_id: u32,
+        dst_buffer_id: u32,
+        params: RafxCmdCopyTextureToBufferParams,
+    },
+    CopyTextureToTexture {
+        src_texture_id: u32,
+        dst_texture_id: u32,
+        params: RafxCmdCopyTextureToTextureParams,
+    },
+    Blit {
+        src_texture_id: u32,
+        dst_texture_id: u32,
+        params: RafxCmdBlitParams,
+    },
+    PushGroupDebugMarker {
+        name: String,
+    },
+    PopGroupDebugMarker,
+}
+
+/// Something that happened on the device, in the order it happened. Objects are referred to by
+/// ids that are unique within the capture.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RafxCaptureEvent {
+    CreateQueue {
+        queue_id: u32,
+        queue_type: RafxQueueType,
+    },
+    CreateCommandBuffer {
+        command_buffer_id: u32,
+        queue_type: RafxQueueType,
+        is_secondary: bool,
+    },
+    CreateBuffer {
+        buffer_id: u32,
+        buffer_def: RafxBufferDef,
+    },
+    /// Data written to a host-visible buffer
+    WriteBuffer {
+        buffer_id: u32,
+        offset: u64,
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+    DestroyBuffer {
+        buffer_id: u32,
+    },
+    /// Also used for textures that were not created through the device context, like swapchain
+    /// images. These are recorded when they are first used.
+    CreateTexture {
+        texture_id: u32,
+        texture_def: RafxTextureDef,
+    },
+    DestroyTexture {
+        texture_id: u32,
+    },
+    CreateTextureView {
+        texture_view_id: u32,
+        texture_id: u32,
+        texture_view_def: RafxTextureViewDef,
+    },
+    CreateSampler {
+        sampler_id: u32,
+        sampler_def: RafxSamplerDef,
+    },
+    CreateShaderModule {
+        shader_module_id: u32,
+        shader_package: RafxShaderPackage,
+    },
+    CreateShader {
+        shader_id: u32,
+        stages: Vec<RafxCaptureShaderStage>,
+    },
+    CreateRootSignature {
+        root_signature_id: u32,
+        shader_ids: Vec<u32>,
+        immutable_samplers: Vec<RafxCaptureImmutableSamplers>,
+        bindless_arrays: Vec<RafxCaptureBindlessArray>,
+    },
+    CreateGraphicsPipeline {
+        pipeline_id: u32,
+        pipeline_def: RafxCaptureGraphicsPipelineDef,
+    },
+    CreateComputePipeline {
+        pipeline_id: u32,
+        shader_id: u32,
+        root_signature_id: u32,
+    },
+    CreateDescriptorSetArray {
+        descriptor_set_array_id: u32,
+        root_signature_id: u32,
+        set_index: u32,
+        array_length: usize,
+    },
+    UpdateDescriptorSet {
+        descriptor_set_array_id: u32,
+        update: RafxCaptureDescriptorUpdate,
+    },
+    QueueDescriptorSetUpdate {
+        descriptor_set_array_id: u32,
+        update: RafxCaptureDescriptorUpdate,
+    },
+    FlushDescriptorSetUpdates {
+        descriptor_set_array_id: u32,
+    },
+    Command {
+        command_buffer_id: u32,
+        command: RafxCaptureCommand,
+    },
+    Submit {
+        queue_id: u32,
+        command_buffer_ids: Vec<u32>,
+    },
+    Present {
+        queue_id: u32,
+    },
+}
+
+/// Everything recorded by a device with capturing enabled. See the module documentation.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RafxCapture {
+    pub events: Vec<RafxCaptureEvent>,
+}
+
+impl RafxCapture {
+    /// Number of frames presented in the capture
+    pub fn frame_count(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|x| matches!(x, RafxCaptureEvent::Present { .. }))
+            .count()
+    }
+
+    /// Serialize the capture, prefixed with a header that identifies the format version
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W,
+    ) -> RafxResult<()> {
+        writer.write_all(RAFX_CAPTURE_MAGIC)?;
+        writer.write_all(&RAFX_CAPTURE_VERSION.to_le_bytes())?;
+        bincode::serialize_into(writer, self)
+            .map_err(|e| RafxError::StringError(format!("Failed to write capture: {}", e)))
+    }
+
+    /// Deserialize a capture written by `write()`. Fails if it was written by an incompatible
+    /// version of rafx-api.
+    pub fn read<R: Read>(mut reader: R) -> RafxResult<RafxCapture> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != RAFX_CAPTURE_MAGIC {
+            Err("The data is not a rafx capture")?;
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != RAFX_CAPTURE_VERSION {
+            Err(format!(
+                "The capture has version {} but only version {} is supported",
+                version, RAFX_CAPTURE_VERSION
+            ))?;
+        }
+
+        bincode::deserialize_from(reader)
+            .map_err(|e| RafxError::StringError(format!("Failed to read capture: {}", e)))
+    }
+
+    /// Write the capture to a file, usually with a `.rafxcapture` extension
+    pub fn write_to_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> RafxResult<()> {
+        let file = std::fs::File::create(path)?;
+        let mut writer = std::io::BufWriter::new(file);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a capture written by `write_to_file()`
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> RafxResult<RafxCapture> {
+        let file = std::fs::File::open(path)?;
+        Self::read(std::io::BufReader::new(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    // Written by write_capture_fixture(). Captures are bincode, so adding, removing or reordering a
+    // field anywhere in RafxCapture (including in shader packages and reflection data) breaks
+    // existing captures. When that happens, bump RAFX_CAPTURE_VERSION and regenerate this file.
+    const CAPTURE_FIXTURE: &[u8] = include_bytes!("test_data/fixture.rafxcapture");
+
+    // Sets every optional field that is serialized so that changes to them are caught
+    fn fixture_capture() -> RafxCapture {
+        let shader_resource = |resource_type, binding, name: &str| RafxShaderResource {
+            resource_type,
+            set_index: 0,
+            binding,
+            element_count: 1,
+            size_in_bytes: 0,
+            used_in_shader_stages: RafxShaderStageFlags::VERTEX,
+            name: Some(name.to_string()),
+            gles_name: Some(format!("{}_gles", name)),
+            gles_sampler_name: None,
+        };
+
+        let stage = RafxCaptureShaderStage {
+            shader_module_id: 1,
+            reflection: RafxShaderStageReflection {
+                shader_stage: RafxShaderStageFlags::VERTEX,
+                resources: vec![
+                    shader_resource(RafxResourceType::UNIFORM_BUFFER, 0, "per_frame"),
+                    RafxShaderResource {
+                        gles_sampler_name: Some("smp".to_string()),
+                        ..shader_resource(RafxResourceType::TEXTURE, 1, "tex")
+                    },
+                    shader_resource(RafxResourceType::SAMPLER, 2, "smp"),
+                ],
+                compute_threads_per_group: None,
+                entry_point_name: "main".to_string(),
+                specialization_constants: vec![RafxSpecializationConstantReflection {
+                    name: Some("ENABLE_FOG".to_string()),
+                    constant_id: 3,
+                    default_value: RafxSpecializationConstantValue::Bool(false),
+                }],
+            },
+            specialization_constants: vec![RafxSpecializationConstant {
+                constant_id: 3,
+                value: RafxSpecializationConstantValue::Bool(true),
+            }],
+        };
+
+        let render_target = RafxCaptureColorRenderTarget {
+            texture_id: 6,
+            load_op: RafxLoadOp::Clear,
+            store_op: RafxStoreOp::Store,
+            mip_slice: None,
+            array_slice: None,
+            texture_view_id: None,
+            clear_value: RafxColorClearValue([0.0, 0.0, 1.0, 1.0]),
+            resolve_texture_id: None,
+            resolve_store_op: RafxStoreOp::DontCare,
+            resolve_mip_slice: None,
+            resolve_array_slice: None,
+        };
+
+        let commands = vec![
+            RafxCaptureCommand::Begin,
+            RafxCaptureCommand::BeginRenderPass {
+                color_targets: vec![render_target],
+                depth_target: None,
+            },
+            RafxCaptureCommand::BindPipeline { pipeline_id: 5 },
+            RafxCaptureCommand::BindDescriptorSet {
+                descriptor_set: RafxCaptureDescriptorSetRef {
+                    descriptor_set_array_id: 8,
+                    array_index: 0,
+                },
+            },
+            RafxCaptureCommand::Draw {
+                vertex_count: 3,
+                first_vertex: 0,
+            },
+            RafxCaptureCommand::EndRenderPass,
+            RafxCaptureCommand::End,
+        ];
+
+        let mut events = vec![
+            RafxCaptureEvent::CreateQueue {
+                queue_id: 1,
+                queue_type: RafxQueueType::Graphics,
+            },
+            RafxCaptureEvent::CreateShaderModule {
+                shader_module_id: 1,
+                shader_package: RafxShaderPackage {
+                    metal: Some(RafxShaderPackageMetal::Src(
+                        "vertex void main0() {}".to_string(),
+                    )),
+                    vk: Some(RafxShaderPackageVulkan::SpvBytes(vec![
+                        0x03, 0x02, 0x23, 0x07,
+                    ])),
+                    gles3: Some(RafxShaderPackageGles3::Src(
+                        "#version 300 es\nvoid main() {}\n".to_string(),
+                    )),
+                    specialization_constants: vec![RafxSpecializationConstant {
+                        constant_id: 3,
+                        value: RafxSpecializationConstantValue::Bool(true),
+                    }],
+                },
+            },
+            RafxCaptureEvent::CreateShader {
+                shader_id: 2,
+                stages: vec![stage],
+            },
+            RafxCaptureEvent::CreateSampler {
+                sampler_id: 3,
+                sampler_def: RafxSamplerDef {
+                    max_anisotropy: 1.0,
+                    ..Default::default()
+                },
+            },
+            RafxCaptureEvent::CreateRootSignature {
+                root_signature_id: 4,
+                shader_ids: vec![2],
+                immutable_samplers: vec![RafxCaptureImmutableSamplers {
+                    key: RafxCaptureBindingKey::Name("smp".to_string()),
+                    sampler_ids: vec![3],
+                }],
+                bindless_arrays: vec![],
+            },
+            RafxCaptureEvent::CreateGraphicsPipeline {
+                pipeline_id: 5,
+                pipeline_def: RafxCaptureGraphicsPipelineDef {
+                    shader_id: 2,
+                    root_signature_id: 4,
+                    vertex_layout: RafxVertexLayout {
+                        attributes: vec![],
+                        buffers: vec![],
+                    },
+                    blend_state: Default::default(),
+                    depth_state: Default::default(),
+                    rasterizer_state: Default::default(),
+                    primitive_topology: RafxPrimitiveTopology::TriangleList,
+                    color_formats: vec![RafxFormat::R8G8B8A8_UNORM],
+                    depth_stencil_format: None,
+                    sample_count: RafxSampleCount::SampleCount1,
+                },
+            },
+            RafxCaptureEvent::CreateTexture {
+                texture_id: 6,
+                texture_def: RafxTextureDef {
+                    extents: RafxExtents3D {
+                        width: 2,
+                        height: 2,
+                        depth: 1,
+                    },
+                    format: RafxFormat::R8G8B8A8_UNORM,
+                    resource_type: RafxResourceType::TEXTURE
+                        | RafxResourceType::RENDER_TARGET_COLOR,
+                    ..Default::default()
+                },
+            },
+            RafxCaptureEvent::CreateBuffer {
+                buffer_id: 7,
+                buffer_def: RafxBufferDef::for_staging_uniform_buffer(16),
+            },
+            RafxCaptureEvent::WriteBuffer {
+                buffer_id: 7,
+                offset: 0,
+                data: vec![1; 16],
+            },
+            RafxCaptureEvent::CreateDescriptorSetArray {
+                descriptor_set_array_id: 8,
+                root_signature_id: 4,
+                set_index: 0,
+                array_length: 1,
+            },
+            RafxCaptureEvent::UpdateDescriptorSet {
+                descriptor_set_array_id: 8,
+                update: RafxCaptureDescriptorUpdate {
+                    array_index: 0,
+                    descriptor_key: RafxCaptureDescriptorKey::Name("per_frame".to_string()),
+                    texture_ids: None,
+                    texture_view_ids: None,
+                    sampler_ids: None,
+                    buffer_ids: Some(vec![7]),
+                    buffer_offset_sizes: None,
+                    dst_element_offset: 0,
+                    texture_bind_type: None,
+                },
+            },
+            RafxCaptureEvent::CreateCommandBuffer {
+                command_buffer_id: 9,
+                queue_type: RafxQueueType::Graphics,
+                is_secondary: false,
+            },
+        ];
+
+        events.extend(
+            commands
+                .into_iter()
+                .map(|command| RafxCaptureEvent::Command {
+                    command_buffer_id: 9,
+                    command,
+                }),
+        );
+        events.push(RafxCaptureEvent::Submit {
+            queue_id: 1,
+            command_buffer_ids: vec![9],
+        });
+        events.push(RafxCaptureEvent::Present { queue_id: 1 });
+
+        RafxCapture { events }
+    }
+
+    #[test]
+    fn decode_capture_fixture() {
+        let capture = RafxCapture::read(CAPTURE_FIXTURE).unwrap();
+        assert_eq!(capture.frame_count(), 1);
+
+        // Writing it back out and writing the capture it was created from must both give the same
+        // bytes, otherwise the format changed
+        let mut data = Vec::new();
+        capture.write(&mut data).unwrap();
+        assert!(data == CAPTURE_FIXTURE);
+
+        let mut data = Vec::new();
+        fixture_capture().write(&mut data).unwrap();
+        assert!(
+            data == CAPTURE_FIXTURE,
+            "The capture format changed. Bump RAFX_CAPTURE_VERSION and run write_capture_fixture"
+        );
+    }
+
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    #[test]
+    fn capture_round_trip_and_replay() {
+        use crate::empty::test_util::EmptyTestContext;
+
+        let test_context = EmptyTestContext::with_api_def(&RafxApiDef {
+            enable_capture: true,
+            ..Default::default()
+        });
+        let device_context = &test_context.device_context;
+        let queue = &test_context.queue;
+        let command_buffer = &test_context.command_buffer;
+
+        let mut swapchain = device_context
+            .create_offscreen_swapchain(
+                queue,
+                &RafxOffscreenSwapchainDef {
+                    swapchain_def: RafxSwapchainDef {
+                        width: 2,
+                        height: 2,
+                        enable_vsync: false,
+                    },
+                    format: RafxFormat::R8G8B8A8_UNORM,
+                    image_count: 1,
+                },
+                None,
+            )
+            .unwrap();
+        let fence = device_context.create_fence().unwrap();
+
+        let staging_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer(
+                16,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+        let gpu_buffer = device_context
+            .create_buffer(&RafxBufferDef {
+                size: 16,
+                memory_usage: RafxMemoryUsage::GpuOnly,
+                resource_type: RafxResourceType::BUFFER,
+                ..Default::default()
+            })
+            .unwrap();
+        let readback_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer(
+                16,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+
+        let mut render_frame = |clear_color: [f32; 4]| {
+            let swapchain_image = swapchain.acquire_next_image_fence(&fence).unwrap();
+            let barrier = |src_state, dst_state| {
+                RafxTextureBarrier::state_transition(&swapchain_image.texture, src_state, dst_state)
+            };
+
+            command_buffer.begin().unwrap();
+            command_buffer
+                .cmd_copy_buffer_to_buffer(&staging_buffer, &gpu_buffer, 0, 0, 16)
+                .unwrap();
+            command_buffer
+                .cmd_copy_buffer_to_buffer(&gpu_buffer, &readback_buffer, 0, 0, 16)
+                .unwrap();
+            command_buffer
+                .cmd_resource_barrier(
+                    &[],
+                    &[barrier(
+                        RafxResourceState::PRESENT,
+                        RafxResourceState::COPY_DST,
+                    )],
+                )
+                .unwrap();
+            command_buffer
+                .cmd_clear_texture(
+                    &swapchain_image.texture,
+                    &RafxCmdClearTextureParams {
+                        texture_state: RafxResourceState::COPY_DST,
+                        clear_value: RafxTextureClearValue::Color(RafxColorClearValue(clear_color)),
+                        mip_slice: None,
+                        array_slice: None,
+                    },
+                )
+                .unwrap();
+            command_buffer
+                .cmd_resource_barrier(
+                    &[],
+                    &[barrier(
+                        RafxResourceState::COPY_DST,
+                        RafxResourceState::PRESENT,
+                    )],
+                )
+                .unwrap();
+            command_buffer.end().unwrap();
+
+            test_context.submit().unwrap();
+            queue
+                .present(&swapchain, &[], swapchain_image.swapchain_image_index)
+                .unwrap();
+            swapchain_image.texture.texture_id()
+        };
+
+        // The buffer is written and a frame is rendered before the capture begins. Only the write
+        // is needed to replay the captured frames.
+        staging_buffer
+            .copy_to_host_visible_buffer(&[1u8; 16])
+            .unwrap();
+        render_frame([0.0, 1.0, 0.0, 1.0]);
+        assert!(device_context.end_capture().is_err());
+        device_context.begin_capture().unwrap();
+        assert!(device_context.begin_capture().is_err());
+
+        // The first captured frame uses the data written before the capture, the second writes
+        // through a mapping
+        render_frame([1.0, 0.0, 0.0, 1.0]);
+
+        let mapped = staging_buffer.map_buffer().unwrap();
+        unsafe { std::ptr::write_bytes(mapped.add(8), 2, 8) };
+        staging_buffer.unmap_buffer().unwrap();
+        let swapchain_texture_id = render_frame([0.0, 0.0, 1.0, 1.0]);
+
+        let capture = device_context.end_capture().unwrap();
+        assert_eq!(capture.frame_count(), 2);
+        assert_eq!(device_context.capture().unwrap().frame_count(), 0);
+
+        let mut data = Vec::new();
+        capture.write(&mut data).unwrap();
+        let capture = RafxCapture::read(&data[..]).unwrap();
+        assert_eq!(capture.frame_count(), 2);
+        assert!(RafxCapture::read(&data[1..]).is_err());
+
+        // Capturing must have been enabled when the api was created
+        let replay_api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        assert!(replay_api.device_context().begin_capture().is_err());
+        assert!(replay_api.device_context().capture().is_err());
+
+        let mut replay = RafxCaptureReplay::new(&replay_api.device_context());
+        replay.replay(&capture).unwrap();
+        assert_eq!(replay.frame_count(), 2);
+
+        let readback_buffer = replay.buffer(readback_buffer.buffer_id()).unwrap();
+        let readback_ptr = readback_buffer.map_buffer().unwrap();
+        let mut expected_data = [1u8; 16];
+        expected_data[8..].copy_from_slice(&[2; 8]);
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(readback_ptr, 16) },
+            &expected_data[..]
+        );
+        readback_buffer.unmap_buffer().unwrap();
+
+        let swapchain_texture = replay
+            .texture(swapchain_texture_id)
+            .unwrap()
+            .empty_texture()
+            .unwrap();
+        assert_eq!(
+            swapchain_texture.read_subresource(0, 0),
+            [0, 0, 255, 255].repeat(4)
+        );
+    }
+
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    #[test]
+    fn replay_capture_fixture() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let mut replay = RafxCaptureReplay::new(&api.device_context());
+        replay
+            .replay(&RafxCapture::read(CAPTURE_FIXTURE).unwrap())
+            .unwrap();
+        assert_eq!(replay.frame_count(), 1);
+    }
+
+    // Regenerates the fixture, run with `cargo test --features capture -- --ignored`
+    #[test]
+    #[ignore]
+    fn write_capture_fixture() {
+        fixture_capture()
+            .write_to_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/capture/test_data/fixture.rafxcapture"
+            ))
+            .unwrap();
+    }
+}
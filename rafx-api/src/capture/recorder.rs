@@ -0,0 +1,918 @@
+// Records events for RafxCapture. Each backend's device context owns one. Wrapper types call into
+// it after creating objects and before recording commands. When capturing is disabled, the only
+// cost is checking a flag, events are built lazily by closures.
+//
+// Outside of a capture window, commands, submits and presents are dropped and the remaining events
+// are periodically compacted down to what's needed to recreate the objects that are still alive.
+// This keeps memory use proportional to the number of live objects rather than the number of
+// frames rendered.
+
+use super::*;
+use crate::{
+    RafxBuffer, RafxBufferBarrier, RafxColorRenderTargetBinding, RafxCommandBufferInheritanceInfo,
+    RafxDepthStencilRenderTargetBinding, RafxDescriptorKey, RafxDescriptorUpdate, RafxMemoryUsage,
+    RafxShaderModuleDef, RafxTexture, RafxTextureBarrier, RafxTextureView,
+};
+use fnv::{FnvHashMap, FnvHashSet};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Ids of samplers, shaders, pipelines, etc. Textures, buffers and queues use their existing ids,
+// so captured ids are only unique per object type.
+static NEXT_CAPTURE_ID: AtomicU32 = AtomicU32::new(1);
+
+pub(crate) fn next_capture_id() -> u32 {
+    NEXT_CAPTURE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+// A host-visible buffer that the CPU may write to through a mapped pointer
+struct HostVisibleBuffer {
+    // Stored as usize so that the recorder is Send/Sync
+    mapped_ptr: Option<usize>,
+    size: usize,
+    // The contents of the buffer as of the last WriteBuffer event. None until the first snapshot
+    shadow: Option<Vec<u8>>,
+}
+
+impl HostVisibleBuffer {
+    // Returns the smallest range of bytes that changed since the last snapshot
+    fn snapshot(&mut self) -> Option<(u64, Vec<u8>)> {
+        let mapped_ptr = self.mapped_ptr?;
+        let data = unsafe { std::slice::from_raw_parts(mapped_ptr as *const u8, self.size) };
+
+        let shadow = match &mut self.shadow {
+            Some(shadow) => shadow,
+            None => {
+                self.shadow = Some(data.to_vec());
+                return Some((0, data.to_vec()));
+            }
+        };
+
+        let first = data.iter().zip(shadow.iter()).position(|(a, b)| a != b)?;
+        let last = data.iter().zip(shadow.iter()).rposition(|(a, b)| a != b)?;
+        shadow[first..=last].copy_from_slice(&data[first..=last]);
+        Some((first as u64, data[first..=last].to_vec()))
+    }
+}
+
+// Compacting is skipped until there are at least this many events
+const MIN_EVENT_COUNT_TO_COMPACT: usize = 1024;
+
+#[derive(Default)]
+pub(crate) struct RafxCaptureRecorderState {
+    events: Vec<RafxCaptureEvent>,
+    known_textures: FnvHashSet<u32>,
+    host_visible_buffers: FnvHashMap<u32, HostVisibleBuffer>,
+    // True between begin_capture() and end_capture()
+    is_capturing: bool,
+    // Number of events after the last compaction
+    compacted_event_count: usize,
+}
+
+impl RafxCaptureRecorderState {
+    // Textures that weren't created through the device context (like swapchain images) are
+    // recorded when they are first referenced
+    pub(crate) fn texture_id(
+        &mut self,
+        texture: &RafxTexture,
+    ) -> u32 {
+        let texture_id = texture.texture_id();
+        if self.known_textures.insert(texture_id) {
+            self.push_event(RafxCaptureEvent::CreateTexture {
+                texture_id,
+                texture_def: texture.texture_def().clone(),
+            });
+        }
+
+        texture_id
+    }
+
+    pub(crate) fn texture_view_id(
+        &mut self,
+        texture_view: &RafxTextureView,
+    ) -> u32 {
+        self.texture_id(texture_view.texture());
+        texture_view.capture_id()
+    }
+
+    pub(crate) fn inheritance_info(
+        &mut self,
+        inheritance_info: &RafxCommandBufferInheritanceInfo,
+    ) -> RafxCaptureInheritanceInfo {
+        RafxCaptureInheritanceInfo {
+            color_formats: inheritance_info.color_formats.to_vec(),
+            depth_stencil_format: inheritance_info.depth_stencil_format,
+            sample_count: inheritance_info.sample_count,
+            primary_command_buffer_id: inheritance_info
+                .primary_command_buffer
+                .map(|x| x.capture_id()),
+        }
+    }
+
+    pub(crate) fn color_targets(
+        &mut self,
+        color_targets: &[RafxColorRenderTargetBinding],
+    ) -> Vec<RafxCaptureColorRenderTarget> {
+        color_targets
+            .iter()
+            .map(|x| RafxCaptureColorRenderTarget {
+                texture_id: self.texture_id(x.texture),
+                load_op: x.load_op,
+                store_op: x.store_op,
+                mip_slice: x.mip_slice,
+                array_slice: x.array_slice,
+                texture_view_id: x.texture_view.map(|x| self.texture_view_id(x)),
+                clear_value: x.clear_value,
+                resolve_texture_id: x.resolve_target.map(|x| self.texture_id(x)),
+                resolve_store_op: x.resolve_store_op,
+                resolve_mip_slice: x.resolve_mip_slice,
+                resolve_array_slice: x.resolve_array_slice,
+            })
+            .collect()
+    }
+
+    pub(crate) fn depth_target(
+        &mut self,
+        depth_target: Option<&RafxDepthStencilRenderTargetBinding>,
+    ) -> Option<RafxCaptureDepthStencilRenderTarget> {
+        depth_target.map(|x| RafxCaptureDepthStencilRenderTarget {
+            texture_id: self.texture_id(x.texture),
+            depth_load_op: x.depth_load_op,
+            stencil_load_op: x.stencil_load_op,
+            depth_store_op: x.depth_store_op,
+            stencil_store_op: x.stencil_store_op,
+            mip_slice: x.mip_slice,
+            array_slice: x.array_slice,
+            texture_view_id: x.texture_view.map(|x| self.texture_view_id(x)),
+            clear_value: x.clear_value,
+        })
+    }
+
+    pub(crate) fn barriers(
+        &mut self,
+        buffer_barriers: &[RafxBufferBarrier],
+        texture_barriers: &[RafxTextureBarrier],
+    ) -> RafxCaptureCommand {
+        RafxCaptureCommand::ResourceBarrier {
+            buffer_barriers: buffer_barriers
+                .iter()
+                .map(|x| RafxCaptureBufferBarrier {
+                    buffer_id: x.buffer.buffer_id(),
+                    src_state: x.src_state,
+                    dst_state: x.dst_state,
+                    queue_transition: x.queue_transition,
+                })
+                .collect(),
+            texture_barriers: texture_barriers
+                .iter()
+                .map(|x| RafxCaptureTextureBarrier {
+                    texture_id: self.texture_id(x.texture),
+                    src_state: x.src_state,
+                    dst_state: x.dst_state,
+                    queue_transition: x.queue_transition,
+                    array_slice: x.array_slice,
+                    mip_slice: x.mip_slice,
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn descriptor_update(
+        &mut self,
+        update: &RafxDescriptorUpdate,
+    ) -> RafxCaptureDescriptorUpdate {
+        let elements = &update.elements;
+        RafxCaptureDescriptorUpdate {
+            array_index: update.array_index,
+            descriptor_key: match &update.descriptor_key {
+                RafxDescriptorKey::Undefined => RafxCaptureDescriptorKey::Undefined,
+                RafxDescriptorKey::Name(name) => RafxCaptureDescriptorKey::Name(name.to_string()),
+                RafxDescriptorKey::Binding(binding) => RafxCaptureDescriptorKey::Binding(*binding),
+                RafxDescriptorKey::DescriptorIndex(index) => {
+                    RafxCaptureDescriptorKey::DescriptorIndex(*index)
+                }
+            },
+            texture_ids: elements
+                .textures
+                .map(|x| x.iter().map(|x| self.texture_id(x)).collect()),
+            texture_view_ids: elements
+                .texture_views
+                .map(|x| x.iter().map(|x| self.texture_view_id(x)).collect()),
+            sampler_ids: elements
+                .samplers
+                .map(|x| x.iter().map(|x| x.capture_id()).collect()),
+            buffer_ids: elements
+                .buffers
+                .map(|x| x.iter().map(|x| x.buffer_id()).collect()),
+            buffer_offset_sizes: elements.buffer_offset_sizes.map(|x| x.to_vec()),
+            dst_element_offset: update.dst_element_offset,
+            texture_bind_type: update.texture_bind_type,
+        }
+    }
+
+    fn snapshot_buffer(
+        &mut self,
+        buffer_id: u32,
+    ) {
+        let snapshot = self
+            .host_visible_buffers
+            .get_mut(&buffer_id)
+            .and_then(|x| x.snapshot());
+        if let Some((offset, data)) = snapshot {
+            self.push_event(RafxCaptureEvent::WriteBuffer {
+                buffer_id,
+                offset,
+                data,
+            });
+        }
+    }
+
+    // Buffer ids are sorted so that captures of the same frames are identical
+    fn snapshot_all_buffers(&mut self) {
+        let mut buffer_ids: Vec<_> = self.host_visible_buffers.keys().copied().collect();
+        buffer_ids.sort_unstable();
+        for buffer_id in buffer_ids {
+            self.snapshot_buffer(buffer_id);
+        }
+    }
+
+    fn push_event(
+        &mut self,
+        event: RafxCaptureEvent,
+    ) {
+        if self.is_capturing {
+            self.events.push(event);
+            return;
+        }
+
+        // Work done outside the capture window is not replayed
+        match event {
+            RafxCaptureEvent::Command { .. }
+            | RafxCaptureEvent::Submit { .. }
+            | RafxCaptureEvent::Present { .. } => return,
+            _ => self.events.push(event),
+        }
+
+        // Compacting is linear in the number of events, so only do it when the count has doubled
+        if self.events.len() >= MIN_EVENT_COUNT_TO_COMPACT
+            && self.events.len() >= self.compacted_event_count * 2
+        {
+            self.compact();
+        }
+    }
+
+    fn compact(&mut self) {
+        self.events = compact_events(&self.events);
+        self.compacted_event_count = self.events.len();
+    }
+}
+
+// Identifies the descriptors written by an update. A later update with the same key overwrites all
+// of them.
+type DescriptorUpdateKey = (u32, u32, RafxCaptureDescriptorKey, u32, usize);
+
+fn descriptor_update_key(
+    descriptor_set_array_id: u32,
+    update: &RafxCaptureDescriptorUpdate,
+) -> DescriptorUpdateKey {
+    let element_count = update
+        .texture_ids
+        .as_ref()
+        .or(update.texture_view_ids.as_ref())
+        .or(update.sampler_ids.as_ref())
+        .or(update.buffer_ids.as_ref())
+        .map(|x| x.len())
+        .unwrap_or(0);
+
+    (
+        descriptor_set_array_id,
+        update.array_index,
+        update.descriptor_key.clone(),
+        update.dst_element_offset,
+        element_count,
+    )
+}
+
+// Objects destroyed while compacting. Ids are never reused, so any descriptor update that refers
+// to one of them is stale.
+#[derive(Default)]
+struct DestroyedObjects {
+    buffers: FnvHashSet<u32>,
+    textures: FnvHashSet<u32>,
+    texture_views: FnvHashSet<u32>,
+}
+
+impl DestroyedObjects {
+    fn is_referenced_by(
+        &self,
+        update: &RafxCaptureDescriptorUpdate,
+    ) -> bool {
+        fn any_in(
+            ids: &Option<Vec<u32>>,
+            destroyed: &FnvHashSet<u32>,
+        ) -> bool {
+            ids.iter().flatten().any(|id| destroyed.contains(id))
+        }
+
+        any_in(&update.buffer_ids, &self.buffers)
+            || any_in(&update.texture_ids, &self.textures)
+            || any_in(&update.texture_view_ids, &self.texture_views)
+    }
+}
+
+// All writes to a buffer merged into one, covering the range from the lowest to the highest byte
+// that was written. Bytes in between that were never written are zero.
+struct MergedBufferWrite {
+    event_index: usize,
+    offset: u64,
+    data: Vec<u8>,
+}
+
+impl MergedBufferWrite {
+    fn merge(
+        &mut self,
+        offset: u64,
+        data: &[u8],
+    ) {
+        let begin = self.offset.min(offset);
+        let end = (self.offset + self.data.len() as u64).max(offset + data.len() as u64);
+        if begin != self.offset || end != self.offset + self.data.len() as u64 {
+            let mut merged = vec![0; (end - begin) as usize];
+            let old_begin = (self.offset - begin) as usize;
+            merged[old_begin..old_begin + self.data.len()].copy_from_slice(&self.data);
+            self.offset = begin;
+            self.data = merged;
+        }
+
+        let new_begin = (offset - self.offset) as usize;
+        self.data[new_begin..new_begin + data.len()].copy_from_slice(data);
+    }
+}
+
+// Returns the events needed to recreate the objects that are alive at the end of the given events,
+// including the contents written to their buffers and descriptor sets. Commands, submits and
+// presents are dropped, as are objects that were destroyed and anything that only refers to them.
+fn compact_events(events: &[RafxCaptureEvent]) -> Vec<RafxCaptureEvent> {
+    // Events that are removed later on are set to None
+    let mut compacted: Vec<Option<RafxCaptureEvent>> = Vec::with_capacity(events.len());
+    let mut buffer_creates = FnvHashMap::<u32, usize>::default();
+    let mut buffer_writes = FnvHashMap::<u32, MergedBufferWrite>::default();
+    let mut texture_creates = FnvHashMap::<u32, usize>::default();
+    let mut texture_view_creates = FnvHashMap::<u32, Vec<(u32, usize)>>::default();
+    let mut descriptor_updates = FnvHashMap::<DescriptorUpdateKey, usize>::default();
+    let mut queued_descriptor_updates =
+        FnvHashMap::<u32, Vec<&RafxCaptureDescriptorUpdate>>::default();
+    let mut destroyed = DestroyedObjects::default();
+
+    let mut push_descriptor_update =
+        |compacted: &mut Vec<Option<RafxCaptureEvent>>,
+         descriptor_set_array_id: u32,
+         update: &RafxCaptureDescriptorUpdate| {
+            let key = descriptor_update_key(descriptor_set_array_id, update);
+            if let Some(overwritten) = descriptor_updates.insert(key, compacted.len()) {
+                compacted[overwritten] = None;
+            }
+            compacted.push(Some(RafxCaptureEvent::UpdateDescriptorSet {
+                descriptor_set_array_id,
+                update: update.clone(),
+            }));
+        };
+
+    for event in events {
+        match event {
+            RafxCaptureEvent::Command { .. }
+            | RafxCaptureEvent::Submit { .. }
+            | RafxCaptureEvent::Present { .. } => {}
+            RafxCaptureEvent::CreateBuffer { buffer_id, .. } => {
+                buffer_creates.insert(*buffer_id, compacted.len());
+                compacted.push(Some(event.clone()));
+            }
+            RafxCaptureEvent::WriteBuffer {
+                buffer_id,
+                offset,
+                data,
+            } => {
+                // The merged write goes wherever the last write was, it is filled in at the end
+                match buffer_writes.get_mut(buffer_id) {
+                    Some(merged) => {
+                        merged.merge(*offset, data);
+                        merged.event_index = compacted.len();
+                    }
+                    None => {
+                        buffer_writes.insert(
+                            *buffer_id,
+                            MergedBufferWrite {
+                                event_index: compacted.len(),
+                                offset: *offset,
+                                data: data.clone(),
+                            },
+                        );
+                    }
+                }
+                compacted.push(None);
+            }
+            RafxCaptureEvent::DestroyBuffer { buffer_id } => {
+                buffer_writes.remove(buffer_id);
+                match buffer_creates.remove(buffer_id) {
+                    Some(create_index) => {
+                        compacted[create_index] = None;
+                        destroyed.buffers.insert(*buffer_id);
+                    }
+                    None => compacted.push(Some(event.clone())),
+                }
+            }
+            RafxCaptureEvent::CreateTexture { texture_id, .. } => {
+                texture_creates.insert(*texture_id, compacted.len());
+                compacted.push(Some(event.clone()));
+            }
+            RafxCaptureEvent::DestroyTexture { texture_id } => {
+                // Views keep their texture alive, so any views of it have already been dropped
+                for (texture_view_id, create_index) in
+                    texture_view_creates.remove(texture_id).unwrap_or_default()
+                {
+                    compacted[create_index] = None;
+                    destroyed.texture_views.insert(texture_view_id);
+                }
+
+                match texture_creates.remove(texture_id) {
+                    Some(create_index) => {
+                        compacted[create_index] = None;
+                        destroyed.textures.insert(*texture_id);
+                    }
+                    None => compacted.push(Some(event.clone())),
+                }
+            }
+            RafxCaptureEvent::CreateTextureView {
+                texture_view_id,
+                texture_id,
+                ..
+            } => {
+                texture_view_creates
+                    .entry(*texture_id)
+                    .or_default()
+                    .push((*texture_view_id, compacted.len()));
+                compacted.push(Some(event.clone()));
+            }
+            RafxCaptureEvent::UpdateDescriptorSet {
+                descriptor_set_array_id,
+                update,
+            } => {
+                push_descriptor_update(&mut compacted, *descriptor_set_array_id, update);
+            }
+            RafxCaptureEvent::QueueDescriptorSetUpdate {
+                descriptor_set_array_id,
+                update,
+            } => {
+                queued_descriptor_updates
+                    .entry(*descriptor_set_array_id)
+                    .or_default()
+                    .push(update);
+            }
+            RafxCaptureEvent::FlushDescriptorSetUpdates {
+                descriptor_set_array_id,
+            } => {
+                // Queued updates are applied when they are flushed
+                for update in queued_descriptor_updates
+                    .remove(descriptor_set_array_id)
+                    .unwrap_or_default()
+                {
+                    push_descriptor_update(&mut compacted, *descriptor_set_array_id, update);
+                }
+            }
+            _ => compacted.push(Some(event.clone())),
+        }
+    }
+
+    for (buffer_id, merged) in buffer_writes {
+        compacted[merged.event_index] = Some(RafxCaptureEvent::WriteBuffer {
+            buffer_id,
+            offset: merged.offset,
+            data: merged.data,
+        });
+    }
+
+    // Sorted so that compacting the same events always gives the same result
+    let mut queued_descriptor_updates: Vec<_> = queued_descriptor_updates.into_iter().collect();
+    queued_descriptor_updates
+        .sort_unstable_by_key(|(descriptor_set_array_id, _)| *descriptor_set_array_id);
+    for (descriptor_set_array_id, updates) in queued_descriptor_updates {
+        for update in updates {
+            compacted.push(Some(RafxCaptureEvent::QueueDescriptorSetUpdate {
+                descriptor_set_array_id,
+                update: update.clone(),
+            }));
+        }
+    }
+
+    compacted
+        .into_iter()
+        .flatten()
+        .filter(|event| match event {
+            RafxCaptureEvent::UpdateDescriptorSet { update, .. }
+            | RafxCaptureEvent::QueueDescriptorSetUpdate { update, .. } => {
+                !destroyed.is_referenced_by(update)
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+struct RafxCaptureRecorderInner {
+    enabled: AtomicBool,
+    state: Mutex<RafxCaptureRecorderState>,
+}
+
+#[derive(Clone)]
+pub(crate) struct RafxCaptureRecorder {
+    inner: Arc<RafxCaptureRecorderInner>,
+}
+
+impl Default for RafxCaptureRecorder {
+    fn default() -> Self {
+        RafxCaptureRecorder {
+            inner: Arc::new(RafxCaptureRecorderInner {
+                enabled: AtomicBool::new(false),
+                state: Default::default(),
+            }),
+        }
+    }
+}
+
+impl RafxCaptureRecorder {
+    pub(crate) fn set_enabled(
+        &self,
+        enabled: bool,
+    ) {
+        self.inner.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    fn verify_enabled(&self) -> RafxResult<()> {
+        if !self.is_enabled() {
+            Err("Capturing is not enabled. Set RafxApiDef::enable_capture when creating the API")?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn begin_capture(&self) -> RafxResult<()> {
+        self.verify_enabled()?;
+
+        let mut state = self.inner.state.lock().unwrap();
+        if state.is_capturing {
+            Err("A capture is already in progress")?;
+        }
+
+        state.compact();
+        state.snapshot_all_buffers();
+        state.is_capturing = true;
+        Ok(())
+    }
+
+    pub(crate) fn end_capture(&self) -> RafxResult<RafxCapture> {
+        self.verify_enabled()?;
+
+        let mut state = self.inner.state.lock().unwrap();
+        if !state.is_capturing {
+            Err("No capture is in progress, call begin_capture() first")?;
+        }
+
+        state.snapshot_all_buffers();
+        state.is_capturing = false;
+        let events = std::mem::take(&mut state.events);
+        state.events = compact_events(&events);
+        state.compacted_event_count = state.events.len();
+        Ok(RafxCapture { events })
+    }
+
+    pub(crate) fn capture(&self) -> RafxResult<RafxCapture> {
+        self.verify_enabled()?;
+
+        let mut state = self.inner.state.lock().unwrap();
+        state.snapshot_all_buffers();
+        if !state.is_capturing {
+            state.compact();
+        }
+
+        Ok(RafxCapture {
+            events: state.events.clone(),
+        })
+    }
+
+    pub(crate) fn record<F: FnOnce(&mut RafxCaptureRecorderState) -> RafxCaptureEvent>(
+        &self,
+        f: F,
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            let event = f(&mut state);
+            state.push_event(event);
+        }
+    }
+
+    pub(crate) fn record_command<F: FnOnce(&mut RafxCaptureRecorderState) -> RafxCaptureCommand>(
+        &self,
+        command_buffer_id: u32,
+        f: F,
+    ) {
+        self.record(|state| RafxCaptureEvent::Command {
+            command_buffer_id,
+            command: f(state),
+        });
+    }
+
+    pub(crate) fn record_submit<F: FnOnce(&mut RafxCaptureRecorderState) -> RafxCaptureEvent>(
+        &self,
+        f: F,
+    ) {
+        // Anything written to mapped buffers may be read by the submitted work
+        self.record(|state| {
+            state.snapshot_all_buffers();
+            f(state)
+        });
+    }
+
+    pub(crate) fn on_texture_created(
+        &self,
+        texture: &RafxTexture,
+    ) {
+        self.record(|state| {
+            state.known_textures.insert(texture.texture_id());
+            RafxCaptureEvent::CreateTexture {
+                texture_id: texture.texture_id(),
+                texture_def: texture.texture_def().clone(),
+            }
+        });
+    }
+
+    pub(crate) fn on_texture_destroyed(
+        &self,
+        texture_id: u32,
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.known_textures.remove(&texture_id) {
+                state.push_event(RafxCaptureEvent::DestroyTexture { texture_id });
+            }
+        }
+    }
+
+    pub(crate) fn on_buffer_created(
+        &self,
+        buffer: &RafxBuffer,
+    ) {
+        self.record(|state| {
+            let buffer_def = buffer.buffer_def();
+            if buffer_def.memory_usage == RafxMemoryUsage::CpuOnly
+                || buffer_def.memory_usage == RafxMemoryUsage::CpuToGpu
+            {
+                state.host_visible_buffers.insert(
+                    buffer.buffer_id(),
+                    HostVisibleBuffer {
+                        mapped_ptr: buffer.mapped_memory().map(|x| x as usize),
+                        size: buffer_def.size as usize,
+                        shadow: None,
+                    },
+                );
+            }
+
+            RafxCaptureEvent::CreateBuffer {
+                buffer_id: buffer.buffer_id(),
+                buffer_def: buffer_def.clone(),
+            }
+        });
+    }
+
+    pub(crate) fn on_buffer_destroyed(
+        &self,
+        buffer_id: u32,
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            // Writes to the buffer may have been read by work that was submitted after they
+            // happened but the buffer is gone now, so nothing needs to be snapshotted
+            state.host_visible_buffers.remove(&buffer_id);
+            state.push_event(RafxCaptureEvent::DestroyBuffer { buffer_id });
+        }
+    }
+
+    pub(crate) fn on_buffer_mapped(
+        &self,
+        buffer: &RafxBuffer,
+        mapped_ptr: *mut u8,
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            if let Some(host_visible_buffer) =
+                state.host_visible_buffers.get_mut(&buffer.buffer_id())
+            {
+                host_visible_buffer.mapped_ptr = Some(mapped_ptr as usize);
+            }
+        }
+    }
+
+    // Called before the buffer is unmapped
+    pub(crate) fn on_buffer_unmapping(
+        &self,
+        buffer: &RafxBuffer,
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            state.snapshot_buffer(buffer.buffer_id());
+        }
+    }
+
+    // Called after the buffer is unmapped. It may still be mapped if it's always mapped
+    pub(crate) fn on_buffer_unmapped(
+        &self,
+        buffer: &RafxBuffer,
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            if let Some(host_visible_buffer) =
+                state.host_visible_buffers.get_mut(&buffer.buffer_id())
+            {
+                host_visible_buffer.mapped_ptr = buffer.mapped_memory().map(|x| x as usize);
+            }
+        }
+    }
+
+    pub(crate) fn on_buffer_written(
+        &self,
+        buffer: &RafxBuffer,
+        offset: u64,
+        data: &[u8],
+    ) {
+        if self.is_enabled() {
+            let mut state = self.inner.state.lock().unwrap();
+            let buffer_id = buffer.buffer_id();
+            let is_mapped = state
+                .host_visible_buffers
+                .get(&buffer_id)
+                .map(|x| x.mapped_ptr.is_some())
+                .unwrap_or(false);
+            if is_mapped {
+                // The shadow copy must stay in sync with the buffer
+                state.snapshot_buffer(buffer_id);
+            } else {
+                state.push_event(RafxCaptureEvent::WriteBuffer {
+                    buffer_id,
+                    offset,
+                    data: data.to_vec(),
+                });
+            }
+        }
+    }
+}
+
+// Only the shader code for the current backend is available
+pub(crate) fn shader_package_from_module_def(
+    _shader_module_def: &RafxShaderModuleDef
+) -> RafxShaderPackage {
+    #[allow(unused_mut)]
+    let mut shader_package = RafxShaderPackage::default();
+
+    #[cfg(feature = "rafx-vulkan")]
+    if let Some(vk) = _shader_module_def.vk {
+        use crate::{RafxShaderModuleDefVulkan, RafxShaderPackageVulkan};
+        let bytes = match vk {
+            RafxShaderModuleDefVulkan::VkSpvBytes(bytes) => bytes.to_vec(),
+            RafxShaderModuleDefVulkan::VkSpvPrepared(words) => words
+                .iter()
+                .flat_map(|x| x.to_le_bytes().to_vec())
+                .collect(),
+        };
+        shader_package.vk = Some(RafxShaderPackageVulkan::SpvBytes(bytes));
+    }
+
+    #[cfg(feature = "rafx-metal")]
+    if let Some(metal) = _shader_module_def.metal {
+        use crate::{RafxShaderModuleDefMetal, RafxShaderPackageMetal};
+        shader_package.metal = Some(match metal {
+            RafxShaderModuleDefMetal::MetalSrc(src) => RafxShaderPackageMetal::Src(src.to_string()),
+            RafxShaderModuleDefMetal::MetalLibBytes(bytes) => {
+                RafxShaderPackageMetal::LibBytes(bytes.to_vec())
+            }
+        });
+    }
+
+    #[cfg(feature = "rafx-gles3")]
+    if let Some(gles3) = _shader_module_def.gles3 {
+        use crate::{RafxShaderModuleDefGles3, RafxShaderPackageGles3};
+        shader_package.gles3 = Some(match gles3 {
+            RafxShaderModuleDefGles3::GlSrc(src) => RafxShaderPackageGles3::Src(src.to_string()),
+        });
+    }
+
+    shader_package
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod tests {
+    use super::*;
+    use crate::empty::test_util::EmptyTestContext;
+    use crate::*;
+
+    #[test]
+    fn capture_drops_events_outside_capture() {
+        let test_context = EmptyTestContext::with_api_def(&RafxApiDef {
+            enable_capture: true,
+            ..Default::default()
+        });
+        let device_context = &test_context.device_context;
+        let queue = &test_context.queue;
+        let command_buffer = &test_context.command_buffer;
+        let persistent_buffer = device_context
+            .create_buffer(&RafxBufferDef::for_staging_buffer(
+                16,
+                RafxResourceType::BUFFER,
+            ))
+            .unwrap();
+
+        // Every frame creates a buffer, writes to both buffers, and submits a copy. None of this is
+        // needed after the frame except the last write to the persistent buffer.
+        let render_frame = |frame_index: u8| {
+            let frame_buffer = device_context
+                .create_buffer(&RafxBufferDef::for_staging_buffer(
+                    16,
+                    RafxResourceType::BUFFER,
+                ))
+                .unwrap();
+            frame_buffer
+                .copy_to_host_visible_buffer(&[frame_index; 16])
+                .unwrap();
+            persistent_buffer
+                .copy_to_host_visible_buffer_with_offset(&[frame_index; 8], 8)
+                .unwrap();
+
+            command_buffer.begin().unwrap();
+            command_buffer
+                .cmd_copy_buffer_to_buffer(&frame_buffer, &persistent_buffer, 0, 0, 8)
+                .unwrap();
+            command_buffer.end().unwrap();
+            test_context.submit().unwrap();
+            queue.wait_for_queue_idle().unwrap();
+        };
+
+        persistent_buffer
+            .copy_to_host_visible_buffer(&[255u8; 16])
+            .unwrap();
+        for frame_index in 0..1000 {
+            render_frame((frame_index % 100) as u8);
+        }
+
+        // Only the queue, command buffer, persistent buffer and its merged writes remain, along with
+        // the last frame's buffer which the command buffer still refers to
+        let capture = device_context.capture().unwrap();
+        assert_eq!(capture.frame_count(), 0);
+        assert_eq!(capture.events.len(), 6);
+        let persistent_buffer_writes: Vec<_> = capture
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                RafxCaptureEvent::WriteBuffer {
+                    buffer_id,
+                    offset,
+                    data,
+                } if *buffer_id == persistent_buffer.buffer_id() => Some((*offset, data)),
+                _ => None,
+            })
+            .collect();
+        // The first half was written by the last frame's copy, which is picked up when the buffer
+        // is mapped to write the second half
+        assert_eq!(persistent_buffer_writes, [(0, &vec![99u8; 16])]);
+
+        device_context.begin_capture().unwrap();
+        render_frame(100);
+        let capture = device_context.end_capture().unwrap();
+        assert!(capture
+            .events
+            .iter()
+            .any(|x| matches!(x, RafxCaptureEvent::Submit { .. })));
+
+        let replay_api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let mut replay = RafxCaptureReplay::new(&replay_api.device_context());
+        replay.replay(&capture).unwrap();
+
+        let replayed_buffer = replay.buffer(persistent_buffer.buffer_id()).unwrap();
+        let replayed_ptr = replayed_buffer.map_buffer().unwrap();
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(replayed_ptr, 16) },
+            &[100u8; 16][..]
+        );
+        replayed_buffer.unmap_buffer().unwrap();
+    }
+}
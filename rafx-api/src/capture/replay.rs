@@ -0,0 +1,842 @@
+use super::*;
+use crate::{
+    RafxBindlessArray, RafxBindlessArrayKey, RafxBuffer, RafxBufferBarrier,
+    RafxColorRenderTargetBinding, RafxCommandBuffer, RafxCommandBufferDef,
+    RafxCommandBufferInheritanceInfo, RafxCommandPool, RafxCommandPoolDef, RafxComputePipelineDef,
+    RafxDepthStencilRenderTargetBinding, RafxDescriptorElements, RafxDescriptorKey,
+    RafxDescriptorSetArray, RafxDescriptorSetArrayDef, RafxDescriptorUpdate, RafxDeviceContext,
+    RafxGraphicsPipelineDef, RafxImmutableSamplerKey, RafxImmutableSamplers,
+    RafxIndexBufferBinding, RafxPipeline, RafxQueue, RafxRootSignature, RafxRootSignatureDef,
+    RafxSampler, RafxShader, RafxShaderModule, RafxShaderStageDef, RafxTexture, RafxTextureBarrier,
+    RafxTextureView, RafxVertexBufferBinding,
+};
+use fnv::FnvHashMap;
+
+// Each command buffer gets its own pool so that it can be reset before it is recorded again
+struct ReplayCommandBuffer {
+    command_pool: RafxCommandPool,
+    command_buffer: RafxCommandBuffer,
+}
+
+fn lookup<'a, T>(
+    objects: &'a FnvHashMap<u32, T>,
+    kind: &str,
+    id: u32,
+) -> RafxResult<&'a T> {
+    objects
+        .get(&id)
+        .ok_or_else(|| format!("The capture refers to {} {} which does not exist", kind, id).into())
+}
+
+/// Replays a `RafxCapture` on a device. Objects are created as they are encountered and are kept
+/// until the capture destroys them (or the replay is dropped).
+///
+/// Fences and semaphores are not captured, so replay waits for the queue to become idle after
+/// every submit. Presents only advance `frame_count()`, swapchain images are replayed as offscreen
+/// textures.
+pub struct RafxCaptureReplay {
+    device_context: RafxDeviceContext,
+    queues: FnvHashMap<u32, RafxQueue>,
+    command_buffers: FnvHashMap<u32, ReplayCommandBuffer>,
+    buffers: FnvHashMap<u32, RafxBuffer>,
+    textures: FnvHashMap<u32, RafxTexture>,
+    texture_views: FnvHashMap<u32, RafxTextureView>,
+    samplers: FnvHashMap<u32, RafxSampler>,
+    shader_modules: FnvHashMap<u32, RafxShaderModule>,
+    shaders: FnvHashMap<u32, RafxShader>,
+    root_signatures: FnvHashMap<u32, RafxRootSignature>,
+    pipelines: FnvHashMap<u32, RafxPipeline>,
+    descriptor_set_arrays: FnvHashMap<u32, RafxDescriptorSetArray>,
+    frame_count: usize,
+}
+
+impl RafxCaptureReplay {
+    pub fn new(device_context: &RafxDeviceContext) -> Self {
+        RafxCaptureReplay {
+            device_context: device_context.clone(),
+            queues: Default::default(),
+            command_buffers: Default::default(),
+            buffers: Default::default(),
+            textures: Default::default(),
+            texture_views: Default::default(),
+            samplers: Default::default(),
+            shader_modules: Default::default(),
+            shaders: Default::default(),
+            root_signatures: Default::default(),
+            pipelines: Default::default(),
+            descriptor_set_arrays: Default::default(),
+            frame_count: 0,
+        }
+    }
+
+    /// Number of presents replayed so far
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Get a texture created by the capture, for example to read back the result of a frame
+    pub fn texture(
+        &self,
+        texture_id: u32,
+    ) -> Option<&RafxTexture> {
+        self.textures.get(&texture_id)
+    }
+
+    /// Get a buffer created by the capture
+    pub fn buffer(
+        &self,
+        buffer_id: u32,
+    ) -> Option<&RafxBuffer> {
+        self.buffers.get(&buffer_id)
+    }
+
+    /// Replay all events in the capture
+    pub fn replay(
+        &mut self,
+        capture: &RafxCapture,
+    ) -> RafxResult<()> {
+        for event in &capture.events {
+            self.replay_event(event)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replay a single event. Events must be replayed in the order they were captured.
+    pub fn replay_event(
+        &mut self,
+        event: &RafxCaptureEvent,
+    ) -> RafxResult<()> {
+        match event {
+            RafxCaptureEvent::CreateQueue {
+                queue_id,
+                queue_type,
+            } => {
+                let queue = self.device_context.create_queue(*queue_type)?;
+                self.queues.insert(*queue_id, queue);
+            }
+            RafxCaptureEvent::CreateCommandBuffer {
+                command_buffer_id,
+                queue_type,
+                is_secondary,
+            } => {
+                let queue = self.device_context.create_queue(*queue_type)?;
+                let mut command_pool =
+                    queue.create_command_pool(&RafxCommandPoolDef { transient: true })?;
+                let command_buffer = command_pool.create_command_buffer(&RafxCommandBufferDef {
+                    is_secondary: *is_secondary,
+                })?;
+                self.command_buffers.insert(
+                    *command_buffer_id,
+                    ReplayCommandBuffer {
+                        command_pool,
+                        command_buffer,
+                    },
+                );
+            }
+            RafxCaptureEvent::CreateBuffer {
+                buffer_id,
+                buffer_def,
+            } => {
+                let buffer = self.device_context.create_buffer(buffer_def)?;
+                self.buffers.insert(*buffer_id, buffer);
+            }
+            RafxCaptureEvent::WriteBuffer {
+                buffer_id,
+                offset,
+                data,
+            } => {
+                lookup(&self.buffers, "buffer", *buffer_id)?
+                    .copy_to_host_visible_buffer_with_offset(data, *offset)?;
+            }
+            RafxCaptureEvent::DestroyBuffer { buffer_id } => {
+                self.buffers.remove(buffer_id);
+            }
+            RafxCaptureEvent::CreateTexture {
+                texture_id,
+                texture_def,
+            } => {
+                let texture = self.device_context.create_texture(texture_def)?;
+                self.textures.insert(*texture_id, texture);
+            }
+            RafxCaptureEvent::DestroyTexture { texture_id } => {
+                self.textures.remove(texture_id);
+            }
+            RafxCaptureEvent::CreateTextureView {
+                texture_view_id,
+                texture_id,
+                texture_view_def,
+            } => {
+                let texture = lookup(&self.textures, "texture", *texture_id)?;
+                let texture_view = self
+                    .device_context
+                    .create_texture_view(texture, texture_view_def)?;
+                self.texture_views.insert(*texture_view_id, texture_view);
+            }
+            RafxCaptureEvent::CreateSampler {
+                sampler_id,
+                sampler_def,
+            } => {
+                let sampler = self.device_context.create_sampler(sampler_def)?;
+                self.samplers.insert(*sampler_id, sampler);
+            }
+            RafxCaptureEvent::CreateShaderModule {
+                shader_module_id,
+                shader_package,
+            } => {
+                let shader_module = self.create_shader_module(shader_package)?;
+                self.shader_modules.insert(*shader_module_id, shader_module);
+            }
+            RafxCaptureEvent::CreateShader { shader_id, stages } => {
+                let mut stage_defs = Vec::with_capacity(stages.len());
+                for stage in stages {
+                    stage_defs.push(RafxShaderStageDef {
+                        shader_module: lookup(
+                            &self.shader_modules,
+                            "shader module",
+                            stage.shader_module_id,
+                        )?
+                        .clone(),
+                        reflection: stage.reflection.clone(),
+                        specialization_constants: stage.specialization_constants.clone(),
+                    });
+                }
+
+                let shader = self.device_context.create_shader(stage_defs)?;
+                self.shaders.insert(*shader_id, shader);
+            }
+            RafxCaptureEvent::CreateRootSignature {
+                root_signature_id,
+                shader_ids,
+                immutable_samplers,
+                bindless_arrays,
+            } => {
+                let root_signature =
+                    self.create_root_signature(shader_ids, immutable_samplers, bindless_arrays)?;
+                self.root_signatures
+                    .insert(*root_signature_id, root_signature);
+            }
+            RafxCaptureEvent::CreateGraphicsPipeline {
+                pipeline_id,
+                pipeline_def,
+            } => {
+                let pipeline =
+                    self.device_context
+                        .create_graphics_pipeline(&RafxGraphicsPipelineDef {
+                            shader: lookup(&self.shaders, "shader", pipeline_def.shader_id)?,
+                            root_signature: lookup(
+                                &self.root_signatures,
+                                "root signature",
+                                pipeline_def.root_signature_id,
+                            )?,
+                            vertex_layout: &pipeline_def.vertex_layout,
+                            blend_state: &pipeline_def.blend_state,
+                            depth_state: &pipeline_def.depth_state,
+                            rasterizer_state: &pipeline_def.rasterizer_state,
+                            primitive_topology: pipeline_def.primitive_topology,
+                            color_formats: &pipeline_def.color_formats,
+                            depth_stencil_format: pipeline_def.depth_stencil_format,
+                            sample_count: pipeline_def.sample_count,
+                        })?;
+                self.pipelines.insert(*pipeline_id, pipeline);
+            }
+            RafxCaptureEvent::CreateComputePipeline {
+                pipeline_id,
+                shader_id,
+                root_signature_id,
+            } => {
+                let pipeline =
+                    self.device_context
+                        .create_compute_pipeline(&RafxComputePipelineDef {
+                            shader: lookup(&self.shaders, "shader", *shader_id)?,
+                            root_signature: lookup(
+                                &self.root_signatures,
+                                "root signature",
+                                *root_signature_id,
+                            )?,
+                        })?;
+                self.pipelines.insert(*pipeline_id, pipeline);
+            }
+            RafxCaptureEvent::CreateDescriptorSetArray {
+                descriptor_set_array_id,
+                root_signature_id,
+                set_index,
+                array_length,
+            } => {
+                let descriptor_set_array = self.device_context.create_descriptor_set_array(
+                    &RafxDescriptorSetArrayDef {
+                        root_signature: lookup(
+                            &self.root_signatures,
+                            "root signature",
+                            *root_signature_id,
+                        )?,
+                        set_index: *set_index,
+                        array_length: *array_length,
+                    },
+                )?;
+                self.descriptor_set_arrays
+                    .insert(*descriptor_set_array_id, descriptor_set_array);
+            }
+            RafxCaptureEvent::UpdateDescriptorSet {
+                descriptor_set_array_id,
+                update,
+            } => self.update_descriptor_set(*descriptor_set_array_id, update, false)?,
+            RafxCaptureEvent::QueueDescriptorSetUpdate {
+                descriptor_set_array_id,
+                update,
+            } => self.update_descriptor_set(*descriptor_set_array_id, update, true)?,
+            RafxCaptureEvent::FlushDescriptorSetUpdates {
+                descriptor_set_array_id,
+            } => {
+                self.descriptor_set_arrays
+                    .get_mut(descriptor_set_array_id)
+                    .ok_or_else(|| {
+                        format!(
+                            "The capture refers to descriptor set array {} which does not exist",
+                            descriptor_set_array_id
+                        )
+                    })?
+                    .flush_descriptor_set_updates()?;
+            }
+            RafxCaptureEvent::Command {
+                command_buffer_id,
+                command,
+            } => self.replay_command(*command_buffer_id, command)?,
+            RafxCaptureEvent::Submit {
+                queue_id,
+                command_buffer_ids,
+            } => {
+                let queue = lookup(&self.queues, "queue", *queue_id)?;
+                let mut command_buffers = Vec::with_capacity(command_buffer_ids.len());
+                for command_buffer_id in command_buffer_ids {
+                    command_buffers.push(
+                        &lookup(&self.command_buffers, "command buffer", *command_buffer_id)?
+                            .command_buffer,
+                    );
+                }
+
+                queue.submit(&command_buffers, &[], &[], None)?;
+                queue.wait_for_queue_idle()?;
+            }
+            RafxCaptureEvent::Present { .. } => {
+                self.frame_count += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_shader_module(
+        &self,
+        shader_package: &RafxShaderPackage,
+    ) -> RafxResult<RafxShaderModule> {
+        #[cfg(feature = "rafx-vulkan")]
+        if self.device_context.vk_device_context().is_some() && shader_package.vk.is_none() {
+            Err("The capture's shader module has no vulkan shader code")?;
+        }
+
+        #[cfg(feature = "rafx-metal")]
+        if self.device_context.metal_device_context().is_some() && shader_package.metal.is_none() {
+            Err("The capture's shader module has no metal shader code")?;
+        }
+
+        self.device_context
+            .create_shader_module(shader_package.module_def())
+    }
+
+    fn create_root_signature(
+        &self,
+        shader_ids: &[u32],
+        immutable_samplers: &[RafxCaptureImmutableSamplers],
+        bindless_arrays: &[RafxCaptureBindlessArray],
+    ) -> RafxResult<RafxRootSignature> {
+        let mut shaders = Vec::with_capacity(shader_ids.len());
+        for shader_id in shader_ids {
+            shaders.push(lookup(&self.shaders, "shader", *shader_id)?.clone());
+        }
+
+        let mut samplers = Vec::with_capacity(immutable_samplers.len());
+        for immutable_sampler in immutable_samplers {
+            let mut immutable_sampler_samplers =
+                Vec::with_capacity(immutable_sampler.sampler_ids.len());
+            for sampler_id in &immutable_sampler.sampler_ids {
+                immutable_sampler_samplers
+                    .push(lookup(&self.samplers, "sampler", *sampler_id)?.clone());
+            }
+            samplers.push(immutable_sampler_samplers);
+        }
+
+        let immutable_samplers: Vec<_> = immutable_samplers
+            .iter()
+            .zip(&samplers)
+            .map(|(immutable_sampler, samplers)| RafxImmutableSamplers {
+                key: match &immutable_sampler.key {
+                    RafxCaptureBindingKey::Name(name) => RafxImmutableSamplerKey::Name(name),
+                    RafxCaptureBindingKey::Binding(set_index, binding) => {
+                        RafxImmutableSamplerKey::Binding(*set_index, *binding)
+                    }
+                },
+                samplers,
+            })
+            .collect();
+
+        let bindless_arrays: Vec<_> = bindless_arrays
+            .iter()
+            .map(|bindless_array| RafxBindlessArray {
+                key: match &bindless_array.key {
+                    RafxCaptureBindingKey::Name(name) => RafxBindlessArrayKey::Name(name),
+                    RafxCaptureBindingKey::Binding(set_index, binding) => {
+                        RafxBindlessArrayKey::Binding(*set_index, *binding)
+                    }
+                },
+                max_element_count: bindless_array.max_element_count,
+            })
+            .collect();
+
+        self.device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: &shaders,
+                immutable_samplers: &immutable_samplers,
+                bindless_arrays: &bindless_arrays,
+            })
+    }
+
+    fn update_descriptor_set(
+        &mut self,
+        descriptor_set_array_id: u32,
+        update: &RafxCaptureDescriptorUpdate,
+        queue_update: bool,
+    ) -> RafxResult<()> {
+        fn lookup_all<'a, T>(
+            objects: &'a FnvHashMap<u32, T>,
+            kind: &str,
+            ids: &Option<Vec<u32>>,
+        ) -> RafxResult<Option<Vec<&'a T>>> {
+            match ids {
+                Some(ids) => {
+                    let mut found = Vec::with_capacity(ids.len());
+                    for id in ids {
+                        found.push(lookup(objects, kind, *id)?);
+                    }
+                    Ok(Some(found))
+                }
+                None => Ok(None),
+            }
+        }
+
+        let textures = lookup_all(&self.textures, "texture", &update.texture_ids)?;
+        let texture_views = lookup_all(
+            &self.texture_views,
+            "texture view",
+            &update.texture_view_ids,
+        )?;
+        let samplers = lookup_all(&self.samplers, "sampler", &update.sampler_ids)?;
+        let buffers = lookup_all(&self.buffers, "buffer", &update.buffer_ids)?;
+
+        let descriptor_update = RafxDescriptorUpdate {
+            array_index: update.array_index,
+            descriptor_key: match &update.descriptor_key {
+                RafxCaptureDescriptorKey::Undefined => RafxDescriptorKey::Undefined,
+                RafxCaptureDescriptorKey::Name(name) => RafxDescriptorKey::Name(name),
+                RafxCaptureDescriptorKey::Binding(binding) => RafxDescriptorKey::Binding(*binding),
+                RafxCaptureDescriptorKey::DescriptorIndex(index) => {
+                    RafxDescriptorKey::DescriptorIndex(*index)
+                }
+            },
+            elements: RafxDescriptorElements {
+                textures: textures.as_deref(),
+                texture_views: texture_views.as_deref(),
+                samplers: samplers.as_deref(),
+                buffers: buffers.as_deref(),
+                buffer_offset_sizes: update.buffer_offset_sizes.as_deref(),
+            },
+            dst_element_offset: update.dst_element_offset,
+            texture_bind_type: update.texture_bind_type,
+        };
+
+        let descriptor_set_array = self
+            .descriptor_set_arrays
+            .get_mut(&descriptor_set_array_id)
+            .ok_or_else(|| {
+                format!(
+                    "The capture refers to descriptor set array {} which does not exist",
+                    descriptor_set_array_id
+                )
+            })?;
+
+        if queue_update {
+            descriptor_set_array.queue_descriptor_set_update(&descriptor_update)
+        } else {
+            descriptor_set_array.update_descriptor_set(&[descriptor_update])
+        }
+    }
+
+    fn color_targets<'a>(
+        &'a self,
+        color_targets: &[RafxCaptureColorRenderTarget],
+    ) -> RafxResult<Vec<RafxColorRenderTargetBinding<'a>>> {
+        let mut bindings = Vec::with_capacity(color_targets.len());
+        for color_target in color_targets {
+            bindings.push(RafxColorRenderTargetBinding {
+                texture: lookup(&self.textures, "texture", color_target.texture_id)?,
+                load_op: color_target.load_op,
+                store_op: color_target.store_op,
+                mip_slice: color_target.mip_slice,
+                array_slice: color_target.array_slice,
+                texture_view: color_target
+                    .texture_view_id
+                    .map(|x| lookup(&self.texture_views, "texture view", x))
+                    .transpose()?,
+                clear_value: color_target.clear_value,
+                resolve_target: color_target
+                    .resolve_texture_id
+                    .map(|x| lookup(&self.textures, "texture", x))
+                    .transpose()?,
+                resolve_store_op: color_target.resolve_store_op,
+                resolve_mip_slice: color_target.resolve_mip_slice,
+                resolve_array_slice: color_target.resolve_array_slice,
+            });
+        }
+
+        Ok(bindings)
+    }
+
+    fn depth_target<'a>(
+        &'a self,
+        depth_target: &Option<RafxCaptureDepthStencilRenderTarget>,
+    ) -> RafxResult<Option<RafxDepthStencilRenderTargetBinding<'a>>> {
+        let depth_target = match depth_target {
+            Some(depth_target) => depth_target,
+            None => return Ok(None),
+        };
+
+        Ok(Some(RafxDepthStencilRenderTargetBinding {
+            texture: lookup(&self.textures, "texture", depth_target.texture_id)?,
+            depth_load_op: depth_target.depth_load_op,
+            stencil_load_op: depth_target.stencil_load_op,
+            depth_store_op: depth_target.depth_store_op,
+            stencil_store_op: depth_target.stencil_store_op,
+            mip_slice: depth_target.mip_slice,
+            array_slice: depth_target.array_slice,
+            texture_view: depth_target
+                .texture_view_id
+                .map(|x| lookup(&self.texture_views, "texture view", x))
+                .transpose()?,
+            clear_value: depth_target.clear_value,
+        }))
+    }
+
+    fn replay_command(
+        &mut self,
+        command_buffer_id: u32,
+        command: &RafxCaptureCommand,
+    ) -> RafxResult<()> {
+        // Resetting the pool requires mutable access, do it before borrowing anything else
+        if let RafxCaptureCommand::Begin | RafxCaptureCommand::BeginSecondary(_) = command {
+            self.command_buffers
+                .get_mut(&command_buffer_id)
+                .ok_or_else(|| {
+                    format!(
+                        "The capture refers to command buffer {} which does not exist",
+                        command_buffer_id
+                    )
+                })?
+                .command_pool
+                .reset_command_pool()?;
+        }
+
+        let command_buffer =
+            &lookup(&self.command_buffers, "command buffer", command_buffer_id)?.command_buffer;
+
+        match command {
+            RafxCaptureCommand::Begin => command_buffer.begin(),
+            RafxCaptureCommand::BeginSecondary(inheritance_info) => {
+                let primary_command_buffer = inheritance_info
+                    .primary_command_buffer_id
+                    .map(|x| lookup(&self.command_buffers, "command buffer", x))
+                    .transpose()?
+                    .map(|x| &x.command_buffer);
+                command_buffer.begin_secondary(&RafxCommandBufferInheritanceInfo {
+                    color_formats: &inheritance_info.color_formats,
+                    depth_stencil_format: inheritance_info.depth_stencil_format,
+                    sample_count: inheritance_info.sample_count,
+                    primary_command_buffer,
+                })
+            }
+            RafxCaptureCommand::End => command_buffer.end(),
+            RafxCaptureCommand::BeginRenderPass {
+                color_targets,
+                depth_target,
+            } => command_buffer.cmd_begin_render_pass(
+                &self.color_targets(color_targets)?,
+                self.depth_target(depth_target)?,
+            ),
+            RafxCaptureCommand::BeginRenderPassForSecondary {
+                color_targets,
+                depth_target,
+            } => command_buffer.cmd_begin_render_pass_for_secondary(
+                &self.color_targets(color_targets)?,
+                self.depth_target(depth_target)?,
+            ),
+            RafxCaptureCommand::ExecuteSecondaryCommandBuffers { command_buffer_ids } => {
+                let mut secondary_command_buffers = Vec::with_capacity(command_buffer_ids.len());
+                for id in command_buffer_ids {
+                    secondary_command_buffers.push(
+                        &lookup(&self.command_buffers, "command buffer", *id)?.command_buffer,
+                    );
+                }
+                command_buffer.cmd_execute_secondary_command_buffers(&secondary_command_buffers)
+            }
+            RafxCaptureCommand::EndRenderPass => command_buffer.cmd_end_render_pass(),
+            RafxCaptureCommand::SetViewport {
+                x,
+                y,
+                width,
+                height,
+                depth_min,
+                depth_max,
+            } => command_buffer.cmd_set_viewport(*x, *y, *width, *height, *depth_min, *depth_max),
+            RafxCaptureCommand::SetScissor {
+                x,
+                y,
+                width,
+                height,
+            } => command_buffer.cmd_set_scissor(*x, *y, *width, *height),
+            RafxCaptureCommand::SetStencilReferenceValue { value } => {
+                command_buffer.cmd_set_stencil_reference_value(*value)
+            }
+            RafxCaptureCommand::SetDepthBias {
+                constant_factor,
+                slope_factor,
+                clamp,
+            } => command_buffer.cmd_set_depth_bias(*constant_factor, *slope_factor, *clamp),
+            RafxCaptureCommand::SetBlendConstants { blend_constants } => {
+                command_buffer.cmd_set_blend_constants(*blend_constants)
+            }
+            RafxCaptureCommand::SetLineWidth { line_width } => {
+                command_buffer.cmd_set_line_width(*line_width)
+            }
+            RafxCaptureCommand::BindPipeline { pipeline_id } => {
+                command_buffer.cmd_bind_pipeline(lookup(&self.pipelines, "pipeline", *pipeline_id)?)
+            }
+            RafxCaptureCommand::BindVertexBuffers {
+                first_binding,
+                bindings,
+            } => {
+                let mut vertex_buffer_bindings = Vec::with_capacity(bindings.len());
+                for binding in bindings {
+                    vertex_buffer_bindings.push(RafxVertexBufferBinding {
+                        buffer: lookup(&self.buffers, "buffer", binding.buffer_id)?,
+                        byte_offset: binding.byte_offset,
+                    });
+                }
+                command_buffer.cmd_bind_vertex_buffers(*first_binding, &vertex_buffer_bindings)
+            }
+            RafxCaptureCommand::BindIndexBuffer {
+                binding,
+                index_type,
+            } => command_buffer.cmd_bind_index_buffer(&RafxIndexBufferBinding {
+                buffer: lookup(&self.buffers, "buffer", binding.buffer_id)?,
+                byte_offset: binding.byte_offset,
+                index_type: *index_type,
+            }),
+            RafxCaptureCommand::BindDescriptorSet { descriptor_set } => command_buffer
+                .cmd_bind_descriptor_set(
+                    lookup(
+                        &self.descriptor_set_arrays,
+                        "descriptor set array",
+                        descriptor_set.descriptor_set_array_id,
+                    )?,
+                    descriptor_set.array_index,
+                ),
+            RafxCaptureCommand::BindDescriptorSetHandle {
+                root_signature_id,
+                set_index,
+                descriptor_set,
+            } => {
+                let descriptor_set_handle = lookup(
+                    &self.descriptor_set_arrays,
+                    "descriptor set array",
+                    descriptor_set.descriptor_set_array_id,
+                )?
+                .handle(descriptor_set.array_index)
+                .ok_or("The capture binds a descriptor set that is out of range")?;
+                command_buffer.cmd_bind_descriptor_set_handle(
+                    lookup(&self.root_signatures, "root signature", *root_signature_id)?,
+                    *set_index,
+                    &descriptor_set_handle,
+                )
+            }
+            RafxCaptureCommand::BindPushConstant {
+                root_signature_id,
+                push_constant_index,
+                data,
+            } => command_buffer.cmd_bind_push_constant_bytes(
+                lookup(&self.root_signatures, "root signature", *root_signature_id)?,
+                *push_constant_index,
+                data,
+            ),
+            RafxCaptureCommand::Draw {
+                vertex_count,
+                first_vertex,
+            } => command_buffer.cmd_draw(*vertex_count, *first_vertex),
+            RafxCaptureCommand::DrawInstanced {
+                vertex_count,
+                first_vertex,
+                instance_count,
+                first_instance,
+            } => command_buffer.cmd_draw_instanced(
+                *vertex_count,
+                *first_vertex,
+                *instance_count,
+                *first_instance,
+            ),
+            RafxCaptureCommand::DrawIndexed {
+                index_count,
+                first_index,
+                vertex_offset,
+            } => command_buffer.cmd_draw_indexed(*index_count, *first_index, *vertex_offset),
+            RafxCaptureCommand::DrawIndexedInstanced {
+                index_count,
+                first_index,
+                instance_count,
+                first_instance,
+                vertex_offset,
+            } => command_buffer.cmd_draw_indexed_instanced(
+                *index_count,
+                *first_index,
+                *instance_count,
+                *first_instance,
+                *vertex_offset,
+            ),
+            RafxCaptureCommand::Dispatch {
+                group_count_x,
+                group_count_y,
+                group_count_z,
+            } => command_buffer.cmd_dispatch(*group_count_x, *group_count_y, *group_count_z),
+            RafxCaptureCommand::DrawIndirect {
+                buffer_id,
+                offset_in_bytes,
+                draw_count,
+                stride,
+            } => command_buffer.cmd_draw_indirect(
+                lookup(&self.buffers, "buffer", *buffer_id)?,
+                *offset_in_bytes,
+                *draw_count,
+                *stride,
+            ),
+            RafxCaptureCommand::DrawIndexedIndirect {
+                buffer_id,
+                offset_in_bytes,
+                draw_count,
+                stride,
+            } => command_buffer.cmd_draw_indexed_indirect(
+                lookup(&self.buffers, "buffer", *buffer_id)?,
+                *offset_in_bytes,
+                *draw_count,
+                *stride,
+            ),
+            RafxCaptureCommand::DispatchIndirect {
+                buffer_id,
+                offset_in_bytes,
+            } => command_buffer.cmd_dispatch_indirect(
+                lookup(&self.buffers, "buffer", *buffer_id)?,
+                *offset_in_bytes,
+            ),
+            RafxCaptureCommand::ResourceBarrier {
+                buffer_barriers,
+                texture_barriers,
+            } => {
+                let mut rafx_buffer_barriers = Vec::with_capacity(buffer_barriers.len());
+                for barrier in buffer_barriers {
+                    rafx_buffer_barriers.push(RafxBufferBarrier {
+                        buffer: lookup(&self.buffers, "buffer", barrier.buffer_id)?,
+                        src_state: barrier.src_state,
+                        dst_state: barrier.dst_state,
+                        queue_transition: barrier.queue_transition,
+                    });
+                }
+
+                let mut rafx_texture_barriers = Vec::with_capacity(texture_barriers.len());
+                for barrier in texture_barriers {
+                    rafx_texture_barriers.push(RafxTextureBarrier {
+                        texture: lookup(&self.textures, "texture", barrier.texture_id)?,
+                        src_state: barrier.src_state,
+                        dst_state: barrier.dst_state,
+                        queue_transition: barrier.queue_transition,
+                        array_slice: barrier.array_slice,
+                        mip_slice: barrier.mip_slice,
+                    });
+                }
+
+                command_buffer.cmd_resource_barrier(&rafx_buffer_barriers, &rafx_texture_barriers)
+            }
+            RafxCaptureCommand::ClearTexture { texture_id, params } => command_buffer
+                .cmd_clear_texture(lookup(&self.textures, "texture", *texture_id)?, params),
+            RafxCaptureCommand::FillBuffer {
+                buffer_id,
+                offset,
+                size,
+                value,
+            } => command_buffer.cmd_fill_buffer(
+                lookup(&self.buffers, "buffer", *buffer_id)?,
+                *offset,
+                *size,
+                *value,
+            ),
+            RafxCaptureCommand::CopyBufferToBuffer {
+                src_buffer_id,
+                dst_buffer_id,
+                src_offset,
+                dst_offset,
+                size,
+            } => command_buffer.cmd_copy_buffer_to_buffer(
+                lookup(&self.buffers, "buffer", *src_buffer_id)?,
+                lookup(&self.buffers, "buffer", *dst_buffer_id)?,
+                *src_offset,
+                *dst_offset,
+                *size,
+            ),
+            RafxCaptureCommand::CopyBufferToTexture {
+                src_buffer_id,
+                dst_texture_id,
+                params,
+            } => command_buffer.cmd_copy_buffer_to_texture(
+                lookup(&self.buffers, "buffer", *src_buffer_id)?,
+                lookup(&self.textures, "texture", *dst_texture_id)?,
+                params,
+            ),
+            RafxCaptureCommand::CopyTextureToBuffer {
+                src_texture_id,
+                dst_buffer_id,
+                params,
+            } => command_buffer.cmd_copy_texture_to_buffer(
+                lookup(&self.textures, "texture", *src_texture_id)?,
+                lookup(&self.buffers, "buffer", *dst_buffer_id)?,
+                params,
+            ),
+            RafxCaptureCommand::CopyTextureToTexture {
+                src_texture_id,
+                dst_texture_id,
+                params,
+            } => command_buffer.cmd_copy_texture_to_texture(
+                lookup(&self.textures, "texture", *src_texture_id)?,
+                lookup(&self.textures, "texture", *dst_texture_id)?,
+                params,
+            ),
+            RafxCaptureCommand::Blit {
+                src_texture_id,
+                dst_texture_id,
+                params,
+            } => command_buffer.cmd_blit(
+                lookup(&self.textures, "texture", *src_texture_id)?,
+                lookup(&self.textures, "texture", *dst_texture_id)?,
+                params,
+            ),
+            RafxCaptureCommand::PushGroupDebugMarker { name } => {
+                command_buffer.cmd_push_group_debug_marker(name)
+            }
+            RafxCaptureCommand::PopGroupDebugMarker => command_buffer.cmd_pop_group_debug_marker(),
+        }
+    }
+}
@@ -1,17 +1,28 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxCommandBufferEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxCommandBufferGles3;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxCommandBufferResourceStates;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxCommandBufferMetal;
 #[cfg(feature = "rafx-vulkan")]
 use crate::vulkan::RafxCommandBufferVulkan;
 use crate::{
-    RafxBuffer, RafxBufferBarrier, RafxCmdCopyBufferToTextureParams, RafxColorRenderTargetBinding,
-    RafxDepthStencilRenderTargetBinding, RafxDescriptorSetArray, RafxDescriptorSetHandle,
-    RafxIndexBufferBinding, RafxPipeline, RafxResult, RafxRootSignature, RafxTexture,
-    RafxTextureBarrier, RafxVertexBufferBinding,
+    RafxBuffer, RafxBufferBarrier, RafxCmdBlitParams, RafxCmdClearTextureParams,
+    RafxCmdCopyBufferToTextureParams, RafxCmdCopyTextureToBufferParams,
+    RafxCmdCopyTextureToTextureParams, RafxColorRenderTargetBinding,
+    RafxCommandBufferInheritanceInfo, RafxDepthStencilRenderTargetBinding, RafxDescriptorSetArray,
+    RafxDescriptorSetHandle, RafxIndexBufferBinding, RafxPipeline, RafxPushConstantIndex,
+    RafxQueryPool, RafxResult, RafxRootSignature, RafxTexture, RafxTextureBarrier,
+    RafxVertexBufferBinding,
 };
 
 /// A list of commands recorded by the CPU and submitted to the GPU.
@@ -45,15 +56,24 @@ pub enum RafxCommandBuffer {
 
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxCommandBufferEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxCommandBufferGles3),
 }
 
 impl RafxCommandBuffer {
     /// Begins writing a command buffer. This can only be called when the command buffer is first
     /// allocated or if the pool has been reset since it was last written
     pub fn begin(&self) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().reset();
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.begin(),
@@ -61,10 +81,69 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.begin(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.begin(),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.begin(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::Begin
+            });
+
+        Ok(())
+    }
+
+    /// Begins writing a secondary command buffer that will be executed within a render pass of a
+    /// primary command buffer. The primary must begin the render pass with
+    /// `cmd_begin_render_pass_for_secondary`, and the secondary must set its own viewport and
+    /// scissor. Blend constants and line width are reset to their defaults.
+    ///
+    /// On metal, secondary command buffers are emulated with parallel render encoders. The
+    /// inheritance info must reference the primary, which must already be in the render pass,
+    /// and the secondaries execute in the order they were begun. Only render commands may be
+    /// recorded into them.
+    pub fn begin_secondary(
+        &self,
+        inheritance_info: &RafxCommandBufferInheritanceInfo,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().reset();
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.begin_secondary(inheritance_info),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.begin_secondary(inheritance_info),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.begin_secondary(inheritance_info),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.begin_secondary(inheritance_info),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::BeginSecondary(
+                    state.inheritance_info(inheritance_info),
+                )
+            });
+
+        Ok(())
     }
 
     /// End writing the command buffer. This must be called before submitting the command buffer
@@ -77,10 +156,24 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.end(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.end(),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.end(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::End
+            });
+
+        Ok(())
     }
 
     /// This returns the command buffer to the pool, allowing it to be allocated again. This must
@@ -97,9 +190,15 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.return_to_pool(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.return_to_pool(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.return_to_pool(),
         }
     }
 
@@ -113,6 +212,10 @@ impl RafxCommandBuffer {
         color_targets: &[RafxColorRenderTargetBinding],
         depth_target: Option<RafxDepthStencilRenderTargetBinding>,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states()
+            .record_render_targets(color_targets, depth_target.as_ref());
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => {
@@ -124,12 +227,148 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_begin_render_pass(color_targets, depth_target)
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_begin_render_pass(color_targets, depth_target)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::BeginRenderPass {
+                    color_targets: state.color_targets(color_targets),
+                    depth_target: state.depth_target(depth_target.as_ref()),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Begin a new renderpass whose draws will be recorded into secondary command buffers (see
+    /// `begin_secondary`). Until the renderpass ends, only `cmd_execute_secondary_command_buffers`
+    /// and debug markers may be recorded into this command buffer.
+    pub fn cmd_begin_render_pass_for_secondary(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<RafxDepthStencilRenderTargetBinding>,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states()
+            .record_render_targets(color_targets, depth_target.as_ref());
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_begin_render_pass_for_secondary(color_targets, depth_target)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_begin_render_pass_for_secondary(color_targets, depth_target)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_begin_render_pass_for_secondary(color_targets, depth_target)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_begin_render_pass_for_secondary(color_targets, depth_target)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::BeginRenderPassForSecondary {
+                    color_targets: state.color_targets(color_targets),
+                    depth_target: state.depth_target(depth_target.as_ref()),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Executes secondary command buffers within a renderpass begun with
+    /// `cmd_begin_render_pass_for_secondary`. The secondary command buffers must have ended, and
+    /// must not be begun again until this command buffer has finished executing on the GPU.
+    pub fn cmd_execute_secondary_command_buffers(
+        &self,
+        secondary_command_buffers: &[&RafxCommandBuffer],
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states()
+            .record_execute_secondary_command_buffers(secondary_command_buffers);
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                let secondary_command_buffers: Vec<_> = secondary_command_buffers
+                    .iter()
+                    .map(|x| x.vk_command_buffer().unwrap())
+                    .collect();
+                inner.cmd_execute_secondary_command_buffers(&secondary_command_buffers)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                let secondary_command_buffers: Vec<_> = secondary_command_buffers
+                    .iter()
+                    .map(|x| x.metal_command_buffer().unwrap())
+                    .collect();
+                inner.cmd_execute_secondary_command_buffers(&secondary_command_buffers)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                let secondary_command_buffers: Vec<_> = secondary_command_buffers
+                    .iter()
+                    .map(|x| x.empty_command_buffer().unwrap())
+                    .collect();
+                inner.cmd_execute_secondary_command_buffers(&secondary_command_buffers)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                let secondary_command_buffers: Vec<_> = secondary_command_buffers
+                    .iter()
+                    .map(|x| x.gles3_command_buffer().unwrap())
+                    .collect();
+                inner.cmd_execute_secondary_command_buffers(&secondary_command_buffers)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::ExecuteSecondaryCommandBuffers {
+                    command_buffer_ids: secondary_command_buffers
+                        .iter()
+                        .map(|x| x.capture_id())
+                        .collect(),
+                }
+            });
+
+        Ok(())
     }
 
     /// Finish the renderpass.
@@ -141,10 +380,24 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.cmd_end_render_pass(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_end_render_pass(),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_end_render_pass(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::EndRenderPass
+            });
+
+        Ok(())
     }
 
     /// Set the viewport state. This may be called inside or outside of a renderpass.
@@ -170,12 +423,35 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_set_viewport(x, y, width, height, depth_min, depth_max)
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_set_viewport(x, y, width, height, depth_min, depth_max)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::SetViewport {
+                    x,
+                    y,
+                    width,
+                    height,
+                    depth_min,
+                    depth_max,
+                }
+            });
+
+        Ok(())
     }
 
     /// Set the scissor state. This may be called inside or outside of a renderpass.
@@ -195,10 +471,29 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.cmd_set_scissor(x, y, width, height),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_set_scissor(x, y, width, height),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_set_scissor(x, y, width, height),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::SetScissor {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            });
+
+        Ok(())
     }
 
     /// Set the stencil buffer state. This may be called inside or outside of a renderpass.
@@ -216,10 +511,142 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.cmd_set_stencil_reference_value(value),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_set_stencil_reference_value(value),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_set_stencil_reference_value(value),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::SetStencilReferenceValue { value }
+            });
+
+        Ok(())
+    }
+
+    /// Sets the depth bias for subsequent draws. The bound pipeline must have been created with
+    /// `RafxRasterizerState::dynamic_depth_bias`, and the bias must be set again after binding
+    /// another pipeline.
+    ///
+    /// Depth bias is commonly used in shadow mapping to avoid self-shadowing artifacts. A clamp of
+    /// 0.0 disables clamping.
+    pub fn cmd_set_depth_bias(
+        &self,
+        constant_factor: f32,
+        slope_factor: f32,
+        clamp: f32,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_set_depth_bias(constant_factor, slope_factor, clamp)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_set_depth_bias(constant_factor, slope_factor, clamp)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_set_depth_bias(constant_factor, slope_factor, clamp)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_set_depth_bias(constant_factor, slope_factor, clamp)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::SetDepthBias {
+                    constant_factor,
+                    slope_factor,
+                    clamp,
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Sets the color used by `RafxBlendFactor::ConstantColor` and
+    /// `RafxBlendFactor::OneMinusConstantColor`. Resets to zero at the start of each render pass.
+    pub fn cmd_set_blend_constants(
+        &self,
+        blend_constants: [f32; 4],
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_set_blend_constants(blend_constants),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_set_blend_constants(blend_constants),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_set_blend_constants(blend_constants),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_set_blend_constants(blend_constants),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::SetBlendConstants { blend_constants }
+            });
+
+        Ok(())
+    }
+
+    /// Sets the width in pixels of lines drawn with `RafxFillMode::Wireframe` or line topologies.
+    /// Resets to 1.0 at the start of each render pass. Other widths require
+    /// `RafxDeviceInfo::supports_wide_lines`.
+    pub fn cmd_set_line_width(
+        &self,
+        line_width: f32,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_set_line_width(line_width),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_set_line_width(line_width),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_set_line_width(line_width),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_set_line_width(line_width),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::SetLineWidth { line_width }
+            });
+
+        Ok(())
     }
 
     /// Binds the given pipeline - which represents fixed-function state and shaders. Draw calls
@@ -239,12 +666,30 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_bind_pipeline(pipeline.empty_pipeline().unwrap())
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_bind_pipeline(pipeline.gles3_pipeline().unwrap())
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::BindPipeline {
+                    pipeline_id: pipeline.capture_id(),
+                }
+            });
+
+        Ok(())
     }
 
     /// Binds a buffer as a vertex buffer. Draw calls will use this buffer as input.
@@ -256,6 +701,11 @@ impl RafxCommandBuffer {
         first_binding: u32,
         bindings: &[RafxVertexBufferBinding],
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        for binding in bindings {
+            self.resource_states().reference_buffer(binding.buffer);
+        }
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_bind_vertex_buffers(first_binding, bindings),
@@ -265,12 +715,37 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_bind_vertex_buffers(first_binding, bindings)
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_bind_vertex_buffers(first_binding, bindings)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::BindVertexBuffers {
+                    first_binding,
+                    bindings: bindings
+                        .iter()
+                        .map(|x| crate::capture::RafxCaptureBufferBinding {
+                            buffer_id: x.buffer.buffer_id(),
+                            byte_offset: x.byte_offset,
+                        })
+                        .collect(),
+                }
+            });
+
+        Ok(())
     }
 
     /// Binds a buffer as a vertex buffer. Draw calls will use this buffer as input.
@@ -281,6 +756,9 @@ impl RafxCommandBuffer {
         &self,
         binding: &RafxIndexBufferBinding,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().reference_buffer(binding.buffer);
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_bind_index_buffer(binding),
@@ -288,10 +766,30 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.cmd_bind_index_buffer(binding),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_bind_index_buffer(binding),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_bind_index_buffer(binding),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::BindIndexBuffer {
+                    binding: crate::capture::RafxCaptureBufferBinding {
+                        buffer_id: binding.buffer.buffer_id(),
+                        byte_offset: binding.byte_offset,
+                    },
+                    index_type: binding.index_type,
+                }
+            });
+
+        Ok(())
     }
 
     /// Binds a descriptor set for use by the shader in the currently bound pipeline.
@@ -302,6 +800,10 @@ impl RafxCommandBuffer {
         descriptor_set_array: &RafxDescriptorSetArray,
         index: u32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states()
+            .record_bind_descriptor_set(descriptor_set_array.resource_states(), index);
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_bind_descriptor_set(
@@ -315,13 +817,35 @@ impl RafxCommandBuffer {
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_bind_descriptor_set(
                 descriptor_set_array.empty_descriptor_set_array().unwrap(),
                 index,
             ),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_bind_descriptor_set(
+                descriptor_set_array.gles3_descriptor_set_array().unwrap(),
+                index,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::BindDescriptorSet {
+                    descriptor_set: crate::capture::RafxCaptureDescriptorSetRef {
+                        descriptor_set_array_id: descriptor_set_array.capture_id(),
+                        array_index: index,
+                    },
+                }
+            });
+
+        Ok(())
     }
 
     /// Binds a descriptor set for use by the shader in the currently bound pipeline.
@@ -334,6 +858,10 @@ impl RafxCommandBuffer {
         set_index: u32,
         descriptor_set_handle: &RafxDescriptorSetHandle,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states()
+            .record_bind_descriptor_set_handle(set_index);
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_bind_descriptor_set_handle(
@@ -349,14 +877,107 @@ impl RafxCommandBuffer {
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_bind_descriptor_set_handle(
                 root_signature.empty_root_signature().unwrap(),
                 set_index,
                 descriptor_set_handle.empty_descriptor_set_handle().unwrap(),
             ),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_bind_descriptor_set_handle(
+                root_signature.gles3_root_signature().unwrap(),
+                set_index,
+                descriptor_set_handle.gles3_descriptor_set_handle().unwrap(),
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::BindDescriptorSetHandle {
+                    root_signature_id: root_signature.capture_id(),
+                    set_index,
+                    descriptor_set: descriptor_set_handle.capture_ref(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Set the value of a push constant. Push constants are small blocks of data that are stored
+    /// directly in the command buffer, avoiding the need to allocate a buffer for them. Use
+    /// `RafxRootSignature::find_push_constant_by_name` to get the index.
+    ///
+    /// The data must match the layout the shader expects (std430 rules in GLSL)
+    pub fn cmd_bind_push_constant<T: Copy>(
+        &self,
+        root_signature: &RafxRootSignature,
+        push_constant_index: RafxPushConstantIndex,
+        data: &T,
+    ) -> RafxResult<()> {
+        self.cmd_bind_push_constant_bytes(
+            root_signature,
+            push_constant_index,
+            rafx_base::memory::any_as_bytes(data),
+        )
+    }
+
+    pub(crate) fn cmd_bind_push_constant_bytes(
+        &self,
+        root_signature: &RafxRootSignature,
+        push_constant_index: RafxPushConstantIndex,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_bind_push_constant(
+                root_signature.vk_root_signature().unwrap(),
+                push_constant_index,
+                data,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_bind_push_constant(
+                root_signature.metal_root_signature().unwrap(),
+                push_constant_index,
+                data,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_bind_push_constant(
+                root_signature.empty_root_signature().unwrap(),
+                push_constant_index,
+                data,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_bind_push_constant(
+                root_signature.gles3_root_signature().unwrap(),
+                push_constant_index,
+                data,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::BindPushConstant {
+                    root_signature_id: root_signature.capture_id(),
+                    push_constant_index,
+                    data: data.to_vec(),
+                }
+            });
+
+        Ok(())
     }
 
     /// Draw primitives using the currently bound pipeline and vertex buffer
@@ -365,6 +986,9 @@ impl RafxCommandBuffer {
         vertex_count: u32,
         first_vertex: u32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().record_bound_texture_usage();
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_draw(vertex_count, first_vertex),
@@ -372,10 +996,27 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => inner.cmd_draw(vertex_count, first_vertex),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_draw(vertex_count, first_vertex),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_draw(vertex_count, first_vertex),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::Draw {
+                    vertex_count,
+                    first_vertex,
+                }
+            });
+
+        Ok(())
     }
 
     /// Draw instanced primitives using the currently bound pipeline and vertex buffer
@@ -386,6 +1027,9 @@ impl RafxCommandBuffer {
         instance_count: u32,
         first_instance: u32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().record_bound_texture_usage();
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => {
@@ -397,12 +1041,33 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_draw_instanced(vertex_count, first_vertex, instance_count, first_instance)
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_draw_instanced(vertex_count, first_vertex, instance_count, first_instance)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::DrawInstanced {
+                    vertex_count,
+                    first_vertex,
+                    instance_count,
+                    first_instance,
+                }
+            });
+
+        Ok(())
     }
 
     /// Draw primitives using the currently bound pipeline, vertex, and index buffer
@@ -412,6 +1077,9 @@ impl RafxCommandBuffer {
         first_index: u32,
         vertex_offset: i32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().record_bound_texture_usage();
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => {
@@ -423,12 +1091,32 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_draw_indexed(index_count, first_index, vertex_offset)
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_draw_indexed(index_count, first_index, vertex_offset)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::DrawIndexed {
+                    index_count,
+                    first_index,
+                    vertex_offset,
+                }
+            });
+
+        Ok(())
     }
 
     /// Draw instanced primitives using the currently bound pipeline, vertex, and index buffer
@@ -440,6 +1128,9 @@ impl RafxCommandBuffer {
         first_instance: u32,
         vertex_offset: i32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().record_bound_texture_usage();
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_draw_indexed_instanced(
@@ -459,7 +1150,11 @@ impl RafxCommandBuffer {
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_draw_indexed_instanced(
                 index_count,
@@ -468,7 +1163,29 @@ impl RafxCommandBuffer {
                 first_instance,
                 vertex_offset,
             ),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_draw_indexed_instanced(
+                index_count,
+                first_index,
+                instance_count,
+                first_instance,
+                vertex_offset,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::DrawIndexedInstanced {
+                    index_count,
+                    first_index,
+                    instance_count,
+                    first_instance,
+                    vertex_offset,
+                }
+            });
+
+        Ok(())
     }
 
     /// Dispatch the current pipeline. Only usable with compute pipelines.
@@ -478,6 +1195,9 @@ impl RafxCommandBuffer {
         group_count_y: u32,
         group_count_z: u32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().record_bound_texture_usage();
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => {
@@ -489,69 +1209,615 @@ impl RafxCommandBuffer {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => {
                 inner.cmd_dispatch(group_count_x, group_count_y, group_count_z)
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_dispatch(group_count_x, group_count_y, group_count_z)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::Dispatch {
+                    group_count_x,
+                    group_count_y,
+                    group_count_z,
+                }
+            });
+
+        Ok(())
     }
 
-    /// Add a memory barrier for one or more resources. This must occur OUTSIDE of a renderpass.
-    pub fn cmd_resource_barrier(
+    /// Draw primitives using the currently bound pipeline and vertex buffer, reading the draw
+    /// arguments from a buffer. The buffer must contain `draw_count` `RafxDrawIndirectCommand`s
+    /// starting at `indirect_buffer_offset_in_bytes`, each `stride` bytes apart. The buffer must be
+    /// created with `RafxResourceType::INDIRECT_BUFFER`.
+    pub fn cmd_draw_indirect(
         &self,
-        buffer_barriers: &[RafxBufferBarrier],
-        texture_barriers: &[RafxTextureBarrier],
+        indirect_buffer: &RafxBuffer,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_buffer(indirect_buffer);
+            self.resource_states().record_bound_texture_usage();
+        }
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
-            RafxCommandBuffer::Vk(inner) => {
-                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
-            }
+            RafxCommandBuffer::Vk(inner) => inner.cmd_draw_indirect(
+                indirect_buffer.vk_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            ),
             #[cfg(feature = "rafx-metal")]
-            RafxCommandBuffer::Metal(inner) => {
-                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
-            }
+            RafxCommandBuffer::Metal(inner) => inner.cmd_draw_indirect(
+                indirect_buffer.metal_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
-            RafxCommandBuffer::Empty(inner) => {
-                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
-            }
-        }
+            RafxCommandBuffer::Empty(inner) => inner.cmd_draw_indirect(
+                indirect_buffer.empty_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_draw_indirect(
+                indirect_buffer.gles3_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::DrawIndirect {
+                    buffer_id: indirect_buffer.buffer_id(),
+                    offset_in_bytes: indirect_buffer_offset_in_bytes,
+                    draw_count,
+                    stride,
+                }
+            });
+
+        Ok(())
     }
 
-    /// Copy the contents of one buffer into another. This occurs on the GPU and allows modifying
-    /// resources that are not accessible to the CPU.
-    pub fn cmd_copy_buffer_to_buffer(
+    /// Draw primitives using the currently bound pipeline, vertex, and index buffer, reading the
+    /// draw arguments from a buffer. The buffer must contain `draw_count`
+    /// `RafxDrawIndexedIndirectCommand`s starting at `indirect_buffer_offset_in_bytes`, each
+    /// `stride` bytes apart. The buffer must be created with `RafxResourceType::INDIRECT_BUFFER`.
+    pub fn cmd_draw_indexed_indirect(
         &self,
-        src_buffer: &RafxBuffer,
-        dst_buffer: &RafxBuffer,
-        src_offset: u64,
-        dst_offset: u64,
-        size: u64,
+        indirect_buffer: &RafxBuffer,
+        indirect_buffer_offset_in_bytes: u64,
+        draw_count: u32,
+        stride: u32,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_buffer(indirect_buffer);
+            self.resource_states().record_bound_texture_usage();
+        }
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
-            RafxCommandBuffer::Vk(inner) => inner.cmd_copy_buffer_to_buffer(
-                src_buffer.vk_buffer().unwrap(),
-                dst_buffer.vk_buffer().unwrap(),
-                src_offset,
-                dst_offset,
-                size,
+            RafxCommandBuffer::Vk(inner) => inner.cmd_draw_indexed_indirect(
+                indirect_buffer.vk_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
             ),
             #[cfg(feature = "rafx-metal")]
-            RafxCommandBuffer::Metal(inner) => inner.cmd_copy_buffer_to_buffer(
-                src_buffer.metal_buffer().unwrap(),
-                dst_buffer.metal_buffer().unwrap(),
-                src_offset,
-                dst_offset,
-                size,
+            RafxCommandBuffer::Metal(inner) => inner.cmd_draw_indexed_indirect(
+                indirect_buffer.metal_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_draw_indexed_indirect(
+                indirect_buffer.empty_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_draw_indexed_indirect(
+                indirect_buffer.gles3_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+                draw_count,
+                stride,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::DrawIndexedIndirect {
+                    buffer_id: indirect_buffer.buffer_id(),
+                    offset_in_bytes: indirect_buffer_offset_in_bytes,
+                    draw_count,
+                    stride,
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Dispatch the current pipeline, reading the group counts from a `RafxDispatchIndirectCommand`
+    /// stored in a buffer. Only usable with compute pipelines. The buffer must be created with
+    /// `RafxResourceType::INDIRECT_BUFFER`.
+    pub fn cmd_dispatch_indirect(
+        &self,
+        indirect_buffer: &RafxBuffer,
+        indirect_buffer_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_buffer(indirect_buffer);
+            self.resource_states().record_bound_texture_usage();
+        }
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_dispatch_indirect(
+                indirect_buffer.vk_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_dispatch_indirect(
+                indirect_buffer.metal_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_dispatch_indirect(
+                indirect_buffer.empty_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_dispatch_indirect(
+                indirect_buffer.gles3_buffer().unwrap(),
+                indirect_buffer_offset_in_bytes,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::DispatchIndirect {
+                    buffer_id: indirect_buffer.buffer_id(),
+                    offset_in_bytes: indirect_buffer_offset_in_bytes,
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Reset queries so that they can be written again. Queries must be reset before they are
+    /// used, and this must be called outside of a render pass.
+    pub fn cmd_reset_query_pool(
+        &self,
+        query_pool: &RafxQueryPool,
+        first_query: u32,
+        query_count: u32,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_reset_query_pool(
+                query_pool.vk_query_pool().unwrap(),
+                first_query,
+                query_count,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_reset_query_pool(
+                query_pool.metal_query_pool().unwrap(),
+                first_query,
+                query_count,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_reset_query_pool(
+                query_pool.empty_query_pool().unwrap(),
+                first_query,
+                query_count,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_reset_query_pool(
+                query_pool.gles3_query_pool().unwrap(),
+                first_query,
+                query_count,
+            ),
+        }
+    }
+
+    /// Begin an occlusion or pipeline statistics query
+    pub fn cmd_begin_query(
+        &self,
+        query_pool: &RafxQueryPool,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_begin_query(query_pool.vk_query_pool().unwrap(), query_index)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_begin_query(query_pool.metal_query_pool().unwrap(), query_index)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_begin_query(query_pool.empty_query_pool().unwrap(), query_index)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_begin_query(query_pool.gles3_query_pool().unwrap(), query_index)
+            }
+        }
+    }
+
+    /// End an occlusion or pipeline statistics query that was started with `cmd_begin_query`
+    pub fn cmd_end_query(
+        &self,
+        query_pool: &RafxQueryPool,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_end_query(query_pool.vk_query_pool().unwrap(), query_index)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_end_query(query_pool.metal_query_pool().unwrap(), query_index)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_end_query(query_pool.empty_query_pool().unwrap(), query_index)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_end_query(query_pool.gles3_query_pool().unwrap(), query_index)
+            }
+        }
+    }
+
+    /// Write a timestamp once all previously recorded commands have completed. The difference
+    /// between two timestamps can be converted to nanoseconds with
+    /// `RafxDeviceInfo::timestamp_period_ns`.
+    pub fn cmd_write_timestamp(
+        &self,
+        query_pool: &RafxQueryPool,
+        query_index: u32,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_write_timestamp(query_pool.vk_query_pool().unwrap(), query_index)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_write_timestamp(query_pool.metal_query_pool().unwrap(), query_index)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_write_timestamp(query_pool.empty_query_pool().unwrap(), query_index)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_write_timestamp(query_pool.gles3_query_pool().unwrap(), query_index)
+            }
+        }
+    }
+
+    /// Copy query results into a buffer as u64 values, waiting for the queries to complete. Each
+    /// query writes `RafxQueryType::values_per_query()` values. This must be called outside of a
+    /// render pass.
+    pub fn cmd_resolve_query(
+        &self,
+        query_pool: &RafxQueryPool,
+        first_query: u32,
+        query_count: u32,
+        dst_buffer: &RafxBuffer,
+        dst_offset_in_bytes: u64,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().reference_buffer(dst_buffer);
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_resolve_query(
+                query_pool.vk_query_pool().unwrap(),
+                first_query,
+                query_count,
+                dst_buffer.vk_buffer().unwrap(),
+                dst_offset_in_bytes,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_resolve_query(
+                query_pool.metal_query_pool().unwrap(),
+                first_query,
+                query_count,
+                dst_buffer.metal_buffer().unwrap(),
+                dst_offset_in_bytes,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_resolve_query(
+                query_pool.empty_query_pool().unwrap(),
+                first_query,
+                query_count,
+                dst_buffer.empty_buffer().unwrap(),
+                dst_offset_in_bytes,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_resolve_query(
+                query_pool.gles3_query_pool().unwrap(),
+                first_query,
+                query_count,
+                dst_buffer.gles3_buffer().unwrap(),
+                dst_offset_in_bytes,
+            ),
+        }
+    }
+
+    /// Add a memory barrier for one or more resources. This must occur OUTSIDE of a renderpass.
+    pub fn cmd_resource_barrier(
+        &self,
+        buffer_barriers: &[RafxBufferBarrier],
+        texture_barriers: &[RafxTextureBarrier],
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states()
+            .record_barriers(buffer_barriers, texture_barriers);
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_resource_barrier(buffer_barriers, texture_barriers)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                state.barriers(buffer_barriers, texture_barriers)
+            });
+
+        Ok(())
+    }
+
+    /// Clear a texture outside of a render pass. Unlike `RafxLoadOp::Clear`, this can clear
+    /// multiple mip levels/array layers and textures that are not render targets, like storage
+    /// images.
+    pub fn cmd_clear_texture(
+        &self,
+        texture: &RafxTexture,
+        params: &RafxCmdClearTextureParams,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().reference_texture(texture);
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_clear_texture(texture.vk_texture().unwrap(), params)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_clear_texture(texture.metal_texture().unwrap(), params)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_clear_texture(texture.empty_texture().unwrap(), params)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_clear_texture(texture.gles3_texture().unwrap(), params)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::ClearTexture {
+                    texture_id: state.texture_id(texture),
+                    params: params.clone(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Fill a range of a buffer with a repeated byte value. The offset and size must be multiples
+    /// of 4. The buffer must be in the COPY_DST state.
+    pub fn cmd_fill_buffer(
+        &self,
+        buffer: &RafxBuffer,
+        offset: u64,
+        size: u64,
+        value: u8,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states().reference_buffer(buffer);
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => {
+                inner.cmd_fill_buffer(buffer.vk_buffer().unwrap(), offset, size, value)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => {
+                inner.cmd_fill_buffer(buffer.metal_buffer().unwrap(), offset, size, value)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => {
+                inner.cmd_fill_buffer(buffer.empty_buffer().unwrap(), offset, size, value)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => {
+                inner.cmd_fill_buffer(buffer.gles3_buffer().unwrap(), offset, size, value)
+            }
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::FillBuffer {
+                    buffer_id: buffer.buffer_id(),
+                    offset,
+                    size,
+                    value,
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Copy the contents of one buffer into another. This occurs on the GPU and allows modifying
+    /// resources that are not accessible to the CPU.
+    pub fn cmd_copy_buffer_to_buffer(
+        &self,
+        src_buffer: &RafxBuffer,
+        dst_buffer: &RafxBuffer,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_buffer(src_buffer);
+            self.resource_states().reference_buffer(dst_buffer);
+        }
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_copy_buffer_to_buffer(
+                src_buffer.vk_buffer().unwrap(),
+                dst_buffer.vk_buffer().unwrap(),
+                src_offset,
+                dst_offset,
+                size,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_copy_buffer_to_buffer(
+                src_buffer.metal_buffer().unwrap(),
+                dst_buffer.metal_buffer().unwrap(),
+                src_offset,
+                dst_offset,
+                size,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_copy_buffer_to_buffer(
                 src_buffer.empty_buffer().unwrap(),
@@ -560,7 +1826,29 @@ impl RafxCommandBuffer {
                 dst_offset,
                 size,
             ),
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_copy_buffer_to_buffer(
+                src_buffer.gles3_buffer().unwrap(),
+                dst_buffer.gles3_buffer().unwrap(),
+                src_offset,
+                dst_offset,
+                size,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::CopyBufferToBuffer {
+                    src_buffer_id: src_buffer.buffer_id(),
+                    dst_buffer_id: dst_buffer.buffer_id(),
+                    src_offset,
+                    dst_offset,
+                    size,
+                }
+            });
+
+        Ok(())
     }
 
     /// Copy the contents of a buffer into a texture. This occurs on the GPU and allows modifying
@@ -571,6 +1859,12 @@ impl RafxCommandBuffer {
         dst_texture: &RafxTexture,
         params: &RafxCmdCopyBufferToTextureParams,
     ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_buffer(src_buffer);
+            self.resource_states().reference_texture(dst_texture);
+        }
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandBuffer::Vk(inner) => inner.cmd_copy_buffer_to_texture(
@@ -586,13 +1880,355 @@ impl RafxCommandBuffer {
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => inner.cmd_copy_buffer_to_texture(
                 src_buffer.empty_buffer().unwrap(),
                 dst_texture.empty_texture().unwrap(),
                 params,
             ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_copy_buffer_to_texture(
+                src_buffer.gles3_buffer().unwrap(),
+                dst_texture.gles3_texture().unwrap(),
+                params,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::CopyBufferToTexture {
+                    src_buffer_id: src_buffer.buffer_id(),
+                    dst_texture_id: state.texture_id(dst_texture),
+                    params: params.clone(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Copy a single mip level/array layer of a texture into a buffer. The buffer is written with
+    /// the layout described by `RafxTextureBufferLayout`. This occurs on the GPU and allows reading
+    /// resources that are not accessible to the CPU.
+    pub fn cmd_copy_texture_to_buffer(
+        &self,
+        src_texture: &RafxTexture,
+        dst_buffer: &RafxBuffer,
+        params: &RafxCmdCopyTextureToBufferParams,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_texture(src_texture);
+            self.resource_states().reference_buffer(dst_buffer);
+        }
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_copy_texture_to_buffer(
+                src_texture.vk_texture().unwrap(),
+                dst_buffer.vk_buffer().unwrap(),
+                params,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_copy_texture_to_buffer(
+                src_texture.metal_texture().unwrap(),
+                dst_buffer.metal_buffer().unwrap(),
+                params,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_copy_texture_to_buffer(
+                src_texture.empty_texture().unwrap(),
+                dst_buffer.empty_buffer().unwrap(),
+                params,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_copy_texture_to_buffer(
+                src_texture.gles3_texture().unwrap(),
+                dst_buffer.gles3_buffer().unwrap(),
+                params,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::CopyTextureToBuffer {
+                    src_texture_id: state.texture_id(src_texture),
+                    dst_buffer_id: dst_buffer.buffer_id(),
+                    params: params.clone(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Copy a region of one texture to another. Both textures must have the same format. The
+    /// source must be in the COPY_SRC state and the destination in the COPY_DST state.
+    pub fn cmd_copy_texture_to_texture(
+        &self,
+        src_texture: &RafxTexture,
+        dst_texture: &RafxTexture,
+        params: &RafxCmdCopyTextureToTextureParams,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_texture(src_texture);
+            self.resource_states().reference_texture(dst_texture);
+        }
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_copy_texture_to_texture(
+                src_texture.vk_texture().unwrap(),
+                dst_texture.vk_texture().unwrap(),
+                params,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_copy_texture_to_texture(
+                src_texture.metal_texture().unwrap(),
+                dst_texture.metal_texture().unwrap(),
+                params,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_copy_texture_to_texture(
+                src_texture.empty_texture().unwrap(),
+                dst_texture.empty_texture().unwrap(),
+                params,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_copy_texture_to_texture(
+                src_texture.gles3_texture().unwrap(),
+                dst_texture.gles3_texture().unwrap(),
+                params,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::CopyTextureToTexture {
+                    src_texture_id: state.texture_id(src_texture),
+                    dst_texture_id: state.texture_id(dst_texture),
+                    params: params.clone(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Copy a region of one texture to another with linear filtering, scaling and converting
+    /// formats as needed. The source and destination may be the same texture if the regions do
+    /// not overlap (for example, different mip levels).
+    ///
+    /// The metal backend renders the blit, so the destination must be a color format that can be
+    /// rendered to, and blits between 3D textures are not supported.
+    pub fn cmd_blit(
+        &self,
+        src_texture: &RafxTexture,
+        dst_texture: &RafxTexture,
+        params: &RafxCmdBlitParams,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "validate-resource-states")]
+        {
+            self.resource_states().reference_texture(src_texture);
+            self.resource_states().reference_texture(dst_texture);
+        }
+
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_blit(
+                src_texture.vk_texture().unwrap(),
+                dst_texture.vk_texture().unwrap(),
+                params,
+            ),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_blit(
+                src_texture.metal_texture().unwrap(),
+                dst_texture.metal_texture().unwrap(),
+                params,
+            ),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_blit(
+                src_texture.empty_texture().unwrap(),
+                dst_texture.empty_texture().unwrap(),
+                params,
+            ),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_blit(
+                src_texture.gles3_texture().unwrap(),
+                dst_texture.gles3_texture().unwrap(),
+                params,
+            ),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |state| {
+                crate::capture::RafxCaptureCommand::Blit {
+                    src_texture_id: state.texture_id(src_texture),
+                    dst_texture_id: state.texture_id(dst_texture),
+                    params: params.clone(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Begins a labeled group of commands. Groups may be nested and are shown by GPU debugging
+    /// tools like RenderDoc and Xcode. Every push must be matched by a
+    /// `cmd_pop_group_debug_marker` in the same command buffer.
+    ///
+    /// On vulkan this requires VK_EXT_debug_utils, which is enabled when available. Otherwise
+    /// the marker is ignored.
+    pub fn cmd_push_group_debug_marker(
+        &self,
+        name: impl AsRef<str>,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_push_group_debug_marker(name.as_ref()),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_push_group_debug_marker(name.as_ref()),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_push_group_debug_marker(name.as_ref()),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_push_group_debug_marker(name.as_ref()),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::PushGroupDebugMarker {
+                    name: name.as_ref().to_string(),
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Ends the group started by the most recent `cmd_push_group_debug_marker`
+    pub fn cmd_pop_group_debug_marker(&self) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.cmd_pop_group_debug_marker(),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.cmd_pop_group_debug_marker(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.cmd_pop_group_debug_marker(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.cmd_pop_group_debug_marker(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_command(self.capture_id(), |_| {
+                crate::capture::RafxCaptureCommand::PopGroupDebugMarker
+            });
+
+        Ok(())
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxCommandBufferResourceStates {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.resource_states(),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.resource_states(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.resource_states(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.resource_states(),
+        }
+    }
+
+    // Used internally to identify the command buffer in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.capture_id(),
+        }
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(inner) => inner.capture_recorder(),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(inner) => inner.capture_recorder(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(inner) => inner.capture_recorder(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => inner.capture_recorder(),
         }
     }
 
@@ -607,9 +2243,15 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(_) => None,
         }
     }
 
@@ -624,9 +2266,15 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(_) => None,
         }
     }
 
@@ -634,7 +2282,11 @@ impl RafxCommandBuffer {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_command_buffer(&self) -> Option<&RafxCommandBufferEmpty> {
         match self {
@@ -644,9 +2296,38 @@ impl RafxCommandBuffer {
             RafxCommandBuffer::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandBuffer::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_command_buffer(&self) -> Option<&RafxCommandBufferGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandBuffer::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandBuffer::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandBuffer::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandBuffer::Empty(_) => None,
         }
     }
 }
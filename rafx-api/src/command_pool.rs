@@ -1,13 +1,21 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxCommandPoolEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxCommandPoolGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxCommandPoolMetal;
 #[cfg(feature = "rafx-vulkan")]
 use crate::vulkan::RafxCommandPoolVulkan;
-use crate::{RafxCommandBuffer, RafxCommandBufferDef, RafxDeviceContext, RafxResult};
+use crate::{
+    RafxCommandBuffer, RafxCommandBufferDef, RafxDeviceContext, RafxQueueType, RafxResult,
+};
 
 /// A pool of command buffers. A command pool is necessary to create a command buffer.
 ///
@@ -27,9 +35,15 @@ pub enum RafxCommandPool {
     Metal(RafxCommandPoolMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxCommandPoolEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxCommandPoolGles3),
 }
 
 impl RafxCommandPool {
@@ -43,11 +57,40 @@ impl RafxCommandPool {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandPool::Empty(inner) => {
                 RafxDeviceContext::Empty(inner.device_context().clone())
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(inner) => {
+                RafxDeviceContext::Gles3(inner.device_context().clone())
+            }
+        }
+    }
+
+    /// The type of queue that command buffers allocated from this pool may be submitted to
+    pub fn queue_type(&self) -> RafxQueueType {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandPool::Vk(inner) => inner.queue_type(),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandPool::Metal(inner) => inner.queue_type(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandPool::Empty(inner) => inner.queue_type(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(inner) => inner.queue_type(),
         }
     }
 
@@ -57,7 +100,7 @@ impl RafxCommandPool {
         &mut self,
         command_buffer_def: &RafxCommandBufferDef,
     ) -> RafxResult<RafxCommandBuffer> {
-        Ok(match self {
+        let command_buffer = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxCommandPool::Vk(inner) => {
                 RafxCommandBuffer::Vk(inner.create_command_buffer(command_buffer_def)?)
@@ -68,12 +111,34 @@ impl RafxCommandPool {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandPool::Empty(inner) => {
                 RafxCommandBuffer::Empty(inner.create_command_buffer(command_buffer_def)?)
             }
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(inner) => {
+                RafxCommandBuffer::Gles3(inner.create_command_buffer(command_buffer_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        {
+            let queue_type = self.queue_type();
+            self.capture_recorder().record(|_| {
+                crate::capture::RafxCaptureEvent::CreateCommandBuffer {
+                    command_buffer_id: command_buffer.capture_id(),
+                    queue_type,
+                    is_secondary: command_buffer_def.is_secondary,
+                }
+            });
+        }
+
+        Ok(command_buffer)
     }
 
     /// Reset all command buffers to an "unwritten" state. This must not be called if any command
@@ -89,9 +154,36 @@ impl RafxCommandPool {
             RafxCommandPool::Metal(inner) => inner.reset_command_pool(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandPool::Empty(inner) => inner.reset_command_pool(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(inner) => inner.reset_command_pool(),
+        }
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandPool::Vk(inner) => inner.device_context().capture_recorder(),
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandPool::Metal(inner) => inner.device_context().capture_recorder(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandPool::Empty(inner) => inner.device_context().capture_recorder(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(inner) => inner.device_context().capture_recorder(),
         }
     }
 
@@ -106,9 +198,15 @@ impl RafxCommandPool {
             RafxCommandPool::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandPool::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(_inner) => None,
         }
     }
 
@@ -123,15 +221,25 @@ impl RafxCommandPool {
             RafxCommandPool::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandPool::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(_inner) => None,
         }
     }
 
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_command_pool(&self) -> Option<&RafxCommandPoolEmpty> {
         match self {
@@ -141,9 +249,38 @@ impl RafxCommandPool {
             RafxCommandPool::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxCommandPool::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_command_pool(&self) -> Option<&RafxCommandPoolGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxCommandPool::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxCommandPool::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxCommandPool::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxCommandPool::Empty(_) => None,
         }
     }
 }
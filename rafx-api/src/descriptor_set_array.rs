@@ -1,8 +1,16 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::{RafxDescriptorSetArrayEmpty, RafxDescriptorSetHandleEmpty};
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::{RafxDescriptorSetArrayGles3, RafxDescriptorSetHandleGles3};
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxDescriptorSetResourceStates;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::{RafxDescriptorSetArrayMetal, RafxDescriptorSetHandleMetal};
 #[cfg(feature = "rafx-vulkan")]
@@ -29,12 +37,40 @@ pub enum RafxDescriptorSetHandle {
     Metal(RafxDescriptorSetHandleMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxDescriptorSetHandleEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxDescriptorSetHandleGles3),
 }
 
 impl RafxDescriptorSetHandle {
+    // Used internally to identify the descriptor set in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_ref(&self) -> crate::capture::RafxCaptureDescriptorSetRef {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDescriptorSetHandle::Vk(inner) => inner.capture_ref(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDescriptorSetHandle::Metal(inner) => inner.capture_ref(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDescriptorSetHandle::Empty(inner) => inner.capture_ref(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetHandle::Gles3(inner) => inner.capture_ref(),
+        }
+    }
+
     #[cfg(feature = "rafx-vulkan")]
     pub fn vk_descriptor_set_handle(&self) -> Option<&RafxDescriptorSetHandleVulkan> {
         match self {
@@ -44,9 +80,15 @@ impl RafxDescriptorSetHandle {
             RafxDescriptorSetHandle::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetHandle::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetHandle::Gles3(_inner) => None,
         }
     }
 
@@ -59,15 +101,25 @@ impl RafxDescriptorSetHandle {
             RafxDescriptorSetHandle::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetHandle::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetHandle::Gles3(_inner) => None,
         }
     }
 
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_descriptor_set_handle(&self) -> Option<&RafxDescriptorSetHandleEmpty> {
         match self {
@@ -77,9 +129,38 @@ impl RafxDescriptorSetHandle {
             RafxDescriptorSetHandle::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetHandle::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetHandle::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_descriptor_set_handle(&self) -> Option<&RafxDescriptorSetHandleGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDescriptorSetHandle::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxDescriptorSetHandle::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetHandle::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDescriptorSetHandle::Empty(_) => None,
         }
     }
 }
@@ -113,9 +194,15 @@ pub enum RafxDescriptorSetArray {
     Metal(RafxDescriptorSetArrayMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxDescriptorSetArrayEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxDescriptorSetArrayGles3),
 }
 
 impl RafxDescriptorSetArray {
@@ -134,11 +221,19 @@ impl RafxDescriptorSetArray {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(inner) => {
                 RafxDescriptorSetHandle::Empty(inner.handle(index)?)
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => {
+                RafxDescriptorSetHandle::Gles3(inner.handle(index)?)
+            }
         })
     }
 
@@ -151,9 +246,15 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(inner) => inner.root_signature(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(inner) => inner.root_signature(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.root_signature(),
         }
     }
 
@@ -170,10 +271,33 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(inner) => inner.update_descriptor_set(params),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(inner) => inner.update_descriptor_set(params),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.update_descriptor_set(params),
+        }?;
+
+        #[cfg(feature = "validate-resource-states")]
+        for update in params {
+            self.resource_states_mut().record_update(update);
         }
+
+        #[cfg(feature = "capture")]
+        for update in params {
+            self.root_signature().capture_recorder().record(|state| {
+                crate::capture::RafxCaptureEvent::UpdateDescriptorSet {
+                    descriptor_set_array_id: self.capture_id(),
+                    update: state.descriptor_update(update),
+                }
+            });
+        }
+
+        Ok(())
     }
 
     /// Update a CPU-only copy of the descriptor set, but does not apply the write to the descriptor
@@ -192,9 +316,70 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(inner) => inner.queue_descriptor_set_update(update),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(inner) => inner.queue_descriptor_set_update(update),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.queue_descriptor_set_update(update),
+        }?;
+
+        #[cfg(feature = "validate-resource-states")]
+        self.resource_states_mut().record_update(update);
+
+        #[cfg(feature = "capture")]
+        self.root_signature().capture_recorder().record(|state| {
+            crate::capture::RafxCaptureEvent::QueueDescriptorSetUpdate {
+                descriptor_set_array_id: self.capture_id(),
+                update: state.descriptor_update(update),
+            }
+        });
+
+        Ok(())
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_states(&self) -> &RafxDescriptorSetResourceStates {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDescriptorSetArray::Vk(inner) => inner.resource_states(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDescriptorSetArray::Metal(inner) => inner.resource_states(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDescriptorSetArray::Empty(inner) => inner.resource_states(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.resource_states(),
+        }
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    fn resource_states_mut(&mut self) -> &mut RafxDescriptorSetResourceStates {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDescriptorSetArray::Vk(inner) => inner.resource_states_mut(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDescriptorSetArray::Metal(inner) => inner.resource_states_mut(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDescriptorSetArray::Empty(inner) => inner.resource_states_mut(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.resource_states_mut(),
         }
     }
 
@@ -207,9 +392,46 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(inner) => inner.flush_descriptor_set_updates(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(inner) => inner.flush_descriptor_set_updates(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.flush_descriptor_set_updates(),
+        }?;
+
+        #[cfg(feature = "capture")]
+        self.root_signature().capture_recorder().record(|_| {
+            crate::capture::RafxCaptureEvent::FlushDescriptorSetUpdates {
+                descriptor_set_array_id: self.capture_id(),
+            }
+        });
+
+        Ok(())
+    }
+
+    // Used internally to identify the descriptor set array in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDescriptorSetArray::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDescriptorSetArray::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDescriptorSetArray::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => inner.capture_id(),
         }
     }
 
@@ -224,9 +446,15 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(_inner) => None,
         }
     }
 
@@ -241,9 +469,15 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(_inner) => None,
         }
     }
 
@@ -251,7 +485,11 @@ impl RafxDescriptorSetArray {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_descriptor_set_array(&self) -> Option<&RafxDescriptorSetArrayEmpty> {
         match self {
@@ -261,9 +499,38 @@ impl RafxDescriptorSetArray {
             RafxDescriptorSetArray::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDescriptorSetArray::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_descriptor_set_array(&self) -> Option<&RafxDescriptorSetArrayGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDescriptorSetArray::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxDescriptorSetArray::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDescriptorSetArray::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDescriptorSetArray::Empty(_) => None,
         }
     }
 }
@@ -1,8 +1,16 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::backends::empty::RafxDeviceContextEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::backends::gles3::RafxDeviceContextGles3;
+#[cfg(feature = "validate-resource-states")]
+use crate::internal_shared::RafxResourceStateTracker;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxDeviceContextMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -22,9 +30,15 @@ pub enum RafxDeviceContext {
     Metal(RafxDeviceContextMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxDeviceContextEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxDeviceContextGles3),
 }
 
 impl RafxDeviceContext {
@@ -37,9 +51,157 @@ impl RafxDeviceContext {
             RafxDeviceContext::Metal(inner) => inner.device_info(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => inner.device_info(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.device_info(),
+        }
+    }
+
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn resource_state_tracker(&self) -> &RafxResourceStateTracker {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.resource_state_tracker(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.resource_state_tracker(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.resource_state_tracker(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.resource_state_tracker(),
+        }
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.capture_recorder(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.capture_recorder(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.capture_recorder(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.capture_recorder(),
+        }
+    }
+
+    /// Start recording frames for a capture. Requires the `capture` feature and
+    /// `RafxApiDef::enable_capture`. Call this between frames, command buffers that were begun
+    /// before it can't be replayed.
+    ///
+    /// Before this is called, only what's needed to recreate the objects that are alive is kept.
+    /// Commands submitted before the capture began are not recorded, so the contents that the GPU
+    /// wrote to textures and non-host-visible buffers before then are not part of the capture.
+    #[cfg(feature = "capture")]
+    pub fn begin_capture(&self) -> RafxResult<()> {
+        self.capture_recorder().begin_capture()
+    }
+
+    /// Stop recording frames and return the capture, which contains the objects that were alive
+    /// when `begin_capture()` was called and everything that happened since. Save it with
+    /// `RafxCapture::write_to_file()` and replay it with the `rafx-replay` tool.
+    #[cfg(feature = "capture")]
+    pub fn end_capture(&self) -> RafxResult<crate::capture::RafxCapture> {
+        self.capture_recorder().end_capture()
+    }
+
+    /// Returns what would be returned by `end_capture()` if it were called now, without ending the
+    /// capture. If no capture is in progress, it contains no frames.
+    #[cfg(feature = "capture")]
+    pub fn capture(&self) -> RafxResult<crate::capture::RafxCapture> {
+        self.capture_recorder().capture()
+    }
+
+    /// Get the current memory budget/usage of each heap and the buffers and textures allocated
+    /// through this device, grouped by `RafxMemoryUsage`. This may query the driver, so avoid
+    /// calling it more than about once per frame.
+    pub fn memory_statistics(&self) -> RafxResult<RafxMemoryStatistics> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.memory_statistics(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.memory_statistics(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.memory_statistics(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.memory_statistics(),
+        }
+    }
+
+    /// Returns the contents of the pipeline cache used when creating pipelines. This can be saved
+    /// to disk and passed to `load_pipeline_cache_data` on a later run to speed up pipeline
+    /// creation. It is only valid for devices with the same `RafxDeviceInfo::pipeline_cache_uuid`.
+    /// Backends without a pipeline cache return an empty vec.
+    pub fn pipeline_cache_data(&self) -> RafxResult<Vec<u8>> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.pipeline_cache_data(),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.pipeline_cache_data(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.pipeline_cache_data(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.pipeline_cache_data(),
+        }
+    }
+
+    /// Merge previously saved data from `pipeline_cache_data` into the pipeline cache. This is
+    /// best called at startup, before pipelines are created. Data from a different device or driver
+    /// version returns an error and is not loaded.
+    pub fn load_pipeline_cache_data(
+        &self,
+        data: &[u8],
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.load_pipeline_cache_data(data),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.load_pipeline_cache_data(data),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.load_pipeline_cache_data(data),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.load_pipeline_cache_data(data),
         }
     }
 
@@ -57,11 +219,19 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 inner.find_supported_format(candidates, resource_type)
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                inner.find_supported_format(candidates, resource_type)
+            }
         }
     }
 
@@ -70,17 +240,32 @@ impl RafxDeviceContext {
         &self,
         queue_type: RafxQueueType,
     ) -> RafxResult<RafxQueue> {
-        Ok(match self {
+        let queue = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => RafxQueue::Vk(inner.create_queue(queue_type)?),
             #[cfg(feature = "rafx-metal")]
             RafxDeviceContext::Metal(inner) => RafxQueue::Metal(inner.create_queue(queue_type)?),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxQueue::Empty(inner.create_queue(queue_type)?),
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxQueue::Gles3(inner.create_queue(queue_type)?),
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record(|_| crate::capture::RafxCaptureEvent::CreateQueue {
+                queue_id: queue.queue_id(),
+                queue_type,
+            });
+
+        Ok(queue)
     }
 
     /// Create a fence
@@ -92,9 +277,15 @@ impl RafxDeviceContext {
             RafxDeviceContext::Metal(inner) => RafxFence::Metal(inner.create_fence()?),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxFence::Empty(inner.create_fence()?),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxFence::Gles3(inner.create_fence()?),
         })
     }
 
@@ -107,9 +298,48 @@ impl RafxDeviceContext {
             RafxDeviceContext::Metal(inner) => RafxSemaphore::Metal(inner.create_semaphore()?),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxSemaphore::Empty(inner.create_semaphore()?),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxSemaphore::Gles3(inner.create_semaphore()?),
+        })
+    }
+
+    /// Create a timeline semaphore with the given initial value. Fails if
+    /// `RafxDeviceInfo::supports_timeline_semaphores` is false.
+    pub fn create_timeline_semaphore(
+        &self,
+        initial_value: u64,
+    ) -> RafxResult<RafxTimelineSemaphore> {
+        Ok(match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => {
+                RafxTimelineSemaphore::Vk(inner.create_timeline_semaphore(initial_value)?)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => {
+                RafxTimelineSemaphore::Metal(inner.create_timeline_semaphore(initial_value)?)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => {
+                RafxTimelineSemaphore::Empty(inner.create_timeline_semaphore(initial_value)?)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxTimelineSemaphore::Gles3(inner.create_timeline_semaphore(initial_value)?)
+            }
         })
     }
 
@@ -130,21 +360,43 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 RafxSwapchain::Empty(inner.create_swapchain(raw_window_handle, swapchain_def)?)
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxSwapchain::Gles3(inner.create_swapchain(raw_window_handle, swapchain_def)?)
+            }
         })
     }
 
+    /// Create a swapchain that is backed by render target textures rather than a window. Images
+    /// are presented on the given queue. If a callback is provided, it receives the contents of
+    /// every presented image.
+    pub fn create_offscreen_swapchain(
+        &self,
+        queue: &RafxQueue,
+        offscreen_swapchain_def: &RafxOffscreenSwapchainDef,
+        present_callback: Option<RafxOffscreenPresentCallback>,
+    ) -> RafxResult<RafxSwapchain> {
+        Ok(RafxSwapchain::Offscreen(Box::new(
+            RafxOffscreenSwapchain::new(self, queue, offscreen_swapchain_def, present_callback)?,
+        )))
+    }
+
     /// Wait for the given fences to complete. If a fence is in an unsubmitted state, the fence is
     /// ignored.
     pub fn wait_for_fences(
         &self,
         fences: &[&RafxFence],
     ) -> RafxResult<()> {
-        Ok(match self {
+        match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => {
                 let fences: Vec<_> = fences.iter().map(|x| x.vk_fence().unwrap()).collect();
@@ -157,12 +409,60 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 let fences: Vec<_> = fences.iter().map(|x| x.empty_fence().unwrap()).collect();
                 inner.wait_for_fences(&fences)?
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                let fences: Vec<_> = fences.iter().map(|x| x.gles3_fence().unwrap()).collect();
+                inner.wait_for_fences(&fences)?
+            }
+        };
+
+        #[cfg(feature = "validate-resource-states")]
+        for fence in fences {
+            fence.on_signaled();
+        }
+
+        Ok(())
+    }
+
+    /// Create a query pool. Check `RafxDeviceInfo` to see which query types are supported.
+    pub fn create_query_pool(
+        &self,
+        query_pool_def: &RafxQueryPoolDef,
+    ) -> RafxResult<RafxQueryPool> {
+        Ok(match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => {
+                RafxQueryPool::Vk(inner.create_query_pool(query_pool_def)?)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => {
+                RafxQueryPool::Metal(inner.create_query_pool(query_pool_def)?)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => {
+                RafxQueryPool::Empty(inner.create_query_pool(query_pool_def)?)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxQueryPool::Gles3(inner.create_query_pool(query_pool_def)?)
+            }
         })
     }
 
@@ -171,7 +471,7 @@ impl RafxDeviceContext {
         &self,
         sampler_def: &RafxSamplerDef,
     ) -> RafxResult<RafxSampler> {
-        Ok(match self {
+        let sampler = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => RafxSampler::Vk(inner.create_sampler(sampler_def)?),
             #[cfg(feature = "rafx-metal")]
@@ -180,12 +480,29 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 RafxSampler::Empty(inner.create_sampler(sampler_def)?)
             }
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxSampler::Gles3(inner.create_sampler(sampler_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record(|_| crate::capture::RafxCaptureEvent::CreateSampler {
+                sampler_id: sampler.capture_id(),
+                sampler_def: sampler_def.clone(),
+            });
+
+        Ok(sampler)
     }
 
     /// Create a texture
@@ -193,7 +510,7 @@ impl RafxDeviceContext {
         &self,
         texture_def: &RafxTextureDef,
     ) -> RafxResult<RafxTexture> {
-        Ok(match self {
+        let texture = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => RafxTexture::Vk(inner.create_texture(texture_def)?),
             #[cfg(feature = "rafx-metal")]
@@ -202,12 +519,70 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 RafxTexture::Empty(inner.create_texture(texture_def)?)
             }
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxTexture::Gles3(inner.create_texture(texture_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_texture_created(&texture);
+
+        Ok(texture)
+    }
+
+    /// Create a view of a subset of a texture's mip levels and array layers, optionally with a
+    /// different format or dimensions
+    pub fn create_texture_view(
+        &self,
+        texture: &RafxTexture,
+        texture_view_def: &RafxTextureViewDef,
+    ) -> RafxResult<RafxTextureView> {
+        let texture_view = match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => {
+                RafxTextureView::Vk(inner.create_texture_view(texture, texture_view_def)?)
+            }
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => {
+                RafxTextureView::Metal(inner.create_texture_view(texture, texture_view_def)?)
+            }
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => {
+                RafxTextureView::Empty(inner.create_texture_view(texture, texture_view_def)?)
+            }
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxTextureView::Gles3(inner.create_texture_view(texture, texture_view_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().record(|state| {
+            crate::capture::RafxCaptureEvent::CreateTextureView {
+                texture_view_id: texture_view.capture_id(),
+                texture_id: state.texture_id(texture),
+                texture_view_def: texture_view_def.clone(),
+            }
+        });
+
+        Ok(texture_view)
     }
 
     /// Create a buffer
@@ -215,24 +590,207 @@ impl RafxDeviceContext {
         &self,
         buffer_def: &RafxBufferDef,
     ) -> RafxResult<RafxBuffer> {
-        Ok(match self {
+        let buffer = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => RafxBuffer::Vk(inner.create_buffer(buffer_def)?),
             #[cfg(feature = "rafx-metal")]
             RafxDeviceContext::Metal(inner) => RafxBuffer::Metal(inner.create_buffer(buffer_def)?),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxBuffer::Empty(inner.create_buffer(buffer_def)?),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxBuffer::Gles3(inner.create_buffer(buffer_def)?),
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_buffer_created(&buffer);
+
+        Ok(buffer)
+    }
+
+    /// Create a heap that textures and buffers can be placed in
+    pub fn create_heap(
+        &self,
+        heap_def: &RafxHeapDef,
+    ) -> RafxResult<RafxHeap> {
+        Ok(match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => RafxHeap::Vk(inner.create_heap(heap_def)?),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => RafxHeap::Metal(inner.create_heap(heap_def)?),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => RafxHeap::Empty(inner.create_heap(heap_def)?),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxHeap::Gles3(inner.create_heap(heap_def)?),
         })
     }
 
+    /// Returns the size and alignment required to place a texture with the given def in a heap
+    pub fn texture_memory_requirements(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.texture_memory_requirements(texture_def),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.texture_memory_requirements(texture_def),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.texture_memory_requirements(texture_def),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.texture_memory_requirements(texture_def),
+        }
+    }
+
+    /// Returns the size and alignment required to place a buffer with the given def in a heap
+    pub fn buffer_memory_requirements(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxMemoryRequirements> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => inner.buffer_memory_requirements(buffer_def),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => inner.buffer_memory_requirements(buffer_def),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => inner.buffer_memory_requirements(buffer_def),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => inner.buffer_memory_requirements(buffer_def),
+        }
+    }
+
+    /// Create a texture in the given heap at the given byte offset. The offset must be a multiple
+    /// of the alignment returned by `texture_memory_requirements`. The texture may overlap other
+    /// resources placed in the heap.
+    pub fn create_texture_in_heap(
+        &self,
+        heap: &RafxHeap,
+        offset: u64,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTexture> {
+        let texture = match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => RafxTexture::Vk(inner.create_texture_in_heap(
+                heap.vk_heap().unwrap(),
+                offset,
+                texture_def,
+            )?),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => RafxTexture::Metal(inner.create_texture_in_heap(
+                heap.metal_heap().unwrap(),
+                offset,
+                texture_def,
+            )?),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => RafxTexture::Empty(inner.create_texture_in_heap(
+                heap.empty_heap().unwrap(),
+                offset,
+                texture_def,
+            )?),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxTexture::Gles3(inner.create_texture_in_heap(
+                heap.gles3_heap().unwrap(),
+                offset,
+                texture_def,
+            )?),
+        };
+
+        // Heaps are not captured, the texture is replayed as a regular texture
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_texture_created(&texture);
+
+        Ok(texture)
+    }
+
+    /// Create a buffer in the given heap at the given byte offset. The offset must be a multiple
+    /// of the alignment returned by `buffer_memory_requirements`. The buffer may overlap other
+    /// resources placed in the heap.
+    pub fn create_buffer_in_heap(
+        &self,
+        heap: &RafxHeap,
+        offset: u64,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<RafxBuffer> {
+        let buffer = match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(inner) => RafxBuffer::Vk(inner.create_buffer_in_heap(
+                heap.vk_heap().unwrap(),
+                offset,
+                buffer_def,
+            )?),
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => RafxBuffer::Metal(inner.create_buffer_in_heap(
+                heap.metal_heap().unwrap(),
+                offset,
+                buffer_def,
+            )?),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(inner) => RafxBuffer::Empty(inner.create_buffer_in_heap(
+                heap.empty_heap().unwrap(),
+                offset,
+                buffer_def,
+            )?),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxBuffer::Gles3(inner.create_buffer_in_heap(
+                heap.gles3_heap().unwrap(),
+                offset,
+                buffer_def,
+            )?),
+        };
+
+        // Heaps are not captured, the buffer is replayed as a regular buffer
+        #[cfg(feature = "capture")]
+        self.capture_recorder().on_buffer_created(&buffer);
+
+        Ok(buffer)
+    }
+
     pub fn create_shader_module(
         &self,
         shader_module_def: RafxShaderModuleDef,
     ) -> RafxResult<RafxShaderModule> {
-        Ok(match self {
+        let shader_module = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => {
                 RafxShaderModule::Vk(inner.create_shader_module(shader_module_def.vk.unwrap())?)
@@ -243,12 +801,29 @@ impl RafxDeviceContext {
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxShaderModule::Empty(
                 inner.create_shader_module(shader_module_def.empty.unwrap())?,
             ),
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxShaderModule::Gles3(
+                inner.create_shader_module(shader_module_def.gles3.unwrap())?,
+            ),
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record(|_| crate::capture::RafxCaptureEvent::CreateShaderModule {
+                shader_module_id: shader_module.capture_id(),
+                shader_package: crate::capture::shader_package_from_module_def(&shader_module_def),
+            });
+
+        Ok(shader_module)
     }
 
     //TODO: Consider a struct with each kind of shader stage instead of a vec of stages
@@ -257,17 +832,42 @@ impl RafxDeviceContext {
         &self,
         stages: Vec<RafxShaderStageDef>,
     ) -> RafxResult<RafxShader> {
-        Ok(match self {
+        #[cfg(feature = "capture")]
+        let captured_stages: Vec<_> = stages
+            .iter()
+            .map(|x| crate::capture::RafxCaptureShaderStage {
+                shader_module_id: x.shader_module.capture_id(),
+                reflection: x.reflection.clone(),
+                specialization_constants: x.specialization_constants.clone(),
+            })
+            .collect();
+
+        let shader = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => RafxShader::Vk(inner.create_shader(stages)?),
             #[cfg(feature = "rafx-metal")]
             RafxDeviceContext::Metal(inner) => RafxShader::Metal(inner.create_shader(stages)?),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxShader::Empty(inner.create_shader(stages)?),
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxShader::Gles3(inner.create_shader(stages)?),
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record(|_| crate::capture::RafxCaptureEvent::CreateShader {
+                shader_id: shader.capture_id(),
+                stages: captured_stages,
+            });
+
+        Ok(shader)
     }
 
     /// Create a root signature
@@ -275,7 +875,7 @@ impl RafxDeviceContext {
         &self,
         root_signature_def: &RafxRootSignatureDef,
     ) -> RafxResult<RafxRootSignature> {
-        Ok(match self {
+        let root_signature = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => {
                 RafxRootSignature::Vk(inner.create_root_signature(root_signature_def)?)
@@ -286,12 +886,65 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 RafxRootSignature::Empty(inner.create_root_signature(root_signature_def)?)
             }
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxRootSignature::Gles3(inner.create_root_signature(root_signature_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().record(|_| {
+            use crate::capture::RafxCaptureBindingKey;
+            crate::capture::RafxCaptureEvent::CreateRootSignature {
+                root_signature_id: root_signature.capture_id(),
+                shader_ids: root_signature_def
+                    .shaders
+                    .iter()
+                    .map(|x| x.capture_id())
+                    .collect(),
+                immutable_samplers: root_signature_def
+                    .immutable_samplers
+                    .iter()
+                    .map(|x| crate::capture::RafxCaptureImmutableSamplers {
+                        key: match x.key {
+                            RafxImmutableSamplerKey::Name(name) => {
+                                RafxCaptureBindingKey::Name(name.to_string())
+                            }
+                            RafxImmutableSamplerKey::Binding(set_index, binding) => {
+                                RafxCaptureBindingKey::Binding(set_index, binding)
+                            }
+                        },
+                        sampler_ids: x.samplers.iter().map(|x| x.capture_id()).collect(),
+                    })
+                    .collect(),
+                bindless_arrays: root_signature_def
+                    .bindless_arrays
+                    .iter()
+                    .map(|x| crate::capture::RafxCaptureBindlessArray {
+                        key: match x.key {
+                            RafxBindlessArrayKey::Name(name) => {
+                                RafxCaptureBindingKey::Name(name.to_string())
+                            }
+                            RafxBindlessArrayKey::Binding(set_index, binding) => {
+                                RafxCaptureBindingKey::Binding(set_index, binding)
+                            }
+                        },
+                        max_element_count: x.max_element_count,
+                    })
+                    .collect(),
+            }
+        });
+
+        Ok(root_signature)
     }
 
     /// Create a graphics pipeline
@@ -299,7 +952,7 @@ impl RafxDeviceContext {
         &self,
         pipeline_def: &RafxGraphicsPipelineDef,
     ) -> RafxResult<RafxPipeline> {
-        Ok(match self {
+        let pipeline = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => {
                 RafxPipeline::Vk(inner.create_graphics_pipeline(pipeline_def)?)
@@ -310,12 +963,41 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 RafxPipeline::Empty(inner.create_graphics_pipeline(pipeline_def)?)
             }
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxPipeline::Gles3(inner.create_graphics_pipeline(pipeline_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().record(|_| {
+            crate::capture::RafxCaptureEvent::CreateGraphicsPipeline {
+                pipeline_id: pipeline.capture_id(),
+                pipeline_def: crate::capture::RafxCaptureGraphicsPipelineDef {
+                    shader_id: pipeline_def.shader.capture_id(),
+                    root_signature_id: pipeline_def.root_signature.capture_id(),
+                    vertex_layout: pipeline_def.vertex_layout.clone(),
+                    blend_state: pipeline_def.blend_state.clone(),
+                    depth_state: pipeline_def.depth_state.clone(),
+                    rasterizer_state: pipeline_def.rasterizer_state.clone(),
+                    primitive_topology: pipeline_def.primitive_topology,
+                    color_formats: pipeline_def.color_formats.to_vec(),
+                    depth_stencil_format: pipeline_def.depth_stencil_format,
+                    sample_count: pipeline_def.sample_count,
+                },
+            }
+        });
+
+        Ok(pipeline)
     }
 
     /// Create a compute pipeline
@@ -323,7 +1005,7 @@ impl RafxDeviceContext {
         &self,
         pipeline_def: &RafxComputePipelineDef,
     ) -> RafxResult<RafxPipeline> {
-        Ok(match self {
+        let pipeline = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => {
                 RafxPipeline::Vk(inner.create_compute_pipeline(pipeline_def)?)
@@ -334,12 +1016,31 @@ impl RafxDeviceContext {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => {
                 RafxPipeline::Empty(inner.create_compute_pipeline(pipeline_def)?)
             }
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => {
+                RafxPipeline::Gles3(inner.create_compute_pipeline(pipeline_def)?)
+            }
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().record(|_| {
+            crate::capture::RafxCaptureEvent::CreateComputePipeline {
+                pipeline_id: pipeline.capture_id(),
+                shader_id: pipeline_def.shader.capture_id(),
+                root_signature_id: pipeline_def.root_signature.capture_id(),
+            }
+        });
+
+        Ok(pipeline)
     }
 
     /// Create a descriptor set array
@@ -347,7 +1048,7 @@ impl RafxDeviceContext {
         &self,
         descriptor_set_array_def: &RafxDescriptorSetArrayDef,
     ) -> RafxResult<RafxDescriptorSetArray> {
-        Ok(match self {
+        let descriptor_set_array = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxDeviceContext::Vk(inner) => RafxDescriptorSetArray::Vk(
                 inner.create_descriptor_set_array(descriptor_set_array_def)?,
@@ -358,12 +1059,32 @@ impl RafxDeviceContext {
             ),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => RafxDescriptorSetArray::Empty(
                 inner.create_descriptor_set_array(descriptor_set_array_def)?,
             ),
-        })
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => RafxDescriptorSetArray::Gles3(
+                inner.create_descriptor_set_array(descriptor_set_array_def)?,
+            ),
+        };
+
+        #[cfg(feature = "capture")]
+        self.capture_recorder().record(|_| {
+            crate::capture::RafxCaptureEvent::CreateDescriptorSetArray {
+                descriptor_set_array_id: descriptor_set_array.capture_id(),
+                root_signature_id: descriptor_set_array_def.root_signature.capture_id(),
+                set_index: descriptor_set_array_def.set_index,
+                array_length: descriptor_set_array_def.array_length,
+            }
+        });
+
+        Ok(descriptor_set_array)
     }
 
     /// Get the underlying vulkan API object. This provides access to any internally created
@@ -377,9 +1098,15 @@ impl RafxDeviceContext {
             RafxDeviceContext::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(_) => None,
         }
     }
 
@@ -394,9 +1121,15 @@ impl RafxDeviceContext {
             RafxDeviceContext::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => None,
         }
     }
 
@@ -404,7 +1137,11 @@ impl RafxDeviceContext {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_device_context(&self) -> Option<&RafxDeviceContextEmpty> {
         match self {
@@ -414,9 +1151,38 @@ impl RafxDeviceContext {
             RafxDeviceContext::Metal(inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxDeviceContext::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_device_context(&self) -> Option<&RafxDeviceContextGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxDeviceContext::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxDeviceContext::Metal(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxDeviceContext::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxDeviceContext::Empty(_) => None,
         }
     }
 }
@@ -1,5 +1,6 @@
 #[cfg(feature = "rafx-vulkan")]
 use crate::vulkan::VkCreateInstanceError;
+use crate::RafxFormat;
 #[cfg(feature = "rafx-vulkan")]
 use ash::vk;
 use std::sync::Arc;
@@ -7,9 +8,35 @@ use std::sync::Arc;
 pub type RafxResult<T> = Result<T, RafxError>;
 
 /// Generic error that contains all the different kinds of errors that may occur when using the API
+///
+/// Errors that callers may want to recover from have their own variants. Backend-specific results
+/// (i.e. `vk::Result`) are mapped onto them where possible. `StringError` is used for anything that
+/// does not fit another variant.
 #[derive(Debug, Clone)]
 pub enum RafxError {
     StringError(String),
+    /// The device was lost (driver reset, GPU hang or removal). The device context and everything
+    /// created from it must be recreated
+    DeviceLost,
+    /// A GPU memory allocation failed
+    OutOfDeviceMemory,
+    /// A CPU memory allocation failed
+    OutOfHostMemory,
+    /// The format is not supported by the device for the way it is being used
+    UnsupportedFormat(RafxFormat),
+    /// Shader reflection data does not agree with how the shader is being used. For example, two
+    /// stages disagree about a binding, or a required entry point or vertex input is missing
+    ReflectionMismatch {
+        /// Name of the resource, entry point or vertex input that does not match, if known
+        name: Option<String>,
+        message: String,
+    },
+    /// A value passed to the API is invalid, for example it exceeds a device limit
+    InvalidArgument {
+        /// Name of the field or parameter that is invalid
+        argument: &'static str,
+        message: String,
+    },
     IoError(Arc<std::io::Error>),
     #[cfg(feature = "rafx-vulkan")]
     VkError(vk::Result),
@@ -25,6 +52,12 @@ impl std::error::Error for RafxError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
             RafxError::StringError(_) => None,
+            RafxError::DeviceLost => None,
+            RafxError::OutOfDeviceMemory => None,
+            RafxError::OutOfHostMemory => None,
+            RafxError::UnsupportedFormat(_) => None,
+            RafxError::ReflectionMismatch { .. } => None,
+            RafxError::InvalidArgument { .. } => None,
             RafxError::IoError(ref e) => Some(&**e),
 
             #[cfg(feature = "rafx-vulkan")]
@@ -46,6 +79,23 @@ impl core::fmt::Display for RafxError {
     ) -> core::fmt::Result {
         match *self {
             RafxError::StringError(ref e) => e.fmt(fmt),
+            RafxError::DeviceLost => write!(fmt, "The device was lost"),
+            RafxError::OutOfDeviceMemory => write!(fmt, "Out of device memory"),
+            RafxError::OutOfHostMemory => write!(fmt, "Out of host memory"),
+            RafxError::UnsupportedFormat(format) => {
+                write!(fmt, "Format {:?} is not supported", format)
+            }
+            RafxError::ReflectionMismatch {
+                ref name,
+                ref message,
+            } => match name {
+                Some(name) => write!(fmt, "Reflection mismatch ({}): {}", name, message),
+                None => write!(fmt, "Reflection mismatch: {}", message),
+            },
+            RafxError::InvalidArgument {
+                argument,
+                ref message,
+            } => write!(fmt, "Invalid argument {}: {}", argument, message),
             RafxError::IoError(ref e) => e.fmt(fmt),
             #[cfg(feature = "rafx-vulkan")]
             RafxError::VkError(ref e) => e.fmt(fmt),
@@ -59,6 +109,36 @@ impl core::fmt::Display for RafxError {
     }
 }
 
+impl RafxError {
+    pub fn reflection_mismatch<T: Into<String>>(
+        name: Option<&str>,
+        message: T,
+    ) -> Self {
+        RafxError::ReflectionMismatch {
+            name: name.map(|x| x.to_string()),
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_argument<T: Into<String>>(
+        argument: &'static str,
+        message: T,
+    ) -> Self {
+        RafxError::InvalidArgument {
+            argument,
+            message: message.into(),
+        }
+    }
+
+    /// True for `OutOfDeviceMemory` and `OutOfHostMemory`
+    pub fn is_out_of_memory(&self) -> bool {
+        matches!(
+            self,
+            RafxError::OutOfDeviceMemory | RafxError::OutOfHostMemory
+        )
+    }
+}
+
 impl From<&str> for RafxError {
     fn from(str: &str) -> Self {
         RafxError::StringError(str.to_string())
@@ -80,7 +160,12 @@ impl From<std::io::Error> for RafxError {
 #[cfg(feature = "rafx-vulkan")]
 impl From<vk::Result> for RafxError {
     fn from(result: vk::Result) -> Self {
-        RafxError::VkError(result)
+        match result {
+            vk::Result::ERROR_DEVICE_LOST => RafxError::DeviceLost,
+            vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => RafxError::OutOfDeviceMemory,
+            vk::Result::ERROR_OUT_OF_HOST_MEMORY => RafxError::OutOfHostMemory,
+            _ => RafxError::VkError(result),
+        }
     }
 }
 
@@ -101,6 +186,14 @@ impl From<VkCreateInstanceError> for RafxError {
 #[cfg(feature = "rafx-vulkan")]
 impl From<vk_mem::Error> for RafxError {
     fn from(error: vk_mem::Error) -> Self {
+        // Allocation failures are reported by the allocator, map them the same way as vk::Result
+        if let vk_mem::ErrorKind::Vulkan(result) = error.kind() {
+            match RafxError::from(*result) {
+                RafxError::VkError(_) => {}
+                mapped => return mapped,
+            }
+        }
+
         RafxError::VkMemError(Arc::new(error))
     }
 }
@@ -1,14 +1,6 @@
-use crate::{RafxCommandBuffer, RafxResult, RafxTexture};
-
-#[cfg(feature = "rafx-metal")]
-use crate::metal::RafxCommandBufferMetal;
-#[cfg(feature = "rafx-vulkan")]
-use crate::vulkan::RafxCommandBufferVulkan;
-
-#[cfg(feature = "rafx-vulkan")]
 use crate::{
-    RafxBarrierQueueTransition, RafxCmdBlitParams, RafxExtents3D, RafxResourceState,
-    RafxTextureBarrier,
+    RafxBarrierQueueTransition, RafxCmdBlitParams, RafxCommandBuffer, RafxExtents3D,
+    RafxResourceState, RafxResult, RafxTexture, RafxTextureBarrier,
 };
 
 /// The max number of mip levels an image can have given its size
@@ -21,60 +13,23 @@ pub fn mip_level_max_count_for_image_size(
 }
 
 // Texture must be in COPY_SRC state
-// After this call, it will be in COPY_DST state
+// After this call, it will be in COPY_SRC state
 // Vulkan requires this on a graphics queue. Metal allows this on any queue.
 pub fn generate_mipmaps(
     command_buffer: &RafxCommandBuffer,
-    _texture: &RafxTexture,
-) -> RafxResult<()> {
-    match command_buffer {
-        #[cfg(feature = "rafx-vulkan")]
-        RafxCommandBuffer::Vk(inner) => generate_mipmaps_vk(inner, _texture),
-        #[cfg(feature = "rafx-metal")]
-        RafxCommandBuffer::Metal(inner) => generate_mipmaps_metal(inner, _texture),
-        #[cfg(any(
-            feature = "rafx-empty",
-            not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
-        ))]
-        RafxCommandBuffer::Empty(_) => unimplemented!(),
-    }
-}
-
-// This custom path for metal can be removed after I implement cmd_blit
-#[cfg(feature = "rafx-metal")]
-fn generate_mipmaps_metal(
-    command_buffer: &RafxCommandBufferMetal,
-    texture: &RafxTexture,
-) -> RafxResult<()> {
-    log::trace!("Generating mipmaps");
-    command_buffer.end_current_encoders(false)?;
-    let blit_encoder = command_buffer
-        .metal_command_buffer()
-        .unwrap()
-        .new_blit_command_encoder();
-    blit_encoder.generate_mipmaps(texture.metal_texture().unwrap().metal_texture());
-    blit_encoder.end_encoding();
-
-    return Ok(());
-}
-
-#[cfg(feature = "rafx-vulkan")]
-fn generate_mipmaps_vk(
-    command_buffer: &RafxCommandBufferVulkan,
     texture: &RafxTexture,
 ) -> RafxResult<()> {
     let mip_level_count = texture.texture_def().mip_count;
 
     for layer in 0..texture.texture_def().array_length {
-        do_generate_mipmaps_vk(command_buffer, texture, layer, mip_level_count)?;
+        do_generate_mipmaps(command_buffer, texture, layer, mip_level_count)?;
     }
 
     Ok(())
 }
 
-#[cfg(feature = "rafx-vulkan")]
-fn do_generate_mipmaps_vk(
-    command_buffer: &RafxCommandBufferVulkan,
+fn do_generate_mipmaps(
+    command_buffer: &RafxCommandBuffer,
     texture: &RafxTexture,
     layer: u32,
     mip_level_count: u32,
@@ -82,7 +37,6 @@ fn do_generate_mipmaps_vk(
     log::debug!("Generating mipmaps");
 
     let texture_def = texture.texture_def();
-    let vk_texture = texture.vk_texture().unwrap();
 
     // Walk through each mip level n:
     // - put level n+1 into write mode
@@ -128,9 +82,9 @@ fn do_generate_mipmaps_vk(
         log::trace!("src {:?}", src_extents[1]);
         log::trace!("dst {:?}", dst_extents[1]);
 
-        command_buffer.cmd_blit_image(
-            texture.vk_texture().unwrap(),
-            vk_texture,
+        command_buffer.cmd_blit(
+            texture,
+            texture,
             &RafxCmdBlitParams {
                 src_mip_level: src_level as u8,
                 dst_mip_level: dst_level as u8,
@@ -160,3 +114,80 @@ fn do_generate_mipmaps_vk(
 
     Ok(())
 }
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod tests {
+    use super::*;
+    use crate::extra::upload::{RafxUpload, RafxUploadState};
+    use crate::{
+        RafxApi, RafxCmdCopyBufferToTextureParams, RafxFormat, RafxQueueType, RafxTextureDef,
+    };
+
+    #[test]
+    fn generate_mipmaps_box_filters_each_level() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 4,
+                    height: 4,
+                    depth: 1,
+                },
+                mip_count: mip_level_max_count_for_image_size(4, 4),
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Red increases with x, green increases with y
+        let mut data = Vec::default();
+        for y in 0..4 {
+            for x in 0..4 {
+                data.extend_from_slice(&[x * 40, y * 40, 100, 255]);
+            }
+        }
+
+        let mut upload = RafxUpload::new(&device_context, &queue, 1024).unwrap();
+        let offset = upload.push(&data, 16).unwrap();
+        upload
+            .command_buffer()
+            .cmd_copy_buffer_to_texture(
+                upload.staging_buffer(),
+                &texture,
+                &RafxCmdCopyBufferToTextureParams {
+                    buffer_offset: offset,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        generate_mipmaps(upload.command_buffer(), &texture).unwrap();
+        upload.submit().unwrap();
+        assert!(upload.state().unwrap() == RafxUploadState::Complete);
+
+        let empty_texture = texture.empty_texture().unwrap();
+        assert_eq!(empty_texture.read_subresource(0, 0), data);
+        assert_eq!(
+            empty_texture.read_subresource(1, 0),
+            vec![
+                20, 20, 100, 255, 100, 20, 100, 255, //
+                20, 100, 100, 255, 100, 100, 100, 255,
+            ]
+        );
+        assert_eq!(empty_texture.read_subresource(2, 0), vec![60, 60, 100, 255]);
+    }
+}
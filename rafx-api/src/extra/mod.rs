@@ -1,3 +1,5 @@
 pub mod mipmaps;
+pub mod offscreen_swapchain;
+pub mod readback;
 pub mod swapchain_helper;
 pub mod upload;
@@ -0,0 +1,491 @@
+use crate::{
+    RafxBuffer, RafxBufferDef, RafxCmdCopyTextureToBufferParams, RafxCommandBuffer,
+    RafxCommandBufferDef, RafxCommandPool, RafxCommandPoolDef, RafxDeviceContext, RafxExtents3D,
+    RafxFence, RafxFormat, RafxMemoryUsage, RafxOffscreenSwapchainDef, RafxPresentSuccessResult,
+    RafxQueue, RafxResourceState, RafxResourceType, RafxResult, RafxSampleCount, RafxSemaphore,
+    RafxSwapchainDef, RafxSwapchainImage, RafxTexture, RafxTextureBarrier, RafxTextureBufferLayout,
+    RafxTextureDef, RafxTextureDimensions,
+};
+use std::sync::Mutex;
+
+/// The contents of an image presented to a `RafxOffscreenSwapchain`
+pub struct RafxOffscreenPresentedImage {
+    /// The swapchain image that was presented
+    pub swapchain_image_index: u32,
+    /// Number of images presented before this one since the swapchain was created
+    pub present_index: u64,
+    pub format: RafxFormat,
+    /// Layout of the image within the staging buffer it was copied to
+    pub layout: RafxTextureBufferLayout,
+    /// The texels with row padding removed (see `RafxReadback::read_texture`)
+    pub data: Vec<u8>,
+}
+
+/// Called with the contents of every image presented to a `RafxOffscreenSwapchain`
+pub type RafxOffscreenPresentCallback = Box<dyn FnMut(RafxOffscreenPresentedImage) + Send>;
+
+struct RafxOffscreenSwapchainPresentState {
+    command_pool: RafxCommandPool,
+    command_buffer: RafxCommandBuffer,
+    fence: RafxFence,
+    // Only allocated if there is a callback to read the presented images
+    staging_buffer: Option<RafxBuffer>,
+    present_callback: Option<RafxOffscreenPresentCallback>,
+    present_count: u64,
+}
+
+/// A swapchain that is backed by plain render target textures rather than a window surface. This
+/// allows windowless rendering (i.e. on a server, generating thumbnails, or in automated tests)
+/// through the same `RafxSwapchain`/`RafxSwapchainHelper` interface used for windows.
+///
+/// Images are handed out round-robin. Acquiring an image signals the fence/semaphore with an empty
+/// submit to the swapchain's queue. Presenting submits to the same queue and blocks until the GPU
+/// finishes the presented frame. If a present callback was provided, the presented image is copied
+/// to a staging buffer and passed to the callback before `present` returns.
+///
+/// Swapchain images are created with `RENDER_TARGET_COLOR` and `TEXTURE` usage. Like a real
+/// swapchain, images are expected to be in the `PRESENT` state when presented.
+pub struct RafxOffscreenSwapchain {
+    device_context: RafxDeviceContext,
+    queue: RafxQueue,
+    offscreen_swapchain_def: RafxOffscreenSwapchainDef,
+    swapchain_images: Vec<RafxTexture>,
+    next_swapchain_image_index: u32,
+
+    acquire_command_pool: RafxCommandPool,
+    acquire_command_buffer: RafxCommandBuffer,
+    acquire_fence: RafxFence,
+
+    // present() only has shared access to the swapchain
+    present_state: Mutex<RafxOffscreenSwapchainPresentState>,
+}
+
+impl RafxOffscreenSwapchain {
+    pub fn new(
+        device_context: &RafxDeviceContext,
+        queue: &RafxQueue,
+        offscreen_swapchain_def: &RafxOffscreenSwapchainDef,
+        present_callback: Option<RafxOffscreenPresentCallback>,
+    ) -> RafxResult<Self> {
+        if offscreen_swapchain_def.image_count == 0 {
+            Err("An offscreen swapchain requires at least one image")?;
+        }
+
+        let swapchain_images =
+            Self::create_swapchain_images(device_context, offscreen_swapchain_def)?;
+
+        let mut acquire_command_pool =
+            queue.create_command_pool(&RafxCommandPoolDef { transient: true })?;
+        let acquire_command_buffer =
+            acquire_command_pool.create_command_buffer(&RafxCommandBufferDef {
+                is_secondary: false,
+            })?;
+
+        let mut command_pool =
+            queue.create_command_pool(&RafxCommandPoolDef { transient: true })?;
+        let command_buffer = command_pool.create_command_buffer(&RafxCommandBufferDef {
+            is_secondary: false,
+        })?;
+
+        let staging_buffer = if present_callback.is_some() {
+            Some(Self::create_staging_buffer(
+                device_context,
+                queue,
+                offscreen_swapchain_def,
+            )?)
+        } else {
+            None
+        };
+
+        Ok(RafxOffscreenSwapchain {
+            device_context: device_context.clone(),
+            queue: queue.clone(),
+            offscreen_swapchain_def: offscreen_swapchain_def.clone(),
+            swapchain_images,
+            next_swapchain_image_index: 0,
+            acquire_command_pool,
+            acquire_command_buffer,
+            acquire_fence: device_context.create_fence()?,
+            present_state: Mutex::new(RafxOffscreenSwapchainPresentState {
+                command_pool,
+                command_buffer,
+                fence: device_context.create_fence()?,
+                staging_buffer,
+                present_callback,
+                present_count: 0,
+            }),
+        })
+    }
+
+    pub fn offscreen_swapchain_def(&self) -> &RafxOffscreenSwapchainDef {
+        &self.offscreen_swapchain_def
+    }
+
+    pub fn swapchain_def(&self) -> &RafxSwapchainDef {
+        &self.offscreen_swapchain_def.swapchain_def
+    }
+
+    pub fn image_count(&self) -> usize {
+        self.swapchain_images.len()
+    }
+
+    pub fn format(&self) -> RafxFormat {
+        self.offscreen_swapchain_def.format
+    }
+
+    pub fn queue(&self) -> &RafxQueue {
+        &self.queue
+    }
+
+    /// Returns the texture that backs the given swapchain image
+    pub fn swapchain_image(
+        &self,
+        image_index: u32,
+    ) -> &RafxTexture {
+        &self.swapchain_images[image_index as usize]
+    }
+
+    fn texture_extents(offscreen_swapchain_def: &RafxOffscreenSwapchainDef) -> RafxExtents3D {
+        RafxExtents3D {
+            width: offscreen_swapchain_def.swapchain_def.width,
+            height: offscreen_swapchain_def.swapchain_def.height,
+            depth: 1,
+        }
+    }
+
+    fn create_swapchain_images(
+        device_context: &RafxDeviceContext,
+        offscreen_swapchain_def: &RafxOffscreenSwapchainDef,
+    ) -> RafxResult<Vec<RafxTexture>> {
+        let texture_def = RafxTextureDef {
+            extents: Self::texture_extents(offscreen_swapchain_def),
+            array_length: 1,
+            mip_count: 1,
+            format: offscreen_swapchain_def.format,
+            resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+            sample_count: RafxSampleCount::SampleCount1,
+            dimensions: RafxTextureDimensions::Dim2D,
+        };
+
+        (0..offscreen_swapchain_def.image_count)
+            .map(|_| device_context.create_texture(&texture_def))
+            .collect()
+    }
+
+    fn create_staging_buffer(
+        device_context: &RafxDeviceContext,
+        queue: &RafxQueue,
+        offscreen_swapchain_def: &RafxOffscreenSwapchainDef,
+    ) -> RafxResult<RafxBuffer> {
+        let layout = RafxTextureBufferLayout::new(
+            device_context.device_info(),
+            offscreen_swapchain_def.format,
+            Self::texture_extents(offscreen_swapchain_def),
+            0,
+        );
+
+        device_context.create_buffer(&RafxBufferDef {
+            size: layout.size,
+            memory_usage: RafxMemoryUsage::GpuToCpu,
+            queue_type: queue.queue_type(),
+            resource_type: RafxResourceType::BUFFER,
+            ..Default::default()
+        })
+    }
+
+    pub fn rebuild(
+        &mut self,
+        swapchain_def: &RafxSwapchainDef,
+    ) -> RafxResult<()> {
+        // The images may still be referenced by in-flight work
+        self.queue.wait_for_queue_idle()?;
+
+        let mut offscreen_swapchain_def = self.offscreen_swapchain_def.clone();
+        offscreen_swapchain_def.swapchain_def = swapchain_def.clone();
+
+        let swapchain_images =
+            Self::create_swapchain_images(&self.device_context, &offscreen_swapchain_def)?;
+
+        let mut present_state = self.present_state.lock().unwrap();
+        if present_state.staging_buffer.is_some() {
+            present_state.staging_buffer = Some(Self::create_staging_buffer(
+                &self.device_context,
+                &self.queue,
+                &offscreen_swapchain_def,
+            )?);
+        }
+
+        self.swapchain_images = swapchain_images;
+        self.offscreen_swapchain_def = offscreen_swapchain_def;
+        self.next_swapchain_image_index = 0;
+        Ok(())
+    }
+
+    fn acquire_next_image(&mut self) -> RafxSwapchainImage {
+        let swapchain_image_index = self.next_swapchain_image_index;
+        self.next_swapchain_image_index =
+            (self.next_swapchain_image_index + 1) % self.swapchain_images.len() as u32;
+
+        RafxSwapchainImage {
+            texture: self.swapchain_images[swapchain_image_index as usize].clone(),
+            swapchain_image_index,
+        }
+    }
+
+    // Records an empty command buffer so that a submit can signal the semaphore/fence
+    fn record_acquire_command_buffer(&mut self) -> RafxResult<()> {
+        // The previous acquire must be finished before the command buffer can be reused
+        self.acquire_fence.wait()?;
+        self.acquire_command_pool.reset_command_pool()?;
+        self.acquire_command_buffer.begin()?;
+        self.acquire_command_buffer.end()
+    }
+
+    /// Acquire the next image. The fence is signaled and already waited on when this returns.
+    pub fn acquire_next_image_fence(
+        &mut self,
+        fence: &RafxFence,
+    ) -> RafxResult<RafxSwapchainImage> {
+        self.record_acquire_command_buffer()?;
+        self.queue
+            .submit(&[&self.acquire_command_buffer], &[], &[], Some(fence))?;
+
+        // Waiting here keeps the acquire command buffer from being reset while it is in use
+        fence.wait()?;
+        Ok(self.acquire_next_image())
+    }
+
+    /// Acquire the next image. The semaphore is signaled by an empty submit to the queue.
+    pub fn acquire_next_image_semaphore(
+        &mut self,
+        semaphore: &RafxSemaphore,
+    ) -> RafxResult<RafxSwapchainImage> {
+        self.record_acquire_command_buffer()?;
+        self.queue.submit(
+            &[&self.acquire_command_buffer],
+            &[],
+            &[semaphore],
+            Some(&self.acquire_fence),
+        )?;
+
+        Ok(self.acquire_next_image())
+    }
+
+    /// Waits for the semaphores and blocks until the GPU is finished with the image. If a present
+    /// callback was provided, the image is read back and passed to it. The queue must be the one
+    /// that the swapchain was created with.
+    pub fn present(
+        &self,
+        queue: &RafxQueue,
+        wait_semaphores: &[&RafxSemaphore],
+        image_index: u32,
+    ) -> RafxResult<RafxPresentSuccessResult> {
+        if queue.queue_id() != self.queue.queue_id() {
+            Err("An offscreen swapchain must be presented on the queue it was created with")?;
+        }
+
+        let texture = self
+            .swapchain_images
+            .get(image_index as usize)
+            .ok_or("Presented an invalid offscreen swapchain image index")?;
+
+        let mut present_state = self.present_state.lock().unwrap();
+        let present_state = &mut *present_state;
+
+        present_state.command_pool.reset_command_pool()?;
+        present_state.command_buffer.begin()?;
+
+        if let Some(staging_buffer) = &present_state.staging_buffer {
+            let barrier = |src_state, dst_state| {
+                RafxTextureBarrier::state_transition(texture, src_state, dst_state)
+            };
+
+            present_state.command_buffer.cmd_resource_barrier(
+                &[],
+                &[barrier(
+                    RafxResourceState::PRESENT,
+                    RafxResourceState::COPY_SRC,
+                )],
+            )?;
+            present_state.command_buffer.cmd_copy_texture_to_buffer(
+                texture,
+                staging_buffer,
+                &RafxCmdCopyTextureToBufferParams {
+                    buffer_offset: 0,
+                    array_layer: 0,
+                    mip_level: 0,
+                },
+            )?;
+            present_state.command_buffer.cmd_resource_barrier(
+                &[],
+                &[barrier(
+                    RafxResourceState::COPY_SRC,
+                    RafxResourceState::PRESENT,
+                )],
+            )?;
+        }
+
+        present_state.command_buffer.end()?;
+        self.queue.submit(
+            &[&present_state.command_buffer],
+            wait_semaphores,
+            &[],
+            Some(&present_state.fence),
+        )?;
+        present_state.fence.wait()?;
+
+        let present_index = present_state.present_count;
+        present_state.present_count += 1;
+
+        if let (Some(staging_buffer), Some(present_callback)) = (
+            &present_state.staging_buffer,
+            &mut present_state.present_callback,
+        ) {
+            let layout = RafxTextureBufferLayout::new(
+                self.device_context.device_info(),
+                self.format(),
+                Self::texture_extents(&self.offscreen_swapchain_def),
+                0,
+            );
+
+            let buffer_begin = staging_buffer.map_buffer()?;
+            let data = unsafe { super::readback::read_unpadded_texels(buffer_begin, &layout) };
+            staging_buffer.unmap_buffer()?;
+
+            (present_callback)(RafxOffscreenPresentedImage {
+                swapchain_image_index: image_index,
+                present_index,
+                format: self.format(),
+                layout,
+                data,
+            });
+        }
+
+        Ok(RafxPresentSuccessResult::Success)
+    }
+}
+
+impl Drop for RafxOffscreenSwapchain {
+    fn drop(&mut self) {
+        log::trace!("destroying RafxOffscreenSwapchain");
+
+        // Don't free the images or command buffers while the GPU is using them
+        self.acquire_fence.wait().unwrap();
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod tests {
+    use super::*;
+    use crate::{RafxApi, RafxCmdCopyBufferToTextureParams, RafxQueueType, RafxSwapchainHelper};
+    use std::sync::Arc;
+
+    #[test]
+    fn offscreen_swapchain_presents_through_helper() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let presented_images = Arc::new(Mutex::new(Vec::default()));
+        let presented_images_clone = presented_images.clone();
+        let swapchain = device_context
+            .create_offscreen_swapchain(
+                &queue,
+                &RafxOffscreenSwapchainDef {
+                    swapchain_def: RafxSwapchainDef {
+                        width: 4,
+                        height: 2,
+                        enable_vsync: false,
+                    },
+                    format: RafxFormat::R8G8B8A8_UNORM,
+                    image_count: 2,
+                },
+                Some(Box::new(move |image: RafxOffscreenPresentedImage| {
+                    presented_images_clone.lock().unwrap().push(image);
+                })),
+            )
+            .unwrap();
+        assert_eq!(swapchain.image_count(), 2);
+        assert!(swapchain.offscreen_swapchain().is_some());
+
+        let mut swapchain_helper =
+            RafxSwapchainHelper::new(&device_context, swapchain, None).unwrap();
+
+        let mut command_pool = queue
+            .create_command_pool(&RafxCommandPoolDef { transient: true })
+            .unwrap();
+        let command_buffer = command_pool
+            .create_command_buffer(&RafxCommandBufferDef {
+                is_secondary: false,
+            })
+            .unwrap();
+
+        // Fill every frame with its frame number. The last frame resizes the swapchain.
+        let sizes = [(4, 2), (4, 2), (4, 2), (2, 2)];
+        for (frame_index, (width, height)) in sizes.iter().enumerate() {
+            let frame = swapchain_helper
+                .acquire_next_image(*width, *height, None)
+                .unwrap();
+
+            let texel_data = vec![frame_index as u8; (width * height * 4) as usize];
+            let buffer = device_context
+                .create_buffer(&RafxBufferDef::for_staging_buffer_data(
+                    &texel_data,
+                    RafxResourceType::BUFFER,
+                ))
+                .unwrap();
+            buffer.copy_to_host_visible_buffer(&texel_data).unwrap();
+
+            command_pool.reset_command_pool().unwrap();
+            command_buffer.begin().unwrap();
+            command_buffer
+                .cmd_copy_buffer_to_texture(
+                    &buffer,
+                    frame.swapchain_texture(),
+                    &RafxCmdCopyBufferToTextureParams {
+                        buffer_offset: 0,
+                        array_layer: 0,
+                        mip_level: 0,
+                    },
+                )
+                .unwrap();
+            command_buffer.end().unwrap();
+
+            frame.present(&queue, &[&command_buffer]).unwrap();
+        }
+
+        swapchain_helper
+            .wait_until_previous_frame_submitted()
+            .unwrap();
+        assert_eq!(swapchain_helper.swapchain_def().width, 2);
+
+        let presented_images = presented_images.lock().unwrap();
+        let image_indices: Vec<_> = presented_images
+            .iter()
+            .map(|x| x.swapchain_image_index)
+            .collect();
+        assert_eq!(image_indices, vec![0, 1, 0, 0]);
+
+        for (frame_index, image) in presented_images.iter().enumerate() {
+            let (width, height) = sizes[frame_index];
+            assert_eq!(image.present_index, frame_index as u64);
+            assert_eq!(image.layout.extents.width, width);
+            assert_eq!(
+                image.data,
+                vec![frame_index as u8; (width * height * 4) as usize]
+            );
+        }
+    }
+}
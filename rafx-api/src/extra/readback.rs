@@ -0,0 +1,373 @@
+use crate::{
+    RafxBuffer, RafxBufferDef, RafxCmdCopyTextureToBufferParams, RafxCommandBuffer,
+    RafxCommandBufferDef, RafxCommandPool, RafxCommandPoolDef, RafxDeviceContext, RafxFence,
+    RafxFenceStatus, RafxMemoryUsage, RafxQueue, RafxResourceState, RafxResourceType, RafxResult,
+    RafxTexture, RafxTextureBarrier, RafxTextureBufferLayout,
+};
+
+#[derive(PartialEq)]
+pub enum RafxReadbackState {
+    /// The readback is not submitted yet and more copies may be enqueued
+    Writable,
+
+    /// The commands have been sent to the GPU and the staging buffer is not readable yet
+    SentToGpu,
+
+    /// The GPU finished writing the staging buffer and the data may be read
+    Complete,
+}
+
+/// Identifies a texture copy enqueued in a `RafxReadback`. Pass it to `RafxReadback::read_texture`
+/// once the readback is complete.
+#[derive(Copy, Clone, Debug)]
+pub struct RafxReadbackTexture {
+    buffer_offset: u64,
+    layout: RafxTextureBufferLayout,
+}
+
+impl RafxReadbackTexture {
+    /// Offset of the copied texture data within the staging buffer
+    pub fn buffer_offset(&self) -> u64 {
+        self.buffer_offset
+    }
+
+    /// Layout of the copied texture data within the staging buffer
+    pub fn layout(&self) -> &RafxTextureBufferLayout {
+        &self.layout
+    }
+}
+
+/// Convenience struct for copying textures back to the CPU. Copies are recorded into a command
+/// buffer that writes into a host-visible staging buffer. After submitting, the data can be read
+/// once the fence signals. This is the counterpart to `RafxUpload`.
+pub struct RafxReadback {
+    queue: RafxQueue,
+    command_pool: RafxCommandPool,
+    command_buffer: RafxCommandBuffer,
+
+    buffer: RafxBuffer,
+
+    writable: bool,
+    fence: RafxFence,
+
+    buffer_begin: *mut u8,
+    bytes_reserved: u64,
+}
+
+unsafe impl Send for RafxReadback {}
+unsafe impl Sync for RafxReadback {}
+
+impl RafxReadback {
+    pub fn new(
+        device_context: &RafxDeviceContext,
+        queue: &RafxQueue,
+        buffer_size: u64,
+    ) -> RafxResult<Self> {
+        let mut command_pool =
+            queue.create_command_pool(&RafxCommandPoolDef { transient: true })?;
+        let command_buffer = command_pool.create_command_buffer(&RafxCommandBufferDef {
+            is_secondary: false,
+        })?;
+        command_buffer.begin()?;
+
+        let buffer = device_context.create_buffer(&RafxBufferDef {
+            size: buffer_size,
+            memory_usage: RafxMemoryUsage::GpuToCpu,
+            queue_type: queue.queue_type(),
+            resource_type: RafxResourceType::BUFFER,
+            ..Default::default()
+        })?;
+
+        let buffer_begin = buffer.map_buffer()?;
+
+        let fence = device_context.create_fence()?;
+
+        Ok(RafxReadback {
+            queue: queue.clone(),
+            command_pool,
+            command_buffer,
+            buffer,
+            writable: true,
+            fence,
+            buffer_begin,
+            bytes_reserved: 0,
+        })
+    }
+
+    /// Records a copy of a single mip level/array layer of the texture into the staging buffer.
+    /// The texture must be in `texture_state` when the commands execute, and it is returned to
+    /// that state afterwards.
+    pub fn enqueue_texture(
+        &mut self,
+        texture: &RafxTexture,
+        texture_state: RafxResourceState,
+        array_layer: u16,
+        mip_level: u8,
+    ) -> RafxResult<RafxReadbackTexture> {
+        if !self.writable {
+            Err("Readback has already been submitted")?;
+        }
+
+        let texture_def = texture.texture_def();
+        let device_context = self.queue.device_context();
+        let device_info = device_context.device_info();
+        let layout = RafxTextureBufferLayout::new(
+            device_info,
+            texture_def.format,
+            texture_def.extents,
+            mip_level,
+        );
+
+        // The copy offset must be a multiple of the texel/block size as well as the texture alignment
+        let alignment = least_common_multiple(
+            least_common_multiple(
+                device_info.upload_buffer_texture_alignment as u64,
+                texture_def.format.block_or_pixel_size_in_bytes() as u64,
+            ),
+            4,
+        );
+        let buffer_offset =
+            rafx_base::memory::round_size_up_to_alignment_u64(self.bytes_reserved, alignment);
+        if buffer_offset + layout.size > self.buffer_size() {
+            Err(format!(
+                "Readback buffer is full, {} bytes are required but only {} remain",
+                layout.size,
+                self.buffer_size() - self.bytes_reserved
+            ))?;
+        }
+
+        let barrier = |src_state, dst_state| RafxTextureBarrier {
+            array_slice: Some(array_layer),
+            mip_slice: Some(mip_level),
+            ..RafxTextureBarrier::state_transition(texture, src_state, dst_state)
+        };
+
+        self.command_buffer
+            .cmd_resource_barrier(&[], &[barrier(texture_state, RafxResourceState::COPY_SRC)])?;
+        self.command_buffer.cmd_copy_texture_to_buffer(
+            texture,
+            &self.buffer,
+            &RafxCmdCopyTextureToBufferParams {
+                buffer_offset,
+                array_layer,
+                mip_level,
+            },
+        )?;
+        self.command_buffer
+            .cmd_resource_barrier(&[], &[barrier(RafxResourceState::COPY_SRC, texture_state)])?;
+
+        self.bytes_reserved = buffer_offset + layout.size;
+
+        Ok(RafxReadbackTexture {
+            buffer_offset,
+            layout,
+        })
+    }
+
+    pub fn buffer_size(&self) -> u64 {
+        self.buffer.buffer_def().size
+    }
+
+    pub fn bytes_reserved(&self) -> u64 {
+        self.bytes_reserved
+    }
+
+    pub fn command_pool(&self) -> &RafxCommandPool {
+        &self.command_pool
+    }
+
+    pub fn command_buffer(&self) -> &RafxCommandBuffer {
+        &self.command_buffer
+    }
+
+    pub fn staging_buffer(&self) -> &RafxBuffer {
+        &self.buffer
+    }
+
+    pub fn queue(&self) -> &RafxQueue {
+        &self.queue
+    }
+
+    pub fn submit(&mut self) -> RafxResult<()> {
+        if self.writable {
+            self.command_buffer.end()?;
+            self.queue
+                .submit(&[&self.command_buffer], &[], &[], Some(&self.fence))?;
+            self.writable = false;
+        }
+
+        Ok(())
+    }
+
+    pub fn state(&self) -> RafxResult<RafxReadbackState> {
+        let state = if self.writable {
+            RafxReadbackState::Writable
+        } else {
+            if self.fence.get_fence_status()? != RafxFenceStatus::Incomplete {
+                RafxReadbackState::Complete
+            } else {
+                RafxReadbackState::SentToGpu
+            }
+        };
+
+        Ok(state)
+    }
+
+    /// Submits the readback if necessary and blocks until the GPU has finished writing the
+    /// staging buffer
+    pub fn block_until_readback_complete(&mut self) -> RafxResult<()> {
+        self.submit()?;
+        self.fence.wait()
+    }
+
+    /// Returns the texels of a copied texture with row and image padding removed. Rows of
+    /// compressed formats are rows of blocks. Fails if the readback is not complete.
+    pub fn read_texture(
+        &self,
+        texture: &RafxReadbackTexture,
+    ) -> RafxResult<Vec<u8>> {
+        if self.state()? != RafxReadbackState::Complete {
+            Err("Readback is not complete")?;
+        }
+
+        unsafe {
+            Ok(read_unpadded_texels(
+                self.buffer_begin.add(texture.buffer_offset as usize),
+                &texture.layout,
+            ))
+        }
+    }
+
+    fn wait_for_idle(&self) -> RafxResult<()> {
+        if self.writable {
+            Ok(())
+        } else {
+            self.fence.wait()
+        }
+    }
+}
+
+// Copies texels laid out according to the given layout into a tightly packed vec. The pointer
+// must be valid for layout.size bytes.
+pub(crate) unsafe fn read_unpadded_texels(
+    base: *const u8,
+    layout: &RafxTextureBufferLayout,
+) -> Vec<u8> {
+    let row_size = layout.bytes_per_row_unpadded as usize;
+    let mut data = Vec::with_capacity(
+        row_size * layout.rows_per_image as usize * layout.extents.depth as usize,
+    );
+
+    for z in 0..layout.extents.depth as usize {
+        for row in 0..layout.rows_per_image as usize {
+            let src =
+                base.add(z * layout.bytes_per_image as usize + row * layout.bytes_per_row as usize);
+            data.extend_from_slice(std::slice::from_raw_parts(src, row_size));
+        }
+    }
+
+    data
+}
+
+impl Drop for RafxReadback {
+    fn drop(&mut self) {
+        log::trace!("destroying RafxReadback");
+
+        // If the copy is in flight, wait for it to complete
+        self.wait_for_idle().unwrap();
+
+        self.buffer.unmap_buffer().unwrap();
+
+        // buffer, command pool, and fence are destroyed by dropping them
+
+        log::trace!("destroyed RafxReadback");
+    }
+}
+
+fn least_common_multiple(
+    a: u64,
+    b: u64,
+) -> u64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        let remainder = x % y;
+        x = y;
+        y = remainder;
+    }
+
+    a / x * b
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod tests {
+    use super::*;
+    use crate::extra::upload::RafxUpload;
+    use crate::{
+        RafxApi, RafxCmdCopyBufferToTextureParams, RafxExtents3D, RafxFormat, RafxQueueType,
+        RafxTextureDef,
+    };
+
+    #[test]
+    fn readback_texture_mip_and_layer() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        // 6x4 with 2 mips and 2 layers. Only mip 1 of layer 1 (3x2) is written
+        let texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 6,
+                    height: 4,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                mip_count: 2,
+                array_length: 2,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let texture_data: Vec<u8> = (0..24).collect();
+        let mut upload = RafxUpload::new(&device_context, &queue, 1024).unwrap();
+        let offset = upload.push(&texture_data, 256).unwrap();
+        upload
+            .command_buffer()
+            .cmd_copy_buffer_to_texture(
+                upload.staging_buffer(),
+                &texture,
+                &RafxCmdCopyBufferToTextureParams {
+                    buffer_offset: offset,
+                    array_layer: 1,
+                    mip_level: 1,
+                },
+            )
+            .unwrap();
+        upload.submit().unwrap();
+
+        let mut readback = RafxReadback::new(&device_context, &queue, 1024).unwrap();
+        let mip_0 = readback
+            .enqueue_texture(&texture, RafxResourceState::SHADER_RESOURCE, 0, 0)
+            .unwrap();
+        let mip_1 = readback
+            .enqueue_texture(&texture, RafxResourceState::SHADER_RESOURCE, 1, 1)
+            .unwrap();
+        assert!(readback.read_texture(&mip_1).is_err());
+        readback.block_until_readback_complete().unwrap();
+
+        assert_eq!(readback.read_texture(&mip_0).unwrap(), vec![0; 96]);
+        assert_eq!(readback.read_texture(&mip_1).unwrap(), texture_data);
+    }
+}
@@ -165,7 +165,7 @@ impl RafxPresentableFrame {
             Some(frame_fence),
         )?;
 
-        let wait_semaphores = [&shared_state.image_available_semaphores[sync_frame_index]];
+        let wait_semaphores = [&shared_state.render_finished_semaphores[sync_frame_index]];
         let swapchain = shared_state.swapchain.lock().unwrap();
 
         let result = queue.present(
@@ -1,7 +1,8 @@
 use crate::{
     RafxBuffer, RafxBufferDef, RafxCommandBuffer, RafxCommandBufferDef, RafxCommandPool,
     RafxCommandPoolDef, RafxDeviceContext, RafxError, RafxFence, RafxFenceStatus, RafxMemoryUsage,
-    RafxQueue, RafxQueueType, RafxResourceType, RafxResult,
+    RafxQueue, RafxQueueType, RafxResourceType, RafxResult, RafxTimelineSemaphore,
+    RafxTimelineSemaphoreValue,
 };
 
 // Based on UploadHeap in cauldron
@@ -222,10 +223,24 @@ impl RafxUpload {
     }
 
     pub fn submit(&mut self) -> RafxResult<()> {
+        self.submit_and_signal(&[])
+    }
+
+    // Submits the upload, additionally signaling timeline semaphore values when it completes
+    fn submit_and_signal(
+        &mut self,
+        signal_timeline_semaphores: &[RafxTimelineSemaphoreValue],
+    ) -> RafxResult<()> {
         if self.writable {
             self.command_buffer.end()?;
-            self.queue
-                .submit(&[&self.command_buffer], &[], &[], Some(&self.fence))?;
+            self.queue.submit_with_timeline_semaphores(
+                &[&self.command_buffer],
+                &[],
+                &[],
+                &[],
+                signal_timeline_semaphores,
+                Some(&self.fence),
+            )?;
             self.writable = false;
         }
 
@@ -287,8 +302,16 @@ pub enum RafxTransferUploadState {
     Complete,
 }
 
+// Value the transfer queue signals on the timeline semaphore when the transfer completes
+const TRANSFER_COMPLETE_TIMELINE_VALUE: u64 = 1;
+
 /// A state machine and associated buffers/synchronization primitives to simplify uploading resources
 /// to the GPU via a transfer queue, and then submitting a memory barrier to the graphics queue
+///
+/// If timeline semaphores are supported, the dst queue waits for the transfer queue on the GPU, so
+/// `submit_dst()` may be called as soon as the transfer has been submitted. Otherwise the transfer
+/// must complete (`RafxTransferUploadState::PendingSubmitDstQueue`) before the dst queue is
+/// submitted.
 pub struct RafxTransferUpload {
     upload: RafxUpload,
 
@@ -296,6 +319,8 @@ pub struct RafxTransferUpload {
     dst_command_pool: RafxCommandPool,
     dst_command_buffer: RafxCommandBuffer,
 
+    // None if timeline semaphores are not supported
+    transfer_timeline_semaphore: Option<RafxTimelineSemaphore>,
     dst_fence: RafxFence,
     sent_to_dst_queue: bool,
 }
@@ -320,6 +345,13 @@ impl RafxTransferUpload {
 
         let upload = RafxUpload::new(device_context, transfer_queue, size)?;
 
+        let transfer_timeline_semaphore =
+            if device_context.device_info().supports_timeline_semaphores {
+                Some(device_context.create_timeline_semaphore(0)?)
+            } else {
+                None
+            };
+
         let dst_fence = device_context.create_fence()?;
 
         Ok(RafxTransferUpload {
@@ -327,6 +359,7 @@ impl RafxTransferUpload {
             dst_queue: dst_queue.clone(),
             dst_command_pool,
             dst_command_buffer,
+            transfer_timeline_semaphore,
             dst_fence,
             sent_to_dst_queue: false,
         })
@@ -390,12 +423,44 @@ impl RafxTransferUpload {
         &self.dst_queue
     }
 
+    /// True if the dst queue waits for the transfer queue on the GPU. If so, `submit_dst()` may be
+    /// called while the upload is still in `RafxTransferUploadState::SentToTransferQueue`
+    pub fn supports_timeline_semaphores(&self) -> bool {
+        self.transfer_timeline_semaphore.is_some()
+    }
+
     pub fn submit_transfer(&mut self) -> RafxResult<()> {
-        self.upload.submit()
+        if let Some(transfer_timeline_semaphore) = &self.transfer_timeline_semaphore {
+            self.upload.submit_and_signal(&[RafxTimelineSemaphoreValue {
+                semaphore: transfer_timeline_semaphore,
+                value: TRANSFER_COMPLETE_TIMELINE_VALUE,
+            }])
+        } else {
+            self.upload.submit()
+        }
     }
 
     pub fn submit_dst(&mut self) -> RafxResult<()> {
-        if self.state()? == RafxTransferUploadState::PendingSubmitDstQueue {
+        let state = self.state()?;
+        if let Some(transfer_timeline_semaphore) = &self.transfer_timeline_semaphore {
+            if state == RafxTransferUploadState::SentToTransferQueue
+                || state == RafxTransferUploadState::PendingSubmitDstQueue
+            {
+                self.dst_command_buffer.end()?;
+                self.dst_queue.submit_with_timeline_semaphores(
+                    &[&self.dst_command_buffer],
+                    &[],
+                    &[],
+                    &[RafxTimelineSemaphoreValue {
+                        semaphore: transfer_timeline_semaphore,
+                        value: TRANSFER_COMPLETE_TIMELINE_VALUE,
+                    }],
+                    &[],
+                    Some(&self.dst_fence),
+                )?;
+                self.sent_to_dst_queue = true;
+            }
+        } else if state == RafxTransferUploadState::PendingSubmitDstQueue {
             self.dst_command_buffer.end()?;
             self.dst_queue
                 .submit(&[&self.dst_command_buffer], &[], &[], Some(&self.dst_fence))?;
@@ -434,9 +499,11 @@ impl RafxTransferUpload {
     pub fn block_until_upload_complete(&mut self) -> RafxResult<()> {
         log::trace!("wait on transfer queue {:?}", self.upload.queue);
         self.submit_transfer()?;
-        loop {
-            if self.state()? == RafxTransferUploadState::PendingSubmitDstQueue {
-                break;
+        if self.transfer_timeline_semaphore.is_none() {
+            loop {
+                if self.state()? == RafxTransferUploadState::PendingSubmitDstQueue {
+                    break;
+                }
             }
         }
 
@@ -463,3 +530,149 @@ impl Drop for RafxTransferUpload {
         log::trace!("destroyed RafxUpload");
     }
 }
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod tests {
+    use super::*;
+    use crate::{
+        RafxApi, RafxCmdCopyBufferToTextureParams, RafxExtents3D, RafxFormat, RafxTextureDef,
+    };
+
+    #[test]
+    fn transfer_upload_to_buffer_and_texture() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let transfer_queue = device_context
+            .create_queue(RafxQueueType::Transfer)
+            .unwrap();
+        let graphics_queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let buffer_data: Vec<u8> = (0..64).collect();
+        let texture_data: Vec<u8> = (0..48).map(|x| 255 - x).collect();
+
+        let dst_buffer = device_context
+            .create_buffer(&RafxBufferDef {
+                size: buffer_data.len() as u64,
+                memory_usage: RafxMemoryUsage::GpuOnly,
+                resource_type: RafxResourceType::VERTEX_BUFFER,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // 3 pixels wide so that rows are not a multiple of the texture alignment
+        let dst_texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 3,
+                    height: 4,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let mut upload =
+            RafxTransferUpload::new(&device_context, &transfer_queue, &graphics_queue, 1024)
+                .unwrap();
+
+        let buffer_offset = upload.push(&buffer_data, 16).unwrap();
+        let texture_offset = upload.push(&texture_data, 16).unwrap();
+        assert_eq!(texture_offset, 64);
+
+        upload
+            .transfer_command_buffer()
+            .cmd_copy_buffer_to_buffer(
+                upload.staging_buffer(),
+                &dst_buffer,
+                buffer_offset,
+                0,
+                buffer_data.len() as u64,
+            )
+            .unwrap();
+        upload
+            .transfer_command_buffer()
+            .cmd_copy_buffer_to_texture(
+                upload.staging_buffer(),
+                &dst_texture,
+                &RafxCmdCopyBufferToTextureParams {
+                    buffer_offset: texture_offset,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        upload.block_until_upload_complete().unwrap();
+
+        let empty_buffer = dst_buffer.empty_buffer().unwrap();
+        let buffer_contents = unsafe {
+            std::slice::from_raw_parts(empty_buffer.host_memory_ptr(), buffer_data.len())
+        };
+        assert_eq!(buffer_contents, &buffer_data[..]);
+
+        let texture_contents = dst_texture.empty_texture().unwrap().read_subresource(0, 0);
+        assert_eq!(texture_contents, texture_data);
+    }
+
+    #[test]
+    fn transfer_upload_submits_dst_without_waiting_for_transfer() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let transfer_queue = device_context
+            .create_queue(RafxQueueType::Transfer)
+            .unwrap();
+        let graphics_queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let mut upload =
+            RafxTransferUpload::new(&device_context, &transfer_queue, &graphics_queue, 1024)
+                .unwrap();
+        assert_eq!(
+            upload.supports_timeline_semaphores(),
+            device_context.device_info().supports_timeline_semaphores
+        );
+        assert!(upload.supports_timeline_semaphores());
+
+        upload.push(&[1, 2, 3, 4], 4).unwrap();
+        upload.submit_transfer().unwrap();
+        upload.submit_dst().unwrap();
+        assert!(upload.state().unwrap() != RafxTransferUploadState::PendingSubmitDstQueue);
+
+        // The empty backend runs submits immediately, so check that the dst submit is ordered
+        // behind the transfer on the GPU by waiting on the value the transfer submit signaled
+        let transfer_values = transfer_queue
+            .empty_queue()
+            .unwrap()
+            .last_submitted_timeline_values();
+        let dst_values = graphics_queue
+            .empty_queue()
+            .unwrap()
+            .last_submitted_timeline_values();
+        assert!(transfer_values.wait_values.is_empty());
+        assert_eq!(
+            transfer_values.signal_values,
+            vec![TRANSFER_COMPLETE_TIMELINE_VALUE]
+        );
+        assert_eq!(
+            dst_values.wait_values,
+            vec![TRANSFER_COMPLETE_TIMELINE_VALUE]
+        );
+        assert!(dst_values.signal_values.is_empty());
+
+        upload.block_until_upload_complete().unwrap();
+        assert!(upload.state().unwrap() == RafxTransferUploadState::Complete);
+    }
+}
@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxFenceEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxFenceGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxFenceMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -28,9 +34,15 @@ pub enum RafxFence {
     Metal(RafxFenceMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxFenceEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxFenceGles3),
 }
 
 impl RafxFence {
@@ -40,17 +52,30 @@ impl RafxFence {
     /// completed state. In other words, the Complete status can only be returned one time unless the
     /// fence is submitted again.
     pub fn get_fence_status(&self) -> RafxResult<RafxFenceStatus> {
-        match self {
+        let fence_status = match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxFence::Vk(inner) => inner.get_fence_status(),
             #[cfg(feature = "rafx-metal")]
             RafxFence::Metal(inner) => inner.get_fence_status(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxFence::Empty(inner) => inner.get_fence_status(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(inner) => inner.get_fence_status(),
+        }?;
+
+        #[cfg(feature = "validate-resource-states")]
+        if fence_status == RafxFenceStatus::Complete {
+            self.on_signaled();
         }
+
+        Ok(fence_status)
     }
 
     /// Wait for the fence to be signaled as complete by the GPU
@@ -62,12 +87,69 @@ impl RafxFence {
             RafxFence::Metal(inner) => inner.wait(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxFence::Empty(inner) => inner.wait(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(inner) => inner.wait(),
+        }?;
+
+        #[cfg(feature = "validate-resource-states")]
+        self.on_signaled();
+
+        Ok(())
+    }
+
+    // Used internally to identify the fence for validation
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn fence_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxFence::Vk(inner) => inner.fence_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxFence::Metal(inner) => inner.fence_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxFence::Empty(inner) => inner.fence_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(inner) => inner.fence_id(),
         }
     }
 
+    // Work submitted before the fence was signaled is no longer in use by the GPU
+    #[cfg(feature = "validate-resource-states")]
+    pub(crate) fn on_signaled(&self) {
+        let resource_state_tracker = match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxFence::Vk(inner) => inner.device_context().resource_state_tracker(),
+            #[cfg(feature = "rafx-metal")]
+            RafxFence::Metal(inner) => inner.device_context().resource_state_tracker(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxFence::Empty(inner) => inner.device_context().resource_state_tracker(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(inner) => inner.device_context().resource_state_tracker(),
+        };
+
+        resource_state_tracker.on_fence_signaled(self.fence_id());
+    }
+
     /// Get the underlying vulkan API object. This provides access to any internally created
     /// vulkan objects.
     #[cfg(feature = "rafx-vulkan")]
@@ -79,9 +161,15 @@ impl RafxFence {
             RafxFence::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxFence::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(_) => None,
         }
     }
 
@@ -96,9 +184,15 @@ impl RafxFence {
             RafxFence::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxFence::Empty(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(inner) => None,
         }
     }
 
@@ -106,7 +200,11 @@ impl RafxFence {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_fence(&self) -> Option<&RafxFenceEmpty> {
         match self {
@@ -116,9 +214,38 @@ impl RafxFence {
             RafxFence::Metal(inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxFence::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_fence(&self) -> Option<&RafxFenceGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxFence::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxFence::Metal(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxFence::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxFence::Empty(_) => None,
         }
     }
 }
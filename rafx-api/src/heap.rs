@@ -0,0 +1,168 @@
+#[cfg(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+))]
+use crate::empty::RafxHeapEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxHeapGles3;
+#[cfg(feature = "rafx-metal")]
+use crate::metal::RafxHeapMetal;
+#[cfg(feature = "rafx-vulkan")]
+use crate::vulkan::RafxHeapVulkan;
+use crate::RafxHeapDef;
+
+/// A block of memory that buffers and textures can be placed in at explicit offsets. Resources
+/// whose lifetimes do not overlap may be placed at overlapping offsets to share memory. This is
+/// implemented with a single vk_mem allocation on vulkan and a placement MTLHeap on metal.
+///
+/// Use `RafxDeviceContext::texture_memory_requirements`/`buffer_memory_requirements` to find the
+/// size and alignment of a resource, and `RafxDeviceContext::create_texture_in_heap`/
+/// `create_buffer_in_heap` to create it. Placed resources keep the heap alive. The contents of
+/// aliased memory are undefined when switching between resources, and the end-user is responsible
+/// for barriers between them.
+///
+/// Heaps must not be dropped if they are in use by the GPU.
+#[derive(Clone, Debug)]
+pub enum RafxHeap {
+    #[cfg(feature = "rafx-vulkan")]
+    Vk(RafxHeapVulkan),
+    #[cfg(feature = "rafx-metal")]
+    Metal(RafxHeapMetal),
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    Empty(RafxHeapEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxHeapGles3),
+}
+
+impl RafxHeap {
+    /// Return the metadata used to create the heap
+    pub fn heap_def(&self) -> &RafxHeapDef {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxHeap::Vk(inner) => inner.heap_def(),
+            #[cfg(feature = "rafx-metal")]
+            RafxHeap::Metal(inner) => inner.heap_def(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxHeap::Empty(inner) => inner.heap_def(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxHeap::Gles3(inner) => inner.heap_def(),
+        }
+    }
+
+    /// Get the underlying vulkan API object. This provides access to any internally created
+    /// vulkan objects.
+    #[cfg(feature = "rafx-vulkan")]
+    pub fn vk_heap(&self) -> Option<&RafxHeapVulkan> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxHeap::Vk(inner) => Some(inner),
+            #[cfg(feature = "rafx-metal")]
+            RafxHeap::Metal(_) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxHeap::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxHeap::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying metal API object. This provides access to any internally created
+    /// metal objects.
+    #[cfg(feature = "rafx-metal")]
+    pub fn metal_heap(&self) -> Option<&RafxHeapMetal> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxHeap::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxHeap::Metal(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxHeap::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxHeap::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying empty API object. This provides access to any internally created
+    /// empty objects.
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn empty_heap(&self) -> Option<&RafxHeapEmpty> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxHeap::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxHeap::Metal(_) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxHeap::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxHeap::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_heap(&self) -> Option<&RafxHeapGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxHeap::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxHeap::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxHeap::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxHeap::Empty(_) => None,
+        }
+    }
+}
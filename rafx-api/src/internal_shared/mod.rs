@@ -1,17 +1,137 @@
-#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
 use crate::{
-    RafxImmutableSamplerKey, RafxImmutableSamplers, RafxPipelineType, RafxResult,
-    RafxRootSignatureDef, RafxShaderResource, RafxShaderStageFlags,
+    RafxBindlessArray, RafxBindlessArrayKey, RafxDescriptorUpdate, RafxDeviceInfo, RafxError,
+    RafxImmutableSamplerKey, RafxImmutableSamplers, RafxMemoryStatistics, RafxMemoryUsage,
+    RafxMemoryUsageStatistics, RafxPipelineType, RafxResourceType, RafxResult,
+    RafxRootSignatureDef, RafxShaderResource, RafxShaderStageFlags, RafxTextureDef,
+    RafxTextureViewDef,
 };
+use std::sync::atomic::{AtomicU64, Ordering};
 
-#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
 pub(crate) static NEXT_TEXTURE_ID: std::sync::atomic::AtomicU32 =
     std::sync::atomic::AtomicU32::new(1);
 
-#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
+// Buffers are identified for resource state validation and capturing. Fences are only identified
+// for resource state validation
+#[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+pub(crate) static NEXT_BUFFER_ID: std::sync::atomic::AtomicU32 =
+    std::sync::atomic::AtomicU32::new(1);
+
+#[cfg(feature = "validate-resource-states")]
+pub(crate) static NEXT_FENCE_ID: std::sync::atomic::AtomicU32 =
+    std::sync::atomic::AtomicU32::new(1);
+
+#[cfg(feature = "validate-resource-states")]
+mod resource_state_validation;
+#[cfg(feature = "validate-resource-states")]
+pub(crate) use resource_state_validation::*;
+
 use fnv::FnvHashMap;
 
-#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
+#[derive(Default)]
+struct RafxMemoryUsageCounters {
+    buffer_count: AtomicU64,
+    buffer_bytes: AtomicU64,
+    texture_count: AtomicU64,
+    texture_bytes: AtomicU64,
+}
+
+impl RafxMemoryUsageCounters {
+    fn statistics(&self) -> RafxMemoryUsageStatistics {
+        RafxMemoryUsageStatistics {
+            buffer_count: self.buffer_count.load(Ordering::Relaxed),
+            buffer_bytes: self.buffer_bytes.load(Ordering::Relaxed),
+            texture_count: self.texture_count.load(Ordering::Relaxed),
+            texture_bytes: self.texture_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// Counts live buffer/texture allocations per RafxMemoryUsage. Each backend's device context owns
+// one, and buffers/textures report to it when their memory is allocated and freed.
+#[derive(Default)]
+pub(crate) struct RafxMemoryUsageTracker {
+    unknown: RafxMemoryUsageCounters,
+    gpu_only: RafxMemoryUsageCounters,
+    cpu_only: RafxMemoryUsageCounters,
+    cpu_to_gpu: RafxMemoryUsageCounters,
+    gpu_to_cpu: RafxMemoryUsageCounters,
+}
+
+impl RafxMemoryUsageTracker {
+    fn counters(
+        &self,
+        memory_usage: RafxMemoryUsage,
+    ) -> &RafxMemoryUsageCounters {
+        match memory_usage {
+            RafxMemoryUsage::Unknown => &self.unknown,
+            RafxMemoryUsage::GpuOnly => &self.gpu_only,
+            RafxMemoryUsage::CpuOnly => &self.cpu_only,
+            RafxMemoryUsage::CpuToGpu => &self.cpu_to_gpu,
+            RafxMemoryUsage::GpuToCpu => &self.gpu_to_cpu,
+        }
+    }
+
+    pub(crate) fn on_buffer_allocated(
+        &self,
+        memory_usage: RafxMemoryUsage,
+        size_in_bytes: u64,
+    ) {
+        let counters = self.counters(memory_usage);
+        counters.buffer_count.fetch_add(1, Ordering::Relaxed);
+        counters
+            .buffer_bytes
+            .fetch_add(size_in_bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn on_buffer_freed(
+        &self,
+        memory_usage: RafxMemoryUsage,
+        size_in_bytes: u64,
+    ) {
+        let counters = self.counters(memory_usage);
+        counters.buffer_count.fetch_sub(1, Ordering::Relaxed);
+        counters
+            .buffer_bytes
+            .fetch_sub(size_in_bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn on_texture_allocated(
+        &self,
+        memory_usage: RafxMemoryUsage,
+        size_in_bytes: u64,
+    ) {
+        let counters = self.counters(memory_usage);
+        counters.texture_count.fetch_add(1, Ordering::Relaxed);
+        counters
+            .texture_bytes
+            .fetch_add(size_in_bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn on_texture_freed(
+        &self,
+        memory_usage: RafxMemoryUsage,
+        size_in_bytes: u64,
+    ) {
+        let counters = self.counters(memory_usage);
+        counters.texture_count.fetch_sub(1, Ordering::Relaxed);
+        counters
+            .texture_bytes
+            .fetch_sub(size_in_bytes, Ordering::Relaxed);
+    }
+
+    // Heap statistics are platform-specific and must be filled in by the caller
+    pub(crate) fn memory_statistics(&self) -> RafxMemoryStatistics {
+        RafxMemoryStatistics {
+            heaps: vec![],
+            unknown: self.unknown.statistics(),
+            gpu_only: self.gpu_only.statistics(),
+            cpu_only: self.cpu_only.statistics(),
+            cpu_to_gpu: self.cpu_to_gpu.statistics(),
+            gpu_to_cpu: self.gpu_to_cpu.statistics(),
+        }
+    }
+}
+
 pub(crate) fn find_immutable_sampler_index(
     samplers: &[RafxImmutableSamplers],
     name: &Option<String>,
@@ -38,7 +158,224 @@ pub(crate) fn find_immutable_sampler_index(
     None
 }
 
-#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
+pub(crate) fn find_bindless_array_index(
+    bindless_arrays: &[RafxBindlessArray],
+    name: &Option<String>,
+    set_index: u32,
+    binding: u32,
+) -> Option<usize> {
+    for (bindless_array_index, bindless_array) in bindless_arrays.iter().enumerate() {
+        match &bindless_array.key {
+            RafxBindlessArrayKey::Name(bindless_array_name) => {
+                if let Some(name) = name {
+                    if name == bindless_array_name {
+                        return Some(bindless_array_index);
+                    }
+                }
+            }
+            RafxBindlessArrayKey::Binding(bindless_array_set_index, bindless_array_binding) => {
+                if set_index == *bindless_array_set_index && binding == *bindless_array_binding {
+                    return Some(bindless_array_index);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// Resizes merged resources that the root signature declares as bindless arrays to their declared
+// maximum element count. Returns a flag per resource indicating if it is a bindless array.
+pub(crate) fn apply_bindless_arrays(
+    root_signature_def: &RafxRootSignatureDef,
+    device_info: &RafxDeviceInfo,
+    merged_resources: &mut [RafxShaderResource],
+) -> RafxResult<Vec<bool>> {
+    let mut is_bindless = vec![false; merged_resources.len()];
+    if root_signature_def.bindless_arrays.is_empty() {
+        return Ok(is_bindless);
+    }
+
+    if !device_info.supports_bindless_descriptors {
+        Err(RafxError::invalid_argument(
+            "bindless_arrays",
+            "The root signature declares bindless arrays but the device does not support bindless descriptors",
+        ))?;
+    }
+
+    let mut bindless_array_found = vec![false; root_signature_def.bindless_arrays.len()];
+    for (resource_index, resource) in merged_resources.iter_mut().enumerate() {
+        let bindless_array_index = find_bindless_array_index(
+            root_signature_def.bindless_arrays,
+            &resource.name,
+            resource.set_index,
+            resource.binding,
+        );
+
+        let bindless_array_index = match bindless_array_index {
+            Some(bindless_array_index) => bindless_array_index,
+            None => continue,
+        };
+
+        let bindless_array = &root_signature_def.bindless_arrays[bindless_array_index];
+        bindless_array_found[bindless_array_index] = true;
+
+        let supported_resource_type = resource.resource_type == RafxResourceType::TEXTURE
+            || resource.resource_type == RafxResourceType::TEXTURE_READ_WRITE
+            || resource.resource_type == RafxResourceType::BUFFER
+            || resource.resource_type == RafxResourceType::BUFFER_READ_WRITE;
+        if !supported_resource_type {
+            Err(RafxError::reflection_mismatch(
+                resource.name.as_deref(),
+                format!(
+                    "Descriptor (set={:?} binding={:?}) named {:?} is declared as a bindless array but has type {:?}. Only textures and storage buffers may be bindless",
+                    resource.set_index, resource.binding, resource.name, resource.resource_type
+                ),
+            ))?;
+        }
+
+        // An element_count of 0 is an unbounded array in the shader
+        if bindless_array.max_element_count < resource.element_count {
+            Err(RafxError::reflection_mismatch(
+                resource.name.as_deref(),
+                format!(
+                    "Descriptor (set={:?} binding={:?}) named {:?} has {} elements in the shader, which exceeds the bindless array's max_element_count {}",
+                    resource.set_index,
+                    resource.binding,
+                    resource.name,
+                    resource.element_count,
+                    bindless_array.max_element_count
+                ),
+            ))?;
+        }
+
+        if bindless_array.max_element_count == 0
+            || bindless_array.max_element_count > device_info.max_bindless_descriptor_count
+        {
+            Err(RafxError::invalid_argument(
+                "max_element_count",
+                format!(
+                    "Bindless array for descriptor (set={:?} binding={:?}) named {:?} has max_element_count {} but it must be between 1 and {}",
+                    resource.set_index,
+                    resource.binding,
+                    resource.name,
+                    bindless_array.max_element_count,
+                    device_info.max_bindless_descriptor_count
+                ),
+            ))?;
+        }
+
+        resource.element_count = bindless_array.max_element_count;
+        is_bindless[resource_index] = true;
+    }
+
+    if let Some(missing_index) = bindless_array_found.iter().position(|x| !*x) {
+        let key = &root_signature_def.bindless_arrays[missing_index].key;
+        let (name, message) = match key {
+            RafxBindlessArrayKey::Name(name) => (
+                Some(*name),
+                format!(
+                    "Bindless array {:?} does not match any descriptor in the root signature's shaders",
+                    name
+                ),
+            ),
+            RafxBindlessArrayKey::Binding(set_index, binding) => (
+                None,
+                format!(
+                    "Bindless array (set={:?} binding={:?}) does not match any descriptor in the root signature's shaders",
+                    set_index, binding
+                ),
+            ),
+        };
+        Err(RafxError::reflection_mismatch(name, message))?;
+    }
+
+    Ok(is_bindless)
+}
+
+// Sparse updates are allowed (only the provided elements are written), but they must stay within
+// the descriptor's array
+pub(crate) fn verify_descriptor_update_range(
+    update: &RafxDescriptorUpdate,
+    descriptor_name: &Option<String>,
+    resource_type: RafxResourceType,
+    element_count: u32,
+) -> RafxResult<()> {
+    let elements = &update.elements;
+    let update_element_count = elements
+        .textures
+        .map(|x| x.len())
+        .or_else(|| elements.texture_views.map(|x| x.len()))
+        .or_else(|| elements.samplers.map(|x| x.len()))
+        .or_else(|| elements.buffers.map(|x| x.len()))
+        .unwrap_or(0) as u32;
+
+    if update.dst_element_offset as u64 + update_element_count as u64 > element_count as u64 {
+        Err(RafxError::invalid_argument(
+            "dst_element_offset",
+            format!(
+                "Descriptor {:?} (name: {:?} type: {:?}) has {} elements but the update writes elements {}..{}",
+                update.descriptor_key,
+                descriptor_name,
+                resource_type,
+                element_count,
+                update.dst_element_offset,
+                update.dst_element_offset as u64 + update_element_count as u64
+            ),
+        ))?;
+    }
+
+    Ok(())
+}
+
+// A render target binding may render to a view instead of the texture's own views. `is_same_texture`
+// is whether the view was created from the binding's texture
+pub(crate) fn verify_render_target_view(
+    texture_def: &RafxTextureDef,
+    texture_view_def: &RafxTextureViewDef,
+    is_same_texture: bool,
+    mip_slice: Option<u8>,
+    array_slice: Option<u16>,
+) -> RafxResult<()> {
+    if !is_same_texture {
+        Err(RafxError::invalid_argument(
+            "texture_view",
+            "The render target's texture_view must be a view of its texture",
+        ))?;
+    }
+
+    if mip_slice.is_some() || array_slice.is_some() {
+        Err(RafxError::invalid_argument(
+            "texture_view",
+            "mip_slice and array_slice must be None when binding a texture_view as a render target",
+        ))?;
+    }
+
+    let mip_range = texture_view_def.mip_range.as_ref().unwrap();
+    if mip_range.end - mip_range.start != 1 {
+        Err(RafxError::invalid_argument(
+            "texture_view",
+            format!(
+                "A texture view bound as a render target must cover a single mip level, it covers {:?}",
+                mip_range
+            ),
+        ))?;
+    }
+
+    let format = texture_view_def.format.unwrap();
+    if texture_def.format.has_depth_or_stencil() && format != texture_def.format {
+        Err(RafxError::invalid_argument(
+            "texture_view",
+            format!(
+                "A {:?} texture can only be bound as a render target using its own format, the view uses {:?}",
+                texture_def.format, format
+            ),
+        ))?;
+    }
+
+    Ok(())
+}
+
 pub(crate) fn merge_resources<'a>(
     root_signature_def: &RafxRootSignatureDef<'a>
 ) -> RafxResult<(
@@ -130,6 +467,7 @@ pub(crate) fn merge_resources<'a>(
                 // }
 
                 existing_resource.used_in_shader_stages |= resource.used_in_shader_stages;
+                existing_resource.merge_gles_names(resource);
             } else {
                 //
                 // We have not seen a resource by this name yet or the name is not set. See if
@@ -166,6 +504,7 @@ pub(crate) fn merge_resources<'a>(
                         resource.used_in_shader_stages
                     );
                     existing_resource.used_in_shader_stages |= resource.used_in_shader_stages;
+                    existing_resource.merge_gles_names(resource);
                 } else {
                     //
                     // It's a new binding name and doesn't overlap with existing bindings
@@ -187,7 +526,6 @@ pub(crate) fn merge_resources<'a>(
     ))
 }
 
-#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
 fn verify_resources_can_overlap(
     resource: &RafxShaderResource,
     previous_resource: &RafxShaderResource,
@@ -0,0 +1,590 @@
+// Tracks the last known RafxResourceState of every texture subresource and buffer, and reports
+// barriers and resource usage that disagree with it. Commands are recorded per command buffer and
+// replayed against the device's tracked states when they are submitted, in submission order.
+//
+// Only enabled with the validate-resource-states feature. The logic only depends on the backend
+// independent API, so it's exercised by the empty backend's tests.
+
+use crate::{
+    RafxBarrierQueueTransition, RafxBuffer, RafxBufferBarrier, RafxColorRenderTargetBinding,
+    RafxCommandBuffer, RafxDepthStencilRenderTargetBinding, RafxDescriptorIndex, RafxDescriptorKey,
+    RafxDescriptorUpdate, RafxResourceState, RafxResourceStateValidationMode, RafxTexture,
+    RafxTextureBarrier, RafxTextureView,
+};
+use fnv::{FnvHashMap, FnvHashSet};
+use std::ops::Range;
+use std::sync::Mutex;
+
+// A range of mip levels and array layers within a texture
+#[derive(Clone, Debug)]
+struct TextureSubresources {
+    texture_id: u32,
+    mip_range: Range<u32>,
+    array_range: Range<u32>,
+}
+
+impl TextureSubresources {
+    fn new(
+        texture: &RafxTexture,
+        mip_slice: Option<u8>,
+        array_slice: Option<u16>,
+    ) -> Self {
+        let texture_def = texture.texture_def();
+        let mip_range = match mip_slice {
+            Some(mip_slice) => mip_slice as u32..mip_slice as u32 + 1,
+            None => 0..texture_def.mip_count,
+        };
+        let array_range = match array_slice {
+            Some(array_slice) => array_slice as u32..array_slice as u32 + 1,
+            None => 0..texture_def.array_length,
+        };
+
+        TextureSubresources {
+            texture_id: texture.texture_id(),
+            mip_range,
+            array_range,
+        }
+    }
+
+    fn from_texture_view(texture_view: &RafxTextureView) -> Self {
+        let texture_view_def = texture_view.texture_view_def();
+        TextureSubresources {
+            texture_id: texture_view.texture().texture_id(),
+            mip_range: texture_view_def.mip_range.clone().unwrap(),
+            array_range: texture_view_def.array_range.clone().unwrap(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.mip_range.clone().flat_map(move |mip_level| {
+            self.array_range
+                .clone()
+                .map(move |array_layer| (mip_level, array_layer))
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum RecordedResourceUse {
+    TextureBarrier {
+        subresources: TextureSubresources,
+        src_state: RafxResourceState,
+        dst_state: RafxResourceState,
+        // The releasing queue already performed the transition
+        is_queue_acquire: bool,
+    },
+    BufferBarrier {
+        buffer_id: u32,
+        src_state: RafxResourceState,
+        dst_state: RafxResourceState,
+        is_queue_acquire: bool,
+    },
+    // A texture in a descriptor set that was bound when drawing or dispatching
+    BoundTexture(TextureSubresources),
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct RafxRecordedResourceUses {
+    uses: Vec<RecordedResourceUse>,
+    referenced_textures: FnvHashSet<u32>,
+    referenced_buffers: FnvHashSet<u32>,
+    // The textures of the descriptor set currently bound at each set index
+    bound_textures: FnvHashMap<u32, Vec<TextureSubresources>>,
+}
+
+// Owned copy of RafxDescriptorKey so that updates can be remembered
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum DescriptorKey {
+    Undefined,
+    Name(String),
+    Binding(u32),
+    DescriptorIndex(RafxDescriptorIndex),
+}
+
+impl From<&RafxDescriptorKey<'_>> for DescriptorKey {
+    fn from(descriptor_key: &RafxDescriptorKey<'_>) -> Self {
+        match descriptor_key {
+            RafxDescriptorKey::Undefined => DescriptorKey::Undefined,
+            RafxDescriptorKey::Name(name) => DescriptorKey::Name(name.to_string()),
+            RafxDescriptorKey::Binding(binding) => DescriptorKey::Binding(*binding),
+            RafxDescriptorKey::DescriptorIndex(index) => DescriptorKey::DescriptorIndex(*index),
+        }
+    }
+}
+
+// Remembers which textures were written into each descriptor set of a descriptor set array
+#[derive(Debug)]
+pub(crate) struct RafxDescriptorSetResourceStates {
+    set_index: u32,
+    // (array_index, descriptor, element) -> texture
+    textures: FnvHashMap<(u32, DescriptorKey, u32), TextureSubresources>,
+}
+
+impl RafxDescriptorSetResourceStates {
+    pub(crate) fn new(set_index: u32) -> Self {
+        RafxDescriptorSetResourceStates {
+            set_index,
+            textures: Default::default(),
+        }
+    }
+
+    pub(crate) fn record_update(
+        &mut self,
+        update: &RafxDescriptorUpdate,
+    ) {
+        let descriptor_key = DescriptorKey::from(&update.descriptor_key);
+        let mut subresources: Vec<_> = Vec::default();
+        if let Some(textures) = update.elements.textures {
+            subresources.extend(
+                textures
+                    .iter()
+                    .map(|texture| TextureSubresources::new(texture, None, None)),
+            );
+        }
+
+        if let Some(texture_views) = update.elements.texture_views {
+            subresources.extend(
+                texture_views
+                    .iter()
+                    .map(|texture_view| TextureSubresources::from_texture_view(texture_view)),
+            );
+        }
+
+        for (i, subresources) in subresources.into_iter().enumerate() {
+            let element = update.dst_element_offset + i as u32;
+            self.textures.insert(
+                (update.array_index, descriptor_key.clone(), element),
+                subresources,
+            );
+        }
+    }
+
+    fn textures(
+        &self,
+        array_index: u32,
+    ) -> Vec<TextureSubresources> {
+        self.textures
+            .iter()
+            .filter(|((x, _, _), _)| *x == array_index)
+            .map(|(_, subresources)| subresources.clone())
+            .collect()
+    }
+}
+
+// Records how a command buffer uses resources so that it can be validated when submitted
+#[derive(Default)]
+pub(crate) struct RafxCommandBufferResourceStates {
+    recorded: Mutex<RafxRecordedResourceUses>,
+}
+
+impl std::fmt::Debug for RafxCommandBufferResourceStates {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.debug_struct("RafxCommandBufferResourceStates").finish()
+    }
+}
+
+impl RafxCommandBufferResourceStates {
+    pub(crate) fn reset(&self) {
+        *self.recorded.lock().unwrap() = Default::default();
+    }
+
+    pub(crate) fn recorded(&self) -> RafxRecordedResourceUses {
+        self.recorded.lock().unwrap().clone()
+    }
+
+    pub(crate) fn reference_texture(
+        &self,
+        texture: &RafxTexture,
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.referenced_textures.insert(texture.texture_id());
+    }
+
+    pub(crate) fn reference_buffer(
+        &self,
+        buffer: &RafxBuffer,
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.referenced_buffers.insert(buffer.buffer_id());
+    }
+
+    pub(crate) fn record_barriers(
+        &self,
+        buffer_barriers: &[RafxBufferBarrier],
+        texture_barriers: &[RafxTextureBarrier],
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        for barrier in buffer_barriers {
+            let buffer_id = barrier.buffer.buffer_id();
+            recorded.referenced_buffers.insert(buffer_id);
+            recorded.uses.push(RecordedResourceUse::BufferBarrier {
+                buffer_id,
+                src_state: barrier.src_state,
+                dst_state: barrier.dst_state,
+                is_queue_acquire: matches!(
+                    barrier.queue_transition,
+                    RafxBarrierQueueTransition::AcquireFrom(_)
+                ),
+            });
+        }
+
+        for barrier in texture_barriers {
+            let subresources =
+                TextureSubresources::new(barrier.texture, barrier.mip_slice, barrier.array_slice);
+            recorded.referenced_textures.insert(subresources.texture_id);
+            recorded.uses.push(RecordedResourceUse::TextureBarrier {
+                subresources,
+                src_state: barrier.src_state,
+                dst_state: barrier.dst_state,
+                is_queue_acquire: matches!(
+                    barrier.queue_transition,
+                    RafxBarrierQueueTransition::AcquireFrom(_)
+                ),
+            });
+        }
+    }
+
+    pub(crate) fn record_render_targets(
+        &self,
+        color_targets: &[RafxColorRenderTargetBinding],
+        depth_target: Option<&RafxDepthStencilRenderTargetBinding>,
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        for color_target in color_targets {
+            recorded
+                .referenced_textures
+                .insert(color_target.texture.texture_id());
+            if let Some(resolve_target) = color_target.resolve_target {
+                recorded
+                    .referenced_textures
+                    .insert(resolve_target.texture_id());
+            }
+        }
+
+        if let Some(depth_target) = depth_target {
+            recorded
+                .referenced_textures
+                .insert(depth_target.texture.texture_id());
+        }
+    }
+
+    pub(crate) fn record_bind_descriptor_set(
+        &self,
+        descriptor_set_resource_states: &RafxDescriptorSetResourceStates,
+        array_index: u32,
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        let textures = descriptor_set_resource_states.textures(array_index);
+        recorded
+            .referenced_textures
+            .extend(textures.iter().map(|x| x.texture_id));
+        recorded
+            .bound_textures
+            .insert(descriptor_set_resource_states.set_index, textures);
+    }
+
+    // Descriptor set handles don't know their contents, so textures bound this way aren't checked
+    pub(crate) fn record_bind_descriptor_set_handle(
+        &self,
+        set_index: u32,
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.bound_textures.remove(&set_index);
+    }
+
+    // Draws and dispatches read the textures in all currently bound descriptor sets
+    pub(crate) fn record_bound_texture_usage(&self) {
+        let mut recorded = self.recorded.lock().unwrap();
+        let recorded = &mut *recorded;
+        for textures in recorded.bound_textures.values() {
+            for subresources in textures {
+                recorded
+                    .uses
+                    .push(RecordedResourceUse::BoundTexture(subresources.clone()));
+            }
+        }
+    }
+
+    pub(crate) fn record_execute_secondary_command_buffers(
+        &self,
+        secondary_command_buffers: &[&RafxCommandBuffer],
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        for secondary_command_buffer in secondary_command_buffers {
+            let secondary = secondary_command_buffer.resource_states().recorded();
+            recorded.uses.extend(secondary.uses);
+            recorded
+                .referenced_textures
+                .extend(secondary.referenced_textures);
+            recorded
+                .referenced_buffers
+                .extend(secondary.referenced_buffers);
+        }
+    }
+}
+
+struct PendingSubmission {
+    queue_id: u32,
+    fence_id: Option<u32>,
+    textures: FnvHashSet<u32>,
+    buffers: FnvHashSet<u32>,
+}
+
+#[derive(Default)]
+struct RafxResourceStateTrackerInner {
+    validation_mode: RafxResourceStateValidationMode,
+    // texture_id -> (mip level, array layer) -> state
+    texture_states: FnvHashMap<u32, FnvHashMap<(u32, u32), RafxResourceState>>,
+    buffer_states: FnvHashMap<u32, RafxResourceState>,
+    // In submission order
+    pending_submissions: Vec<PendingSubmission>,
+}
+
+impl RafxResourceStateTrackerInner {
+    fn apply_texture_barrier(
+        &mut self,
+        subresources: &TextureSubresources,
+        src_state: RafxResourceState,
+        dst_state: RafxResourceState,
+        is_queue_acquire: bool,
+        errors: &mut Vec<String>,
+    ) {
+        let texture_states = self
+            .texture_states
+            .entry(subresources.texture_id)
+            .or_default();
+        for (mip_level, array_layer) in subresources.iter() {
+            // Transitioning from UNDEFINED discards the contents, so it's valid from any state
+            if let Some(&state) = texture_states.get(&(mip_level, array_layer)) {
+                if !is_queue_acquire
+                    && src_state != RafxResourceState::UNDEFINED
+                    && src_state != state
+                {
+                    errors.push(format!(
+                        "Texture barrier src_state {:?} does not match the last known state {:?} of texture {} (mip level: {} array layer: {})",
+                        src_state, state, subresources.texture_id, mip_level, array_layer
+                    ));
+                }
+            }
+
+            texture_states.insert((mip_level, array_layer), dst_state);
+        }
+    }
+
+    fn apply_buffer_barrier(
+        &mut self,
+        buffer_id: u32,
+        src_state: RafxResourceState,
+        dst_state: RafxResourceState,
+        is_queue_acquire: bool,
+        errors: &mut Vec<String>,
+    ) {
+        if let Some(state) = self.buffer_states.get(&buffer_id) {
+            if !is_queue_acquire && src_state != RafxResourceState::UNDEFINED && src_state != *state
+            {
+                errors.push(format!(
+                    "Buffer barrier src_state {:?} does not match the last known state {:?} of buffer {}",
+                    src_state, state, buffer_id
+                ));
+            }
+        }
+
+        self.buffer_states.insert(buffer_id, dst_state);
+    }
+
+    fn check_bound_texture(
+        &self,
+        subresources: &TextureSubresources,
+        errors: &mut Vec<String>,
+    ) {
+        if let Some(texture_states) = self.texture_states.get(&subresources.texture_id) {
+            for (mip_level, array_layer) in subresources.iter() {
+                if let Some(state) = texture_states.get(&(mip_level, array_layer)) {
+                    if state.intersects(RafxResourceState::RENDER_TARGET) {
+                        errors.push(format!(
+                            "Texture {} (mip level: {} array layer: {}) is sampled by a draw or dispatch while in state {:?}",
+                            subresources.texture_id, mip_level, array_layer, state
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Owned by each device context
+#[derive(Default)]
+pub(crate) struct RafxResourceStateTracker {
+    inner: Mutex<RafxResourceStateTrackerInner>,
+}
+
+impl RafxResourceStateTracker {
+    pub(crate) fn set_validation_mode(
+        &self,
+        validation_mode: RafxResourceStateValidationMode,
+    ) {
+        self.inner.lock().unwrap().validation_mode = validation_mode;
+    }
+
+    pub(crate) fn on_submit(
+        &self,
+        queue_id: u32,
+        command_buffers: &[&RafxCommandBuffer],
+        fence_id: Option<u32>,
+    ) {
+        let mut errors = Vec::default();
+        let validation_mode = {
+            let mut inner = self.inner.lock().unwrap();
+            let mut submission = PendingSubmission {
+                queue_id,
+                fence_id,
+                textures: Default::default(),
+                buffers: Default::default(),
+            };
+
+            for command_buffer in command_buffers {
+                let recorded = command_buffer.resource_states().recorded();
+                for resource_use in &recorded.uses {
+                    match resource_use {
+                        RecordedResourceUse::TextureBarrier {
+                            subresources,
+                            src_state,
+                            dst_state,
+                            is_queue_acquire,
+                        } => inner.apply_texture_barrier(
+                            subresources,
+                            *src_state,
+                            *dst_state,
+                            *is_queue_acquire,
+                            &mut errors,
+                        ),
+                        RecordedResourceUse::BufferBarrier {
+                            buffer_id,
+                            src_state,
+                            dst_state,
+                            is_queue_acquire,
+                        } => inner.apply_buffer_barrier(
+                            *buffer_id,
+                            *src_state,
+                            *dst_state,
+                            *is_queue_acquire,
+                            &mut errors,
+                        ),
+                        RecordedResourceUse::BoundTexture(subresources) => {
+                            inner.check_bound_texture(subresources, &mut errors)
+                        }
+                    }
+                }
+
+                submission.textures.extend(recorded.referenced_textures);
+                submission.buffers.extend(recorded.referenced_buffers);
+            }
+
+            inner.pending_submissions.push(submission);
+            inner.validation_mode
+        };
+
+        Self::report(validation_mode, errors);
+    }
+
+    // Signaling a fence also completes everything submitted to the queue before it
+    pub(crate) fn on_fence_signaled(
+        &self,
+        fence_id: u32,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        let last_signaled = inner
+            .pending_submissions
+            .iter()
+            .rposition(|x| x.fence_id == Some(fence_id));
+        if let Some(last_signaled) = last_signaled {
+            let queue_id = inner.pending_submissions[last_signaled].queue_id;
+            let mut index = 0;
+            inner.pending_submissions.retain(|x| {
+                let is_complete = index <= last_signaled && x.queue_id == queue_id;
+                index += 1;
+                !is_complete
+            });
+        }
+    }
+
+    pub(crate) fn on_queue_idle(
+        &self,
+        queue_id: u32,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending_submissions.retain(|x| x.queue_id != queue_id);
+    }
+
+    pub(crate) fn on_texture_destroyed(
+        &self,
+        texture_id: u32,
+    ) {
+        let (validation_mode, errors) = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.texture_states.remove(&texture_id);
+            let mut errors = Vec::default();
+            let is_referenced = inner
+                .pending_submissions
+                .iter()
+                .any(|x| x.textures.contains(&texture_id));
+            if is_referenced {
+                errors.push(format!(
+                    "Texture {} was destroyed while still referenced by a submission that has not signaled completion",
+                    texture_id
+                ));
+            }
+
+            (inner.validation_mode, errors)
+        };
+
+        Self::report(validation_mode, errors);
+    }
+
+    pub(crate) fn on_buffer_destroyed(
+        &self,
+        buffer_id: u32,
+    ) {
+        let (validation_mode, errors) = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.buffer_states.remove(&buffer_id);
+            let mut errors = Vec::default();
+            let is_referenced = inner
+                .pending_submissions
+                .iter()
+                .any(|x| x.buffers.contains(&buffer_id));
+            if is_referenced {
+                errors.push(format!(
+                    "Buffer {} was destroyed while still referenced by a submission that has not signaled completion",
+                    buffer_id
+                ));
+            }
+
+            (inner.validation_mode, errors)
+        };
+
+        Self::report(validation_mode, errors);
+    }
+
+    // Called after releasing the lock so that a panic doesn't poison it
+    fn report(
+        validation_mode: RafxResourceStateValidationMode,
+        errors: Vec<String>,
+    ) {
+        if errors.is_empty() {
+            return;
+        }
+
+        // Panicking while already unwinding (i.e. dropping resources) would abort
+        if validation_mode == RafxResourceStateValidationMode::Panic && !std::thread::panicking() {
+            panic!("Resource state validation failed:\n{}", errors.join("\n"));
+        }
+
+        for error in errors {
+            log::error!("Resource state validation failed: {}", error);
+        }
+    }
+}
@@ -11,6 +11,15 @@
 //! enforce safe usage at compile time or runtime. Safer abstractions are available in
 //! rafx-framework and rafx-assets.
 //!
+//! To help debug incorrect barriers, the `validate-resource-states` feature tracks the last known
+//! state of every texture subresource and buffer as command buffers are submitted. Mismatched
+//! barriers, sampling render targets, and destroying resources that are still in use are logged
+//! or cause a panic, depending on `RafxApiDef::resource_state_validation_mode`.
+//!
+//! To help reproduce rendering bugs, the `capture` feature can record the resources and commands
+//! submitted to a device into a file that can be replayed with the `rafx-replay` tool. See the
+//! [capture] module.
+//!
 //! **Every API call is potentially unsafe.** However, the unsafe keyword is only placed on APIs
 //! that are particularly likely to cause undefined behavior if used incorrectly.
 //!
@@ -30,6 +39,7 @@
 //! * [RafxDescriptorSetArray] - An array of descriptor sets. These are expected to be pooled and reused.
 //! * [RafxDeviceContext] - A cloneable, thread-safe handle used to create graphics resources.
 //! * [RafxFence] - A GPU -> CPU synchronization mechanism.
+//! * [RafxHeap] - A block of memory that buffers and textures can be placed in, allowing them to alias.
 //! * [RafxPipeline] - Represents a complete GPU configuration for executing work.
 //! * [RafxQueue] - A queue allows work to be submitted to the GPU
 //! * [RafxRootSignature] - Represents the full "layout" or "interface" of a shader (or set of shaders.)
@@ -39,6 +49,7 @@
 //! * [RafxShaderModule] - Rrepresents loaded shader code that can be used to create a pipeline.
 //! * [RafxSwapchain] - A set of images that act as a "backbuffer" of a window.
 //! * [RafxTexture] - An image that can be used by the GPU.
+//! * [RafxTimelineSemaphore] - A GPU <-> GPU and GPU <-> CPU synchronization mechanism with a u64 value.
 //!
 //! # Usage Summary
 //!
@@ -242,13 +253,32 @@ pub use backends::vulkan::RafxApiDefVulkan;
 
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 pub use backends::empty;
+#[cfg(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+))]
+pub use backends::empty::RafxApiDefEmpty;
+#[cfg(feature = "rafx-gles3")]
+pub use backends::gles3;
+#[cfg(feature = "rafx-gles3")]
+pub use backends::gles3::RafxApiDefGles3;
 
 //
 // Public modules
 //
+#[cfg(feature = "capture")]
+pub mod capture;
 pub mod extra;
 
 //
@@ -270,7 +300,9 @@ mod command_pool;
 mod descriptor_set_array;
 mod device_context;
 mod fence;
+mod heap;
 mod pipeline;
+mod query_pool;
 mod queue;
 mod root_signature;
 mod sampler;
@@ -279,6 +311,8 @@ mod shader;
 mod shader_module;
 mod swapchain;
 mod texture;
+mod texture_view;
+mod timeline_semaphore;
 
 //
 // Constants
@@ -289,6 +323,9 @@ pub const MAX_DESCRIPTOR_SET_LAYOUTS: usize = 4;
 /// The maximum number of simultaneously attached render targets
 // In sync with RafxBlendStateTargets
 pub const MAX_RENDER_TARGET_ATTACHMENTS: usize = 8;
+/// The metal buffer index that push constants are bound to. Argument buffers (one per descriptor
+/// set) occupy the indices before it. Shaders must be cross-compiled to read push constants here.
+pub const METAL_PUSH_CONSTANT_BUFFER_INDEX: u32 = MAX_DESCRIPTOR_SET_LAYOUTS as u32;
 
 //
 // Exported public API
@@ -300,9 +337,12 @@ pub use command_pool::*;
 pub use descriptor_set_array::*;
 pub use device_context::*;
 pub use error::*;
+pub use extra::offscreen_swapchain::*;
 pub use extra::swapchain_helper::*;
 pub use fence::*;
+pub use heap::*;
 pub use pipeline::*;
+pub use query_pool::*;
 pub use queue::*;
 pub use root_signature::*;
 pub use sampler::*;
@@ -311,4 +351,6 @@ pub use shader::*;
 pub use shader_module::*;
 pub use swapchain::*;
 pub use texture::*;
+pub use texture_view::*;
+pub use timeline_semaphore::*;
 pub use types::*;
@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxPipelineEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxPipelineGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxPipelineMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -30,9 +36,15 @@ pub enum RafxPipeline {
     Metal(RafxPipelineMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxPipelineEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxPipelineGles3),
 }
 
 impl RafxPipeline {
@@ -45,9 +57,15 @@ impl RafxPipeline {
             RafxPipeline::Metal(inner) => inner.pipeline_type(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxPipeline::Empty(inner) => inner.pipeline_type(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(inner) => inner.pipeline_type(),
         }
     }
 
@@ -60,9 +78,64 @@ impl RafxPipeline {
             RafxPipeline::Metal(inner) => inner.root_signature(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxPipeline::Empty(inner) => inner.root_signature(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(inner) => inner.root_signature(),
+        }
+    }
+
+    /// Sets the name of the pipeline shown by GPU debugging tools like RenderDoc and Xcode. On
+    /// vulkan this requires VK_EXT_debug_utils, which is enabled when available.
+    ///
+    /// Metal objects of this type can't be renamed after creation, so this does nothing on metal.
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxPipeline::Vk(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxPipeline::Metal(inner) => inner.set_debug_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxPipeline::Empty(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(inner) => inner.set_debug_name(name),
+        }
+    }
+
+    // Used internally to identify the pipeline in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxPipeline::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxPipeline::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxPipeline::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(inner) => inner.capture_id(),
         }
     }
 
@@ -77,9 +150,15 @@ impl RafxPipeline {
             RafxPipeline::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxPipeline::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(_inner) => None,
         }
     }
 
@@ -94,15 +173,25 @@ impl RafxPipeline {
             RafxPipeline::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxPipeline::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(_inner) => None,
         }
     }
 
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_pipeline(&self) -> Option<&RafxPipelineEmpty> {
         match self {
@@ -112,9 +201,38 @@ impl RafxPipeline {
             RafxPipeline::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxPipeline::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_pipeline(&self) -> Option<&RafxPipelineGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxPipeline::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxPipeline::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxPipeline::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxPipeline::Empty(_) => None,
         }
     }
 }
@@ -0,0 +1,165 @@
+#[cfg(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+))]
+use crate::empty::RafxQueryPoolEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxQueryPoolGles3;
+#[cfg(feature = "rafx-metal")]
+use crate::metal::RafxQueryPoolMetal;
+#[cfg(feature = "rafx-vulkan")]
+use crate::vulkan::RafxQueryPoolVulkan;
+use crate::RafxQueryPoolDef;
+
+/// A fixed-size set of GPU queries of a single `RafxQueryType`.
+///
+/// Queries must be reset with `cmd_reset_query_pool` before they are written. Results are copied
+/// into a buffer with `cmd_resolve_query`. Each query produces
+/// `RafxQueryType::values_per_query()` u64 values.
+///
+/// Check `RafxDeviceInfo` to see which query types are supported.
+///
+/// Query pools must not be dropped if they are in use by the GPU.
+pub enum RafxQueryPool {
+    #[cfg(feature = "rafx-vulkan")]
+    Vk(RafxQueryPoolVulkan),
+    #[cfg(feature = "rafx-metal")]
+    Metal(RafxQueryPoolMetal),
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    Empty(RafxQueryPoolEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxQueryPoolGles3),
+}
+
+impl RafxQueryPool {
+    /// Returns the definition used to create the query pool
+    pub fn query_pool_def(&self) -> &RafxQueryPoolDef {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueryPool::Vk(inner) => inner.query_pool_def(),
+            #[cfg(feature = "rafx-metal")]
+            RafxQueryPool::Metal(inner) => inner.query_pool_def(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueryPool::Empty(inner) => inner.query_pool_def(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueryPool::Gles3(inner) => inner.query_pool_def(),
+        }
+    }
+
+    /// Get the underlying vulkan API object. This provides access to any internally created
+    /// vulkan objects.
+    #[cfg(feature = "rafx-vulkan")]
+    pub fn vk_query_pool(&self) -> Option<&RafxQueryPoolVulkan> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueryPool::Vk(inner) => Some(inner),
+            #[cfg(feature = "rafx-metal")]
+            RafxQueryPool::Metal(_) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueryPool::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueryPool::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying metal API object. This provides access to any internally created
+    /// metal objects.
+    #[cfg(feature = "rafx-metal")]
+    pub fn metal_query_pool(&self) -> Option<&RafxQueryPoolMetal> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueryPool::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxQueryPool::Metal(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueryPool::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueryPool::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying empty API object. This provides access to any internally created
+    /// empty objects.
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn empty_query_pool(&self) -> Option<&RafxQueryPoolEmpty> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueryPool::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxQueryPool::Metal(_) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueryPool::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueryPool::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_query_pool(&self) -> Option<&RafxQueryPoolGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueryPool::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxQueryPool::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueryPool::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueryPool::Empty(_) => None,
+        }
+    }
+}
@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxQueueEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxQueueGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxQueueMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -10,6 +16,7 @@ use crate::vulkan::RafxQueueVulkan;
 use crate::{
     RafxCommandBuffer, RafxCommandPool, RafxCommandPoolDef, RafxDeviceContext, RafxFence,
     RafxPresentSuccessResult, RafxQueueType, RafxResult, RafxSemaphore, RafxSwapchain,
+    RafxTimelineSemaphoreValue,
 };
 
 /// A queue allows work to be submitted to the GPU
@@ -32,9 +39,15 @@ pub enum RafxQueue {
     Metal(RafxQueueMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxQueueEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxQueueGles3),
 }
 
 impl RafxQueue {
@@ -46,9 +59,15 @@ impl RafxQueue {
             RafxQueue::Metal(inner) => RafxDeviceContext::Metal(inner.device_context().clone()),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => RafxDeviceContext::Empty(inner.device_context().clone()),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => RafxDeviceContext::Gles3(inner.device_context().clone()),
         }
     }
 
@@ -62,9 +81,15 @@ impl RafxQueue {
             RafxQueue::Metal(inner) => inner.queue_id(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => inner.queue_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => inner.queue_id(),
         }
     }
 
@@ -77,9 +102,15 @@ impl RafxQueue {
             RafxQueue::Metal(inner) => inner.queue_type(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => inner.queue_type(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => inner.queue_type(),
         }
     }
 
@@ -99,11 +130,19 @@ impl RafxQueue {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => {
                 RafxCommandPool::Empty(inner.create_command_pool(command_pool_def)?)
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => {
+                RafxCommandPool::Gles3(inner.create_command_pool(command_pool_def)?)
+            }
         })
     }
 
@@ -119,6 +158,40 @@ impl RafxQueue {
         signal_semaphores: &[&RafxSemaphore],
         signal_fence: Option<&RafxFence>,
     ) -> RafxResult<()> {
+        self.submit_with_timeline_semaphores(
+            command_buffers,
+            wait_semaphores,
+            signal_semaphores,
+            &[],
+            &[],
+            signal_fence,
+        )
+    }
+
+    /// Submit command buffers for processing by the GPU, additionally waiting for and signaling
+    /// timeline semaphore values.
+    ///
+    /// Execution will not begin until all `wait_semaphores` are signaled and all
+    /// `wait_timeline_semaphores` have reached their values.
+    ///
+    /// After execution, the given `signal_semaphores` and `signal_fence` are signaled as completed,
+    /// and the `signal_timeline_semaphores` are set to their values.
+    pub fn submit_with_timeline_semaphores(
+        &self,
+        command_buffers: &[&RafxCommandBuffer],
+        wait_semaphores: &[&RafxSemaphore],
+        signal_semaphores: &[&RafxSemaphore],
+        wait_timeline_semaphores: &[RafxTimelineSemaphoreValue],
+        signal_timeline_semaphores: &[RafxTimelineSemaphoreValue],
+        signal_fence: Option<&RafxFence>,
+    ) -> RafxResult<()> {
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record_submit(|_| crate::capture::RafxCaptureEvent::Submit {
+                queue_id: self.queue_id(),
+                command_buffer_ids: command_buffers.iter().map(|x| x.capture_id()).collect(),
+            });
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxQueue::Vk(inner) => {
@@ -134,10 +207,20 @@ impl RafxQueue {
                     .iter()
                     .map(|x| x.vk_semaphore().unwrap())
                     .collect();
+                let wait_timeline_semaphores: Vec<_> = wait_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.vk_timeline_semaphore().unwrap(), x.value))
+                    .collect();
+                let signal_timeline_semaphores: Vec<_> = signal_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.vk_timeline_semaphore().unwrap(), x.value))
+                    .collect();
                 inner.submit(
                     &command_buffers,
                     &wait_semaphores,
                     &signal_semaphores,
+                    &wait_timeline_semaphores,
+                    &signal_timeline_semaphores,
                     signal_fence.map(|x| x.vk_fence().unwrap()),
                 )
             }
@@ -155,16 +238,30 @@ impl RafxQueue {
                     .iter()
                     .map(|x| x.metal_semaphore().unwrap())
                     .collect();
+                let wait_timeline_semaphores: Vec<_> = wait_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.metal_timeline_semaphore().unwrap(), x.value))
+                    .collect();
+                let signal_timeline_semaphores: Vec<_> = signal_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.metal_timeline_semaphore().unwrap(), x.value))
+                    .collect();
                 inner.submit(
                     &command_buffers,
                     &wait_semaphores,
                     &signal_semaphores,
+                    &wait_timeline_semaphores,
+                    &signal_timeline_semaphores,
                     signal_fence.map(|x| x.metal_fence().unwrap()),
                 )
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => {
                 let command_buffers: Vec<_> = command_buffers
@@ -179,25 +276,86 @@ impl RafxQueue {
                     .iter()
                     .map(|x| x.empty_semaphore().unwrap())
                     .collect();
+                let wait_timeline_semaphores: Vec<_> = wait_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.empty_timeline_semaphore().unwrap(), x.value))
+                    .collect();
+                let signal_timeline_semaphores: Vec<_> = signal_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.empty_timeline_semaphore().unwrap(), x.value))
+                    .collect();
                 inner.submit(
                     &command_buffers,
                     &wait_semaphores,
                     &signal_semaphores,
+                    &wait_timeline_semaphores,
+                    &signal_timeline_semaphores,
                     signal_fence.map(|x| x.empty_fence().unwrap()),
                 )
             }
-        }
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => {
+                let command_buffers: Vec<_> = command_buffers
+                    .iter()
+                    .map(|x| x.gles3_command_buffer().unwrap())
+                    .collect();
+                let wait_semaphores: Vec<_> = wait_semaphores
+                    .iter()
+                    .map(|x| x.gles3_semaphore().unwrap())
+                    .collect();
+                let signal_semaphores: Vec<_> = signal_semaphores
+                    .iter()
+                    .map(|x| x.gles3_semaphore().unwrap())
+                    .collect();
+                let wait_timeline_semaphores: Vec<_> = wait_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.gles3_timeline_semaphore().unwrap(), x.value))
+                    .collect();
+                let signal_timeline_semaphores: Vec<_> = signal_timeline_semaphores
+                    .iter()
+                    .map(|x| (x.semaphore.gles3_timeline_semaphore().unwrap(), x.value))
+                    .collect();
+                inner.submit(
+                    &command_buffers,
+                    &wait_semaphores,
+                    &signal_semaphores,
+                    &wait_timeline_semaphores,
+                    &signal_timeline_semaphores,
+                    signal_fence.map(|x| x.gles3_fence().unwrap()),
+                )
+            }
+        }?;
+
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context().resource_state_tracker().on_submit(
+            self.queue_id(),
+            command_buffers,
+            signal_fence.map(|x| x.fence_id()),
+        );
+
+        Ok(())
     }
 
     /// Presents an image in the swapchain.
     ///
-    /// Execution will not begin until all `wait_semaphores` are signaled.
+    /// Execution will not begin until all `wait_semaphores` are signaled. Offscreen swapchains
+    /// block until the image is no longer in use (see `RafxOffscreenSwapchain::present`).
     pub fn present(
         &self,
         swapchain: &RafxSwapchain,
         wait_semaphores: &[&RafxSemaphore],
         image_index: u32,
     ) -> RafxResult<RafxPresentSuccessResult> {
+        #[cfg(feature = "capture")]
+        self.capture_recorder()
+            .record(|_| crate::capture::RafxCaptureEvent::Present {
+                queue_id: self.queue_id(),
+            });
+
+        if let Some(offscreen_swapchain) = swapchain.offscreen_swapchain() {
+            return offscreen_swapchain.present(self, wait_semaphores, image_index);
+        }
+
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxQueue::Vk(inner) => {
@@ -225,7 +383,11 @@ impl RafxQueue {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => {
                 let wait_semaphores: Vec<_> = wait_semaphores
@@ -238,6 +400,18 @@ impl RafxQueue {
                     image_index,
                 )
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => {
+                let wait_semaphores: Vec<_> = wait_semaphores
+                    .iter()
+                    .map(|x| x.gles3_semaphore().unwrap())
+                    .collect();
+                inner.present(
+                    swapchain.gles3_swapchain().unwrap(),
+                    &wait_semaphores,
+                    image_index,
+                )
+            }
         }
     }
 
@@ -250,9 +424,43 @@ impl RafxQueue {
             RafxQueue::Metal(inner) => inner.wait_for_queue_idle(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => inner.wait_for_queue_idle(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => inner.wait_for_queue_idle(),
+        }?;
+
+        #[cfg(feature = "validate-resource-states")]
+        self.device_context()
+            .resource_state_tracker()
+            .on_queue_idle(self.queue_id());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueue::Vk(inner) => inner.device_context().capture_recorder(),
+            #[cfg(feature = "rafx-metal")]
+            RafxQueue::Metal(inner) => inner.device_context().capture_recorder(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueue::Empty(inner) => inner.device_context().capture_recorder(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => inner.device_context().capture_recorder(),
         }
     }
 
@@ -267,9 +475,15 @@ impl RafxQueue {
             RafxQueue::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(_inner) => None,
         }
     }
 
@@ -284,9 +498,15 @@ impl RafxQueue {
             RafxQueue::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => None,
         }
     }
 
@@ -294,7 +514,11 @@ impl RafxQueue {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_queue(&self) -> Option<&RafxQueueEmpty> {
         match self {
@@ -304,9 +528,38 @@ impl RafxQueue {
             RafxQueue::Metal(inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxQueue::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_queue(&self) -> Option<&RafxQueueGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxQueue::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxQueue::Metal(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxQueue::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxQueue::Empty(_) => None,
         }
     }
 }
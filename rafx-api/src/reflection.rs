@@ -1,5 +1,8 @@
 use crate::types::{RafxResourceType, RafxShaderStageFlags};
-use crate::{RafxResult, RafxShaderStageDef, MAX_DESCRIPTOR_SET_LAYOUTS};
+use crate::{
+    RafxError, RafxResult, RafxShaderStageDef, RafxSpecializationConstantValue,
+    MAX_DESCRIPTOR_SET_LAYOUTS,
+};
 use fnv::FnvHashMap;
 #[cfg(feature = "serde-support")]
 use serde::{Deserialize, Serialize};
@@ -30,6 +33,14 @@ pub struct RafxShaderResource {
     // Name is optional
     //TODO: Add some sort of hashing-friendly option
     pub name: Option<String>,
+    // Name of the uniform block or sampler uniform in GLSL ES source, if it differs from name. Only
+    // used by the GL ES backend
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub gles_name: Option<String>,
+    // For textures, the sampler resource it is combined with in GLSL ES source. Only used by the
+    // GL ES backend
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub gles_sampler_name: Option<String>,
     //pub texture_dimensions: Option<RafxTextureDimension>,
     // metal stuff?
 }
@@ -95,6 +106,20 @@ impl RafxShaderResource {
         Ok(())
     }
 
+    // GLSL ES names may only be provided by some of the stages that use a resource
+    pub(crate) fn merge_gles_names(
+        &mut self,
+        other: &Self,
+    ) {
+        if self.gles_name.is_none() {
+            self.gles_name = other.gles_name.clone();
+        }
+
+        if self.gles_sampler_name.is_none() {
+            self.gles_sampler_name = other.gles_sampler_name.clone();
+        }
+    }
+
     fn binding_key(&self) -> RafxShaderResourceBindingKey {
         RafxShaderResourceBindingKey {
             set: self.set_index,
@@ -107,26 +132,35 @@ impl RafxShaderResource {
         other: &Self,
     ) -> RafxResult<()> {
         if self.resource_type != other.resource_type {
-            Err(format!(
-                "Pass is using shaders in different stages with different resource_type {:?} and {:?} (set={} binding={})",
-                self.resource_type, other.resource_type,
-                self.set_index,
-                self.binding
+            Err(RafxError::reflection_mismatch(
+                self.name.as_deref(),
+                format!(
+                    "Pass is using shaders in different stages with different resource_type {:?} and {:?} (set={} binding={})",
+                    self.resource_type, other.resource_type, self.set_index, self.binding
+                ),
             ))?;
         }
 
         if self.element_count_normalized() != other.element_count_normalized() {
-            Err(format!(
-                "Pass is using shaders in different stages with different element_count {} and {} (set={} binding={})", self.element_count_normalized(), other.element_count_normalized(),
-                self.set_index, self.binding
+            Err(RafxError::reflection_mismatch(
+                self.name.as_deref(),
+                format!(
+                    "Pass is using shaders in different stages with different element_count {} and {} (set={} binding={})",
+                    self.element_count_normalized(),
+                    other.element_count_normalized(),
+                    self.set_index,
+                    self.binding
+                ),
             ))?;
         }
 
         if self.size_in_bytes != other.size_in_bytes {
-            Err(format!(
-                "Pass is using shaders in different stages with different size_in_bytes {} and {} (set={} binding={})",
-                self.size_in_bytes, other.size_in_bytes,
-                self.set_index, self.binding
+            Err(RafxError::reflection_mismatch(
+                self.name.as_deref(),
+                format!(
+                    "Pass is using shaders in different stages with different size_in_bytes {} and {} (set={} binding={})",
+                    self.size_in_bytes, other.size_in_bytes, self.set_index, self.binding
+                ),
             ))?;
         }
 
@@ -134,6 +168,16 @@ impl RafxShaderResource {
     }
 }
 
+/// A specialization constant declared by a shader stage
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxSpecializationConstantReflection {
+    pub name: Option<String>,
+    pub constant_id: u32,
+    // Used if no value is provided when creating the shader. Also determines the constant's type
+    pub default_value: RafxSpecializationConstantValue,
+}
+
 /// Reflection data for a single shader stage
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
@@ -144,6 +188,8 @@ pub struct RafxShaderStageReflection {
     pub resources: Vec<RafxShaderResource>,
     pub compute_threads_per_group: Option<[u32; 3]>,
     pub entry_point_name: String,
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub specialization_constants: Vec<RafxSpecializationConstantReflection>,
     // Right now we will infer mappings based on spirv_cross default behavior, but likely will want
     // to allow providing them explicitly. This isn't implemented yet
     //pub binding_arg_buffer_mappings: FnvHashMap<(u32, u32), u32>
@@ -172,7 +218,10 @@ impl RafxPipelineReflection {
                         resource.used_in_shader_stages - stage.reflection.shader_stage
                     );
                     log::error!("{}", message);
-                    Err(message)?;
+                    Err(RafxError::reflection_mismatch(
+                        resource.name.as_deref(),
+                        message,
+                    ))?;
                 }
 
                 let mut resource = resource.clone();
@@ -181,6 +230,10 @@ impl RafxPipelineReflection {
             }
         }
 
+        for stage in stages {
+            stage.verify_specialization_constants()?;
+        }
+
         let mut compute_threads_per_group = None;
         for stage in stages {
             if stage
@@ -196,9 +249,12 @@ impl RafxPipelineReflection {
         let mut all_shader_stages = RafxShaderStageFlags::empty();
         for stage in stages {
             if all_shader_stages.intersects(stage.reflection.shader_stage) {
-                Err(format!(
-                    "Duplicate shader stage ({}) found when creating RafxPipelineReflection",
-                    (all_shader_stages & stage.reflection.shader_stage).bits()
+                Err(RafxError::invalid_argument(
+                    "stages",
+                    format!(
+                        "Duplicate shader stage ({}) found when creating RafxPipelineReflection",
+                        (all_shader_stages & stage.reflection.shader_stage).bits()
+                    ),
                 ))?;
             }
 
@@ -230,6 +286,7 @@ impl RafxPipelineReflection {
                     resource.used_in_shader_stages,
                 );
                 existing_resource.used_in_shader_stages |= resource.used_in_shader_stages;
+                existing_resource.merge_gles_names(resource);
             } else {
                 // insert it
                 log::trace!(
@@ -1,13 +1,19 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxRootSignatureEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxRootSignatureGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxRootSignatureMetal;
 #[cfg(feature = "rafx-vulkan")]
 use crate::vulkan::RafxRootSignatureVulkan;
-use crate::RafxPipelineType;
+use crate::{RafxDescriptorIndex, RafxPipelineType, RafxPushConstantIndex};
 
 /// Represents the full "layout" or "interface" of a shader (or set of shaders.)
 ///
@@ -21,9 +27,15 @@ pub enum RafxRootSignature {
     Metal(RafxRootSignatureMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxRootSignatureEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxRootSignatureGles3),
 }
 
 impl RafxRootSignature {
@@ -36,9 +48,135 @@ impl RafxRootSignature {
             RafxRootSignature::Metal(inner) => inner.pipeline_type(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxRootSignature::Empty(inner) => inner.pipeline_type(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => inner.pipeline_type(),
+        }
+    }
+
+    /// Find the descriptor with the given name. The index can be used to update descriptor sets
+    /// created from this root signature. Query it once during startup rather than every frame.
+    pub fn find_descriptor_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxDescriptorIndex> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxRootSignature::Vk(inner) => inner.find_descriptor_by_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxRootSignature::Metal(inner) => inner.find_descriptor_by_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxRootSignature::Empty(inner) => inner.find_descriptor_by_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => inner.find_descriptor_by_name(name),
+        }
+    }
+
+    /// Find the push constant with the given name. The index can be passed to
+    /// `cmd_bind_push_constant`. Query it once during startup rather than every frame.
+    pub fn find_push_constant_by_name(
+        &self,
+        name: &str,
+    ) -> Option<RafxPushConstantIndex> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxRootSignature::Vk(inner) => inner.find_push_constant_by_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxRootSignature::Metal(inner) => inner.find_push_constant_by_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxRootSignature::Empty(inner) => inner.find_push_constant_by_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => inner.find_push_constant_by_name(name),
+        }
+    }
+
+    /// Sets the name of the root signature shown by GPU debugging tools like RenderDoc and Xcode. On
+    /// vulkan this requires VK_EXT_debug_utils, which is enabled when available.
+    ///
+    /// Metal objects of this type can't be renamed after creation, so this does nothing on metal.
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxRootSignature::Vk(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxRootSignature::Metal(inner) => inner.set_debug_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxRootSignature::Empty(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => inner.set_debug_name(name),
+        }
+    }
+
+    // Used internally to identify the root signature in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxRootSignature::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxRootSignature::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxRootSignature::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => inner.capture_id(),
+        }
+    }
+
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_recorder(&self) -> &crate::capture::RafxCaptureRecorder {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxRootSignature::Vk(inner) => inner.device_context().capture_recorder(),
+            #[cfg(feature = "rafx-metal")]
+            RafxRootSignature::Metal(inner) => inner.device_context().capture_recorder(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxRootSignature::Empty(inner) => inner.device_context().capture_recorder(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => inner.device_context().capture_recorder(),
         }
     }
 
@@ -53,9 +191,15 @@ impl RafxRootSignature {
             RafxRootSignature::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxRootSignature::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(_inner) => None,
         }
     }
 
@@ -70,9 +214,15 @@ impl RafxRootSignature {
             RafxRootSignature::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxRootSignature::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(_inner) => None,
         }
     }
 
@@ -80,7 +230,11 @@ impl RafxRootSignature {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_root_signature(&self) -> Option<&RafxRootSignatureEmpty> {
         match self {
@@ -90,9 +244,38 @@ impl RafxRootSignature {
             RafxRootSignature::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxRootSignature::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_root_signature(&self) -> Option<&RafxRootSignatureGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxRootSignature::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxRootSignature::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxRootSignature::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxRootSignature::Empty(_) => None,
         }
     }
 }
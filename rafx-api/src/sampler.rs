@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxSamplerEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxSamplerGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxSamplerMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -19,12 +25,67 @@ pub enum RafxSampler {
     Metal(RafxSamplerMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxSamplerEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxSamplerGles3),
 }
 
 impl RafxSampler {
+    /// Sets the name of the sampler shown by GPU debugging tools like RenderDoc and Xcode. On
+    /// vulkan this requires VK_EXT_debug_utils, which is enabled when available.
+    ///
+    /// Metal objects of this type can't be renamed after creation, so this does nothing on metal.
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxSampler::Vk(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxSampler::Metal(inner) => inner.set_debug_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxSampler::Empty(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSampler::Gles3(inner) => inner.set_debug_name(name),
+        }
+    }
+
+    // Used internally to identify the sampler in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxSampler::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxSampler::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxSampler::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSampler::Gles3(inner) => inner.capture_id(),
+        }
+    }
+
     /// Get the underlying vulkan API object. This provides access to any internally created
     /// vulkan objects.
     #[cfg(feature = "rafx-vulkan")]
@@ -36,9 +97,15 @@ impl RafxSampler {
             RafxSampler::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSampler::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSampler::Gles3(_inner) => None,
         }
     }
 
@@ -53,9 +120,15 @@ impl RafxSampler {
             RafxSampler::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSampler::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSampler::Gles3(_inner) => None,
         }
     }
 
@@ -63,7 +136,11 @@ impl RafxSampler {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_sampler(&self) -> Option<&RafxSamplerEmpty> {
         match self {
@@ -73,9 +150,38 @@ impl RafxSampler {
             RafxSampler::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSampler::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSampler::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_sampler(&self) -> Option<&RafxSamplerGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxSampler::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxSampler::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSampler::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxSampler::Empty(_) => None,
         }
     }
 }
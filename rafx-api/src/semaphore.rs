@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxSemaphoreEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxSemaphoreGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxSemaphoreMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -23,9 +29,15 @@ pub enum RafxSemaphore {
     Metal(RafxSemaphoreMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxSemaphoreEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxSemaphoreGles3),
 }
 
 impl RafxSemaphore {
@@ -40,9 +52,15 @@ impl RafxSemaphore {
             RafxSemaphore::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSemaphore::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSemaphore::Gles3(_) => None,
         }
     }
 
@@ -57,9 +75,15 @@ impl RafxSemaphore {
             RafxSemaphore::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSemaphore::Empty(inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSemaphore::Gles3(inner) => None,
         }
     }
 
@@ -67,7 +91,11 @@ impl RafxSemaphore {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_semaphore(&self) -> Option<&RafxSemaphoreEmpty> {
         match self {
@@ -77,9 +105,38 @@ impl RafxSemaphore {
             RafxSemaphore::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSemaphore::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSemaphore::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_semaphore(&self) -> Option<&RafxSemaphoreGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxSemaphore::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxSemaphore::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSemaphore::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxSemaphore::Empty(_) => None,
         }
     }
 }
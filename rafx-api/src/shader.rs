@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxShaderEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxShaderGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxShaderMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -18,9 +24,15 @@ pub enum RafxShader {
     Metal(RafxShaderMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxShaderEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxShaderGles3),
 }
 
 impl RafxShader {
@@ -32,9 +44,37 @@ impl RafxShader {
             RafxShader::Metal(inner) => inner.pipeline_reflection(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShader::Empty(inner) => inner.pipeline_reflection(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxShader::Gles3(inner) => inner.pipeline_reflection(),
+        }
+    }
+
+    // Used internally to identify the shader in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxShader::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxShader::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxShader::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxShader::Gles3(inner) => inner.capture_id(),
         }
     }
 
@@ -49,9 +89,15 @@ impl RafxShader {
             RafxShader::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShader::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxShader::Gles3(_inner) => None,
         }
     }
 
@@ -66,14 +112,27 @@ impl RafxShader {
             RafxShader::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShader::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxShader::Gles3(_inner) => None,
         }
     }
 
-    #[cfg(feature = "rafx-metal")]
-    pub fn empty_shader(&self) -> Option<&RafxShaderMetal> {
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn empty_shader(&self) -> Option<&RafxShaderEmpty> {
         match self {
             #[cfg(feature = "rafx-vulkan")]
             RafxShader::Vk(_inner) => None,
@@ -81,9 +140,38 @@ impl RafxShader {
             RafxShader::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShader::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxShader::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_shader(&self) -> Option<&RafxShaderGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxShader::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxShader::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxShader::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxShader::Empty(_) => None,
         }
     }
 }
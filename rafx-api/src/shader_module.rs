@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxShaderModuleEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxShaderModuleGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxShaderModuleMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -21,12 +27,40 @@ pub enum RafxShaderModule {
     Metal(RafxShaderModuleMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxShaderModuleEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxShaderModuleGles3),
 }
 
 impl RafxShaderModule {
+    // Used internally to identify the shader module in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxShaderModule::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxShaderModule::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxShaderModule::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxShaderModule::Gles3(inner) => inner.capture_id(),
+        }
+    }
+
     /// Get the underlying vulkan API object. This provides access to any internally created
     /// vulkan objects.
     #[cfg(feature = "rafx-vulkan")]
@@ -38,9 +72,15 @@ impl RafxShaderModule {
             RafxShaderModule::Metal(_shader_module) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShaderModule::Empty(_shader_module) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxShaderModule::Gles3(_shader_module) => None,
         }
     }
 
@@ -55,9 +95,15 @@ impl RafxShaderModule {
             RafxShaderModule::Metal(shader_module) => Some(shader_module),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShaderModule::Empty(_shader_module) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxShaderModule::Gles3(_shader_module) => None,
         }
     }
 
@@ -65,7 +111,11 @@ impl RafxShaderModule {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_shader_module(&self) -> Option<&RafxShaderModuleEmpty> {
         match self {
@@ -75,9 +125,38 @@ impl RafxShaderModule {
             RafxShaderModule::Metal(_shader_module) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxShaderModule::Empty(shader_module) => Some(shader_module),
+            #[cfg(feature = "rafx-gles3")]
+            RafxShaderModule::Gles3(_shader_module) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_shader_module(&self) -> Option<&RafxShaderModuleGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxShaderModule::Vk(_shader_module) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxShaderModule::Metal(_shader_module) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxShaderModule::Empty(_shader_module) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxShaderModule::Gles3(shader_module) => Some(shader_module),
         }
     }
 }
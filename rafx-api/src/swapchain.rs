@@ -1,17 +1,25 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxSwapchainEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxSwapchainGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxSwapchainMetal;
 #[cfg(feature = "rafx-vulkan")]
 use crate::vulkan::RafxSwapchainVulkan;
 use crate::{
-    RafxFence, RafxFormat, RafxResult, RafxSemaphore, RafxSwapchainDef, RafxSwapchainImage,
+    RafxFence, RafxFormat, RafxOffscreenSwapchain, RafxResult, RafxSemaphore, RafxSwapchainDef,
+    RafxSwapchainImage,
 };
 
-/// A set of images that act as a "backbuffer" of a window.
+/// A set of images that act as a "backbuffer" of a window, or of an offscreen render target
+/// (see `RafxOffscreenSwapchain`).
 pub enum RafxSwapchain {
     #[cfg(feature = "rafx-vulkan")]
     Vk(RafxSwapchainVulkan),
@@ -19,9 +27,17 @@ pub enum RafxSwapchain {
     Metal(RafxSwapchainMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxSwapchainEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxSwapchainGles3),
+    /// Not backed by a window, see `RafxOffscreenSwapchain`
+    Offscreen(Box<RafxOffscreenSwapchain>),
 }
 
 impl RafxSwapchain {
@@ -36,9 +52,16 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(inner) => inner.image_count(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => inner.image_count(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => inner.image_count(),
+            RafxSwapchain::Offscreen(inner) => inner.image_count(),
         }
     }
 
@@ -51,9 +74,16 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(inner) => inner.format(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => inner.format(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => inner.format(),
+            RafxSwapchain::Offscreen(inner) => inner.format(),
         }
     }
 
@@ -66,9 +96,16 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(inner) => inner.swapchain_def(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => inner.swapchain_def(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => inner.swapchain_def(),
+            RafxSwapchain::Offscreen(inner) => inner.swapchain_def(),
         }
     }
 
@@ -88,11 +125,20 @@ impl RafxSwapchain {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => {
                 inner.acquire_next_image_fence(fence.empty_fence().unwrap())
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => {
+                inner.acquire_next_image_fence(fence.gles3_fence().unwrap())
+            }
+            RafxSwapchain::Offscreen(inner) => inner.acquire_next_image_fence(fence),
         }
     }
 
@@ -114,11 +160,20 @@ impl RafxSwapchain {
             }
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => {
                 inner.acquire_next_image_semaphore(semaphore.empty_semaphore().unwrap())
             }
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => {
+                inner.acquire_next_image_semaphore(semaphore.gles3_semaphore().unwrap())
+            }
+            RafxSwapchain::Offscreen(inner) => inner.acquire_next_image_semaphore(semaphore),
         }
     }
 
@@ -134,9 +189,16 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(inner) => inner.rebuild(swapchain_def),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => inner.rebuild(swapchain_def),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => inner.rebuild(swapchain_def),
+            RafxSwapchain::Offscreen(inner) => inner.rebuild(swapchain_def),
         }
     }
 
@@ -151,9 +213,16 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(_) => None,
+            RafxSwapchain::Offscreen(_) => None,
         }
     }
 
@@ -168,9 +237,16 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(_) => None,
+            RafxSwapchain::Offscreen(_) => None,
         }
     }
 
@@ -178,7 +254,11 @@ impl RafxSwapchain {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_swapchain(&self) -> Option<&RafxSwapchainEmpty> {
         match self {
@@ -188,9 +268,48 @@ impl RafxSwapchain {
             RafxSwapchain::Metal(_) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxSwapchain::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(_) => None,
+            RafxSwapchain::Offscreen(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_swapchain(&self) -> Option<&RafxSwapchainGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxSwapchain::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxSwapchain::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxSwapchain::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxSwapchain::Empty(_) => None,
+            RafxSwapchain::Offscreen(_) => None,
+        }
+    }
+
+    /// Get the offscreen swapchain, if this swapchain is not backed by a window
+    pub fn offscreen_swapchain(&self) -> Option<&RafxOffscreenSwapchain> {
+        match self {
+            RafxSwapchain::Offscreen(inner) => Some(&**inner),
+            _ => None,
         }
     }
 }
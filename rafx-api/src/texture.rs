@@ -1,8 +1,14 @@
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 use crate::empty::RafxTextureEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxTextureGles3;
 #[cfg(feature = "rafx-metal")]
 use crate::metal::RafxTextureMetal;
 #[cfg(feature = "rafx-vulkan")]
@@ -20,9 +26,15 @@ pub enum RafxTexture {
     Metal(RafxTextureMetal),
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     Empty(RafxTextureEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxTextureGles3),
 }
 
 impl RafxTexture {
@@ -35,9 +47,62 @@ impl RafxTexture {
             RafxTexture::Metal(inner) => inner.texture_def(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxTexture::Empty(inner) => inner.texture_def(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(inner) => inner.texture_def(),
+        }
+    }
+
+    // Used internally to identify the texture for validation and capture
+    #[cfg(any(feature = "validate-resource-states", feature = "capture"))]
+    pub(crate) fn texture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTexture::Vk(inner) => inner.texture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxTexture::Metal(inner) => inner.texture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTexture::Empty(inner) => inner.texture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(inner) => inner.texture_id(),
+        }
+    }
+
+    /// Sets the name of the texture shown by GPU debugging tools like RenderDoc and Xcode. On
+    /// vulkan this requires VK_EXT_debug_utils, which is enabled when available.
+    pub fn set_debug_name(
+        &self,
+        name: impl AsRef<str>,
+    ) {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTexture::Vk(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-metal")]
+            RafxTexture::Metal(inner) => inner.set_debug_name(name),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTexture::Empty(inner) => inner.set_debug_name(name),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(inner) => inner.set_debug_name(name),
         }
     }
 
@@ -52,9 +117,15 @@ impl RafxTexture {
             RafxTexture::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxTexture::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(_inner) => None,
         }
     }
 
@@ -69,9 +140,15 @@ impl RafxTexture {
             RafxTexture::Metal(inner) => Some(inner),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxTexture::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(_inner) => None,
         }
     }
 
@@ -79,7 +156,11 @@ impl RafxTexture {
     /// metal objects.
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     pub fn empty_texture(&self) -> Option<&RafxTextureEmpty> {
         match self {
@@ -89,9 +170,38 @@ impl RafxTexture {
             RafxTexture::Metal(_inner) => None,
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             RafxTexture::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_texture(&self) -> Option<&RafxTextureGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTexture::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTexture::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTexture::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTexture::Empty(_) => None,
         }
     }
 }
@@ -0,0 +1,203 @@
+#[cfg(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+))]
+use crate::empty::RafxTextureViewEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxTextureViewGles3;
+#[cfg(feature = "rafx-metal")]
+use crate::metal::RafxTextureViewMetal;
+#[cfg(feature = "rafx-vulkan")]
+use crate::vulkan::RafxTextureViewVulkan;
+use crate::{RafxTexture, RafxTextureViewDef};
+
+/// A view of a subset of a texture's mip levels and array layers, optionally reinterpreting its
+/// format or dimensions. Views can be bound to descriptors and used as render targets.
+///
+/// The view keeps its texture alive. Views must not be dropped if they are in use by the GPU.
+#[derive(Clone, Debug)]
+pub enum RafxTextureView {
+    #[cfg(feature = "rafx-vulkan")]
+    Vk(RafxTextureViewVulkan),
+    #[cfg(feature = "rafx-metal")]
+    Metal(RafxTextureViewMetal),
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    Empty(RafxTextureViewEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxTextureViewGles3),
+}
+
+impl RafxTextureView {
+    /// Return the metadata used to create the view, with every field filled in
+    pub fn texture_view_def(&self) -> &RafxTextureViewDef {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(inner) => inner.texture_view_def(),
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(inner) => inner.texture_view_def(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(inner) => inner.texture_view_def(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(inner) => inner.texture_view_def(),
+        }
+    }
+
+    /// The texture this is a view of
+    pub fn texture(&self) -> &RafxTexture {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(inner) => inner.texture(),
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(inner) => inner.texture(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(inner) => inner.texture(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(inner) => inner.texture(),
+        }
+    }
+
+    // Used internally to identify the texture view in captures
+    #[cfg(feature = "capture")]
+    pub(crate) fn capture_id(&self) -> u32 {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(inner) => inner.capture_id(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(inner) => inner.capture_id(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(inner) => inner.capture_id(),
+        }
+    }
+
+    /// Get the underlying vulkan API object. This provides access to any internally created
+    /// vulkan objects.
+    #[cfg(feature = "rafx-vulkan")]
+    pub fn vk_texture_view(&self) -> Option<&RafxTextureViewVulkan> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(inner) => Some(inner),
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(_inner) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(_inner) => None,
+        }
+    }
+
+    /// Get the underlying metal API object. This provides access to any internally created
+    /// metal objects.
+    #[cfg(feature = "rafx-metal")]
+    pub fn metal_texture_view(&self) -> Option<&RafxTextureViewMetal> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(_inner) => None,
+        }
+    }
+
+    /// Get the underlying empty API object.
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn empty_texture_view(&self) -> Option<&RafxTextureViewEmpty> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(_inner) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_texture_view(&self) -> Option<&RafxTextureViewGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTextureView::Vk(_inner) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTextureView::Metal(_inner) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTextureView::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTextureView::Empty(_) => None,
+        }
+    }
+}
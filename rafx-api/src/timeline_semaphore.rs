@@ -0,0 +1,217 @@
+#[cfg(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+))]
+use crate::empty::RafxTimelineSemaphoreEmpty;
+#[cfg(feature = "rafx-gles3")]
+use crate::gles3::RafxTimelineSemaphoreGles3;
+#[cfg(feature = "rafx-metal")]
+use crate::metal::RafxTimelineSemaphoreMetal;
+#[cfg(feature = "rafx-vulkan")]
+use crate::vulkan::RafxTimelineSemaphoreVulkan;
+use crate::RafxResult;
+
+/// A GPU <-> GPU and GPU <-> CPU synchronization mechanism with a monotonically increasing u64
+/// value.
+///
+/// Submits to any queue may wait for the value to reach a given value, and may set it to a new
+/// value when they complete (see `RafxQueue::submit_with_timeline_semaphores`). The CPU may also
+/// read, wait for, and signal the value. This makes it possible to track many frames or queues
+/// with a single object rather than an array of fences.
+///
+/// Check `RafxDeviceInfo::supports_timeline_semaphores` before creating one. This is implemented
+/// with VK_KHR_timeline_semaphore on vulkan and MTLSharedEvent on metal.
+///
+/// Timeline semaphores must not be dropped if they are in use by the GPU.
+pub enum RafxTimelineSemaphore {
+    #[cfg(feature = "rafx-vulkan")]
+    Vk(RafxTimelineSemaphoreVulkan),
+    #[cfg(feature = "rafx-metal")]
+    Metal(RafxTimelineSemaphoreMetal),
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    Empty(RafxTimelineSemaphoreEmpty),
+    #[cfg(feature = "rafx-gles3")]
+    Gles3(RafxTimelineSemaphoreGles3),
+}
+
+impl RafxTimelineSemaphore {
+    /// Returns the value most recently signaled by the GPU or CPU
+    pub fn current_value(&self) -> RafxResult<u64> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(inner) => inner.current_value(),
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(inner) => inner.current_value(),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(inner) => inner.current_value(),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(inner) => inner.current_value(),
+        }
+    }
+
+    /// Sets the value from the CPU. The value must be greater than the current value and any
+    /// value that a pending submit will signal.
+    pub fn signal(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(inner) => inner.signal(value),
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(inner) => inner.signal(value),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(inner) => inner.signal(value),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(inner) => inner.signal(value),
+        }
+    }
+
+    /// Blocks until the value is greater than or equal to the given value
+    pub fn wait(
+        &self,
+        value: u64,
+    ) -> RafxResult<()> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(inner) => inner.wait(value),
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(inner) => inner.wait(value),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(inner) => inner.wait(value),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(inner) => inner.wait(value),
+        }
+    }
+
+    /// Get the underlying vulkan API object. This provides access to any internally created
+    /// vulkan objects.
+    #[cfg(feature = "rafx-vulkan")]
+    pub fn vk_timeline_semaphore(&self) -> Option<&RafxTimelineSemaphoreVulkan> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(inner) => Some(inner),
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(_) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying metal API object. This provides access to any internally created
+    /// metal objects.
+    #[cfg(feature = "rafx-metal")]
+    pub fn metal_timeline_semaphore(&self) -> Option<&RafxTimelineSemaphoreMetal> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying empty API object. This provides access to any internally created
+    /// empty objects.
+    #[cfg(any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    ))]
+    pub fn empty_timeline_semaphore(&self) -> Option<&RafxTimelineSemaphoreEmpty> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(_) => None,
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(inner) => Some(inner),
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(_) => None,
+        }
+    }
+
+    /// Get the underlying GL ES API object. This provides access to any internally created
+    /// GL ES objects.
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_timeline_semaphore(&self) -> Option<&RafxTimelineSemaphoreGles3> {
+        match self {
+            #[cfg(feature = "rafx-vulkan")]
+            RafxTimelineSemaphore::Vk(_) => None,
+            #[cfg(feature = "rafx-metal")]
+            RafxTimelineSemaphore::Metal(_) => None,
+            #[cfg(feature = "rafx-gles3")]
+            RafxTimelineSemaphore::Gles3(inner) => Some(inner),
+            #[cfg(any(
+                feature = "rafx-empty",
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
+            ))]
+            RafxTimelineSemaphore::Empty(_) => None,
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use super::*;
-use crate::{RafxRootSignature, RafxSampler, RafxShader, RafxShaderModule};
+use crate::{
+    RafxCommandBuffer, RafxError, RafxResult, RafxRootSignature, RafxSampler, RafxShader,
+    RafxShaderModule,
+};
 use rafx_base::DecimalF32;
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
 use fnv::FnvHasher;
 #[cfg(feature = "serde-support")]
@@ -10,10 +14,47 @@ use serde::{Deserialize, Serialize};
 /// General configuration that all APIs will make best effort to respect
 #[derive(Default)]
 pub struct RafxApiDef {
-    // Don't have anything that's universal across APIs to add here yet
+    /// Create the device on the adapter with this index in `RafxApi::enumerate_adapters()`. If
+    /// None, the backend picks the most suitable adapter (generally a discrete GPU if available).
+    /// Creation fails if the index is out of range or the adapter is unsupported.
+    pub adapter_index: Option<u32>,
+    /// How resource state errors are reported. Only used when rafx-api is built with the
+    /// `validate-resource-states` feature.
+    pub resource_state_validation_mode: RafxResourceStateValidationMode,
+    /// Track the objects created on the device so that frames recorded between
+    /// `RafxDeviceContext::begin_capture()` and `end_capture()` can be saved and replayed. Only
+    /// used when rafx-api is built with the `capture` feature.
+    pub enable_capture: bool,
+}
+
+/// How the resource state validation enabled by the `validate-resource-states` feature reports
+/// errors. It tracks the last known `RafxResourceState` of each texture subresource and buffer
+/// as command buffers are submitted and reports:
+///  * Barriers with a `src_state` that doesn't match the last known state
+///  * Textures in descriptor sets that are bound for a draw or dispatch while in `RENDER_TARGET`
+///    state
+///  * Textures and buffers that are destroyed while referenced by a submission that is not known
+///    to be complete. Submissions complete when a fence signaled by them (or a later submission
+///    on the same queue) is waited on, or when their queue is waited on to become idle.
+///
+/// Resources are in an unknown state until their first barrier. Transitioning from `UNDEFINED`
+/// is valid from any state. Textures bound through descriptor set handles are not checked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RafxResourceStateValidationMode {
+    /// Log errors with `log::error!`
+    Log,
+    /// Panic when an error is detected
+    Panic,
+}
+
+impl Default for RafxResourceStateValidationMode {
+    fn default() -> Self {
+        RafxResourceStateValidationMode::Log
+    }
 }
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxBufferElementData {
     // For storage buffers
     pub element_begin_index: u64,
@@ -23,6 +64,7 @@ pub struct RafxBufferElementData {
 
 /// Used to create a `RafxBuffer`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxBufferDef {
     pub size: u64,
     pub alignment: u32, // May be 0
@@ -102,8 +144,95 @@ impl RafxBufferDef {
     }
 }
 
+/// Used to create a `RafxHeap`
+#[derive(Clone, Debug)]
+pub struct RafxHeapDef {
+    /// Size of the heap in bytes
+    pub size: u64,
+    /// Buffers placed in the heap must have the same memory usage. Textures may only be placed in
+    /// `GpuOnly` heaps.
+    pub memory_usage: RafxMemoryUsage,
+}
+
+impl Default for RafxHeapDef {
+    fn default() -> Self {
+        RafxHeapDef {
+            size: 0,
+            memory_usage: RafxMemoryUsage::GpuOnly,
+        }
+    }
+}
+
+impl RafxHeapDef {
+    /// Returns an error if a resource with the given requirements can't be placed at the offset
+    pub fn verify_placement(
+        &self,
+        offset: u64,
+        memory_requirements: &RafxMemoryRequirements,
+    ) -> RafxResult<()> {
+        if memory_requirements.alignment != 0 && offset % memory_requirements.alignment != 0 {
+            Err(RafxError::invalid_argument(
+                "offset",
+                format!(
+                    "Heap offset {} does not meet the resource's required alignment of {}",
+                    offset, memory_requirements.alignment
+                ),
+            ))?;
+        }
+
+        if offset + memory_requirements.size > self.size {
+            Err(RafxError::invalid_argument(
+                "offset",
+                format!(
+                    "A resource of {} bytes at offset {} does not fit in a heap of {} bytes",
+                    memory_requirements.size, offset, self.size
+                ),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error if the buffer can't be placed in a heap with this def
+    pub fn verify_buffer_def(
+        &self,
+        buffer_def: &RafxBufferDef,
+    ) -> RafxResult<()> {
+        if buffer_def.memory_usage != self.memory_usage {
+            Err(RafxError::invalid_argument(
+                "memory_usage",
+                format!(
+                    "Buffer memory usage {:?} does not match the heap's memory usage {:?}",
+                    buffer_def.memory_usage, self.memory_usage
+                ),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error if the texture can't be placed in a heap with this def
+    pub fn verify_texture_def(
+        &self,
+        _texture_def: &RafxTextureDef,
+    ) -> RafxResult<()> {
+        if self.memory_usage != RafxMemoryUsage::GpuOnly {
+            Err(RafxError::invalid_argument(
+                "memory_usage",
+                format!(
+                    "Textures may only be placed in GpuOnly heaps, this heap is {:?}",
+                    self.memory_usage
+                ),
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Determines how many dimensions the texture will have.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxTextureDimensions {
     /// Assume 2D if depth = 1, otherwise 3d
     Auto,
@@ -147,6 +276,7 @@ impl RafxTextureDimensions {
 
 /// Used to create a `RafxTexture`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxTextureDef {
     pub extents: RafxExtents3D,
     // Corresponds to number of vulkan layers, metal array length, and dx12 array size. Generally
@@ -204,6 +334,242 @@ impl RafxTextureDef {
             "Cannot use depth stencil as UAV"
         );
     }
+
+    /// Returns an error if the texture exceeds the limits or uses features not supported by the
+    /// device
+    pub fn verify_device_limits(
+        &self,
+        device_info: &RafxDeviceInfo,
+    ) -> RafxResult<()> {
+        let is_cube = self.resource_type.contains(RafxResourceType::TEXTURE_CUBE);
+        let dimensions = self.dimensions.determine_dimensions(self.extents);
+        let (max_dimension, dimension_limit_name) = if is_cube {
+            (
+                device_info.max_texture_dimension_cube,
+                "max_texture_dimension_cube",
+            )
+        } else if dimensions == RafxTextureDimensions::Dim3D {
+            (
+                device_info.max_texture_dimension_3d,
+                "max_texture_dimension_3d",
+            )
+        } else {
+            (
+                device_info.max_texture_dimension_2d,
+                "max_texture_dimension_2d",
+            )
+        };
+
+        let largest_extent = self
+            .extents
+            .width
+            .max(self.extents.height)
+            .max(self.extents.depth);
+        if largest_extent > max_dimension {
+            Err(RafxError::invalid_argument(
+                "extents",
+                format!(
+                    "Texture extents {:?} exceed the device limit {} ({})",
+                    self.extents, dimension_limit_name, max_dimension
+                ),
+            ))?;
+        }
+
+        if self.array_length > device_info.max_texture_array_layers {
+            Err(RafxError::invalid_argument(
+                "array_length",
+                format!(
+                    "Texture array_length {} exceeds the device limit max_texture_array_layers ({})",
+                    self.array_length, device_info.max_texture_array_layers
+                ),
+            ))?;
+        }
+
+        if !device_info.supports_sample_count(self.sample_count) {
+            Err(RafxError::invalid_argument(
+                "sample_count",
+                format!(
+                    "Texture sample_count {:?} is not supported by the device",
+                    self.sample_count
+                ),
+            ))?;
+        }
+
+        if (self.format.is_bc_compressed() && !device_info.supports_bc_texture_compression)
+            || (self.format.is_astc_compressed() && !device_info.supports_astc_texture_compression)
+            || (self.format.is_etc2_compressed() && !device_info.supports_etc2_texture_compression)
+        {
+            Err(RafxError::UnsupportedFormat(self.format))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Determines how a `RafxTextureView` interprets the mip levels and array layers it covers
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub enum RafxTextureViewDimensions {
+    Dim1D,
+    Dim1DArray,
+    Dim2D,
+    Dim2DArray,
+    Dim3D,
+    /// Six array layers viewed as the faces of a cube. The texture must have been created with
+    /// `RafxResourceType::TEXTURE_CUBE`
+    Cube,
+    /// A multiple of six array layers viewed as an array of cubes. The texture must have been
+    /// created with `RafxResourceType::TEXTURE_CUBE`
+    CubeArray,
+}
+
+/// Used to create a `RafxTextureView`. Fields that are None are inherited from the texture: its
+/// format, all of its mip levels and array layers, and the dimensions its own views would use.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxTextureViewDef {
+    /// Reinterpret the texels as this format. Formats with the same texel size (like sRGB and
+    /// UNORM) may be used if the texture was created with
+    /// `RafxResourceType::TEXTURE_MUTABLE_FORMAT`. A depth texture may always be sampled as its
+    /// `RafxFormat::depth_sample_format()`.
+    pub format: Option<RafxFormat>,
+    pub dimensions: Option<RafxTextureViewDimensions>,
+    pub mip_range: Option<Range<u32>>,
+    pub array_range: Option<Range<u32>>,
+}
+
+impl RafxTextureViewDef {
+    /// Returns a copy with every field filled in, or an error if the view is not compatible with
+    /// the texture
+    pub fn resolve(
+        &self,
+        texture_def: &RafxTextureDef,
+    ) -> RafxResult<RafxTextureViewDef> {
+        let texture_format = texture_def.format;
+        let format = self.format.unwrap_or(texture_format);
+        if format != texture_format && texture_format.depth_sample_format() != Some(format) {
+            if !texture_def
+                .resource_type
+                .contains(RafxResourceType::TEXTURE_MUTABLE_FORMAT)
+            {
+                Err(RafxError::invalid_argument(
+                    "format",
+                    format!(
+                        "Cannot view a {:?} texture as {:?}, the texture was not created with RafxResourceType::TEXTURE_MUTABLE_FORMAT",
+                        texture_format, format
+                    ),
+                ))?;
+            }
+
+            let is_compatible = !format.has_depth_or_stencil()
+                && !texture_format.has_depth_or_stencil()
+                && format.block_or_pixel_size_in_bytes()
+                    == texture_format.block_or_pixel_size_in_bytes()
+                && format.block_width_in_pixels() == texture_format.block_width_in_pixels()
+                && format.block_height_in_pixels() == texture_format.block_height_in_pixels();
+            if !is_compatible {
+                Err(RafxError::invalid_argument(
+                    "format",
+                    format!(
+                        "Cannot view a {:?} texture as {:?}, the formats are not compatible",
+                        texture_format, format
+                    ),
+                ))?;
+            }
+        }
+
+        let mip_range = self.mip_range.clone().unwrap_or(0..texture_def.mip_count);
+        if mip_range.start >= mip_range.end || mip_range.end > texture_def.mip_count {
+            Err(RafxError::invalid_argument(
+                "mip_range",
+                format!(
+                    "Mip range {:?} is empty or exceeds the texture's mip count of {}",
+                    mip_range, texture_def.mip_count
+                ),
+            ))?;
+        }
+
+        let array_range = self
+            .array_range
+            .clone()
+            .unwrap_or(0..texture_def.array_length);
+        if array_range.start >= array_range.end || array_range.end > texture_def.array_length {
+            Err(RafxError::invalid_argument(
+                "array_range",
+                format!(
+                    "Array range {:?} is empty or exceeds the texture's array length of {}",
+                    array_range, texture_def.array_length
+                ),
+            ))?;
+        }
+
+        let texture_dimensions = texture_def
+            .dimensions
+            .determine_dimensions(texture_def.extents);
+        let is_cube = texture_def
+            .resource_type
+            .contains(RafxResourceType::TEXTURE_CUBE);
+        let layer_count = array_range.end - array_range.start;
+        let dimensions = self.dimensions.unwrap_or(match texture_dimensions {
+            RafxTextureDimensions::Dim1D if layer_count > 1 => {
+                RafxTextureViewDimensions::Dim1DArray
+            }
+            RafxTextureDimensions::Dim1D => RafxTextureViewDimensions::Dim1D,
+            RafxTextureDimensions::Dim2D if is_cube && layer_count == 6 => {
+                RafxTextureViewDimensions::Cube
+            }
+            RafxTextureDimensions::Dim2D if is_cube && layer_count % 6 == 0 => {
+                RafxTextureViewDimensions::CubeArray
+            }
+            RafxTextureDimensions::Dim2D if layer_count > 1 => {
+                RafxTextureViewDimensions::Dim2DArray
+            }
+            RafxTextureDimensions::Dim2D => RafxTextureViewDimensions::Dim2D,
+            RafxTextureDimensions::Dim3D => RafxTextureViewDimensions::Dim3D,
+            RafxTextureDimensions::Auto => unreachable!(),
+        });
+
+        let is_compatible = match dimensions {
+            RafxTextureViewDimensions::Dim1D => {
+                texture_dimensions == RafxTextureDimensions::Dim1D && layer_count == 1
+            }
+            RafxTextureViewDimensions::Dim1DArray => {
+                texture_dimensions == RafxTextureDimensions::Dim1D
+            }
+            RafxTextureViewDimensions::Dim2D => {
+                texture_dimensions == RafxTextureDimensions::Dim2D && layer_count == 1
+            }
+            RafxTextureViewDimensions::Dim2DArray => {
+                texture_dimensions == RafxTextureDimensions::Dim2D
+            }
+            RafxTextureViewDimensions::Dim3D => texture_dimensions == RafxTextureDimensions::Dim3D,
+            RafxTextureViewDimensions::Cube => {
+                texture_dimensions == RafxTextureDimensions::Dim2D && is_cube && layer_count == 6
+            }
+            RafxTextureViewDimensions::CubeArray => {
+                texture_dimensions == RafxTextureDimensions::Dim2D
+                    && is_cube
+                    && layer_count % 6 == 0
+            }
+        };
+
+        if !is_compatible {
+            Err(RafxError::invalid_argument(
+                "dimensions",
+                format!(
+                    "A {:?} view of {} array layers is not compatible with a {:?} texture (resource_type: {:?})",
+                    dimensions, layer_count, texture_dimensions, texture_def.resource_type
+                ),
+            ))?;
+        }
+
+        Ok(RafxTextureViewDef {
+            format: Some(format),
+            dimensions: Some(dimensions),
+            mip_range: Some(mip_range),
+            array_range: Some(array_range),
+        })
+    }
 }
 
 /// Used to create a `RafxCommandPool`
@@ -214,6 +580,19 @@ pub struct RafxCommandPoolDef {
     pub transient: bool,
 }
 
+/// Used to create a `RafxQueryPool`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RafxQueryPoolDef {
+    pub query_type: RafxQueryType,
+    pub query_count: u32,
+}
+
+impl RafxQueryPoolDef {
+    pub fn verify(&self) {
+        assert!(self.query_count > 0);
+    }
+}
+
 /// Used to create a `RafxCommandBuffer`
 #[derive(Debug, Clone, PartialEq)]
 pub struct RafxCommandBufferDef {
@@ -221,6 +600,20 @@ pub struct RafxCommandBufferDef {
     pub is_secondary: bool,
 }
 
+/// Used to begin a secondary `RafxCommandBuffer` that will be executed within a render pass. The
+/// formats and sample count must match the render targets of the render pass.
+#[derive(Debug)]
+pub struct RafxCommandBufferInheritanceInfo<'a> {
+    pub color_formats: &'a [RafxFormat],
+    pub depth_stencil_format: Option<RafxFormat>,
+    pub sample_count: RafxSampleCount,
+
+    /// The primary command buffer the secondary command buffer will be executed by. Required on
+    /// metal, where the render pass must already have been begun with
+    /// `cmd_begin_render_pass_for_secondary` and the secondary encodes directly into it.
+    pub primary_command_buffer: Option<&'a RafxCommandBuffer>,
+}
+
 /// Used to create a `RafxSwapchain`
 #[derive(Clone, Debug)]
 pub struct RafxSwapchainDef {
@@ -230,11 +623,23 @@ pub struct RafxSwapchainDef {
     // image count?
 }
 
+/// Used to create a `RafxOffscreenSwapchain`, a swapchain backed by plain render target textures
+/// rather than a window surface
+#[derive(Clone, Debug)]
+pub struct RafxOffscreenSwapchainDef {
+    /// Size of the images. `enable_vsync` is ignored.
+    pub swapchain_def: RafxSwapchainDef,
+    pub format: RafxFormat,
+    pub image_count: u32,
+}
+
 /// Describes a single stage within a shader
 #[derive(Clone, Debug)]
 pub struct RafxShaderStageDef {
     pub shader_module: RafxShaderModule,
     pub reflection: RafxShaderStageReflection,
+    // Overrides the defaults of constants listed in reflection.specialization_constants
+    pub specialization_constants: Vec<RafxSpecializationConstant>,
 }
 
 impl RafxShaderStageDef {
@@ -242,21 +647,28 @@ impl RafxShaderStageDef {
         hasher: &mut HasherT,
         reflection_data: &[&RafxShaderStageReflection],
         shader_module_hashes: &[ShaderModuleHashT],
+        specialization_constants: &[&[RafxSpecializationConstant]],
     ) {
         assert_eq!(reflection_data.len(), shader_module_hashes.len());
+        assert_eq!(reflection_data.len(), specialization_constants.len());
         fn hash_stage<HasherT: std::hash::Hasher, ShaderModuleHashT: Hash>(
             hasher: &mut HasherT,
             stage_flag: RafxShaderStageFlags,
             reflection_data: &[&RafxShaderStageReflection],
             shader_module_hashes: &[ShaderModuleHashT],
+            specialization_constants: &[&[RafxSpecializationConstant]],
         ) {
-            for (reflection, shader_module_hash) in reflection_data.iter().zip(shader_module_hashes)
+            for ((reflection, shader_module_hash), specialization_constants) in reflection_data
+                .iter()
+                .zip(shader_module_hashes)
+                .zip(specialization_constants)
             {
                 if reflection.shader_stage.intersects(stage_flag) {
                     reflection.shader_stage.hash(hasher);
                     reflection.entry_point_name.hash(hasher);
                     reflection.resources.hash(hasher);
                     shader_module_hash.hash(hasher);
+                    specialization_constants.hash(hasher);
                     break;
                 }
             }
@@ -264,8 +676,63 @@ impl RafxShaderStageDef {
 
         // Hash stages in a deterministic order
         for stage_flag in &crate::ALL_SHADER_STAGE_FLAGS {
-            hash_stage(hasher, *stage_flag, reflection_data, shader_module_hashes);
+            hash_stage(
+                hasher,
+                *stage_flag,
+                reflection_data,
+                shader_module_hashes,
+                specialization_constants,
+            );
+        }
+    }
+
+    pub(crate) fn verify_specialization_constants(&self) -> RafxResult<()> {
+        for (index, constant) in self.specialization_constants.iter().enumerate() {
+            if self.specialization_constants[..index]
+                .iter()
+                .any(|x| x.constant_id == constant.constant_id)
+            {
+                Err(RafxError::invalid_argument(
+                    "specialization_constants",
+                    format!(
+                        "Specialization constant {} is set more than once",
+                        constant.constant_id
+                    ),
+                ))?;
+            }
+
+            let reflection = self
+                .reflection
+                .specialization_constants
+                .iter()
+                .find(|x| x.constant_id == constant.constant_id)
+                .ok_or_else(|| {
+                    RafxError::reflection_mismatch(
+                        None,
+                        format!(
+                            "Specialization constant {} is not declared by shader stage {:?} (entry point {:?})",
+                            constant.constant_id,
+                            self.reflection.shader_stage,
+                            self.reflection.entry_point_name
+                        ),
+                    )
+                })?;
+
+            if !reflection.default_value.is_same_type(constant.value) {
+                Err(RafxError::reflection_mismatch(
+                    reflection.name.as_deref(),
+                    format!(
+                        "Specialization constant {} ({:?}) was given value {:?} but the shader declares it as {:?}",
+                        constant.constant_id,
+                        reflection.name,
+                        constant.value,
+                        reflection.default_value
+                    ),
+                ))?;
+            }
         }
+
+        Ok(())
     }
 }
 
@@ -318,15 +785,70 @@ impl<'a> RafxImmutableSamplers<'a> {
     }
 }
 
+/// Indicates which descriptor is declared as a bindless array
+#[derive(Clone, Hash)]
+pub enum RafxBindlessArrayKey<'a> {
+    Name(&'a str),
+    Binding(u32, u32),
+}
+
+impl<'a> RafxBindlessArrayKey<'a> {
+    pub fn from_name(name: &'a str) -> RafxBindlessArrayKey<'a> {
+        RafxBindlessArrayKey::Name(name)
+    }
+
+    pub fn from_binding(
+        set_index: u32,
+        binding: u32,
+    ) -> RafxBindlessArrayKey<'a> {
+        RafxBindlessArrayKey::Binding(set_index, binding)
+    }
+}
+
+/// Declares a texture or buffer array descriptor as bindless. The array is sized to
+/// `max_element_count` regardless of the size declared in the shader (which may be unbounded),
+/// elements may be left unset, and elements may be updated after the descriptor set has been
+/// bound as long as the GPU is not using them. Requires
+/// `RafxDeviceInfo::supports_bindless_descriptors`.
+#[derive(Clone, Hash)]
+pub struct RafxBindlessArray<'a> {
+    pub key: RafxBindlessArrayKey<'a>,
+    pub max_element_count: u32,
+}
+
+impl<'a> RafxBindlessArray<'a> {
+    pub fn from_name(
+        name: &'a str,
+        max_element_count: u32,
+    ) -> RafxBindlessArray<'a> {
+        RafxBindlessArray {
+            key: RafxBindlessArrayKey::from_name(name),
+            max_element_count,
+        }
+    }
+
+    pub fn from_binding(
+        set_index: u32,
+        binding: u32,
+        max_element_count: u32,
+    ) -> RafxBindlessArray<'a> {
+        RafxBindlessArray {
+            key: RafxBindlessArrayKey::from_binding(set_index, binding),
+            max_element_count,
+        }
+    }
+}
+
 /// Used to create a `RafxRootSignature`
 pub struct RafxRootSignatureDef<'a> {
     pub shaders: &'a [RafxShader],
     pub immutable_samplers: &'a [RafxImmutableSamplers<'a>],
+    pub bindless_arrays: &'a [RafxBindlessArray<'a>],
 }
 
 impl<'a> RafxRootSignatureDef<'a> {
     // The current implementation here is minimal. It will produce different hash values for
-    // shader orderings and immutable samplers.
+    // shader orderings, immutable samplers and bindless arrays.
     pub fn hash_definition<
         HasherT: std::hash::Hasher,
         ShaderHashT: Hash,
@@ -336,6 +858,7 @@ impl<'a> RafxRootSignatureDef<'a> {
         shader_hashes: &[ShaderHashT],
         immutable_sampler_keys: &[RafxImmutableSamplerKey],
         immutable_sampler_hashes: &[Vec<ImmutableSamplerHashT>],
+        bindless_arrays: &[RafxBindlessArray],
     ) {
         // Hash all the shader hashes and xor them together, this keeps them order-independent
         let mut combined_shaders_hash = 0;
@@ -355,9 +878,18 @@ impl<'a> RafxRootSignatureDef<'a> {
             combined_immutable_samplers_hash ^= h.finish();
         }
 
-        // Hash both combined hashes to produce the final hash
+        // Same for bindless arrays
+        let mut combined_bindless_arrays_hash = 0;
+        for bindless_array in bindless_arrays {
+            let mut h = FnvHasher::default();
+            bindless_array.hash(&mut h);
+            combined_bindless_arrays_hash ^= h.finish();
+        }
+
+        // Hash all combined hashes to produce the final hash
         combined_shaders_hash.hash(hasher);
         combined_immutable_samplers_hash.hash(hasher);
+        combined_bindless_arrays_hash.hash(hasher);
     }
 }
 
@@ -388,6 +920,36 @@ pub struct RafxSamplerDef {
 
 impl Eq for RafxSamplerDef {}
 
+impl RafxSamplerDef {
+    /// Returns an error if the sampler can't be created on the device. Sampler defs usually ask for
+    /// 16x anisotropy regardless of the device, so a max_anisotropy above the device limit only logs
+    /// a warning and backends clamp it.
+    pub fn verify_device_limits(
+        &self,
+        device_info: &RafxDeviceInfo,
+    ) -> RafxResult<()> {
+        if self.max_anisotropy.is_nan() || self.max_anisotropy < 0.0 {
+            Err(RafxError::invalid_argument(
+                "max_anisotropy",
+                format!(
+                    "Sampler max_anisotropy {} must not be negative",
+                    self.max_anisotropy
+                ),
+            ))?;
+        }
+
+        if self.max_anisotropy > device_info.max_sampler_anisotropy {
+            log::warn!(
+                "Sampler max_anisotropy {} exceeds the device limit max_sampler_anisotropy ({}), it will be clamped",
+                self.max_anisotropy,
+                device_info.max_sampler_anisotropy
+            );
+        }
+
+        Ok(())
+    }
+}
+
 impl Hash for RafxSamplerDef {
     fn hash<H: Hasher>(
         &self,
@@ -407,6 +969,7 @@ impl Hash for RafxSamplerDef {
 
 /// Describes an attribute within a RafxVertexLayout
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxVertexLayoutAttribute {
     /// Format of the attribute
     pub format: RafxFormat,
@@ -420,6 +983,7 @@ pub struct RafxVertexLayoutAttribute {
 
 /// Describes a buffer that provides vertex attribute data (See RafxVertexLayout)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxVertexLayoutBuffer {
     pub stride: u32,
     pub rate: RafxVertexAttributeRate,
@@ -427,6 +991,7 @@ pub struct RafxVertexLayoutBuffer {
 
 /// Describes how vertex attributes are laid out within one or more buffers
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxVertexLayout {
     pub attributes: Vec<RafxVertexLayoutAttribute>,
     pub buffers: Vec<RafxVertexLayoutBuffer>,
@@ -474,7 +1039,7 @@ impl Default for RafxDepthState {
 }
 
 /// Affects rasterization, commonly used to enable backface culling or wireframe rendering
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxRasterizerState {
     pub cull_mode: RafxCullMode,
@@ -482,10 +1047,33 @@ pub struct RafxRasterizerState {
     pub fill_mode: RafxFillMode,
     pub depth_bias: i32,
     pub depth_bias_slope_scaled: f32,
+    /// If true, depth_bias and depth_bias_slope_scaled are ignored and the bias is set with
+    /// `RafxCommandBuffer::cmd_set_depth_bias` after binding the pipeline. The ignored values are
+    /// not compared or hashed, so states that only differ by depth bias can share a pipeline.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub dynamic_depth_bias: bool,
     pub depth_clamp_enable: bool,
     pub multisample: bool,
     pub scissor: bool,
-    // Hash implemented manually below, don't forget to update it!
+    // PartialEq and Hash implemented manually below, don't forget to update them!
+}
+
+impl PartialEq for RafxRasterizerState {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.cull_mode == other.cull_mode
+            && self.front_face == other.front_face
+            && self.fill_mode == other.fill_mode
+            && self.dynamic_depth_bias == other.dynamic_depth_bias
+            && (self.dynamic_depth_bias
+                || (self.depth_bias == other.depth_bias
+                    && self.depth_bias_slope_scaled == other.depth_bias_slope_scaled))
+            && self.depth_clamp_enable == other.depth_clamp_enable
+            && self.multisample == other.multisample
+            && self.scissor == other.scissor
+    }
 }
 
 impl Eq for RafxRasterizerState {}
@@ -498,8 +1086,11 @@ impl Hash for RafxRasterizerState {
         self.cull_mode.hash(&mut state);
         self.front_face.hash(&mut state);
         self.fill_mode.hash(&mut state);
-        self.depth_bias.hash(&mut state);
-        DecimalF32(self.depth_bias_slope_scaled).hash(&mut state);
+        self.dynamic_depth_bias.hash(&mut state);
+        if !self.dynamic_depth_bias {
+            self.depth_bias.hash(&mut state);
+            DecimalF32(self.depth_bias_slope_scaled).hash(&mut state);
+        }
         self.depth_clamp_enable.hash(&mut state);
         self.multisample.hash(&mut state);
         self.scissor.hash(&mut state);
@@ -514,6 +1105,7 @@ impl Default for RafxRasterizerState {
             fill_mode: Default::default(),
             depth_bias: 0,
             depth_bias_slope_scaled: 0.0,
+            dynamic_depth_bias: false,
             depth_clamp_enable: false,
             multisample: false,
             scissor: false,
@@ -646,6 +1238,81 @@ pub struct RafxGraphicsPipelineDef<'a> {
     //indirect_commands_enable: bool
 }
 
+impl<'a> RafxGraphicsPipelineDef<'a> {
+    /// Returns an error if the pipeline exceeds the limits or uses features not supported by the
+    /// device
+    pub fn verify_device_limits(
+        &self,
+        device_info: &RafxDeviceInfo,
+    ) -> RafxResult<()> {
+        if self.color_formats.len() > device_info.max_color_render_targets as usize {
+            Err(RafxError::invalid_argument(
+                "color_formats",
+                format!(
+                    "Graphics pipeline has {} color formats, exceeding the device limit max_color_render_targets ({})",
+                    self.color_formats.len(),
+                    device_info.max_color_render_targets
+                ),
+            ))?;
+        }
+
+        if !device_info.supports_sample_count(self.sample_count) {
+            Err(RafxError::invalid_argument(
+                "sample_count",
+                format!(
+                    "Graphics pipeline sample_count {:?} is not supported by the device",
+                    self.sample_count
+                ),
+            ))?;
+        }
+
+        if self.vertex_layout.buffers.len() > device_info.max_vertex_input_bindings as usize {
+            Err(RafxError::invalid_argument(
+                "vertex_layout",
+                format!(
+                    "Graphics pipeline has {} vertex buffers, exceeding the device limit max_vertex_input_bindings ({})",
+                    self.vertex_layout.buffers.len(),
+                    device_info.max_vertex_input_bindings
+                ),
+            ))?;
+        }
+
+        if self.vertex_layout.attributes.len() > device_info.max_vertex_attributes as usize {
+            Err(RafxError::invalid_argument(
+                "vertex_layout",
+                format!(
+                    "Graphics pipeline has {} vertex attributes, exceeding the device limit max_vertex_attributes ({})",
+                    self.vertex_layout.attributes.len(),
+                    device_info.max_vertex_attributes
+                ),
+            ))?;
+        }
+
+        let shader_stages = self.shader.pipeline_reflection().shader_stages;
+        if shader_stages.intersects(RafxShaderStageFlags::GEOMETRY)
+            && !device_info.supports_geometry_shaders
+        {
+            Err(RafxError::invalid_argument(
+                "shader",
+                "Graphics pipeline uses a geometry shader, which is not supported by the device",
+            ))?;
+        }
+
+        if shader_stages.intersects(
+            RafxShaderStageFlags::TESSELLATION_CONTROL
+                | RafxShaderStageFlags::TESSELLATION_EVALUATION,
+        ) && !device_info.supports_tessellation_shaders
+        {
+            Err(RafxError::invalid_argument(
+                "shader",
+                "Graphics pipeline uses tessellation shaders, which are not supported by the device",
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Used to create a `RafxPipeline` for compute operations
 #[derive(Debug)]
 pub struct RafxComputePipelineDef<'a> {
@@ -783,7 +783,8 @@ impl Into<MTLPixelFormat> for RafxFormat {
             RafxFormat::ASTC_12X10_SRGB_BLOCK => MTLPixelFormat::ASTC_12x10_sRGB,
             RafxFormat::ASTC_12X12_UNORM_BLOCK => MTLPixelFormat::ASTC_12x12_LDR,
             RafxFormat::ASTC_12X12_SRGB_BLOCK => MTLPixelFormat::ASTC_12x12_sRGB,
-            _ => unimplemented!(),
+            // Metal has no equivalent (i.e. 3-channel formats), textures reject this as unsupported
+            _ => MTLPixelFormat::Invalid,
         }
     }
 }
@@ -882,10 +883,93 @@ impl RafxFormat {
         self.is_depth_and_stencil() || self.is_stencil_only() || self.is_depth_only()
     }
 
+    /// The single-channel color format that shaders read when sampling the depth of this format,
+    /// if there is one. Texture views may use it to sample a depth texture as a color texture.
+    pub fn depth_sample_format(self) -> Option<RafxFormat> {
+        match self {
+            RafxFormat::D16_UNORM | RafxFormat::D16_UNORM_S8_UINT => Some(RafxFormat::R16_UNORM),
+            RafxFormat::D32_SFLOAT | RafxFormat::D32_SFLOAT_S8_UINT => Some(RafxFormat::R32_SFLOAT),
+            _ => None,
+        }
+    }
+
     pub fn is_undefined(self) -> bool {
         self == RafxFormat::UNDEFINED
     }
 
+    pub fn is_bc_compressed(self) -> bool {
+        match self {
+            RafxFormat::BC1_RGB_UNORM_BLOCK
+            | RafxFormat::BC1_RGB_SRGB_BLOCK
+            | RafxFormat::BC1_RGBA_UNORM_BLOCK
+            | RafxFormat::BC1_RGBA_SRGB_BLOCK
+            | RafxFormat::BC2_UNORM_BLOCK
+            | RafxFormat::BC2_SRGB_BLOCK
+            | RafxFormat::BC3_UNORM_BLOCK
+            | RafxFormat::BC3_SRGB_BLOCK
+            | RafxFormat::BC4_UNORM_BLOCK
+            | RafxFormat::BC4_SNORM_BLOCK
+            | RafxFormat::BC5_UNORM_BLOCK
+            | RafxFormat::BC5_SNORM_BLOCK
+            | RafxFormat::BC6H_UFLOAT_BLOCK
+            | RafxFormat::BC6H_SFLOAT_BLOCK
+            | RafxFormat::BC7_UNORM_BLOCK
+            | RafxFormat::BC7_SRGB_BLOCK => true,
+            _ => false,
+        }
+    }
+
+    // Includes EAC formats, which are supported alongside ETC2
+    pub fn is_etc2_compressed(self) -> bool {
+        match self {
+            RafxFormat::ETC2_R8G8B8_UNORM_BLOCK
+            | RafxFormat::ETC2_R8G8B8_SRGB_BLOCK
+            | RafxFormat::ETC2_R8G8B8A1_UNORM_BLOCK
+            | RafxFormat::ETC2_R8G8B8A1_SRGB_BLOCK
+            | RafxFormat::ETC2_R8G8B8A8_UNORM_BLOCK
+            | RafxFormat::ETC2_R8G8B8A8_SRGB_BLOCK
+            | RafxFormat::EAC_R11_UNORM_BLOCK
+            | RafxFormat::EAC_R11_SNORM_BLOCK
+            | RafxFormat::EAC_R11G11_UNORM_BLOCK
+            | RafxFormat::EAC_R11G11_SNORM_BLOCK => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_astc_compressed(self) -> bool {
+        match self {
+            RafxFormat::ASTC_4X4_UNORM_BLOCK
+            | RafxFormat::ASTC_4X4_SRGB_BLOCK
+            | RafxFormat::ASTC_5X4_UNORM_BLOCK
+            | RafxFormat::ASTC_5X4_SRGB_BLOCK
+            | RafxFormat::ASTC_5X5_UNORM_BLOCK
+            | RafxFormat::ASTC_5X5_SRGB_BLOCK
+            | RafxFormat::ASTC_6X5_UNORM_BLOCK
+            | RafxFormat::ASTC_6X5_SRGB_BLOCK
+            | RafxFormat::ASTC_6X6_UNORM_BLOCK
+            | RafxFormat::ASTC_6X6_SRGB_BLOCK
+            | RafxFormat::ASTC_8X5_UNORM_BLOCK
+            | RafxFormat::ASTC_8X5_SRGB_BLOCK
+            | RafxFormat::ASTC_8X6_UNORM_BLOCK
+            | RafxFormat::ASTC_8X6_SRGB_BLOCK
+            | RafxFormat::ASTC_8X8_UNORM_BLOCK
+            | RafxFormat::ASTC_8X8_SRGB_BLOCK
+            | RafxFormat::ASTC_10X5_UNORM_BLOCK
+            | RafxFormat::ASTC_10X5_SRGB_BLOCK
+            | RafxFormat::ASTC_10X6_UNORM_BLOCK
+            | RafxFormat::ASTC_10X6_SRGB_BLOCK
+            | RafxFormat::ASTC_10X8_UNORM_BLOCK
+            | RafxFormat::ASTC_10X8_SRGB_BLOCK
+            | RafxFormat::ASTC_10X10_UNORM_BLOCK
+            | RafxFormat::ASTC_10X10_SRGB_BLOCK
+            | RafxFormat::ASTC_12X10_UNORM_BLOCK
+            | RafxFormat::ASTC_12X10_SRGB_BLOCK
+            | RafxFormat::ASTC_12X12_UNORM_BLOCK
+            | RafxFormat::ASTC_12X12_SRGB_BLOCK => true,
+            _ => false,
+        }
+    }
+
     pub fn block_or_pixel_size_in_bytes(self) -> u32 {
         match self {
             RafxFormat::UNDEFINED => unimplemented!(),
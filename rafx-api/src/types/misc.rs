@@ -1,7 +1,10 @@
 #[cfg(feature = "serde-support")]
 use serde::{Deserialize, Serialize};
 
-use crate::{RafxBuffer, RafxSampler, RafxTexture};
+use crate::{
+    RafxBuffer, RafxError, RafxFormat, RafxResult, RafxSampler, RafxTexture, RafxTextureView,
+    RafxTimelineSemaphore,
+};
 use rafx_base::DecimalF32;
 use std::hash::{Hash, Hasher};
 
@@ -43,7 +46,85 @@ pub struct RafxDeviceInfo {
 
     // Requires iOS 14.0, macOS 10.12
     pub supports_clamp_to_border_color: bool,
-    // max_vertex_input_binding_count: u32,
+
+    /// If false, creating a timestamp query pool will fail
+    pub supports_timestamp_queries: bool,
+    /// If false, creating an occlusion query pool will fail
+    pub supports_occlusion_queries: bool,
+    /// If false, creating a pipeline statistics query pool will fail
+    pub supports_pipeline_statistics_queries: bool,
+    /// Nanoseconds per timestamp tick. Multiply the difference between two timestamps by this to
+    /// get elapsed time
+    pub timestamp_period_ns: f32,
+
+    /// If false, creating a RafxTimelineSemaphore will fail
+    pub supports_timeline_semaphores: bool,
+
+    /// Identifies the driver's format for RafxDeviceContext::pipeline_cache_data(). Cache data can
+    /// only be loaded by a device with the same UUID. All zeros if the backend has no pipeline
+    /// cache
+    pub pipeline_cache_uuid: [u8; 16],
+
+    /// The largest width or height of a 2D texture. Creating a texture that exceeds it will fail
+    pub max_texture_dimension_2d: u32,
+    /// The largest width, height or depth of a 3D texture. Creating a texture that exceeds it will
+    /// fail
+    pub max_texture_dimension_3d: u32,
+    /// The largest width or height of a cube texture. Creating a texture that exceeds it will fail
+    pub max_texture_dimension_cube: u32,
+    /// The largest array_length of a texture. Creating a texture that exceeds it will fail
+    pub max_texture_array_layers: u32,
+    /// The highest value allowed for RafxSamplerDef::max_anisotropy. Samplers that exceed it are
+    /// clamped to it
+    pub max_sampler_anisotropy: f32,
+
+    /// The most color render targets a graphics pipeline may write to. Creating a pipeline that
+    /// exceeds it will fail
+    pub max_color_render_targets: u32,
+    /// The most vertex buffers a graphics pipeline may read from. Creating a pipeline that exceeds
+    /// it will fail
+    pub max_vertex_input_bindings: u32,
+    /// The most vertex attributes a graphics pipeline may declare. Creating a pipeline that
+    /// exceeds it will fail
+    pub max_vertex_attributes: u32,
+
+    /// The largest workgroup size of a compute shader in each dimension, in the same units as the
+    /// shader's declared workgroup size
+    pub max_compute_workgroup_size: [u32; 3],
+    /// The most invocations in a single compute workgroup (the product of the workgroup size)
+    pub max_compute_workgroup_invocations: u32,
+    /// The most workgroups that may be passed to cmd_dispatch() in each dimension
+    pub max_compute_workgroup_count: [u32; 3],
+
+    /// Sample counts that may be used for render targets (both color and depth)
+    pub supported_sample_counts: Vec<RafxSampleCount>,
+
+    /// If false, creating a texture with a BC compressed format will fail
+    pub supports_bc_texture_compression: bool,
+    /// If false, creating a texture with an ASTC compressed format will fail
+    pub supports_astc_texture_compression: bool,
+    /// If false, creating a texture with an ETC2 or EAC compressed format will fail
+    pub supports_etc2_texture_compression: bool,
+
+    /// If false, creating a pipeline with a geometry shader stage will fail. Never supported on
+    /// metal
+    pub supports_geometry_shaders: bool,
+    /// If false, creating a pipeline with tessellation shader stages will fail. Never supported
+    /// on metal
+    pub supports_tessellation_shaders: bool,
+
+    /// Line widths other than 1.0 may only be passed to cmd_set_line_width if wide lines are
+    /// supported. Never supported on metal
+    pub supports_wide_lines: bool,
+    /// The widest line width that may be passed to cmd_set_line_width
+    pub max_line_width: f32,
+
+    /// Root signatures may only declare bindless arrays (see RafxBindlessArray) if this is
+    /// supported. Requires VK_EXT_descriptor_indexing on vulkan and argument buffers tier 2 on
+    /// metal
+    pub supports_bindless_descriptors: bool,
+    /// The highest RafxBindlessArray::max_element_count allowed
+    pub max_bindless_descriptor_count: u32,
     // max_root_signature_dwords: u32,
     // wave_lane_count: u32,
     // wave_ops_support_flags: u32,
@@ -54,8 +135,126 @@ pub struct RafxDeviceInfo {
     // metal_draw_index_vertex_offset_supported: bool,
 }
 
+impl RafxDeviceInfo {
+    /// Returns true if render targets may be created and rendered with the given sample count
+    pub fn supports_sample_count(
+        &self,
+        sample_count: RafxSampleCount,
+    ) -> bool {
+        self.supported_sample_counts.contains(&sample_count)
+    }
+
+    /// Returns an error if the width cannot be passed to `RafxCommandBuffer::cmd_set_line_width`
+    pub fn verify_line_width(
+        &self,
+        line_width: f32,
+    ) -> RafxResult<()> {
+        if line_width.is_nan() || line_width <= 0.0 {
+            Err(RafxError::invalid_argument(
+                "line_width",
+                format!("Line width {} must be greater than 0", line_width),
+            ))?;
+        }
+
+        if line_width != 1.0 && !self.supports_wide_lines {
+            Err(RafxError::invalid_argument(
+                "line_width",
+                format!(
+                    "Line width {} is not supported, the device does not support wide lines",
+                    line_width
+                ),
+            ))?;
+        }
+
+        if line_width > self.max_line_width {
+            Err(RafxError::invalid_argument(
+                "line_width",
+                format!(
+                    "Line width {} exceeds the device limit max_line_width ({})",
+                    line_width, self.max_line_width
+                ),
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The kind of adapter, as reported by the driver
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RafxAdapterType {
+    DiscreteGpu,
+    IntegratedGpu,
+    VirtualGpu,
+    /// A software rasterizer like lavapipe or SwiftShader
+    Cpu,
+    Other,
+}
+
+/// Describes a GPU (or software rasterizer) that a device can be created on. Returned by
+/// `RafxApi::enumerate_adapters()` and the backend-specific `enumerate_adapters_*()` functions
+#[derive(Clone, Debug)]
+pub struct RafxAdapterInfo {
+    /// Pass this as `RafxApiDef::adapter_index` to create the device on this adapter
+    pub adapter_index: u32,
+    pub name: String,
+    /// PCI vendor ID, or 0 if not reported
+    pub vendor_id: u32,
+    /// PCI device ID, or 0 if not reported
+    pub device_id: u32,
+    pub adapter_type: RafxAdapterType,
+    /// Driver version in a vendor-specific format. Empty if the backend does not report one.
+    pub driver_version: String,
+    /// False if the adapter lacks something rafx requires, creating a device on it will fail
+    pub is_supported: bool,
+    /// True if this is the adapter the backend picks when `RafxApiDef::adapter_index` is None
+    pub is_default: bool,
+}
+
+/// The kind of data collected by a `RafxQueryPool`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RafxQueryType {
+    /// Written by `cmd_write_timestamp`. Use `RafxDeviceInfo::timestamp_period_ns` to convert the
+    /// difference between two timestamps to nanoseconds.
+    Timestamp,
+    /// Counts the samples that pass depth/stencil testing between `cmd_begin_query` and
+    /// `cmd_end_query`
+    Occlusion,
+    /// Collects the counters in `RafxPipelineStatistics` between `cmd_begin_query` and
+    /// `cmd_end_query`
+    PipelineStatistics,
+}
+
+impl RafxQueryType {
+    /// The number of u64 values written per query by `cmd_resolve_query`
+    pub fn values_per_query(self) -> u32 {
+        match self {
+            RafxQueryType::Timestamp => 1,
+            RafxQueryType::Occlusion => 1,
+            RafxQueryType::PipelineStatistics => {
+                (std::mem::size_of::<RafxPipelineStatistics>() / std::mem::size_of::<u64>()) as u32
+            }
+        }
+    }
+}
+
+/// The counters collected by a `RafxQueryType::PipelineStatistics` query, in the order they are
+/// written by `cmd_resolve_query`. A resolved buffer can be read directly as this struct.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct RafxPipelineStatistics {
+    pub input_assembly_vertices: u64,
+    pub input_assembly_primitives: u64,
+    pub vertex_shader_invocations: u64,
+    pub clipping_invocations: u64,
+    pub clipping_primitives: u64,
+    pub fragment_shader_invocations: u64,
+    pub compute_shader_invocations: u64,
+}
+
 /// Used to indicate which type of queue to use. Some operations require certain types of queues.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxQueueType {
     /// Graphics queues generally supports all operations and are a safe default choice
     Graphics,
@@ -92,6 +291,7 @@ pub enum RafxColorType {
 bitflags::bitflags! {
     /// The current state of a resource. When an operation is performed that references a resource,
     /// it must be in the correct state. Resources are moved between state using barriers.
+    #[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
     pub struct RafxResourceState: u32 {
         const UNDEFINED = 0;
         const VERTEX_AND_CONSTANT_BUFFER = 0x1;
@@ -129,6 +329,7 @@ pub struct RafxExtents2D {
 
 /// A 3d size for windows, textures, etc.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxExtents3D {
     pub width: u32,
     pub height: u32,
@@ -200,6 +401,9 @@ bitflags::bitflags! {
         const RENDER_TARGET_COLOR = 1<<23;
         /// A depth/stencil attachment in a renderpass
         const RENDER_TARGET_DEPTH_STENCIL = 1<<24;
+        /// Allows creating `RafxTextureView`s with a different but compatible format. Similar to
+        /// vulkan's MUTABLE_FORMAT image create flag and metal's PixelFormatView texture usage
+        const TEXTURE_MUTABLE_FORMAT = 1<<25;
     }
 }
 
@@ -239,6 +443,7 @@ impl Default for RafxColorFlags {
 
 /// Indicates how the memory will be accessed and affects where in memory it needs to be allocated.
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxMemoryUsage {
     Unknown,
 
@@ -255,6 +460,87 @@ pub enum RafxMemoryUsage {
     GpuToCpu,
 }
 
+/// Live buffer and texture allocations made through rafx for a single `RafxMemoryUsage`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RafxMemoryUsageStatistics {
+    pub buffer_count: u64,
+    pub buffer_bytes: u64,
+    pub texture_count: u64,
+    pub texture_bytes: u64,
+}
+
+impl RafxMemoryUsageStatistics {
+    pub fn allocation_count(&self) -> u64 {
+        self.buffer_count + self.texture_count
+    }
+
+    pub fn allocated_bytes(&self) -> u64 {
+        self.buffer_bytes + self.texture_bytes
+    }
+}
+
+/// Budget and usage of a single memory heap. All values are for the current process.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RafxMemoryHeapStatistics {
+    /// True if the heap is local to the GPU. (On unified memory systems, all heaps may be)
+    pub device_local: bool,
+    /// Total size of the heap
+    pub size_bytes: u64,
+    /// Estimate of how much memory can be allocated from the heap before performance degrades or
+    /// allocations fail. This changes at runtime depending on what other processes are doing.
+    pub budget_bytes: u64,
+    /// Estimate of how much memory is currently allocated from the heap, including allocations
+    /// not made through rafx (like swapchain images)
+    pub usage_bytes: u64,
+}
+
+/// Returned by `RafxDeviceContext::memory_statistics`
+#[derive(Clone, Debug, Default)]
+pub struct RafxMemoryStatistics {
+    /// Budget/usage per memory heap. If the platform can't report a budget (vulkan without
+    /// VK_EXT_memory_budget), budget is the heap size and usage is 0.
+    pub heaps: Vec<RafxMemoryHeapStatistics>,
+    pub unknown: RafxMemoryUsageStatistics,
+    pub gpu_only: RafxMemoryUsageStatistics,
+    pub cpu_only: RafxMemoryUsageStatistics,
+    pub cpu_to_gpu: RafxMemoryUsageStatistics,
+    pub gpu_to_cpu: RafxMemoryUsageStatistics,
+}
+
+impl RafxMemoryStatistics {
+    /// Returns the allocations made with the given memory usage
+    pub fn usage_statistics(
+        &self,
+        memory_usage: RafxMemoryUsage,
+    ) -> &RafxMemoryUsageStatistics {
+        match memory_usage {
+            RafxMemoryUsage::Unknown => &self.unknown,
+            RafxMemoryUsage::GpuOnly => &self.gpu_only,
+            RafxMemoryUsage::CpuOnly => &self.cpu_only,
+            RafxMemoryUsage::CpuToGpu => &self.cpu_to_gpu,
+            RafxMemoryUsage::GpuToCpu => &self.gpu_to_cpu,
+        }
+    }
+
+    /// Returns the sum of allocations across all memory usages
+    pub fn total_usage_statistics(&self) -> RafxMemoryUsageStatistics {
+        let mut total = RafxMemoryUsageStatistics::default();
+        for usage in &[
+            self.unknown,
+            self.gpu_only,
+            self.cpu_only,
+            self.cpu_to_gpu,
+            self.gpu_to_cpu,
+        ] {
+            total.buffer_count += usage.buffer_count;
+            total.buffer_bytes += usage.buffer_bytes;
+            total.texture_count += usage.texture_count;
+            total.texture_bytes += usage.texture_bytes;
+        }
+        total
+    }
+}
+
 /// Indicates the result of presenting a swapchain image
 #[derive(Clone, Debug)]
 pub enum RafxPresentSuccessResult {
@@ -340,6 +626,7 @@ pub enum RafxPipelineType {
 
 /// Affects how quickly vertex attributes are consumed from buffers, similar to VkVertexInputRate
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxVertexAttributeRate {
     Vertex,
     Instance,
@@ -354,6 +641,7 @@ impl Default for RafxVertexAttributeRate {
 /// Determines if the contents of an image attachment in a renderpass begins with its previous
 /// contents, a clear value, or undefined data. Similar to VkAttachmentLoadOp
 #[derive(Copy, Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxLoadOp {
     DontCare,
     Load,
@@ -369,6 +657,7 @@ impl Default for RafxLoadOp {
 /// Determines if the contents of an image attachment in a rander pass will store the resulting
 /// state for use after the render pass
 #[derive(Copy, Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxStoreOp {
     /// Do not store the image, leaving the contents of it undefined
     DontCare,
@@ -396,7 +685,8 @@ pub enum RafxPrimitiveTopology {
 }
 
 /// The size of index buffer elements
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxIndexType {
     Uint32,
     Uint16,
@@ -587,6 +877,7 @@ impl Default for RafxMipMapMode {
 
 /// A clear value for color attachments
 #[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxColorClearValue(pub [f32; 4]);
 
 impl Hash for RafxColorClearValue {
@@ -603,6 +894,7 @@ impl Hash for RafxColorClearValue {
 /// A clear values for depth/stencil attachments. One or both values may be used depending on the
 /// format of the attached image
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxDepthStencilClearValue {
     pub depth: f32,
     pub stencil: u32,
@@ -627,7 +919,31 @@ impl Hash for RafxDepthStencilClearValue {
     }
 }
 
+/// The value written by `cmd_clear_texture`. Use `Color` for color formats and `DepthStencil` for
+/// depth and/or stencil formats.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub enum RafxTextureClearValue {
+    Color(RafxColorClearValue),
+    DepthStencil(RafxDepthStencilClearValue),
+}
+
+/// Parameters for clearing a texture outside of a render pass with `cmd_clear_texture`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxCmdClearTextureParams {
+    /// The state the texture is in when the clear executes. Must be COPY_DST or UNORDERED_ACCESS
+    pub texture_state: RafxResourceState,
+    pub clear_value: RafxTextureClearValue,
+    /// If set, only the specified mip level is cleared
+    pub mip_slice: Option<u8>,
+    /// If set, only the specified array element is cleared
+    pub array_slice: Option<u16>,
+}
+
 /// Determines if a barrier is transferring a resource from one queue to another.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxBarrierQueueTransition {
     /// No queue transition will take place
     None,
@@ -687,6 +1003,14 @@ impl<'a> RafxTextureBarrier<'a> {
     }
 }
 
+/// The amount of memory a buffer or texture needs when placed in a `RafxHeap`. The offset it is
+/// placed at must be a multiple of the alignment.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RafxMemoryRequirements {
+    pub size: u64,
+    pub alignment: u64,
+}
+
 /// Represents an image owned by the swapchain
 #[derive(Clone)]
 pub struct RafxSwapchainImage {
@@ -695,13 +1019,16 @@ pub struct RafxSwapchainImage {
 }
 
 /// A color render target bound during a renderpass
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct RafxColorRenderTargetBinding<'a> {
     pub texture: &'a RafxTexture,
     pub load_op: RafxLoadOp,
     pub store_op: RafxStoreOp,
     pub mip_slice: Option<u8>,
     pub array_slice: Option<u16>,
+    /// Render to this view of `texture` instead. The view must cover a single mip level, and
+    /// mip_slice/array_slice must be None.
+    pub texture_view: Option<&'a RafxTextureView>,
     pub clear_value: RafxColorClearValue,
     pub resolve_target: Option<&'a RafxTexture>,
     pub resolve_store_op: RafxStoreOp,
@@ -710,7 +1037,7 @@ pub struct RafxColorRenderTargetBinding<'a> {
 }
 
 /// A depth/stencil render target to be bound during a renderpass
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct RafxDepthStencilRenderTargetBinding<'a> {
     pub texture: &'a RafxTexture,
     pub depth_load_op: RafxLoadOp,
@@ -719,6 +1046,9 @@ pub struct RafxDepthStencilRenderTargetBinding<'a> {
     pub stencil_store_op: RafxStoreOp,
     pub mip_slice: Option<u8>,
     pub array_slice: Option<u16>,
+    /// Render to this view of `texture` instead. The view must cover a single mip level, and
+    /// mip_slice/array_slice must be None.
+    pub texture_view: Option<&'a RafxTextureView>,
     pub clear_value: RafxDepthStencilClearValue,
 }
 
@@ -735,15 +1065,149 @@ pub struct RafxIndexBufferBinding<'a> {
     pub index_type: RafxIndexType,
 }
 
+/// A timeline semaphore and the value to wait for or signal in
+/// `RafxQueue::submit_with_timeline_semaphores`
+#[derive(Copy, Clone)]
+pub struct RafxTimelineSemaphoreValue<'a> {
+    pub semaphore: &'a RafxTimelineSemaphore,
+    pub value: u64,
+}
+
 /// Parameters for copying a buffer to a texture
-#[derive(Default)]
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxCmdCopyBufferToTextureParams {
     pub buffer_offset: u64,
     pub array_layer: u16,
     pub mip_level: u8,
 }
 
-/// Parameters for blitting one image to another (vulkan backend only)
+/// Parameters for copying a texture to a buffer. The texture is written to the buffer as described
+/// by `RafxTextureBufferLayout`.
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxCmdCopyTextureToBufferParams {
+    pub buffer_offset: u64,
+    pub array_layer: u16,
+    pub mip_level: u8,
+}
+
+/// Describes how a single mip level/array layer of a texture is laid out in a buffer by
+/// `cmd_copy_texture_to_buffer`. Rows are padded to `upload_buffer_texture_row_alignment` and
+/// images (depth slices) to `upload_buffer_texture_alignment`, the same layout that
+/// `cmd_copy_buffer_to_texture` reads.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RafxTextureBufferLayout {
+    /// Size of the mip level in pixels
+    pub extents: RafxExtents3D,
+    /// Number of bytes of actual texel data in a row (excluding padding)
+    pub bytes_per_row_unpadded: u32,
+    /// Offset between the start of consecutive rows. For compressed formats, a row is a row of blocks
+    pub bytes_per_row: u32,
+    pub rows_per_image: u32,
+    /// Offset between the start of consecutive depth slices
+    pub bytes_per_image: u32,
+    /// Total number of bytes required in the buffer
+    pub size: u64,
+}
+
+impl RafxTextureBufferLayout {
+    pub fn new(
+        device_info: &RafxDeviceInfo,
+        format: RafxFormat,
+        texture_extents: RafxExtents3D,
+        mip_level: u8,
+    ) -> Self {
+        let extents = RafxExtents3D {
+            width: 1.max(texture_extents.width >> mip_level),
+            height: 1.max(texture_extents.height >> mip_level),
+            depth: 1.max(texture_extents.depth >> mip_level),
+        };
+
+        let block_width = format.block_width_in_pixels();
+        let block_height = format.block_height_in_pixels();
+        let blocks_wide =
+            rafx_base::memory::round_size_up_to_alignment_u32(extents.width, block_width)
+                / block_width;
+        let rows_per_image =
+            rafx_base::memory::round_size_up_to_alignment_u32(extents.height, block_height)
+                / block_height;
+
+        let bytes_per_row_unpadded = blocks_wide * format.block_or_pixel_size_in_bytes();
+        let bytes_per_row = rafx_base::memory::round_size_up_to_alignment_u32(
+            bytes_per_row_unpadded,
+            device_info.upload_buffer_texture_row_alignment,
+        );
+        let bytes_per_image = rafx_base::memory::round_size_up_to_alignment_u32(
+            bytes_per_row * rows_per_image,
+            device_info.upload_buffer_texture_alignment,
+        );
+
+        RafxTextureBufferLayout {
+            extents,
+            bytes_per_row_unpadded,
+            bytes_per_row,
+            rows_per_image,
+            bytes_per_image,
+            size: bytes_per_image as u64 * extents.depth as u64,
+        }
+    }
+}
+
+/// Arguments for a single draw read from an indirect buffer by `cmd_draw_indirect`. The layout
+/// matches `VkDrawIndirectCommand` and `MTLDrawPrimitivesIndirectArguments`, so an array of these
+/// can be copied directly into an indirect buffer.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct RafxDrawIndirectCommand {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+/// Arguments for a single indexed draw read from an indirect buffer by
+/// `cmd_draw_indexed_indirect`. The layout matches `VkDrawIndexedIndirectCommand` and
+/// `MTLDrawIndexedPrimitivesIndirectArguments`
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct RafxDrawIndexedIndirectCommand {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32,
+}
+
+/// Arguments for a dispatch read from an indirect buffer by `cmd_dispatch_indirect`. The layout
+/// matches `VkDispatchIndirectCommand` and `MTLDispatchThreadgroupsIndirectArguments`
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct RafxDispatchIndirectCommand {
+    pub group_count_x: u32,
+    pub group_count_y: u32,
+    pub group_count_z: u32,
+}
+
+/// Parameters for copying a region of one texture to another with `cmd_copy_texture_to_texture`.
+/// Offsets and extents are in pixels. For block-compressed formats they must be multiples of the
+/// block size.
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxCmdCopyTextureToTextureParams {
+    pub src_offset: RafxExtents3D,
+    pub dst_offset: RafxExtents3D,
+    pub extents: RafxExtents3D,
+    pub src_mip_level: u8,
+    pub dst_mip_level: u8,
+    /// If set, copy a single src/dst array slice pair. Otherwise all array slices are copied.
+    pub array_slices: Option<[u16; 2]>,
+}
+
+/// Parameters for blitting one image to another with `cmd_blit`. Extents are given as
+/// [min, max) corners and may be flipped by swapping the corners.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxCmdBlitParams {
     pub src_state: RafxResourceState,
     pub dst_state: RafxResourceState,
@@ -758,8 +1222,15 @@ pub struct RafxCmdBlitParams {
 /// every frame, query the descriptor index during startup and use it instead. This is a more
 /// efficient way to address descriptors.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxDescriptorIndex(pub(crate) u32);
 
+/// A rafx-specific index that refers to a particular push constant. Query it by name during startup
+/// and pass it to `cmd_bind_push_constant`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxPushConstantIndex(pub(crate) u32);
+
 /// Selects a particular descriptor in a descriptor set
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum RafxDescriptorKey<'a> {
@@ -777,6 +1248,7 @@ impl<'a> Default for RafxDescriptorKey<'a> {
 
 /// Used when binding buffers to descriptor sets
 #[derive(Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct RafxOffsetSize {
     pub byte_offset: u64,
     pub size: u64,
@@ -786,6 +1258,8 @@ pub struct RafxOffsetSize {
 #[derive(Default, Debug)]
 pub struct RafxDescriptorElements<'a> {
     pub textures: Option<&'a [&'a RafxTexture]>,
+    /// Bound as-is, texture_bind_type is ignored
+    pub texture_views: Option<&'a [&'a RafxTextureView]>,
     pub samplers: Option<&'a [&'a RafxSampler]>,
     pub buffers: Option<&'a [&'a RafxBuffer]>,
     pub buffer_offset_sizes: Option<&'a [RafxOffsetSize]>,
@@ -793,6 +1267,7 @@ pub struct RafxDescriptorElements<'a> {
 
 /// Used when binding a texture to select between different ways to bind the texture
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum RafxTextureBindType {
     // Color or depth only
     Srv,
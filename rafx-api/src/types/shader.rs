@@ -25,6 +25,15 @@ pub enum RafxShaderPackageVulkan {
     SpvBytes(Vec<u8>),
 }
 
+/// GL ES-specific shader package. Can be used to create a RafxShaderModuleDef, which in turn is
+/// used to initialize a shader module GPU object
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub enum RafxShaderPackageGles3 {
+    /// GLSL ES 3.00 source code, as produced by spirv_cross. Will be compiled at runtime.
+    Src(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 #[doc(hidden)]
@@ -32,6 +41,67 @@ pub enum RafxShaderPackageEmpty {
     Empty,
 }
 
+/// The value of a specialization constant. In GLSL these are declared with
+/// `layout(constant_id = N) const`, and they become function constants on metal.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub enum RafxSpecializationConstantValue {
+    Bool(bool),
+    Int(i32),
+    UInt(u32),
+    Float(f32),
+}
+
+impl RafxSpecializationConstantValue {
+    /// The 32-bit representation of the value, as used by vulkan (bools are VkBool32)
+    pub fn to_bits(self) -> u32 {
+        match self {
+            RafxSpecializationConstantValue::Bool(value) => value as u32,
+            RafxSpecializationConstantValue::Int(value) => value as u32,
+            RafxSpecializationConstantValue::UInt(value) => value,
+            RafxSpecializationConstantValue::Float(value) => value.to_bits(),
+        }
+    }
+
+    /// Returns true if both values are the same variant, regardless of the value
+    pub fn is_same_type(
+        self,
+        other: RafxSpecializationConstantValue,
+    ) -> bool {
+        std::mem::discriminant(&self) == std::mem::discriminant(&other)
+    }
+}
+
+// Floats are compared and hashed by their bits so that values can be used in hashed keys
+impl PartialEq for RafxSpecializationConstantValue {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.is_same_type(*other) && self.to_bits() == other.to_bits()
+    }
+}
+
+impl Eq for RafxSpecializationConstantValue {}
+
+impl Hash for RafxSpecializationConstantValue {
+    fn hash<H: std::hash::Hasher>(
+        &self,
+        state: &mut H,
+    ) {
+        std::mem::discriminant(self).hash(state);
+        self.to_bits().hash(state);
+    }
+}
+
+/// Assigns a value to the specialization constant with the given constant_id
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct RafxSpecializationConstant {
+    pub constant_id: u32,
+    pub value: RafxSpecializationConstantValue,
+}
+
 /// Owns data necessary to create a shader module in (optionally) multiple APIs.
 ///
 /// This struct can be serialized/deserialized and is intended to allow asset pipeline to store
@@ -42,6 +112,13 @@ pub enum RafxShaderPackageEmpty {
 pub struct RafxShaderPackage {
     pub metal: Option<RafxShaderPackageMetal>,
     pub vk: Option<RafxShaderPackageVulkan>,
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub gles3: Option<RafxShaderPackageGles3>,
+    /// Values for specialization constants, applied to every stage created from this package that
+    /// declares the constant. Constants that are not listed keep the default value declared in the
+    /// shader
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub specialization_constants: Vec<RafxSpecializationConstant>,
 }
 
 impl RafxShaderPackage {
@@ -80,13 +157,26 @@ impl RafxShaderPackage {
     /// does not contain data necessary for vulkan
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     #[doc(hidden)]
     pub fn empty_module_def(&self) -> Option<RafxShaderModuleDefEmpty> {
         Some(RafxShaderModuleDefEmpty::Empty(Default::default()))
     }
 
+    /// Create a shader module def for use with a GL ES RafxDevice. Returns none if the package
+    /// does not contain data necessary for GL ES
+    #[cfg(feature = "rafx-gles3")]
+    pub fn gles3_module_def(&self) -> Option<RafxShaderModuleDefGles3> {
+        self.gles3.as_ref().map(|gles3| match gles3 {
+            RafxShaderPackageGles3::Src(src) => RafxShaderModuleDefGles3::GlSrc(src),
+        })
+    }
+
     pub fn module_def(&self) -> RafxShaderModuleDef {
         RafxShaderModuleDef {
             #[cfg(feature = "rafx-metal")]
@@ -95,10 +185,16 @@ impl RafxShaderPackage {
             vk: self.vulkan_module_def(),
             #[cfg(any(
                 feature = "rafx-empty",
-                not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+                not(any(
+                    feature = "rafx-metal",
+                    feature = "rafx-vulkan",
+                    feature = "rafx-gles3"
+                ))
             ))]
             #[doc(hidden)]
             empty: self.empty_module_def(),
+            #[cfg(feature = "rafx-gles3")]
+            gles3: self.gles3_module_def(),
         }
     }
 }
@@ -129,7 +225,11 @@ pub enum RafxShaderModuleDefVulkan<'a> {
 
 #[cfg(any(
     feature = "rafx-empty",
-    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
 ))]
 #[derive(Copy, Clone, Hash)]
 #[doc(hidden)]
@@ -137,6 +237,16 @@ pub enum RafxShaderModuleDefEmpty<'a> {
     Empty(std::marker::PhantomData<&'a u32>),
 }
 
+/// Used to create a RafxShaderModule
+///
+/// This enum may be populated manually or created from a RafxShaderPackage.
+#[derive(Copy, Clone, Hash)]
+#[cfg(feature = "rafx-gles3")]
+pub enum RafxShaderModuleDefGles3<'a> {
+    /// GLSL ES 3.00 source code. Will be compiled at runtime.
+    GlSrc(&'a str),
+}
+
 /// Used to create a RafxShaderModule
 ///
 /// This enum may be populated manually or created from a RafxShaderPackage.
@@ -148,8 +258,14 @@ pub struct RafxShaderModuleDef<'a> {
     pub vk: Option<RafxShaderModuleDefVulkan<'a>>,
     #[cfg(any(
         feature = "rafx-empty",
-        not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
     ))]
     #[doc(hidden)]
     pub empty: Option<RafxShaderModuleDefEmpty<'a>>,
+    #[cfg(feature = "rafx-gles3")]
+    pub gles3: Option<RafxShaderModuleDefGles3<'a>>,
 }
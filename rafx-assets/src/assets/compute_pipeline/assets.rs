@@ -3,7 +3,7 @@ use type_uuid::*;
 
 use crate::{AssetManager, DefaultAssetTypeHandler, DefaultAssetTypeLoadHandler, ShaderAsset};
 use distill::loader::handle::Handle;
-use rafx_api::RafxResult;
+use rafx_api::{RafxError, RafxResult};
 pub use rafx_framework::DescriptorSetLayoutResource;
 pub use rafx_framework::GraphicsPipelineResource;
 use rafx_framework::{ComputePipelineResource, DescriptorSetLayout, ResourceArc};
@@ -52,17 +52,19 @@ impl DefaultAssetTypeLoadHandler<ComputePipelineAssetData, ComputePipelineAsset>
                 asset_data.entry_name
             );
             log::error!("{}", error_message);
-            error_message
+            RafxError::reflection_mismatch(Some(&asset_data.entry_name), error_message)
         })?;
 
         let shader = asset_manager
             .resources()
             .get_or_create_shader(&[shader_module.shader_module.clone()], &[&reflection_data])?;
 
-        let root_signature =
-            asset_manager
-                .resources()
-                .get_or_create_root_signature(&[shader.clone()], &[], &[])?;
+        let root_signature = asset_manager.resources().get_or_create_root_signature(
+            &[shader.clone()],
+            &[],
+            &[],
+            &[],
+        )?;
 
         //
         // Create the push constant ranges
@@ -114,6 +114,9 @@ pub struct FixedFunctionStateData {
     front_face: Option<RafxFrontFace>,
     #[serde(default)]
     fill_mode: Option<RafxFillMode>,
+    // Set the depth bias with cmd_set_depth_bias instead of the rasterizer state
+    #[serde(default)]
+    dynamic_depth_bias: bool,
 }
 
 impl FixedFunctionStateData {
@@ -164,6 +167,10 @@ impl FixedFunctionStateData {
             rasterizer_state.front_face = front_face;
         }
 
+        if self.dynamic_depth_bias {
+            rasterizer_state.dynamic_depth_bias = true;
+        }
+
         Ok(FixedFunctionState {
             blend_state,
             depth_state,
@@ -218,7 +225,7 @@ impl MaterialPassData {
                     stage.entry_name
                 );
                 log::error!("{}", error_message);
-                error_message
+                RafxError::reflection_mismatch(Some(&stage.entry_name), error_message)
             })?;
 
             entry_points.push(reflection_data);
@@ -231,7 +238,10 @@ impl MaterialPassData {
                     reflection_data.rafx_api_reflection.shader_stage
                 );
                 log::error!("{}", error);
-                return Err(error)?;
+                return Err(RafxError::reflection_mismatch(
+                    Some(&stage.entry_name),
+                    error,
+                ));
             }
 
             log::trace!("  Reflection data:\n{:#?}", reflection_data);
@@ -250,10 +260,14 @@ impl MaterialPassData {
             &entry_points,
         )
         .map_err(|x| {
-            RafxError::StringError(format!(
-                "While loading pass '{:?}' for phase '{:?}': {:?}",
-                self.name, self.phase, x
-            ))
+            // Log the context but keep the original error so that callers can still match on it
+            log::error!(
+                "While loading pass '{:?}' for phase '{:?}': {}",
+                self.name,
+                self.phase,
+                x
+            );
+            x
         })?;
 
         //
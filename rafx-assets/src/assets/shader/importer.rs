@@ -71,6 +71,8 @@ impl Importer for ShaderImporterSpv {
         let shader_package = RafxShaderPackage {
             metal: None,
             vk: Some(RafxShaderPackageVulkan::SpvBytes(spv_bytes)),
+            gles3: None,
+            specialization_constants: vec![],
         };
 
         let shader_module_hash = ShaderModuleHash::new(&shader_package);
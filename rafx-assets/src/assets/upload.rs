@@ -177,6 +177,9 @@ impl InProgressUpload {
     // - Submits on the transfer queue and waits
     // - Submits on the graphics queue and waits
     //
+    // If timeline semaphores are supported, the graphics queue waits for the transfer queue on the
+    // GPU, so the graphics queue submit happens right away instead of after the transfer completes
+    //
     // Calls load_op.complete() or load_op.error() as appropriate
     pub fn poll_load(&mut self) -> InProgressUploadPollResult {
         loop {
@@ -190,8 +193,13 @@ impl InProgressUpload {
                         }
                         RafxTransferUploadState::SentToTransferQueue => {
                             //log::trace!("RafxTransferUploadState::SentToTransferQueue");
-                            self.inner = Some(inner);
-                            break InProgressUploadPollResult::Pending;
+                            if inner.upload.supports_timeline_semaphores() {
+                                inner.upload.submit_dst().unwrap();
+                                self.inner = Some(inner);
+                            } else {
+                                self.inner = Some(inner);
+                                break InProgressUploadPollResult::Pending;
+                            }
                         }
                         RafxTransferUploadState::PendingSubmitDstQueue => {
                             //log::trace!("RafxTransferUploadState::PendingSubmitDstQueue");
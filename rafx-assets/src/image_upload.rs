@@ -273,3 +273,46 @@ pub fn load_image_blocking(
 
     Ok(texture)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GpuImageData;
+    use rafx_api::{RafxApi, RafxFormat, RafxQueueType};
+
+    #[test]
+    fn load_image_with_generated_mips() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let transfer_queue = device_context
+            .create_queue(RafxQueueType::Transfer)
+            .unwrap();
+        let graphics_queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let data = vec![
+            0, 0, 0, 255, 100, 0, 0, 255, //
+            0, 100, 0, 255, 100, 100, 0, 255,
+        ];
+        let image_data = GpuImageData::new_simple(2, 2, RafxFormat::R8G8B8A8_UNORM, data.clone());
+
+        let texture = load_image_blocking(
+            &device_context,
+            &transfer_queue,
+            &graphics_queue,
+            1024,
+            &image_data,
+            ImageUploadParams {
+                generate_mips: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let empty_texture = texture.empty_texture().unwrap();
+        assert_eq!(texture.texture_def().mip_count, 2);
+        assert_eq!(empty_texture.read_subresource(0, 0), data);
+        assert_eq!(empty_texture.read_subresource(1, 0), vec![50, 50, 0, 255]);
+    }
+}
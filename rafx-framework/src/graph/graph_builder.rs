@@ -5,7 +5,7 @@ use crate::BufferResource;
 use fnv::{FnvHashMap, FnvHashSet};
 use rafx_api::{
     RafxColorClearValue, RafxDepthStencilClearValue, RafxResourceState, RafxResourceType,
-    RafxResult,
+    RafxResult, RafxTextureClearValue,
 };
 
 #[derive(Copy, Clone)]
@@ -555,6 +555,71 @@ impl RenderGraphBuilder {
         usage
     }
 
+    pub fn create_storage_image(
+        &mut self,
+        node: RenderGraphNodeId,
+        mut constraint: RenderGraphImageConstraint,
+        view_options: RenderGraphImageViewOptions,
+    ) -> RenderGraphImageUsageId {
+        constraint.resource_type |= RafxResourceType::TEXTURE_READ_WRITE;
+
+        let usage = self.add_image_create(node, constraint, view_options);
+
+        self.node_mut(node).storage_images.push(usage);
+        usage
+    }
+
+    /// Creates a storage image that is cleared to the given value before the node executes.
+    /// Without this the contents of a newly created image are undefined.
+    pub fn create_cleared_storage_image(
+        &mut self,
+        node: RenderGraphNodeId,
+        clear_value: RafxTextureClearValue,
+        constraint: RenderGraphImageConstraint,
+        view_options: RenderGraphImageViewOptions,
+    ) -> RenderGraphImageUsageId {
+        let usage = self.create_storage_image(node, constraint, view_options);
+
+        self.node_mut(node)
+            .image_clears
+            .push(RenderGraphImageClear {
+                image: usage,
+                clear_value,
+            });
+        usage
+    }
+
+    pub fn read_storage_image(
+        &mut self,
+        node: RenderGraphNodeId,
+        image: RenderGraphImageUsageId,
+        mut constraint: RenderGraphImageConstraint,
+        view_options: RenderGraphImageViewOptions,
+    ) -> RenderGraphImageUsageId {
+        constraint.resource_type |= RafxResourceType::TEXTURE_READ_WRITE;
+
+        let usage = self.add_image_read(node, image, constraint, view_options);
+
+        self.node_mut(node).storage_images.push(usage);
+        usage
+    }
+
+    pub fn modify_storage_image(
+        &mut self,
+        node: RenderGraphNodeId,
+        image: RenderGraphImageUsageId,
+        mut constraint: RenderGraphImageConstraint,
+        view_options: RenderGraphImageViewOptions,
+    ) -> RenderGraphImageUsageId {
+        constraint.resource_type |= RafxResourceType::TEXTURE_READ_WRITE;
+
+        let (read_image, write_image) =
+            self.add_image_modify(node, image, constraint, view_options);
+
+        self.node_mut(node).storage_images.push(read_image);
+        write_image
+    }
+
     pub fn set_output_image(
         &mut self,
         image_id: RenderGraphImageUsageId,
@@ -1,6 +1,6 @@
 use super::*;
 use crate::graph::graph_builder::RenderGraphQueue;
-use rafx_api::{RafxColorClearValue, RafxDepthStencilClearValue};
+use rafx_api::{RafxColorClearValue, RafxDepthStencilClearValue, RafxTextureClearValue};
 use std::fmt::Formatter;
 
 //
@@ -49,6 +49,12 @@ pub struct RenderGraphBufferModify {
     pub constraint: RenderGraphBufferConstraint,
 }
 
+#[derive(Debug, Clone)]
+pub struct RenderGraphImageClear {
+    pub image: RenderGraphImageUsageId,
+    pub clear_value: RafxTextureClearValue,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum RenderGraphPassAttachmentType {
     Create,
@@ -140,6 +146,10 @@ pub struct RenderGraphNode {
     pub(super) resolve_attachments: Vec<Option<RenderGraphPassResolveAttachmentInfo>>,
 
     pub(super) sampled_images: Vec<RenderGraphImageUsageId>,
+    pub(super) storage_images: Vec<RenderGraphImageUsageId>,
+
+    // Images that are cleared before the node executes
+    pub(super) image_clears: Vec<RenderGraphImageClear>,
 }
 
 impl std::fmt::Debug for RenderGraphNode {
@@ -160,6 +170,8 @@ impl std::fmt::Debug for RenderGraphNode {
             .field("depth_attachment", &self.depth_attachment)
             .field("resolve_attachments", &self.resolve_attachments)
             .field("sampled_images", &self.sampled_images)
+            .field("storage_images", &self.storage_images)
+            .field("image_clears", &self.image_clears)
             .finish()
     }
 }
@@ -185,6 +197,8 @@ impl RenderGraphNode {
             depth_attachment: Default::default(),
             resolve_attachments: Default::default(),
             sampled_images: Default::default(),
+            storage_images: Default::default(),
+            image_clears: Default::default(),
         }
     }
 
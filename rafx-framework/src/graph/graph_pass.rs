@@ -6,7 +6,7 @@ use crate::GraphicsPipelineRenderTargetMeta;
 use fnv::FnvHashMap;
 use rafx_api::{
     RafxColorClearValue, RafxDepthStencilClearValue, RafxFormat, RafxLoadOp, RafxResourceState,
-    RafxSampleCount, RafxStoreOp,
+    RafxSampleCount, RafxStoreOp, RafxTextureClearValue,
 };
 
 /// Information provided per image used in a pass to properly synchronize access to it from
@@ -214,10 +214,20 @@ pub struct RenderGraphDepthStencilRenderTarget {
     pub mip_slice: Option<u8>,
 }
 
+/// An image that is cleared after the pre-pass barriers and before the pass begins
+#[derive(Debug)]
+pub struct RenderGraphPassImageClear {
+    pub image: PhysicalImageId,
+    pub clear_value: RafxTextureClearValue,
+    pub array_slice: Option<u16>,
+    pub mip_slice: Option<u8>,
+}
+
 pub struct RenderGraphOutputRenderPass {
     pub(super) node_id: RenderGraphNodeId,
     pub(super) pre_pass_barrier: Option<PrepassBarrier>,
     pub(super) post_pass_barrier: Option<PostpassBarrier>,
+    pub(super) image_clears: Vec<RenderGraphPassImageClear>,
     pub(super) debug_name: Option<RenderGraphNodeName>,
     pub(super) attachment_images: Vec<PhysicalImageViewId>,
     pub(super) color_render_targets: Vec<RenderGraphColorRenderTarget>,
@@ -243,6 +253,7 @@ pub struct RenderGraphOutputComputePass {
     pub(super) node: RenderGraphNodeId,
     pub(super) pre_pass_barrier: Option<PrepassBarrier>,
     pub(super) post_pass_barrier: Option<PostpassBarrier>,
+    pub(super) image_clears: Vec<RenderGraphPassImageClear>,
     pub(super) debug_name: Option<RenderGraphNodeName>,
 }
 
@@ -274,6 +285,13 @@ impl RenderGraphOutputPass {
         }
     }
 
+    pub fn image_clears(&self) -> &[RenderGraphPassImageClear] {
+        match self {
+            RenderGraphOutputPass::Renderpass(pass) => &pass.image_clears,
+            RenderGraphOutputPass::Compute(pass) => &pass.image_clears,
+        }
+    }
+
     pub fn debug_name(&self) -> Option<RenderGraphNodeName> {
         match self {
             RenderGraphOutputPass::Renderpass(pass) => pass.debug_name,
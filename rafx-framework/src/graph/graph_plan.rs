@@ -1936,6 +1936,19 @@ fn build_node_barriers(
                 });
         }
 
+        for storage_image in &node.storage_images {
+            let physical_image = physical_resources
+                .image_usage_to_physical
+                .get(storage_image)
+                .unwrap();
+
+            image_node_barriers
+                .entry(*physical_image)
+                .or_insert_with(|| {
+                    RenderGraphPassImageBarriers::new(RafxResourceState::UNORDERED_ACCESS)
+                });
+        }
+
         for buffer_create in &node.buffer_creates {
             let physical_buffer = physical_resources
                 .buffer_usage_to_physical
@@ -2228,8 +2241,30 @@ fn build_pass_barriers(
 }
 
 #[profiling::function]
+fn create_output_pass_image_clears(
+    graph: &RenderGraphBuilder,
+    node_id: RenderGraphNodeId,
+    physical_resources: &AssignPhysicalResourcesResult,
+) -> Vec<RenderGraphPassImageClear> {
+    graph
+        .node(node_id)
+        .image_clears
+        .iter()
+        .map(|image_clear| {
+            let view_options = &graph.image_usages[image_clear.image.0].view_options;
+            RenderGraphPassImageClear {
+                image: physical_resources.image_usage_to_physical[&image_clear.image],
+                clear_value: image_clear.clear_value,
+                array_slice: view_options.array_slice,
+                mip_slice: view_options.mip_slice,
+            }
+        })
+        .collect()
+}
+
 fn create_output_passes(
     graph: &RenderGraphBuilder,
+    physical_resources: &AssignPhysicalResourcesResult,
     passes: Vec<RenderGraphPass>,
 ) -> Vec<RenderGraphOutputPass> {
     let mut renderpasses = Vec::with_capacity(passes.len());
@@ -2345,6 +2380,11 @@ fn create_output_passes(
                     attachment_images,
                     pre_pass_barrier: pass.pre_pass_barrier,
                     post_pass_barrier: pass.post_pass_barrier,
+                    image_clears: create_output_pass_image_clears(
+                        graph,
+                        pass.node_id,
+                        physical_resources,
+                    ),
                     debug_name,
                     color_render_targets,
                     depth_stencil_render_target,
@@ -2358,6 +2398,11 @@ fn create_output_passes(
                     node: pass.node,
                     pre_pass_barrier: pass.pre_pass_barrier,
                     post_pass_barrier: None,
+                    image_clears: create_output_pass_image_clears(
+                        graph,
+                        pass.node,
+                        physical_resources,
+                    ),
                     debug_name: graph.node(pass.node).name,
                 };
 
@@ -2804,7 +2849,7 @@ impl RenderGraphPlan {
         // passed into the resource system to create the renderpass but also includes other metadata
         // required to push them through the command queue
         //
-        let output_passes = create_output_passes(&graph, passes);
+        let output_passes = create_output_passes(&graph, &assign_physical_resources_result, passes);
 
         //
         // Separate the output images from the intermediate images (the rendergraph will be
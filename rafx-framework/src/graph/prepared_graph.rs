@@ -14,9 +14,10 @@ use crate::{BufferResource, GraphicsPipelineRenderTargetMeta, ImageResource};
 use crate::{ImageViewResource, ResourceArc, ResourceContext};
 use fnv::FnvHashMap;
 use rafx_api::{
-    RafxBarrierQueueTransition, RafxBufferBarrier, RafxColorRenderTargetBinding, RafxCommandBuffer,
-    RafxCommandBufferDef, RafxCommandPoolDef, RafxDepthStencilRenderTargetBinding,
-    RafxDeviceContext, RafxExtents2D, RafxFormat, RafxQueue, RafxResult, RafxTextureBarrier,
+    RafxBarrierQueueTransition, RafxBufferBarrier, RafxCmdClearTextureParams,
+    RafxColorRenderTargetBinding, RafxCommandBuffer, RafxCommandBufferDef, RafxCommandPoolDef,
+    RafxDepthStencilRenderTargetBinding, RafxDeviceContext, RafxExtents2D, RafxFormat, RafxQueue,
+    RafxResourceState, RafxResult, RafxTextureBarrier,
 };
 use std::hash::Hash;
 
@@ -313,6 +314,9 @@ impl PreparedRenderGraph {
 
             let node_id = pass.node();
 
+            // Group the pass's commands under the node's name in GPU debugging tools
+            command_buffer.cmd_push_group_debug_marker(pass.debug_name().unwrap_or("unnamed"))?;
+
             if let Some(pre_pass_barrier) = pass.pre_pass_barrier() {
                 log::trace!(
                     "prepass barriers for pass {} {:?}",
@@ -326,6 +330,19 @@ impl PreparedRenderGraph {
                 )?;
             }
 
+            for image_clear in pass.image_clears() {
+                let image = self.image_resources[&image_clear.image].get_raw().image;
+                command_buffer.cmd_clear_texture(
+                    &image,
+                    &RafxCmdClearTextureParams {
+                        texture_state: RafxResourceState::UNORDERED_ACCESS,
+                        clear_value: image_clear.clear_value,
+                        mip_slice: image_clear.mip_slice,
+                        array_slice: image_clear.array_slice,
+                    },
+                )?;
+            }
+
             match pass {
                 RenderGraphOutputPass::Renderpass(pass) => {
                     let color_images: Vec<_> = pass
@@ -355,6 +372,7 @@ impl PreparedRenderGraph {
                                 load_op: color_image.load_op,
                                 store_op: color_image.store_op,
                                 array_slice: color_image.array_slice,
+                                texture_view: None,
                                 mip_slice: color_image.mip_slice,
                                 resolve_target: resolve_images[color_image_index].as_ref(),
                                 resolve_store_op: color_image.resolve_store_op.into(),
@@ -376,6 +394,7 @@ impl PreparedRenderGraph {
                             depth_store_op: x.depth_store_op,
                             stencil_store_op: x.stencil_store_op,
                             array_slice: x.array_slice,
+                            texture_view: None,
                             mip_slice: x.mip_slice,
                         }
                     });
@@ -418,6 +437,8 @@ impl PreparedRenderGraph {
                     &post_pass_barrier.image_barriers,
                 )?;
             }
+
+            command_buffer.cmd_pop_group_debug_marker()?;
         }
 
         command_buffer.end()?;
@@ -425,3 +446,138 @@ impl PreparedRenderGraph {
         Ok(vec![command_buffer])
     }
 }
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod test {
+    use super::*;
+    use crate::graph::{
+        RenderGraphImageConstraint, RenderGraphImageExtents, RenderGraphImageSpecification,
+        RenderGraphQueue,
+    };
+    use crate::nodes::RenderRegistryBuilder;
+    use crate::ResourceManager;
+    use rafx_api::{
+        RafxApi, RafxColorClearValue, RafxExtents3D, RafxQueueType, RafxResourceType,
+        RafxSampleCount, RafxTextureDef,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // Runs a graph with a single renderpass node through the same path a renderer uses each frame,
+    // and checks that its cleared color attachment lands in the output image
+    #[test]
+    fn execute_graph_clears_output_image() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let graphics_queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .unwrap();
+
+        let render_registry = RenderRegistryBuilder::default().build();
+        let mut resource_manager = ResourceManager::new(&device_context, &render_registry);
+        let resource_context = resource_manager.resource_context();
+
+        let swapchain_surface_info = SwapchainSurfaceInfo {
+            extents: RafxExtents2D {
+                width: 2,
+                height: 2,
+            },
+            format: RafxFormat::R8G8B8A8_UNORM,
+        };
+
+        let output_texture = device_context
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 2,
+                    height: 2,
+                    depth: 1,
+                },
+                format: swapchain_surface_info.format,
+                resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+                ..Default::default()
+            })
+            .unwrap();
+        let output_image = resource_context.resources().insert_image(output_texture);
+        let output_image_view = resource_context
+            .resources()
+            .get_or_create_image_view(&output_image, None)
+            .unwrap();
+
+        let mut graph_builder = RenderGraphBuilder::default();
+        let node = graph_builder.add_node("clear", RenderGraphQueue::DefaultGraphics);
+        let color_attachment = graph_builder.create_color_attachment(
+            node,
+            0,
+            Some(RafxColorClearValue([1.0, 0.0, 0.0, 1.0])),
+            RenderGraphImageConstraint {
+                samples: Some(RafxSampleCount::SampleCount1),
+                format: Some(swapchain_surface_info.format),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+
+        let visit_count = Arc::new(AtomicUsize::new(0));
+        let callback_visit_count = visit_count.clone();
+        graph_builder.set_renderpass_callback(node, move |_args| {
+            callback_visit_count.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        });
+
+        graph_builder.set_output_image(
+            color_attachment,
+            output_image_view,
+            RenderGraphImageSpecification {
+                samples: RafxSampleCount::SampleCount1,
+                format: swapchain_surface_info.format,
+                resource_type: RafxResourceType::TEXTURE | RafxResourceType::RENDER_TARGET_COLOR,
+                extents: RenderGraphImageExtents::MatchSurface,
+                layer_count: 1,
+                mip_count: 1,
+            },
+            Default::default(),
+            RafxResourceState::SHADER_RESOURCE,
+        );
+
+        let prepared_graph = PreparedRenderGraph::new(
+            &device_context,
+            &resource_context,
+            graph_builder,
+            &swapchain_surface_info,
+        )
+        .unwrap();
+        let command_buffers = prepared_graph
+            .execute_graph(PreparedRenderData::empty(), &graphics_queue)
+            .unwrap();
+        assert_eq!(visit_count.load(Ordering::Relaxed), 1);
+
+        let command_buffer_refs: Vec<&RafxCommandBuffer> =
+            command_buffers.iter().map(|x| &**x).collect();
+        graphics_queue
+            .submit(&command_buffer_refs, &[], &[], None)
+            .unwrap();
+
+        let output_bytes = output_image
+            .get_raw()
+            .image
+            .empty_texture()
+            .unwrap()
+            .read_subresource(0, 0);
+        assert_eq!(output_bytes, [255, 0, 0, 255].repeat(4));
+
+        std::mem::drop(command_buffers);
+        std::mem::drop(prepared_graph);
+        std::mem::drop(output_image);
+        resource_manager.on_frame_complete().unwrap();
+    }
+}
@@ -0,0 +1,461 @@
+use crate::resources::{ImageViewResource, ResourceArc};
+use rafx_api::{
+    RafxDescriptorElements, RafxDescriptorIndex, RafxDescriptorKey, RafxDescriptorSetArray,
+    RafxDescriptorSetArrayDef, RafxDescriptorSetHandle, RafxDescriptorUpdate, RafxDeviceContext,
+    RafxError, RafxResult, RafxRootSignature,
+};
+use std::collections::VecDeque;
+use std::num::Wrapping;
+
+/// A stable index into a `BindlessTextureTable`. Shaders use it to index the bindless array.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BindlessTextureIndex(pub u32);
+
+struct RetiredSlot {
+    index: u32,
+    // Keeps the image alive until the GPU can no longer be reading it through the table
+    _image_view: ResourceArc<ImageViewResource>,
+    live_until_frame: Wrapping<u32>,
+}
+
+/// A global table of textures, backed by a single descriptor set containing a bindless array (see
+/// `RafxBindlessArray`). Each texture inserted into the table gets an index that stays valid until
+/// it is removed, so materials can refer to textures by index instead of needing their own
+/// descriptor sets.
+///
+/// Occupied slots are never rewritten. Removed indices are not reused until N + 1 calls to
+/// `on_frame_complete()` have passed, so frames that are still in flight never see a slot change
+/// under them. Call `on_frame_complete()` once per frame.
+pub struct BindlessTextureTable {
+    descriptor_set_array: RafxDescriptorSetArray,
+    descriptor_index: RafxDescriptorIndex,
+    capacity: u32,
+
+    // One per index that has ever been handed out. None if the index is free or retired
+    slots: Vec<Option<ResourceArc<ImageViewResource>>>,
+    assigned_count: u32,
+    free_indices: Vec<u32>,
+    retired_slots: VecDeque<RetiredSlot>,
+
+    // All removed indices will be reused after N frames
+    max_in_flight_frames: Wrapping<u32>,
+
+    // Incremented when on_frame_complete is called
+    frame_index: Wrapping<u32>,
+}
+
+impl BindlessTextureTable {
+    /// Create a table for the given descriptor, which must be declared as a bindless array in the
+    /// root signature. `capacity` should match the array's `max_element_count`.
+    pub fn new(
+        device_context: &RafxDeviceContext,
+        root_signature: &RafxRootSignature,
+        set_index: u32,
+        descriptor_name: &str,
+        capacity: u32,
+        max_in_flight_frames: u32,
+    ) -> RafxResult<Self> {
+        let descriptor_index = root_signature
+            .find_descriptor_by_name(descriptor_name)
+            .ok_or_else(|| {
+                RafxError::reflection_mismatch(
+                    Some(descriptor_name),
+                    "Could not find bindless texture array descriptor",
+                )
+            })?;
+
+        let descriptor_set_array =
+            device_context.create_descriptor_set_array(&RafxDescriptorSetArrayDef {
+                root_signature,
+                set_index,
+                array_length: 1,
+            })?;
+
+        Ok(BindlessTextureTable {
+            descriptor_set_array,
+            descriptor_index,
+            capacity,
+            slots: Default::default(),
+            assigned_count: 0,
+            free_indices: Default::default(),
+            retired_slots: Default::default(),
+            max_in_flight_frames: Wrapping(max_in_flight_frames),
+            frame_index: Wrapping(0),
+        })
+    }
+
+    /// The descriptor set to bind when drawing with textures from this table
+    pub fn descriptor_set_handle(&self) -> RafxDescriptorSetHandle {
+        self.descriptor_set_array.handle(0).unwrap()
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Number of indices currently assigned to a texture
+    pub fn len(&self) -> u32 {
+        self.assigned_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(
+        &self,
+        index: BindlessTextureIndex,
+    ) -> Option<&ResourceArc<ImageViewResource>> {
+        self.slots.get(index.0 as usize).and_then(|x| x.as_ref())
+    }
+
+    /// Write the texture into a free slot of the table and return its index
+    pub fn insert(
+        &mut self,
+        image_view: &ResourceArc<ImageViewResource>,
+    ) -> RafxResult<BindlessTextureIndex> {
+        let index = if let Some(index) = self.free_indices.pop() {
+            index
+        } else if (self.slots.len() as u32) < self.capacity {
+            self.slots.push(None);
+            self.slots.len() as u32 - 1
+        } else {
+            Err(format!(
+                "Bindless texture table is full ({} textures)",
+                self.capacity
+            ))?
+        };
+
+        if let Err(e) = self.write_slot(index, image_view) {
+            self.free_indices.push(index);
+            return Err(e);
+        }
+
+        self.slots[index as usize] = Some(image_view.clone());
+        self.assigned_count += 1;
+        Ok(BindlessTextureIndex(index))
+    }
+
+    /// Release the index. It will be reused after N frames have passed.
+    pub fn remove(
+        &mut self,
+        index: BindlessTextureIndex,
+    ) -> RafxResult<()> {
+        let image_view = self
+            .slots
+            .get_mut(index.0 as usize)
+            .and_then(|x| x.take())
+            .ok_or_else(|| {
+                RafxError::invalid_argument(
+                    "index",
+                    format!(
+                        "Bindless texture index {} is not assigned to a texture",
+                        index.0
+                    ),
+                )
+            })?;
+
+        self.assigned_count -= 1;
+        self.retired_slots.push_back(RetiredSlot {
+            index: index.0,
+            _image_view: image_view,
+            live_until_frame: self.frame_index + self.max_in_flight_frames + Wrapping(1),
+        });
+
+        Ok(())
+    }
+
+    /// Call when we are ready to recycle another set of indices, most likely when a frame is
+    /// presented or a new frame begins
+    pub fn on_frame_complete(&mut self) {
+        self.frame_index += Wrapping(1);
+
+        while let Some(retired_slot) = self.retired_slots.front() {
+            // If frame_index hasn't reached live_until_frame yet, then the result will be a very
+            // high value due to wrapping a negative value to u32::MAX
+            if self.frame_index - retired_slot.live_until_frame > Wrapping(std::u32::MAX / 2) {
+                break;
+            }
+
+            let retired_slot = self.retired_slots.pop_front().unwrap();
+            self.free_indices.push(retired_slot.index);
+        }
+    }
+
+    fn write_slot(
+        &mut self,
+        index: u32,
+        image_view: &ResourceArc<ImageViewResource>,
+    ) -> RafxResult<()> {
+        let image_view = image_view.get_raw();
+        let image = image_view.image.get_raw();
+        self.descriptor_set_array
+            .update_descriptor_set(&[RafxDescriptorUpdate {
+                array_index: 0,
+                descriptor_key: RafxDescriptorKey::DescriptorIndex(self.descriptor_index),
+                elements: RafxDescriptorElements {
+                    textures: Some(&[&image.image]),
+                    ..Default::default()
+                },
+                dst_element_offset: index,
+                texture_bind_type: image_view.texture_bind_type,
+            }])
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "rafx-empty",
+        not(any(
+            feature = "rafx-metal",
+            feature = "rafx-vulkan",
+            feature = "rafx-gles3"
+        ))
+    )
+))]
+mod test {
+    use super::*;
+    use crate::nodes::RenderRegistryBuilder;
+    use crate::ResourceManager;
+    use rafx_api::{
+        RafxApi, RafxBindlessArray, RafxExtents3D, RafxFormat, RafxResourceType,
+        RafxRootSignatureDef, RafxShaderModuleDef, RafxShaderModuleDefEmpty, RafxShaderResource,
+        RafxShaderStageDef, RafxShaderStageFlags, RafxShaderStageReflection, RafxTextureDef,
+    };
+
+    const MAX_IN_FLIGHT_FRAMES: u32 = 2;
+
+    // A root signature with an unbounded texture array named "textures" at set 0, binding 0
+    fn create_root_signature(
+        device_context: &RafxDeviceContext,
+        max_element_count: u32,
+    ) -> RafxRootSignature {
+        let shader_module = device_context
+            .create_shader_module(RafxShaderModuleDef {
+                empty: Some(RafxShaderModuleDefEmpty::Empty(Default::default())),
+                #[cfg(feature = "rafx-vulkan")]
+                vk: None,
+                #[cfg(feature = "rafx-metal")]
+                metal: None,
+                #[cfg(feature = "rafx-gles3")]
+                gles3: None,
+            })
+            .unwrap();
+        let shader = device_context
+            .create_shader(vec![RafxShaderStageDef {
+                shader_module,
+                reflection: RafxShaderStageReflection {
+                    shader_stage: RafxShaderStageFlags::FRAGMENT,
+                    resources: vec![RafxShaderResource {
+                        resource_type: RafxResourceType::TEXTURE,
+                        set_index: 0,
+                        binding: 0,
+                        element_count: 0,
+                        size_in_bytes: 0,
+                        used_in_shader_stages: RafxShaderStageFlags::FRAGMENT,
+                        name: Some("textures".to_string()),
+                        ..Default::default()
+                    }],
+                    compute_threads_per_group: None,
+                    entry_point_name: "main".to_string(),
+                    specialization_constants: vec![],
+                },
+                specialization_constants: vec![],
+            }])
+            .unwrap();
+
+        device_context
+            .create_root_signature(&RafxRootSignatureDef {
+                shaders: &[shader],
+                immutable_samplers: &[],
+                bindless_arrays: &[RafxBindlessArray::from_name("textures", max_element_count)],
+            })
+            .unwrap()
+    }
+
+    fn create_image_view(resource_manager: &ResourceManager) -> ResourceArc<ImageViewResource> {
+        let texture = resource_manager
+            .device_context()
+            .create_texture(&RafxTextureDef {
+                extents: RafxExtents3D {
+                    width: 1,
+                    height: 1,
+                    depth: 1,
+                },
+                format: RafxFormat::R8G8B8A8_UNORM,
+                resource_type: RafxResourceType::TEXTURE,
+                ..Default::default()
+            })
+            .unwrap();
+        let image = resource_manager.resources().insert_image(texture);
+        resource_manager
+            .resources()
+            .get_or_create_image_view(&image, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn removed_indices_are_reused_after_frames_in_flight() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let mut resource_manager =
+            ResourceManager::new(&device_context, &RenderRegistryBuilder::default().build());
+        let root_signature = create_root_signature(&device_context, 2);
+        let mut table = BindlessTextureTable::new(
+            &device_context,
+            &root_signature,
+            0,
+            "textures",
+            2,
+            MAX_IN_FLIGHT_FRAMES,
+        )
+        .unwrap();
+
+        let image_views: Vec<_> = (0..3)
+            .map(|_| create_image_view(&resource_manager))
+            .collect();
+        assert_eq!(
+            table.insert(&image_views[0]).unwrap(),
+            BindlessTextureIndex(0)
+        );
+        assert_eq!(
+            table.insert(&image_views[1]).unwrap(),
+            BindlessTextureIndex(1)
+        );
+
+        // Index 0 stays reserved while frames that may still be reading it are in flight
+        table.remove(BindlessTextureIndex(0)).unwrap();
+        assert_eq!(table.len(), 1);
+        assert!(table.get(BindlessTextureIndex(0)).is_none());
+        for _ in 0..MAX_IN_FLIGHT_FRAMES {
+            table.on_frame_complete();
+            assert!(table.insert(&image_views[2]).is_err());
+        }
+
+        table.on_frame_complete();
+        assert_eq!(
+            table.insert(&image_views[2]).unwrap(),
+            BindlessTextureIndex(0)
+        );
+        assert_eq!(table.get(BindlessTextureIndex(0)).unwrap(), &image_views[2]);
+        assert_eq!(table.len(), 2);
+
+        std::mem::drop(table);
+        std::mem::drop(image_views);
+        resource_manager.on_frame_complete().unwrap();
+    }
+
+    #[test]
+    fn insert_fails_past_capacity() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let mut resource_manager =
+            ResourceManager::new(&device_context, &RenderRegistryBuilder::default().build());
+        let root_signature = create_root_signature(&device_context, 4);
+        let mut table = BindlessTextureTable::new(
+            &device_context,
+            &root_signature,
+            0,
+            "textures",
+            2,
+            MAX_IN_FLIGHT_FRAMES,
+        )
+        .unwrap();
+
+        let image_view = create_image_view(&resource_manager);
+        table.insert(&image_view).unwrap();
+        table.insert(&image_view).unwrap();
+        assert!(table.insert(&image_view).is_err());
+        assert_eq!(table.len(), table.capacity());
+
+        std::mem::drop(table);
+        std::mem::drop(image_view);
+        resource_manager.on_frame_complete().unwrap();
+    }
+
+    #[test]
+    fn remove_fails_for_unassigned_index() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let mut resource_manager =
+            ResourceManager::new(&device_context, &RenderRegistryBuilder::default().build());
+        let root_signature = create_root_signature(&device_context, 4);
+        let mut table = BindlessTextureTable::new(
+            &device_context,
+            &root_signature,
+            0,
+            "textures",
+            4,
+            MAX_IN_FLIGHT_FRAMES,
+        )
+        .unwrap();
+
+        // Never handed out
+        assert!(matches!(
+            table.remove(BindlessTextureIndex(3)),
+            Err(RafxError::InvalidArgument {
+                argument: "index",
+                ..
+            })
+        ));
+
+        // Already removed
+        let image_view = create_image_view(&resource_manager);
+        let index = table.insert(&image_view).unwrap();
+        table.remove(index).unwrap();
+        assert!(matches!(
+            table.remove(index),
+            Err(RafxError::InvalidArgument {
+                argument: "index",
+                ..
+            })
+        ));
+        assert!(table.is_empty());
+
+        std::mem::drop(table);
+        std::mem::drop(image_view);
+        resource_manager.on_frame_complete().unwrap();
+    }
+
+    #[test]
+    fn failed_write_returns_index_to_free_list() {
+        let api = RafxApi::new_empty(&Default::default(), &Default::default()).unwrap();
+        let device_context = api.device_context();
+        let mut resource_manager =
+            ResourceManager::new(&device_context, &RenderRegistryBuilder::default().build());
+
+        // The table is larger than the bindless array, so writing index 1 fails
+        let root_signature = create_root_signature(&device_context, 1);
+        let mut table = BindlessTextureTable::new(
+            &device_context,
+            &root_signature,
+            0,
+            "textures",
+            2,
+            MAX_IN_FLIGHT_FRAMES,
+        )
+        .unwrap();
+
+        let image_view = create_image_view(&resource_manager);
+        table.insert(&image_view).unwrap();
+
+        // If the failed index weren't returned to the free list, the second insert would report
+        // the table as full instead of retrying the write
+        for _ in 0..2 {
+            assert!(matches!(
+                table.insert(&image_view),
+                Err(RafxError::InvalidArgument {
+                    argument: "dst_element_offset",
+                    ..
+                })
+            ));
+            assert_eq!(table.len(), 1);
+            assert!(table.get(BindlessTextureIndex(1)).is_none());
+        }
+
+        std::mem::drop(table);
+        std::mem::drop(image_view);
+        resource_manager.on_frame_complete().unwrap();
+    }
+}
@@ -248,3 +248,46 @@ impl ReflectedShader {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::Path;
+
+    // Cooked shaders are bincode, which has no way to skip unknown fields or default missing ones.
+    // The ones checked into the repo must be regenerated with rafx-shader-processor whenever the
+    // layout of anything in CookedShaderPackage changes.
+    #[test]
+    fn decode_checked_in_cooked_shaders() {
+        let repo_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let cooked_shader_paths = [
+            "demo/assets/shaders",
+            "rafx/examples/asset_triangle/assets",
+            "rafx/examples/framework_triangle/cooked_shaders",
+        ];
+
+        let mut cooked_shader_count = 0;
+        for cooked_shader_path in &cooked_shader_paths {
+            for entry in std::fs::read_dir(repo_path.join(cooked_shader_path)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().and_then(|x| x.to_str()) != Some("cookedshaderpackage") {
+                    continue;
+                }
+
+                let bytes = std::fs::read(&path).unwrap();
+                let cooked_shader: CookedShaderPackage = bincode::deserialize(&bytes)
+                    .unwrap_or_else(|e| panic!("Could not decode {:?}: {}", path, e));
+                assert_eq!(
+                    cooked_shader.hash,
+                    ShaderModuleHash::new(&cooked_shader.shader_package),
+                    "{:?}",
+                    path
+                );
+                assert!(!cooked_shader.entry_points.is_empty());
+                cooked_shader_count += 1;
+            }
+        }
+
+        assert!(cooked_shader_count > 0);
+    }
+}
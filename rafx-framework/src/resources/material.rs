@@ -4,7 +4,7 @@ use crate::{
     ShaderModuleResource, SlotNameLookup,
 };
 use fnv::FnvHashSet;
-use rafx_api::{RafxImmutableSamplerKey, RafxShaderStageFlags};
+use rafx_api::{RafxError, RafxImmutableSamplerKey, RafxShaderStageFlags};
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use std::sync::Arc;
@@ -66,9 +66,12 @@ impl MaterialPass {
         // Combine reflection data from all stages in the shader
         let reflected_shader = ReflectedShader::new(entry_points)?;
 
-        let vertex_inputs = reflected_shader
-            .vertex_inputs
-            .ok_or_else(|| "The material pass does not specify a vertex shader")?;
+        let vertex_inputs = reflected_shader.vertex_inputs.ok_or_else(|| {
+            RafxError::reflection_mismatch(
+                None,
+                "The material pass does not specify a vertex shader",
+            )
+        })?;
 
         //
         // Shader
@@ -118,6 +121,7 @@ impl MaterialPass {
             &[shader.clone()],
             &immutable_rafx_sampler_keys,
             &immutable_rafx_sampler_lists,
+            &[],
         )?;
 
         //
@@ -71,6 +71,10 @@ pub use pool::PooledResourceImpl;
 mod cleanup;
 pub use cleanup::ResourceDropSink;
 
+mod bindless_texture_table;
+pub use bindless_texture_table::BindlessTextureIndex;
+pub use bindless_texture_table::BindlessTextureTable;
+
 pub mod cooked_shader;
 pub use cooked_shader::*;
 
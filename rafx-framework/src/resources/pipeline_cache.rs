@@ -8,12 +8,12 @@ use rafx_api::{
     RafxVertexLayoutAttribute, RafxVertexLayoutBuffer,
 };
 use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 //TODO: Allow caching for N frames
 //TODO: Return a kind of ResourceArc for a cached pipeline. Allow dropping after N frames pass with
 // nothing request/using it
-//TODO: vulkan pipeline cache object
 
 //TODO: Remove Serialize/Deserialize
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -203,6 +203,80 @@ impl GraphicsPipelineCache {
         }
     }
 
+    // Cache data is only valid for the device/driver that produced it, so the file is keyed by
+    // the driver's pipeline cache UUID
+    fn pipeline_cache_data_path(
+        inner: &GraphicsPipelineCacheInner,
+        directory: &Path,
+    ) -> PathBuf {
+        let device_info = inner.resource_lookup_set.device_context().device_info();
+        let uuid: String = device_info
+            .pipeline_cache_uuid
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect();
+        directory.join(format!("pipeline_cache_{}.bin", uuid))
+    }
+
+    // Write the device's pipeline cache to a file in the given directory so that it can be loaded
+    // by load_pipeline_cache_data() on a later run. Nothing is written if the backend has no data.
+    pub fn save_pipeline_cache_data(
+        &self,
+        directory: &Path,
+    ) -> RafxResult<()> {
+        let inner = self.inner.lock().unwrap();
+        let data = inner
+            .resource_lookup_set
+            .device_context()
+            .pipeline_cache_data()?;
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let path = Self::pipeline_cache_data_path(&*inner, directory);
+        log::info!(
+            "Saving {} bytes of pipeline cache data to {:?}",
+            data.len(),
+            path
+        );
+        std::fs::create_dir_all(directory)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    // Seed the device's pipeline cache with a file written by save_pipeline_cache_data(). Should be
+    // called before pipelines are created. Returns false if there was no file for this device or
+    // the data could not be used.
+    pub fn load_pipeline_cache_data(
+        &self,
+        directory: &Path,
+    ) -> RafxResult<bool> {
+        let inner = self.inner.lock().unwrap();
+        let path = Self::pipeline_cache_data_path(&*inner, directory);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let data = std::fs::read(&path)?;
+        let result = inner
+            .resource_lookup_set
+            .device_context()
+            .load_pipeline_cache_data(&data);
+
+        // A stale or corrupt cache file is not fatal, pipelines will just be built from scratch
+        if let Err(e) = result {
+            log::warn!("Ignoring pipeline cache data in {:?}: {}", path, e);
+            return Ok(false);
+        }
+
+        log::info!(
+            "Loaded {} bytes of pipeline cache data from {:?}",
+            data.len(),
+            path
+        );
+        Ok(true)
+    }
+
     #[cfg(debug_assertions)]
     fn verify_data_set_layout_hash_unique(
         inner: &mut GraphicsPipelineCacheInner,
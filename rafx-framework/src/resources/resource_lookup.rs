@@ -292,6 +292,8 @@ where
 // renderpass with the swapchain surface it would be applied to)
 //
 
+// Dynamic state (like depth bias with RafxRasterizerState::dynamic_depth_bias) is not hashed, so
+// material passes that only differ by dynamic state share a material pass and graphics pipeline
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FixedFunctionState {
     pub blend_state: RafxBlendState,
@@ -316,13 +318,23 @@ impl ShaderHash {
     pub fn new(
         entry_points: &[&ReflectedEntryPoint],
         shader_module_hashes: &[ShaderModuleHash],
+        specialization_constants: &[Vec<RafxSpecializationConstant>],
     ) -> Self {
         let reflection_data: Vec<_> = entry_points
             .iter()
             .map(|x| &x.rafx_api_reflection)
             .collect();
+        let specialization_constants: Vec<_> = specialization_constants
+            .iter()
+            .map(|x| x.as_slice())
+            .collect();
         let mut hasher = FnvHasher::default();
-        RafxShaderStageDef::hash_definition(&mut hasher, &reflection_data, shader_module_hashes);
+        RafxShaderStageDef::hash_definition(
+            &mut hasher,
+            &reflection_data,
+            shader_module_hashes,
+            &specialization_constants,
+        );
         let hash = hasher.finish();
         ShaderHash(hash)
     }
@@ -346,6 +358,7 @@ impl RootSignatureHash {
         shader_hashes: &[ShaderHash],
         immutable_sampler_keys: &[RafxImmutableSamplerKey],
         immutable_sampler_hashes: &[Vec<SamplerHash>],
+        bindless_arrays: &[RafxBindlessArray],
     ) -> Self {
         let mut hasher = FnvHasher::default();
         RafxRootSignatureDef::hash_definition(
@@ -353,6 +366,7 @@ impl RootSignatureHash {
             shader_hashes,
             immutable_sampler_keys,
             immutable_sampler_hashes,
+            bindless_arrays,
         );
         let hash = hasher.finish();
         RootSignatureHash(hash)
@@ -799,17 +813,47 @@ impl ResourceLookupSet {
             .map(|x| x.get_raw().shader_module_key.hash)
             .collect();
 
-        let hash = ShaderHash::new(entry_points, &shader_module_hashes);
+        // A package's constants apply to whichever of its entry points declare them
+        let specialization_constants: Vec<_> = entry_points
+            .iter()
+            .zip(shader_modules)
+            .map(|(entry_point, module)| {
+                let declared_constants = &entry_point.rafx_api_reflection.specialization_constants;
+                module
+                    .get_raw()
+                    .shader_package
+                    .specialization_constants
+                    .iter()
+                    .filter(|x| {
+                        declared_constants
+                            .iter()
+                            .any(|declared| declared.constant_id == x.constant_id)
+                    })
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let hash = ShaderHash::new(
+            entry_points,
+            &shader_module_hashes,
+            &specialization_constants,
+        );
         let key = ShaderKey { hash };
 
         self.inner.shaders.get_or_create(&key, || {
             log::trace!("Creating shader\n");
 
             let mut shader_defs = Vec::with_capacity(entry_points.len());
-            for (entry_point, module) in entry_points.iter().zip(shader_modules) {
+            for ((entry_point, module), specialization_constants) in entry_points
+                .iter()
+                .zip(shader_modules)
+                .zip(&specialization_constants)
+            {
                 shader_defs.push(RafxShaderStageDef {
                     shader_module: module.get_raw().shader_module.clone(),
                     reflection: entry_point.rafx_api_reflection.clone(),
+                    specialization_constants: specialization_constants.clone(),
                 });
             }
 
@@ -831,6 +875,7 @@ impl ResourceLookupSet {
         shader_resources: &[ResourceArc<ShaderResource>],
         immutable_sampler_keys: &[RafxImmutableSamplerKey],
         immutable_sampler_resources: &[Vec<ResourceArc<SamplerResource>>],
+        bindless_arrays: &[RafxBindlessArray],
     ) -> RafxResult<ResourceArc<RootSignatureResource>> {
         let shader_hashes: Vec<_> = shader_resources
             .iter()
@@ -846,7 +891,12 @@ impl ResourceLookupSet {
             sampler_hashes.push(hashes);
         }
 
-        let hash = RootSignatureHash::new(&shader_hashes, immutable_sampler_keys, &sampler_hashes);
+        let hash = RootSignatureHash::new(
+            &shader_hashes,
+            immutable_sampler_keys,
+            &sampler_hashes,
+            bindless_arrays,
+        );
         let key = RootSignatureKey { hash };
 
         self.inner.root_signatures.get_or_create(&key, || {
@@ -878,6 +928,7 @@ impl ResourceLookupSet {
                     .create_root_signature(&RafxRootSignatureDef {
                         shaders: &shaders,
                         immutable_samplers: &immutable_samplers,
+                        bindless_arrays,
                     })?;
 
             let shaders = shader_resources.iter().cloned().collect();
@@ -1115,7 +1166,10 @@ impl ResourceLookupSet {
     ) -> RafxResult<ResourceArc<ImageViewResource>> {
         if image.get_raw().image_key.is_none() {
             log::error!("Tried to create an image view resource with a dynamic image");
-            return Err("Tried to create an image view resource with a dynamic image")?;
+            return Err(RafxError::invalid_argument(
+                "image",
+                "Tried to create an image view resource with a dynamic image",
+            ));
         }
 
         let image_view_key = ImageViewKey {
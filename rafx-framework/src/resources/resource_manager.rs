@@ -14,18 +14,58 @@ use crate::resources::dyn_resources::{
     DynResourceAllocatorSetManager, DynResourceAllocatorSetProvider,
 };
 use crate::resources::resource_lookup::ResourceLookupSet;
-use rafx_api::{RafxDeviceContext, RafxResult};
+use rafx_api::{RafxDeviceContext, RafxMemoryStatistics, RafxResult};
 use std::sync::Arc;
 
 //TODO: Support descriptors that can be different per-view
 //TODO: Support dynamic descriptors tied to command buffers?
 //TODO: Support data inheritance for descriptors
 
+/// GPU memory used by images and buffers allocated through the device, rolled up from
+/// `RafxDeviceContext::memory_statistics()`
+#[derive(Debug, Default)]
+pub struct ResourceMemoryMetrics {
+    pub image_count: u64,
+    pub image_bytes: u64,
+    pub buffer_count: u64,
+    pub buffer_bytes: u64,
+    // Summed across device-local heaps
+    pub device_local_budget_bytes: u64,
+    pub device_local_usage_bytes: u64,
+    pub memory_statistics: RafxMemoryStatistics,
+}
+
+impl ResourceMemoryMetrics {
+    pub fn new(memory_statistics: RafxMemoryStatistics) -> Self {
+        let total = memory_statistics.total_usage_statistics();
+
+        let mut device_local_budget_bytes = 0;
+        let mut device_local_usage_bytes = 0;
+        for heap in &memory_statistics.heaps {
+            if heap.device_local {
+                device_local_budget_bytes += heap.budget_bytes;
+                device_local_usage_bytes += heap.usage_bytes;
+            }
+        }
+
+        ResourceMemoryMetrics {
+            image_count: total.texture_count,
+            image_bytes: total.texture_bytes,
+            buffer_count: total.buffer_count,
+            buffer_bytes: total.buffer_bytes,
+            device_local_budget_bytes,
+            device_local_usage_bytes,
+            memory_statistics,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ResourceManagerMetrics {
     pub dyn_resource_metrics: dyn_resources::ResourceMetrics,
     pub resource_metrics: resource_lookup::ResourceMetrics,
     pub graphics_pipeline_cache_metrics: pipeline_cache::GraphicsPipelineCacheMetrics,
+    pub memory_metrics: ResourceMemoryMetrics,
 }
 
 struct ResourceContextInner {
@@ -162,11 +202,19 @@ impl ResourceManager {
         let dyn_resource_metrics = self.dyn_resource_allocators.metrics();
         let resource_metrics = self.resources.metrics();
         let graphics_pipeline_cache_metrics = self.graphics_pipeline_cache.metrics();
+        let memory_metrics = match self.device_context().memory_statistics() {
+            Ok(memory_statistics) => ResourceMemoryMetrics::new(memory_statistics),
+            Err(e) => {
+                log::warn!("Failed to query memory statistics: {:?}", e);
+                ResourceMemoryMetrics::default()
+            }
+        };
 
         ResourceManagerMetrics {
             dyn_resource_metrics,
             resource_metrics,
             graphics_pipeline_cache_metrics,
+            memory_metrics,
         }
     }
 
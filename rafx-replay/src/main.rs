@@ -0,0 +1,176 @@
+use log::LevelFilter;
+
+use rafx_api::capture::{RafxCapture, RafxCaptureReplay};
+use rafx_api::*;
+use std::path::PathBuf;
+use std::time::Instant;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(
+    name = "rafx-replay",
+    about = "Replays a capture recorded by rafx-api's `capture` feature"
+)]
+struct ReplayArgs {
+    /// The capture file to replay
+    #[structopt(parse(from_os_str))]
+    capture: PathBuf,
+
+    /// Replay on the empty backend even if a GPU backend is available
+    #[structopt(long)]
+    empty: bool,
+
+    /// Replay the capture this many times
+    #[structopt(long, default_value = "1")]
+    repeat: u32,
+}
+
+fn main() {
+    env_logger::Builder::from_default_env()
+        .default_format_timestamp_nanos(true)
+        .filter_level(LevelFilter::Info)
+        .init();
+
+    let args = ReplayArgs::from_args();
+    if let Err(e) = run(&args) {
+        log::error!("Replay failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &ReplayArgs) -> RafxResult<()> {
+    let capture = RafxCapture::read_from_file(&args.capture)?;
+    log::info!(
+        "Loaded {:?}: {} events, {} frames",
+        args.capture,
+        capture.events.len(),
+        capture.frame_count()
+    );
+
+    // The window (if any) must outlive the api
+    let (_window, mut api) = create_api(args.empty)?;
+
+    // Wrap all of this so that it gets dropped before we drop the API object
+    {
+        let device_context = api.device_context();
+        for iteration in 0..args.repeat {
+            replay_capture(&device_context, &capture, iteration)?;
+        }
+    }
+
+    api.destroy()
+}
+
+fn replay_capture(
+    device_context: &RafxDeviceContext,
+    capture: &RafxCapture,
+    iteration: u32,
+) -> RafxResult<()> {
+    let mut replay = RafxCaptureReplay::new(device_context);
+
+    let replay_start = Instant::now();
+    let mut frame_start = replay_start;
+    for event in &capture.events {
+        let frame_count = replay.frame_count();
+        replay.replay_event(event)?;
+
+        if replay.frame_count() != frame_count {
+            let now = Instant::now();
+            log::debug!("Replayed frame {} in {:?}", frame_count, now - frame_start);
+            frame_start = now;
+        }
+    }
+
+    log::info!(
+        "Replay {} finished: {} frames in {:?}",
+        iteration,
+        replay.frame_count(),
+        replay_start.elapsed()
+    );
+
+    Ok(())
+}
+
+#[cfg(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+))]
+fn create_empty_api() -> RafxResult<(Option<ReplayWindow>, RafxApi)> {
+    let api = RafxApi::new_empty(&Default::default(), &Default::default())?;
+    Ok((None, api))
+}
+
+#[cfg(not(any(
+    feature = "rafx-empty",
+    not(any(
+        feature = "rafx-metal",
+        feature = "rafx-vulkan",
+        feature = "rafx-gles3"
+    ))
+)))]
+fn create_empty_api() -> RafxResult<(Option<ReplayWindow>, RafxApi)> {
+    Err("rafx-replay was compiled without the rafx-empty feature".into())
+}
+
+#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
+struct ReplayWindow {
+    _context: sdl2::Sdl,
+    _video_subsystem: sdl2::VideoSubsystem,
+    _window: sdl2::video::Window,
+}
+
+#[cfg(any(feature = "rafx-metal", feature = "rafx-vulkan"))]
+fn create_api(empty: bool) -> RafxResult<(Option<ReplayWindow>, RafxApi)> {
+    if empty {
+        return create_empty_api();
+    }
+
+    // The device is created for a window, but nothing is ever presented to it
+    let context = sdl2::init()?;
+    let video_subsystem = context.video()?;
+    let window = video_subsystem
+        .window("rafx-replay", 1, 1)
+        .hidden()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let api = RafxApi::new(&window, &Default::default())?;
+    Ok((
+        Some(ReplayWindow {
+            _context: context,
+            _video_subsystem: video_subsystem,
+            _window: window,
+        }),
+        api,
+    ))
+}
+
+#[cfg(not(any(feature = "rafx-metal", feature = "rafx-vulkan")))]
+type ReplayWindow = ();
+
+// Without a windowed backend, GL ES replays into an offscreen (pbuffer) context
+#[cfg(all(
+    feature = "rafx-gles3",
+    not(any(feature = "rafx-metal", feature = "rafx-vulkan"))
+))]
+fn create_api(empty: bool) -> RafxResult<(Option<ReplayWindow>, RafxApi)> {
+    if empty {
+        return create_empty_api();
+    }
+
+    let api = RafxApi::new_gles3_headless(&Default::default(), &Default::default())?;
+    Ok((None, api))
+}
+
+#[cfg(not(any(
+    feature = "rafx-metal",
+    feature = "rafx-vulkan",
+    feature = "rafx-gles3"
+)))]
+fn create_api(_empty: bool) -> RafxResult<(Option<ReplayWindow>, RafxApi)> {
+    create_empty_api()
+}
@@ -1,4 +1,6 @@
-use rafx_api::{RafxShaderPackage, RafxShaderPackageMetal, RafxShaderPackageVulkan};
+use rafx_api::{
+    RafxShaderPackage, RafxShaderPackageGles3, RafxShaderPackageMetal, RafxShaderPackageVulkan,
+};
 use rafx_framework::CookedShaderPackage;
 use rafx_framework::{ReflectedEntryPoint, ShaderModuleHash};
 
@@ -6,6 +8,7 @@ pub(crate) fn cook_shader(
     reflected_data: &[ReflectedEntryPoint],
     spv: &[u8],
     metal_source: String,
+    gles_source: Option<String>,
 ) -> Result<Vec<u8>, String> {
     let shader_package = RafxShaderPackage {
         vk: Some(RafxShaderPackageVulkan::SpvBytes(spv.to_vec())),
@@ -15,6 +18,8 @@ pub(crate) fn cook_shader(
         // to produce a cooked shader on machines without the tools. (Also the tools don't provide an
         // API so will need to figure out how to compile the shader programmatically.)
         metal: Some(RafxShaderPackageMetal::Src(metal_source)),
+        gles3: gles_source.map(RafxShaderPackageGles3::Src),
+        specialization_constants: vec![],
     };
 
     let cooked_shader = CookedShaderPackage {
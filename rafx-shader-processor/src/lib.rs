@@ -1,3 +1,4 @@
+use rafx_api::RafxShaderStageFlags;
 use std::error::Error;
 use std::path::{Path, PathBuf};
 use structopt::StructOpt;
@@ -31,6 +32,8 @@ pub struct ShaderProcessorArgs {
     pub rs_file: Option<PathBuf>,
     #[structopt(name = "metal-generated-src-file", long, parse(from_os_str))]
     pub metal_generated_src_file: Option<PathBuf>,
+    #[structopt(name = "gles-generated-src-file", long, parse(from_os_str))]
+    pub gles_generated_src_file: Option<PathBuf>,
     #[structopt(name = "cooked-shader-file", long, parse(from_os_str))]
     pub cooked_shader_file: Option<PathBuf>,
 
@@ -45,6 +48,8 @@ pub struct ShaderProcessorArgs {
     pub rs_path: Option<PathBuf>,
     #[structopt(name = "metal-generated-src-path", long, parse(from_os_str))]
     pub metal_generated_src_path: Option<PathBuf>,
+    #[structopt(name = "gles-generated-src-path", long, parse(from_os_str))]
+    pub gles_generated_src_path: Option<PathBuf>,
     #[structopt(name = "cooked-shaders-path", long, parse(from_os_str))]
     pub cooked_shaders_path: Option<PathBuf>,
 
@@ -82,6 +87,7 @@ pub fn run(args: &ShaderProcessorArgs) -> Result<(), Box<dyn Error>> {
             args.spv_file.as_ref(),
             args.rs_file.as_ref(),
             args.metal_generated_src_file.as_ref(),
+            args.gles_generated_src_file.as_ref(),
             args.cooked_shader_file.as_ref(),
             shader_kind,
             args.optimize_shaders,
@@ -125,6 +131,12 @@ pub fn run(args: &ShaderProcessorArgs) -> Result<(), Box<dyn Error>> {
                     .as_ref()
                     .map(|x| x.join(metal_src_name));
 
+                let gles_src_name = format!("{}.gles", file_name);
+                let gles_generated_src_path = args
+                    .gles_generated_src_path
+                    .as_ref()
+                    .map(|x| x.join(gles_src_name));
+
                 let cooked_shader_name = format!("{}.cookedshaderpackage", file_name);
                 let cooked_shader_path = args
                     .cooked_shaders_path
@@ -146,6 +158,7 @@ pub fn run(args: &ShaderProcessorArgs) -> Result<(), Box<dyn Error>> {
                     spv_path.as_ref(),
                     rs_path.as_ref(),
                     metal_generated_src_path.as_ref(),
+                    gles_generated_src_path.as_ref(),
                     cooked_shader_path.as_ref(),
                     shader_kind,
                     args.optimize_shaders,
@@ -188,6 +201,7 @@ fn process_glsl_shader(
     spv_file: Option<&PathBuf>,
     rs_file: Option<&PathBuf>,
     metal_generated_src_file: Option<&PathBuf>,
+    gles_generated_src_file: Option<&PathBuf>,
     cooked_shader_file: Option<&PathBuf>,
     shader_kind: shaderc::ShaderKind,
     optimize_shaders: bool,
@@ -197,6 +211,7 @@ fn process_glsl_shader(
     log::trace!("spv: {:?}", spv_file);
     log::trace!("rs: {:?}", rs_file);
     log::trace!("metal: {:?}", metal_generated_src_file);
+    log::trace!("gles: {:?}", gles_generated_src_file);
     log::trace!("cooked: {:?}", cooked_shader_file);
     log::trace!("shader kind: {:?}", shader_kind);
 
@@ -261,13 +276,15 @@ fn process_glsl_shader(
     let mut ast = spirv_cross::spirv::Ast::<spirv_cross::glsl::Target>::parse(&spirv_cross_module)?;
     ast.set_compiler_options(&spirv_cross_glsl_options)?;
 
-    let reflected_data = if rs_file.is_some()
+    let mut reflected_data = if rs_file.is_some()
         || cooked_shader_file.is_some()
         || metal_generated_src_file.is_some()
+        || gles_generated_src_file.is_some()
     {
         let require_semantics = rs_file.is_some() || cooked_shader_file.is_some();
         Some(reflect::reflect_data(
             &ast,
+            unoptimized_compile_spirv_result.as_binary(),
             &parsed_declarations,
             require_semantics,
         )?)
@@ -362,6 +379,32 @@ fn process_glsl_shader(
         None
     };
 
+    // Also assigns the GLSL ES names of resources in the reflection data, so this must happen
+    // before cooking. Shaders that use features GLSL ES 3.00 lacks (like compute or storage
+    // buffers) are still cooked, but can't be used with the GL ES backend.
+    let gles_src = if gles_generated_src_file.is_some() || cooked_shader_file.is_some() {
+        log::trace!("{:?}: create glsl es", glsl_file);
+        let gles_src = compile_gles_src(
+            &spirv_cross_module,
+            &mut reflected_data.as_mut().unwrap().reflection,
+        );
+
+        match gles_src {
+            Ok(gles_src) => Some(gles_src),
+            Err(e) if gles_generated_src_file.is_some() => return Err(e),
+            Err(e) => {
+                log::warn!(
+                    "{:?}: could not generate GLSL ES, the shader will not be usable with the GL ES backend: {}",
+                    glsl_file,
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Don't worry about the return value
     log::trace!("{:?}: cook shader", glsl_file);
     let cooked_shader = if cooked_shader_file.is_some() {
@@ -369,6 +412,7 @@ fn process_glsl_shader(
             &reflected_data.as_ref().unwrap().reflection,
             &output_spv,
             metal_src.as_ref().unwrap().clone(),
+            gles_src.clone(),
         )?)
     } else {
         None
@@ -389,6 +433,10 @@ fn process_glsl_shader(
         std::fs::write(metal_generated_src_file, metal_src.unwrap())?;
     }
 
+    if let Some(gles_generated_src_file) = &gles_generated_src_file {
+        std::fs::write(gles_generated_src_file, gles_src.unwrap())?;
+    }
+
     if let Some(cooked_shader_file) = &cooked_shader_file {
         std::fs::write(cooked_shader_file, cooked_shader.unwrap())?;
     }
@@ -396,6 +444,36 @@ fn process_glsl_shader(
     Ok(())
 }
 
+fn compile_gles_src(
+    spirv_cross_module: &spirv_cross::spirv::Module,
+    reflection: &mut [rafx_framework::ReflectedEntryPoint],
+) -> Result<String, Box<dyn Error>> {
+    let is_compute = reflection
+        .iter()
+        .any(|x| x.rafx_api_reflection.shader_stage == RafxShaderStageFlags::COMPUTE);
+    if is_compute {
+        return Err("GLSL ES 3.00 does not support compute shaders".into());
+    }
+
+    let mut gles_ast =
+        spirv_cross::spirv::Ast::<spirv_cross::glsl::Target>::parse(spirv_cross_module)?;
+    let mut spirv_cross_gles_options = spirv_cross::glsl::CompilerOptions::default();
+    spirv_cross_gles_options.version = spirv_cross::glsl::Version::V3_00Es;
+    spirv_cross_gles_options.vulkan_semantics = false;
+    spirv_cross_gles_options.enable_420_pack_extension = false;
+    spirv_cross_gles_options.emit_push_constant_as_uniform_buffer = true;
+    // GL clip space depth is -1..1, vulkan's is 0..1
+    spirv_cross_gles_options.vertex.transform_clip_space = true;
+    // GL ES has no base instance, so gl_InstanceIndex adds the first instance from a uniform
+    spirv_cross_gles_options
+        .vertex
+        .support_nonzero_base_instance = true;
+    gles_ast.set_compiler_options(&spirv_cross_gles_options)?;
+
+    reflect::gles_assign_names(&mut gles_ast, reflection)?;
+    Ok(gles_ast.compile()?)
+}
+
 fn shader_kind_from_args(args: &ShaderProcessorArgs) -> Option<shaderc::ShaderKind> {
     let extensions = [
         ("vert", shaderc::ShaderKind::Vertex),
@@ -7,7 +7,8 @@ use fnv::FnvHashMap;
 use rafx_api::{
     RafxAddressMode, RafxCompareOp, RafxFilterType, RafxMipMapMode, RafxResourceType, RafxResult,
     RafxSamplerDef, RafxShaderResource, RafxShaderStageFlags, RafxShaderStageReflection,
-    MAX_DESCRIPTOR_SET_LAYOUTS,
+    RafxSpecializationConstantReflection, RafxSpecializationConstantValue,
+    MAX_DESCRIPTOR_SET_LAYOUTS, METAL_PUSH_CONSTANT_BUFFER_INDEX,
 };
 use spirv_cross::msl::{ResourceBinding, ResourceBindingLocation, SamplerData, SamplerLocation};
 use spirv_cross::spirv::{ExecutionModel, Type};
@@ -147,6 +148,7 @@ where
         resource_type,
         used_in_shader_stages: stage_flags,
         element_count,
+        ..Default::default()
     };
 
     Ok(ReflectedDescriptorSetLayoutBinding {
@@ -195,6 +197,7 @@ where
         size_in_bytes: 0,
         used_in_shader_stages: stage_flags,
         name: Some(slot_name.unwrap_or_else(|| name.clone())),
+        ..Default::default()
     };
 
     resource.validate()?;
@@ -386,9 +389,16 @@ where
 pub(crate) fn msl_assign_argument_buffer_ids(
     entry_points: &[ReflectedEntryPoint]
 ) -> RafxResult<BTreeMap<ResourceBindingLocation, ResourceBinding>> {
+    let mut push_constant_stages = RafxShaderStageFlags::empty();
     let mut all_resources_lookup = FnvHashMap::<(u32, u32), RafxShaderResource>::default();
     for entry_point in entry_points {
         for resource in &entry_point.rafx_api_reflection.resources {
+            // Push constants are not placed in argument buffers, they are bound separately below
+            if resource.resource_type == RafxResourceType::ROOT_CONSTANT {
+                push_constant_stages |= resource.used_in_shader_stages;
+                continue;
+            }
+
             let key = (resource.set_index, resource.binding);
             if let Some(old) = all_resources_lookup.get_mut(&key) {
                 if resource.resource_type != old.resource_type {
@@ -444,58 +454,83 @@ pub(crate) fn msl_assign_argument_buffer_ids(
             count: resource.element_count_normalized(),
         };
 
-        if resource
-            .used_in_shader_stages
-            .intersects(RafxShaderStageFlags::VERTEX)
-        {
-            let mut location = location.clone();
-            location.stage = ExecutionModel::Vertex;
-            argument_buffer_assignments.insert(location, new_binding.clone());
-        }
-
-        if resource
-            .used_in_shader_stages
-            .intersects(RafxShaderStageFlags::FRAGMENT)
-        {
-            let mut location = location.clone();
-            location.stage = ExecutionModel::Fragment;
-            argument_buffer_assignments.insert(location, new_binding.clone());
-        }
+        msl_insert_resource_binding(
+            &mut argument_buffer_assignments,
+            resource.used_in_shader_stages,
+            location,
+            new_binding,
+        );
 
-        if resource
-            .used_in_shader_stages
-            .intersects(RafxShaderStageFlags::COMPUTE)
-        {
-            let mut location = location.clone();
-            location.stage = ExecutionModel::Kernel;
-            argument_buffer_assignments.insert(location, new_binding.clone());
-        }
+        next_msl_argument_buffer_id[resource.set_index as usize] +=
+            resource.element_count_normalized();
+    }
 
-        if resource
-            .used_in_shader_stages
-            .intersects(RafxShaderStageFlags::TESSELLATION_CONTROL)
-        {
-            let mut location = location.clone();
-            location.stage = ExecutionModel::TessellationControl;
-            argument_buffer_assignments.insert(location, new_binding.clone());
-        }
+    // Push constants are set with set*Bytes at a fixed buffer index that doesn't overlap the
+    // argument buffers or vertex buffers
+    if !push_constant_stages.is_empty() {
+        let location = ResourceBindingLocation {
+            stage: spirv_cross::spirv::ExecutionModel::TessellationEvaluation,
+            // kPushConstDescSet and kPushConstBinding in spirv_cross
+            desc_set: !0,
+            binding: 0,
+        };
 
-        if resource
-            .used_in_shader_stages
-            .intersects(RafxShaderStageFlags::TESSELLATION_EVALUATION)
-        {
-            let mut location = location.clone();
-            location.stage = ExecutionModel::TessellationEvaluation;
-            argument_buffer_assignments.insert(location, new_binding.clone());
-        }
+        let new_binding = ResourceBinding {
+            buffer_id: METAL_PUSH_CONSTANT_BUFFER_INDEX,
+            texture_id: 0,
+            sampler_id: 0,
+            count: 0,
+        };
 
-        next_msl_argument_buffer_id[resource.set_index as usize] +=
-            resource.element_count_normalized();
+        msl_insert_resource_binding(
+            &mut argument_buffer_assignments,
+            push_constant_stages,
+            location,
+            new_binding,
+        );
     }
 
     Ok(argument_buffer_assignments)
 }
 
+// Inserts the binding once per shader stage, overwriting the location's stage
+fn msl_insert_resource_binding(
+    argument_buffer_assignments: &mut BTreeMap<ResourceBindingLocation, ResourceBinding>,
+    stages: RafxShaderStageFlags,
+    location: ResourceBindingLocation,
+    new_binding: ResourceBinding,
+) {
+    if stages.intersects(RafxShaderStageFlags::VERTEX) {
+        let mut location = location.clone();
+        location.stage = ExecutionModel::Vertex;
+        argument_buffer_assignments.insert(location, new_binding.clone());
+    }
+
+    if stages.intersects(RafxShaderStageFlags::FRAGMENT) {
+        let mut location = location.clone();
+        location.stage = ExecutionModel::Fragment;
+        argument_buffer_assignments.insert(location, new_binding.clone());
+    }
+
+    if stages.intersects(RafxShaderStageFlags::COMPUTE) {
+        let mut location = location.clone();
+        location.stage = ExecutionModel::Kernel;
+        argument_buffer_assignments.insert(location, new_binding.clone());
+    }
+
+    if stages.intersects(RafxShaderStageFlags::TESSELLATION_CONTROL) {
+        let mut location = location.clone();
+        location.stage = ExecutionModel::TessellationControl;
+        argument_buffer_assignments.insert(location, new_binding.clone());
+    }
+
+    if stages.intersects(RafxShaderStageFlags::TESSELLATION_EVALUATION) {
+        let mut location = location.clone();
+        location.stage = ExecutionModel::TessellationEvaluation;
+        argument_buffer_assignments.insert(location, new_binding.clone());
+    }
+}
+
 fn msl_create_sampler_data(
     sampler_def: &RafxSamplerDef
 ) -> RafxResult<spirv_cross::msl::SamplerData> {
@@ -623,14 +658,308 @@ pub(crate) fn msl_const_samplers(
     Ok(immutable_samplers)
 }
 
+// Sets the GLSL ES names of a resource in both the rafx-api reflection and the descriptor set
+// layouts of every entry point
+fn gles_set_resource_names(
+    entry_points: &mut [ReflectedEntryPoint],
+    matches: impl Fn(&RafxShaderResource) -> bool,
+    gles_name: &str,
+    gles_sampler_name: Option<&String>,
+) {
+    for entry_point in entry_points {
+        let layout_resources = entry_point
+            .descriptor_set_layouts
+            .iter_mut()
+            .flatten()
+            .flat_map(|x| x.bindings.iter_mut().map(|x| &mut x.resource));
+        for resource in entry_point
+            .rafx_api_reflection
+            .resources
+            .iter_mut()
+            .chain(layout_resources)
+        {
+            if matches(resource) {
+                resource.gles_name = Some(gles_name.to_string());
+                resource.gles_sampler_name = gles_sampler_name.cloned();
+            }
+        }
+    }
+}
+
+// GLSL ES 3.00 has no separate textures and samplers, and links uniform blocks and varyings by name
+// rather than binding or location. This combines textures with their samplers, renames varyings
+// so the vertex outputs match the fragment inputs, and records the names the GL ES backend binds
+// resources by.
+pub(crate) fn gles_assign_names(
+    ast: &mut spirv_cross::spirv::Ast<spirv_cross::glsl::Target>,
+    entry_points: &mut [ReflectedEntryPoint],
+) -> RafxResult<()> {
+    let shader_resources = ast
+        .get_shader_resources()
+        .map_err(|_x| "could not get resources from reflection data")?;
+
+    let get_set_and_binding = |ast: &spirv_cross::spirv::Ast<spirv_cross::glsl::Target>,
+                               id: u32|
+     -> RafxResult<(u32, u32)> {
+        let set = ast
+            .get_decoration(id, spirv_cross::spirv::Decoration::DescriptorSet)
+            .map_err(|_x| "could not get descriptor set index from reflection data")?;
+        let binding = ast
+            .get_decoration(id, spirv_cross::spirv::Decoration::Binding)
+            .map_err(|_x| "could not get descriptor binding index from reflection data")?;
+        Ok((set, binding))
+    };
+
+    // Uniform blocks are found by their block (type) name, not the instance name
+    for uniform_buffer in &shader_resources.uniform_buffers {
+        let (set, binding) = get_set_and_binding(ast, uniform_buffer.id)?;
+        let block_name = ast
+            .get_name(uniform_buffer.base_type_id)
+            .map_err(|_x| "could not get uniform block name from reflection data")?;
+        gles_set_resource_names(
+            entry_points,
+            |x| {
+                x.resource_type == RafxResourceType::UNIFORM_BUFFER
+                    && x.set_index == set
+                    && x.binding == binding
+            },
+            &block_name,
+            None,
+        );
+    }
+
+    // Push constants are emitted as a uniform block
+    for push_constant in &shader_resources.push_constant_buffers {
+        let block_name = ast
+            .get_name(push_constant.base_type_id)
+            .map_err(|_x| "could not get push constant block name from reflection data")?;
+        gles_set_resource_names(
+            entry_points,
+            |x| {
+                x.resource_type == RafxResourceType::ROOT_CONSTANT
+                    && x.name.as_ref() == Some(&push_constant.name)
+            },
+            &block_name,
+            None,
+        );
+    }
+
+    // Each texture becomes a sampler uniform with the texture's name. The GL ES backend binds the
+    // sampler object of the sampler it was combined with to the texture's unit, so a texture can
+    // only be used with one sampler.
+    ast.build_combined_image_samplers()
+        .map_err(|_x| "could not build combined image samplers")?;
+    let combined_image_samplers = ast
+        .get_combined_image_samplers()
+        .map_err(|_x| "could not get combined image samplers")?;
+
+    let mut texture_samplers = FnvHashMap::<u32, u32>::default();
+    for combined_image_sampler in &combined_image_samplers {
+        let image_name = ast
+            .get_name(combined_image_sampler.image_id)
+            .map_err(|_x| "could not get texture name from reflection data")?;
+        if let Some(old_sampler_id) = texture_samplers.get(&combined_image_sampler.image_id) {
+            if *old_sampler_id != combined_image_sampler.sampler_id {
+                Err(format!(
+                    "Texture {} is sampled with more than one sampler, this is not supported in GLSL ES",
+                    image_name
+                ))?;
+            }
+        }
+        texture_samplers.insert(
+            combined_image_sampler.image_id,
+            combined_image_sampler.sampler_id,
+        );
+
+        let (image_set, image_binding) = get_set_and_binding(ast, combined_image_sampler.image_id)?;
+        let (sampler_set, sampler_binding) =
+            get_set_and_binding(ast, combined_image_sampler.sampler_id)?;
+
+        let sampler_name = entry_points
+            .iter()
+            .flat_map(|x| &x.rafx_api_reflection.resources)
+            .find(|x| {
+                x.resource_type == RafxResourceType::SAMPLER
+                    && x.set_index == sampler_set
+                    && x.binding == sampler_binding
+            })
+            .and_then(|x| x.name.clone());
+
+        ast.set_name(combined_image_sampler.combined_id, &image_name)
+            .map_err(|_x| "could not rename combined image sampler")?;
+        gles_set_resource_names(
+            entry_points,
+            |x| {
+                x.resource_type == RafxResourceType::TEXTURE
+                    && x.set_index == image_set
+                    && x.binding == image_binding
+            },
+            &image_name,
+            sampler_name.as_ref(),
+        );
+    }
+
+    // Varyings are named after their location so that the vertex and fragment shaders, which are
+    // processed separately, agree on them
+    let stage_flags = entry_points
+        .first()
+        .map(|x| x.rafx_api_reflection.shader_stage)
+        .unwrap_or_else(RafxShaderStageFlags::empty);
+    let varyings = if stage_flags == RafxShaderStageFlags::VERTEX {
+        &shader_resources.stage_outputs
+    } else if stage_flags == RafxShaderStageFlags::FRAGMENT {
+        &shader_resources.stage_inputs
+    } else {
+        return Ok(());
+    };
+
+    for varying in varyings {
+        let location = ast
+            .get_decoration(varying.id, spirv_cross::spirv::Decoration::Location)
+            .map_err(|_x| "could not get varying location from reflection data")?;
+        ast.set_name(varying.id, &format!("rafx_varying_{}", location))
+            .map_err(|_x| "could not rename varying")?;
+    }
+
+    Ok(())
+}
+
 pub struct ShaderProcessorRefectionData {
     pub reflection: Vec<ReflectedEntryPoint>,
     pub msl_argument_buffer_assignments: BTreeMap<ResourceBindingLocation, ResourceBinding>,
     pub msl_const_samplers: BTreeMap<SamplerLocation, SamplerData>,
 }
 
+// Decodes a null-terminated SPIR-V literal string
+fn spirv_literal_string(words: &[u32]) -> String {
+    let mut bytes = Vec::with_capacity(words.len() * 4);
+    for word in words {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+
+    let length = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..length]).to_string()
+}
+
+// spirv_cross doesn't expose the types or default values of specialization constants, so walk the
+// SPIR-V instructions directly. Spec constants are module-level, so every entry point sees all of
+// them.
+pub(crate) fn reflect_specialization_constants(
+    spirv_words: &[u32]
+) -> RafxResult<Vec<RafxSpecializationConstantReflection>> {
+    const OP_NAME: u32 = 5;
+    const OP_TYPE_BOOL: u32 = 20;
+    const OP_TYPE_INT: u32 = 21;
+    const OP_TYPE_FLOAT: u32 = 22;
+    const OP_SPEC_CONSTANT_TRUE: u32 = 48;
+    const OP_SPEC_CONSTANT_FALSE: u32 = 49;
+    const OP_SPEC_CONSTANT: u32 = 50;
+    const OP_DECORATE: u32 = 71;
+    const DECORATION_SPEC_ID: u32 = 1;
+
+    enum ScalarType {
+        Bool,
+        Int,
+        UInt,
+        Float,
+    }
+
+    let mut names = FnvHashMap::default();
+    let mut spec_ids = FnvHashMap::default();
+    let mut scalar_types = FnvHashMap::default();
+    // (result id, default value)
+    let mut spec_constants = Vec::default();
+
+    // The first 5 words are the header
+    let mut offset = 5;
+    while offset < spirv_words.len() {
+        let opcode = spirv_words[offset] & 0xFFFF;
+        let word_count = (spirv_words[offset] >> 16) as usize;
+        if word_count == 0 || offset + word_count > spirv_words.len() {
+            Err("Malformed SPIR-V while reflecting specialization constants")?;
+        }
+
+        let operands = &spirv_words[offset + 1..offset + word_count];
+        match opcode {
+            OP_NAME if !operands.is_empty() => {
+                names.insert(operands[0], spirv_literal_string(&operands[1..]));
+            }
+            OP_DECORATE if operands.len() >= 3 && operands[1] == DECORATION_SPEC_ID => {
+                spec_ids.insert(operands[0], operands[2]);
+            }
+            OP_TYPE_BOOL if !operands.is_empty() => {
+                scalar_types.insert(operands[0], (ScalarType::Bool, 32));
+            }
+            OP_TYPE_INT if operands.len() >= 3 => {
+                let scalar_type = if operands[2] != 0 {
+                    ScalarType::Int
+                } else {
+                    ScalarType::UInt
+                };
+                scalar_types.insert(operands[0], (scalar_type, operands[1]));
+            }
+            OP_TYPE_FLOAT if operands.len() >= 2 => {
+                scalar_types.insert(operands[0], (ScalarType::Float, operands[1]));
+            }
+            OP_SPEC_CONSTANT_TRUE | OP_SPEC_CONSTANT_FALSE if operands.len() >= 2 => {
+                spec_constants.push((
+                    operands[1],
+                    RafxSpecializationConstantValue::Bool(opcode == OP_SPEC_CONSTANT_TRUE),
+                ));
+            }
+            OP_SPEC_CONSTANT if operands.len() >= 3 => {
+                let (scalar_type, width) = scalar_types.get(&operands[0]).ok_or_else(|| {
+                    format!(
+                        "Specialization constant {:?} has an unsupported type",
+                        names.get(&operands[1])
+                    )
+                })?;
+
+                if *width != 32 {
+                    Err(format!(
+                        "Specialization constant {:?} is {} bits wide, only 32-bit specialization constants are supported",
+                        names.get(&operands[1]),
+                        width
+                    ))?;
+                }
+
+                let value = match scalar_type {
+                    ScalarType::Bool => RafxSpecializationConstantValue::Bool(operands[2] != 0),
+                    ScalarType::Int => RafxSpecializationConstantValue::Int(operands[2] as i32),
+                    ScalarType::UInt => RafxSpecializationConstantValue::UInt(operands[2]),
+                    ScalarType::Float => {
+                        RafxSpecializationConstantValue::Float(f32::from_bits(operands[2]))
+                    }
+                };
+
+                spec_constants.push((operands[1], value));
+            }
+            _ => {}
+        }
+
+        offset += word_count;
+    }
+
+    let mut reflected_constants = Vec::with_capacity(spec_constants.len());
+    for (id, default_value) in spec_constants {
+        // Spec constants without a SpecId can't be overridden (i.e. they are derived from other
+        // constants)
+        if let Some(constant_id) = spec_ids.get(&id) {
+            reflected_constants.push(RafxSpecializationConstantReflection {
+                name: names.get(&id).cloned(),
+                constant_id: *constant_id,
+                default_value,
+            });
+        }
+    }
+
+    reflected_constants.sort_by_key(|x| x.constant_id);
+    Ok(reflected_constants)
+}
+
 pub(crate) fn reflect_data<TargetT>(
     ast: &spirv_cross::spirv::Ast<TargetT>,
+    spirv_words: &[u32],
     declarations: &super::parse_declarations::ParseDeclarationsResult,
     require_semantics: bool,
 ) -> RafxResult<ShaderProcessorRefectionData>
@@ -639,6 +968,8 @@ where
     spirv_cross::spirv::Ast<TargetT>: spirv_cross::spirv::Parse<TargetT>,
     spirv_cross::spirv::Ast<TargetT>: spirv_cross::spirv::Compile<TargetT>,
 {
+    let specialization_constants = reflect_specialization_constants(spirv_words)?;
+
     let mut reflected_entry_points = Vec::default();
     for entry_point in ast
         .get_entry_points()
@@ -741,6 +1072,7 @@ where
                 entry_point.work_group_size.y,
                 entry_point.work_group_size.z,
             ]),
+            specialization_constants: specialization_constants.clone(),
         };
 
         reflected_entry_points.push(ReflectedEntryPoint {
@@ -774,3 +1106,122 @@ fn map_shader_stage_flags(
         ExecutionModel::Kernel => RafxShaderStageFlags::COMPUTE,
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn compile_vertex_shader(glsl: &str) -> Vec<u32> {
+        let mut compiler = shaderc::Compiler::new().unwrap();
+        compiler
+            .compile_into_spirv(glsl, shaderc::ShaderKind::Vertex, "test.vert", "main", None)
+            .unwrap()
+            .as_binary()
+            .to_vec()
+    }
+
+    fn constant(
+        name: &str,
+        constant_id: u32,
+        default_value: RafxSpecializationConstantValue,
+    ) -> RafxSpecializationConstantReflection {
+        RafxSpecializationConstantReflection {
+            name: Some(name.to_string()),
+            constant_id,
+            default_value,
+        }
+    }
+
+    #[test]
+    fn test_reflect_specialization_constants() {
+        let spirv_words = compile_vertex_shader(
+            r#"
+            #version 450
+            layout(constant_id = 7) const float SCALE = 0.5;
+            layout(constant_id = 0) const bool ENABLE_FOG = true;
+            layout(constant_id = 1) const bool ENABLE_SHADOWS = false;
+            layout(constant_id = 3) const int OFFSET = -4;
+            layout(constant_id = 2) const uint LIGHT_COUNT = 16;
+
+            // Derived from other constants, can't be specialized directly
+            const int DOUBLE_OFFSET = OFFSET * 2;
+
+            void main() {
+                float x = float(LIGHT_COUNT) * SCALE + float(DOUBLE_OFFSET);
+                if (ENABLE_FOG || ENABLE_SHADOWS) {
+                    x += 1.0;
+                }
+                gl_Position = vec4(x);
+            }
+            "#,
+        );
+
+        let reflected_constants = reflect_specialization_constants(&spirv_words).unwrap();
+        assert_eq!(
+            reflected_constants,
+            vec![
+                constant("ENABLE_FOG", 0, RafxSpecializationConstantValue::Bool(true)),
+                constant(
+                    "ENABLE_SHADOWS",
+                    1,
+                    RafxSpecializationConstantValue::Bool(false)
+                ),
+                constant("LIGHT_COUNT", 2, RafxSpecializationConstantValue::UInt(16)),
+                constant("OFFSET", 3, RafxSpecializationConstantValue::Int(-4)),
+                constant("SCALE", 7, RafxSpecializationConstantValue::Float(0.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reflect_specialization_constants_none_declared() {
+        let spirv_words = compile_vertex_shader(
+            r#"
+            #version 450
+            void main() {
+                gl_Position = vec4(0.0);
+            }
+            "#,
+        );
+
+        assert!(reflect_specialization_constants(&spirv_words)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reflect_specialization_constants_rejects_64_bit() {
+        let spirv_words = compile_vertex_shader(
+            r#"
+            #version 450
+            layout(constant_id = 0) const double SCALE = 0.5;
+            void main() {
+                gl_Position = vec4(float(SCALE));
+            }
+            "#,
+        );
+
+        assert!(reflect_specialization_constants(&spirv_words).is_err());
+    }
+
+    #[test]
+    fn test_reflect_specialization_constants_rejects_truncated_spirv() {
+        let spirv_words = compile_vertex_shader(
+            r#"
+            #version 450
+            layout(constant_id = 0) const float SCALE = 0.5;
+            void main() {
+                gl_Position = vec4(SCALE);
+            }
+            "#,
+        );
+
+        // Cut off the OpSpecConstant instruction partway through
+        let spec_constant_offset = spirv_words
+            .iter()
+            .position(|&word| word == (4 << 16) | 50)
+            .unwrap();
+        let truncated_words = &spirv_words[..spec_constant_offset + 2];
+        assert!(reflect_specialization_constants(truncated_words).is_err());
+    }
+}
@@ -176,7 +176,9 @@ fn run() -> RafxResult<()> {
                 shader_stage: RafxShaderStageFlags::VERTEX,
                 compute_threads_per_group: None,
                 resources: vec![color_shader_resource.clone()],
+                specialization_constants: vec![],
             },
+            specialization_constants: vec![],
         };
 
         let frag_shader_stage_def = RafxShaderStageDef {
@@ -186,7 +188,9 @@ fn run() -> RafxResult<()> {
                 shader_stage: RafxShaderStageFlags::FRAGMENT,
                 compute_threads_per_group: None,
                 resources: vec![color_shader_resource],
+                specialization_constants: vec![],
             },
+            specialization_constants: vec![],
         };
 
         //
@@ -202,6 +206,7 @@ fn run() -> RafxResult<()> {
         let root_signature = device_context.create_root_signature(&RafxRootSignatureDef {
             shaders: &[shader.clone()],
             immutable_samplers: &[],
+            bindless_arrays: &[],
         })?;
 
         //
@@ -340,6 +345,7 @@ fn run() -> RafxResult<()> {
                     load_op: RafxLoadOp::Clear,
                     store_op: RafxStoreOp::Store,
                     array_slice: None,
+                    texture_view: None,
                     mip_slice: None,
                     clear_value: RafxColorClearValue([0.0, 0.0, 0.0, 0.0]),
                     resolve_target: None,